@@ -1,59 +1,55 @@
-use crate::services::alert_service::{AlertEvent, AlertService, CreateAlertRequest, ResourceAlert};
+use crate::services::alert_history_service::{local_user, AlertHistoryQuery, AlertInstance};
+use crate::services::alert_rule_service::{CreateEventRuleRequest, EventAlertRule};
+use crate::services::alert_service::{AlertEvent, CreateAlertRequest, ResourceAlert};
 use crate::state::app_state::AppState;
 use tauri::State;
 
 /// Create a new resource alert
 #[tauri::command]
 pub async fn create_alert(
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
     request: CreateAlertRequest,
 ) -> Result<ResourceAlert, String> {
-    let alert_service = AlertService::new().map_err(|e| e.to_string())?;
-    alert_service.create_alert(request).map_err(|e| e.to_string())
+    state.alerts.create_alert(request).map_err(|e| e.to_string())
 }
 
 /// List all resource alerts
 #[tauri::command]
-pub async fn list_alerts(_state: State<'_, AppState>) -> Result<Vec<ResourceAlert>, String> {
-    let alert_service = AlertService::new().map_err(|e| e.to_string())?;
-    alert_service.list_alerts().map_err(|e| e.to_string())
+pub async fn list_alerts(state: State<'_, AppState>) -> Result<Vec<ResourceAlert>, String> {
+    state.alerts.list_alerts().map_err(|e| e.to_string())
 }
 
 /// Get an alert by ID
 #[tauri::command]
-pub async fn get_alert(_state: State<'_, AppState>, id: String) -> Result<ResourceAlert, String> {
-    let alert_service = AlertService::new().map_err(|e| e.to_string())?;
-    alert_service.get_alert(&id).map_err(|e| e.to_string())
+pub async fn get_alert(state: State<'_, AppState>, id: String) -> Result<ResourceAlert, String> {
+    state.alerts.get_alert(&id).map_err(|e| e.to_string())
 }
 
 /// Update alert enabled status
 #[tauri::command]
 pub async fn update_alert_status(
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
     id: String,
     enabled: bool,
 ) -> Result<ResourceAlert, String> {
-    let alert_service = AlertService::new().map_err(|e| e.to_string())?;
-    alert_service
+    state.alerts
         .update_alert_status(&id, enabled)
         .map_err(|e| e.to_string())
 }
 
 /// Delete an alert
 #[tauri::command]
-pub async fn delete_alert(_state: State<'_, AppState>, id: String) -> Result<(), String> {
-    let alert_service = AlertService::new().map_err(|e| e.to_string())?;
-    alert_service.delete_alert(&id).map_err(|e| e.to_string())
+pub async fn delete_alert(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    state.alerts.delete_alert(&id).map_err(|e| e.to_string())
 }
 
 /// Get alerts for a specific VM
 #[tauri::command]
 pub async fn get_vm_alerts(
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
     vm_id: String,
 ) -> Result<Vec<ResourceAlert>, String> {
-    let alert_service = AlertService::new().map_err(|e| e.to_string())?;
-    alert_service
+    state.alerts
         .get_vm_alerts(&vm_id)
         .map_err(|e| e.to_string())
 }
@@ -61,12 +57,97 @@ pub async fn get_vm_alerts(
 /// Check threshold and get alert event if triggered
 #[tauri::command]
 pub async fn check_alert_threshold(
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
     alert_id: String,
     current_value: f64,
 ) -> Result<Option<AlertEvent>, String> {
-    let alert_service = AlertService::new().map_err(|e| e.to_string())?;
-    alert_service
+    state.alerts
         .check_threshold(&alert_id, current_value)
         .map_err(|e| e.to_string())
 }
+
+/// Select the notification channels an alert is delivered to
+#[tauri::command]
+pub async fn set_alert_channels(
+    state: State<'_, AppState>,
+    id: String,
+    channel_ids: Vec<String>,
+) -> Result<ResourceAlert, String> {
+    state.alerts
+        .set_alert_channels(&id, channel_ids)
+        .map_err(|e| e.to_string())
+}
+
+/// Create an event-based alert rule
+#[tauri::command]
+pub async fn create_alert_rule(
+    state: State<'_, AppState>,
+    request: CreateEventRuleRequest,
+) -> Result<EventAlertRule, String> {
+    state.alert_rules.create_rule(request).map_err(|e| e.to_string())
+}
+
+/// List all event-based alert rules
+#[tauri::command]
+pub async fn list_alert_rules(state: State<'_, AppState>) -> Result<Vec<EventAlertRule>, String> {
+    state.alert_rules.list_rules().map_err(|e| e.to_string())
+}
+
+/// Enable or disable an event-based alert rule
+#[tauri::command]
+pub async fn update_alert_rule_status(
+    state: State<'_, AppState>,
+    id: String,
+    enabled: bool,
+) -> Result<EventAlertRule, String> {
+    state.alert_rules
+        .update_rule_status(&id, enabled)
+        .map_err(|e| e.to_string())
+}
+
+/// Delete an event-based alert rule
+#[tauri::command]
+pub async fn delete_alert_rule(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    state.alert_rules.delete_rule(&id).map_err(|e| e.to_string())
+}
+
+/// List alert instances that are firing or acknowledged
+#[tauri::command]
+pub async fn list_active_alerts(state: State<'_, AppState>) -> Result<Vec<AlertInstance>, String> {
+    state.alert_history.list_open().map_err(|e| e.to_string())
+}
+
+/// Acknowledge a firing alert, silencing reminders until it resolves
+#[tauri::command]
+pub async fn acknowledge_alert(
+    state: State<'_, AppState>,
+    id: String,
+    by: Option<String>,
+) -> Result<AlertInstance, String> {
+    let by = by.filter(|b| !b.is_empty()).unwrap_or_else(local_user);
+    state.alert_history
+        .acknowledge(&id, &by)
+        .map_err(|e| e.to_string())
+}
+
+/// Manually resolve an alert instance
+#[tauri::command]
+pub async fn resolve_alert(
+    state: State<'_, AppState>,
+    id: String,
+    by: Option<String>,
+) -> Result<AlertInstance, String> {
+    let by = by.filter(|b| !b.is_empty()).unwrap_or_else(local_user);
+    state.alert_history
+        .resolve(&id, Some(&by))
+        .map_err(|e| e.to_string())
+}
+
+/// Query alert history by VM, alert, state and time range
+#[tauri::command]
+pub async fn query_alert_history(
+    state: State<'_, AppState>,
+    query: AlertHistoryQuery,
+) -> Result<Vec<AlertInstance>, String> {
+    state.alert_history.query(&query).map_err(|e| e.to_string())
+}
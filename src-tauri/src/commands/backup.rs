@@ -1,16 +1,21 @@
-use crate::services::backup_service::{BackupConfig, BackupService, CreateBackupRequest};
+use crate::models::job::JobKind;
+use crate::services::backup_service::{BackupChain, BackupConfig, BackupRun, BackupService, CreateBackupRequest, BACKUP_CONNECTION};
+use crate::services::backup_target_service::{BackupTarget, BackupTargetService, CreateBackupTargetRequest, TargetCheck, TargetUsage};
+use crate::services::backup_verify_service::{BackupVerifyService, VerifyOptions};
+use crate::services::restore_service::{RestoreBackupRequest, RestoreDiskRequest, RestoreService};
 use crate::state::app_state::AppState;
 use tauri::State;
 
 /// Create a new backup configuration
 #[tauri::command]
 pub async fn create_backup_config(
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
     request: CreateBackupRequest,
 ) -> Result<BackupConfig, String> {
     let backup_service = BackupService::new().map_err(|e| e.to_string())?;
+    let libvirt = state.libvirt(request.connection_id.as_deref()).map_err(|e| e.to_string())?;
     backup_service
-        .create_backup_config(request)
+        .create_backup_config(&libvirt, request)
         .map_err(|e| e.to_string())
 }
 
@@ -74,12 +79,223 @@ pub async fn get_vm_backup_configs(
         .map_err(|e| e.to_string())
 }
 
-/// Record a backup execution
+/// Back up a config's VM now
+///
+/// Runs as a background job and returns its ID; the job result is the recorded run.
+#[tauri::command]
+pub async fn run_backup_now(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<String, String> {
+    let backup_service = BackupService::new().map_err(|e| e.to_string())?;
+    let config = backup_service.get_backup_config(&id).map_err(|e| e.to_string())?;
+    let libvirt = state.libvirt(Some(BACKUP_CONNECTION)).map_err(|e| e.to_string())?;
+
+    BackupService::start_backup_job(&state.jobs, state.events.clone(), libvirt, config)
+        .map_err(|e| e.to_string())
+}
+
+/// List the runs of a backup configuration, newest first
+#[tauri::command]
+pub async fn list_backup_runs(
+    _state: State<'_, AppState>,
+    config_id: String,
+) -> Result<Vec<BackupRun>, String> {
+    let backup_service = BackupService::new().map_err(|e| e.to_string())?;
+    backup_service
+        .list_backup_runs(&config_id)
+        .map_err(|e| e.to_string())
+}
+
+/// List the restore point chains of a backup configuration, newest first
+#[tauri::command]
+pub async fn list_backup_chains(
+    _state: State<'_, AppState>,
+    config_id: String,
+) -> Result<Vec<BackupChain>, String> {
+    let backup_service = BackupService::new().map_err(|e| e.to_string())?;
+    backup_service
+        .list_backup_chains(&config_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Restore a VM from a backup point, as a new VM or in place
+///
+/// Runs as a background job and returns its ID; the job result is the restored VM.
+#[tauri::command]
+pub async fn restore_backup(
+    state: State<'_, AppState>,
+    request: RestoreBackupRequest,
+) -> Result<String, String> {
+    let backup_service = BackupService::new().map_err(|e| e.to_string())?;
+    let config = backup_service.get_backup_config(&request.config_id).map_err(|e| e.to_string())?;
+    let libvirt = state.libvirt(Some(BACKUP_CONNECTION)).map_err(|e| e.to_string())?;
+
+    let description = format!("Restore {}", config.name);
+    Ok(state.jobs.start(JobKind::RestoreBackup, description, config.vm_id, Some(BACKUP_CONNECTION.to_string()), move |job| {
+        RestoreService::restore_backup(&libvirt, &request, Some(job))
+    }))
+}
+
+/// Restore a single disk of a backup point into a storage pool
+///
+/// Runs as a background job and returns its ID; the job result is the restored image.
+#[tauri::command]
+pub async fn restore_backup_disk(
+    state: State<'_, AppState>,
+    request: RestoreDiskRequest,
+) -> Result<String, String> {
+    let backup_service = BackupService::new().map_err(|e| e.to_string())?;
+    let config = backup_service.get_backup_config(&request.config_id).map_err(|e| e.to_string())?;
+    let libvirt = state.libvirt(Some(BACKUP_CONNECTION)).map_err(|e| e.to_string())?;
+
+    let description = format!("Restore disk {} of {}", request.disk, config.name);
+    Ok(state.jobs.start(JobKind::RestoreBackup, description, config.vm_id, Some(BACKUP_CONNECTION.to_string()), move |job| {
+        RestoreService::restore_disk(&libvirt, &request, Some(job))
+    }))
+}
+
+/// Attach a disk of a backup point read-only to a VM, returning its target device
+#[tauri::command]
+pub async fn attach_backup_disk(
+    state: State<'_, AppState>,
+    config_id: String,
+    run_id: String,
+    disk: String,
+    vm_id: String,
+) -> Result<String, String> {
+    let libvirt = state.libvirt(Some(BACKUP_CONNECTION)).map_err(|e| e.to_string())?;
+    RestoreService::attach_backup_disk(&libvirt, &config_id, &run_id, &disk, &vm_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Verify a restore point: checksums, image checks and optionally a sandbox boot
+///
+/// Runs as a background job and returns its ID; the job result is the verification.
+/// A failed verification is reported to the alert engine.
+#[tauri::command]
+pub async fn verify_backup(
+    state: State<'_, AppState>,
+    config_id: String,
+    run_id: String,
+    options: Option<VerifyOptions>,
+) -> Result<String, String> {
+    let backup_service = BackupService::new().map_err(|e| e.to_string())?;
+    let config = backup_service.get_backup_config(&config_id).map_err(|e| e.to_string())?;
+    let libvirt = state.libvirt(Some(BACKUP_CONNECTION)).map_err(|e| e.to_string())?;
+
+    Ok(BackupVerifyService::start_verify_job(
+        &state.jobs, state.events.clone(), libvirt, config, run_id, options.unwrap_or_default(),
+    ))
+}
+
+/// Upload restore points missing from a config's backup target
+///
+/// Runs as a background job and returns its ID; the job result is the updated run list.
+#[tauri::command]
+pub async fn sync_backup_target(
+    state: State<'_, AppState>,
+    config_id: String,
+) -> Result<String, String> {
+    let backup_service = BackupService::new().map_err(|e| e.to_string())?;
+    let config = backup_service.get_backup_config(&config_id).map_err(|e| e.to_string())?;
+
+    let description = format!("Upload backups of {}", config.name);
+    Ok(state.jobs.start(JobKind::UploadBackup, description, config.vm_id, None, move |job| {
+        backup_service.sync_backup_target(&config_id, Some(job))
+    }))
+}
+
+/// Add the restore points uploaded to a backup target to this host's catalog
+///
+/// Runs as a background job and returns its ID; the job result is a `CatalogImport`.
+#[tauri::command]
+pub async fn import_backup_catalog(
+    state: State<'_, AppState>,
+    target_id: String,
+) -> Result<String, String> {
+    let target = BackupTargetService::new()
+        .and_then(|targets| targets.get_target(&target_id))
+        .map_err(|e| e.to_string())?;
+    let backup_service = BackupService::new().map_err(|e| e.to_string())?;
+
+    let description = format!("Import backup catalog from {}", target.name);
+    Ok(state.jobs.start(JobKind::ImportBackupCatalog, description, target.name, None, move |job| {
+        backup_service.import_catalog(&target_id, Some(job))
+    }))
+}
+
+/// Create a backup target
+#[tauri::command]
+pub async fn create_backup_target(
+    _state: State<'_, AppState>,
+    request: CreateBackupTargetRequest,
+) -> Result<BackupTarget, String> {
+    let target_service = BackupTargetService::new().map_err(|e| e.to_string())?;
+    target_service
+        .create_target(request)
+        .map_err(|e| e.to_string())
+}
+
+/// List backup targets
+#[tauri::command]
+pub async fn list_backup_targets(
+    _state: State<'_, AppState>,
+) -> Result<Vec<BackupTarget>, String> {
+    let target_service = BackupTargetService::new().map_err(|e| e.to_string())?;
+    target_service
+        .list_targets()
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a backup target that no backup configuration uses
 #[tauri::command]
-pub async fn record_backup(
+pub async fn delete_backup_target(
     _state: State<'_, AppState>,
     id: String,
-) -> Result<BackupConfig, String> {
+) -> Result<(), String> {
+    let backup_service = BackupService::new().map_err(|e| e.to_string())?;
+    let users: Vec<String> = backup_service
+        .list_backup_configs()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|config| config.target_id.as_deref() == Some(id.as_str()))
+        .map(|config| config.name)
+        .collect();
+    if !users.is_empty() {
+        return Err(format!("Backup target is used by: {}", users.join(", ")));
+    }
+
+    let target_service = BackupTargetService::new().map_err(|e| e.to_string())?;
+    target_service
+        .delete_target(&id)
+        .map_err(|e| e.to_string())
+}
+
+/// Check that a backup target can be written, read and cleaned up
+#[tauri::command]
+pub async fn check_backup_target(
+    _state: State<'_, AppState>,
+    id: String,
+) -> Result<TargetCheck, String> {
+    let target_service = BackupTargetService::new().map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || target_service.check_target(&id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Get the space a backup target uses for backups
+#[tauri::command]
+pub async fn get_backup_target_usage(
+    _state: State<'_, AppState>,
+    id: String,
+) -> Result<TargetUsage, String> {
+    let target_service = BackupTargetService::new().map_err(|e| e.to_string())?;
+    let target = target_service.get_target(&id).map_err(|e| e.to_string())?;
     let backup_service = BackupService::new().map_err(|e| e.to_string())?;
-    backup_service.record_backup(&id).map_err(|e| e.to_string())
+    tauri::async_runtime::spawn_blocking(move || backup_service.target_usage(&target))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
 }
@@ -1,6 +1,8 @@
 use tauri::State;
+use crate::models::event::ConnectionStateEvent;
 use crate::state::app_state::AppState;
-use crate::services::connection_service::{SavedConnection, ConnectionType};
+use crate::services::connection_service::{ConnectionService, SavedConnection, ConnectionType};
+use crate::services::secret_service::{ConnectionCredentials, CredentialsInfo};
 
 /// Get all saved connections
 #[tauri::command]
@@ -18,6 +20,22 @@ pub async fn get_active_connection(state: State<'_, AppState>) -> Result<Option<
         .map_err(|e| e.to_string())
 }
 
+/// Get every currently connected host
+#[tauri::command]
+pub async fn get_connected_connections(state: State<'_, AppState>) -> Result<Vec<SavedConnection>, String> {
+    tracing::debug!("get_connected_connections command called");
+    state.connections.get_connected_connections()
+        .map_err(|e| e.to_string())
+}
+
+/// Get the supervised state of every connection
+#[tauri::command]
+pub async fn get_connection_states(state: State<'_, AppState>) -> Result<Vec<ConnectionStateEvent>, String> {
+    tracing::debug!("get_connection_states command called");
+    state.connections.get_connection_states()
+        .map_err(|e| e.to_string())
+}
+
 /// Connect to a saved connection by ID
 #[tauri::command]
 pub async fn connect_to(state: State<'_, AppState>, connection_id: String) -> Result<(), String> {
@@ -126,6 +144,47 @@ pub async fn remove_connection(state: State<'_, AppState>, connection_id: String
         .map_err(|e| e.to_string())
 }
 
+/// Store credentials (SSH key, TLS PKI directory, SASL login) for a connection
+#[tauri::command]
+pub async fn set_connection_credentials(
+    state: State<'_, AppState>,
+    connection_id: String,
+    credentials: ConnectionCredentials,
+) -> Result<(), String> {
+    tracing::info!("set_connection_credentials command called: {}", connection_id);
+    state.connections.set_credentials(&connection_id, credentials)
+        .map_err(|e| e.to_string())
+}
+
+/// Get the stored credentials for a connection, without the SASL password
+#[tauri::command]
+pub async fn get_connection_credentials(
+    state: State<'_, AppState>,
+    connection_id: String,
+) -> Result<Option<CredentialsInfo>, String> {
+    tracing::debug!("get_connection_credentials command called: {}", connection_id);
+    let credentials = state.connections.get_credentials(&connection_id)
+        .map_err(|e| e.to_string())?;
+    Ok(credentials.as_ref().map(CredentialsInfo::from))
+}
+
+/// List saved connections whose credentials must be entered again
+#[tauri::command]
+pub async fn get_connections_needing_credentials(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    Ok(state.connections.connections_needing_credentials())
+}
+
+/// Remove the stored credentials for a connection
+#[tauri::command]
+pub async fn clear_connection_credentials(
+    state: State<'_, AppState>,
+    connection_id: String,
+) -> Result<(), String> {
+    tracing::info!("clear_connection_credentials command called: {}", connection_id);
+    state.connections.clear_credentials(&connection_id)
+        .map_err(|e| e.to_string())
+}
+
 /// Test a connection before saving
 #[tauri::command]
 pub async fn test_connection(
@@ -134,9 +193,8 @@ pub async fn test_connection(
     username: Option<String>,
     ssh_port: Option<u16>,
     tls_port: Option<u16>,
+    credentials: Option<ConnectionCredentials>,
 ) -> Result<String, String> {
-    use virt::connect::Connect;
-
     tracing::info!("test_connection command called");
 
     let conn_type = match connection_type.as_str() {
@@ -161,15 +219,15 @@ pub async fn test_connection(
         path: "system".to_string(),
     };
 
-    let uri = config.build_uri();
+    let uri = config.build_uri_with_credentials(credentials.as_ref());
     tracing::info!("Testing connection to: {}", uri);
 
     // Try to connect
-    let conn = Connect::open(Some(&uri))
+    let libvirt = ConnectionService::open_libvirt(&uri, credentials.as_ref())
         .map_err(|e| format!("Connection failed: {}", e))?;
 
     // Get hostname as confirmation
-    let hostname = conn.get_hostname()
+    let hostname = libvirt.get_hostname()
         .map_err(|e| format!("Failed to get hostname: {}", e))?;
 
     Ok(format!("Connected to {}", hostname))
@@ -11,6 +11,14 @@ use crate::services::guest_agent_service::{
 use serde::Serialize;
 use tauri::State;
 
+/// URI of the connection the VM lives on; `None` is the selected connection
+fn connection_uri(state: &AppState, connection_id: Option<&str>) -> Result<String, String> {
+    state
+        .libvirt(connection_id)
+        .map(|libvirt| libvirt.get_uri().to_string())
+        .map_err(|e| e.to_string())
+}
+
 #[derive(Debug, Serialize)]
 pub struct GuestAgentStatus {
     pub available: bool,
@@ -21,11 +29,13 @@ pub struct GuestAgentStatus {
 #[tauri::command]
 pub async fn check_guest_agent_status(
     vm_name: String,
+    connection_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<GuestAgentStatus, String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
     let available = state
         .guest_agent
-        .is_agent_available(&vm_name)
+        .is_agent_available(&uri, &vm_name)
         .await;
 
     if !available {
@@ -38,7 +48,7 @@ pub async fn check_guest_agent_status(
     // Get agent info if available
     let agent_info = state
         .guest_agent
-        .get_agent_info(&vm_name)
+        .get_agent_info(&uri, &vm_name)
         .await
         .ok();
 
@@ -52,11 +62,13 @@ pub async fn check_guest_agent_status(
 #[tauri::command]
 pub async fn get_guest_system_info(
     vm_name: String,
+    connection_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<SystemInfo, String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
     state
         .guest_agent
-        .get_system_info(&vm_name)
+        .get_system_info(&uri, &vm_name)
         .await
         .map_err(|e| format!("Failed to get guest system info: {}", e))
 }
@@ -65,11 +77,13 @@ pub async fn get_guest_system_info(
 #[tauri::command]
 pub async fn get_guest_network_info(
     vm_name: String,
+    connection_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<NetworkInfo, String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
     state
         .guest_agent
-        .get_network_info(&vm_name)
+        .get_network_info(&uri, &vm_name)
         .await
         .map_err(|e| format!("Failed to get guest network info: {}", e))
 }
@@ -78,11 +92,13 @@ pub async fn get_guest_network_info(
 #[tauri::command]
 pub async fn get_guest_disk_usage(
     vm_name: String,
+    connection_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<DiskUsageInfo, String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
     state
         .guest_agent
-        .get_disk_usage(&vm_name)
+        .get_disk_usage(&uri, &vm_name)
         .await
         .map_err(|e| format!("Failed to get guest disk usage: {}", e))
 }
@@ -91,14 +107,16 @@ pub async fn get_guest_disk_usage(
 #[tauri::command]
 pub async fn execute_guest_command(
     vm_name: String,
+    connection_id: Option<String>,
     command: String,
     args: Vec<String>,
     _timeout_seconds: Option<u64>,
     state: State<'_, AppState>,
 ) -> Result<ExecCommandResult, String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
     state
         .guest_agent
-        .exec_command(&vm_name, &command, args)
+        .exec_command(&uri, &vm_name, &command, args)
         .await
         .map_err(|e| format!("Failed to execute command: {}", e))
 }
@@ -107,12 +125,14 @@ pub async fn execute_guest_command(
 #[tauri::command]
 pub async fn read_guest_file(
     vm_name: String,
+    connection_id: Option<String>,
     path: String,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
     let result = state
         .guest_agent
-        .exec_command(&vm_name, "cat", vec![path])
+        .exec_command(&uri, &vm_name, "cat", vec![path])
         .await
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
@@ -126,15 +146,17 @@ pub async fn read_guest_file(
 #[tauri::command]
 pub async fn write_guest_file(
     vm_name: String,
+    connection_id: Option<String>,
     path: String,
     content: String,
     _create_dirs: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
     // Use echo with redirect via guest-exec
     let result = state
         .guest_agent
-        .exec_command(&vm_name, "bash", vec![
+        .exec_command(&uri, &vm_name, "bash", vec![
             "-c".to_string(),
             format!("echo '{}' > {}", content.replace("'", "'\\''"), path)
         ])
@@ -151,14 +173,16 @@ pub async fn write_guest_file(
 #[tauri::command]
 pub async fn guest_agent_shutdown(
     vm_name: String,
+    connection_id: Option<String>,
     force: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
     let force_shutdown = force.unwrap_or(false);
 
     state
         .guest_agent
-        .shutdown(&vm_name, force_shutdown)
+        .shutdown(&uri, &vm_name, force_shutdown)
         .await
         .map_err(|e| format!("Failed to shutdown guest: {}", e))
 }
@@ -167,14 +191,16 @@ pub async fn guest_agent_shutdown(
 #[tauri::command]
 pub async fn guest_agent_reboot(
     vm_name: String,
+    connection_id: Option<String>,
     force: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
     let force_reboot = force.unwrap_or(false);
 
     state
         .guest_agent
-        .reboot(&vm_name, force_reboot)
+        .reboot(&uri, &vm_name, force_reboot)
         .await
         .map_err(|e| format!("Failed to reboot guest: {}", e))
 }
@@ -183,11 +209,13 @@ pub async fn guest_agent_reboot(
 #[tauri::command]
 pub async fn get_guest_cpu_stats(
     vm_name: String,
+    connection_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<GuestCpuStats, String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
     state
         .guest_agent
-        .get_cpu_stats(&vm_name)
+        .get_cpu_stats(&uri, &vm_name)
         .await
         .map_err(|e| format!("Failed to get guest CPU stats: {}", e))
 }
@@ -196,11 +224,13 @@ pub async fn get_guest_cpu_stats(
 #[tauri::command]
 pub async fn get_guest_disk_stats(
     vm_name: String,
+    connection_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<GuestDiskStats, String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
     state
         .guest_agent
-        .get_disk_stats(&vm_name)
+        .get_disk_stats(&uri, &vm_name)
         .await
         .map_err(|e| format!("Failed to get guest disk stats: {}", e))
 }
@@ -209,11 +239,13 @@ pub async fn get_guest_disk_stats(
 #[tauri::command]
 pub async fn get_guest_users(
     vm_name: String,
+    connection_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<Vec<GuestUser>, String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
     state
         .guest_agent
-        .get_users(&vm_name)
+        .get_users(&uri, &vm_name)
         .await
         .map_err(|e| format!("Failed to get guest users: {}", e))
 }
@@ -222,11 +254,13 @@ pub async fn get_guest_users(
 #[tauri::command]
 pub async fn get_guest_timezone(
     vm_name: String,
+    connection_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<GuestTimezone, String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
     state
         .guest_agent
-        .get_timezone(&vm_name)
+        .get_timezone(&uri, &vm_name)
         .await
         .map_err(|e| format!("Failed to get guest timezone: {}", e))
 }
@@ -235,11 +269,59 @@ pub async fn get_guest_timezone(
 #[tauri::command]
 pub async fn get_guest_full_info(
     vm_name: String,
+    connection_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<GuestFullInfo, String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
     state
         .guest_agent
-        .get_full_info(&vm_name)
+        .get_full_info(&uri, &vm_name)
         .await
         .map_err(|e| format!("Failed to get full guest info: {}", e))
 }
+
+/// Freeze guest filesystems, or only those at the given mountpoints
+///
+/// The guest stays frozen until `guest_fs_thaw` is called; snapshots and
+/// backups freeze and thaw on their own when quiescing is enabled.
+#[tauri::command]
+pub async fn guest_fs_freeze(
+    vm_name: String,
+    connection_id: Option<String>,
+    mountpoints: Option<Vec<String>>,
+    state: State<'_, AppState>,
+) -> Result<u32, String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
+    state
+        .guest_agent
+        .fs_freeze(Some(&uri), &vm_name, &mountpoints.unwrap_or_default())
+        .map_err(|e| format!("Failed to freeze guest filesystems: {}", e))
+}
+
+/// Thaw guest filesystems
+#[tauri::command]
+pub async fn guest_fs_thaw(
+    vm_name: String,
+    connection_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<u32, String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
+    state
+        .guest_agent
+        .fs_thaw(Some(&uri), &vm_name)
+        .map_err(|e| format!("Failed to thaw guest filesystems: {}", e))
+}
+
+/// Get the freeze state of guest filesystems ("thawed" or "frozen")
+#[tauri::command]
+pub async fn get_guest_fs_freeze_status(
+    vm_name: String,
+    connection_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let uri = connection_uri(&state, connection_id.as_deref())?;
+    state
+        .guest_agent
+        .fs_freeze_status(Some(&uri), &vm_name)
+        .map_err(|e| format!("Failed to get guest freeze status: {}", e))
+}
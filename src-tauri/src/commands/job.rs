@@ -0,0 +1,48 @@
+use tauri::State;
+use crate::models::job::Job;
+use crate::state::app_state::AppState;
+
+/// List running and recently finished background jobs
+#[tauri::command]
+pub async fn list_jobs(
+    state: State<'_, AppState>,
+) -> Result<Vec<Job>, String> {
+    tracing::debug!("list_jobs command called");
+
+    Ok(state.jobs.list_jobs())
+}
+
+/// Get a single background job
+#[tauri::command]
+pub async fn get_job(
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<Job, String> {
+    tracing::debug!("get_job command called for job: {}", job_id);
+
+    state.jobs.get_job(&job_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Request cancellation of a running job
+#[tauri::command]
+pub async fn cancel_job(
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<(), String> {
+    tracing::info!("cancel_job command called for job: {}", job_id);
+
+    state.jobs.cancel_job(&job_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Remove finished jobs from the job list
+#[tauri::command]
+pub async fn clear_finished_jobs(
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    tracing::info!("clear_finished_jobs command called");
+
+    state.jobs.clear_finished();
+    Ok(())
+}
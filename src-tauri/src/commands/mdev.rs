@@ -24,10 +24,13 @@ pub async fn list_mdev_types() -> Result<Vec<MdevType>, String> {
 #[tauri::command]
 pub async fn list_mdev_devices(
     state: State<'_, AppState>,
+    connection_id: Option<String>,
 ) -> Result<Vec<MdevDevice>, String> {
     tracing::info!("list_mdev_devices command called");
 
-    MdevService::list_mdev_devices(&state.libvirt)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    MdevService::list_mdev_devices(&libvirt)
         .map_err(|e| e.to_string())
 }
 
@@ -37,10 +40,13 @@ pub async fn attach_mdev(
     state: State<'_, AppState>,
     vm_id: String,
     mdev_uuid: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_mdev command called for VM: {} mdev: {}", vm_id, mdev_uuid);
 
-    MdevService::attach_mdev(&state.libvirt, &vm_id, &mdev_uuid)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    MdevService::attach_mdev(&libvirt, &vm_id, &mdev_uuid)
         .map_err(|e| e.to_string())
 }
 
@@ -50,10 +56,13 @@ pub async fn detach_mdev(
     state: State<'_, AppState>,
     vm_id: String,
     mdev_uuid: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("detach_mdev command called for VM: {} mdev: {}", vm_id, mdev_uuid);
 
-    MdevService::detach_mdev(&state.libvirt, &vm_id, &mdev_uuid)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    MdevService::detach_mdev(&libvirt, &vm_id, &mdev_uuid)
         .map_err(|e| e.to_string())
 }
 
@@ -1,6 +1,6 @@
 use tauri::State;
 use crate::state::app_state::AppState;
-use crate::services::metrics_service::{VmMetrics, HistoricalMetrics};
+use crate::services::metrics_service::{VmMetrics, HistoricalMetrics, HostMetrics, HostSnapshot, PoolMetrics};
 
 /// Store VM performance metrics
 #[tauri::command]
@@ -54,3 +54,49 @@ pub async fn get_metrics_count(
     state.metrics.get_metrics_count()
         .map_err(|e| e.to_string())
 }
+
+/// Get the latest host, pool and network sample of every connected host
+#[tauri::command]
+pub async fn get_host_metrics(
+    state: State<'_, AppState>,
+) -> Result<Vec<HostSnapshot>, String> {
+    tracing::debug!("get_host_metrics command called");
+
+    Ok(state.metrics.latest_host_snapshots())
+}
+
+/// Get historical host metrics for a connection
+#[tauri::command]
+pub async fn get_host_metrics_history(
+    state: State<'_, AppState>,
+    connection_id: String,
+    start_time: i64,
+    end_time: i64,
+    max_points: Option<usize>,
+) -> Result<Vec<HostMetrics>, String> {
+    tracing::debug!(
+        "get_host_metrics_history command called for {}, range: {} to {}",
+        connection_id, start_time, end_time
+    );
+
+    state.metrics.get_host_metrics_history(&connection_id, start_time, end_time, max_points)
+        .map_err(|e| e.to_string())
+}
+
+/// Get historical capacity of a storage pool
+#[tauri::command]
+pub async fn get_pool_metrics_history(
+    state: State<'_, AppState>,
+    connection_id: String,
+    pool_name: String,
+    start_time: i64,
+    end_time: i64,
+) -> Result<Vec<PoolMetrics>, String> {
+    tracing::debug!(
+        "get_pool_metrics_history command called for {}/{}, range: {} to {}",
+        connection_id, pool_name, start_time, end_time
+    );
+
+    state.metrics.get_pool_metrics_history(&connection_id, &pool_name, start_time, end_time)
+        .map_err(|e| e.to_string())
+}
@@ -8,6 +8,7 @@ pub mod metrics;
 pub mod template;
 pub mod scheduler;
 pub mod alert;
+pub mod notification;
 pub mod backup;
 pub mod optimization;
 pub mod retention;
@@ -19,3 +20,4 @@ pub mod mdev;
 pub mod nwfilter;
 pub mod serial_console;
 pub mod sriov;
+pub mod job;
@@ -4,20 +4,33 @@ use crate::services::network_service::NetworkService;
 use crate::state::app_state::AppState;
 
 /// Get all virtual networks (active and inactive)
+///
+/// Without a `connection_id` every connected host is queried and each result
+/// is tagged with the connection it came from. A host that fails to answer is
+/// logged and skipped so one unreachable hypervisor does not hide the rest.
 #[tauri::command]
-pub async fn get_networks(state: State<'_, AppState>) -> Result<Vec<Network>, String> {
+pub async fn get_networks(
+    state: State<'_, AppState>,
+    connection_id: Option<String>,
+) -> Result<Vec<Network>, String> {
     tracing::debug!("get_networks command called");
 
-    NetworkService::list_networks(&state.libvirt)
+    state.list_from_targets(connection_id.as_deref(), "virtual networks", NetworkService::list_networks, |item, id| item.connection_id = Some(id))
         .map_err(|e| e.to_string())
 }
 
 /// Get a single network by name
 #[tauri::command]
-pub async fn get_network(state: State<'_, AppState>, network_name: String) -> Result<Network, String> {
+pub async fn get_network(
+    state: State<'_, AppState>,
+    network_name: String,
+    connection_id: Option<String>,
+) -> Result<Network, String> {
     tracing::debug!("get_network command called for network: {}", network_name);
 
-    NetworkService::get_network(&state.libvirt, &network_name)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    NetworkService::get_network(&libvirt, &network_name)
         .map_err(|e| e.to_string())
 }
 
@@ -26,10 +39,13 @@ pub async fn get_network(state: State<'_, AppState>, network_name: String) -> Re
 pub async fn create_network(
     state: State<'_, AppState>,
     config: NetworkConfig,
+    connection_id: Option<String>,
 ) -> Result<String, String> {
     tracing::info!("create_network command called for network: {}", config.name);
 
-    NetworkService::create_network(&state.libvirt, config)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    NetworkService::create_network(&libvirt, config)
         .map_err(|e| e.to_string())
 }
 
@@ -38,10 +54,13 @@ pub async fn create_network(
 pub async fn delete_network(
     state: State<'_, AppState>,
     network_name: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("delete_network command called for network: {}", network_name);
 
-    NetworkService::delete_network(&state.libvirt, &network_name)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    NetworkService::delete_network(&libvirt, &network_name)
         .map_err(|e| e.to_string())
 }
 
@@ -50,10 +69,13 @@ pub async fn delete_network(
 pub async fn start_network(
     state: State<'_, AppState>,
     network_name: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("start_network command called for network: {}", network_name);
 
-    NetworkService::start_network(&state.libvirt, &network_name)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    NetworkService::start_network(&libvirt, &network_name)
         .map_err(|e| e.to_string())
 }
 
@@ -62,10 +84,13 @@ pub async fn start_network(
 pub async fn stop_network(
     state: State<'_, AppState>,
     network_name: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("stop_network command called for network: {}", network_name);
 
-    NetworkService::stop_network(&state.libvirt, &network_name)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    NetworkService::stop_network(&libvirt, &network_name)
         .map_err(|e| e.to_string())
 }
 
@@ -103,10 +128,13 @@ pub async fn set_network_autostart(
     state: State<'_, AppState>,
     network_name: String,
     autostart: bool,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("set_network_autostart command called: {} -> {}", network_name, autostart);
 
-    NetworkService::set_network_autostart(&state.libvirt, &network_name, autostart)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    NetworkService::set_network_autostart(&libvirt, &network_name, autostart)
         .map_err(|e| e.to_string())
 }
 
@@ -115,10 +143,13 @@ pub async fn set_network_autostart(
 pub async fn get_dhcp_leases(
     state: State<'_, AppState>,
     network_name: String,
+    connection_id: Option<String>,
 ) -> Result<Vec<crate::services::network_service::DhcpLease>, String> {
     tracing::info!("get_dhcp_leases command called: {}", network_name);
 
-    NetworkService::get_dhcp_leases(&state.libvirt, &network_name)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    NetworkService::get_dhcp_leases(&libvirt, &network_name)
         .map_err(|e| e.to_string())
 }
 
@@ -127,9 +158,12 @@ pub async fn get_dhcp_leases(
 pub async fn get_network_details(
     state: State<'_, AppState>,
     network_name: String,
+    connection_id: Option<String>,
 ) -> Result<crate::services::network_service::NetworkDetails, String> {
     tracing::info!("get_network_details command called: {}", network_name);
 
-    NetworkService::get_network_details(&state.libvirt, &network_name)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    NetworkService::get_network_details(&libvirt, &network_name)
         .map_err(|e| e.to_string())
 }
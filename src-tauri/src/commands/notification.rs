@@ -0,0 +1,63 @@
+use crate::services::notification_service::{DeliveryRecord, NotificationChannel, NotificationChannelRequest};
+use crate::state::app_state::AppState;
+use tauri::State;
+
+/// List all notification channels
+#[tauri::command]
+pub async fn list_notification_channels(state: State<'_, AppState>) -> Result<Vec<NotificationChannel>, String> {
+    state.notifications.list_channels().map_err(|e| e.to_string())
+}
+
+/// Create a notification channel
+#[tauri::command]
+pub async fn create_notification_channel(
+    state: State<'_, AppState>,
+    request: NotificationChannelRequest,
+) -> Result<NotificationChannel, String> {
+    state.notifications
+        .create_channel(request)
+        .map_err(|e| e.to_string())
+}
+
+/// Update a notification channel
+#[tauri::command]
+pub async fn update_notification_channel(
+    state: State<'_, AppState>,
+    id: String,
+    request: NotificationChannelRequest,
+) -> Result<NotificationChannel, String> {
+    state.notifications
+        .update_channel(&id, request)
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a notification channel and its delivery log
+#[tauri::command]
+pub async fn delete_notification_channel(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    state.notifications.delete_channel(&id).map_err(|e| e.to_string())
+}
+
+/// Send a test notification through a channel
+#[tauri::command]
+pub async fn test_notification_channel(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<DeliveryRecord, String> {
+    // Webhook, SMTP and command deliveries block until they finish or time out
+    let notifications = state.notifications.clone();
+    tauri::async_runtime::spawn_blocking(move || notifications.test_channel(&id))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+/// Get the delivery log of one channel, or of all channels
+#[tauri::command]
+pub async fn get_notification_deliveries(
+    state: State<'_, AppState>,
+    channel_id: Option<String>,
+) -> Result<Vec<DeliveryRecord>, String> {
+    state.notifications
+        .get_deliveries(channel_id.as_deref())
+        .map_err(|e| e.to_string())
+}
@@ -5,25 +5,42 @@ use crate::services::nwfilter_service::NwFilterService;
 
 /// List all network filters
 #[tauri::command]
-pub async fn get_nwfilters(state: State<'_, AppState>) -> Result<Vec<NwFilter>, String> {
+pub async fn get_nwfilters(
+    state: State<'_, AppState>,
+    connection_id: Option<String>,
+) -> Result<Vec<NwFilter>, String> {
     tracing::debug!("get_nwfilters command called");
-    NwFilterService::list_filters(&state.libvirt)
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    NwFilterService::list_filters(&libvirt)
         .map_err(|e| e.to_string())
 }
 
 /// Get a specific network filter by name
 #[tauri::command]
-pub async fn get_nwfilter(state: State<'_, AppState>, name: String) -> Result<NwFilter, String> {
+pub async fn get_nwfilter(
+    state: State<'_, AppState>,
+    name: String,
+    connection_id: Option<String>,
+) -> Result<NwFilter, String> {
     tracing::debug!("get_nwfilter command called for: {}", name);
-    NwFilterService::get_filter(&state.libvirt, &name)
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    NwFilterService::get_filter(&libvirt, &name)
         .map_err(|e| e.to_string())
 }
 
 /// Get the XML of a network filter
 #[tauri::command]
-pub async fn get_nwfilter_xml(state: State<'_, AppState>, name: String) -> Result<String, String> {
+pub async fn get_nwfilter_xml(
+    state: State<'_, AppState>,
+    name: String,
+    connection_id: Option<String>,
+) -> Result<String, String> {
     tracing::debug!("get_nwfilter_xml command called for: {}", name);
-    NwFilterService::get_filter_xml(&state.libvirt, &name)
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    NwFilterService::get_filter_xml(&libvirt, &name)
         .map_err(|e| e.to_string())
 }
 
@@ -32,9 +49,12 @@ pub async fn get_nwfilter_xml(state: State<'_, AppState>, name: String) -> Resul
 pub async fn create_nwfilter(
     state: State<'_, AppState>,
     config: NwFilterConfig,
+    connection_id: Option<String>,
 ) -> Result<NwFilter, String> {
     tracing::info!("create_nwfilter command called for: {}", config.name);
-    NwFilterService::create_filter(&state.libvirt, config)
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    NwFilterService::create_filter(&libvirt, config)
         .map_err(|e| e.to_string())
 }
 
@@ -43,16 +63,25 @@ pub async fn create_nwfilter(
 pub async fn create_nwfilter_from_xml(
     state: State<'_, AppState>,
     xml: String,
+    connection_id: Option<String>,
 ) -> Result<NwFilter, String> {
     tracing::info!("create_nwfilter_from_xml command called");
-    NwFilterService::create_filter_from_xml(&state.libvirt, &xml)
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    NwFilterService::create_filter_from_xml(&libvirt, &xml)
         .map_err(|e| e.to_string())
 }
 
 /// Delete a network filter
 #[tauri::command]
-pub async fn delete_nwfilter(state: State<'_, AppState>, name: String) -> Result<(), String> {
+pub async fn delete_nwfilter(
+    state: State<'_, AppState>,
+    name: String,
+    connection_id: Option<String>,
+) -> Result<(), String> {
     tracing::info!("delete_nwfilter command called for: {}", name);
-    NwFilterService::delete_filter(&state.libvirt, &name)
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    NwFilterService::delete_filter(&libvirt, &name)
         .map_err(|e| e.to_string())
 }
@@ -5,10 +5,15 @@ use crate::state::app_state::AppState;
 
 /// List all PCI devices on the host
 #[tauri::command]
-pub async fn list_pci_devices(state: State<'_, AppState>) -> Result<Vec<PciDevice>, String> {
+pub async fn list_pci_devices(
+    state: State<'_, AppState>,
+    connection_id: Option<String>,
+) -> Result<Vec<PciDevice>, String> {
     tracing::debug!("list_pci_devices command called");
 
-    PciService::list_pci_devices(&state.libvirt)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    PciService::list_pci_devices(&libvirt)
         .map_err(|e| e.to_string())
 }
 
@@ -38,10 +43,13 @@ pub async fn attach_pci_device(
     vm_id: String,
     pci_address: String,
     managed: bool,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_pci_device command called for VM: {} device: {}", vm_id, pci_address);
 
-    PciService::attach_pci_device(&state.libvirt, &vm_id, &pci_address, managed)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    PciService::attach_pci_device(&libvirt, &vm_id, &pci_address, managed)
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -61,10 +69,13 @@ pub async fn detach_pci_device(
     state: State<'_, AppState>,
     vm_id: String,
     pci_address: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("detach_pci_device command called for VM: {} device: {}", vm_id, pci_address);
 
-    PciService::detach_pci_device(&state.libvirt, &vm_id, &pci_address)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    PciService::detach_pci_device(&libvirt, &vm_id, &pci_address)
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -7,10 +7,13 @@ use crate::services::serial_console_service::{SerialConsoleService, SerialConsol
 pub async fn get_serial_console_info(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<SerialConsoleInfo, String> {
     tracing::info!("get_serial_console_info command called for VM: {}", vm_id);
 
-    SerialConsoleService::get_serial_console_info(&state.libvirt, &vm_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    SerialConsoleService::get_serial_console_info(&libvirt, &vm_id)
         .map_err(|e| e.to_string())
 }
 
@@ -19,11 +22,14 @@ pub async fn get_serial_console_info(
 pub async fn open_serial_console(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<SerialConsoleInfo, String> {
     tracing::info!("open_serial_console command called for VM: {}", vm_id);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     // First get the console info
-    let info = SerialConsoleService::get_serial_console_info(&state.libvirt, &vm_id)
+    let info = SerialConsoleService::get_serial_console_info(&libvirt, &vm_id)
         .map_err(|e| e.to_string())?;
 
     if !info.active {
@@ -31,7 +37,7 @@ pub async fn open_serial_console(
     }
 
     // Open the connection with vm_name for virsh console
-    state.serial_console.open_connection(&vm_id, &info.vm_name, &info.pty_path)
+    state.serial_console.open_connection(libvirt.get_uri(), &vm_id, &info.vm_name, &info.pty_path)
         .map_err(|e| e.to_string())?;
 
     Ok(info)
@@ -1,5 +1,7 @@
 use tauri::{AppHandle, Emitter, State};
-use crate::models::snapshot::{Snapshot, SnapshotConfig};
+use crate::models::snapshot::{Snapshot, SnapshotConfig, SnapshotConfigDiff, SnapshotNode};
+use crate::services::scheduler_service::SCHEDULED_SNAPSHOT_PREFIX;
+use crate::services::snapshot_retention_service::{CreateRetentionPolicyRequest, PruneReport, SnapshotRetentionPolicy};
 use crate::services::snapshot_service::SnapshotService;
 use crate::state::app_state::AppState;
 
@@ -8,9 +10,44 @@ use crate::state::app_state::AppState;
 pub async fn get_snapshots(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<Vec<Snapshot>, String> {
     tracing::debug!("get_snapshots command called for VM: {}", vm_id);
-    SnapshotService::list_snapshots(&state.libvirt, &vm_id)
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    SnapshotService::list_snapshots(&libvirt, &vm_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Get the snapshots of a VM as a tree with their saved definitions and sizes
+#[tauri::command]
+pub async fn get_snapshot_tree(
+    state: State<'_, AppState>,
+    vm_id: String,
+    connection_id: Option<String>,
+) -> Result<Vec<SnapshotNode>, String> {
+    tracing::debug!("get_snapshot_tree command called for VM: {}", vm_id);
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    // Sizing internal snapshots runs qemu-img on every disk image
+    tauri::async_runtime::spawn_blocking(move || SnapshotService::get_snapshot_tree(&libvirt, &vm_id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Compare a snapshot's domain definition with the VM's current one
+#[tauri::command]
+pub async fn diff_snapshot_config(
+    state: State<'_, AppState>,
+    vm_id: String,
+    snapshot_name: String,
+    connection_id: Option<String>,
+) -> Result<SnapshotConfigDiff, String> {
+    tracing::debug!("diff_snapshot_config command called: {} for VM {}", snapshot_name, vm_id);
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    SnapshotService::diff_snapshot_config(&libvirt, &vm_id, &snapshot_name)
         .map_err(|e| e.to_string())
 }
 
@@ -21,10 +58,22 @@ pub async fn create_snapshot(
     state: State<'_, AppState>,
     vm_id: String,
     config: SnapshotConfig,
+    connection_id: Option<String>,
 ) -> Result<String, String> {
     tracing::debug!("create_snapshot command called for VM: {}", vm_id);
 
-    let snapshot_name = SnapshotService::create_snapshot(&state.libvirt, &vm_id, config)
+    // Retention policies prune snapshots by this prefix, so it must only mark scheduled ones
+    if config.name.starts_with(SCHEDULED_SNAPSHOT_PREFIX) {
+        return Err(format!("Snapshot names starting with '{}' are reserved for scheduled snapshots", SCHEDULED_SNAPSHOT_PREFIX));
+    }
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    // Quiescing and writing memory state can take a while
+    let id = vm_id.clone();
+    let snapshot_name = tauri::async_runtime::spawn_blocking(move || SnapshotService::create_snapshot(&libvirt, &id, config))
+        .await
+        .map_err(|e| e.to_string())?
         .map_err(|e| e.to_string())?;
 
     // Emit event for real-time updates
@@ -44,10 +93,17 @@ pub async fn delete_snapshot(
     state: State<'_, AppState>,
     vm_id: String,
     snapshot_name: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::debug!("delete_snapshot command called: {} from VM {}", snapshot_name, vm_id);
 
-    SnapshotService::delete_snapshot(&state.libvirt, &vm_id, &snapshot_name)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    // Merging the overlays of an external snapshot runs block jobs that can take a while
+    let (id, name) = (vm_id.clone(), snapshot_name.clone());
+    tauri::async_runtime::spawn_blocking(move || SnapshotService::delete_snapshot(&libvirt, &id, &name))
+        .await
+        .map_err(|e| e.to_string())?
         .map_err(|e| e.to_string())?;
 
     // Emit event for real-time updates
@@ -67,10 +123,16 @@ pub async fn revert_snapshot(
     state: State<'_, AppState>,
     vm_id: String,
     snapshot_name: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::debug!("revert_snapshot command called: {} for VM {}", snapshot_name, vm_id);
 
-    SnapshotService::revert_snapshot(&state.libvirt, &vm_id, &snapshot_name)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    let (id, name) = (vm_id.clone(), snapshot_name.clone());
+    tauri::async_runtime::spawn_blocking(move || SnapshotService::revert_snapshot(&libvirt, &id, &name))
+        .await
+        .map_err(|e| e.to_string())?
         .map_err(|e| e.to_string())?;
 
     // Emit event for real-time updates
@@ -82,3 +144,54 @@ pub async fn revert_snapshot(
 
     Ok(())
 }
+
+/// Create a snapshot retention policy
+#[tauri::command]
+pub async fn create_snapshot_retention_policy(
+    state: State<'_, AppState>,
+    request: CreateRetentionPolicyRequest,
+) -> Result<SnapshotRetentionPolicy, String> {
+    state.snapshot_retention.create_policy(request).map_err(|e| e.to_string())
+}
+
+/// List snapshot retention policies with their last prune reports
+#[tauri::command]
+pub async fn list_snapshot_retention_policies(
+    state: State<'_, AppState>,
+) -> Result<Vec<SnapshotRetentionPolicy>, String> {
+    state.snapshot_retention.list_policies().map_err(|e| e.to_string())
+}
+
+/// Enable or disable a snapshot retention policy
+#[tauri::command]
+pub async fn update_snapshot_retention_policy_status(
+    state: State<'_, AppState>,
+    id: String,
+    enabled: bool,
+) -> Result<SnapshotRetentionPolicy, String> {
+    state.snapshot_retention.update_policy_status(&id, enabled).map_err(|e| e.to_string())
+}
+
+/// Delete a snapshot retention policy
+#[tauri::command]
+pub async fn delete_snapshot_retention_policy(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), String> {
+    state.snapshot_retention.delete_policy(&id).map_err(|e| e.to_string())
+}
+
+/// Apply a snapshot retention policy now; a dry run only lists what would be deleted
+#[tauri::command]
+pub async fn run_snapshot_retention_policy(
+    state: State<'_, AppState>,
+    id: String,
+    dry_run: bool,
+) -> Result<PruneReport, String> {
+    let service = state.snapshot_retention.clone();
+    let connections = state.connections.clone();
+    tauri::async_runtime::spawn_blocking(move || service.run_policy(&id, &connections, dry_run))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
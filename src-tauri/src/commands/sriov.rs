@@ -15,9 +15,12 @@ pub async fn list_sriov_pfs() -> Result<Vec<SriovPf>, String> {
 pub async fn list_sriov_vfs(
     state: State<'_, AppState>,
     pf_address: String,
+    connection_id: Option<String>,
 ) -> Result<Vec<SriovVf>, String> {
     tracing::debug!("list_sriov_vfs command called for PF: {}", pf_address);
-    SriovService::list_vfs(&pf_address, &state.libvirt).map_err(|e| e.to_string())
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    SriovService::list_vfs(&pf_address, &libvirt).map_err(|e| e.to_string())
 }
 
 /// Enable VFs on a Physical Function
@@ -64,10 +67,13 @@ pub async fn attach_sriov_vf(
     state: State<'_, AppState>,
     vm_id: String,
     vf_address: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_sriov_vf command called: VM={}, VF={}", vm_id, vf_address);
 
-    SriovService::attach_vf_to_vm(&state.libvirt, &vm_id, &vf_address)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    SriovService::attach_vf_to_vm(&libvirt, &vm_id, &vf_address)
         .map_err(|e| e.to_string())?;
 
     let _ = app.emit("sriov-vf-attached", serde_json::json!({
@@ -85,10 +91,13 @@ pub async fn detach_sriov_vf(
     state: State<'_, AppState>,
     vm_id: String,
     vf_address: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("detach_sriov_vf command called: VM={}, VF={}", vm_id, vf_address);
 
-    SriovService::detach_vf_from_vm(&state.libvirt, &vm_id, &vf_address)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    SriovService::detach_vf_from_vm(&libvirt, &vm_id, &vf_address)
         .map_err(|e| e.to_string())?;
 
     let _ = app.emit("sriov-vf-detached", serde_json::json!({
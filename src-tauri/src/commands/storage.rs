@@ -1,23 +1,37 @@
 use tauri::State;
+use crate::models::job::JobKind;
 use crate::models::storage::{StoragePool, Volume, VolumeConfig, StoragePoolConfig, VolumeEncryptionInfo};
 use crate::services::storage_service::StorageService;
 use crate::state::app_state::AppState;
 
 /// Get all storage pools (active and inactive)
+///
+/// Without a `connection_id` every connected host is queried and each result
+/// is tagged with the connection it came from. A host that fails to answer is
+/// logged and skipped so one unreachable hypervisor does not hide the rest.
 #[tauri::command]
-pub async fn get_storage_pools(state: State<'_, AppState>) -> Result<Vec<StoragePool>, String> {
+pub async fn get_storage_pools(
+    state: State<'_, AppState>,
+    connection_id: Option<String>,
+) -> Result<Vec<StoragePool>, String> {
     tracing::debug!("get_storage_pools command called");
 
-    StorageService::list_storage_pools(&state.libvirt)
+    state.list_from_targets(connection_id.as_deref(), "storage pools", StorageService::list_storage_pools, |item, id| item.connection_id = Some(id))
         .map_err(|e| e.to_string())
 }
 
 /// Get all volumes in a storage pool
 #[tauri::command]
-pub async fn get_volumes(state: State<'_, AppState>, pool_id: String) -> Result<Vec<Volume>, String> {
+pub async fn get_volumes(
+    state: State<'_, AppState>,
+    pool_id: String,
+    connection_id: Option<String>,
+) -> Result<Vec<Volume>, String> {
     tracing::debug!("get_volumes command called for pool: {}", pool_id);
 
-    StorageService::list_volumes(&state.libvirt, &pool_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    StorageService::list_volumes(&libvirt, &pool_id)
         .map_err(|e| e.to_string())
 }
 
@@ -27,10 +41,13 @@ pub async fn create_volume(
     state: State<'_, AppState>,
     pool_id: String,
     config: VolumeConfig,
+    connection_id: Option<String>,
 ) -> Result<String, String> {
     tracing::info!("create_volume command called for volume: {} in pool: {}", config.name, pool_id);
 
-    StorageService::create_volume(&state.libvirt, &pool_id, config)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    StorageService::create_volume(&libvirt, &pool_id, config)
         .map_err(|e| e.to_string())
 }
 
@@ -40,10 +57,13 @@ pub async fn delete_volume(
     state: State<'_, AppState>,
     pool_id: String,
     volume_name: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("delete_volume command called for volume: {} in pool: {}", volume_name, pool_id);
 
-    StorageService::delete_volume(&state.libvirt, &pool_id, &volume_name)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    StorageService::delete_volume(&libvirt, &pool_id, &volume_name)
         .map_err(|e| e.to_string())
 }
 
@@ -52,10 +72,13 @@ pub async fn delete_volume(
 pub async fn create_storage_pool(
     state: State<'_, AppState>,
     config: StoragePoolConfig,
+    connection_id: Option<String>,
 ) -> Result<String, String> {
     tracing::info!("create_storage_pool command called for pool: {}", config.name);
 
-    StorageService::create_storage_pool(&state.libvirt, config)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    StorageService::create_storage_pool(&libvirt, config)
         .map_err(|e| e.to_string())
 }
 
@@ -66,15 +89,20 @@ pub async fn resize_volume(
     pool_id: String,
     volume_name: String,
     new_capacity_gb: u64,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("resize_volume command called for volume: {} in pool: {} to {}GB",
                    volume_name, pool_id, new_capacity_gb);
 
-    StorageService::resize_volume(&state.libvirt, &pool_id, &volume_name, new_capacity_gb)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    StorageService::resize_volume(&libvirt, &pool_id, &volume_name, new_capacity_gb)
         .map_err(|e| e.to_string())
 }
 
 /// Upload a file to create/replace a volume
+///
+/// Runs as a background job and returns its ID; the job result is the new `Volume`.
 #[tauri::command]
 pub async fn upload_volume(
     state: State<'_, AppState>,
@@ -82,38 +110,52 @@ pub async fn upload_volume(
     volume_name: String,
     source_path: String,
     format: Option<String>,
-) -> Result<Volume, String> {
+    connection_id: Option<String>,
+) -> Result<String, String> {
     tracing::info!("upload_volume command called: {} -> volume {} in pool {}",
                    source_path, volume_name, pool_id);
 
-    StorageService::upload_volume(
-        &state.libvirt,
-        &pool_id,
-        &volume_name,
-        &source_path,
-        format.as_deref(),
-    )
-    .map_err(|e| e.to_string())
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    let description = format!("Upload {} to volume {}", source_path, volume_name);
+    Ok(state.jobs.start(JobKind::UploadVolume, description, volume_name.clone(), connection_id, move |job| {
+        StorageService::upload_volume(
+            &libvirt,
+            &pool_id,
+            &volume_name,
+            &source_path,
+            format.as_deref(),
+            Some(job),
+        )
+    }))
 }
 
 /// Download a volume to a local file
+///
+/// Runs as a background job and returns its ID; the job result is the number of bytes written.
 #[tauri::command]
 pub async fn download_volume(
     state: State<'_, AppState>,
     pool_id: String,
     volume_name: String,
     dest_path: String,
-) -> Result<u64, String> {
+    connection_id: Option<String>,
+) -> Result<String, String> {
     tracing::info!("download_volume command called: volume {} from pool {} -> {}",
                    volume_name, pool_id, dest_path);
 
-    StorageService::download_volume(
-        &state.libvirt,
-        &pool_id,
-        &volume_name,
-        &dest_path,
-    )
-    .map_err(|e| e.to_string())
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    let description = format!("Download volume {} to {}", volume_name, dest_path);
+    Ok(state.jobs.start(JobKind::DownloadVolume, description, volume_name.clone(), connection_id, move |job| {
+        StorageService::download_volume(
+            &libvirt,
+            &pool_id,
+            &volume_name,
+            &dest_path,
+            Some(job),
+        )
+    }))
 }
 
 /// Get the file path of a volume
@@ -122,11 +164,14 @@ pub async fn get_volume_path(
     state: State<'_, AppState>,
     pool_id: String,
     volume_name: String,
+    connection_id: Option<String>,
 ) -> Result<String, String> {
     tracing::debug!("get_volume_path command called for volume: {} in pool: {}",
                     volume_name, pool_id);
 
-    StorageService::get_volume_path(&state.libvirt, &pool_id, &volume_name)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    StorageService::get_volume_path(&libvirt, &pool_id, &volume_name)
         .map_err(|e| e.to_string())
 }
 
@@ -136,11 +181,14 @@ pub async fn get_volume_encryption_info(
     state: State<'_, AppState>,
     pool_id: String,
     volume_name: String,
+    connection_id: Option<String>,
 ) -> Result<VolumeEncryptionInfo, String> {
     tracing::debug!("get_volume_encryption_info command called for volume: {} in pool: {}",
                     volume_name, pool_id);
 
-    StorageService::get_volume_encryption_info(&state.libvirt, &pool_id, &volume_name)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    StorageService::get_volume_encryption_info(&libvirt, &pool_id, &volume_name)
         .map_err(|e| e.to_string())
 }
 
@@ -156,14 +204,19 @@ pub async fn get_ova_metadata(
 }
 
 /// Import OVA/OVF file and convert disks
+///
+/// Runs as a background job and returns its ID; the job result is the path of the main disk.
 #[tauri::command]
 pub async fn import_ova(
+    state: State<'_, AppState>,
     config: crate::services::ova_service::OvaImportConfig,
 ) -> Result<String, String> {
     tracing::info!("import_ova command called for: {}", config.source_path);
 
-    let result_path = crate::services::ova_service::OvaService::import_ova(config)
-        .map_err(|e| e.to_string())?;
-
-    Ok(result_path.to_string_lossy().to_string())
+    let description = format!("Import {}", config.source_path);
+    let target = config.source_path.clone();
+    Ok(state.jobs.start(JobKind::ImportOva, description, target, None, move |job| {
+        let result_path = crate::services::ova_service::OvaService::import_ova(config, Some(job))?;
+        Ok(result_path.to_string_lossy().to_string())
+    }))
 }
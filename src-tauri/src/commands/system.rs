@@ -4,12 +4,16 @@ use crate::state::app_state::AppState;
 
 /// Get host information from libvirt
 #[tauri::command]
-pub async fn get_host_info(state: State<'_, AppState>) -> Result<HostInfo, String> {
+pub async fn get_host_info(
+    state: State<'_, AppState>,
+    connection_id: Option<String>,
+) -> Result<HostInfo, String> {
     use crate::services::vm_service::VmService;
 
     tracing::debug!("get_host_info command called");
 
-    let libvirt = &state.libvirt;
+    let libvirt = state.libvirt(connection_id.as_deref())
+        .map_err(|e| e.to_string())?;
 
     let hostname = libvirt.get_hostname()
         .map_err(|e| e.to_string())?;
@@ -38,7 +42,7 @@ pub async fn get_host_info(state: State<'_, AppState>) -> Result<HostInfo, Strin
         .map_err(|e| format!("Failed to get hypervisor type: {}", e))?;
 
     // Get VM counts
-    let all_vms = VmService::list_vms(&state.libvirt)
+    let all_vms = VmService::list_vms(&libvirt)
         .map_err(|e| e.to_string())?;
     let total_vms = all_vms.len() as u32;
     let active_vms = all_vms.iter()
@@ -62,28 +66,46 @@ pub async fn get_host_info(state: State<'_, AppState>) -> Result<HostInfo, Strin
 
 /// Get libvirt connection status
 #[tauri::command]
-pub async fn get_connection_status(state: State<'_, AppState>) -> Result<ConnectionStatus, String> {
+pub async fn get_connection_status(
+    state: State<'_, AppState>,
+    connection_id: Option<String>,
+) -> Result<ConnectionStatus, String> {
     tracing::debug!("get_connection_status command called");
 
-    let libvirt = &state.libvirt;
+    let libvirt = match state.libvirt(connection_id.as_deref()) {
+        Ok(libvirt) => libvirt,
+        Err(e) => {
+            return Ok(ConnectionStatus {
+                connected: false,
+                uri: String::new(),
+                error: Some(e.to_string()),
+            });
+        }
+    };
     let connected = libvirt.is_alive();
 
     Ok(ConnectionStatus {
         connected,
-        uri: "qemu:///system".to_string(),
+        uri: libvirt.get_uri().to_string(),
         error: if connected { None } else { Some("Connection lost".to_string()) },
     })
 }
 
 /// Get graphics display info for a VM (VNC or SPICE, starts websockify proxy if needed)
 #[tauri::command]
-pub async fn get_vnc_info(state: State<'_, AppState>, vm_id: String) -> Result<VncInfo, String> {
+pub async fn get_vnc_info(
+    state: State<'_, AppState>,
+    vm_id: String,
+    connection_id: Option<String>,
+) -> Result<VncInfo, String> {
     use virt::domain::Domain;
     use virt::sys;
 
     tracing::debug!("get_vnc_info command called for VM: {}", vm_id);
 
-    let conn = state.libvirt.get_connection();
+    let libvirt = state.libvirt(connection_id.as_deref())
+        .map_err(|e| e.to_string())?;
+    let conn = libvirt.get_connection();
     let domain = Domain::lookup_by_uuid_string(conn, &vm_id)
         .map_err(|_| format!("VM not found: {}", vm_id))?;
 
@@ -153,12 +175,18 @@ pub async fn get_vnc_info(state: State<'_, AppState>, vm_id: String) -> Result<V
 
 /// Launch external VNC viewer for a VM
 #[tauri::command]
-pub async fn open_vnc_console(state: State<'_, AppState>, vm_id: String) -> Result<(), String> {
+pub async fn open_vnc_console(
+    state: State<'_, AppState>,
+    vm_id: String,
+    connection_id: Option<String>,
+) -> Result<(), String> {
     use virt::domain::Domain;
 
     tracing::debug!("open_vnc_console command called for VM: {}", vm_id);
 
-    let conn = state.libvirt.get_connection();
+    let libvirt = state.libvirt(connection_id.as_deref())
+        .map_err(|e| e.to_string())?;
+    let conn = libvirt.get_connection();
     let domain = Domain::lookup_by_uuid_string(conn, &vm_id)
         .map_err(|_| format!("VM not found: {}", vm_id))?;
 
@@ -175,7 +203,7 @@ pub async fn open_vnc_console(state: State<'_, AppState>, vm_id: String) -> Resu
 
         match std::process::Command::new(viewer)
             .arg("--connect")
-            .arg("qemu:///system")
+            .arg(libvirt.get_uri())
             .arg(&name)
             .stderr(std::process::Stdio::null()) // Suppress GTK warnings
             .spawn()
@@ -5,9 +5,14 @@ use crate::state::app_state::AppState;
 
 /// Get list of USB devices connected to the host
 #[tauri::command]
-pub async fn list_usb_devices(state: State<'_, AppState>) -> Result<Vec<UsbDevice>, String> {
+pub async fn list_usb_devices(
+    state: State<'_, AppState>,
+    connection_id: Option<String>,
+) -> Result<Vec<UsbDevice>, String> {
     tracing::debug!("list_usb_devices command called");
-    usb_service::list_usb_devices(&state.libvirt)
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    usb_service::list_usb_devices(&libvirt)
 }
 
 /// Attach a USB device to a VM by vendor and product ID
@@ -18,10 +23,13 @@ pub async fn attach_usb_device(
     vm_id: String,
     vendor_id: String,
     product_id: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_usb_device command called for VM: {} device: {}:{}", vm_id, vendor_id, product_id);
 
-    usb_service::attach_usb_device(&state.libvirt, &vm_id, &vendor_id, &product_id)?;
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    usb_service::attach_usb_device(&libvirt, &vm_id, &vendor_id, &product_id)?;
 
     // Emit event
     let _ = app.emit("vm-usb-device-attached", serde_json::json!({
@@ -41,10 +49,13 @@ pub async fn detach_usb_device(
     vm_id: String,
     vendor_id: String,
     product_id: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("detach_usb_device command called for VM: {} device: {}:{}", vm_id, vendor_id, product_id);
 
-    usb_service::detach_usb_device(&state.libvirt, &vm_id, &vendor_id, &product_id)?;
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    usb_service::detach_usb_device(&libvirt, &vm_id, &vendor_id, &product_id)?;
 
     // Emit event
     let _ = app.emit("vm-usb-device-detached", serde_json::json!({
@@ -61,7 +72,10 @@ pub async fn detach_usb_device(
 pub async fn get_vm_usb_devices(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<Vec<UsbDevice>, String> {
     tracing::debug!("get_vm_usb_devices command called for VM: {}", vm_id);
-    usb_service::get_vm_usb_devices(&state.libvirt, &vm_id)
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    usb_service::get_vm_usb_devices(&libvirt, &vm_id)
 }
@@ -15,21 +15,48 @@ struct VmStateChangedPayload {
 }
 
 /// Get all VMs (active and inactive)
+///
+/// Without a `connection_id` every connected host is queried and each result
+/// is tagged with the connection it came from. A host that fails to answer is
+/// logged and skipped so one unreachable hypervisor does not hide the rest.
 #[tauri::command]
-pub async fn get_vms(state: State<'_, AppState>) -> Result<Vec<VM>, String> {
+pub async fn get_vms(
+    state: State<'_, AppState>,
+    connection_id: Option<String>,
+) -> Result<Vec<VM>, String> {
     tracing::debug!("get_vms command called");
 
-    VmService::list_vms(&state.libvirt)
+    state.list_from_targets(connection_id.as_deref(), "VMs", VmService::list_vms, |item, id| item.connection_id = Some(id))
         .map_err(|e| e.to_string())
 }
 
 /// Get a single VM by ID
+///
+/// Without a `connection_id` every connected host is searched, so detail
+/// windows keep resolving their VM after the active connection changes.
 #[tauri::command]
-pub async fn get_vm(state: State<'_, AppState>, vm_id: String) -> Result<VM, String> {
+pub async fn get_vm(
+    state: State<'_, AppState>,
+    vm_id: String,
+    connection_id: Option<String>,
+) -> Result<VM, String> {
     tracing::debug!("get_vm command called for VM: {}", vm_id);
 
-    VmService::get_vm(&state.libvirt, &vm_id)
-        .map_err(|e| e.to_string())
+    let targets = state.libvirt_targets(connection_id.as_deref())
+        .map_err(|e| e.to_string())?;
+
+    let mut last_error = format!("VM not found: {}", vm_id);
+    for (id, libvirt) in targets {
+        match VmService::get_vm(&libvirt, &vm_id) {
+            Ok(mut vm) => {
+                vm.connection_id = Some(id);
+                return Ok(vm);
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+
+    Err(last_error)
 }
 
 /// Start a VM
@@ -38,16 +65,19 @@ pub async fn start_vm(
     app: AppHandle,
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("start_vm command called for VM: {}", vm_id);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     // Get VM info before state change
-    let vm = VmService::get_vm(&state.libvirt, &vm_id)
+    let vm = VmService::get_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())?;
     let old_state = vm.state.clone();
     let vm_name = vm.name.clone();
 
-    VmService::start_vm(&state.libvirt, &vm_id)
+    VmService::start_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -68,16 +98,19 @@ pub async fn stop_vm(
     app: AppHandle,
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("stop_vm command called for VM: {}", vm_id);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     // Get VM info before state change
-    let vm = VmService::get_vm(&state.libvirt, &vm_id)
+    let vm = VmService::get_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())?;
     let old_state = vm.state.clone();
     let vm_name = vm.name.clone();
 
-    VmService::stop_vm(&state.libvirt, &vm_id)
+    VmService::stop_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -98,16 +131,19 @@ pub async fn force_stop_vm(
     app: AppHandle,
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("force_stop_vm command called for VM: {}", vm_id);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     // Get VM info before state change
-    let vm = VmService::get_vm(&state.libvirt, &vm_id)
+    let vm = VmService::get_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())?;
     let old_state = vm.state.clone();
     let vm_name = vm.name.clone();
 
-    VmService::force_stop_vm(&state.libvirt, &vm_id)
+    VmService::force_stop_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -128,16 +164,19 @@ pub async fn pause_vm(
     app: AppHandle,
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("pause_vm command called for VM: {}", vm_id);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     // Get VM info before state change
-    let vm = VmService::get_vm(&state.libvirt, &vm_id)
+    let vm = VmService::get_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())?;
     let old_state = vm.state.clone();
     let vm_name = vm.name.clone();
 
-    VmService::pause_vm(&state.libvirt, &vm_id)
+    VmService::pause_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -158,16 +197,19 @@ pub async fn resume_vm(
     app: AppHandle,
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("resume_vm command called for VM: {}", vm_id);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     // Get VM info before state change
-    let vm = VmService::get_vm(&state.libvirt, &vm_id)
+    let vm = VmService::get_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())?;
     let old_state = vm.state.clone();
     let vm_name = vm.name.clone();
 
-    VmService::resume_vm(&state.libvirt, &vm_id)
+    VmService::resume_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -188,16 +230,19 @@ pub async fn hibernate_vm(
     app: AppHandle,
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("hibernate_vm command called for VM: {}", vm_id);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     // Get VM info before state change
-    let vm = VmService::get_vm(&state.libvirt, &vm_id)
+    let vm = VmService::get_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())?;
     let old_state = vm.state.clone();
     let vm_name = vm.name.clone();
 
-    VmService::hibernate_vm(&state.libvirt, &vm_id)
+    VmService::hibernate_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())?;
 
     // Emit event - VM is now stopped but with saved state
@@ -217,10 +262,13 @@ pub async fn hibernate_vm(
 pub async fn has_managed_save(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<bool, String> {
     tracing::debug!("has_managed_save command called for VM: {}", vm_id);
 
-    VmService::has_managed_save(&state.libvirt, &vm_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::has_managed_save(&libvirt, &vm_id)
         .map_err(|e| e.to_string())
 }
 
@@ -229,19 +277,28 @@ pub async fn has_managed_save(
 pub async fn remove_managed_save(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("remove_managed_save command called for VM: {}", vm_id);
 
-    VmService::remove_managed_save(&state.libvirt, &vm_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::remove_managed_save(&libvirt, &vm_id)
         .map_err(|e| e.to_string())
 }
 
 /// Reboot a VM
 #[tauri::command]
-pub async fn reboot_vm(state: State<'_, AppState>, vm_id: String) -> Result<(), String> {
+pub async fn reboot_vm(
+    state: State<'_, AppState>,
+    vm_id: String,
+    connection_id: Option<String>,
+) -> Result<(), String> {
     tracing::info!("reboot_vm command called for VM: {}", vm_id);
 
-    VmService::reboot_vm(&state.libvirt, &vm_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::reboot_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())
 }
 
@@ -253,11 +310,14 @@ pub async fn delete_vm(
     vm_id: String,
     delete_disks: bool,
     delete_snapshots: bool,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("delete_vm command called for VM: {} (delete_disks: {}, delete_snapshots: {})", vm_id, delete_disks, delete_snapshots);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     // Get VM info before deletion
-    let vm = VmService::get_vm(&state.libvirt, &vm_id)
+    let vm = VmService::get_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())?;
     let vm_name = vm.name.clone();
 
@@ -266,10 +326,10 @@ pub async fn delete_vm(
         tracing::info!("Deleting all snapshots for VM: {}", vm_id);
         use crate::services::snapshot_service::SnapshotService;
 
-        match SnapshotService::list_snapshots(&state.libvirt, &vm_id) {
+        match SnapshotService::list_snapshots(&libvirt, &vm_id) {
             Ok(snapshots) => {
                 for snapshot in snapshots {
-                    if let Err(e) = SnapshotService::delete_snapshot(&state.libvirt, &vm_id, &snapshot.name) {
+                    if let Err(e) = SnapshotService::delete_snapshot(&libvirt, &vm_id, &snapshot.name) {
                         tracing::warn!("Failed to delete snapshot {}: {}", snapshot.name, e);
                     } else {
                         tracing::info!("Deleted snapshot: {}", snapshot.name);
@@ -282,7 +342,7 @@ pub async fn delete_vm(
         }
     }
 
-    VmService::delete_vm(&state.libvirt, &vm_id, delete_disks)
+    VmService::delete_vm(&libvirt, &vm_id, delete_disks)
         .map_err(|e| e.to_string())?;
 
     // Emit event (use a custom event for deletion)
@@ -304,10 +364,13 @@ pub async fn clone_vm(
     state: State<'_, AppState>,
     source_vm_id: String,
     new_name: String,
+    connection_id: Option<String>,
 ) -> Result<String, String> {
     tracing::info!("clone_vm command called: source_vm_id={}, new_name={}", source_vm_id, new_name);
 
-    let cloned_vm_id = VmService::clone_vm(&state.libvirt, &source_vm_id, &new_name)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    let cloned_vm_id = VmService::clone_vm(&libvirt, &source_vm_id, &new_name)
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -322,29 +385,37 @@ pub async fn clone_vm(
 }
 
 /// Clone an existing VM with advanced options (disk cloning, snapshots)
+///
+/// Runs as a background job and returns its ID; the job result is the cloned VM's ID.
 #[tauri::command]
 pub async fn clone_vm_with_options(
     app: AppHandle,
     state: State<'_, AppState>,
     source_vm_id: String,
     config: crate::models::vm::CloneConfig,
+    connection_id: Option<String>,
 ) -> Result<String, String> {
     tracing::info!("clone_vm_with_options command called: source_vm_id={}, config={:?}", source_vm_id, config);
 
-    let cloned_vm_id = VmService::clone_vm_with_options(&state.libvirt, &source_vm_id, &config)
-        .map_err(|e| e.to_string())?;
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
 
-    // Emit event
-    let _ = app.emit("vm-cloned", serde_json::json!({
-        "sourceVmId": source_vm_id,
-        "clonedVmId": cloned_vm_id,
-        "newName": config.new_name,
-        "cloneDisks": config.clone_disks,
-        "cloneSnapshots": config.clone_snapshots,
-        "timestamp": chrono::Utc::now().timestamp_millis(),
-    }));
+    let description = format!("Clone VM as {}", config.new_name);
+    let target = source_vm_id.clone();
+    Ok(state.jobs.start(crate::models::job::JobKind::CloneVm, description, target, connection_id, move |job| {
+        let cloned_vm_id = VmService::clone_vm_with_options(&libvirt, &source_vm_id, &config, Some(job))?;
 
-    Ok(cloned_vm_id)
+        // Emit event
+        let _ = app.emit("vm-cloned", serde_json::json!({
+            "sourceVmId": source_vm_id,
+            "clonedVmId": cloned_vm_id,
+            "newName": config.new_name,
+            "cloneDisks": config.clone_disks,
+            "cloneSnapshots": config.clone_snapshots,
+            "timestamp": chrono::Utc::now().timestamp_millis(),
+        }));
+
+        Ok(cloned_vm_id)
+    }))
 }
 
 /// Create a new VM
@@ -353,10 +424,13 @@ pub async fn create_vm(
     app: AppHandle,
     state: State<'_, AppState>,
     config: crate::models::vm::VmConfig,
+    connection_id: Option<String>,
 ) -> Result<String, String> {
     tracing::info!("create_vm command called for VM: {}", config.name);
 
-    let vm_id = VmService::create_vm(&state.libvirt, config)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    let vm_id = VmService::create_vm(&libvirt, config)
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -370,10 +444,16 @@ pub async fn create_vm(
 
 /// Get VM performance statistics
 #[tauri::command]
-pub async fn get_vm_stats(state: State<'_, AppState>, vm_id: String) -> Result<VmStats, String> {
+pub async fn get_vm_stats(
+    state: State<'_, AppState>,
+    vm_id: String,
+    connection_id: Option<String>,
+) -> Result<VmStats, String> {
     tracing::debug!("get_vm_stats command called for VM: {}", vm_id);
 
-    VmService::get_vm_stats(&state.libvirt, &vm_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::get_vm_stats(&libvirt, &vm_id)
         .map_err(|e| e.to_string())
 }
 
@@ -384,10 +464,13 @@ pub async fn add_vm_tags(
     state: State<'_, AppState>,
     vm_id: String,
     tags: Vec<String>,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("add_vm_tags command called for VM: {}", vm_id);
 
-    VmService::add_vm_tags(&state.libvirt, &vm_id, tags.clone())
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::add_vm_tags(&libvirt, &vm_id, tags.clone())
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -407,10 +490,13 @@ pub async fn remove_vm_tags(
     state: State<'_, AppState>,
     vm_id: String,
     tags: Vec<String>,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("remove_vm_tags command called for VM: {}", vm_id);
 
-    VmService::remove_vm_tags(&state.libvirt, &vm_id, tags.clone())
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::remove_vm_tags(&libvirt, &vm_id, tags.clone())
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -428,10 +514,13 @@ pub async fn remove_vm_tags(
 pub async fn export_vm(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<String, String> {
     tracing::info!("export_vm command called for VM: {}", vm_id);
 
-    VmService::export_vm(&state.libvirt, &vm_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::export_vm(&libvirt, &vm_id)
         .map_err(|e| e.to_string())
 }
 
@@ -441,10 +530,13 @@ pub async fn import_vm(
     app: AppHandle,
     state: State<'_, AppState>,
     xml: String,
+    connection_id: Option<String>,
 ) -> Result<String, String> {
     tracing::info!("import_vm command called");
 
-    let vm_id = VmService::import_vm(&state.libvirt, &xml)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    let vm_id = VmService::import_vm(&libvirt, &xml)
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -465,10 +557,13 @@ pub async fn attach_disk(
     disk_path: String,
     device_target: String,
     bus_type: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_disk command called for VM: {}", vm_id);
 
-    VmService::attach_disk(&state.libvirt, &vm_id, &disk_path, &device_target, &bus_type)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_disk(&libvirt, &vm_id, &disk_path, &device_target, &bus_type)
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -489,10 +584,13 @@ pub async fn detach_disk(
     state: State<'_, AppState>,
     vm_id: String,
     device_target: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("detach_disk command called for VM: {}", vm_id);
 
-    VmService::detach_disk(&state.libvirt, &vm_id, &device_target)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::detach_disk(&libvirt, &vm_id, &device_target)
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -520,11 +618,14 @@ pub async fn update_disk_settings(
     write_iops_sec: Option<u64>,
     read_bytes_sec: Option<u64>,
     write_bytes_sec: Option<u64>,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("update_disk_settings command called for VM: {}, disk: {}", vm_id, device_target);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     VmService::update_disk_settings(
-        &state.libvirt,
+        &libvirt,
         &vm_id,
         &device_target,
         cache.clone(),
@@ -556,9 +657,12 @@ pub async fn update_disk_settings(
 pub async fn get_kernel_boot_settings(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<KernelBootSettings, String> {
     tracing::info!("get_kernel_boot_settings command called for VM: {}", vm_id);
-    VmService::get_kernel_boot_settings(&state.libvirt, &vm_id).map_err(|e| e.to_string())
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    VmService::get_kernel_boot_settings(&libvirt, &vm_id).map_err(|e| e.to_string())
 }
 
 /// Update direct kernel boot settings for a VM (VM must be shut off)
@@ -572,9 +676,12 @@ pub async fn set_kernel_boot_settings(
     initrd_path: Option<String>,
     kernel_args: Option<String>,
     dtb_path: Option<String>,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("set_kernel_boot_settings command called for VM: {}", vm_id);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     let settings = KernelBootSettings {
         enabled,
         kernel_path: kernel_path.clone(),
@@ -583,7 +690,7 @@ pub async fn set_kernel_boot_settings(
         dtb_path: dtb_path.clone(),
     };
 
-    VmService::set_kernel_boot_settings(&state.libvirt, &vm_id, settings).map_err(|e| e.to_string())?;
+    VmService::set_kernel_boot_settings(&libvirt, &vm_id, settings).map_err(|e| e.to_string())?;
 
     // Emit event
     let _ = app.emit("vm-kernel-boot-updated", serde_json::json!({
@@ -603,9 +710,12 @@ pub async fn set_kernel_boot_settings(
 pub async fn get_hugepages_settings(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<HugepagesSettings, String> {
     tracing::info!("get_hugepages_settings command called for VM: {}", vm_id);
-    VmService::get_hugepages_settings(&state.libvirt, &vm_id).map_err(|e| e.to_string())
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    VmService::get_hugepages_settings(&libvirt, &vm_id).map_err(|e| e.to_string())
 }
 
 /// Set hugepages memory backing for a VM (VM must be shut off)
@@ -615,11 +725,14 @@ pub async fn set_hugepages(
     state: State<'_, AppState>,
     vm_id: String,
     enabled: bool,
-    size: Option<u64>,  // Size in KiB
+    size: Option<u64>,  // Size in KiB,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("set_hugepages command called for VM: {} enabled={} size={:?}", vm_id, enabled, size);
 
-    VmService::set_hugepages(&state.libvirt, &vm_id, enabled, size).map_err(|e| e.to_string())?;
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::set_hugepages(&libvirt, &vm_id, enabled, size).map_err(|e| e.to_string())?;
 
     // Emit event
     let _ = app.emit("vm-hugepages-updated", serde_json::json!({
@@ -652,11 +765,14 @@ pub async fn update_interface_bandwidth(
     outbound_average: Option<u64>,
     outbound_peak: Option<u64>,
     outbound_burst: Option<u64>,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("update_interface_bandwidth command called for VM: {}, interface: {}", vm_id, mac_address);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     VmService::update_interface_bandwidth(
-        &state.libvirt,
+        &libvirt,
         &vm_id,
         &mac_address,
         inbound_average,
@@ -688,11 +804,14 @@ pub async fn set_interface_link_state(
     vm_id: String,
     mac_address: String,
     link_up: bool,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("set_interface_link_state command called for VM: {}, interface: {}, up: {}", vm_id, mac_address, link_up);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     VmService::set_interface_link_state(
-        &state.libvirt,
+        &libvirt,
         &vm_id,
         &mac_address,
         link_up,
@@ -716,11 +835,14 @@ pub async fn get_interface_link_state(
     state: State<'_, AppState>,
     vm_id: String,
     mac_address: String,
+    connection_id: Option<String>,
 ) -> Result<bool, String> {
     tracing::info!("get_interface_link_state command called for VM: {}, interface: {}", vm_id, mac_address);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     VmService::get_interface_link_state(
-        &state.libvirt,
+        &libvirt,
         &vm_id,
         &mac_address,
     )
@@ -742,13 +864,16 @@ pub async fn batch_start_vms(
     app: AppHandle,
     state: State<'_, AppState>,
     vm_ids: Vec<String>,
+    connection_id: Option<String>,
 ) -> Result<Vec<BatchOperationResult>, String> {
     tracing::info!("batch_start_vms command called for {} VMs", vm_ids.len());
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     let mut results = Vec::new();
 
     for vm_id in vm_ids {
-        let vm = match VmService::get_vm(&state.libvirt, &vm_id) {
+        let vm = match VmService::get_vm(&libvirt, &vm_id) {
             Ok(v) => v,
             Err(e) => {
                 results.push(BatchOperationResult {
@@ -764,7 +889,7 @@ pub async fn batch_start_vms(
         let old_state = vm.state.clone();
         let vm_name = vm.name.clone();
 
-        let result = match VmService::start_vm(&state.libvirt, &vm_id) {
+        let result = match VmService::start_vm(&libvirt, &vm_id) {
             Ok(_) => {
                 let _ = app.emit("vm-state-changed", VmStateChangedPayload {
                     vm_id: vm_id.clone(),
@@ -802,13 +927,16 @@ pub async fn batch_stop_vms(
     state: State<'_, AppState>,
     vm_ids: Vec<String>,
     force: bool,
+    connection_id: Option<String>,
 ) -> Result<Vec<BatchOperationResult>, String> {
     tracing::info!("batch_stop_vms command called for {} VMs (force: {})", vm_ids.len(), force);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     let mut results = Vec::new();
 
     for vm_id in vm_ids {
-        let vm = match VmService::get_vm(&state.libvirt, &vm_id) {
+        let vm = match VmService::get_vm(&libvirt, &vm_id) {
             Ok(v) => v,
             Err(e) => {
                 results.push(BatchOperationResult {
@@ -825,7 +953,7 @@ pub async fn batch_stop_vms(
         let vm_name = vm.name.clone();
 
         let result = if force {
-            match VmService::force_stop_vm(&state.libvirt, &vm_id) {
+            match VmService::force_stop_vm(&libvirt, &vm_id) {
                 Ok(_) => {
                     let _ = app.emit("vm-state-changed", VmStateChangedPayload {
                         vm_id: vm_id.clone(),
@@ -850,7 +978,7 @@ pub async fn batch_stop_vms(
                 },
             }
         } else {
-            match VmService::stop_vm(&state.libvirt, &vm_id) {
+            match VmService::stop_vm(&libvirt, &vm_id) {
                 Ok(_) => {
                     let _ = app.emit("vm-state-changed", VmStateChangedPayload {
                         vm_id: vm_id.clone(),
@@ -888,13 +1016,16 @@ pub async fn batch_reboot_vms(
     app: AppHandle,
     state: State<'_, AppState>,
     vm_ids: Vec<String>,
+    connection_id: Option<String>,
 ) -> Result<Vec<BatchOperationResult>, String> {
     tracing::info!("batch_reboot_vms command called for {} VMs", vm_ids.len());
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     let mut results = Vec::new();
 
     for vm_id in vm_ids {
-        let vm = match VmService::get_vm(&state.libvirt, &vm_id) {
+        let vm = match VmService::get_vm(&libvirt, &vm_id) {
             Ok(v) => v,
             Err(e) => {
                 results.push(BatchOperationResult {
@@ -909,7 +1040,7 @@ pub async fn batch_reboot_vms(
 
         let vm_name = vm.name.clone();
 
-        let result = match VmService::reboot_vm(&state.libvirt, &vm_id) {
+        let result = match VmService::reboot_vm(&libvirt, &vm_id) {
             Ok(_) => {
                 let _ = app.emit("vm-rebooted", serde_json::json!({
                     "vmId": vm_id.clone(),
@@ -945,10 +1076,13 @@ pub async fn mount_iso(
     state: State<'_, AppState>,
     vm_id: String,
     iso_path: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("mount_iso command called for VM: {} with ISO: {}", vm_id, iso_path);
 
-    VmService::mount_cd_iso(&state.libvirt, &vm_id, &iso_path)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::mount_cd_iso(&libvirt, &vm_id, &iso_path)
         .map_err(|e| e.to_string())
 }
 
@@ -956,18 +1090,21 @@ pub async fn mount_iso(
 pub async fn mount_guest_agent_iso(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("mount_guest_agent_iso command called for VM: {}", vm_id);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     // First, try to add the KVM Manager agent channel if it doesn't exist
     // This is a best-effort attempt - the VM might need to be stopped first
-    match VmService::attach_channel(&state.libvirt, &vm_id, "kvmmanager-agent") {
+    match VmService::attach_channel(&libvirt, &vm_id, "kvmmanager-agent") {
         Ok(_) => tracing::info!("Added KVM Manager agent channel to VM {}", vm_id),
         Err(e) => tracing::debug!("Could not add agent channel (may already exist): {}", e),
     }
 
     // Mount the ISO
-    VmService::mount_cd_iso(&state.libvirt, &vm_id, "/var/lib/libvirt/images/kvmmanager-guest-agent.iso")
+    VmService::mount_cd_iso(&libvirt, &vm_id, "/var/lib/libvirt/images/kvmmanager-guest-agent.iso")
         .map_err(|e| e.to_string())
 }
 
@@ -976,10 +1113,13 @@ pub async fn mount_guest_agent_iso(
 pub async fn eject_cdrom(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("eject_cdrom command called for VM: {}", vm_id);
 
-    VmService::eject_cd(&state.libvirt, &vm_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::eject_cd(&libvirt, &vm_id)
         .map_err(|e| e.to_string())
 }
 
@@ -989,10 +1129,13 @@ pub async fn update_vm_boot_order(
     state: State<'_, AppState>,
     vm_id: String,
     boot_order: Vec<String>,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("update_vm_boot_order command called for VM: {} with order: {:?}", vm_id, boot_order);
 
-    VmService::update_boot_order(&state.libvirt, &vm_id, boot_order)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::update_boot_order(&libvirt, &vm_id, boot_order)
         .map_err(|e| e.to_string())
 }
 
@@ -1003,10 +1146,13 @@ pub async fn rename_vm(
     state: State<'_, AppState>,
     vm_id: String,
     new_name: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("rename_vm command called for VM: {} to new name: {}", vm_id, new_name);
 
-    VmService::rename_vm(&state.libvirt, &vm_id, &new_name)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::rename_vm(&libvirt, &vm_id, &new_name)
         .map_err(|e| e.to_string())?;
 
     // Emit event
@@ -1024,10 +1170,13 @@ pub async fn rename_vm(
 pub async fn get_vm_autostart(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<bool, String> {
     tracing::debug!("get_vm_autostart command called for VM: {}", vm_id);
 
-    VmService::get_vm_autostart(&state.libvirt, &vm_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::get_vm_autostart(&libvirt, &vm_id)
         .map_err(|e| e.to_string())
 }
 
@@ -1037,10 +1186,13 @@ pub async fn set_vm_autostart(
     state: State<'_, AppState>,
     vm_id: String,
     enable: bool,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("set_vm_autostart command called for VM: {} to {}", vm_id, enable);
 
-    VmService::set_vm_autostart(&state.libvirt, &vm_id, enable)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::set_vm_autostart(&libvirt, &vm_id, enable)
         .map_err(|e| e.to_string())
 }
 
@@ -1052,10 +1204,13 @@ pub async fn attach_interface(
     network: String,
     model: String,
     mac_address: Option<String>,
+    connection_id: Option<String>,
 ) -> Result<String, String> {
     tracing::info!("attach_interface command called for VM: {} on network: {}", vm_id, network);
 
-    VmService::attach_interface(&state.libvirt, &vm_id, &network, &model, mac_address.as_deref())
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_interface(&libvirt, &vm_id, &network, &model, mac_address.as_deref())
         .map_err(|e| e.to_string())
 }
 
@@ -1072,11 +1227,14 @@ pub async fn attach_interface_advanced(
     vlan_id: Option<u16>,
     portgroup: Option<String>,
     mtu: Option<u32>,
+    connection_id: Option<String>,
 ) -> Result<String, String> {
     tracing::info!("attach_interface_advanced command called for VM: {} type: {} source: {}", vm_id, interface_type, source);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     VmService::attach_interface_advanced(
-        &state.libvirt,
+        &libvirt,
         &vm_id,
         &interface_type,
         &source,
@@ -1103,10 +1261,13 @@ pub async fn detach_interface(
     state: State<'_, AppState>,
     vm_id: String,
     mac_address: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("detach_interface command called for VM: {} MAC: {}", vm_id, mac_address);
 
-    VmService::detach_interface(&state.libvirt, &vm_id, &mac_address)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::detach_interface(&libvirt, &vm_id, &mac_address)
         .map_err(|e| e.to_string())
 }
 
@@ -1116,10 +1277,13 @@ pub async fn attach_sound(
     state: State<'_, AppState>,
     vm_id: String,
     model: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_sound command called for VM: {} model: {}", vm_id, model);
 
-    VmService::attach_sound(&state.libvirt, &vm_id, &model)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_sound(&libvirt, &vm_id, &model)
         .map_err(|e| e.to_string())
 }
 
@@ -1128,10 +1292,13 @@ pub async fn attach_sound(
 pub async fn detach_sound(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("detach_sound command called for VM: {}", vm_id);
 
-    VmService::detach_sound(&state.libvirt, &vm_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::detach_sound(&libvirt, &vm_id)
         .map_err(|e| e.to_string())
 }
 
@@ -1142,10 +1309,13 @@ pub async fn attach_input(
     vm_id: String,
     device_type: String,
     bus: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_input command called for VM: {} type: {} bus: {}", vm_id, device_type, bus);
 
-    VmService::attach_input(&state.libvirt, &vm_id, &device_type, &bus)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_input(&libvirt, &vm_id, &device_type, &bus)
         .map_err(|e| e.to_string())
 }
 
@@ -1155,10 +1325,13 @@ pub async fn attach_rng(
     state: State<'_, AppState>,
     vm_id: String,
     backend: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_rng command called for VM: {} backend: {}", vm_id, backend);
 
-    VmService::attach_rng(&state.libvirt, &vm_id, &backend)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_rng(&libvirt, &vm_id, &backend)
         .map_err(|e| e.to_string())
 }
 
@@ -1169,10 +1342,13 @@ pub async fn attach_watchdog(
     vm_id: String,
     model: String,
     action: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_watchdog command called for VM: {} model: {} action: {}", vm_id, model, action);
 
-    VmService::attach_watchdog(&state.libvirt, &vm_id, &model, &action)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_watchdog(&libvirt, &vm_id, &model, &action)
         .map_err(|e| e.to_string())
 }
 
@@ -1182,10 +1358,13 @@ pub async fn attach_channel(
     state: State<'_, AppState>,
     vm_id: String,
     channel_type: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_channel command called for VM: {} type: {}", vm_id, channel_type);
 
-    VmService::attach_channel(&state.libvirt, &vm_id, &channel_type)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_channel(&libvirt, &vm_id, &channel_type)
         .map_err(|e| e.to_string())
 }
 
@@ -1198,11 +1377,14 @@ pub async fn attach_filesystem(
     target_mount: String,
     fs_type: String,
     readonly: bool,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_filesystem command called for VM: {} path: {} -> {}",
         vm_id, source_path, target_mount);
 
-    VmService::attach_filesystem(&state.libvirt, &vm_id, &source_path, &target_mount, &fs_type, readonly)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_filesystem(&libvirt, &vm_id, &source_path, &target_mount, &fs_type, readonly)
         .map_err(|e| e.to_string())
 }
 
@@ -1214,11 +1396,14 @@ pub async fn attach_graphics(
     graphics_type: String,
     listen_address: Option<String>,
     port: Option<i32>,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_graphics command called for VM: {} type: {}", vm_id, graphics_type);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     VmService::attach_graphics(
-        &state.libvirt,
+        &libvirt,
         &vm_id,
         &graphics_type,
         listen_address.as_deref(),
@@ -1236,11 +1421,14 @@ pub async fn attach_video(
     vram: Option<u32>,
     heads: Option<u32>,
     acceleration_3d: Option<bool>,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_video command called for VM: {} model: {}", vm_id, model);
 
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
     VmService::attach_video(
-        &state.libvirt,
+        &libvirt,
         &vm_id,
         &model,
         vram,
@@ -1256,10 +1444,13 @@ pub async fn set_vm_vcpus(
     state: State<'_, AppState>,
     vm_id: String,
     vcpus: u32,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("set_vm_vcpus command called for VM: {} vcpus: {}", vm_id, vcpus);
 
-    VmService::set_vcpus(&state.libvirt, &vm_id, vcpus)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::set_vcpus(&libvirt, &vm_id, vcpus)
         .map_err(|e| e.to_string())
 }
 
@@ -1269,10 +1460,13 @@ pub async fn set_vm_memory(
     state: State<'_, AppState>,
     vm_id: String,
     memory_mb: u64,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("set_vm_memory command called for VM: {} memory_mb: {}", vm_id, memory_mb);
 
-    VmService::set_memory(&state.libvirt, &vm_id, memory_mb)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::set_memory(&libvirt, &vm_id, memory_mb)
         .map_err(|e| e.to_string())
 }
 
@@ -1284,11 +1478,14 @@ pub async fn set_vm_cpu_topology(
     sockets: u32,
     cores: u32,
     threads: u32,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("set_vm_cpu_topology command called for VM: {} topology: {}s/{}c/{}t",
         vm_id, sockets, cores, threads);
 
-    VmService::set_cpu_topology(&state.libvirt, &vm_id, sockets, cores, threads)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::set_cpu_topology(&libvirt, &vm_id, sockets, cores, threads)
         .map_err(|e| e.to_string())
 }
 
@@ -1297,10 +1494,13 @@ pub async fn set_vm_cpu_topology(
 pub async fn get_cpu_model(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<CpuModelConfig, String> {
     tracing::info!("get_cpu_model command called for VM: {}", vm_id);
 
-    VmService::get_cpu_model(&state.libvirt, &vm_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::get_cpu_model(&libvirt, &vm_id)
         .map_err(|e| e.to_string())
 }
 
@@ -1311,10 +1511,13 @@ pub async fn set_cpu_model(
     vm_id: String,
     mode: String,
     model: Option<String>,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("set_cpu_model command called for VM: {} mode: {} model: {:?}", vm_id, mode, model);
 
-    VmService::set_cpu_model(&state.libvirt, &vm_id, &mode, model.as_deref())
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::set_cpu_model(&libvirt, &vm_id, &mode, model.as_deref())
         .map_err(|e| e.to_string())
 }
 
@@ -1322,10 +1525,13 @@ pub async fn set_cpu_model(
 #[tauri::command]
 pub async fn get_available_cpu_models(
     state: State<'_, AppState>,
+    connection_id: Option<String>,
 ) -> Result<Vec<String>, String> {
     tracing::info!("get_available_cpu_models command called");
 
-    VmService::get_available_cpu_models(&state.libvirt)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::get_available_cpu_models(&libvirt)
         .map_err(|e| e.to_string())
 }
 
@@ -1334,10 +1540,13 @@ pub async fn get_available_cpu_models(
 pub async fn get_cpu_pinning(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<Vec<(u32, Vec<u32>)>, String> {
     tracing::info!("get_cpu_pinning command called for VM: {}", vm_id);
 
-    VmService::get_cpu_pinning(&state.libvirt, &vm_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::get_cpu_pinning(&libvirt, &vm_id)
         .map_err(|e| e.to_string())
 }
 
@@ -1348,10 +1557,13 @@ pub async fn set_cpu_pin(
     vm_id: String,
     vcpu: u32,
     host_cpus: Vec<u32>,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("set_cpu_pin command called for VM: {} vCPU: {} -> CPUs: {:?}", vm_id, vcpu, host_cpus);
 
-    VmService::set_cpu_pin(&state.libvirt, &vm_id, vcpu, host_cpus)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::set_cpu_pin(&libvirt, &vm_id, vcpu, host_cpus)
         .map_err(|e| e.to_string())
 }
 
@@ -1361,10 +1573,13 @@ pub async fn clear_cpu_pin(
     state: State<'_, AppState>,
     vm_id: String,
     vcpu: u32,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("clear_cpu_pin command called for VM: {} vCPU: {}", vm_id, vcpu);
 
-    VmService::clear_cpu_pin(&state.libvirt, &vm_id, vcpu)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::clear_cpu_pin(&libvirt, &vm_id, vcpu)
         .map_err(|e| e.to_string())
 }
 
@@ -1375,10 +1590,13 @@ pub async fn attach_serial(
     vm_id: String,
     port_type: String,
     target_port: u32,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_serial command called for VM: {} type: {} port: {}", vm_id, port_type, target_port);
 
-    VmService::attach_serial(&state.libvirt, &vm_id, &port_type, target_port)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_serial(&libvirt, &vm_id, &port_type, target_port)
         .map_err(|e| e.to_string())
 }
 
@@ -1389,10 +1607,13 @@ pub async fn attach_console(
     vm_id: String,
     target_port: u32,
     target_type: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_console command called for VM: {} type: {} port: {}", vm_id, target_type, target_port);
 
-    VmService::attach_console(&state.libvirt, &vm_id, target_port, &target_type)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_console(&libvirt, &vm_id, target_port, &target_type)
         .map_err(|e| e.to_string())
 }
 
@@ -1403,10 +1624,13 @@ pub async fn attach_tpm(
     vm_id: String,
     model: String,
     version: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_tpm command called for VM: {} model: {} version: {}", vm_id, model, version);
 
-    VmService::attach_tpm(&state.libvirt, &vm_id, &model, &version)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_tpm(&libvirt, &vm_id, &model, &version)
         .map_err(|e| e.to_string())
 }
 
@@ -1416,10 +1640,13 @@ pub async fn attach_usb_controller(
     state: State<'_, AppState>,
     vm_id: String,
     model: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_usb_controller command called for VM: {} model: {}", vm_id, model);
 
-    VmService::attach_usb_controller(&state.libvirt, &vm_id, &model)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_usb_controller(&libvirt, &vm_id, &model)
         .map_err(|e| e.to_string())
 }
 
@@ -1428,9 +1655,12 @@ pub async fn attach_usb_controller(
 pub async fn get_usb_redirection(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<UsbRedirectionInfo, String> {
     tracing::info!("get_usb_redirection command called for VM: {}", vm_id);
-    VmService::get_usb_redirection(&state.libvirt, &vm_id).map_err(|e| e.to_string())
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    VmService::get_usb_redirection(&libvirt, &vm_id).map_err(|e| e.to_string())
 }
 
 /// Attach USB redirection channels to a VM for SPICE USB passthrough
@@ -1440,10 +1670,13 @@ pub async fn attach_usb_redirection(
     state: State<'_, AppState>,
     vm_id: String,
     count: u32,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_usb_redirection command called for VM: {} count: {}", vm_id, count);
 
-    VmService::attach_usb_redirection(&state.libvirt, &vm_id, count).map_err(|e| e.to_string())?;
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_usb_redirection(&libvirt, &vm_id, count).map_err(|e| e.to_string())?;
 
     let _ = app.emit("vm-usb-redirection-updated", serde_json::json!({
         "vmId": vm_id,
@@ -1460,10 +1693,13 @@ pub async fn remove_usb_redirection(
     app: AppHandle,
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("remove_usb_redirection command called for VM: {}", vm_id);
 
-    VmService::remove_usb_redirection(&state.libvirt, &vm_id).map_err(|e| e.to_string())?;
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::remove_usb_redirection(&libvirt, &vm_id).map_err(|e| e.to_string())?;
 
     let _ = app.emit("vm-usb-redirection-updated", serde_json::json!({
         "vmId": vm_id,
@@ -1480,10 +1716,13 @@ pub async fn attach_scsi_controller(
     state: State<'_, AppState>,
     vm_id: String,
     model: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_scsi_controller command called for VM: {} model: {}", vm_id, model);
 
-    VmService::attach_scsi_controller(&state.libvirt, &vm_id, &model)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_scsi_controller(&libvirt, &vm_id, &model)
         .map_err(|e| e.to_string())
 }
 
@@ -1494,10 +1733,13 @@ pub async fn attach_panic_notifier(
     state: State<'_, AppState>,
     vm_id: String,
     model: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_panic_notifier command called for VM: {} model: {}", vm_id, model);
 
-    VmService::attach_panic_notifier(&state.libvirt, &vm_id, &model)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_panic_notifier(&libvirt, &vm_id, &model)
         .map_err(|e| e.to_string())
 }
 
@@ -1508,10 +1750,13 @@ pub async fn attach_vsock(
     state: State<'_, AppState>,
     vm_id: String,
     cid: u32,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_vsock command called for VM: {} CID: {}", vm_id, cid);
 
-    VmService::attach_vsock(&state.libvirt, &vm_id, cid)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_vsock(&libvirt, &vm_id, cid)
         .map_err(|e| e.to_string())
 }
 
@@ -1521,10 +1766,13 @@ pub async fn attach_parallel(
     state: State<'_, AppState>,
     vm_id: String,
     target_port: u32,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_parallel command called for VM: {} port: {}", vm_id, target_port);
 
-    VmService::attach_parallel(&state.libvirt, &vm_id, target_port)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_parallel(&libvirt, &vm_id, target_port)
         .map_err(|e| e.to_string())
 }
 
@@ -1534,10 +1782,13 @@ pub async fn attach_smartcard(
     state: State<'_, AppState>,
     vm_id: String,
     mode: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_smartcard command called for VM: {} mode: {}", vm_id, mode);
 
-    VmService::attach_smartcard(&state.libvirt, &vm_id, &mode)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_smartcard(&libvirt, &vm_id, &mode)
         .map_err(|e| e.to_string())
 }
 
@@ -1545,10 +1796,13 @@ pub async fn attach_smartcard(
 #[tauri::command]
 pub async fn get_host_numa_topology(
     state: State<'_, AppState>,
+    connection_id: Option<String>,
 ) -> Result<Vec<crate::models::vm::HostNumaNode>, String> {
     tracing::info!("get_host_numa_topology command called");
 
-    VmService::get_host_numa_topology(&state.libvirt)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::get_host_numa_topology(&libvirt)
         .map_err(|e| e.to_string())
 }
 
@@ -1557,10 +1811,13 @@ pub async fn get_host_numa_topology(
 pub async fn get_vm_numa_config(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<Option<crate::models::vm::VmNumaConfig>, String> {
     tracing::info!("get_vm_numa_config command called for VM: {}", vm_id);
 
-    VmService::get_vm_numa_config(&state.libvirt, &vm_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::get_vm_numa_config(&libvirt, &vm_id)
         .map_err(|e| e.to_string())
 }
 
@@ -1570,10 +1827,13 @@ pub async fn set_vm_numa_config(
     state: State<'_, AppState>,
     vm_id: String,
     config: crate::models::vm::VmNumaConfig,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("set_vm_numa_config command called for VM: {}", vm_id);
 
-    VmService::set_vm_numa_config(&state.libvirt, &vm_id, config)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::set_vm_numa_config(&libvirt, &vm_id, config)
         .map_err(|e| e.to_string())
 }
 
@@ -1582,14 +1842,19 @@ pub async fn set_vm_numa_config(
 pub async fn clear_vm_numa_config(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("clear_vm_numa_config command called for VM: {}", vm_id);
 
-    VmService::clear_vm_numa_config(&state.libvirt, &vm_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::clear_vm_numa_config(&libvirt, &vm_id)
         .map_err(|e| e.to_string())
 }
 
 /// Migrate a VM to another host
+///
+/// Runs as a background job and returns its ID; cancelling the job aborts the migration.
 #[tauri::command]
 pub async fn migrate_vm(
     state: State<'_, AppState>,
@@ -1597,11 +1862,17 @@ pub async fn migrate_vm(
     dest_uri: String,
     live: bool,
     unsafe_migration: bool,
-) -> Result<(), String> {
+    connection_id: Option<String>,
+) -> Result<String, String> {
     tracing::info!("migrate_vm command called for VM: {} to {}", vm_id, dest_uri);
 
-    VmService::migrate_vm(&state.libvirt, &vm_id, &dest_uri, live, unsafe_migration)
-        .map_err(|e| e.to_string())
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    let description = format!("Migrate VM to {}", dest_uri);
+    let target = vm_id.clone();
+    Ok(state.jobs.start(crate::models::job::JobKind::MigrateVm, description, target, connection_id, move |job| {
+        VmService::migrate_vm(&libvirt, &vm_id, &dest_uri, live, unsafe_migration, Some(job))
+    }))
 }
 
 /// Get migration info for a VM
@@ -1609,10 +1880,13 @@ pub async fn migrate_vm(
 pub async fn get_migration_info(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<crate::services::vm_service::MigrationInfo, String> {
     tracing::info!("get_migration_info command called for VM: {}", vm_id);
 
-    VmService::get_migration_info(&state.libvirt, &vm_id)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::get_migration_info(&libvirt, &vm_id)
         .map_err(|e| e.to_string())
 }
 
@@ -1631,10 +1905,13 @@ pub async fn attach_evdev(
     vm_id: String,
     device_path: String,
     grab_all: bool,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("attach_evdev command called for VM: {} device: {}", vm_id, device_path);
 
-    VmService::attach_evdev(&state.libvirt, &vm_id, &device_path, grab_all)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::attach_evdev(&libvirt, &vm_id, &device_path, grab_all)
         .map_err(|e| e.to_string())?;
 
     let _ = app.emit("vm-evdev-updated", serde_json::json!({
@@ -1652,9 +1929,12 @@ pub async fn attach_evdev(
 pub async fn get_vm_evdev_devices(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<Vec<String>, String> {
     tracing::info!("get_vm_evdev_devices command called for VM: {}", vm_id);
-    VmService::get_vm_evdev_devices(&state.libvirt, &vm_id).map_err(|e| e.to_string())
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    VmService::get_vm_evdev_devices(&libvirt, &vm_id).map_err(|e| e.to_string())
 }
 
 /// Remove an evdev device from a VM
@@ -1664,10 +1944,13 @@ pub async fn detach_evdev(
     state: State<'_, AppState>,
     vm_id: String,
     device_path: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("detach_evdev command called for VM: {} device: {}", vm_id, device_path);
 
-    VmService::detach_evdev(&state.libvirt, &vm_id, &device_path)
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+
+    VmService::detach_evdev(&libvirt, &vm_id, &device_path)
         .map_err(|e| e.to_string())?;
 
     let _ = app.emit("vm-evdev-updated", serde_json::json!({
@@ -1685,9 +1968,12 @@ pub async fn detach_evdev(
 pub async fn check_migration_compatibility(
     state: State<'_, AppState>,
     vm_id: String,
+    connection_id: Option<String>,
 ) -> Result<(bool, Vec<String>), String> {
     tracing::debug!("check_migration_compatibility command called for VM: {}", vm_id);
-    VmService::check_migration_compatibility(&state.libvirt, &vm_id).map_err(|e| e.to_string())
+
+    let libvirt = state.libvirt(connection_id.as_deref()).map_err(|e| e.to_string())?;
+    VmService::check_migration_compatibility(&libvirt, &vm_id).map_err(|e| e.to_string())
 }
 
 /// Get list of available migration targets (saved remote connections)
@@ -8,6 +8,14 @@ lazy_static::lazy_static! {
     static ref OPEN_WINDOWS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
 }
 
+/// Pin a window route to the connection its VM lives on
+fn with_connection_query(path: String, connection_id: Option<&str>) -> String {
+    match connection_id {
+        Some(id) => format!("{}?connectionId={}", path, id),
+        None => path,
+    }
+}
+
 /// Opens a VM details window for the specified VM
 /// If a window is already open for this VM, it will be focused instead
 #[tauri::command]
@@ -15,6 +23,7 @@ pub async fn open_vm_details_window(
     app: AppHandle,
     vm_id: String,
     vm_name: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     let window_label = format!("vm-details-{}", vm_id);
 
@@ -36,7 +45,7 @@ pub async fn open_vm_details_window(
 
     // Create new window
     let window_title = format!("{} - VM Details", vm_name);
-    let url = format!("/vms/{}", vm_id);
+    let url = with_connection_query(format!("/vms/{}", vm_id), connection_id.as_deref());
 
     let mut builder = WebviewWindowBuilder::new(
         &app,
@@ -78,6 +87,7 @@ pub async fn open_console_window(
     app: AppHandle,
     vm_id: String,
     vm_name: String,
+    connection_id: Option<String>,
 ) -> Result<(), String> {
     let window_label = format!("console-{}", vm_id);
 
@@ -99,7 +109,7 @@ pub async fn open_console_window(
 
     // Create new window
     let window_title = format!("{} - Console", vm_name);
-    let url = format!("/console/{}", vm_id);
+    let url = with_connection_query(format!("/console/{}", vm_id), connection_id.as_deref());
 
     let mut builder = WebviewWindowBuilder::new(
         &app,
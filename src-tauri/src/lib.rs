@@ -23,9 +23,7 @@ pub fn run() {
         Ok(state) => state,
         Err(e) => {
             tracing::error!("Failed to initialize AppState: {}", e);
-            eprintln!("Failed to connect to libvirt: {}", e);
-            eprintln!("Please ensure libvirtd is running and you have proper permissions.");
-            eprintln!("Try: sudo systemctl start libvirtd");
+            eprintln!("Failed to initialize KVM Manager: {}", e);
             std::process::exit(1);
         }
     };
@@ -33,6 +31,7 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .manage(app_state)
         .setup(|app| {
             // Note: Native menu bar disabled for cleaner look
@@ -40,6 +39,7 @@ pub fn run() {
 
             // Start background tasks after Tauri runtime is initialized
             let app_state = app.state::<AppState>();
+            app_state.events.set_app_handle(app.handle().clone());
             app_state.start_background_tasks();
             Ok(())
         })
@@ -166,9 +166,16 @@ pub fn run() {
             commands::storage::import_ova,
             // Snapshot Commands
             commands::snapshot::get_snapshots,
+            commands::snapshot::get_snapshot_tree,
+            commands::snapshot::diff_snapshot_config,
             commands::snapshot::create_snapshot,
             commands::snapshot::delete_snapshot,
             commands::snapshot::revert_snapshot,
+            commands::snapshot::create_snapshot_retention_policy,
+            commands::snapshot::list_snapshot_retention_policies,
+            commands::snapshot::update_snapshot_retention_policy_status,
+            commands::snapshot::delete_snapshot_retention_policy,
+            commands::snapshot::run_snapshot_retention_policy,
             // System Commands
             commands::system::get_host_info,
             commands::system::get_connection_status,
@@ -178,17 +185,26 @@ pub fn run() {
             // Connection Commands
             commands::connection::get_saved_connections,
             commands::connection::get_active_connection,
+            commands::connection::get_connected_connections,
+            commands::connection::get_connection_states,
             commands::connection::connect_to,
             commands::connection::disconnect_from,
             commands::connection::add_connection,
             commands::connection::update_connection,
             commands::connection::remove_connection,
             commands::connection::test_connection,
+            commands::connection::set_connection_credentials,
+            commands::connection::get_connection_credentials,
+            commands::connection::get_connections_needing_credentials,
+            commands::connection::clear_connection_credentials,
             // Metrics Commands
             commands::metrics::store_vm_metrics,
             commands::metrics::get_historical_metrics,
             commands::metrics::cleanup_old_metrics,
             commands::metrics::get_metrics_count,
+            commands::metrics::get_host_metrics,
+            commands::metrics::get_host_metrics_history,
+            commands::metrics::get_pool_metrics_history,
             // Template Commands
             commands::template::create_template,
             commands::template::list_templates,
@@ -202,6 +218,7 @@ pub fn run() {
             commands::scheduler::update_schedule_status,
             commands::scheduler::delete_schedule,
             commands::scheduler::get_vm_schedules,
+            commands::scheduler::get_schedule_runs,
             // Alert Commands
             commands::alert::create_alert,
             commands::alert::list_alerts,
@@ -210,6 +227,22 @@ pub fn run() {
             commands::alert::delete_alert,
             commands::alert::get_vm_alerts,
             commands::alert::check_alert_threshold,
+            commands::alert::set_alert_channels,
+            commands::alert::create_alert_rule,
+            commands::alert::list_alert_rules,
+            commands::alert::update_alert_rule_status,
+            commands::alert::delete_alert_rule,
+            commands::alert::list_active_alerts,
+            commands::alert::acknowledge_alert,
+            commands::alert::resolve_alert,
+            commands::alert::query_alert_history,
+            // Notification Channel Commands
+            commands::notification::list_notification_channels,
+            commands::notification::create_notification_channel,
+            commands::notification::update_notification_channel,
+            commands::notification::delete_notification_channel,
+            commands::notification::test_notification_channel,
+            commands::notification::get_notification_deliveries,
             // Backup Commands
             commands::backup::create_backup_config,
             commands::backup::list_backup_configs,
@@ -217,7 +250,20 @@ pub fn run() {
             commands::backup::update_backup_status,
             commands::backup::delete_backup_config,
             commands::backup::get_vm_backup_configs,
-            commands::backup::record_backup,
+            commands::backup::run_backup_now,
+            commands::backup::list_backup_runs,
+            commands::backup::list_backup_chains,
+            commands::backup::restore_backup,
+            commands::backup::restore_backup_disk,
+            commands::backup::attach_backup_disk,
+            commands::backup::verify_backup,
+            commands::backup::sync_backup_target,
+            commands::backup::import_backup_catalog,
+            commands::backup::create_backup_target,
+            commands::backup::list_backup_targets,
+            commands::backup::delete_backup_target,
+            commands::backup::check_backup_target,
+            commands::backup::get_backup_target_usage,
             // Optimization Commands
             commands::optimization::analyze_vm_performance,
             commands::optimization::analyze_all_vms,
@@ -240,6 +286,9 @@ pub fn run() {
             commands::guest_agent::get_guest_users,
             commands::guest_agent::get_guest_timezone,
             commands::guest_agent::get_guest_full_info,
+            commands::guest_agent::guest_fs_freeze,
+            commands::guest_agent::guest_fs_thaw,
+            commands::guest_agent::get_guest_fs_freeze_status,
             // PCI Device Commands
             commands::pci::list_pci_devices,
             commands::pci::check_iommu_status,
@@ -282,6 +331,11 @@ pub fn run() {
             commands::sriov::configure_sriov_vf,
             commands::sriov::attach_sriov_vf,
             commands::sriov::detach_sriov_vf,
+            // Background Job Commands
+            commands::job::list_jobs,
+            commands::job::get_job,
+            commands::job::cancel_job,
+            commands::job::clear_finished_jobs,
             // Window State Persistence Commands
             window_state::save_window_state,
             window_state::load_window_state,
@@ -0,0 +1,1686 @@
+//! Typed view over libvirt domain XML.
+//!
+//! `DomainXml` wraps a parsed element tree and exposes typed getters and
+//! setters for the sections the app edits (devices, cpu, numa, memory
+//! backing, os/boot and our metadata). Setters only touch the attributes and
+//! children they own, so elements the app does not model are written back
+//! untouched when the domain is redefined.
+
+use virt::domain::Domain;
+use crate::utils::error::{AppError, map_libvirt_error};
+use crate::utils::xml::XmlElement;
+
+/// Namespace of the metadata element the app stores under `<metadata>`
+pub const KVM_METADATA_NS: &str = "http://kvm-manager.local/metadata";
+/// Prefix used for the app metadata namespace
+pub const KVM_METADATA_PREFIX: &str = "kvm";
+
+/// A typed section of domain XML that can be read from and written back to an element
+pub trait XmlModel: Sized {
+    /// Element name this model maps to
+    const TAG: &'static str;
+
+    /// Read the model from an element
+    fn from_element(element: &XmlElement) -> Self;
+
+    /// Write the model into an existing element, leaving unmodelled content intact
+    fn apply_to(&self, element: &mut XmlElement);
+
+    /// Build a fresh element from the model
+    fn to_element(&self) -> XmlElement {
+        let mut element = XmlElement::new(Self::TAG);
+        self.apply_to(&mut element);
+        element
+    }
+
+    /// Serialize the model as a standalone XML snippet (e.g. for attach_device)
+    fn to_xml(&self) -> String {
+        self.to_element().to_xml()
+    }
+}
+
+/// Sync a list of models into the `T::TAG` children of `parent`.
+///
+/// Existing children matched by `same` are updated in place, unmatched ones are
+/// removed and new items are appended, so per-item unknown content survives.
+fn apply_list<T: XmlModel>(parent: &mut XmlElement, items: &[T], same: impl Fn(&T, &XmlElement) -> bool) {
+    let mut applied = vec![false; items.len()];
+    parent.retain_elements(|element| {
+        element.name != T::TAG || items.iter().any(|item| same(item, element))
+    });
+    for element in parent.children_named_mut(T::TAG) {
+        if let Some(index) = items.iter().position(|item| same(item, element)) {
+            items[index].apply_to(element);
+            applied[index] = true;
+        }
+    }
+    for (item, done) in items.iter().zip(applied) {
+        if !done {
+            parent.push_child(item.to_element());
+        }
+    }
+}
+
+/// Convert a libvirt memory value with a unit attribute into KiB
+pub fn to_kib(value: u64, unit: Option<&str>) -> u64 {
+    match unit.unwrap_or("KiB").to_ascii_lowercase().as_str() {
+        "b" | "bytes" => value / 1024,
+        "k" | "kib" => value,
+        "kb" => value * 1000 / 1024,
+        "m" | "mib" => value * 1024,
+        "mb" => value * 1_000_000 / 1024,
+        "g" | "gib" => value * 1024 * 1024,
+        "gb" => value * 1_000_000_000 / 1024,
+        "t" | "tib" => value * 1024 * 1024 * 1024,
+        "tb" => value * 1_000_000_000_000 / 1024,
+        _ => value,
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "yes" } else { "no" }
+}
+
+/// A libvirt domain definition
+#[derive(Debug, Clone)]
+pub struct DomainXml {
+    root: XmlElement,
+}
+
+impl DomainXml {
+    /// Start an empty definition for a new domain
+    pub fn new(domain_type: &str, name: &str) -> Self {
+        let root = XmlElement::new("domain")
+            .with_attr("type", domain_type)
+            .with_child(XmlElement::new("name").with_text(name));
+        Self { root }
+    }
+
+    /// Parse a domain definition
+    pub fn parse(xml: &str) -> Result<Self, AppError> {
+        let root = XmlElement::parse(xml)?;
+        if root.name != "domain" {
+            return Err(AppError::XmlError(format!("Expected <domain> root element, found <{}>", root.name)));
+        }
+        Ok(Self { root })
+    }
+
+    /// Read the definition of a domain
+    pub fn from_domain(domain: &Domain) -> Result<Self, AppError> {
+        Self::from_domain_flags(domain, 0)
+    }
+
+    /// Read the definition of a domain with `virDomainGetXMLDesc` flags
+    pub fn from_domain_flags(domain: &Domain, flags: u32) -> Result<Self, AppError> {
+        let xml = domain.get_xml_desc(flags).map_err(map_libvirt_error)?;
+        Self::parse(&xml)
+    }
+
+    /// Serialize the definition
+    pub fn to_xml(&self) -> String {
+        self.root.to_xml()
+    }
+
+    /// The underlying element tree, for sections without a typed model
+    pub fn root(&self) -> &XmlElement {
+        &self.root
+    }
+
+    /// The underlying element tree, mutably
+    pub fn root_mut(&mut self) -> &mut XmlElement {
+        &mut self.root
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.root.child_text("name")
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        self.root.child_or_insert("name").set_text(name);
+    }
+
+    pub fn uuid(&self) -> Option<String> {
+        self.root.child_text("uuid")
+    }
+
+    /// Set or clear the UUID (clearing lets libvirt generate a new one)
+    pub fn set_uuid(&mut self, uuid: Option<&str>) {
+        self.root.set_child_text("uuid", uuid);
+    }
+
+    /// Maximum memory in KiB
+    pub fn memory_kib(&self) -> Option<u64> {
+        let memory = self.root.child("memory")?;
+        memory.text().parse().ok().map(|value| to_kib(value, memory.attr("unit")))
+    }
+
+    pub fn set_memory_kib(&mut self, kib: u64) {
+        let memory = self.root.child_or_insert("memory");
+        memory.set_attr("unit", "KiB");
+        memory.set_text(kib);
+    }
+
+    pub fn set_current_memory_kib(&mut self, kib: u64) {
+        let memory = self.root.child_or_insert("currentMemory");
+        memory.set_attr("unit", "KiB");
+        memory.set_text(kib);
+    }
+
+    /// Maximum vCPU count
+    pub fn vcpus(&self) -> Option<u32> {
+        self.root.child("vcpu").and_then(|vcpu| vcpu.text().parse().ok())
+    }
+
+    /// Set the vCPU count, dropping a `current` value that would exceed it
+    pub fn set_vcpus(&mut self, count: u32) {
+        let vcpu = self.root.child_or_insert("vcpu");
+        if vcpu.attr("placement").is_none() {
+            vcpu.set_attr("placement", "static");
+        }
+        if vcpu.attr_parse::<u32>("current").is_some_and(|current| current > count) {
+            vcpu.remove_attr("current");
+        }
+        vcpu.set_text(count);
+    }
+
+    /// `<os>` section
+    pub fn os(&self) -> Os {
+        self.root.child("os").map(Os::from_element).unwrap_or_default()
+    }
+
+    pub fn set_os(&mut self, os: &Os) {
+        os.apply_to(self.root.child_or_insert("os"));
+    }
+
+    /// Names of the enabled `<features>` flags (acpi, apic, ...)
+    pub fn features(&self) -> Vec<String> {
+        self.root.child("features")
+            .map(|features| features.elements().map(|feature| feature.name.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Enable a `<features>` flag if it is not already present
+    pub fn enable_feature(&mut self, name: &str) {
+        let features = self.root.child_or_insert("features");
+        if features.child(name).is_none() {
+            features.push_child(XmlElement::new(name));
+        }
+    }
+
+    /// `<cpu>` section, defaulted when absent
+    pub fn cpu(&self) -> Cpu {
+        self.root.child("cpu").map(Cpu::from_element).unwrap_or_default()
+    }
+
+    pub fn set_cpu(&mut self, cpu: &Cpu) {
+        cpu.apply_to(self.root.child_or_insert("cpu"));
+    }
+
+    /// `<cputune>` section, defaulted when absent
+    pub fn cputune(&self) -> CpuTune {
+        self.root.child("cputune").map(CpuTune::from_element).unwrap_or_default()
+    }
+
+    /// Write `<cputune>`, removing it when nothing is left inside
+    pub fn set_cputune(&mut self, cputune: &CpuTune) {
+        let element = self.root.child_or_insert("cputune");
+        cputune.apply_to(element);
+        if element.elements().next().is_none() {
+            self.root.remove_children("cputune");
+        }
+    }
+
+    /// `<numatune>` section
+    pub fn numatune(&self) -> Option<NumaTune> {
+        self.root.child("numatune").map(NumaTune::from_element)
+    }
+
+    pub fn set_numatune(&mut self, numatune: Option<&NumaTune>) {
+        match numatune {
+            Some(numatune) => numatune.apply_to(self.root.child_or_insert("numatune")),
+            None => self.root.remove_children("numatune"),
+        }
+    }
+
+    /// `<memoryBacking>` section
+    pub fn memory_backing(&self) -> Option<MemoryBacking> {
+        self.root.child("memoryBacking").map(MemoryBacking::from_element)
+    }
+
+    /// Write `<memoryBacking>`, removing it when nothing is left inside
+    pub fn set_memory_backing(&mut self, backing: Option<&MemoryBacking>) {
+        match backing {
+            Some(backing) => {
+                let element = self.root.child_or_insert("memoryBacking");
+                backing.apply_to(element);
+                if element.elements().next().is_none() {
+                    self.root.remove_children("memoryBacking");
+                }
+            }
+            None => self.root.remove_children("memoryBacking"),
+        }
+    }
+
+    /// Tags stored in the app metadata namespace
+    pub fn tags(&self) -> Vec<String> {
+        self.app_metadata("tags")
+            .map(|tags| tags.elements()
+                .filter(|tag| local_name(&tag.name) == "tag")
+                .map(|tag| tag.text())
+                .filter(|tag| !tag.is_empty())
+                .collect())
+            .unwrap_or_default()
+    }
+
+    /// Replace the app tags, leaving other applications' metadata alone
+    pub fn set_tags(&mut self, tags: &[String]) {
+        let tag_name = format!("{}:tag", KVM_METADATA_PREFIX);
+        let mut element = XmlElement::new(&format!("{}:tags", KVM_METADATA_PREFIX))
+            .with_attr(&format!("xmlns:{}", KVM_METADATA_PREFIX), KVM_METADATA_NS);
+        for tag in tags {
+            element.push_child(XmlElement::new(&tag_name).with_text(tag));
+        }
+        self.set_app_metadata("tags", if tags.is_empty() { None } else { Some(element) });
+    }
+
+    /// Find an element in the app metadata namespace by local name
+    pub fn app_metadata(&self, local: &str) -> Option<&XmlElement> {
+        self.root.child("metadata")?
+            .elements()
+            .find(|element| local_name(&element.name) == local && namespace_of(element) == Some(KVM_METADATA_NS))
+    }
+
+    /// Replace (or remove, for `None`) an element in the app metadata namespace.
+    /// The element must declare the namespace itself, as libvirt requires.
+    pub fn set_app_metadata(&mut self, local: &str, element: Option<XmlElement>) {
+        let metadata = self.root.child_or_insert("metadata");
+        metadata.retain_elements(|existing| {
+            !(local_name(&existing.name) == local && namespace_of(existing) == Some(KVM_METADATA_NS))
+        });
+        if let Some(element) = element {
+            metadata.push_child(element);
+        }
+        if metadata.elements().next().is_none() {
+            self.root.remove_children("metadata");
+        }
+    }
+
+    /// `<devices>` element, created on demand
+    pub fn devices_mut(&mut self) -> &mut XmlElement {
+        self.root.child_or_insert("devices")
+    }
+
+    /// All device elements with the given name (disk, interface, sound, ...)
+    pub fn devices_named<'a>(&'a self, name: &'a str) -> Vec<&'a XmlElement> {
+        self.root.child("devices")
+            .map(|devices| devices.children_named(name).collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether any device with the given name is present
+    pub fn has_device(&self, name: &str) -> bool {
+        !self.devices_named(name).is_empty()
+    }
+
+    /// Remove device elements matching the predicate
+    pub fn remove_devices(&mut self, mut remove: impl FnMut(&XmlElement) -> bool) {
+        self.devices_mut().retain_elements(|device| !remove(device));
+    }
+
+    /// Disks (including cdroms and floppies)
+    pub fn disks(&self) -> Vec<Disk> {
+        self.devices_named("disk").into_iter().map(Disk::from_element).collect()
+    }
+
+    /// Disk element with the given target device (e.g. `vda`)
+    pub fn disk_element(&self, target: &str) -> Option<&XmlElement> {
+        self.devices_named("disk").into_iter()
+            .find(|disk| disk.child("target").and_then(|t| t.attr("dev")) == Some(target))
+    }
+
+    /// Mutable disk element with the given target device
+    pub fn disk_element_mut(&mut self, target: &str) -> Option<&mut XmlElement> {
+        self.devices_mut().children_named_mut("disk")
+            .find(|disk| disk.child("target").and_then(|t| t.attr("dev")) == Some(target))
+    }
+
+    /// Network interfaces
+    pub fn interfaces(&self) -> Vec<Interface> {
+        self.devices_named("interface").into_iter().map(Interface::from_element).collect()
+    }
+
+    /// Interface element with the given MAC address (case-insensitive)
+    pub fn interface_element(&self, mac: &str) -> Option<&XmlElement> {
+        self.devices_named("interface").into_iter()
+            .find(|iface| iface.child("mac").and_then(|m| m.attr("address"))
+                .is_some_and(|address| address.eq_ignore_ascii_case(mac)))
+    }
+
+    /// Mutable interface element with the given MAC address (case-insensitive)
+    pub fn interface_element_mut(&mut self, mac: &str) -> Option<&mut XmlElement> {
+        self.devices_mut().children_named_mut("interface")
+            .find(|iface| iface.child("mac").and_then(|m| m.attr("address"))
+                .is_some_and(|address| address.eq_ignore_ascii_case(mac)))
+    }
+}
+
+/// Local part of a qualified name
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Namespace URI declared on an element for its own prefix
+fn namespace_of(element: &XmlElement) -> Option<&str> {
+    match element.name.split_once(':') {
+        Some((prefix, _)) => element.attr(&format!("xmlns:{}", prefix)),
+        None => element.attr("xmlns"),
+    }
+}
+
+/// `<os>` section: machine type, firmware, boot order and direct kernel boot
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Os {
+    pub os_type: String,
+    pub arch: Option<String>,
+    pub machine: Option<String>,
+    /// Firmware auto-selection (`efi` or `bios`)
+    pub firmware: Option<String>,
+    pub loader: Option<Loader>,
+    pub nvram: Option<Nvram>,
+    /// Boot devices in order (hd, cdrom, network, fd)
+    pub boot_devices: Vec<String>,
+    pub boot_menu: Option<BootMenu>,
+    pub kernel: Option<String>,
+    pub initrd: Option<String>,
+    pub cmdline: Option<String>,
+    pub dtb: Option<String>,
+}
+
+impl Os {
+    /// Whether direct kernel boot is configured
+    pub fn has_direct_kernel(&self) -> bool {
+        self.kernel.is_some() || self.initrd.is_some() || self.cmdline.is_some()
+    }
+}
+
+impl XmlModel for Os {
+    const TAG: &'static str = "os";
+
+    fn from_element(element: &XmlElement) -> Self {
+        let os_type = element.child("type");
+        Self {
+            os_type: os_type.map(|t| t.text()).unwrap_or_else(|| "hvm".to_string()),
+            arch: os_type.and_then(|t| t.attr("arch")).map(String::from),
+            machine: os_type.and_then(|t| t.attr("machine")).map(String::from),
+            firmware: element.attr("firmware").map(String::from),
+            loader: element.child("loader").map(Loader::from_element),
+            nvram: element.child("nvram").map(Nvram::from_element),
+            boot_devices: element.children_named("boot")
+                .filter_map(|boot| boot.attr("dev").map(String::from))
+                .collect(),
+            boot_menu: element.child("bootmenu").map(BootMenu::from_element),
+            kernel: element.child_text("kernel"),
+            initrd: element.child_text("initrd"),
+            cmdline: element.child_text("cmdline"),
+            dtb: element.child_text("dtb"),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_opt_attr("firmware", self.firmware.as_ref());
+
+        let os_type = element.child_or_insert("type");
+        os_type.set_opt_attr("arch", self.arch.as_ref());
+        os_type.set_opt_attr("machine", self.machine.as_ref());
+        os_type.set_text(&self.os_type);
+
+        match &self.loader {
+            Some(loader) => loader.apply_to(element.child_or_insert("loader")),
+            None => element.remove_children("loader"),
+        }
+        match &self.nvram {
+            Some(nvram) => nvram.apply_to(element.child_or_insert("nvram")),
+            None => element.remove_children("nvram"),
+        }
+
+        element.set_child_text("kernel", self.kernel.as_ref());
+        element.set_child_text("initrd", self.initrd.as_ref());
+        element.set_child_text("cmdline", self.cmdline.as_ref());
+        element.set_child_text("dtb", self.dtb.as_ref());
+
+        element.remove_children("boot");
+        for dev in &self.boot_devices {
+            element.push_child(XmlElement::new("boot").with_attr("dev", dev));
+        }
+        match &self.boot_menu {
+            Some(menu) => menu.apply_to(element.child_or_insert("bootmenu")),
+            None => element.remove_children("bootmenu"),
+        }
+    }
+}
+
+/// Firmware loader (e.g. OVMF code image)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Loader {
+    pub path: String,
+    pub readonly: bool,
+    pub secure: bool,
+    /// `pflash` or `rom`
+    pub loader_type: Option<String>,
+}
+
+impl XmlModel for Loader {
+    const TAG: &'static str = "loader";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            path: element.text(),
+            readonly: element.attr("readonly") == Some("yes"),
+            secure: element.attr("secure") == Some("yes"),
+            loader_type: element.attr("type").map(String::from),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("readonly", yes_no(self.readonly));
+        element.set_opt_attr("secure", self.secure.then_some("yes"));
+        element.set_opt_attr("type", self.loader_type.as_ref());
+        element.set_text(&self.path);
+    }
+}
+
+/// UEFI variable store
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Nvram {
+    pub path: Option<String>,
+    pub template: Option<String>,
+}
+
+impl XmlModel for Nvram {
+    const TAG: &'static str = "nvram";
+
+    fn from_element(element: &XmlElement) -> Self {
+        let path = element.text();
+        Self {
+            path: if path.is_empty() { None } else { Some(path) },
+            template: element.attr("template").map(String::from),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_opt_attr("template", self.template.as_ref());
+        match &self.path {
+            Some(path) => element.set_text(path),
+            None => element.children.clear(),
+        }
+    }
+}
+
+/// Interactive boot menu
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BootMenu {
+    pub enable: bool,
+    /// Timeout in milliseconds
+    pub timeout: Option<u32>,
+}
+
+impl XmlModel for BootMenu {
+    const TAG: &'static str = "bootmenu";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            enable: element.attr("enable") == Some("yes"),
+            timeout: element.attr_parse("timeout"),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("enable", yes_no(self.enable));
+        element.set_opt_attr("timeout", self.timeout);
+    }
+}
+
+/// `<cpu>` section: mode, model, topology, guest NUMA cells and features
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cpu {
+    /// host-passthrough, host-model, custom or maximum
+    pub mode: Option<String>,
+    pub match_mode: Option<String>,
+    pub check: Option<String>,
+    pub model: Option<CpuModel>,
+    pub topology: Option<CpuTopology>,
+    pub features: Vec<CpuFeature>,
+    /// Guest NUMA cells
+    pub numa_cells: Vec<NumaCell>,
+}
+
+impl XmlModel for Cpu {
+    const TAG: &'static str = "cpu";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            mode: element.attr("mode").map(String::from),
+            match_mode: element.attr("match").map(String::from),
+            check: element.attr("check").map(String::from),
+            model: element.child("model").map(CpuModel::from_element),
+            topology: element.child("topology").map(CpuTopology::from_element),
+            features: element.children_named("feature").map(CpuFeature::from_element).collect(),
+            numa_cells: element.child("numa")
+                .map(|numa| numa.children_named("cell").map(NumaCell::from_element).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_opt_attr("mode", self.mode.as_ref());
+        element.set_opt_attr("match", self.match_mode.as_ref());
+        element.set_opt_attr("check", self.check.as_ref());
+
+        match &self.model {
+            Some(model) => model.apply_to(element.child_or_insert("model")),
+            None => element.remove_children("model"),
+        }
+        match &self.topology {
+            Some(topology) => topology.apply_to(element.child_or_insert("topology")),
+            None => element.remove_children("topology"),
+        }
+        apply_list(element, &self.features, |feature, existing| existing.attr("name") == Some(feature.name.as_str()));
+
+        if self.numa_cells.is_empty() {
+            element.remove_children("numa");
+        } else {
+            apply_list(element.child_or_insert("numa"), &self.numa_cells, |cell, existing| {
+                existing.attr_parse::<u32>("id") == Some(cell.id)
+            });
+        }
+    }
+}
+
+/// Named CPU model for `custom` mode
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuModel {
+    pub name: String,
+    /// allow or forbid
+    pub fallback: Option<String>,
+}
+
+impl XmlModel for CpuModel {
+    const TAG: &'static str = "model";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            name: element.text(),
+            fallback: element.attr("fallback").map(String::from),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_opt_attr("fallback", self.fallback.as_ref());
+        element.set_text(&self.name);
+    }
+}
+
+/// Guest CPU topology
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuTopology {
+    pub sockets: u32,
+    pub dies: Option<u32>,
+    pub cores: u32,
+    pub threads: u32,
+}
+
+impl Default for CpuTopology {
+    fn default() -> Self {
+        Self { sockets: 1, dies: None, cores: 1, threads: 1 }
+    }
+}
+
+impl XmlModel for CpuTopology {
+    const TAG: &'static str = "topology";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            sockets: element.attr_parse("sockets").unwrap_or(1),
+            dies: element.attr_parse("dies"),
+            cores: element.attr_parse("cores").unwrap_or(1),
+            threads: element.attr_parse("threads").unwrap_or(1),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("sockets", self.sockets);
+        element.set_opt_attr("dies", self.dies);
+        element.set_attr("cores", self.cores);
+        element.set_attr("threads", self.threads);
+    }
+}
+
+/// CPU feature flag
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuFeature {
+    /// require, optional, disable, forbid or force
+    pub policy: String,
+    pub name: String,
+}
+
+impl XmlModel for CpuFeature {
+    const TAG: &'static str = "feature";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            policy: element.attr("policy").unwrap_or("require").to_string(),
+            name: element.attr("name").unwrap_or_default().to_string(),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("policy", &self.policy);
+        element.set_attr("name", &self.name);
+    }
+}
+
+/// Guest NUMA cell
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NumaCell {
+    pub id: u32,
+    /// vCPU list (e.g. `0-3`)
+    pub cpus: String,
+    pub memory_kib: u64,
+    /// shared or private
+    pub mem_access: Option<String>,
+}
+
+impl XmlModel for NumaCell {
+    const TAG: &'static str = "cell";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            id: element.attr_parse("id").unwrap_or(0),
+            cpus: element.attr("cpus").unwrap_or_default().to_string(),
+            memory_kib: element.attr_parse("memory")
+                .map(|memory| to_kib(memory, element.attr("unit")))
+                .unwrap_or(0),
+            mem_access: element.attr("memAccess").map(String::from),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("id", self.id);
+        element.set_attr("cpus", &self.cpus);
+        element.set_attr("memory", self.memory_kib);
+        element.set_attr("unit", "KiB");
+        element.set_opt_attr("memAccess", self.mem_access.as_ref());
+    }
+}
+
+/// `<cputune>` section: vCPU and emulator pinning
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuTune {
+    pub vcpupins: Vec<VcpuPin>,
+    /// Host cpuset for the emulator threads
+    pub emulatorpin: Option<String>,
+}
+
+impl XmlModel for CpuTune {
+    const TAG: &'static str = "cputune";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            vcpupins: element.children_named("vcpupin").map(VcpuPin::from_element).collect(),
+            emulatorpin: element.child("emulatorpin")
+                .and_then(|pin| pin.attr("cpuset"))
+                .map(String::from),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        apply_list(element, &self.vcpupins, |pin, existing| existing.attr_parse::<u32>("vcpu") == Some(pin.vcpu));
+        match &self.emulatorpin {
+            Some(cpuset) => element.child_or_insert("emulatorpin").set_attr("cpuset", cpuset),
+            None => element.remove_children("emulatorpin"),
+        }
+    }
+}
+
+/// Pin one vCPU to a host cpuset
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VcpuPin {
+    pub vcpu: u32,
+    pub cpuset: String,
+}
+
+impl XmlModel for VcpuPin {
+    const TAG: &'static str = "vcpupin";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            vcpu: element.attr_parse("vcpu").unwrap_or(0),
+            cpuset: element.attr("cpuset").unwrap_or_default().to_string(),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("vcpu", self.vcpu);
+        element.set_attr("cpuset", &self.cpuset);
+    }
+}
+
+/// `<numatune>` section: host NUMA memory placement
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NumaTune {
+    /// strict, preferred, interleave or restrictive
+    pub mode: Option<String>,
+    pub nodeset: Option<String>,
+    /// Per guest-cell placement
+    pub memnodes: Vec<MemNode>,
+}
+
+impl XmlModel for NumaTune {
+    const TAG: &'static str = "numatune";
+
+    fn from_element(element: &XmlElement) -> Self {
+        let memory = element.child("memory");
+        Self {
+            mode: memory.and_then(|m| m.attr("mode")).map(String::from),
+            nodeset: memory.and_then(|m| m.attr("nodeset")).map(String::from),
+            memnodes: element.children_named("memnode").map(MemNode::from_element).collect(),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        if self.mode.is_some() || self.nodeset.is_some() {
+            let memory = element.child_or_insert("memory");
+            memory.set_opt_attr("mode", self.mode.as_ref());
+            memory.set_opt_attr("nodeset", self.nodeset.as_ref());
+        } else {
+            element.remove_children("memory");
+        }
+        apply_list(element, &self.memnodes, |node, existing| existing.attr_parse::<u32>("cellid") == Some(node.cellid));
+    }
+}
+
+/// Host node placement for one guest NUMA cell
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemNode {
+    pub cellid: u32,
+    pub mode: String,
+    pub nodeset: String,
+}
+
+impl XmlModel for MemNode {
+    const TAG: &'static str = "memnode";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            cellid: element.attr_parse("cellid").unwrap_or(0),
+            mode: element.attr("mode").unwrap_or("strict").to_string(),
+            nodeset: element.attr("nodeset").unwrap_or_default().to_string(),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("cellid", self.cellid);
+        element.set_attr("mode", &self.mode);
+        element.set_attr("nodeset", &self.nodeset);
+    }
+}
+
+/// `<memoryBacking>` section
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryBacking {
+    /// `Some` enables hugepages; an empty page list uses the default size
+    pub hugepages: Option<Vec<HugePage>>,
+    pub nosharepages: bool,
+    pub locked: bool,
+    /// file, anonymous or memfd
+    pub source_type: Option<String>,
+    /// shared or private
+    pub access_mode: Option<String>,
+}
+
+impl MemoryBacking {
+    /// Default-size page in KiB of the first hugepage entry
+    pub fn hugepage_size_kib(&self) -> Option<u64> {
+        self.hugepages.as_ref()?.first().map(|page| to_kib(page.size, page.unit.as_deref()))
+    }
+}
+
+fn set_flag(element: &mut XmlElement, name: &str, enabled: bool) {
+    if !enabled {
+        element.remove_children(name);
+    } else if element.child(name).is_none() {
+        element.push_child(XmlElement::new(name));
+    }
+}
+
+impl XmlModel for MemoryBacking {
+    const TAG: &'static str = "memoryBacking";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            hugepages: element.child("hugepages")
+                .map(|hugepages| hugepages.children_named("page").map(HugePage::from_element).collect()),
+            nosharepages: element.child("nosharepages").is_some(),
+            locked: element.child("locked").is_some(),
+            source_type: element.child("source").and_then(|s| s.attr("type")).map(String::from),
+            access_mode: element.child("access").and_then(|a| a.attr("mode")).map(String::from),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        match &self.hugepages {
+            Some(pages) => {
+                let hugepages = element.child_or_insert("hugepages");
+                hugepages.remove_children("page");
+                for page in pages {
+                    hugepages.push_child(page.to_element());
+                }
+            }
+            None => element.remove_children("hugepages"),
+        }
+        set_flag(element, "nosharepages", self.nosharepages);
+        set_flag(element, "locked", self.locked);
+        match &self.source_type {
+            Some(source) => element.child_or_insert("source").set_attr("type", source),
+            None => element.remove_children("source"),
+        }
+        match &self.access_mode {
+            Some(mode) => element.child_or_insert("access").set_attr("mode", mode),
+            None => element.remove_children("access"),
+        }
+    }
+}
+
+/// Hugepage size entry
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HugePage {
+    pub size: u64,
+    pub unit: Option<String>,
+    pub nodeset: Option<String>,
+}
+
+impl XmlModel for HugePage {
+    const TAG: &'static str = "page";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            size: element.attr_parse("size").unwrap_or(0),
+            unit: element.attr("unit").map(String::from),
+            nodeset: element.attr("nodeset").map(String::from),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("size", self.size);
+        element.set_opt_attr("unit", self.unit.as_ref());
+        element.set_opt_attr("nodeset", self.nodeset.as_ref());
+    }
+}
+
+/// Disk device (`<disk>`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Disk {
+    /// file, block, network or volume
+    pub disk_type: String,
+    /// disk, cdrom, floppy or lun
+    pub device: String,
+    pub driver: Option<DiskDriver>,
+    pub source: Option<DiskSource>,
+    pub target_dev: String,
+    pub target_bus: Option<String>,
+    pub readonly: bool,
+    pub shareable: bool,
+    pub boot_order: Option<u32>,
+    pub serial: Option<String>,
+    pub iotune: Option<IoTune>,
+}
+
+impl Disk {
+    /// A file-backed disk with a qemu driver
+    pub fn file(device: &str, path: &str, format: &str, target_dev: &str, bus: &str) -> Self {
+        Self {
+            disk_type: "file".to_string(),
+            device: device.to_string(),
+            driver: Some(DiskDriver {
+                name: Some("qemu".to_string()),
+                format: Some(format.to_string()),
+                ..Default::default()
+            }),
+            source: Some(DiskSource { file: Some(path.to_string()), ..Default::default() }),
+            target_dev: target_dev.to_string(),
+            target_bus: Some(bus.to_string()),
+            readonly: device == "cdrom",
+            ..Default::default()
+        }
+    }
+
+    /// Host path backing the disk, if it is file or block backed
+    pub fn source_path(&self) -> Option<&str> {
+        let source = self.source.as_ref()?;
+        source.file.as_deref().or(source.dev.as_deref())
+    }
+}
+
+impl XmlModel for Disk {
+    const TAG: &'static str = "disk";
+
+    fn from_element(element: &XmlElement) -> Self {
+        let target = element.child("target");
+        Self {
+            disk_type: element.attr("type").unwrap_or("file").to_string(),
+            device: element.attr("device").unwrap_or("disk").to_string(),
+            driver: element.child("driver").map(DiskDriver::from_element),
+            source: element.child("source").map(DiskSource::from_element),
+            target_dev: target.and_then(|t| t.attr("dev")).unwrap_or_default().to_string(),
+            target_bus: target.and_then(|t| t.attr("bus")).map(String::from),
+            readonly: element.child("readonly").is_some(),
+            shareable: element.child("shareable").is_some(),
+            boot_order: element.child("boot").and_then(|b| b.attr_parse("order")),
+            serial: element.child_text("serial"),
+            iotune: element.child("iotune").map(IoTune::from_element),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("type", &self.disk_type);
+        element.set_attr("device", &self.device);
+        match &self.driver {
+            Some(driver) => driver.apply_to(element.child_or_insert("driver")),
+            None => element.remove_children("driver"),
+        }
+        match &self.source {
+            Some(source) => source.apply_to(element.child_or_insert("source")),
+            None => element.remove_children("source"),
+        }
+        let target = element.child_or_insert("target");
+        target.set_attr("dev", &self.target_dev);
+        target.set_opt_attr("bus", self.target_bus.as_ref());
+        set_flag(element, "readonly", self.readonly);
+        set_flag(element, "shareable", self.shareable);
+        match self.boot_order {
+            Some(order) => element.child_or_insert("boot").set_attr("order", order),
+            None => element.remove_children("boot"),
+        }
+        element.set_child_text("serial", self.serial.as_ref());
+        match &self.iotune {
+            Some(iotune) if !iotune.is_empty() => iotune.apply_to(element.child_or_insert("iotune")),
+            _ => element.remove_children("iotune"),
+        }
+    }
+}
+
+/// Disk `<driver>` settings
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiskDriver {
+    pub name: Option<String>,
+    /// Image format (the `type` attribute: qcow2, raw, ...)
+    pub format: Option<String>,
+    pub cache: Option<String>,
+    pub io: Option<String>,
+    pub discard: Option<String>,
+    pub detect_zeroes: Option<String>,
+}
+
+impl XmlModel for DiskDriver {
+    const TAG: &'static str = "driver";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            name: element.attr("name").map(String::from),
+            format: element.attr("type").map(String::from),
+            cache: element.attr("cache").map(String::from),
+            io: element.attr("io").map(String::from),
+            discard: element.attr("discard").map(String::from),
+            detect_zeroes: element.attr("detect_zeroes").map(String::from),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_opt_attr("name", self.name.as_ref());
+        element.set_opt_attr("type", self.format.as_ref());
+        element.set_opt_attr("cache", self.cache.as_ref());
+        element.set_opt_attr("io", self.io.as_ref());
+        element.set_opt_attr("discard", self.discard.as_ref());
+        element.set_opt_attr("detect_zeroes", self.detect_zeroes.as_ref());
+    }
+}
+
+/// Disk `<source>`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiskSource {
+    pub file: Option<String>,
+    pub dev: Option<String>,
+    pub pool: Option<String>,
+    pub volume: Option<String>,
+    pub protocol: Option<String>,
+    pub name: Option<String>,
+}
+
+impl XmlModel for DiskSource {
+    const TAG: &'static str = "source";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            file: element.attr("file").map(String::from),
+            dev: element.attr("dev").map(String::from),
+            pool: element.attr("pool").map(String::from),
+            volume: element.attr("volume").map(String::from),
+            protocol: element.attr("protocol").map(String::from),
+            name: element.attr("name").map(String::from),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_opt_attr("file", self.file.as_ref());
+        element.set_opt_attr("dev", self.dev.as_ref());
+        element.set_opt_attr("pool", self.pool.as_ref());
+        element.set_opt_attr("volume", self.volume.as_ref());
+        element.set_opt_attr("protocol", self.protocol.as_ref());
+        element.set_opt_attr("name", self.name.as_ref());
+    }
+}
+
+/// Disk I/O throttling (`<iotune>`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IoTune {
+    pub read_iops_sec: Option<u64>,
+    pub write_iops_sec: Option<u64>,
+    pub read_bytes_sec: Option<u64>,
+    pub write_bytes_sec: Option<u64>,
+}
+
+impl IoTune {
+    pub fn is_empty(&self) -> bool {
+        self.read_iops_sec.is_none()
+            && self.write_iops_sec.is_none()
+            && self.read_bytes_sec.is_none()
+            && self.write_bytes_sec.is_none()
+    }
+}
+
+impl XmlModel for IoTune {
+    const TAG: &'static str = "iotune";
+
+    fn from_element(element: &XmlElement) -> Self {
+        let value = |name: &str| element.child_text(name).and_then(|v| v.parse().ok());
+        Self {
+            read_iops_sec: value("read_iops_sec"),
+            write_iops_sec: value("write_iops_sec"),
+            read_bytes_sec: value("read_bytes_sec"),
+            write_bytes_sec: value("write_bytes_sec"),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_child_text("read_iops_sec", self.read_iops_sec);
+        element.set_child_text("write_iops_sec", self.write_iops_sec);
+        element.set_child_text("read_bytes_sec", self.read_bytes_sec);
+        element.set_child_text("write_bytes_sec", self.write_bytes_sec);
+    }
+}
+
+/// Network interface (`<interface>`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Interface {
+    /// network, bridge, direct, user, ...
+    pub interface_type: String,
+    pub mac: Option<String>,
+    pub source_network: Option<String>,
+    pub source_bridge: Option<String>,
+    /// Host device for `direct` (macvtap) interfaces
+    pub source_dev: Option<String>,
+    /// macvtap mode (vepa, bridge, private, passthrough)
+    pub source_mode: Option<String>,
+    pub model: Option<String>,
+    pub target_dev: Option<String>,
+    pub boot_order: Option<u32>,
+    /// Link state (`up` or `down`)
+    pub link_state: Option<String>,
+    pub bandwidth: Option<Bandwidth>,
+}
+
+impl Interface {
+    /// Interface attached to a libvirt virtual network
+    pub fn network(network: &str, model: &str) -> Self {
+        Self {
+            interface_type: "network".to_string(),
+            source_network: Some(network.to_string()),
+            model: Some(model.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Human-readable source: network name, `bridge:<name>` or `direct:<dev>`
+    pub fn source_label(&self) -> Option<String> {
+        if let Some(network) = &self.source_network {
+            Some(network.clone())
+        } else if let Some(bridge) = &self.source_bridge {
+            Some(format!("bridge:{}", bridge))
+        } else {
+            self.source_dev.as_ref().map(|dev| format!("direct:{}", dev))
+        }
+    }
+}
+
+impl XmlModel for Interface {
+    const TAG: &'static str = "interface";
+
+    fn from_element(element: &XmlElement) -> Self {
+        let source = element.child("source");
+        Self {
+            interface_type: element.attr("type").unwrap_or("network").to_string(),
+            mac: element.child("mac").and_then(|m| m.attr("address")).map(String::from),
+            source_network: source.and_then(|s| s.attr("network")).map(String::from),
+            source_bridge: source.and_then(|s| s.attr("bridge")).map(String::from),
+            source_dev: source.and_then(|s| s.attr("dev")).map(String::from),
+            source_mode: source.and_then(|s| s.attr("mode")).map(String::from),
+            model: element.child("model").and_then(|m| m.attr("type")).map(String::from),
+            target_dev: element.child("target").and_then(|t| t.attr("dev")).map(String::from),
+            boot_order: element.child("boot").and_then(|b| b.attr_parse("order")),
+            link_state: element.child("link").and_then(|l| l.attr("state")).map(String::from),
+            bandwidth: element.child("bandwidth").map(Bandwidth::from_element),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("type", &self.interface_type);
+        match &self.mac {
+            Some(mac) => element.child_or_insert("mac").set_attr("address", mac),
+            None => element.remove_children("mac"),
+        }
+        let source = element.child_or_insert("source");
+        source.set_opt_attr("network", self.source_network.as_ref());
+        source.set_opt_attr("bridge", self.source_bridge.as_ref());
+        source.set_opt_attr("dev", self.source_dev.as_ref());
+        source.set_opt_attr("mode", self.source_mode.as_ref());
+        match &self.model {
+            Some(model) => element.child_or_insert("model").set_attr("type", model),
+            None => element.remove_children("model"),
+        }
+        match &self.target_dev {
+            Some(dev) => element.child_or_insert("target").set_attr("dev", dev),
+            None => element.remove_children("target"),
+        }
+        match self.boot_order {
+            Some(order) => element.child_or_insert("boot").set_attr("order", order),
+            None => element.remove_children("boot"),
+        }
+        match &self.link_state {
+            Some(state) => element.child_or_insert("link").set_attr("state", state),
+            None => element.remove_children("link"),
+        }
+        match &self.bandwidth {
+            Some(bandwidth) if !bandwidth.is_empty() => bandwidth.apply_to(element.child_or_insert("bandwidth")),
+            _ => element.remove_children("bandwidth"),
+        }
+    }
+}
+
+/// Interface QoS (`<bandwidth>`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bandwidth {
+    pub inbound: Option<BandwidthLimit>,
+    pub outbound: Option<BandwidthLimit>,
+}
+
+impl Bandwidth {
+    pub fn is_empty(&self) -> bool {
+        self.inbound.is_none() && self.outbound.is_none()
+    }
+}
+
+impl XmlModel for Bandwidth {
+    const TAG: &'static str = "bandwidth";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            inbound: element.child("inbound").map(BandwidthLimit::from_element),
+            outbound: element.child("outbound").map(BandwidthLimit::from_element),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        match &self.inbound {
+            Some(limit) => limit.apply_to(element.child_or_insert("inbound")),
+            None => element.remove_children("inbound"),
+        }
+        match &self.outbound {
+            Some(limit) => limit.apply_to(element.child_or_insert("outbound")),
+            None => element.remove_children("outbound"),
+        }
+    }
+}
+
+/// One direction of interface QoS. Average/peak in KB/s, burst in KB.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BandwidthLimit {
+    pub average: Option<u64>,
+    pub peak: Option<u64>,
+    pub burst: Option<u64>,
+}
+
+impl XmlModel for BandwidthLimit {
+    const TAG: &'static str = "inbound";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            average: element.attr_parse("average"),
+            peak: element.attr_parse("peak"),
+            burst: element.attr_parse("burst"),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_opt_attr("average", self.average);
+        element.set_opt_attr("peak", self.peak);
+        element.set_opt_attr("burst", self.burst);
+    }
+}
+
+/// Graphics console (`<graphics>`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Graphics {
+    /// vnc or spice
+    pub graphics_type: String,
+    pub port: Option<i32>,
+    pub autoport: bool,
+    pub listen: Option<String>,
+    pub password: Option<String>,
+}
+
+impl XmlModel for Graphics {
+    const TAG: &'static str = "graphics";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            graphics_type: element.attr("type").unwrap_or("vnc").to_string(),
+            port: element.attr_parse("port"),
+            autoport: element.attr("autoport") == Some("yes"),
+            listen: element.attr("listen").map(String::from),
+            password: element.attr("passwd").map(String::from),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("type", &self.graphics_type);
+        element.set_opt_attr("port", self.port);
+        element.set_attr("autoport", yes_no(self.autoport));
+        element.set_opt_attr("listen", self.listen.as_ref());
+        element.set_opt_attr("passwd", self.password.as_ref());
+        let listen = element.child_or_insert("listen");
+        match &self.listen {
+            Some(address) => {
+                listen.set_attr("type", "address");
+                listen.set_attr("address", address);
+            }
+            None => {
+                if listen.attr("type").is_none() {
+                    listen.set_attr("type", "address");
+                }
+            }
+        }
+    }
+}
+
+/// Video adapter (`<video>`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Video {
+    /// qxl, virtio, vga, cirrus, bochs, ramfb, none
+    pub model: String,
+    /// Video RAM in KiB
+    pub vram: Option<u64>,
+    pub heads: Option<u32>,
+    pub accel3d: bool,
+}
+
+impl XmlModel for Video {
+    const TAG: &'static str = "video";
+
+    fn from_element(element: &XmlElement) -> Self {
+        let model = element.child("model");
+        Self {
+            model: model.and_then(|m| m.attr("type")).unwrap_or("vga").to_string(),
+            vram: model.and_then(|m| m.attr_parse("vram")),
+            heads: model.and_then(|m| m.attr_parse("heads")),
+            accel3d: model.and_then(|m| m.child("acceleration"))
+                .and_then(|a| a.attr("accel3d")) == Some("yes"),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        let model = element.child_or_insert("model");
+        model.set_attr("type", &self.model);
+        model.set_opt_attr("vram", self.vram);
+        model.set_opt_attr("heads", self.heads);
+        if self.accel3d {
+            model.child_or_insert("acceleration").set_attr("accel3d", "yes");
+        } else {
+            model.remove_children("acceleration");
+        }
+    }
+}
+
+/// TPM device (`<tpm>`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tpm {
+    /// tpm-tis, tpm-crb or tpm-spapr
+    pub model: String,
+    /// emulator or passthrough
+    pub backend_type: String,
+    pub version: Option<String>,
+    /// Host device path for passthrough
+    pub device_path: Option<String>,
+}
+
+impl XmlModel for Tpm {
+    const TAG: &'static str = "tpm";
+
+    fn from_element(element: &XmlElement) -> Self {
+        let backend = element.child("backend");
+        Self {
+            model: element.attr("model").unwrap_or("tpm-tis").to_string(),
+            backend_type: backend.and_then(|b| b.attr("type")).unwrap_or("emulator").to_string(),
+            version: backend.and_then(|b| b.attr("version")).map(String::from),
+            device_path: backend.and_then(|b| b.child("device"))
+                .and_then(|d| d.attr("path"))
+                .map(String::from),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("model", &self.model);
+        let backend = element.child_or_insert("backend");
+        backend.set_attr("type", &self.backend_type);
+        backend.set_opt_attr("version", self.version.as_ref());
+        match &self.device_path {
+            Some(path) => backend.child_or_insert("device").set_attr("path", path),
+            None => backend.remove_children("device"),
+        }
+    }
+}
+
+/// Random number generator (`<rng>`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Rng {
+    pub model: String,
+    /// random or egd
+    pub backend_model: String,
+    /// Host entropy source for the `random` backend
+    pub source: Option<String>,
+}
+
+impl XmlModel for Rng {
+    const TAG: &'static str = "rng";
+
+    fn from_element(element: &XmlElement) -> Self {
+        let backend = element.child("backend");
+        Self {
+            model: element.attr("model").unwrap_or("virtio").to_string(),
+            backend_model: backend.and_then(|b| b.attr("model")).unwrap_or("random").to_string(),
+            source: backend.map(|b| b.text()).filter(|s| !s.is_empty()),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("model", &self.model);
+        let backend = element.child_or_insert("backend");
+        backend.set_attr("model", &self.backend_model);
+        if let Some(source) = &self.source {
+            backend.set_text(source);
+        }
+    }
+}
+
+/// Watchdog (`<watchdog>`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Watchdog {
+    pub model: String,
+    pub action: String,
+}
+
+impl XmlModel for Watchdog {
+    const TAG: &'static str = "watchdog";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            model: element.attr("model").unwrap_or("i6300esb").to_string(),
+            action: element.attr("action").unwrap_or("reset").to_string(),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("model", &self.model);
+        element.set_attr("action", &self.action);
+    }
+}
+
+/// Sound card (`<sound>`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sound {
+    pub model: String,
+    /// Audio backend id the card is wired to
+    pub audio_id: Option<u32>,
+}
+
+impl XmlModel for Sound {
+    const TAG: &'static str = "sound";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            model: element.attr("model").unwrap_or_default().to_string(),
+            audio_id: element.child("audio").and_then(|a| a.attr_parse("id")),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("model", &self.model);
+        match self.audio_id {
+            Some(id) => element.child_or_insert("audio").set_attr("id", id),
+            None => element.remove_children("audio"),
+        }
+    }
+}
+
+/// Input device (`<input>`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Input {
+    /// tablet, mouse, keyboard, passthrough or evdev
+    pub input_type: String,
+    pub bus: Option<String>,
+    /// Host device for passthrough/evdev inputs
+    pub source_dev: Option<String>,
+    /// evdev grab mode (`all`) and repeat
+    pub grab: Option<String>,
+    pub repeat: Option<bool>,
+}
+
+impl XmlModel for Input {
+    const TAG: &'static str = "input";
+
+    fn from_element(element: &XmlElement) -> Self {
+        let source = element.child("source");
+        Self {
+            input_type: element.attr("type").unwrap_or_default().to_string(),
+            bus: element.attr("bus").map(String::from),
+            source_dev: source.and_then(|s| s.attr("dev").or_else(|| s.attr("evdev"))).map(String::from),
+            grab: source.and_then(|s| s.attr("grab")).map(String::from),
+            repeat: source.and_then(|s| s.attr("repeat")).map(|r| r == "on"),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("type", &self.input_type);
+        element.set_opt_attr("bus", self.bus.as_ref());
+        match &self.source_dev {
+            Some(dev) => {
+                let attr = if self.input_type == "passthrough" { "evdev" } else { "dev" };
+                let source = element.child_or_insert("source");
+                source.set_attr(attr, dev);
+                source.set_opt_attr("grab", self.grab.as_ref());
+                source.set_opt_attr("repeat", self.repeat.map(|r| if r { "on" } else { "off" }));
+            }
+            None => element.remove_children("source"),
+        }
+    }
+}
+
+/// Controller (`<controller>`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Controller {
+    /// usb, scsi, sata, virtio-serial, pci, ...
+    pub controller_type: String,
+    pub index: Option<u32>,
+    pub model: Option<String>,
+    pub ports: Option<u32>,
+}
+
+impl XmlModel for Controller {
+    const TAG: &'static str = "controller";
+
+    fn from_element(element: &XmlElement) -> Self {
+        Self {
+            controller_type: element.attr("type").unwrap_or_default().to_string(),
+            index: element.attr_parse("index"),
+            model: element.attr("model").map(String::from),
+            ports: element.attr_parse("ports"),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("type", &self.controller_type);
+        element.set_opt_attr("index", self.index);
+        element.set_opt_attr("model", self.model.as_ref());
+        element.set_opt_attr("ports", self.ports);
+    }
+}
+
+/// PCI host device passthrough (`<hostdev type='pci'>`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PciHostdev {
+    pub domain: u32,
+    pub bus: u32,
+    pub slot: u32,
+    pub function: u32,
+    pub managed: bool,
+}
+
+impl PciHostdev {
+    /// Parse a host PCI address in `DDDD:BB:SS.F` form
+    pub fn from_address(address: &str, managed: bool) -> Option<Self> {
+        let (domain, rest) = address.split_once(':')?;
+        let (bus, rest) = rest.split_once(':')?;
+        let (slot, function) = rest.split_once('.')?;
+        Some(Self {
+            domain: u32::from_str_radix(domain, 16).ok()?,
+            bus: u32::from_str_radix(bus, 16).ok()?,
+            slot: u32::from_str_radix(slot, 16).ok()?,
+            function: u32::from_str_radix(function, 16).ok()?,
+            managed,
+        })
+    }
+
+    /// Host address in `DDDD:BB:SS.F` form
+    pub fn address(&self) -> String {
+        format!("{:04x}:{:02x}:{:02x}.{:x}", self.domain, self.bus, self.slot, self.function)
+    }
+}
+
+fn parse_hex(value: Option<&str>) -> u32 {
+    value
+        .map(|v| v.trim_start_matches("0x"))
+        .and_then(|v| u32::from_str_radix(v, 16).ok())
+        .unwrap_or(0)
+}
+
+impl XmlModel for PciHostdev {
+    const TAG: &'static str = "hostdev";
+
+    fn from_element(element: &XmlElement) -> Self {
+        let address = element.find("source/address");
+        Self {
+            domain: parse_hex(address.and_then(|a| a.attr("domain"))),
+            bus: parse_hex(address.and_then(|a| a.attr("bus"))),
+            slot: parse_hex(address.and_then(|a| a.attr("slot"))),
+            function: parse_hex(address.and_then(|a| a.attr("function"))),
+            managed: element.attr("managed") == Some("yes"),
+        }
+    }
+
+    fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("mode", "subsystem");
+        element.set_attr("type", "pci");
+        element.set_attr("managed", yes_no(self.managed));
+        let address = element.child_or_insert("source").child_or_insert("address");
+        address.set_attr("domain", format!("0x{:04x}", self.domain));
+        address.set_attr("bus", format!("0x{:02x}", self.bus));
+        address.set_attr("slot", format!("0x{:02x}", self.slot));
+        address.set_attr("function", format!("0x{:x}", self.function));
+    }
+}
+
+/// Character device (`<serial>`, `<console>`, `<channel>`, `<parallel>`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CharDevice {
+    /// Element name: serial, console, channel or parallel
+    pub kind: String,
+    /// Host side: pty, unix, spicevmc, file, tcp, ...
+    pub source_type: String,
+    /// Host path for unix/file sources
+    pub source_path: Option<String>,
+    /// unix socket mode (bind or connect)
+    pub source_mode: Option<String>,
+    /// Guest side target type (serial, virtio, isa-serial, ...)
+    pub target_type: Option<String>,
+    /// Channel name (e.g. org.qemu.guest_agent.0)
+    pub target_name: Option<String>,
+    pub target_port: Option<u32>,
+}
+
+impl CharDevice {
+    /// Read a character device; `kind` is taken from the element name
+    pub fn from_element(element: &XmlElement) -> Self {
+        let source = element.child("source");
+        let target = element.child("target");
+        Self {
+            kind: element.name.clone(),
+            source_type: element.attr("type").unwrap_or("pty").to_string(),
+            source_path: source.and_then(|s| s.attr("path")).map(String::from),
+            source_mode: source.and_then(|s| s.attr("mode")).map(String::from),
+            target_type: target.and_then(|t| t.attr("type")).map(String::from),
+            target_name: target.and_then(|t| t.attr("name")).map(String::from),
+            target_port: target.and_then(|t| t.attr_parse("port")),
+        }
+    }
+
+    /// Write the device into an existing element
+    pub fn apply_to(&self, element: &mut XmlElement) {
+        element.set_attr("type", &self.source_type);
+        if self.source_path.is_some() || self.source_mode.is_some() {
+            let source = element.child_or_insert("source");
+            source.set_opt_attr("mode", self.source_mode.as_ref());
+            source.set_opt_attr("path", self.source_path.as_ref());
+        } else {
+            element.remove_children("source");
+        }
+        if self.target_type.is_some() || self.target_name.is_some() || self.target_port.is_some() {
+            let target = element.child_or_insert("target");
+            target.set_opt_attr("type", self.target_type.as_ref());
+            target.set_opt_attr("name", self.target_name.as_ref());
+            target.set_opt_attr("port", self.target_port);
+        } else {
+            element.remove_children("target");
+        }
+    }
+
+    /// Build the device element
+    pub fn to_element(&self) -> XmlElement {
+        let mut element = XmlElement::new(&self.kind);
+        self.apply_to(&mut element);
+        element
+    }
+
+    /// Serialize the device as a standalone XML snippet
+    pub fn to_xml(&self) -> String {
+        self.to_element().to_xml()
+    }
+}
@@ -0,0 +1,176 @@
+use serde::Serialize;
+use super::vm::VmState;
+
+/// Tauri event name for domain events
+pub const VM_EVENT: &str = "vm-event";
+/// Tauri event name for virtual network events
+pub const NETWORK_EVENT: &str = "network-event";
+/// Tauri event name for storage pool events
+pub const STORAGE_POOL_EVENT: &str = "storage-pool-event";
+
+/// Domain event forwarded from libvirt
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VmEvent {
+    pub connection_id: String,
+    pub vm_id: String,
+    pub vm_name: String,
+    /// State of the VM after the event
+    pub state: VmState,
+    pub kind: VmEventKind,
+    pub timestamp: i64,
+}
+
+/// What happened to the domain
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum VmEventKind {
+    /// Lifecycle transition (started, stopped, crashed, ...)
+    Lifecycle { event: LifecycleEvent, detail: i32 },
+    /// Guest rebooted
+    Reboot,
+    /// Device hot-plugged
+    DeviceAdded { device_alias: String },
+    /// Device hot-unplugged
+    DeviceRemoved { device_alias: String },
+    /// Block job (copy, commit, pull) changed status
+    BlockJob { disk: String, job_type: BlockJobType, status: BlockJobStatus },
+    /// Guest agent connected or disconnected
+    AgentLifecycle { connected: bool, reason: i32 },
+    /// Watchdog device fired; `action` is the libvirt watchdog action taken
+    Watchdog { action: i32 },
+}
+
+/// Domain lifecycle event
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LifecycleEvent {
+    Defined,
+    Undefined,
+    Started,
+    Suspended,
+    Resumed,
+    Stopped,
+    Shutdown,
+    PmSuspended,
+    Crashed,
+    Unknown,
+}
+
+/// Block job type
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockJobType {
+    Pull,
+    Copy,
+    Commit,
+    ActiveCommit,
+    Backup,
+    Unknown,
+}
+
+/// Block job status
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockJobStatus {
+    Completed,
+    Failed,
+    Canceled,
+    Ready,
+    Unknown,
+}
+
+/// Virtual network or storage pool lifecycle event
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResourceLifecycleEvent {
+    Defined,
+    Undefined,
+    Started,
+    Stopped,
+    Created,
+    Deleted,
+    Unknown,
+}
+
+/// Virtual network event forwarded from libvirt
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkEvent {
+    pub connection_id: String,
+    pub network_name: String,
+    pub event: ResourceLifecycleEvent,
+    pub active: bool,
+    pub timestamp: i64,
+}
+
+/// Storage pool event forwarded from libvirt
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StoragePoolEvent {
+    pub connection_id: String,
+    pub pool_id: String,
+    pub pool_name: String,
+    pub event: ResourceLifecycleEvent,
+    pub active: bool,
+    pub timestamp: i64,
+}
+
+/// Tauri event name for connection state changes
+pub const CONNECTION_STATE_EVENT: &str = "connection-state-changed";
+
+/// Health of a supervised libvirt connection
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionState {
+    Connected,
+    Disconnected,
+    Reconnecting,
+}
+
+/// Connection state change emitted by the connection supervisor
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionStateEvent {
+    pub connection_id: String,
+    pub state: ConnectionState,
+    /// Reconnect attempts made since the connection was lost
+    pub attempt: u32,
+    /// Seconds until the next reconnect attempt
+    pub retry_in_secs: Option<u64>,
+    pub error: Option<String>,
+    pub timestamp: i64,
+}
+
+/// Kind of background task reported through `BackendEvent::TaskFailed`
+#[derive(Clone, Debug, PartialEq)]
+pub enum TaskKind {
+    Schedule,
+    Backup,
+    /// A restore point failed verification
+    BackupVerify,
+    /// A snapshot retention policy could not prune a VM
+    SnapshotPrune,
+}
+
+/// A scheduled operation, backup, backup verification or snapshot prune that failed
+#[derive(Clone, Debug)]
+pub struct TaskFailure {
+    pub kind: TaskKind,
+    /// Schedule, backup config or snapshot retention policy ID
+    pub id: String,
+    pub name: String,
+    pub vm_id: Option<String>,
+    pub error: String,
+}
+
+/// Event published to in-process subscribers such as the alert rule engine.
+///
+/// Unlike the Tauri events these never leave the backend.
+#[derive(Clone, Debug)]
+pub enum BackendEvent {
+    Vm(VmEvent),
+    Network(NetworkEvent),
+    Connection(ConnectionStateEvent),
+    TaskFailed(TaskFailure),
+}
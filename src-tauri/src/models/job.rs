@@ -0,0 +1,64 @@
+use serde::Serialize;
+
+/// Tauri event name for job progress and state changes
+pub const JOB_EVENT: &str = "job-event";
+
+/// Long-running operation run by the job service
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum JobKind {
+    CloneVm,
+    MigrateVm,
+    UploadVolume,
+    DownloadVolume,
+    ImportOva,
+    BackupVm,
+    RestoreBackup,
+    UploadBackup,
+    VerifyBackup,
+    ImportBackupCatalog,
+}
+
+/// Job state
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Whether the job has reached a final state
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, JobStatus::Running)
+    }
+}
+
+/// A background job and its latest progress.
+///
+/// The same payload is emitted as `JOB_EVENT` whenever progress or state changes.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    pub id: String,
+    pub kind: JobKind,
+    pub description: String,
+    pub connection_id: Option<String>,
+    /// VM, volume or file the job operates on
+    pub target: String,
+    pub status: JobStatus,
+    /// Current step, e.g. "Converting disk 2 of 3"
+    pub step: Option<String>,
+    /// Overall progress, 0-100
+    pub percent: f64,
+    pub bytes_processed: u64,
+    pub bytes_total: Option<u64>,
+    /// Result of a completed job (new VM ID, volume, imported disk path, ...)
+    pub result: Option<serde_json::Value>,
+    /// Final error of a failed job
+    pub error: Option<String>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+}
@@ -8,3 +8,7 @@ pub mod cloud_init;
 pub mod usb;
 pub mod mdev;
 pub mod nwfilter;
+pub mod event;
+pub mod domain_xml;
+pub mod job;
+pub mod quiesce;
@@ -11,6 +11,9 @@ pub struct Network {
     pub active: bool,
     pub autostart: bool,
     pub ip_range: Option<String>,
+    /// Saved connection this network was listed from
+    #[serde(default)]
+    pub connection_id: Option<String>,
 }
 
 /// Network Configuration for creating new networks
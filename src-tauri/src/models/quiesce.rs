@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+/// How to make a snapshot or backup application-consistent through the guest agent.
+///
+/// The pre-freeze hook runs, filesystems are frozen, the image is taken, and
+/// the guest is thawed before the post-thaw hook runs. A watchdog thaws the
+/// guest after `timeout_secs` even if the operation hangs.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuiesceOptions {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Guest mountpoints to freeze; empty freezes every filesystem
+    #[serde(default)]
+    pub mountpoints: Vec<String>,
+    /// Longest the guest may stay frozen before the watchdog thaws it
+    #[serde(default = "default_freeze_timeout")]
+    pub timeout_secs: u64,
+    /// Fail instead of taking a crash-consistent image when the guest can't be quiesced
+    #[serde(default)]
+    pub required: bool,
+    /// Run in the guest before freezing, e.g. to flush a database
+    #[serde(default)]
+    pub pre_freeze_hook: Option<GuestHook>,
+    /// Run in the guest after thawing
+    #[serde(default)]
+    pub post_thaw_hook: Option<GuestHook>,
+}
+
+impl Default for QuiesceOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mountpoints: Vec::new(),
+            timeout_secs: default_freeze_timeout(),
+            required: false,
+            pre_freeze_hook: None,
+            post_thaw_hook: None,
+        }
+    }
+}
+
+/// A program run inside the guest with guest-exec
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GuestHook {
+    /// Absolute path of the program in the guest
+    pub path: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_hook_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_freeze_timeout() -> u64 {
+    60
+}
+
+fn default_hook_timeout() -> u64 {
+    120
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::models::quiesce::QuiesceOptions;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -9,6 +10,12 @@ pub struct Snapshot {
     pub state: SnapshotState,
     pub parent: Option<String>,
     pub is_current: bool,
+    pub location: SnapshotLocation,
+    /// Per-disk record of what the snapshot captured
+    #[serde(default)]
+    pub disks: Vec<SnapshotDisk>,
+    /// File holding the guest memory of an external snapshot of a running VM
+    pub memory_file: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -20,6 +27,81 @@ pub enum SnapshotState {
     Shutoff,
 }
 
+/// Where a snapshot keeps its state
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotLocation {
+    /// Inside the qcow2 disk images (and memory in the first of them)
+    #[default]
+    Internal,
+    /// Each disk is frozen and later writes go to a new overlay file
+    External,
+}
+
+/// One disk of a snapshot.
+///
+/// For external snapshots `backing` is the image frozen at the snapshot and
+/// `overlay` the file created on top of it, so the snapshots of a VM describe
+/// its overlay chain from the base image up to the active layer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDisk {
+    pub target: String,
+    /// internal, external or no
+    pub snapshot: String,
+    pub overlay: Option<String>,
+    pub backing: Option<String>,
+}
+
+/// A snapshot with everything it holds and the snapshots taken after it
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotNode {
+    #[serde(flatten)]
+    pub snapshot: Snapshot,
+    /// Domain definition captured with the snapshot
+    pub domain_xml: String,
+    /// Whether reverting resumes the guest from saved memory
+    pub has_memory: bool,
+    /// Approximate space the snapshot holds on the host; `None` when it cannot be measured
+    pub size_bytes: Option<u64>,
+    /// Oldest first
+    pub children: Vec<SnapshotNode>,
+}
+
+/// How one part of the domain definition differs between a snapshot and now
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChange {
+    /// Top-level element, or `devices` for hardware
+    pub section: String,
+    /// What changed, e.g. `disk vda` or `memory`
+    pub item: String,
+    pub kind: ConfigChangeKind,
+    /// XML in the snapshot
+    pub snapshot: Option<String>,
+    /// XML in the current definition
+    pub current: Option<String>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigChangeKind {
+    /// Only in the current definition, so reverting removes it
+    Added,
+    /// Only in the snapshot, so reverting brings it back
+    Removed,
+    Modified,
+}
+
+/// Differences between a snapshot's domain definition and the current one
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotConfigDiff {
+    pub snapshot_name: String,
+    pub changes: Vec<ConfigChange>,
+}
+
 #[derive(Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SnapshotConfig {
@@ -27,4 +109,9 @@ pub struct SnapshotConfig {
     pub description: Option<String>,
     #[serde(default)]
     pub include_memory: bool,
+    /// Freeze guest filesystems while a disk-only snapshot of a running VM is taken
+    #[serde(default)]
+    pub quiesce: QuiesceOptions,
+    #[serde(default)]
+    pub location: SnapshotLocation,
 }
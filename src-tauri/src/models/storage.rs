@@ -41,6 +41,9 @@ pub struct StoragePool {
     pub available_bytes: u64,
     pub path: String,
     pub autostart: bool,
+    /// Saved connection this pool was listed from
+    #[serde(default)]
+    pub connection_id: Option<String>,
 }
 
 /// Volume model
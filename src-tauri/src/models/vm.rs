@@ -105,6 +105,9 @@ pub struct VM {
     pub cpu_threads: u32,
     #[serde(default)]
     pub cloud_init: Option<super::cloud_init::CloudInitConfig>,
+    /// Saved connection this VM was listed from
+    #[serde(default)]
+    pub connection_id: Option<String>,
 }
 
 /// VM Configuration for creating new VMs
@@ -0,0 +1,374 @@
+use crate::services::alert_service::AlertSeverity;
+use crate::services::event_service::EventService;
+use crate::utils::error::AppError;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// Tauri event name emitted whenever an alert instance changes state
+pub const ALERT_INSTANCE_EVENT: &str = "alert-instance-changed";
+
+/// Default number of rows returned by a history query
+const HISTORY_QUERY_LIMIT: u32 = 500;
+
+/// What raised an alert instance
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertSource {
+    /// A `ResourceAlert` threshold
+    Resource,
+    /// An event rule
+    Rule,
+}
+
+/// Alert instance lifecycle: firing → acknowledged → resolved
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertState {
+    Firing,
+    Acknowledged,
+    Resolved,
+}
+
+/// One occurrence of an alert, from the moment it fired until it cleared
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertInstance {
+    pub id: String,
+    pub source: AlertSource,
+    /// ID of the resource alert or event rule
+    pub source_id: String,
+    pub name: String,
+    pub severity: AlertSeverity,
+    pub vm_id: Option<String>,
+    /// VM ID, pool name, network name, ... the alert is about
+    pub subject_id: String,
+    pub subject_name: String,
+    pub state: AlertState,
+    pub message: String,
+    /// Sampled value that fired a threshold alert
+    pub trigger_value: Option<f64>,
+    pub fired_at: i64,
+    pub last_notified_at: i64,
+    pub notify_count: u32,
+    pub acknowledged_at: Option<i64>,
+    pub acknowledged_by: Option<String>,
+    pub resolved_at: Option<i64>,
+    /// Who resolved the instance; `None` when it cleared on its own
+    pub resolved_by: Option<String>,
+}
+
+/// Filter for alert history queries
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertHistoryQuery {
+    pub vm_id: Option<String>,
+    pub source_id: Option<String>,
+    pub state: Option<AlertState>,
+    /// Instances active at or after this time (seconds)
+    pub start_time: Option<i64>,
+    /// Instances that fired at or before this time (seconds)
+    pub end_time: Option<i64>,
+    pub limit: Option<u32>,
+}
+
+/// Persists alert instances in the `alert_history` table of the metrics database
+pub struct AlertHistoryService {
+    conn: Arc<Mutex<Connection>>,
+    events: Arc<EventService>,
+}
+
+impl AlertHistoryService {
+    /// Create the history table on the shared metrics database connection
+    pub fn new(conn: Arc<Mutex<Connection>>, events: Arc<EventService>) -> Result<Self, AppError> {
+        {
+            let db = conn.lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+            db.execute(
+                "CREATE TABLE IF NOT EXISTS alert_history (
+                    id TEXT PRIMARY KEY,
+                    source TEXT NOT NULL,
+                    source_id TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    severity TEXT NOT NULL,
+                    vm_id TEXT,
+                    subject_id TEXT NOT NULL,
+                    subject_name TEXT NOT NULL,
+                    state TEXT NOT NULL,
+                    message TEXT NOT NULL,
+                    trigger_value REAL,
+                    fired_at INTEGER NOT NULL,
+                    last_notified_at INTEGER NOT NULL,
+                    notify_count INTEGER NOT NULL,
+                    acknowledged_at INTEGER,
+                    acknowledged_by TEXT,
+                    resolved_at INTEGER,
+                    resolved_by TEXT
+                )",
+                [],
+            )
+            .map_err(|e| AppError::AlertError(format!("Failed to create alert history table: {}", e)))?;
+
+            db.execute(
+                "CREATE INDEX IF NOT EXISTS idx_alert_history_vm ON alert_history(vm_id, fired_at)",
+                [],
+            )
+            .map_err(|e| AppError::AlertError(format!("Failed to create index: {}", e)))?;
+
+            db.execute(
+                "CREATE INDEX IF NOT EXISTS idx_alert_history_source ON alert_history(source_id, subject_id, state)",
+                [],
+            )
+            .map_err(|e| AppError::AlertError(format!("Failed to create index: {}", e)))?;
+        }
+
+        Ok(Self { conn, events })
+    }
+
+    /// Record a newly fired instance
+    pub fn open(&self, instance: AlertInstance) -> Result<AlertInstance, AppError> {
+        self.write(&instance)?;
+        self.events.emit(ALERT_INSTANCE_EVENT, instance.clone());
+        Ok(instance)
+    }
+
+    /// The firing or acknowledged instance of an alert for a subject, if any
+    pub fn find_open(&self, source_id: &str, subject_id: &str) -> Result<Option<AlertInstance>, AppError> {
+        let conn = self.lock()?;
+        conn.query_row(
+            "SELECT * FROM alert_history
+             WHERE source_id = ?1 AND subject_id = ?2 AND state != 'resolved'
+             ORDER BY fired_at DESC LIMIT 1",
+            params![source_id, subject_id],
+            Self::from_row,
+        )
+        .optional()
+        .map_err(|e| AppError::AlertError(format!("Failed to query alert history: {}", e)))
+    }
+
+    /// When an alert last cleared for a subject, used to enforce its cooldown
+    pub fn last_resolved_at(&self, source_id: &str, subject_id: &str) -> Result<Option<i64>, AppError> {
+        let conn = self.lock()?;
+        conn.query_row(
+            "SELECT MAX(resolved_at) FROM alert_history WHERE source_id = ?1 AND subject_id = ?2",
+            params![source_id, subject_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::AlertError(format!("Failed to query alert history: {}", e)))
+    }
+
+    /// Every instance that has not been resolved yet
+    pub fn list_open(&self) -> Result<Vec<AlertInstance>, AppError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT * FROM alert_history WHERE state != 'resolved' ORDER BY fired_at DESC",
+        )
+        .map_err(|e| AppError::AlertError(format!("Failed to prepare query: {}", e)))?;
+
+        let instances = stmt.query_map([], Self::from_row)
+            .map_err(|e| AppError::AlertError(format!("Failed to query alert history: {}", e)))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::AlertError(format!("Failed to read alert history: {}", e)))?;
+        Ok(instances)
+    }
+
+    /// Query past and current instances, newest first
+    pub fn query(&self, filter: &AlertHistoryQuery) -> Result<Vec<AlertInstance>, AppError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT * FROM alert_history
+             WHERE (?1 IS NULL OR vm_id = ?1)
+               AND (?2 IS NULL OR source_id = ?2)
+               AND (?3 IS NULL OR state = ?3)
+               AND (?4 IS NULL OR resolved_at IS NULL OR resolved_at >= ?4)
+               AND (?5 IS NULL OR fired_at <= ?5)
+             ORDER BY fired_at DESC
+             LIMIT ?6",
+        )
+        .map_err(|e| AppError::AlertError(format!("Failed to prepare query: {}", e)))?;
+
+        let instances = stmt.query_map(
+            params![
+                filter.vm_id,
+                filter.source_id,
+                filter.state.as_ref().map(state_str),
+                filter.start_time,
+                filter.end_time,
+                filter.limit.unwrap_or(HISTORY_QUERY_LIMIT),
+            ],
+            Self::from_row,
+        )
+        .map_err(|e| AppError::AlertError(format!("Failed to query alert history: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::AlertError(format!("Failed to read alert history: {}", e)))?;
+        Ok(instances)
+    }
+
+    /// Acknowledge a firing instance, which stops reminders until it resolves
+    pub fn acknowledge(&self, id: &str, by: &str) -> Result<AlertInstance, AppError> {
+        let mut instance = self.get(id)?;
+        if instance.state != AlertState::Firing {
+            return Err(AppError::AlertError(format!("Alert is already {}", state_str(&instance.state))));
+        }
+
+        instance.state = AlertState::Acknowledged;
+        instance.acknowledged_at = Some(chrono::Utc::now().timestamp());
+        instance.acknowledged_by = Some(by.to_string());
+        self.update(instance)
+    }
+
+    /// Resolve an instance; `by` is `None` when the condition cleared on its own
+    pub fn resolve(&self, id: &str, by: Option<&str>) -> Result<AlertInstance, AppError> {
+        let mut instance = self.get(id)?;
+        if instance.state == AlertState::Resolved {
+            return Err(AppError::AlertError("Alert is already resolved".to_string()));
+        }
+
+        instance.state = AlertState::Resolved;
+        instance.resolved_at = Some(chrono::Utc::now().timestamp());
+        instance.resolved_by = by.map(str::to_string);
+        self.update(instance)
+    }
+
+    /// Resolve every open instance of an alert or rule, e.g. when it is disabled or deleted
+    pub fn resolve_source(&self, source_id: &str) -> Result<(), AppError> {
+        let open: Vec<AlertInstance> = self.list_open()?
+            .into_iter()
+            .filter(|i| i.source_id == source_id)
+            .collect();
+
+        for instance in open {
+            self.resolve(&instance.id, None)?;
+        }
+        Ok(())
+    }
+
+    /// Whether a reminder is due for an unacknowledged instance
+    pub fn renotify_due(instance: &AlertInstance, renotify_secs: u64, now: i64) -> bool {
+        instance.state == AlertState::Firing
+            && renotify_secs > 0
+            && now - instance.last_notified_at >= renotify_secs as i64
+    }
+
+    /// Record that a reminder was sent for a still-firing instance
+    pub fn mark_notified(&self, id: &str) -> Result<AlertInstance, AppError> {
+        let mut instance = self.get(id)?;
+        instance.last_notified_at = chrono::Utc::now().timestamp();
+        instance.notify_count += 1;
+        self.update(instance)
+    }
+
+    fn get(&self, id: &str) -> Result<AlertInstance, AppError> {
+        let conn = self.lock()?;
+        conn.query_row("SELECT * FROM alert_history WHERE id = ?1", params![id], Self::from_row)
+            .optional()
+            .map_err(|e| AppError::AlertError(format!("Failed to query alert history: {}", e)))?
+            .ok_or_else(|| AppError::AlertError(format!("Alert instance not found: {}", id)))
+    }
+
+    fn update(&self, instance: AlertInstance) -> Result<AlertInstance, AppError> {
+        self.write(&instance)?;
+        self.events.emit(ALERT_INSTANCE_EVENT, instance.clone());
+        Ok(instance)
+    }
+
+    fn write(&self, instance: &AlertInstance) -> Result<(), AppError> {
+        let conn = self.lock()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO alert_history (
+                id, source, source_id, name, severity, vm_id, subject_id, subject_name,
+                state, message, trigger_value, fired_at, last_notified_at, notify_count,
+                acknowledged_at, acknowledged_by, resolved_at, resolved_by
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+            params![
+                instance.id,
+                match instance.source {
+                    AlertSource::Resource => "resource",
+                    AlertSource::Rule => "rule",
+                },
+                instance.source_id,
+                instance.name,
+                severity_str(&instance.severity),
+                instance.vm_id,
+                instance.subject_id,
+                instance.subject_name,
+                state_str(&instance.state),
+                instance.message,
+                instance.trigger_value,
+                instance.fired_at,
+                instance.last_notified_at,
+                instance.notify_count,
+                instance.acknowledged_at,
+                instance.acknowledged_by,
+                instance.resolved_at,
+                instance.resolved_by,
+            ],
+        )
+        .map_err(|e| AppError::AlertError(format!("Failed to write alert history: {}", e)))?;
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, AppError> {
+        self.conn.lock()
+            .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<AlertInstance> {
+        let source: String = row.get("source")?;
+        let severity: String = row.get("severity")?;
+        let state: String = row.get("state")?;
+
+        Ok(AlertInstance {
+            id: row.get("id")?,
+            source: if source == "rule" { AlertSource::Rule } else { AlertSource::Resource },
+            source_id: row.get("source_id")?,
+            name: row.get("name")?,
+            severity: match severity.as_str() {
+                "critical" => AlertSeverity::Critical,
+                "warning" => AlertSeverity::Warning,
+                _ => AlertSeverity::Info,
+            },
+            vm_id: row.get("vm_id")?,
+            subject_id: row.get("subject_id")?,
+            subject_name: row.get("subject_name")?,
+            state: match state.as_str() {
+                "acknowledged" => AlertState::Acknowledged,
+                "resolved" => AlertState::Resolved,
+                _ => AlertState::Firing,
+            },
+            message: row.get("message")?,
+            trigger_value: row.get("trigger_value")?,
+            fired_at: row.get("fired_at")?,
+            last_notified_at: row.get("last_notified_at")?,
+            notify_count: row.get("notify_count")?,
+            acknowledged_at: row.get("acknowledged_at")?,
+            acknowledged_by: row.get("acknowledged_by")?,
+            resolved_at: row.get("resolved_at")?,
+            resolved_by: row.get("resolved_by")?,
+        })
+    }
+}
+
+/// Name recorded when the frontend acknowledges or resolves an alert without naming anyone
+pub fn local_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+fn severity_str(severity: &AlertSeverity) -> &'static str {
+    match severity {
+        AlertSeverity::Info => "info",
+        AlertSeverity::Warning => "warning",
+        AlertSeverity::Critical => "critical",
+    }
+}
+
+fn state_str(state: &AlertState) -> &'static str {
+    match state {
+        AlertState::Firing => "firing",
+        AlertState::Acknowledged => "acknowledged",
+        AlertState::Resolved => "resolved",
+    }
+}
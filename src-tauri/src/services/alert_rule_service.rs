@@ -0,0 +1,637 @@
+use crate::models::event::{
+    BackendEvent, ConnectionState, LifecycleEvent, ResourceLifecycleEvent, TaskKind, VmEventKind,
+};
+use crate::models::vm::VmState;
+use crate::services::alert_history_service::{AlertHistoryService, AlertInstance, AlertSource, AlertState};
+use crate::services::alert_service::AlertSeverity;
+use crate::services::connection_service::ConnectionService;
+use crate::services::event_service::EventService;
+use crate::services::guest_agent_service::GuestAgentService;
+use crate::services::notification_service::{Notification, NotificationService};
+use crate::services::storage_service::StorageService;
+use crate::services::vm_service::VmService;
+use crate::utils::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use virt::sys;
+
+/// Tauri event name for triggered event rules
+pub const RULE_ALERT_EVENT: &str = "alert-rule-triggered";
+
+/// Seconds between polls of conditions libvirt has no event for (pool usage, agent ping)
+const RULE_POLL_INTERVAL_SECS: u64 = 60;
+
+/// Condition an event rule watches for
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum EventRuleKind {
+    /// VM stopped because it crashed or QEMU failed
+    VmCrashed,
+    /// Guest agent disconnected while the VM runs, or stopped answering pings
+    GuestAgentUnresponsive,
+    /// pvpanic device reported a guest panic, or the watchdog fired
+    GuestPanic,
+    /// Storage pool usage reached `threshold_percent`
+    StoragePoolUsage,
+    /// Virtual network was stopped
+    NetworkInactive,
+    /// Supervisor lost a libvirt connection
+    ConnectionLost,
+    BackupFailed,
+    /// A restore point failed verification
+    BackupVerifyFailed,
+    ScheduleFailed,
+    /// A snapshot retention policy failed to prune a VM
+    SnapshotPruneFailed,
+}
+
+/// Alert rule triggered by events rather than sampled thresholds
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EventAlertRule {
+    pub id: String,
+    pub name: String,
+    pub kind: EventRuleKind,
+    pub severity: AlertSeverity,
+    pub enabled: bool,
+    /// VM ID, pool name, network name, connection ID, or schedule/backup config/retention policy ID;
+    /// `None` matches every subject of the rule's kind
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Only match events from this connection
+    #[serde(default)]
+    pub connection_id: Option<String>,
+    /// Usage percentage for `StoragePoolUsage`
+    #[serde(default)]
+    pub threshold_percent: Option<f64>,
+    /// Usage percentage a full pool must drop below before its alert resolves
+    #[serde(default)]
+    pub clear_threshold_percent: Option<f64>,
+    #[serde(default)]
+    pub channel_ids: Vec<String>,
+    /// Seconds after a resolution during which the rule will not fire again for the same subject
+    #[serde(default)]
+    pub cooldown_secs: u64,
+    /// Seconds between reminders while the rule fires unacknowledged; 0 notifies once
+    #[serde(default)]
+    pub renotify_secs: u64,
+    pub last_triggered: Option<i64>,
+    pub created_at: i64,
+}
+
+/// Request to create an event rule
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateEventRuleRequest {
+    pub name: String,
+    pub kind: EventRuleKind,
+    pub severity: AlertSeverity,
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub connection_id: Option<String>,
+    #[serde(default)]
+    pub threshold_percent: Option<f64>,
+    #[serde(default)]
+    pub clear_threshold_percent: Option<f64>,
+    #[serde(default)]
+    pub channel_ids: Vec<String>,
+    #[serde(default)]
+    pub cooldown_secs: u64,
+    #[serde(default)]
+    pub renotify_secs: u64,
+}
+
+/// Emitted as `RULE_ALERT_EVENT` when a rule fires
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleAlertEvent {
+    pub rule_id: String,
+    /// Alert instance opened by this trigger
+    pub instance_id: String,
+    pub rule_name: String,
+    pub kind: EventRuleKind,
+    pub severity: AlertSeverity,
+    pub connection_id: Option<String>,
+    /// ID of the VM, pool, network, connection or task the event is about
+    pub subject_id: String,
+    pub subject_name: String,
+    pub message: String,
+    pub timestamp: i64,
+}
+
+/// Something a rule can match, extracted from a backend event or a poll
+struct Occurrence {
+    kind: EventRuleKind,
+    /// Whether the condition started (`true`) or cleared (`false`)
+    holds: bool,
+    connection_id: Option<String>,
+    vm_id: Option<String>,
+    subject_id: String,
+    subject_name: String,
+    /// Pool usage for `StoragePoolUsage`
+    value: Option<f64>,
+    message: String,
+}
+
+/// Evaluates event rules against backend events and polled host state
+pub struct AlertRuleService {
+    rules_dir: PathBuf,
+    notifications: Arc<NotificationService>,
+    events: Arc<EventService>,
+    /// Open instances make a full pool or a hung agent report once rather than every poll
+    history: Arc<AlertHistoryService>,
+}
+
+impl AlertRuleService {
+    /// Create a new AlertRuleService instance
+    pub fn new(
+        notifications: Arc<NotificationService>,
+        events: Arc<EventService>,
+        history: Arc<AlertHistoryService>,
+    ) -> Result<Self, AppError> {
+        let config_dir = dirs::config_dir()
+            .ok_or_else(|| AppError::AlertError("Could not determine config directory".to_string()))?;
+        let rules_dir = config_dir.join("kvm-manager").join("alert-rules");
+
+        fs::create_dir_all(&rules_dir)
+            .map_err(|e| AppError::AlertError(format!("Failed to create alert rules directory: {}", e)))?;
+
+        Ok(Self {
+            rules_dir,
+            notifications,
+            events,
+            history,
+        })
+    }
+
+    /// Create a new event rule
+    pub fn create_rule(&self, request: CreateEventRuleRequest) -> Result<EventAlertRule, AppError> {
+        if request.kind == EventRuleKind::StoragePoolUsage {
+            match request.threshold_percent {
+                Some(percent) if (0.0..=100.0).contains(&percent) => {}
+                _ => {
+                    return Err(AppError::AlertError(
+                        "Storage pool rules need a threshold between 0 and 100".to_string(),
+                    ))
+                }
+            }
+
+            if request.clear_threshold_percent
+                .is_some_and(|clear| request.threshold_percent.is_some_and(|threshold| clear > threshold))
+            {
+                return Err(AppError::AlertError(
+                    "Clear threshold must not be above the alert threshold".to_string(),
+                ));
+            }
+        }
+
+        let rule = EventAlertRule {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: request.name,
+            kind: request.kind,
+            severity: request.severity,
+            enabled: true,
+            target: request.target.filter(|t| !t.is_empty()),
+            connection_id: request.connection_id.filter(|c| !c.is_empty()),
+            threshold_percent: request.threshold_percent,
+            clear_threshold_percent: request.clear_threshold_percent,
+            channel_ids: request.channel_ids,
+            cooldown_secs: request.cooldown_secs,
+            renotify_secs: request.renotify_secs,
+            last_triggered: None,
+            created_at: chrono::Utc::now().timestamp(),
+        };
+
+        self.save_rule(&rule)?;
+        Ok(rule)
+    }
+
+    /// List all event rules
+    pub fn list_rules(&self) -> Result<Vec<EventAlertRule>, AppError> {
+        let entries = fs::read_dir(&self.rules_dir)
+            .map_err(|e| AppError::AlertError(format!("Failed to read alert rules directory: {}", e)))?;
+
+        let mut rules = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+
+            match self.load_rule(&path) {
+                Ok(rule) => rules.push(rule),
+                Err(e) => tracing::warn!("Failed to load alert rule {:?}: {}", path, e),
+            }
+        }
+
+        rules.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(rules)
+    }
+
+    /// Enable or disable a rule
+    pub fn update_rule_status(&self, id: &str, enabled: bool) -> Result<EventAlertRule, AppError> {
+        let mut rule = self.get_rule(id)?;
+        rule.enabled = enabled;
+        self.save_rule(&rule)?;
+
+        if !enabled {
+            self.history.resolve_source(id)?;
+        }
+        Ok(rule)
+    }
+
+    /// Delete a rule
+    pub fn delete_rule(&self, id: &str) -> Result<(), AppError> {
+        let path = self.rule_path(id);
+        if !path.exists() {
+            return Err(AppError::AlertError(format!("Alert rule not found: {}", id)));
+        }
+
+        fs::remove_file(&path)
+            .map_err(|e| AppError::AlertError(format!("Failed to delete alert rule: {}", e)))?;
+        self.history.resolve_source(id)?;
+        Ok(())
+    }
+
+    /// Match a published backend event against the enabled rules
+    pub fn handle_event(&self, event: &BackendEvent) {
+        let occurrences = Self::occurrences(event);
+        if occurrences.is_empty() {
+            return;
+        }
+
+        let rules = match self.list_rules() {
+            Ok(rules) => rules,
+            Err(e) => {
+                tracing::error!("Failed to load alert rules: {}", e);
+                return;
+            }
+        };
+
+        for occurrence in &occurrences {
+            for rule in rules.iter().filter(|r| Self::matches(r, occurrence)) {
+                self.evaluate(rule, occurrence, occurrence.holds, occurrence.holds);
+            }
+        }
+    }
+
+    /// Start the background task that polls pool usage and guest agents
+    pub fn start_poll_task(self: Arc<Self>, connections: Arc<ConnectionService>) {
+        tracing::info!("Starting alert rule poll task");
+
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(tokio::time::Duration::from_secs(RULE_POLL_INTERVAL_SECS)).await;
+
+                let rules = self.clone();
+                let connections = connections.clone();
+                let pass = tauri::async_runtime::spawn_blocking(move || rules.poll(&connections)).await;
+
+                if let Err(e) = pass {
+                    tracing::error!("Alert rule poll failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Evaluate the polled conditions and send reminders for rules that are still firing
+    fn poll(&self, connections: &ConnectionService) {
+        let rules: Vec<EventAlertRule> = match self.list_rules() {
+            Ok(rules) => rules.into_iter().filter(|r| r.enabled).collect(),
+            Err(e) => {
+                tracing::error!("Failed to load alert rules: {}", e);
+                return;
+            }
+        };
+
+        self.send_reminders(&rules);
+
+        let wants_pools = rules.iter().any(|r| r.kind == EventRuleKind::StoragePoolUsage);
+        // Pinging every VM would flag guests that never had an agent, so only explicit targets are polled
+        let agent_rules: Vec<&EventAlertRule> = rules.iter()
+            .filter(|r| r.kind == EventRuleKind::GuestAgentUnresponsive && r.target.is_some())
+            .collect();
+        if !wants_pools && agent_rules.is_empty() {
+            return;
+        }
+
+        let targets = match connections.get_connected() {
+            Ok(targets) => targets,
+            Err(e) => {
+                tracing::warn!("Skipping alert rule poll, no connection available: {}", e);
+                return;
+            }
+        };
+
+        let mut occurrences = Vec::new();
+        for (connection_id, libvirt) in &targets {
+            if wants_pools {
+                match StorageService::list_storage_pools(libvirt) {
+                    Ok(pools) => {
+                        occurrences.extend(pools.into_iter().filter(|p| p.capacity_bytes > 0).map(|pool| {
+                            let used = pool.capacity_bytes.saturating_sub(pool.available_bytes);
+                            let percent = used as f64 / pool.capacity_bytes as f64 * 100.0;
+                            Occurrence {
+                                kind: EventRuleKind::StoragePoolUsage,
+                                holds: true,
+                                connection_id: Some(connection_id.clone()),
+                                vm_id: None,
+                                subject_id: pool.name.clone(),
+                                message: format!("Storage pool {} is {:.1}% full", pool.name, percent),
+                                subject_name: pool.name,
+                                value: Some(percent),
+                            }
+                        }));
+                    }
+                    Err(e) => tracing::warn!("Failed to list storage pools on {}: {}", connection_id, e),
+                }
+            }
+
+            for rule in &agent_rules {
+                let vm_id = rule.target.as_deref().unwrap_or_default();
+                let Ok(vm) = VmService::get_vm(libvirt, vm_id) else {
+                    continue;
+                };
+                if vm.state != VmState::Running {
+                    continue;
+                }
+
+                // The poll already runs on a blocking thread, so ping synchronously
+                let responsive = GuestAgentService::new().ping_on(Some(libvirt.get_uri()), &vm.name).is_ok();
+                let occurrence = Occurrence {
+                    kind: EventRuleKind::GuestAgentUnresponsive,
+                    holds: !responsive,
+                    connection_id: Some(connection_id.clone()),
+                    vm_id: Some(vm.id.clone()),
+                    subject_id: vm.id.clone(),
+                    subject_name: vm.name.clone(),
+                    value: None,
+                    message: format!("Guest agent in VM {} is not responding", vm.name),
+                };
+                self.evaluate(rule, &occurrence, occurrence.holds, occurrence.holds);
+            }
+        }
+
+        for rule in rules.iter().filter(|r| r.kind == EventRuleKind::StoragePoolUsage) {
+            let threshold = rule.threshold_percent.unwrap_or(100.0);
+            let clear_threshold = rule.clear_threshold_percent.unwrap_or(threshold);
+            for occurrence in occurrences.iter().filter(|o| Self::matches(rule, o)) {
+                let usage = occurrence.value.unwrap_or(0.0);
+                self.evaluate(rule, occurrence, usage >= threshold, usage >= clear_threshold);
+            }
+        }
+    }
+
+    /// Open, remind or resolve the rule's instance for the occurrence's subject.
+    ///
+    /// `fires` decides whether a new instance opens; `still_holds` whether an open
+    /// one stays open, which lets pool rules clear at a lower usage than they fire.
+    fn evaluate(&self, rule: &EventAlertRule, occurrence: &Occurrence, fires: bool, still_holds: bool) {
+        let result = match self.history.find_open(&rule.id, &occurrence.subject_id) {
+            Ok(Some(open)) if !still_holds => self.history.resolve(&open.id, None).map(|resolved| {
+                tracing::info!("Alert rule '{}' resolved for {}", rule.name, occurrence.subject_name);
+                self.notifications.dispatch(Notification::resolved(&resolved), &rule.channel_ids);
+            }),
+            // Repeats of an open condition only count towards reminders
+            Ok(Some(_)) => Ok(()),
+            Ok(None) if fires => match self.cooling_down(rule, &occurrence.subject_id) {
+                Ok(false) => self.fire(rule.clone(), occurrence),
+                Ok(true) => Ok(()),
+                Err(e) => Err(e),
+            },
+            Ok(None) => Ok(()),
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            tracing::error!("Failed to evaluate alert rule {}: {}", rule.id, e);
+        }
+    }
+
+    fn cooling_down(&self, rule: &EventAlertRule, subject_id: &str) -> Result<bool, AppError> {
+        if rule.cooldown_secs == 0 {
+            return Ok(false);
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        Ok(self.history.last_resolved_at(&rule.id, subject_id)?
+            .is_some_and(|resolved| now - resolved < rule.cooldown_secs as i64))
+    }
+
+    /// Re-deliver unacknowledged rule instances whose renotify interval elapsed
+    fn send_reminders(&self, rules: &[EventAlertRule]) {
+        let open = match self.history.list_open() {
+            Ok(open) => open,
+            Err(e) => {
+                tracing::error!("Failed to load open alerts: {}", e);
+                return;
+            }
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        for instance in open.into_iter().filter(|i| i.source == AlertSource::Rule) {
+            let Some(rule) = rules.iter().find(|r| r.id == instance.source_id) else {
+                continue;
+            };
+            if !AlertHistoryService::renotify_due(&instance, rule.renotify_secs, now) {
+                continue;
+            }
+
+            match self.history.mark_notified(&instance.id) {
+                Ok(instance) => self.notifications.dispatch(Notification::reminder(&instance), &rule.channel_ids),
+                Err(e) => tracing::error!("Failed to record reminder for alert {}: {}", instance.id, e),
+            }
+        }
+    }
+
+    /// Translate a backend event into rule occurrences; recoveries (a VM starting, a
+    /// network coming back) produce occurrences that resolve the matching instances
+    fn occurrences(event: &BackendEvent) -> Vec<Occurrence> {
+        match event {
+            BackendEvent::Vm(vm) => {
+                let conditions: Vec<(EventRuleKind, bool, String)> = match &vm.kind {
+                    VmEventKind::Lifecycle { event: LifecycleEvent::Stopped, detail }
+                        if *detail as u32 == sys::VIR_DOMAIN_EVENT_STOPPED_CRASHED
+                            || *detail as u32 == sys::VIR_DOMAIN_EVENT_STOPPED_FAILED =>
+                    {
+                        vec![(EventRuleKind::VmCrashed, true, format!("VM {} stopped unexpectedly", vm.vm_name))]
+                    }
+                    VmEventKind::Lifecycle { event: LifecycleEvent::Crashed, .. } => {
+                        vec![(EventRuleKind::GuestPanic, true, format!("Guest in VM {} panicked", vm.vm_name))]
+                    }
+                    VmEventKind::Watchdog { .. } => {
+                        vec![(EventRuleKind::GuestPanic, true, format!("Watchdog fired in VM {}", vm.vm_name))]
+                    }
+                    VmEventKind::Lifecycle { event: LifecycleEvent::Started, .. } => vec![
+                        (EventRuleKind::VmCrashed, false, format!("VM {} started", vm.vm_name)),
+                        (EventRuleKind::GuestPanic, false, format!("VM {} started", vm.vm_name)),
+                    ],
+                    VmEventKind::AgentLifecycle { connected: false, .. } if vm.state == VmState::Running => {
+                        vec![(EventRuleKind::GuestAgentUnresponsive, true, format!("Guest agent in VM {} disconnected", vm.vm_name))]
+                    }
+                    VmEventKind::AgentLifecycle { connected: true, .. } => {
+                        vec![(EventRuleKind::GuestAgentUnresponsive, false, format!("Guest agent in VM {} connected", vm.vm_name))]
+                    }
+                    _ => Vec::new(),
+                };
+
+                conditions.into_iter().map(|(kind, holds, message)| Occurrence {
+                    kind,
+                    holds,
+                    connection_id: Some(vm.connection_id.clone()),
+                    vm_id: Some(vm.vm_id.clone()),
+                    subject_id: vm.vm_id.clone(),
+                    subject_name: vm.vm_name.clone(),
+                    value: None,
+                    message,
+                }).collect()
+            }
+            BackendEvent::Network(network)
+                if matches!(network.event, ResourceLifecycleEvent::Stopped | ResourceLifecycleEvent::Started) =>
+            {
+                let holds = network.event == ResourceLifecycleEvent::Stopped;
+                vec![Occurrence {
+                    kind: EventRuleKind::NetworkInactive,
+                    holds,
+                    connection_id: Some(network.connection_id.clone()),
+                    vm_id: None,
+                    subject_id: network.network_name.clone(),
+                    subject_name: network.network_name.clone(),
+                    value: None,
+                    message: if holds {
+                        format!("Network {} became inactive", network.network_name)
+                    } else {
+                        format!("Network {} is active again", network.network_name)
+                    },
+                }]
+            }
+            BackendEvent::Connection(connection)
+                if matches!(connection.state, ConnectionState::Disconnected | ConnectionState::Connected) =>
+            {
+                let holds = connection.state == ConnectionState::Disconnected;
+                vec![Occurrence {
+                    kind: EventRuleKind::ConnectionLost,
+                    holds,
+                    connection_id: Some(connection.connection_id.clone()),
+                    vm_id: None,
+                    subject_id: connection.connection_id.clone(),
+                    subject_name: connection.connection_id.clone(),
+                    value: None,
+                    message: if holds {
+                        format!(
+                            "Lost libvirt connection {}: {}",
+                            connection.connection_id,
+                            connection.error.as_deref().unwrap_or("unknown error")
+                        )
+                    } else {
+                        format!("Reconnected to {}", connection.connection_id)
+                    },
+                }]
+            }
+            BackendEvent::TaskFailed(failure) => vec![Occurrence {
+                kind: match failure.kind {
+                    TaskKind::Backup => EventRuleKind::BackupFailed,
+                    TaskKind::BackupVerify => EventRuleKind::BackupVerifyFailed,
+                    TaskKind::Schedule => EventRuleKind::ScheduleFailed,
+                    TaskKind::SnapshotPrune => EventRuleKind::SnapshotPruneFailed,
+                },
+                holds: true,
+                connection_id: None,
+                vm_id: failure.vm_id.clone(),
+                subject_id: failure.id.clone(),
+                subject_name: failure.name.clone(),
+                value: None,
+                message: match &failure.vm_id {
+                    Some(vm_id) => format!("{} failed for VM {}: {}", failure.name, vm_id, failure.error),
+                    None => format!("{} failed: {}", failure.name, failure.error),
+                },
+            }],
+            _ => Vec::new(),
+        }
+    }
+
+    fn matches(rule: &EventAlertRule, occurrence: &Occurrence) -> bool {
+        rule.enabled
+            && rule.kind == occurrence.kind
+            && rule.target.as_ref().is_none_or(|t| *t == occurrence.subject_id)
+            && rule.connection_id.as_ref().is_none_or(|c| occurrence.connection_id.as_ref() == Some(c))
+    }
+
+    /// Open an instance for a triggered rule, then emit and deliver it
+    fn fire(&self, mut rule: EventAlertRule, occurrence: &Occurrence) -> Result<(), AppError> {
+        let now = chrono::Utc::now().timestamp();
+        rule.last_triggered = Some(now);
+        self.save_rule(&rule)?;
+
+        tracing::warn!("Alert rule '{}' triggered: {}", rule.name, occurrence.message);
+
+        let instance = self.history.open(AlertInstance {
+            id: uuid::Uuid::new_v4().to_string(),
+            source: AlertSource::Rule,
+            source_id: rule.id.clone(),
+            name: rule.name.clone(),
+            severity: rule.severity.clone(),
+            vm_id: occurrence.vm_id.clone(),
+            subject_id: occurrence.subject_id.clone(),
+            subject_name: occurrence.subject_name.clone(),
+            state: AlertState::Firing,
+            message: occurrence.message.clone(),
+            trigger_value: occurrence.value,
+            fired_at: now,
+            last_notified_at: now,
+            notify_count: 1,
+            acknowledged_at: None,
+            acknowledged_by: None,
+            resolved_at: None,
+            resolved_by: None,
+        })?;
+
+        let event = RuleAlertEvent {
+            rule_id: rule.id,
+            instance_id: instance.id,
+            rule_name: rule.name,
+            kind: rule.kind,
+            severity: rule.severity,
+            connection_id: occurrence.connection_id.clone(),
+            subject_id: occurrence.subject_id.clone(),
+            subject_name: occurrence.subject_name.clone(),
+            message: occurrence.message.clone(),
+            timestamp: now,
+        };
+
+        self.notifications.dispatch(Notification::from_rule(&event), &rule.channel_ids);
+        self.events.emit(RULE_ALERT_EVENT, event);
+        Ok(())
+    }
+
+    fn get_rule(&self, id: &str) -> Result<EventAlertRule, AppError> {
+        let path = self.rule_path(id);
+        if !path.exists() {
+            return Err(AppError::AlertError(format!("Alert rule not found: {}", id)));
+        }
+        self.load_rule(&path)
+    }
+
+    fn rule_path(&self, id: &str) -> PathBuf {
+        self.rules_dir.join(format!("{}.json", id))
+    }
+
+    fn save_rule(&self, rule: &EventAlertRule) -> Result<(), AppError> {
+        let json = serde_json::to_string_pretty(rule)
+            .map_err(|e| AppError::AlertError(format!("Failed to serialize alert rule: {}", e)))?;
+
+        fs::write(self.rule_path(&rule.id), json)
+            .map_err(|e| AppError::AlertError(format!("Failed to write alert rule file: {}", e)))
+    }
+
+    fn load_rule(&self, path: &PathBuf) -> Result<EventAlertRule, AppError> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| AppError::AlertError(format!("Failed to read alert rule file: {}", e)))?;
+
+        serde_json::from_str(&json)
+            .map_err(|e| AppError::AlertError(format!("Failed to parse alert rule file: {}", e)))
+    }
+}
@@ -1,7 +1,25 @@
+use crate::models::vm::VmStats;
+use crate::services::alert_history_service::{AlertHistoryService, AlertInstance, AlertSource, AlertState};
+use crate::services::connection_service::ConnectionService;
+use crate::services::event_service::EventService;
+use crate::services::libvirt::LibvirtService;
+use crate::services::metrics_service::{HostSnapshot, MetricsService};
+use crate::services::notification_service::{Notification, NotificationService};
+use crate::services::vm_service::VmService;
 use crate::utils::error::AppError;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Tauri event name for alerts raised by the background evaluator
+pub const ALERT_EVENT: &str = "alert-triggered";
+
+/// Seconds between evaluator passes; `consecutive_checks` counts these passes
+const EVALUATOR_INTERVAL_SECS: u64 = 15;
+
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
 
 /// Alert threshold type
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -11,6 +29,28 @@ pub enum ThresholdType {
     Memory,
     Disk,
     Network,
+    /// Host swap used, percent
+    Swap,
+    /// Host 1-minute load average
+    Load,
+    /// Host hugepages in use, percent
+    Hugepages,
+    /// Storage pool allocation, percent of capacity
+    Storage,
+}
+
+/// What a resource alert watches
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertScope {
+    #[default]
+    Vm,
+    /// The hypervisor of `connection_id`
+    Host,
+    /// Storage pool `target` on `connection_id`
+    Pool,
+    /// Virtual network `target` on `connection_id`
+    Network,
 }
 
 /// Alert severity level
@@ -28,15 +68,38 @@ pub enum AlertSeverity {
 pub struct ResourceAlert {
     pub id: String,
     pub name: String,
+    #[serde(default)]
+    pub scope: AlertScope,
+    /// VM the alert watches; empty for host, pool and network alerts
+    #[serde(default)]
     pub vm_id: String,
+    /// Host the alert watches, or the host of its pool or network
+    #[serde(default)]
+    pub connection_id: Option<String>,
+    /// Pool or network name
+    #[serde(default)]
+    pub target: Option<String>,
     pub threshold_type: ThresholdType,
-    pub threshold_value: f64, // Percentage (0-100) or absolute value
+    pub threshold_value: f64, // Percentage (0-100) for CPU/memory, MB/s for disk/network
     pub severity: AlertSeverity,
     pub enabled: bool,
     pub consecutive_checks: u32, // Number of consecutive checks before alerting
     pub current_trigger_count: u32,
     pub last_triggered: Option<i64>,
     pub created_at: i64,
+    /// Notification channels this alert is delivered to
+    #[serde(default)]
+    pub channel_ids: Vec<String>,
+    /// Value the metric must drop below before a firing alert resolves;
+    /// `None` clears at `threshold_value`
+    #[serde(default)]
+    pub clear_threshold: Option<f64>,
+    /// Seconds after a resolution during which the alert will not fire again
+    #[serde(default)]
+    pub cooldown_secs: u64,
+    /// Seconds between reminders while the alert fires unacknowledged; 0 notifies once
+    #[serde(default)]
+    pub renotify_secs: u64,
 }
 
 /// Request to create an alert
@@ -44,11 +107,26 @@ pub struct ResourceAlert {
 #[serde(rename_all = "camelCase")]
 pub struct CreateAlertRequest {
     pub name: String,
+    #[serde(default)]
+    pub scope: AlertScope,
+    #[serde(default)]
     pub vm_id: String,
+    #[serde(default)]
+    pub connection_id: Option<String>,
+    #[serde(default)]
+    pub target: Option<String>,
     pub threshold_type: ThresholdType,
     pub threshold_value: f64,
     pub severity: AlertSeverity,
     pub consecutive_checks: u32,
+    #[serde(default)]
+    pub channel_ids: Vec<String>,
+    #[serde(default)]
+    pub clear_threshold: Option<f64>,
+    #[serde(default)]
+    pub cooldown_secs: u64,
+    #[serde(default)]
+    pub renotify_secs: u64,
 }
 
 /// Alert event when threshold is exceeded
@@ -56,8 +134,13 @@ pub struct CreateAlertRequest {
 #[serde(rename_all = "camelCase")]
 pub struct AlertEvent {
     pub alert_id: String,
+    /// Alert instance opened by this trigger
+    pub instance_id: String,
     pub alert_name: String,
+    pub scope: AlertScope,
     pub vm_id: String,
+    /// Human-readable description of what breached, e.g. "host local"
+    pub subject: String,
     pub threshold_type: ThresholdType,
     pub threshold_value: f64,
     pub current_value: f64,
@@ -65,14 +148,23 @@ pub struct AlertEvent {
     pub timestamp: i64,
 }
 
-/// Alert service for managing resource alerts
+/// Alert service for managing and evaluating resource alerts
 pub struct AlertService {
     alerts_dir: PathBuf,
+    /// Previous stats sample per VM, used to turn cumulative I/O counters into rates
+    samples: Mutex<HashMap<String, VmStats>>,
+    notifications: Arc<NotificationService>,
+    history: Arc<AlertHistoryService>,
+    metrics: Arc<MetricsService>,
 }
 
 impl AlertService {
     /// Create a new AlertService instance
-    pub fn new() -> Result<Self, AppError> {
+    pub fn new(
+        notifications: Arc<NotificationService>,
+        history: Arc<AlertHistoryService>,
+        metrics: Arc<MetricsService>,
+    ) -> Result<Self, AppError> {
         let alerts_dir = Self::get_alerts_dir()?;
 
         // Create alerts directory if it doesn't exist
@@ -81,7 +173,13 @@ impl AlertService {
                 .map_err(|e| AppError::AlertError(format!("Failed to create alerts directory: {}", e)))?;
         }
 
-        Ok(Self { alerts_dir })
+        Ok(Self {
+            alerts_dir,
+            samples: Mutex::new(HashMap::new()),
+            notifications,
+            history,
+            metrics,
+        })
     }
 
     /// Get the alerts directory path
@@ -93,11 +191,31 @@ impl AlertService {
     }
 
     /// Create a new resource alert
-    pub fn create_alert(&self, request: CreateAlertRequest) -> Result<ResourceAlert, AppError> {
+    pub fn create_alert(&self, mut request: CreateAlertRequest) -> Result<ResourceAlert, AppError> {
+        Self::validate_target(&mut request)?;
+
         // Validate threshold value
-        if request.threshold_value < 0.0 || request.threshold_value > 100.0 {
+        match request.threshold_type {
+            ThresholdType::Cpu | ThresholdType::Memory | ThresholdType::Swap
+            | ThresholdType::Hugepages | ThresholdType::Storage => {
+                if request.threshold_value < 0.0 || request.threshold_value > 100.0 {
+                    return Err(AppError::AlertError(
+                        "Threshold value must be between 0 and 100".to_string(),
+                    ));
+                }
+            }
+            ThresholdType::Disk | ThresholdType::Network | ThresholdType::Load => {
+                if request.threshold_value < 0.0 {
+                    return Err(AppError::AlertError(
+                        "Threshold must not be negative".to_string(),
+                    ));
+                }
+            }
+        }
+
+        if request.clear_threshold.is_some_and(|clear| clear > request.threshold_value) {
             return Err(AppError::AlertError(
-                "Threshold value must be between 0 and 100".to_string(),
+                "Clear threshold must not be above the alert threshold".to_string(),
             ));
         }
 
@@ -107,7 +225,10 @@ impl AlertService {
         let alert = ResourceAlert {
             id: id.clone(),
             name: request.name,
+            scope: request.scope,
             vm_id: request.vm_id,
+            connection_id: request.connection_id,
+            target: request.target,
             threshold_type: request.threshold_type,
             threshold_value: request.threshold_value,
             severity: request.severity,
@@ -116,6 +237,10 @@ impl AlertService {
             current_trigger_count: 0,
             last_triggered: None,
             created_at: now,
+            channel_ids: request.channel_ids,
+            clear_threshold: request.clear_threshold,
+            cooldown_secs: request.cooldown_secs,
+            renotify_secs: request.renotify_secs,
         };
 
         // Save alert to file
@@ -155,6 +280,52 @@ impl AlertService {
         Ok(alerts)
     }
 
+    /// Check that the alert's target and metric fit its scope
+    fn validate_target(request: &mut CreateAlertRequest) -> Result<(), AppError> {
+        let supported: &[ThresholdType] = match request.scope {
+            AlertScope::Vm => &[ThresholdType::Cpu, ThresholdType::Memory, ThresholdType::Disk, ThresholdType::Network],
+            AlertScope::Host => &[
+                ThresholdType::Cpu, ThresholdType::Memory, ThresholdType::Swap,
+                ThresholdType::Load, ThresholdType::Hugepages,
+            ],
+            AlertScope::Pool => &[ThresholdType::Storage],
+            AlertScope::Network => &[ThresholdType::Network],
+        };
+        if !supported.contains(&request.threshold_type) {
+            return Err(AppError::AlertError(format!(
+                "{:?} alerts cannot watch {:?}",
+                request.scope, request.threshold_type
+            )));
+        }
+
+        match request.scope {
+            AlertScope::Vm => {
+                if request.vm_id.is_empty() {
+                    return Err(AppError::AlertError("VM alerts need a VM".to_string()));
+                }
+                request.connection_id = None;
+                request.target = None;
+            }
+            AlertScope::Host | AlertScope::Pool | AlertScope::Network => {
+                request.vm_id.clear();
+                if request.connection_id.as_deref().is_none_or(str::is_empty) {
+                    request.connection_id = Some("local".to_string());
+                }
+                if request.scope == AlertScope::Host {
+                    request.target = None;
+                } else if request.target.as_deref().is_none_or(str::is_empty) {
+                    return Err(AppError::AlertError(format!(
+                        "{:?} alerts need a {} name",
+                        request.scope,
+                        if request.scope == AlertScope::Pool { "pool" } else { "network" }
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Get an alert by ID
     pub fn get_alert(&self, id: &str) -> Result<ResourceAlert, AppError> {
         let path = self.alerts_dir.join(format!("{}.json", id));
@@ -171,15 +342,24 @@ impl AlertService {
         let mut alert = self.get_alert(id)?;
         alert.enabled = enabled;
 
-        // Reset trigger count when disabling
+        // Reset trigger count and close the open instance when disabling
         if !enabled {
             alert.current_trigger_count = 0;
+            self.history.resolve_source(id)?;
         }
 
         self.save_alert(&alert)?;
         Ok(alert)
     }
 
+    /// Select the notification channels an alert is delivered to
+    pub fn set_alert_channels(&self, id: &str, channel_ids: Vec<String>) -> Result<ResourceAlert, AppError> {
+        let mut alert = self.get_alert(id)?;
+        alert.channel_ids = channel_ids;
+        self.save_alert(&alert)?;
+        Ok(alert)
+    }
+
     /// Delete an alert
     pub fn delete_alert(&self, id: &str) -> Result<(), AppError> {
         let path = self.alerts_dir.join(format!("{}.json", id));
@@ -190,6 +370,7 @@ impl AlertService {
 
         fs::remove_file(&path)
             .map_err(|e| AppError::AlertError(format!("Failed to delete alert: {}", e)))?;
+        self.history.resolve_source(id)?;
 
         Ok(())
     }
@@ -206,48 +387,302 @@ impl AlertService {
         alert_id: &str,
         current_value: f64,
     ) -> Result<Option<AlertEvent>, AppError> {
-        let mut alert = self.get_alert(alert_id)?;
+        let alert = self.get_alert(alert_id)?;
+        self.apply_value(alert, current_value)
+    }
 
+    /// Count a sampled value against an alert.
+    ///
+    /// Without an open instance, `consecutive_checks` breaches fire a new one unless
+    /// the alert is cooling down. With an open instance, the value either clears it
+    /// (below `clear_threshold`) or may trigger a reminder.
+    fn apply_value(
+        &self,
+        mut alert: ResourceAlert,
+        current_value: f64,
+    ) -> Result<Option<AlertEvent>, AppError> {
         if !alert.enabled {
             return Ok(None);
         }
 
+        let now = chrono::Utc::now().timestamp();
+        let (subject_id, subject) = Self::subject(&alert);
+
+        if let Some(open) = self.history.find_open(&alert.id, &subject_id)? {
+            let clear_threshold = alert.clear_threshold.unwrap_or(alert.threshold_value);
+            if current_value < clear_threshold {
+                let resolved = self.history.resolve(&open.id, None)?;
+                tracing::info!("Alert '{}' resolved for {}", alert.name, subject);
+                self.notifications.dispatch(Notification::resolved(&resolved), &alert.channel_ids);
+            } else if AlertHistoryService::renotify_due(&open, alert.renotify_secs, now) {
+                let instance = self.history.mark_notified(&open.id)?;
+                self.notifications.dispatch(Notification::reminder(&instance), &alert.channel_ids);
+            }
+            return Ok(None);
+        }
+
         // Check if threshold is exceeded
         let exceeded = current_value >= alert.threshold_value;
 
-        if exceeded {
-            alert.current_trigger_count += 1;
+        if !exceeded {
+            // Reset trigger count if threshold not exceeded
+            if alert.current_trigger_count > 0 {
+                alert.current_trigger_count = 0;
+                self.save_alert(&alert)?;
+            }
+            return Ok(None);
+        }
+
+        alert.current_trigger_count += 1;
 
-            // Trigger alert if consecutive checks threshold is met
-            if alert.current_trigger_count >= alert.consecutive_checks {
-                let now = chrono::Utc::now().timestamp();
-                alert.last_triggered = Some(now);
-                alert.current_trigger_count = 0; // Reset after triggering
+        let cooling_down = alert.cooldown_secs > 0
+            && self.history.last_resolved_at(&alert.id, &subject_id)?
+                .is_some_and(|resolved| now - resolved < alert.cooldown_secs as i64);
 
-                self.save_alert(&alert)?;
+        // Trigger alert if consecutive checks threshold is met
+        if alert.current_trigger_count < alert.consecutive_checks || cooling_down {
+            self.save_alert(&alert)?;
+            return Ok(None);
+        }
 
-                return Ok(Some(AlertEvent {
-                    alert_id: alert.id,
-                    alert_name: alert.name,
-                    vm_id: alert.vm_id,
-                    threshold_type: alert.threshold_type,
-                    threshold_value: alert.threshold_value,
-                    current_value,
-                    severity: alert.severity,
-                    timestamp: now,
-                }));
+        alert.last_triggered = Some(now);
+        alert.current_trigger_count = 0; // Reset after triggering
+        self.save_alert(&alert)?;
+
+        let event = AlertEvent {
+            alert_id: alert.id.clone(),
+            instance_id: uuid::Uuid::new_v4().to_string(),
+            alert_name: alert.name.clone(),
+            scope: alert.scope.clone(),
+            vm_id: alert.vm_id.clone(),
+            subject: subject.clone(),
+            threshold_type: alert.threshold_type,
+            threshold_value: alert.threshold_value,
+            current_value,
+            severity: alert.severity.clone(),
+            timestamp: now,
+        };
+        let notification = Notification::from_alert(&event);
+
+        self.history.open(AlertInstance {
+            id: event.instance_id.clone(),
+            source: AlertSource::Resource,
+            source_id: alert.id,
+            name: alert.name,
+            severity: alert.severity,
+            vm_id: (alert.scope == AlertScope::Vm).then_some(alert.vm_id),
+            subject_id,
+            subject_name: subject,
+            state: AlertState::Firing,
+            message: notification.message.clone(),
+            trigger_value: Some(current_value),
+            fired_at: now,
+            last_notified_at: now,
+            notify_count: 1,
+            acknowledged_at: None,
+            acknowledged_by: None,
+            resolved_at: None,
+            resolved_by: None,
+        })?;
+
+        self.notifications.dispatch(notification, &alert.channel_ids);
+        Ok(Some(event))
+    }
+
+    /// History key and display name of what an alert watches
+    fn subject(alert: &ResourceAlert) -> (String, String) {
+        let connection = alert.connection_id.as_deref().unwrap_or("local");
+        let target = alert.target.as_deref().unwrap_or_default();
+        match alert.scope {
+            AlertScope::Vm => (alert.vm_id.clone(), format!("VM {}", alert.vm_id)),
+            AlertScope::Host => (connection.to_string(), format!("host {}", connection)),
+            AlertScope::Pool => (format!("{}/{}", connection, target), format!("pool {} on {}", target, connection)),
+            AlertScope::Network => (format!("{}/{}", connection, target), format!("network {} on {}", target, connection)),
+        }
+    }
+
+    /// Start the background task that samples VM stats and evaluates enabled alerts
+    pub fn start_evaluator_task(self: Arc<Self>, connections: Arc<ConnectionService>, events: Arc<EventService>) {
+        tracing::info!("Starting alert evaluator task");
+
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(tokio::time::Duration::from_secs(EVALUATOR_INTERVAL_SECS)).await;
+
+                // Stats collection shells out to virsh and the guest agent, keep it off the async runtime
+                let alerts = self.clone();
+                let connections = connections.clone();
+                let events = events.clone();
+                let pass = tauri::async_runtime::spawn_blocking(move || {
+                    alerts.evaluate_alerts(&connections, &events)
+                }).await;
+
+                if let Err(e) = pass {
+                    tracing::error!("Alert evaluator pass failed: {}", e);
+                }
             }
+        });
+    }
 
-            self.save_alert(&alert)?;
-        } else {
-            // Reset trigger count if threshold not exceeded
-            if alert.current_trigger_count > 0 {
-                alert.current_trigger_count = 0;
-                self.save_alert(&alert)?;
+    /// Sample every VM that has enabled alerts once and evaluate its alerts
+    fn evaluate_alerts(&self, connections: &ConnectionService, events: &EventService) {
+        let alerts = match self.list_alerts() {
+            Ok(alerts) => alerts,
+            Err(e) => {
+                tracing::error!("Failed to load alerts: {}", e);
+                return;
+            }
+        };
+
+        let mut by_vm: HashMap<String, Vec<ResourceAlert>> = HashMap::new();
+        let mut host_alerts = Vec::new();
+        for alert in alerts.into_iter().filter(|a| a.enabled) {
+            if alert.scope == AlertScope::Vm {
+                by_vm.entry(alert.vm_id.clone()).or_default().push(alert);
+            } else {
+                host_alerts.push(alert);
             }
         }
 
-        Ok(None)
+        // Host, pool and network alerts read the metrics collector's latest sample
+        for alert in host_alerts {
+            let connection_id = alert.connection_id.clone().unwrap_or_else(|| "local".to_string());
+            let Some(snapshot) = self.metrics.latest_host_snapshot(&connection_id) else {
+                continue;
+            };
+            let Some(value) = Self::measure_host(&alert, &snapshot) else {
+                continue;
+            };
+            self.evaluate_value(alert, value, events);
+        }
+
+        // Drop samples of VMs that no longer have enabled alerts so rates restart cleanly
+        if let Ok(mut samples) = self.samples.lock() {
+            samples.retain(|vm_id, _| by_vm.contains_key(vm_id));
+        }
+
+        if by_vm.is_empty() {
+            return;
+        }
+
+        let targets = match connections.get_connected() {
+            Ok(targets) => targets,
+            Err(e) => {
+                tracing::warn!("Skipping alert evaluation, no connection available: {}", e);
+                return;
+            }
+        };
+
+        for (vm_id, alerts) in by_vm {
+            let Some(stats) = Self::sample_vm(&targets, &vm_id) else {
+                continue;
+            };
+
+            let previous = match self.samples.lock() {
+                Ok(mut samples) => samples.insert(vm_id.clone(), stats.clone()),
+                Err(_) => None,
+            };
+
+            for alert in alerts {
+                let Some(value) = Self::measure(&alert.threshold_type, &stats, previous.as_ref()) else {
+                    continue;
+                };
+
+                self.evaluate_value(alert, value, events);
+            }
+        }
+    }
+
+    /// Apply a measured value and emit the alert if it fired
+    fn evaluate_value(&self, alert: ResourceAlert, value: f64, events: &EventService) {
+        let alert_id = alert.id.clone();
+        match self.apply_value(alert, value) {
+            Ok(Some(event)) => {
+                tracing::warn!(
+                    "Alert '{}' triggered for {}: {:.1} >= {:.1}",
+                    event.alert_name, event.subject, event.current_value, event.threshold_value
+                );
+                events.emit(ALERT_EVENT, event);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::error!("Failed to evaluate alert {}: {}", alert_id, e),
+        }
+    }
+
+    /// Derive a host, pool or network alert's value from the latest host sample
+    fn measure_host(alert: &ResourceAlert, snapshot: &HostSnapshot) -> Option<f64> {
+        let host = &snapshot.host;
+        let percent = |used: u64, total: u64| (total > 0).then(|| used as f64 / total as f64 * 100.0);
+        let target = alert.target.as_deref();
+
+        match (&alert.scope, &alert.threshold_type) {
+            (AlertScope::Host, ThresholdType::Cpu) => host.cpu_usage,
+            (AlertScope::Host, ThresholdType::Memory) => {
+                percent(host.memory_total_mb.saturating_sub(host.memory_free_mb), host.memory_total_mb)
+            }
+            (AlertScope::Host, ThresholdType::Swap) => {
+                let total = host.swap_total_mb?;
+                percent(total.saturating_sub(host.swap_free_mb?), total)
+            }
+            (AlertScope::Host, ThresholdType::Load) => host.load_1,
+            (AlertScope::Host, ThresholdType::Hugepages) => {
+                let total = host.hugepages_total?;
+                percent(total.saturating_sub(host.hugepages_free?), total)
+            }
+            (AlertScope::Pool, ThresholdType::Storage) => snapshot.pools.iter()
+                .find(|p| Some(p.pool_name.as_str()) == target)
+                .and_then(|p| percent(p.allocation_bytes, p.capacity_bytes)),
+            (AlertScope::Network, ThresholdType::Network) => snapshot.networks.iter()
+                .find(|n| Some(n.network_name.as_str()) == target)
+                .map(|n| (n.rx_bytes_per_sec + n.tx_bytes_per_sec) / BYTES_PER_MB),
+            _ => None,
+        }
+    }
+
+    /// Collect stats for a VM from whichever connected host defines it.
+    ///
+    /// VMs are looked up by UUID and their stats read through that host's own
+    /// connection URI, so a remote VM is never measured against a same-named
+    /// domain on the local host.
+    fn sample_vm(targets: &[(String, Arc<LibvirtService>)], vm_id: &str) -> Option<VmStats> {
+        for (connection_id, libvirt) in targets {
+            match VmService::get_vm_stats(libvirt, vm_id) {
+                Ok(stats) => return Some(stats),
+                Err(AppError::VmNotFound(_)) => continue,
+                Err(e) => {
+                    tracing::warn!("Failed to sample stats for VM {} on {}: {}", vm_id, connection_id, e);
+                    return None;
+                }
+            }
+        }
+        None
+    }
+
+    /// Derive the value an alert compares against from the latest sample.
+    ///
+    /// Disk and network are rates (MB/s, read+write and rx+tx combined) and need a
+    /// previous sample; `None` means there is nothing to compare yet.
+    fn measure(threshold_type: &ThresholdType, stats: &VmStats, previous: Option<&VmStats>) -> Option<f64> {
+        match threshold_type {
+            ThresholdType::Cpu => Some(stats.cpu_usage_percent),
+            ThresholdType::Memory => (stats.memory_available_mb > 0)
+                .then(|| stats.memory_used_mb as f64 / stats.memory_available_mb as f64 * 100.0),
+            ThresholdType::Disk => {
+                Self::rate(previous?, stats, |s| s.disk_read_bytes + s.disk_write_bytes)
+            }
+            ThresholdType::Network => {
+                Self::rate(previous?, stats, |s| s.network_rx_bytes + s.network_tx_bytes)
+            }
+            ThresholdType::Swap | ThresholdType::Load | ThresholdType::Hugepages | ThresholdType::Storage => None,
+        }
+    }
+
+    /// MB/s between two samples of a cumulative byte counter
+    fn rate(previous: &VmStats, current: &VmStats, counter: impl Fn(&VmStats) -> u64) -> Option<f64> {
+        let elapsed = (current.timestamp - previous.timestamp) as f64 / 1000.0;
+        // Counters start over when the VM restarts
+        let delta = counter(current).checked_sub(counter(previous))?;
+        (elapsed > 0.0).then(|| delta as f64 / BYTES_PER_MB / elapsed)
     }
 
     /// Save an alert to disk
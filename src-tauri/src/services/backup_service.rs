@@ -1,8 +1,56 @@
-use crate::services::scheduler_service::{OperationType, ScheduleFrequency, SchedulerService};
-use crate::utils::error::AppError;
+use crate::models::domain_xml::{Disk, DomainXml};
+use crate::models::event::{BackendEvent, TaskFailure, TaskKind};
+use crate::models::job::JobKind;
+use crate::models::quiesce::QuiesceOptions;
+use crate::services::backup_target_service::{BackupTarget, BackupTargetService, RemoteCopy, RemoteStatus, TargetUsage};
+use crate::services::backup_verify_service::{BackupVerification, BackupVerifyService};
+use crate::services::event_service::EventService;
+use crate::services::job_service::{self, JobHandle, JobService};
+use crate::services::libvirt::LibvirtService;
+use crate::services::quiesce_service::{FreezeGuard, QuiesceService};
+use crate::services::scheduler_service::{BackupParams, MissedRunPolicy, OperationParams, OperationType, ScheduleFrequency, SchedulerService};
+use crate::services::vm_service::VmService;
+use crate::utils::error::{map_libvirt_error, AppError};
+use crate::utils::xml::XmlElement;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ffi::CString;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use virt::domain::Domain;
+use virt::sys;
+
+/// Runs kept in a config's history, including failed and pruned ones
+const RUN_HISTORY_LIMIT: usize = 100;
+/// Interval between progress polls of a push-mode backup job
+const BACKUP_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Where libvirt keeps swtpm state, one directory per domain UUID
+pub const SWTPM_STATE_DIR: &str = "/var/lib/libvirt/swtpm";
+/// File name of the run record written into every backup set
+pub const BACKUP_MANIFEST_FILE: &str = "backup.json";
+/// Name prefix of the libvirt checkpoints incremental chains are taken relative to
+const CHECKPOINT_PREFIX: &str = "kvm-manager-backup-";
+/// Connection backups run through; sets are read and written on this host's disk
+pub const BACKUP_CONNECTION: &str = "local";
+
+fn default_target_dir() -> String {
+    "/var/lib/libvirt/backups".to_string()
+}
+
+/// How a backup config copies data
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupMode {
+    /// Every run copies all disks
+    #[default]
+    Full,
+    /// Runs copy the blocks changed since the previous run of the chain
+    Incremental,
+    /// Runs copy the blocks changed since the chain's full backup
+    Differential,
+}
 
 /// Backup configuration
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -13,6 +61,20 @@ pub struct BackupConfig {
     pub vm_id: String,
     pub schedule_id: String, // Link to scheduler service
     pub retention_count: u32, // Number of backups to keep
+    /// Directory backup sets are written to, as `<vm name>/<config id>/<timestamp>`
+    #[serde(default = "default_target_dir")]
+    pub target_dir: String,
+    #[serde(default)]
+    pub mode: BackupMode,
+    /// Incremental or differential runs after which a new full backup starts a chain; 0 for no limit
+    #[serde(default)]
+    pub chain_length: u32,
+    /// Freeze guest filesystems while a live backup starts
+    #[serde(default)]
+    pub quiesce: QuiesceOptions,
+    /// Backup target every set is copied to after it is written to `target_dir`
+    #[serde(default)]
+    pub target_id: Option<String>,
     pub enabled: bool,
     pub last_backup: Option<i64>,
     pub backup_count: u32,
@@ -30,6 +92,168 @@ pub struct CreateBackupRequest {
     pub day_of_week: Option<u8>,
     pub day_of_month: Option<u8>,
     pub retention_count: u32,
+    #[serde(default = "default_target_dir")]
+    pub target_dir: String,
+    #[serde(default)]
+    pub mode: BackupMode,
+    #[serde(default)]
+    pub chain_length: u32,
+    #[serde(default)]
+    pub quiesce: QuiesceOptions,
+    #[serde(default)]
+    pub target_id: Option<String>,
+    /// Connection the VM was listed from; only the local host is supported
+    #[serde(default)]
+    pub connection_id: Option<String>,
+}
+
+/// Outcome of a backup run
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupRunStatus {
+    Completed,
+    Failed,
+    /// Completed, then removed by retention; the stats remain for history
+    Pruned,
+}
+
+/// A disk copied into a backup set
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupDisk {
+    /// Target device in the domain (vda, sdb, ...)
+    pub target: String,
+    /// Image the disk was read from
+    pub source: String,
+    /// Image format of the source
+    pub source_format: Option<String>,
+    /// qcow2 file inside the backup set
+    pub file: String,
+    /// Image in the parent set this file is an overlay of; `None` for a full copy
+    #[serde(default)]
+    pub backing: Option<String>,
+    pub size_bytes: u64,
+}
+
+/// One execution of a backup config
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRun {
+    pub id: String,
+    pub config_id: String,
+    pub vm_id: String,
+    pub vm_name: String,
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub duration_secs: u64,
+    pub status: BackupRunStatus,
+    /// What this restore point holds; only a full point stands on its own
+    #[serde(default)]
+    pub kind: BackupMode,
+    /// Mode of the config when the run was taken; a chain only continues in the same mode
+    #[serde(default)]
+    pub chain_mode: BackupMode,
+    /// ID of the run that started the chain
+    #[serde(default)]
+    pub chain_id: String,
+    /// Run whose set this run's overlays are backed by
+    #[serde(default)]
+    pub parent_run_id: Option<String>,
+    /// Checkpoint the next run of the chain is taken relative to
+    #[serde(default)]
+    pub checkpoint: Option<String>,
+    /// Why a full backup was taken instead of the configured mode
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Whether the VM was running and backed up with a push-mode backup job
+    pub live: bool,
+    /// Whether guest filesystems were frozen when the backup job started
+    #[serde(default)]
+    pub quiesced: bool,
+    /// Directory of the backup set
+    pub path: String,
+    /// Total size of the backup set
+    pub size_bytes: u64,
+    pub disks: Vec<BackupDisk>,
+    /// Disks left out: CD-ROMs, read-only, shareable and network disks
+    #[serde(default)]
+    pub skipped_disks: Vec<String>,
+    /// Copy of the UEFI variable store, relative to the set directory
+    pub nvram: Option<String>,
+    /// Whether the swtpm state directory was copied
+    #[serde(default)]
+    pub tpm: bool,
+    /// Copy of the set on the config's backup target
+    #[serde(default)]
+    pub remote: Option<RemoteCopy>,
+    /// Latest verification of the restore point
+    #[serde(default)]
+    pub verification: Option<BackupVerification>,
+    pub error: Option<String>,
+}
+
+/// A full restore point and the incremental or differential points built on it
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupChain {
+    pub id: String,
+    pub mode: BackupMode,
+    /// Restore points still on disk, oldest first; the first is self-contained
+    pub points: Vec<BackupRun>,
+    pub size_bytes: u64,
+    /// Checkpoint the next run of the chain is taken relative to
+    pub checkpoint: Option<String>,
+}
+
+/// Outcome of importing the catalog of a backup target
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogImport {
+    /// Sets found on the target
+    pub sets: u32,
+    /// Restore points added to this host's catalog
+    pub imported_runs: u32,
+    /// Backup configs recreated for sets whose config is not on this host
+    pub created_configs: u32,
+    /// Sets whose run record could not be read
+    pub skipped_sets: u32,
+}
+
+/// How the next run continues a chain
+#[derive(Default)]
+struct ChainPlan {
+    /// Checkpoint whose dirty bitmap selects the blocks to copy
+    incremental_from: Option<String>,
+    /// Checkpoint created together with the backup
+    new_checkpoint: Option<String>,
+}
+
+lazy_static::lazy_static! {
+    /// Configs with a backup in progress; runs of one config share its run
+    /// list, chain and retention, so they must not overlap
+    static ref RUNNING_BACKUPS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+/// Marks a config's backup as running until dropped
+struct RunningBackup(String);
+
+impl RunningBackup {
+    fn acquire(config: &BackupConfig) -> Result<Self, AppError> {
+        let mut running = RUNNING_BACKUPS.lock()
+            .map_err(|_| AppError::Other("Backup lock poisoned".to_string()))?;
+        if !running.insert(config.id.clone()) {
+            return Err(AppError::Other(format!("A backup of '{}' is already running", config.name)));
+        }
+        Ok(Self(config.id.clone()))
+    }
+}
+
+impl Drop for RunningBackup {
+    fn drop(&mut self) {
+        if let Ok(mut running) = RUNNING_BACKUPS.lock() {
+            running.remove(&self.0);
+        }
+    }
 }
 
 /// Backup service
@@ -60,26 +284,54 @@ impl BackupService {
     }
 
     /// Create a new backup configuration
-    pub fn create_backup_config(&self, request: CreateBackupRequest) -> Result<BackupConfig, AppError> {
+    ///
+    /// `libvirt` is the VM's connection. Sets are written to and verified on
+    /// this host's disk, so VMs on remote hosts are rejected up front rather
+    /// than failing on every scheduled run.
+    pub fn create_backup_config(&self, libvirt: &LibvirtService, request: CreateBackupRequest) -> Result<BackupConfig, AppError> {
+        if !libvirt.is_local() {
+            return Err(AppError::Other(format!(
+                "Backups are only supported for VMs on the local host, not {}", libvirt.get_uri()
+            )));
+        }
+        Domain::lookup_by_uuid_string(libvirt.get_connection(), &request.vm_id)
+            .map_err(|_| AppError::VmNotFound(request.vm_id.clone()))?;
+
         // Validate retention count
         if request.retention_count == 0 {
             return Err(AppError::Other("Retention count must be at least 1".to_string()));
         }
+        if !Path::new(&request.target_dir).is_absolute() {
+            return Err(AppError::Other("Backup target must be an absolute directory path".to_string()));
+        }
+
+        if let Some(target_id) = &request.target_id {
+            BackupTargetService::new()?.get_target(target_id)?;
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
 
         // Create a schedule for the backup using the scheduler service
         let scheduler = SchedulerService::new()?;
         let schedule_request = crate::services::scheduler_service::CreateScheduleRequest {
             name: format!("Backup: {}", request.name),
             vm_id: request.vm_id.clone(),
-            operation: OperationType::Snapshot,
+            connection_id: None,
+            tag_selector: None,
+            parallelism: 1,
+            stagger_secs: 0,
+            operation: OperationType::Backup,
+            params: Some(OperationParams::Backup(BackupParams { backup_config_id: id.clone() })),
             frequency: request.frequency,
             scheduled_time: request.scheduled_time,
             day_of_week: request.day_of_week,
             day_of_month: request.day_of_month,
+            cron_expression: None,
+            timezone: None,
+            missed_run_policy: MissedRunPolicy::RunOnce,
         };
         let schedule = scheduler.create_schedule(schedule_request)?;
 
-        let id = uuid::Uuid::new_v4().to_string();
         let now = chrono::Utc::now().timestamp();
 
         let backup_config = BackupConfig {
@@ -88,6 +340,11 @@ impl BackupService {
             vm_id: request.vm_id,
             schedule_id: schedule.id,
             retention_count: request.retention_count,
+            target_dir: request.target_dir,
+            mode: request.mode,
+            chain_length: request.chain_length,
+            quiesce: request.quiesce,
+            target_id: request.target_id,
             enabled: true,
             last_backup: None,
             backup_count: 0,
@@ -156,6 +413,8 @@ impl BackupService {
     }
 
     /// Delete a backup configuration
+    ///
+    /// Backup sets already written stay in the target directory.
     pub fn delete_backup_config(&self, id: &str) -> Result<(), AppError> {
         let config = self.get_backup_config(id)?;
 
@@ -177,18 +436,930 @@ impl BackupService {
         Ok(configs.into_iter().filter(|c| c.vm_id == vm_id).collect())
     }
 
-    /// Record a backup execution
-    pub fn record_backup(&self, id: &str) -> Result<BackupConfig, AppError> {
+    /// Start `run_backup` as a background job and return the job ID.
+    ///
+    /// Manual and scheduled backups both go through here so every run shows up
+    /// in the job list and can be cancelled. A failed run is reported to the
+    /// alert engine.
+    pub fn start_backup_job(
+        jobs: &Arc<JobService>,
+        events: Arc<EventService>,
+        libvirt: Arc<LibvirtService>,
+        config: BackupConfig,
+    ) -> Result<String, AppError> {
+        let backups = Self::new()?;
+        let description = format!("Back up {}", config.name);
+        Ok(jobs.start(JobKind::BackupVm, description, config.vm_id.clone(), Some(BACKUP_CONNECTION.to_string()), move |job| {
+            let result = backups.run_backup(&libvirt, &config.id, Some(job));
+            match &result {
+                Err(AppError::Cancelled) | Ok(_) => {}
+                Err(e) => events.publish(BackendEvent::TaskFailed(TaskFailure {
+                    kind: TaskKind::Backup,
+                    id: config.id.clone(),
+                    name: config.name.clone(),
+                    vm_id: Some(config.vm_id.clone()),
+                    error: e.to_string(),
+                })),
+            }
+            result
+        }))
+    }
+
+    /// Back up the config's VM now and return the recorded run.
+    ///
+    /// Running VMs are copied with a push-mode backup job, so every disk is
+    /// captured at the same point in time while the guest keeps running;
+    /// stopped VMs are copied with `qemu-img convert`. The set also holds the
+    /// inactive domain XML, the NVRAM file and the swtpm state.
+    ///
+    /// In incremental and differential mode a running VM is copied relative to
+    /// a libvirt checkpoint: only the clusters in the qcow2 dirty bitmap are
+    /// written, into overlays backed by the chain's previous point
+    /// (incremental) or its base (differential). When the chain cannot be
+    /// continued a full backup starts a new one and the reason is noted.
+    ///
+    /// A failed run is recorded and its partial set removed; a completed run
+    /// prunes the oldest restore points beyond `retention_count`. A second run
+    /// of a config while one is in progress is rejected.
+    pub fn run_backup(&self, libvirt: &LibvirtService, id: &str, job: Option<&JobHandle>) -> Result<BackupRun, AppError> {
+        let config = self.get_backup_config(id)?;
+        let _running = RunningBackup::acquire(&config)?;
+        // Re-read under the lock so the run starts from the latest state
         let mut config = self.get_backup_config(id)?;
 
-        let now = chrono::Utc::now().timestamp();
-        config.last_backup = Some(now);
-        config.backup_count += 1;
+        if !libvirt.is_local() {
+            return Err(AppError::Other(format!(
+                "Backups can only be taken on the local host, not {}", libvirt.get_uri()
+            )));
+        }
+
+        let domain = Domain::lookup_by_uuid_string(libvirt.get_connection(), &config.vm_id)
+            .map_err(|_| AppError::VmNotFound(config.vm_id.clone()))?;
+        let vm_name = domain.get_name().map_err(map_libvirt_error)?;
+
+        let started = Instant::now();
+        let started_at = chrono::Utc::now().timestamp();
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+        let set_dir = Path::new(&config.target_dir).join(&vm_name).join(&config.id).join(&timestamp);
+
+        let run_id = uuid::Uuid::new_v4().to_string();
+        let mut run = BackupRun {
+            id: run_id.clone(),
+            config_id: config.id.clone(),
+            vm_id: config.vm_id.clone(),
+            vm_name: vm_name.clone(),
+            started_at,
+            finished_at: started_at,
+            duration_secs: 0,
+            status: BackupRunStatus::Completed,
+            kind: BackupMode::Full,
+            chain_mode: config.mode.clone(),
+            chain_id: run_id,
+            parent_run_id: None,
+            checkpoint: None,
+            note: None,
+            live: false,
+            quiesced: false,
+            path: set_dir.to_string_lossy().to_string(),
+            size_bytes: 0,
+            disks: Vec::new(),
+            skipped_disks: Vec::new(),
+            nvram: None,
+            tpm: false,
+            remote: None,
+            verification: None,
+            error: None,
+        };
+
+        let mut runs = self.list_backup_runs(&config.id)?;
+
+        tracing::info!("Backing up VM {} to {}", vm_name, run.path);
+        let result = Self::write_backup_set(&domain, libvirt.get_uri(), &set_dir, &config, &runs, &mut run, job)
+            .and_then(|()| {
+                refresh_sizes(&mut run);
+                BackupVerifyService::write_manifest(&set_dir, &run, job).map(|_| ())
+            });
+
+        run.finished_at = chrono::Utc::now().timestamp();
+        run.duration_secs = started.elapsed().as_secs();
+
+        match result {
+            Ok(()) => {
+                Self::write_manifest(&set_dir, &run)?;
+                tracing::info!("{:?} backup of VM {} completed: {} bytes in {}s",
+                              run.kind, vm_name, run.size_bytes, run.duration_secs);
+
+                Self::release_checkpoint(&domain, &run, &runs);
+
+                config.last_backup = Some(run.finished_at);
+                config.backup_count += 1;
+                self.save_backup_config(&config)?;
+
+                runs.insert(0, run);
+                Self::prune_runs(&mut runs, config.retention_count);
+                self.save_backup_runs(&config.id, &runs)?;
+                // The local set is complete; a failed upload is retried with the next sync
+                if let Err(e) = self.sync_runs(&config, &mut runs, job) {
+                    tracing::error!("Failed to sync backup target of {}: {}", config.name, e);
+                }
+                Ok(runs.remove(0))
+            }
+            Err(e) => {
+                tracing::error!("Backup of VM {} failed: {}", vm_name, e);
+                if set_dir.exists() {
+                    if let Err(cleanup) = fs::remove_dir_all(&set_dir) {
+                        tracing::warn!("Failed to remove partial backup {}: {}", run.path, cleanup);
+                    }
+                }
+
+                // A checkpoint created with a failed job must not anchor the next run
+                delete_checkpoint(&domain, &checkpoint_name(&run.id));
+
+                run.status = BackupRunStatus::Failed;
+                run.error = Some(e.to_string());
+                runs.insert(0, run);
+                runs.truncate(RUN_HISTORY_LIMIT);
+                self.save_backup_runs(&config.id, &runs)?;
+                Err(e)
+            }
+        }
+    }
 
+    /// Runs of a backup config, newest first
+    pub fn list_backup_runs(&self, config_id: &str) -> Result<Vec<BackupRun>, AppError> {
+        let path = self.runs_path(config_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = fs::read_to_string(&path)
+            .map_err(|e| AppError::Other(format!("Failed to read backup runs: {}", e)))?;
+        serde_json::from_str(&json)
+            .map_err(|e| AppError::Other(format!("Failed to parse backup runs: {}", e)))
+    }
+
+    /// A completed run of a backup config that can be restored from.
+    ///
+    /// If its set, or a set it is backed by, is no longer on this host, it is
+    /// downloaded from the backup target first.
+    pub fn get_backup_point(&self, config_id: &str, run_id: &str, job: Option<&JobHandle>) -> Result<BackupRun, AppError> {
+        let chain = self.point_chain(config_id, run_id)?;
+        let targets = BackupTargetService::new()?;
+        for point in chain.iter().filter(|point| !Path::new(&point.path).is_dir()) {
+            let remote = point.remote.as_ref()
+                .filter(|remote| remote.status == RemoteStatus::Uploaded)
+                .ok_or_else(|| AppError::NotFound(format!("Backup set {}", point.path)))?;
+            let target = targets.get_target(&remote.target_id)?;
+            if let Some(job) = job {
+                job.set_step(format!("Downloading backup set from {}", target.name));
+            }
+            let set_dir = Path::new(&point.path);
+            if let Err(e) = targets.download_set(&target, &remote.key, set_dir, job) {
+                let _ = fs::remove_dir_all(set_dir);
+                return Err(e);
+            }
+            tracing::info!("Downloaded backup set {} from target {}", point.path, target.name);
+        }
+        chain.into_iter()
+            .last()
+            .ok_or_else(|| AppError::NotFound(format!("Backup run {}", run_id)))
+    }
+
+    /// Rebuild the catalog from the sets uploaded to a backup target, so a
+    /// new host can list and restore them.
+    ///
+    /// Every set carries its run record; runs already known are left alone.
+    /// Configs missing on this host are recreated disabled, with a disabled
+    /// schedule, and their sets are downloaded on first restore.
+    pub fn import_catalog(&self, target_id: &str, job: Option<&JobHandle>) -> Result<CatalogImport, AppError> {
+        let targets = BackupTargetService::new()?;
+        let target = targets.get_target(target_id)?;
+        if let Some(job) = job {
+            job.set_step(format!("Listing backup sets on {}", target.name));
+        }
+        let keys = targets.list_sets(&target)?;
+
+        let mut result = CatalogImport { sets: keys.len() as u32, ..Default::default() };
+        let mut found: HashMap<String, Vec<BackupRun>> = HashMap::new();
+        for (index, key) in keys.iter().enumerate() {
+            if let Some(job) = job {
+                job.check_cancelled()?;
+                job.set_step(format!("Reading {}", key));
+                job.set_percent(index as f64 * 100.0 / keys.len() as f64);
+            }
+            let run = targets.read_set_file(&target, key, BACKUP_MANIFEST_FILE)
+                .and_then(|json| serde_json::from_slice::<BackupRun>(&json)
+                    .map_err(|e| AppError::Other(format!("Failed to parse backup manifest: {}", e))));
+            match run {
+                Ok(mut run) => {
+                    run.remote = Some(RemoteCopy {
+                        target_id: target.id.clone(),
+                        key: key.clone(),
+                        status: RemoteStatus::Uploaded,
+                        stored_bytes: 0,
+                        uploaded_at: None,
+                        error: None,
+                    });
+                    found.entry(run.config_id.clone()).or_default().push(run);
+                }
+                Err(e) => {
+                    tracing::warn!("Skipping {} on target {}: {}", key, target.name, e);
+                    result.skipped_sets += 1;
+                }
+            }
+        }
+
+        for (config_id, mut imported) in found {
+            let config = match self.get_backup_config(&config_id) {
+                Ok(config) => config,
+                Err(_) => {
+                    let config = self.recreate_config(&target, &imported)?;
+                    result.created_configs += 1;
+                    config
+                }
+            };
+            let _running = RunningBackup::acquire(&config)?;
+
+            let mut runs = self.list_backup_runs(&config.id)?;
+            imported.retain(|run| !runs.iter().any(|known| known.id == run.id));
+            for run in &mut imported {
+                let set_name = Path::new(&run.path).file_name().unwrap_or_default().to_string_lossy().to_string();
+                run.path = Path::new(&config.target_dir).join(&run.vm_name).join(&config.id).join(set_name)
+                    .to_string_lossy().to_string();
+                // Checkpoints belong to the libvirt of the host that took the run
+                run.checkpoint = None;
+            }
+
+            result.imported_runs += imported.len() as u32;
+            runs.extend(imported);
+            runs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+            self.save_backup_runs(&config.id, &runs)?;
+        }
+
+        tracing::info!(
+            "Imported {} restore point(s) from {} set(s) on target {}",
+            result.imported_runs, result.sets, target.name
+        );
+        Ok(result)
+    }
+
+    /// Config for imported runs whose config is not on this host; disabled until the user reviews it
+    fn recreate_config(&self, target: &BackupTarget, runs: &[BackupRun]) -> Result<BackupConfig, AppError> {
+        let newest = runs.iter()
+            .max_by_key(|run| run.started_at)
+            .ok_or_else(|| AppError::Other("No runs to recreate a backup config from".to_string()))?;
+
+        let scheduler = SchedulerService::new()?;
+        let schedule = scheduler.create_schedule(crate::services::scheduler_service::CreateScheduleRequest {
+            name: format!("Backup: {}", newest.vm_name),
+            vm_id: newest.vm_id.clone(),
+            connection_id: None,
+            tag_selector: None,
+            parallelism: 1,
+            stagger_secs: 0,
+            operation: OperationType::Backup,
+            params: Some(OperationParams::Backup(BackupParams { backup_config_id: newest.config_id.clone() })),
+            frequency: ScheduleFrequency::Daily,
+            scheduled_time: "02:00".to_string(),
+            day_of_week: None,
+            day_of_month: None,
+            cron_expression: None,
+            timezone: None,
+            missed_run_policy: MissedRunPolicy::RunOnce,
+        })?;
+        scheduler.update_schedule_status(&schedule.id, false)?;
+
+        let config = BackupConfig {
+            id: newest.config_id.clone(),
+            name: format!("{} (imported)", newest.vm_name),
+            vm_id: newest.vm_id.clone(),
+            schedule_id: schedule.id,
+            retention_count: runs.len() as u32,
+            target_dir: default_target_dir(),
+            mode: newest.chain_mode.clone(),
+            chain_length: 0,
+            quiesce: QuiesceOptions::default(),
+            target_id: Some(target.id.clone()),
+            enabled: false,
+            last_backup: Some(newest.finished_at),
+            backup_count: runs.len() as u32,
+            created_at: chrono::Utc::now().timestamp(),
+        };
         self.save_backup_config(&config)?;
+        tracing::info!("Recreated backup config {} for VM {} from target {}", config.id, newest.vm_name, target.name);
         Ok(config)
     }
 
+    /// A restore point and the points it is backed by, oldest first
+    pub fn point_chain(&self, config_id: &str, run_id: &str) -> Result<Vec<BackupRun>, AppError> {
+        let runs = self.list_backup_runs(config_id)?;
+        let run = runs.iter()
+            .find(|run| run.id == run_id)
+            .cloned()
+            .ok_or_else(|| AppError::NotFound(format!("Backup run {}", run_id)))?;
+        if run.status != BackupRunStatus::Completed {
+            return Err(AppError::InvalidConfig(format!("Backup run {} is not a restore point", run_id)));
+        }
+
+        // Walk back to the chain's base
+        let mut chain = vec![run];
+        while let Some(parent_id) = chain.last().and_then(|point| point.parent_run_id.clone()) {
+            let parent = runs.iter()
+                .find(|point| point.id == parent_id)
+                .ok_or_else(|| AppError::NotFound(format!("Parent backup run {}", parent_id)))?;
+            chain.push(parent.clone());
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+
+    /// Store the result of verifying a restore point on its run
+    pub fn record_verification(&self, config_id: &str, run_id: &str, verification: &BackupVerification) -> Result<(), AppError> {
+        let mut runs = self.list_backup_runs(config_id)?;
+        let run = runs.iter_mut()
+            .find(|run| run.id == run_id)
+            .ok_or_else(|| AppError::NotFound(format!("Backup run {}", run_id)))?;
+        run.verification = Some(verification.clone());
+        self.save_backup_runs(config_id, &runs)
+    }
+
+    /// Newest completed restore point of a config
+    pub fn latest_point(&self, config_id: &str) -> Result<BackupRun, AppError> {
+        self.list_backup_runs(config_id)?
+            .into_iter()
+            .find(|run| run.status == BackupRunStatus::Completed)
+            .ok_or_else(|| AppError::NotFound(format!("Restore point of backup config {}", config_id)))
+    }
+
+    /// Upload restore points missing from the config's backup target and
+    /// remove pruned ones from it. Returns the updated runs.
+    pub fn sync_backup_target(&self, config_id: &str, job: Option<&JobHandle>) -> Result<Vec<BackupRun>, AppError> {
+        let config = self.get_backup_config(config_id)?;
+        let mut runs = self.list_backup_runs(config_id)?;
+        self.sync_runs(&config, &mut runs, job)?;
+        Ok(runs)
+    }
+
+    /// Space used on a backup target by every config that copies to it
+    pub fn target_usage(&self, target: &BackupTarget) -> Result<TargetUsage, AppError> {
+        let mut usage = TargetUsage {
+            target_id: target.id.clone(),
+            available_bytes: BackupTargetService::new()?.available_bytes(target),
+            ..Default::default()
+        };
+        for config in self.list_backup_configs()? {
+            for run in self.list_backup_runs(&config.id)? {
+                match &run.remote {
+                    Some(remote) if remote.target_id == target.id && remote.status == RemoteStatus::Uploaded => {
+                        usage.sets += 1;
+                        usage.raw_bytes += run.size_bytes;
+                        usage.stored_bytes += remote.stored_bytes;
+                    }
+                    Some(remote) if remote.target_id == target.id && run.status == BackupRunStatus::Completed => {
+                        usage.pending_sets += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(usage)
+    }
+
+    /// Restore point chains of a backup config, newest first
+    pub fn list_backup_chains(&self, config_id: &str) -> Result<Vec<BackupChain>, AppError> {
+        let mut chains: Vec<BackupChain> = Vec::new();
+
+        // Runs are stored newest first; build chains oldest first
+        let completed = self.list_backup_runs(config_id)?
+            .into_iter()
+            .rev()
+            .filter(|run| run.status == BackupRunStatus::Completed);
+        for run in completed {
+            match chains.iter_mut().find(|chain| chain.id == run.chain_id) {
+                Some(chain) => {
+                    chain.size_bytes += run.size_bytes;
+                    chain.checkpoint = run.checkpoint.clone();
+                    chain.points.push(run);
+                }
+                None => chains.push(BackupChain {
+                    id: run.chain_id.clone(),
+                    mode: run.chain_mode.clone(),
+                    size_bytes: run.size_bytes,
+                    checkpoint: run.checkpoint.clone(),
+                    points: vec![run],
+                }),
+            }
+        }
+
+        chains.reverse();
+        Ok(chains)
+    }
+
+    /// Copy the domain's disks and state into `set_dir`, filling in `run`
+    fn write_backup_set(
+        domain: &Domain,
+        uri: &str,
+        set_dir: &Path,
+        config: &BackupConfig,
+        previous: &[BackupRun],
+        run: &mut BackupRun,
+        job: Option<&JobHandle>,
+    ) -> Result<(), AppError> {
+        fs::create_dir_all(set_dir)
+            .map_err(|e| AppError::Other(format!("Failed to create backup directory {}: {}", set_dir.display(), e)))?;
+
+        if let Some(job) = job {
+            job.set_step("Saving domain configuration");
+        }
+        let inactive = domain.get_xml_desc(sys::VIR_DOMAIN_XML_INACTIVE | sys::VIR_DOMAIN_XML_SECURE)
+            .map_err(map_libvirt_error)?;
+        fs::write(set_dir.join("domain.xml"), &inactive)
+            .map_err(|e| AppError::Other(format!("Failed to write domain XML: {}", e)))?;
+
+        // Disk sources come from the live definition, which reflects any active overlays
+        let xml = DomainXml::from_domain(domain)?;
+        let (disks, skipped): (Vec<Disk>, Vec<Disk>) = xml.disks().into_iter().partition(is_backup_candidate);
+        run.skipped_disks = skipped.iter().map(|disk| disk.target_dev.clone()).collect();
+        if disks.is_empty() {
+            return Err(AppError::Other(format!("VM {} has no disks that can be backed up", run.vm_name)));
+        }
+
+        run.disks = disks.iter()
+            .map(|disk| BackupDisk {
+                target: disk.target_dev.clone(),
+                source: disk.source_path().unwrap_or_default().to_string(),
+                source_format: disk.driver.as_ref().and_then(|driver| driver.format.clone()),
+                file: format!("{}.qcow2", disk.target_dev),
+                backing: None,
+                size_bytes: 0,
+            })
+            .collect();
+
+        run.live = domain.is_active().map_err(map_libvirt_error)?;
+        let plan = Self::plan_chain(domain, config, previous, run);
+        if run.live {
+            let freeze = if config.quiesce.enabled {
+                if let Some(job) = job {
+                    job.set_step("Quiescing guest filesystems");
+                }
+                let freeze = QuiesceService::freeze(Some(uri), &run.vm_name, &config.quiesce)?;
+                if let Some(note) = freeze.note() {
+                    add_note(run, note);
+                }
+                run.quiesced = freeze.is_frozen();
+                Some(freeze).filter(FreezeGuard::is_frozen)
+            } else {
+                None
+            };
+            if !Self::push_backup(domain, set_dir, run, &plan, freeze, job)? {
+                run.quiesced = false;
+                add_note(run, "The freeze watchdog thawed the guest before the backup job started; the image is crash-consistent");
+            }
+        } else {
+            Self::copy_disks(set_dir, &run.disks, job)?;
+        }
+
+        if let Some(job) = job {
+            job.set_step("Copying firmware and TPM state");
+        }
+        if let Some(nvram) = xml.os().nvram.and_then(|nvram| nvram.path) {
+            fs::copy(&nvram, set_dir.join("nvram.fd"))
+                .map_err(|e| AppError::Other(format!("Failed to copy NVRAM {}: {}", nvram, e)))?;
+            run.nvram = Some("nvram.fd".to_string());
+        }
+
+        let tpm_state = Path::new(SWTPM_STATE_DIR).join(&run.vm_id);
+        if !xml.devices_named("tpm").is_empty() && tpm_state.is_dir() {
+            copy_dir(&tpm_state, &set_dir.join("tpm"))?;
+            run.tpm = true;
+        }
+
+        Ok(())
+    }
+
+    /// Decide whether `run` continues the latest chain, filling in its kind,
+    /// chain and overlay backing files, or starts a new chain with a full backup
+    fn plan_chain(domain: &Domain, config: &BackupConfig, previous: &[BackupRun], run: &mut BackupRun) -> ChainPlan {
+        if config.mode == BackupMode::Full {
+            return ChainPlan::default();
+        }
+        if !run.live {
+            run.note = Some("Changed blocks are only tracked while the VM runs; took a full backup".to_string());
+            return ChainPlan::default();
+        }
+        if let Some(disk) = run.disks.iter().find(|disk| disk.source_format.as_deref() != Some("qcow2")) {
+            run.note = Some(format!("Disk {} is not qcow2 and cannot hold a dirty bitmap; took a full backup", disk.target));
+            return ChainPlan::default();
+        }
+
+        let new_checkpoint = checkpoint_name(&run.id);
+        let (parent, anchor) = match Self::chain_parent(domain, config, previous, run) {
+            Ok(found) => found,
+            Err(reason) => {
+                run.note = Some(format!("{}; started a new chain", reason));
+                run.checkpoint = Some(new_checkpoint.clone());
+                return ChainPlan { incremental_from: None, new_checkpoint: Some(new_checkpoint) };
+            }
+        };
+
+        run.kind = config.mode.clone();
+        run.chain_id = parent.chain_id.clone();
+        run.parent_run_id = Some(parent.id.clone());
+        for disk in run.disks.iter_mut() {
+            disk.backing = parent.disks.iter()
+                .find(|parent_disk| parent_disk.target == disk.target)
+                .map(|parent_disk| Path::new(&parent.path).join(&parent_disk.file).to_string_lossy().to_string());
+        }
+
+        // Differential runs all copy relative to the chain's first checkpoint
+        if config.mode == BackupMode::Differential {
+            run.checkpoint = Some(anchor.clone());
+            ChainPlan { incremental_from: Some(anchor), new_checkpoint: None }
+        } else {
+            run.checkpoint = Some(new_checkpoint.clone());
+            ChainPlan { incremental_from: Some(anchor), new_checkpoint: Some(new_checkpoint) }
+        }
+    }
+
+    /// The point a new incremental or differential run is backed by and the
+    /// checkpoint it is taken relative to, or why the chain cannot continue
+    fn chain_parent<'a>(
+        domain: &Domain,
+        config: &BackupConfig,
+        previous: &'a [BackupRun],
+        run: &BackupRun,
+    ) -> Result<(&'a BackupRun, String), String> {
+        let latest = previous.iter()
+            .find(|r| r.status == BackupRunStatus::Completed)
+            .ok_or_else(|| "No previous backup".to_string())?;
+        if latest.chain_mode != config.mode {
+            return Err("Backup mode changed".to_string());
+        }
+
+        // Points of the latest chain, newest first
+        let chain: Vec<&BackupRun> = previous.iter()
+            .filter(|r| r.status == BackupRunStatus::Completed && r.chain_id == latest.chain_id)
+            .collect();
+        if config.chain_length > 0 && chain.len() > config.chain_length as usize {
+            return Err(format!("Chain reached {} restore points", chain.len()));
+        }
+
+        let targets = |r: &BackupRun| r.disks.iter().map(|disk| disk.target.clone()).collect::<Vec<_>>();
+        if targets(latest) != targets(run) {
+            return Err("Disk layout changed".to_string());
+        }
+
+        let anchor = latest.checkpoint.clone()
+            .ok_or_else(|| "Previous backup has no checkpoint".to_string())?;
+        if !checkpoint_exists(domain, &anchor) {
+            return Err(format!("Checkpoint {} no longer exists", anchor));
+        }
+
+        let parent = match config.mode {
+            BackupMode::Differential => chain.last().copied().unwrap_or(latest),
+            _ => latest,
+        };
+        Ok((parent, anchor))
+    }
+
+    /// Delete the checkpoint a completed run no longer needs: the previous
+    /// point's after an incremental, the old chain's after a new full
+    fn release_checkpoint(domain: &Domain, run: &BackupRun, previous: &[BackupRun]) {
+        if run.kind == BackupMode::Differential {
+            return;
+        }
+        let old = previous.iter()
+            .find(|r| r.status == BackupRunStatus::Completed)
+            .and_then(|r| r.checkpoint.as_deref());
+        if let Some(old) = old.filter(|old| Some(*old) != run.checkpoint.as_deref()) {
+            delete_checkpoint(domain, old);
+        }
+    }
+
+    /// Run a push-mode backup job writing every disk to a qcow2 file in the
+    /// run's set. Overlays of incremental and differential runs are created
+    /// first, backed by the parent set, and receive only the changed blocks.
+    ///
+    /// A frozen guest is thawed as soon as the job has started. Returns false
+    /// if the freeze watchdog thawed it before that.
+    fn push_backup(
+        domain: &Domain,
+        set_dir: &Path,
+        run: &BackupRun,
+        plan: &ChainPlan,
+        freeze: Option<FreezeGuard>,
+        job: Option<&JobHandle>,
+    ) -> Result<bool, AppError> {
+        let disks = &run.disks;
+        let mut flags = 0;
+        for disk in disks {
+            let Some(backing) = disk.backing.as_deref() else {
+                continue;
+            };
+            let capacity = disk_capacity(domain, &disk.target)?.to_string();
+            let file = set_dir.join(&disk.file).to_string_lossy().to_string();
+            job_service::run_qemu_img(
+                None,
+                &["create", "-q", "-f", "qcow2", "-F", "qcow2", "-b", backing, &file, &capacity],
+                0.0..0.0,
+            )?;
+            flags |= sys::VIR_DOMAIN_BACKUP_BEGIN_REUSE_EXTERNAL;
+        }
+
+        let mut disks_xml = XmlElement::new("disks");
+        for disk in disks {
+            disks_xml.push_child(XmlElement::new("disk")
+                .with_attr("name", &disk.target)
+                .with_attr("backup", "yes")
+                .with_attr("type", "file")
+                .with_child(XmlElement::new("target")
+                    .with_attr("file", set_dir.join(&disk.file).to_string_lossy()))
+                .with_child(XmlElement::new("driver").with_attr("type", "qcow2")));
+        }
+        for target in &run.skipped_disks {
+            disks_xml.push_child(XmlElement::new("disk")
+                .with_attr("name", target)
+                .with_attr("backup", "no"));
+        }
+        let mut backup_xml = XmlElement::new("domainbackup").with_attr("mode", "push");
+        if let Some(checkpoint) = &plan.incremental_from {
+            backup_xml.push_child(XmlElement::new("incremental").with_text(checkpoint));
+        }
+        backup_xml.push_child(disks_xml);
+
+        // The checkpoint starts a dirty bitmap on every backed-up disk at the moment the job starts
+        let checkpoint_xml = plan.new_checkpoint.as_ref().map(|name| {
+            let mut checkpoint_disks = XmlElement::new("disks");
+            for disk in disks {
+                checkpoint_disks.push_child(XmlElement::new("disk")
+                    .with_attr("name", &disk.target)
+                    .with_attr("checkpoint", "bitmap"));
+            }
+            for target in &run.skipped_disks {
+                checkpoint_disks.push_child(XmlElement::new("disk")
+                    .with_attr("name", target)
+                    .with_attr("checkpoint", "no"));
+            }
+            XmlElement::new("domaincheckpoint")
+                .with_child(XmlElement::new("name").with_text(name))
+                .with_child(checkpoint_disks)
+                .to_xml()
+        });
+
+        if let Some(job) = job {
+            job.check_cancelled()?;
+            match plan.incremental_from {
+                Some(_) => job.set_step(format!("Copying changed blocks of {} disk(s)", disks.len())),
+                None => job.set_step(format!("Copying {} disk(s) from the running VM", disks.len())),
+            }
+        }
+
+        let backup_xml = CString::new(backup_xml.to_xml())
+            .map_err(|e| AppError::Other(format!("Invalid backup XML: {}", e)))?;
+        let checkpoint_xml = checkpoint_xml
+            .map(CString::new)
+            .transpose()
+            .map_err(|e| AppError::Other(format!("Invalid checkpoint XML: {}", e)))?;
+        let ret = unsafe {
+            sys::virDomainBackupBegin(
+                domain.as_ptr(),
+                backup_xml.as_ptr(),
+                checkpoint_xml.as_ref().map_or(std::ptr::null(), |xml| xml.as_ptr()),
+                flags,
+            )
+        };
+        if ret < 0 {
+            return Err(AppError::LibvirtError(format!(
+                "Failed to start backup job: {}", virt::error::Error::last_error()
+            )));
+        }
+        // The job's point in time is fixed once it has started
+        let consistent = match freeze {
+            Some(freeze) => freeze.thaw(),
+            None => true,
+        };
+
+        let mut aborted = false;
+        while domain_job_active(domain) {
+            if let Some(job) = job {
+                if job.is_cancelled() && !aborted {
+                    match VmService::abort_domain_job(domain) {
+                        Ok(()) => aborted = true,
+                        Err(e) => tracing::warn!("Failed to abort backup job: {}", e),
+                    }
+                }
+                if let Some((processed, total)) = VmService::domain_job_progress(domain) {
+                    job.set_bytes(processed, total);
+                }
+            }
+            std::thread::sleep(BACKUP_POLL_INTERVAL);
+        }
+
+        if aborted {
+            return Err(AppError::Cancelled);
+        }
+        if !last_job_completed(domain) {
+            return Err(AppError::LibvirtError("Backup job did not complete".to_string()));
+        }
+        Ok(consistent)
+    }
+
+    /// Convert each disk of a stopped VM into a qcow2 file in `set_dir`
+    fn copy_disks(set_dir: &Path, disks: &[BackupDisk], job: Option<&JobHandle>) -> Result<(), AppError> {
+        let count = disks.len() as f64;
+        for (index, disk) in disks.iter().enumerate() {
+            if let Some(job) = job {
+                job.set_step(format!("Copying disk {} of {} ({})", index + 1, disks.len(), disk.target));
+            }
+
+            let destination = set_dir.join(&disk.file).to_string_lossy().to_string();
+            let mut args = vec!["convert", "-O", "qcow2"];
+            if let Some(format) = disk.source_format.as_deref() {
+                args.extend(["-f", format]);
+            }
+            args.extend([disk.source.as_str(), destination.as_str()]);
+
+            let span = index as f64 / count * 100.0..(index + 1) as f64 / count * 100.0;
+            job_service::run_qemu_img(job, &args, span)?;
+        }
+        Ok(())
+    }
+
+    /// Retire the oldest restore points beyond `retention_count`
+    fn prune_runs(runs: &mut Vec<BackupRun>, retention_count: u32) {
+        loop {
+            let completed: Vec<usize> = runs.iter()
+                .enumerate()
+                .filter(|(_, run)| run.status == BackupRunStatus::Completed)
+                .map(|(index, _)| index)
+                .collect();
+            if completed.len() <= retention_count as usize {
+                break;
+            }
+            let Some(&oldest) = completed.last() else {
+                break;
+            };
+
+            if let Err(e) = Self::retire_run(runs, oldest) {
+                // Keep it listed as completed so the next run retries
+                tracing::warn!("Failed to prune backup set {}: {}", runs[oldest].path, e);
+                break;
+            }
+        }
+        runs.truncate(RUN_HISTORY_LIMIT);
+    }
+
+    /// Delete the oldest point of a chain, which is always its self-contained base.
+    ///
+    /// The next point of the chain is flattened into a full image first and
+    /// every other point backed by the base is rebased onto it, so no
+    /// remaining restore point loses its backing file.
+    fn retire_run(runs: &mut [BackupRun], index: usize) -> Result<(), AppError> {
+        let base = runs[index].clone();
+        // Runs are newest first, so the last match is the oldest remaining point
+        let successor = runs.iter().rposition(|run| {
+            run.status == BackupRunStatus::Completed && run.id != base.id && run.chain_id == base.chain_id
+        });
+
+        if let Some(next) = successor {
+            let next_id = runs[next].id.clone();
+            let next_files: HashMap<String, PathBuf> = runs[next].disks.iter()
+                .map(|disk| (disk.target.clone(), Path::new(&runs[next].path).join(&disk.file)))
+                .collect();
+
+            // Differential points all hang off the base; move them onto the successor
+            for run in runs.iter_mut().filter(|run| {
+                run.status == BackupRunStatus::Completed && run.chain_id == base.chain_id
+                    && run.id != base.id && run.id != next_id
+            }) {
+                let mut rebased = false;
+                for disk in run.disks.iter_mut() {
+                    if !disk.backing.as_deref().is_some_and(|backing| Path::new(backing).starts_with(&base.path)) {
+                        continue;
+                    }
+                    let new_backing = next_files.get(&disk.target)
+                        .ok_or_else(|| AppError::Other(format!("Disk {} is missing from the next restore point", disk.target)))?;
+                    rebase_image(&Path::new(&run.path).join(&disk.file), Some(new_backing))?;
+                    disk.backing = Some(new_backing.to_string_lossy().to_string());
+                    rebased = true;
+                }
+                if rebased {
+                    run.parent_run_id = Some(next_id.clone());
+                    refresh_sizes(run);
+                    Self::write_manifest(Path::new(&run.path), run)?;
+                    BackupVerifyService::write_manifest(Path::new(&run.path), run, None)?;
+                    mark_remote_stale(run);
+                }
+            }
+
+            let next = &mut runs[next];
+            for disk in next.disks.iter_mut().filter(|disk| disk.backing.is_some()) {
+                rebase_image(&Path::new(&next.path).join(&disk.file), None)?;
+                disk.backing = None;
+            }
+            next.kind = BackupMode::Full;
+            next.parent_run_id = None;
+            refresh_sizes(next);
+            Self::write_manifest(Path::new(&next.path), next)?;
+            BackupVerifyService::write_manifest(Path::new(&next.path), next, None)?;
+            mark_remote_stale(next);
+        }
+
+        match fs::remove_dir_all(&base.path) {
+            Ok(()) => tracing::info!("Pruned backup set {}", base.path),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(AppError::Other(format!("Failed to delete {}: {}", base.path, e))),
+        }
+        runs[index].status = BackupRunStatus::Pruned;
+        Ok(())
+    }
+
+    /// Bring the config's backup target in line with its runs: delete pruned
+    /// sets from it and upload completed ones it lacks, oldest first so a
+    /// chain's base arrives before its overlays. Upload failures are recorded
+    /// on the run and retried next time.
+    fn sync_runs(&self, config: &BackupConfig, runs: &mut [BackupRun], job: Option<&JobHandle>) -> Result<(), AppError> {
+        let Some(target_id) = &config.target_id else {
+            return Ok(());
+        };
+        let targets = BackupTargetService::new()?;
+        let target = targets.get_target(target_id)?;
+
+        for run in runs.iter_mut().filter(|run| run.status == BackupRunStatus::Pruned) {
+            let Some(remote) = run.remote.take() else {
+                continue;
+            };
+            if let Err(e) = targets.delete_set(&target, &remote.key) {
+                tracing::warn!("Failed to delete {} from target {}: {}", remote.key, target.name, e);
+                run.remote = Some(remote);
+            }
+        }
+
+        for run in runs.iter_mut().rev().filter(|run| {
+            run.status == BackupRunStatus::Completed
+                && !run.remote.as_ref().is_some_and(|remote| remote.target_id == target.id && remote.status == RemoteStatus::Uploaded)
+        }) {
+            let set_name = Path::new(&run.path).file_name().unwrap_or_default().to_string_lossy().to_string();
+            let key = format!("{}/{}/{}", run.vm_name, run.config_id, set_name);
+            if let Some(job) = job {
+                job.set_step(format!("Uploading {} to {}", set_name, target.name));
+            }
+
+            let mut remote = RemoteCopy {
+                target_id: target.id.clone(),
+                key,
+                status: RemoteStatus::Pending,
+                stored_bytes: 0,
+                uploaded_at: None,
+                error: None,
+            };
+            match targets.upload_set(&target, Path::new(&run.path), &remote.key, job) {
+                Ok(stored) => {
+                    remote.status = RemoteStatus::Uploaded;
+                    remote.stored_bytes = stored;
+                    remote.uploaded_at = Some(chrono::Utc::now().timestamp());
+                }
+                Err(e) => {
+                    tracing::error!("Failed to upload {} to target {}: {}", run.path, target.name, e);
+                    remote.status = RemoteStatus::Failed;
+                    remote.error = Some(e.to_string());
+                }
+            }
+            let cancelled = job.is_some_and(|job| job.is_cancelled());
+            run.remote = Some(remote);
+            if cancelled {
+                break;
+            }
+        }
+
+        self.save_backup_runs(&config.id, runs)
+    }
+
+    /// Write the run record into the set so it describes itself without the catalog
+    fn write_manifest(set_dir: &Path, run: &BackupRun) -> Result<(), AppError> {
+        let json = serde_json::to_string_pretty(run)
+            .map_err(|e| AppError::Other(format!("Failed to serialize backup manifest: {}", e)))?;
+        fs::write(set_dir.join(BACKUP_MANIFEST_FILE), json)
+            .map_err(|e| AppError::Other(format!("Failed to write backup manifest: {}", e)))
+    }
+
+    fn runs_path(&self, config_id: &str) -> PathBuf {
+        self.backups_dir.join("runs").join(format!("{}.json", config_id))
+    }
+
+    fn save_backup_runs(&self, config_id: &str, runs: &[BackupRun]) -> Result<(), AppError> {
+        let path = self.runs_path(config_id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::Other(format!("Failed to create backup run directory: {}", e)))?;
+        }
+
+        let json = serde_json::to_string_pretty(runs)
+            .map_err(|e| AppError::Other(format!("Failed to serialize backup runs: {}", e)))?;
+        fs::write(&path, json)
+            .map_err(|e| AppError::Other(format!("Failed to write backup runs: {}", e)))
+    }
+
     /// Save a backup config to disk
     fn save_backup_config(&self, config: &BackupConfig) -> Result<(), AppError> {
         let path = self.backups_dir.join(format!("{}.json", config.id));
@@ -213,3 +1384,159 @@ impl BackupService {
         Ok(config)
     }
 }
+
+/// Local, writable data disks; CD-ROMs, read-only, shareable and network disks are left out
+fn is_backup_candidate(disk: &Disk) -> bool {
+    disk.device == "disk"
+        && !disk.readonly
+        && !disk.shareable
+        && disk.source_path().is_some()
+}
+
+/// Name of the checkpoint created by a run
+fn checkpoint_name(run_id: &str) -> String {
+    format!("{}{}", CHECKPOINT_PREFIX, run_id)
+}
+
+fn checkpoint_exists(domain: &Domain, name: &str) -> bool {
+    let Ok(name) = CString::new(name) else {
+        return false;
+    };
+    let checkpoint = unsafe { sys::virDomainCheckpointLookupByName(domain.as_ptr(), name.as_ptr(), 0) };
+    if checkpoint.is_null() {
+        return false;
+    }
+    unsafe { sys::virDomainCheckpointFree(checkpoint) };
+    true
+}
+
+/// Delete a checkpoint if it exists; its dirty bitmap is merged into the parent checkpoint's
+fn delete_checkpoint(domain: &Domain, name: &str) {
+    let Ok(c_name) = CString::new(name) else {
+        return;
+    };
+    let checkpoint = unsafe { sys::virDomainCheckpointLookupByName(domain.as_ptr(), c_name.as_ptr(), 0) };
+    if checkpoint.is_null() {
+        return;
+    }
+    let ret = unsafe { sys::virDomainCheckpointDelete(checkpoint, 0) };
+    unsafe { sys::virDomainCheckpointFree(checkpoint) };
+    if ret < 0 {
+        tracing::warn!("Failed to delete checkpoint {}: {}", name, virt::error::Error::last_error());
+    }
+}
+
+/// Virtual size of a domain disk in bytes
+fn disk_capacity(domain: &Domain, target: &str) -> Result<u64, AppError> {
+    let c_target = CString::new(target)
+        .map_err(|e| AppError::Other(format!("Invalid disk name: {}", e)))?;
+    let mut info: sys::virDomainBlockInfo = unsafe { std::mem::zeroed() };
+    let ret = unsafe { sys::virDomainGetBlockInfo(domain.as_ptr(), c_target.as_ptr(), &mut info, 0) };
+    if ret < 0 {
+        return Err(AppError::LibvirtError(format!(
+            "Failed to get size of disk {}: {}", target, virt::error::Error::last_error()
+        )));
+    }
+    Ok(info.capacity)
+}
+
+/// Rebase a qcow2 image onto `backing`, or make it self-contained with `None`.
+///
+/// Safe mode rebase copies whatever differs, so the image's content is unchanged.
+fn rebase_image(file: &Path, backing: Option<&Path>) -> Result<(), AppError> {
+    let file = file.to_string_lossy();
+    let backing = backing.map(|path| path.to_string_lossy().to_string());
+    let mut args = vec!["rebase", "-f", "qcow2", "-b", backing.as_deref().unwrap_or("")];
+    if backing.is_some() {
+        args.extend(["-F", "qcow2"]);
+    }
+    args.push(&file);
+    job_service::run_qemu_img(None, &args, 0.0..100.0)
+}
+
+/// Update the disk and set sizes of a run from its files
+fn refresh_sizes(run: &mut BackupRun) {
+    let set_dir = PathBuf::from(&run.path);
+    for disk in run.disks.iter_mut() {
+        disk.size_bytes = fs::metadata(set_dir.join(&disk.file)).map(|m| m.len()).unwrap_or(0);
+    }
+    run.size_bytes = dir_size(&set_dir);
+}
+
+/// Whether the domain has an active job
+fn domain_job_active(domain: &Domain) -> bool {
+    let mut info: sys::virDomainJobInfo = unsafe { std::mem::zeroed() };
+    let ret = unsafe { sys::virDomainGetJobInfo(domain.as_ptr(), &mut info) };
+    ret == 0 && info.type_ != sys::VIR_DOMAIN_JOB_NONE as i32
+}
+
+/// Whether the domain's most recently finished job completed successfully
+fn last_job_completed(domain: &Domain) -> bool {
+    let mut job_type: i32 = 0;
+    let mut params: sys::virTypedParameterPtr = std::ptr::null_mut();
+    let mut nparams: i32 = 0;
+    let ret = unsafe {
+        sys::virDomainGetJobStats(
+            domain.as_ptr(),
+            &mut job_type,
+            &mut params,
+            &mut nparams,
+            sys::VIR_DOMAIN_JOB_STATS_COMPLETED,
+        )
+    };
+    if ret < 0 {
+        return false;
+    }
+    unsafe { sys::virTypedParamsFree(params, nparams) };
+    job_type == sys::VIR_DOMAIN_JOB_COMPLETED as i32
+}
+
+/// Flag a run's remote copy for re-upload after its local set changed
+fn mark_remote_stale(run: &mut BackupRun) {
+    if let Some(remote) = run.remote.as_mut() {
+        remote.status = RemoteStatus::Pending;
+    }
+}
+
+/// Append to a run's note
+fn add_note(run: &mut BackupRun, note: &str) {
+    run.note = Some(match run.note.take() {
+        Some(existing) => format!("{}. {}", existing, note),
+        None => note.to_string(),
+    });
+}
+
+/// Recursively copy a directory
+pub fn copy_dir(from: &Path, to: &Path) -> Result<(), AppError> {
+    fs::create_dir_all(to)
+        .map_err(|e| AppError::Other(format!("Failed to create {}: {}", to.display(), e)))?;
+    let entries = fs::read_dir(from)
+        .map_err(|e| AppError::Other(format!("Failed to read {}: {}", from.display(), e)))?;
+
+    for entry in entries.flatten() {
+        let source = entry.path();
+        let destination = to.join(entry.file_name());
+        if source.is_dir() {
+            copy_dir(&source, &destination)?;
+        } else {
+            fs::copy(&source, &destination)
+                .map_err(|e| AppError::Other(format!("Failed to copy {}: {}", source.display(), e)))?;
+        }
+    }
+    Ok(())
+}
+
+/// Total size of the files under a directory
+fn dir_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries.flatten()
+                .map(|entry| match entry.metadata() {
+                    Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+                    Ok(metadata) => metadata.len(),
+                    Err(_) => 0,
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
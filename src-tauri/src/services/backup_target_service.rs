@@ -0,0 +1,1037 @@
+use crate::services::job_service::JobHandle;
+use crate::services::secret_service::{write_private, SecretService};
+use crate::utils::error::AppError;
+use crate::utils::xml::XmlElement;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{Read, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Object written last, marking a remote set as complete
+pub const REMOTE_MANIFEST: &str = "upload.json";
+const PROBE_OBJECT: &str = ".kvm-manager-probe";
+const ZSTD_LEVEL: i32 = 3;
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+const PBKDF2_ROUNDS: u32 = 100_000;
+const S3_TIMEOUT: Duration = Duration::from_secs(300);
+/// Sets are stored as `<vm name>/<config id>/<set>`
+const SET_KEY_DEPTH: usize = 3;
+
+/// Where a backup target stores its objects
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TargetConfig {
+    Local(LocalTarget),
+    Sftp(SftpTarget),
+    S3(S3Target),
+}
+
+/// Directory on the host, typically an NFS share or a removable disk
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalTarget {
+    pub path: String,
+    /// Refuse to write unless `path` is a mountpoint, so an unplugged disk
+    /// doesn't silently fill the root filesystem
+    #[serde(default)]
+    pub require_mount: bool,
+}
+
+/// Directory on an SFTP server, reached with the `sftp` client and key authentication
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpTarget {
+    pub host: String,
+    #[serde(default = "default_sftp_port")]
+    pub port: u16,
+    pub username: String,
+    #[serde(default)]
+    pub ssh_key_path: Option<String>,
+    pub path: String,
+}
+
+/// Bucket on S3 or an S3-compatible store such as MinIO
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct S3Target {
+    /// Base URL, e.g. `https://s3.eu-central-1.amazonaws.com` or `http://localhost:9000`
+    pub endpoint: String,
+    #[serde(default = "default_region")]
+    pub region: String,
+    pub bucket: String,
+    /// Key prefix inside the bucket
+    #[serde(default)]
+    pub prefix: String,
+    pub access_key: String,
+    /// Kept in the secret store; empty in the target file
+    #[serde(default)]
+    pub secret_key: String,
+    /// Address the bucket in the path rather than the host name; MinIO needs this
+    #[serde(default = "default_path_style")]
+    pub path_style: bool,
+}
+
+fn default_sftp_port() -> u16 {
+    22
+}
+
+fn default_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_path_style() -> bool {
+    true
+}
+
+fn default_chunk_size() -> u32 {
+    64
+}
+
+/// Result of a connectivity check
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetCheck {
+    pub ok: bool,
+    pub checked_at: i64,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+/// A place backup sets are copied to after they are written locally
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupTarget {
+    pub id: String,
+    pub name: String,
+    pub config: TargetConfig,
+    /// Compress chunks with zstd before upload
+    #[serde(default)]
+    pub compression: bool,
+    /// Encrypt chunks client-side with a key derived from this passphrase.
+    /// Sets can't be restored without it. Kept in the secret store; absent from the target file.
+    #[serde(default)]
+    pub encryption_passphrase: Option<String>,
+    /// Uncompressed size of each uploaded chunk; a resumed upload restarts at the first missing chunk
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size_mb: u32,
+    #[serde(default)]
+    pub last_check: Option<TargetCheck>,
+    pub created_at: i64,
+}
+
+/// Request to create a backup target
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBackupTargetRequest {
+    pub name: String,
+    pub config: TargetConfig,
+    #[serde(default)]
+    pub compression: bool,
+    #[serde(default)]
+    pub encryption_passphrase: Option<String>,
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size_mb: u32,
+}
+
+/// State of a backup set's copy on its target
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RemoteStatus {
+    /// Not uploaded yet, or changed locally by pruning and due for re-upload
+    Pending,
+    Uploaded,
+    /// Upload failed; the next attempt resumes at the first missing chunk
+    Failed,
+}
+
+/// A backup set's copy on a target
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteCopy {
+    pub target_id: String,
+    /// Key of the set on the target, relative to its root
+    pub key: String,
+    pub status: RemoteStatus,
+    /// Bytes stored on the target after compression and encryption
+    pub stored_bytes: u64,
+    pub uploaded_at: Option<i64>,
+    pub error: Option<String>,
+}
+
+/// Space a target uses for backups
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetUsage {
+    pub target_id: String,
+    /// Uploaded backup sets
+    pub sets: u32,
+    /// Size of the uploaded sets before compression and encryption
+    pub raw_bytes: u64,
+    pub stored_bytes: u64,
+    /// Sets waiting for an upload or a retry
+    pub pending_sets: u32,
+    /// Free space on the target, where it can be determined
+    pub available_bytes: Option<u64>,
+}
+
+/// Layout of an uploaded set, stored as `upload.json` next to its chunks
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct RemoteSet {
+    chunk_size: u64,
+    compression: bool,
+    /// Hex salt of the encryption key; `None` for unencrypted sets
+    salt: Option<String>,
+    /// Chunks are bound to their object key as associated data; older sets are not
+    #[serde(default)]
+    bound_chunks: bool,
+    files: Vec<RemoteFile>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct RemoteFile {
+    /// Path relative to the set directory
+    path: String,
+    size_bytes: u64,
+    chunks: u64,
+}
+
+/// Progress of an upload, kept locally so an interrupted upload resumes
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct UploadState {
+    set: RemoteSet,
+    /// Chunk keys already stored
+    done: BTreeSet<String>,
+    stored_bytes: u64,
+}
+
+/// BackupTargetService stores backup targets and moves backup sets to and from them.
+///
+/// Every file of a set is split into chunks that are optionally compressed
+/// with zstd and encrypted with AES-256-GCM, then stored as one object each
+/// under `<set key>/<file>/<chunk>`. The set's `upload.json` is written last.
+pub struct BackupTargetService {
+    targets_dir: PathBuf,
+    uploads_dir: PathBuf,
+    secrets: Arc<SecretService>,
+}
+
+impl BackupTargetService {
+    pub fn new() -> Result<Self, AppError> {
+        let backups_dir = dirs::config_dir()
+            .ok_or_else(|| AppError::Other("Could not determine config directory".to_string()))?
+            .join("kvm-manager")
+            .join("backups");
+        let targets_dir = backups_dir.join("targets");
+        let uploads_dir = backups_dir.join("uploads");
+        fs::create_dir_all(&targets_dir)
+            .and_then(|_| fs::create_dir_all(&uploads_dir))
+            .map_err(|e| AppError::Other(format!("Failed to create backup target directory: {}", e)))?;
+
+        Ok(Self { targets_dir, uploads_dir, secrets: SecretService::shared()? })
+    }
+
+    /// Create a backup target
+    pub fn create_target(&self, request: CreateBackupTargetRequest) -> Result<BackupTarget, AppError> {
+        if request.name.trim().is_empty() {
+            return Err(AppError::InvalidConfig("Target name is required".to_string()));
+        }
+        if request.chunk_size_mb == 0 {
+            return Err(AppError::InvalidConfig("Chunk size must be at least 1 MB".to_string()));
+        }
+        if request.encryption_passphrase.as_deref().is_some_and(|p| p.len() < 8) {
+            return Err(AppError::InvalidConfig("Encryption passphrase must be at least 8 characters".to_string()));
+        }
+        match &request.config {
+            TargetConfig::Local(local) if !Path::new(&local.path).is_absolute() => {
+                return Err(AppError::InvalidConfig("Target path must be absolute".to_string()));
+            }
+            TargetConfig::Sftp(sftp) if sftp.host.is_empty() || sftp.username.is_empty() => {
+                return Err(AppError::InvalidConfig("SFTP host and username are required".to_string()));
+            }
+            TargetConfig::S3(s3) if s3.bucket.is_empty() || split_endpoint(&s3.endpoint).is_err() => {
+                return Err(AppError::InvalidConfig("S3 endpoint must be an http(s) URL and a bucket is required".to_string()));
+            }
+            _ => {}
+        }
+
+        let target = BackupTarget {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: request.name,
+            config: request.config,
+            compression: request.compression,
+            encryption_passphrase: request.encryption_passphrase.filter(|p| !p.is_empty()),
+            chunk_size_mb: request.chunk_size_mb,
+            last_check: None,
+            created_at: chrono::Utc::now().timestamp(),
+        };
+        self.save_target(&target)?;
+        tracing::info!("Created backup target {} ({})", target.name, target.id);
+        Ok(target)
+    }
+
+    /// List backup targets, oldest first
+    pub fn list_targets(&self) -> Result<Vec<BackupTarget>, AppError> {
+        let entries = fs::read_dir(&self.targets_dir)
+            .map_err(|e| AppError::Other(format!("Failed to read backup targets: {}", e)))?;
+        let mut targets: Vec<BackupTarget> = entries
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| self.read_target(&entry.path()).ok())
+            .collect();
+        targets.sort_by_key(|target| target.created_at);
+        Ok(targets)
+    }
+
+    pub fn get_target(&self, id: &str) -> Result<BackupTarget, AppError> {
+        self.read_target(&self.targets_dir.join(format!("{}.json", id)))
+    }
+
+    /// Delete a target's definition; backup sets stored on it are left alone
+    pub fn delete_target(&self, id: &str) -> Result<(), AppError> {
+        fs::remove_file(self.targets_dir.join(format!("{}.json", id)))
+            .map_err(|_| AppError::NotFound(format!("Backup target {}", id)))?;
+        self.secrets.remove_values(&format!("backup-target/{}/", id))
+    }
+
+    /// Write, read back and delete a probe object, recording the result on the target
+    pub fn check_target(&self, id: &str) -> Result<TargetCheck, AppError> {
+        let mut target = self.get_target(id)?;
+        let started = Instant::now();
+        let probe = format!("{} {}", target.id, chrono::Utc::now().timestamp());
+
+        let result = target.config.put(PROBE_OBJECT, probe.as_bytes())
+            .and_then(|_| target.config.get(PROBE_OBJECT))
+            .and_then(|data| {
+                if data == probe.as_bytes() {
+                    Ok(())
+                } else {
+                    Err(AppError::Other("Probe object read back with different content".to_string()))
+                }
+            })
+            .and_then(|_| target.config.delete(&[PROBE_OBJECT.to_string()]));
+
+        let check = TargetCheck {
+            ok: result.is_ok(),
+            checked_at: chrono::Utc::now().timestamp(),
+            latency_ms: started.elapsed().as_millis() as u64,
+            error: result.err().map(|e| e.to_string()),
+        };
+        target.last_check = Some(check.clone());
+        self.save_target(&target)?;
+        Ok(check)
+    }
+
+    /// Free space on the target, where it can be determined
+    pub fn available_bytes(&self, target: &BackupTarget) -> Option<u64> {
+        match &target.config {
+            TargetConfig::Local(local) => local.available_bytes(),
+            TargetConfig::Sftp(sftp) => sftp.available_bytes(),
+            TargetConfig::S3(_) => None,
+        }
+    }
+
+    /// Upload a local set directory under `key`, resuming a previous attempt.
+    ///
+    /// Returns the number of bytes stored on the target.
+    pub fn upload_set(&self, target: &BackupTarget, set_dir: &Path, key: &str, job: Option<&JobHandle>) -> Result<u64, AppError> {
+        let chunk_size = target.chunk_size_mb as u64 * 1024 * 1024;
+        let files: Vec<RemoteFile> = list_files(set_dir)?
+            .into_iter()
+            .map(|(path, size_bytes)| RemoteFile { path, size_bytes, chunks: size_bytes.div_ceil(chunk_size).max(1) })
+            .collect();
+
+        let state_path = self.uploads_dir.join(format!("{}.json", key.replace('/', "_")));
+        let resumable = fs::read_to_string(&state_path).ok()
+            .and_then(|json| serde_json::from_str::<UploadState>(&json).ok())
+            .filter(|state| {
+                state.set.files == files
+                    && state.set.chunk_size == chunk_size
+                    && state.set.compression == target.compression
+                    && state.set.bound_chunks
+                    && state.set.salt.is_some() == target.encryption_passphrase.is_some()
+            });
+        let mut state = match resumable {
+            Some(state) => {
+                tracing::info!("Resuming upload of {} with {} chunk(s) already stored", key, state.done.len());
+                state
+            }
+            None => {
+                // Replace whatever an earlier upload of this set left behind
+                if let Err(e) = self.delete_set(target, key) {
+                    tracing::debug!("No previous copy of {} removed: {}", key, e);
+                }
+                let salt = target.encryption_passphrase.as_ref().map(|_| {
+                    let mut salt = [0u8; SALT_LEN];
+                    rand::thread_rng().fill_bytes(&mut salt);
+                    hex::encode(salt)
+                });
+                UploadState {
+                    set: RemoteSet { chunk_size, compression: target.compression, salt, bound_chunks: true, files },
+                    done: BTreeSet::new(),
+                    stored_bytes: 0,
+                }
+            }
+        };
+        let cipher = cipher_for(target, &state.set)?;
+
+        let total: u64 = state.set.files.iter().map(|file| file.size_bytes).sum();
+        let mut processed = 0;
+        for file in state.set.files.clone() {
+            let mut reader = fs::File::open(set_dir.join(&file.path))
+                .map_err(|e| AppError::Other(format!("Failed to open {}: {}", file.path, e)))?;
+            for index in 0..file.chunks {
+                let chunk_key = chunk_key(&file.path, index);
+                let length = chunk_size.min(file.size_bytes.saturating_sub(index * chunk_size));
+                if state.done.contains(&chunk_key) {
+                    std::io::copy(&mut (&mut reader).take(length), &mut std::io::sink())
+                        .map_err(|e| AppError::Other(format!("Failed to read {}: {}", file.path, e)))?;
+                    processed += length;
+                    continue;
+                }
+                if let Some(job) = job {
+                    job.check_cancelled()?;
+                }
+
+                let mut data = Vec::with_capacity(length as usize);
+                (&mut reader).take(length).read_to_end(&mut data)
+                    .map_err(|e| AppError::Other(format!("Failed to read {}: {}", file.path, e)))?;
+                let object = format!("{}/{}", key, chunk_key);
+                let sealed = seal_chunk(data, state.set.compression, cipher.as_ref(), &object)?;
+                target.config.put(&object, &sealed)?;
+
+                state.stored_bytes += sealed.len() as u64;
+                state.done.insert(chunk_key);
+                self.save_state(&state_path, &state)?;
+
+                processed += length;
+                if let Some(job) = job {
+                    job.set_bytes(processed, total);
+                }
+            }
+        }
+
+        let manifest = serde_json::to_vec_pretty(&state.set)
+            .map_err(|e| AppError::Other(format!("Failed to serialize upload manifest: {}", e)))?;
+        target.config.put(&format!("{}/{}", key, REMOTE_MANIFEST), &manifest)?;
+        let _ = fs::remove_file(&state_path);
+
+        tracing::info!("Uploaded {} to target {}: {} bytes stored", key, target.name, state.stored_bytes);
+        Ok(state.stored_bytes + manifest.len() as u64)
+    }
+
+    /// Download the set stored under `key` into `set_dir`
+    pub fn download_set(&self, target: &BackupTarget, key: &str, set_dir: &Path, job: Option<&JobHandle>) -> Result<(), AppError> {
+        let set = self.remote_set(target, key)?;
+        let cipher = cipher_for(target, &set)?;
+
+        let total: u64 = set.files.iter().map(|file| file.size_bytes).sum();
+        let mut processed = 0;
+        for file in &set.files {
+            let path = set_dir.join(&file.path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| AppError::Other(format!("Failed to create {}: {}", parent.display(), e)))?;
+            }
+            let mut writer = fs::File::create(&path)
+                .map_err(|e| AppError::Other(format!("Failed to create {}: {}", path.display(), e)))?;
+            for index in 0..file.chunks {
+                if let Some(job) = job {
+                    job.check_cancelled()?;
+                }
+                let data = fetch_chunk(target, &set, cipher.as_ref(), key, &file.path, index)?;
+                writer.write_all(&data)
+                    .map_err(|e| AppError::Other(format!("Failed to write {}: {}", path.display(), e)))?;
+                processed += data.len() as u64;
+                if let Some(job) = job {
+                    job.set_bytes(processed, total);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Download one file of the set stored under `key`
+    pub fn read_set_file(&self, target: &BackupTarget, key: &str, path: &str) -> Result<Vec<u8>, AppError> {
+        let set = self.remote_set(target, key)?;
+        let file = set.files.iter()
+            .find(|file| file.path == path)
+            .ok_or_else(|| AppError::NotFound(format!("{} in backup set {}", path, key)))?;
+        let cipher = cipher_for(target, &set)?;
+
+        let mut data = Vec::with_capacity(file.size_bytes as usize);
+        for index in 0..file.chunks {
+            data.extend(fetch_chunk(target, &set, cipher.as_ref(), key, &file.path, index)?);
+        }
+        Ok(data)
+    }
+
+    /// Keys of the complete sets on the target, found by their `upload.json`
+    pub fn list_sets(&self, target: &BackupTarget) -> Result<Vec<String>, AppError> {
+        let suffix = format!("/{}", REMOTE_MANIFEST);
+        let mut keys: Vec<String> = target.config.list_manifests()?
+            .into_iter()
+            .filter_map(|manifest| manifest.strip_suffix(&suffix).map(str::to_string))
+            .filter(|key| key.split('/').count() == SET_KEY_DEPTH)
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    /// Delete a set from the target
+    pub fn delete_set(&self, target: &BackupTarget, key: &str) -> Result<(), AppError> {
+        let set = self.remote_set(target, key)?;
+        let mut objects: Vec<String> = set.files.iter()
+            .flat_map(|file| (0..file.chunks).map(move |index| format!("{}/{}", key, chunk_key(&file.path, index))))
+            .collect();
+        objects.push(format!("{}/{}", key, REMOTE_MANIFEST));
+        target.config.delete(&objects)?;
+        tracing::info!("Deleted {} from target {}", key, target.name);
+        Ok(())
+    }
+
+    /// Layout of an uploaded set, or of a partial upload still tracked locally
+    fn remote_set(&self, target: &BackupTarget, key: &str) -> Result<RemoteSet, AppError> {
+        if let Ok(manifest) = target.config.get(&format!("{}/{}", key, REMOTE_MANIFEST)) {
+            return serde_json::from_slice(&manifest)
+                .map_err(|e| AppError::Other(format!("Failed to parse upload manifest of {}: {}", key, e)));
+        }
+        let state_path = self.uploads_dir.join(format!("{}.json", key.replace('/', "_")));
+        let json = fs::read_to_string(&state_path)
+            .map_err(|_| AppError::NotFound(format!("Backup set {} on target {}", key, target.name)))?;
+        let state: UploadState = serde_json::from_str(&json)
+            .map_err(|e| AppError::Other(format!("Failed to parse upload state: {}", e)))?;
+        Ok(state.set)
+    }
+
+    fn save_state(&self, path: &Path, state: &UploadState) -> Result<(), AppError> {
+        let json = serde_json::to_string(state)
+            .map_err(|e| AppError::Other(format!("Failed to serialize upload state: {}", e)))?;
+        fs::write(path, json)
+            .map_err(|e| AppError::Other(format!("Failed to write upload state: {}", e)))
+    }
+
+    /// Load a target file and fill in its S3 secret key and passphrase from the secret store
+    fn read_target(&self, path: &Path) -> Result<BackupTarget, AppError> {
+        let json = fs::read_to_string(path)
+            .map_err(|_| AppError::NotFound(format!("Backup target {}", path.file_stem().unwrap_or_default().to_string_lossy())))?;
+        let mut target: BackupTarget = serde_json::from_str(&json)
+            .map_err(|e| AppError::Other(format!("Failed to parse backup target: {}", e)))?;
+
+        // Files written before secrets moved to the secret store
+        let plaintext = target.encryption_passphrase.is_some()
+            || matches!(&target.config, TargetConfig::S3(s3) if !s3.secret_key.is_empty());
+        if plaintext {
+            self.save_target(&target)?;
+            return Ok(target);
+        }
+
+        target.encryption_passphrase = self.secrets.get_value(&secret_reference(&target.id, "passphrase"))?;
+        if let TargetConfig::S3(s3) = &mut target.config {
+            s3.secret_key = self.secrets.get_value(&secret_reference(&target.id, "secretKey"))?.unwrap_or_default();
+        }
+        Ok(target)
+    }
+
+    /// Write a target file, moving its S3 secret key and passphrase into the secret store
+    fn save_target(&self, target: &BackupTarget) -> Result<(), AppError> {
+        let mut stored = target.clone();
+        let passphrase = stored.encryption_passphrase.take();
+        let secret_key = match &mut stored.config {
+            TargetConfig::S3(s3) => Some(std::mem::take(&mut s3.secret_key)),
+            _ => None,
+        };
+        self.secrets.set_value(&secret_reference(&target.id, "passphrase"), passphrase.as_deref())?;
+        self.secrets.set_value(
+            &secret_reference(&target.id, "secretKey"),
+            secret_key.as_deref().filter(|key| !key.is_empty()),
+        )?;
+
+        let json = serde_json::to_string_pretty(&stored)
+            .map_err(|e| AppError::Other(format!("Failed to serialize backup target: {}", e)))?;
+        fs::write(self.targets_dir.join(format!("{}.json", target.id)), json)
+            .map_err(|e| AppError::Other(format!("Failed to write backup target: {}", e)))
+    }
+}
+
+impl TargetConfig {
+    fn put(&self, key: &str, data: &[u8]) -> Result<(), AppError> {
+        match self {
+            TargetConfig::Local(local) => local.put(key, data),
+            TargetConfig::Sftp(sftp) => sftp.put(key, data),
+            TargetConfig::S3(s3) => s3.request("PUT", key, data).map(|_| ()),
+        }
+    }
+
+    fn get(&self, key: &str) -> Result<Vec<u8>, AppError> {
+        match self {
+            TargetConfig::Local(local) => fs::read(local.root()?.join(key))
+                .map_err(|e| AppError::Other(format!("Failed to read {}: {}", key, e))),
+            TargetConfig::Sftp(sftp) => sftp.get(key),
+            TargetConfig::S3(s3) => s3.request("GET", key, &[]),
+        }
+    }
+
+    /// Keys of set manifests, possibly including some outside the set layout
+    fn list_manifests(&self) -> Result<Vec<String>, AppError> {
+        match self {
+            TargetConfig::Local(local) => local.list_manifests(),
+            TargetConfig::Sftp(sftp) => sftp.list_manifests(),
+            TargetConfig::S3(s3) => Ok(s3.list()?
+                .into_iter()
+                .filter(|key| key.ends_with(REMOTE_MANIFEST))
+                .collect()),
+        }
+    }
+
+    fn delete(&self, keys: &[String]) -> Result<(), AppError> {
+        match self {
+            TargetConfig::Local(local) => local.delete(keys),
+            TargetConfig::Sftp(sftp) => sftp.delete(keys),
+            TargetConfig::S3(s3) => keys.iter().try_for_each(|key| s3.request("DELETE", key, &[]).map(|_| ())),
+        }
+    }
+}
+
+impl LocalTarget {
+    /// The target directory, after checking a removable disk is actually mounted
+    fn root(&self) -> Result<PathBuf, AppError> {
+        let root = PathBuf::from(&self.path);
+        if self.require_mount {
+            let mounted = match (fs::metadata(&root), root.parent().map(fs::metadata)) {
+                (Ok(dir), Some(Ok(parent))) => dir.dev() != parent.dev(),
+                _ => false,
+            };
+            if !mounted {
+                return Err(AppError::Other(format!("{} is not a mounted filesystem", self.path)));
+            }
+        }
+        Ok(root)
+    }
+
+    fn put(&self, key: &str, data: &[u8]) -> Result<(), AppError> {
+        let path = self.root()?.join(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::Other(format!("Failed to create {}: {}", parent.display(), e)))?;
+        }
+        // Write beside the object and rename, so a crash never leaves a truncated chunk
+        let partial = path.with_extension("partial");
+        fs::write(&partial, data)
+            .and_then(|_| fs::rename(&partial, &path))
+            .map_err(|e| AppError::Other(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    fn delete(&self, keys: &[String]) -> Result<(), AppError> {
+        let root = self.root()?;
+        let mut dirs = BTreeSet::new();
+        for key in keys {
+            let path = root.join(key);
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(AppError::Other(format!("Failed to delete {}: {}", path.display(), e))),
+            }
+            dirs.extend(path.ancestors().skip(1).take_while(|dir| *dir != root).map(Path::to_path_buf));
+        }
+        // Deepest first; directories still holding other sets stay
+        for dir in dirs.iter().rev() {
+            let _ = fs::remove_dir(dir);
+        }
+        Ok(())
+    }
+
+    fn list_manifests(&self) -> Result<Vec<String>, AppError> {
+        let root = self.root()?;
+        let mut dirs = vec![String::new()];
+        for _ in 0..SET_KEY_DEPTH {
+            dirs = dirs.iter()
+                .flat_map(|dir| {
+                    fs::read_dir(root.join(dir)).into_iter().flatten().flatten()
+                        .filter(|entry| entry.path().is_dir())
+                        .map(move |entry| format!("{}{}/", dir, entry.file_name().to_string_lossy()))
+                })
+                .collect();
+        }
+        Ok(dirs.into_iter()
+            .map(|dir| format!("{}{}", dir, REMOTE_MANIFEST))
+            .filter(|key| root.join(key).is_file())
+            .collect())
+    }
+
+    fn available_bytes(&self) -> Option<u64> {
+        let path = std::ffi::CString::new(self.root().ok()?.to_string_lossy().as_bytes()).ok()?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+            return None;
+        }
+        Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+    }
+}
+
+impl SftpTarget {
+    fn remote_path(&self, key: &str) -> String {
+        format!("{}/{}", self.path.trim_end_matches('/'), key)
+    }
+
+    fn put(&self, key: &str, data: &[u8]) -> Result<(), AppError> {
+        let local = temp_file(data)?;
+        let remote = self.remote_path(key);
+
+        // Create missing parents; `-` makes sftp ignore the error for existing ones
+        let mut batch = String::new();
+        let parents: Vec<&Path> = Path::new(&remote).ancestors().skip(1).collect();
+        for dir in parents.iter().rev().filter(|dir| !dir.as_os_str().is_empty() && **dir != Path::new("/")) {
+            batch.push_str(&format!("-mkdir {}\n", quote(&dir.to_string_lossy())));
+        }
+        batch.push_str(&format!("put {} {}\n", quote(&local.to_string_lossy()), quote(&remote)));
+
+        let result = self.run(&batch);
+        let _ = fs::remove_file(&local);
+        result.map(|_| ())
+    }
+
+    fn get(&self, key: &str) -> Result<Vec<u8>, AppError> {
+        let local = temp_file(&[])?;
+        let result = self.run(&format!("get {} {}\n", quote(&self.remote_path(key)), quote(&local.to_string_lossy())))
+            .and_then(|_| fs::read(&local).map_err(|e| AppError::Other(format!("Failed to read download: {}", e))));
+        let _ = fs::remove_file(&local);
+        result
+    }
+
+    fn delete(&self, keys: &[String]) -> Result<(), AppError> {
+        let mut batch = String::new();
+        let mut dirs = BTreeSet::new();
+        for key in keys {
+            batch.push_str(&format!("-rm {}\n", quote(&self.remote_path(key))));
+            dirs.extend(Path::new(key).ancestors().skip(1).filter(|dir| !dir.as_os_str().is_empty()).map(Path::to_path_buf));
+        }
+        for dir in dirs.iter().rev() {
+            batch.push_str(&format!("-rmdir {}\n", quote(&self.remote_path(&dir.to_string_lossy()))));
+        }
+        self.run(&batch).map(|_| ())
+    }
+
+    fn list_manifests(&self) -> Result<Vec<String>, AppError> {
+        let root = format!("{}/", self.path.trim_end_matches('/'));
+        let pattern = format!("{}{}", "*/".repeat(SET_KEY_DEPTH), REMOTE_MANIFEST);
+        // `-` keeps an empty target, where the glob matches nothing, from failing the batch
+        let output = self.run(&format!("-ls -1 {}\n", quote(&self.remote_path(&pattern))))?;
+        Ok(output.lines()
+            .filter_map(|line| line.trim().strip_prefix(&root))
+            .filter(|key| key.ends_with(REMOTE_MANIFEST))
+            .map(str::to_string)
+            .collect())
+    }
+
+    fn available_bytes(&self) -> Option<u64> {
+        // `df` prints sizes in KiB: Size Used Avail (root) %Capacity
+        let output = self.run(&format!("df {}\n", quote(&self.path))).ok()?;
+        let line = output.lines().rev().find(|line| line.split_whitespace().count() >= 4)?;
+        let available: u64 = line.split_whitespace().nth(2)?.parse().ok()?;
+        Some(available * 1024)
+    }
+
+    /// Run an sftp batch; commands without a `-` prefix abort it on failure
+    fn run(&self, batch: &str) -> Result<String, AppError> {
+        let mut command = Command::new("sftp");
+        command.args(["-b", "-", "-o", "BatchMode=yes", "-o", "StrictHostKeyChecking=accept-new"]);
+        command.args(["-P", &self.port.to_string()]);
+        if let Some(key) = self.ssh_key_path.as_deref().filter(|key| !key.is_empty()) {
+            command.args(["-i", key]);
+        }
+        command.arg(format!("{}@{}", self.username, self.host));
+
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| AppError::Other(format!("Failed to run sftp: {}", e)))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(batch.as_bytes())
+                .map_err(|e| AppError::Other(format!("Failed to send sftp commands: {}", e)))?;
+        }
+        let output = child.wait_with_output()
+            .map_err(|e| AppError::Other(format!("Failed to run sftp: {}", e)))?;
+        if !output.status.success() {
+            return Err(AppError::Other(format!(
+                "sftp to {} failed: {}", self.host, String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+impl S3Target {
+    /// Object name of a key, below the target's prefix
+    fn object(&self, key: &str) -> String {
+        match self.prefix.trim_matches('/') {
+            "" => key.to_string(),
+            prefix => format!("{}/{}", prefix, key),
+        }
+    }
+
+    fn request(&self, method: &str, key: &str, body: &[u8]) -> Result<Vec<u8>, AppError> {
+        self.send(method, &self.object(key), &[], body)
+    }
+
+    /// Keys of every object on the target, relative to its prefix
+    fn list(&self) -> Result<Vec<String>, AppError> {
+        let prefix = self.object("");
+        let mut keys = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2".to_string()), ("prefix", prefix.clone())];
+            if let Some(token) = token.take() {
+                query.push(("continuation-token", token));
+            }
+            let body = self.send("GET", "", &query, &[])?;
+            let listing = XmlElement::parse(&String::from_utf8_lossy(&body))?;
+            keys.extend(listing.children_named("Contents")
+                .filter_map(|object| object.child_text("Key"))
+                .filter_map(|key| key.strip_prefix(&prefix).map(str::to_string)));
+
+            match listing.child_text("NextContinuationToken") {
+                Some(next) if listing.child_text("IsTruncated").as_deref() == Some("true") => token = Some(next),
+                _ => return Ok(keys),
+            }
+        }
+    }
+
+    /// Send a request signed with AWS Signature Version 4; an empty `object` addresses the bucket
+    fn send(&self, method: &str, object: &str, query: &[(&str, String)], body: &[u8]) -> Result<Vec<u8>, AppError> {
+        let (scheme, authority) = split_endpoint(&self.endpoint)?;
+        let (host, path) = if self.path_style {
+            (authority.to_string(), format!("/{}/{}", self.bucket, uri_encode(&object)))
+        } else {
+            (format!("{}.{}", self.bucket, authority), format!("/{}", uri_encode(&object)))
+        };
+        let mut query: Vec<String> = query.iter()
+            .map(|(name, value)| format!("{}={}", uri_encode(name).replace('/', "%2F"), uri_encode(value).replace('/', "%2F")))
+            .collect();
+        query.sort();
+        let query = query.join("&");
+
+        let now = chrono::Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex::encode(Sha256::digest(body));
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method, path, query, host, payload_hash, amz_date, signed_headers, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date, scope, hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        let mut signing_key = hmac_sha256(format!("AWS4{}", self.secret_key).as_bytes(), date.as_bytes());
+        for part in [self.region.as_str(), "s3", "aws4_request"] {
+            signing_key = hmac_sha256(&signing_key, part.as_bytes());
+        }
+        let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key, scope, signed_headers, signature
+        );
+
+        let url = match query.as_str() {
+            "" => format!("{}://{}{}", scheme, host, path),
+            query => format!("{}://{}{}?{}", scheme, host, path, query),
+        };
+        let response = ureq::request(method, &url)
+            .timeout(S3_TIMEOUT)
+            .set("x-amz-date", &amz_date)
+            .set("x-amz-content-sha256", &payload_hash)
+            .set("Authorization", &authorization)
+            .send_bytes(body)
+            .map_err(|e| match e {
+                ureq::Error::Status(code, response) => AppError::Other(format!(
+                    "S3 {} {} returned HTTP {}: {}", method, object, code, s3_error_message(response)
+                )),
+                e => AppError::Other(format!("S3 request to {} failed: {}", self.endpoint, e)),
+            })?;
+
+        let mut data = Vec::new();
+        response.into_reader().read_to_end(&mut data)
+            .map_err(|e| AppError::Other(format!("Failed to read S3 response: {}", e)))?;
+        Ok(data)
+    }
+}
+
+/// Split an endpoint URL into scheme and `host[:port]`
+fn split_endpoint(endpoint: &str) -> Result<(&str, &str), AppError> {
+    let (scheme, rest) = endpoint.split_once("://")
+        .filter(|(scheme, _)| *scheme == "http" || *scheme == "https")
+        .ok_or_else(|| AppError::InvalidConfig(format!("Invalid S3 endpoint: {}", endpoint)))?;
+    let authority = rest.split('/').next().unwrap_or_default();
+    if authority.is_empty() {
+        return Err(AppError::InvalidConfig(format!("Invalid S3 endpoint: {}", endpoint)));
+    }
+    Ok((scheme, authority))
+}
+
+/// Percent-encode an object key as SigV4 expects, keeping `/` separators
+fn uri_encode(value: &str) -> String {
+    value.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// The `<Message>` of an S3 error response, or its status text
+fn s3_error_message(response: ureq::Response) -> String {
+    let status = response.status_text().to_string();
+    response.into_string().ok()
+        .and_then(|body| XmlElement::parse(&body).ok())
+        .and_then(|error| error.find("Message").map(|message| message.text()))
+        .unwrap_or(status)
+}
+
+/// Key of a chunk relative to its set
+fn chunk_key(path: &str, index: u64) -> String {
+    format!("{}/{:06}", path, index)
+}
+
+/// Fetch and open one chunk of an uploaded set
+fn fetch_chunk(
+    target: &BackupTarget,
+    set: &RemoteSet,
+    cipher: Option<&Aes256Gcm>,
+    key: &str,
+    path: &str,
+    index: u64,
+) -> Result<Vec<u8>, AppError> {
+    let object = format!("{}/{}", key, chunk_key(path, index));
+    let sealed = target.config.get(&object)?;
+    let aad = if set.bound_chunks { object.as_str() } else { "" };
+    open_chunk(sealed, set.compression, cipher, aad)
+}
+
+/// Secret store reference of one of a target's secrets
+fn secret_reference(target_id: &str, name: &str) -> String {
+    format!("backup-target/{}/{}", target_id, name)
+}
+
+/// Cipher for a set's chunks, if the set is encrypted
+fn cipher_for(target: &BackupTarget, set: &RemoteSet) -> Result<Option<Aes256Gcm>, AppError> {
+    let Some(salt) = &set.salt else {
+        return Ok(None);
+    };
+    let passphrase = target.encryption_passphrase.as_deref()
+        .ok_or_else(|| AppError::InvalidConfig(format!("Target {} has no passphrase for this encrypted set", target.name)))?;
+    let salt = hex::decode(salt)
+        .map_err(|e| AppError::Other(format!("Invalid encryption salt: {}", e)))?;
+    Ok(Some(derive_key(passphrase, &salt)))
+}
+
+/// PBKDF2-HMAC-SHA256 key for a set's chunks
+fn derive_key(passphrase: &str, salt: &[u8]) -> Aes256Gcm {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key))
+}
+
+/// Compress and encrypt a chunk; encrypted chunks are `nonce || ciphertext`.
+///
+/// The chunk's object key is authenticated as associated data, so a chunk
+/// moved or swapped to another position fails to decrypt.
+fn seal_chunk(data: Vec<u8>, compression: bool, cipher: Option<&Aes256Gcm>, aad: &str) -> Result<Vec<u8>, AppError> {
+    let data = if compression {
+        zstd::stream::encode_all(data.as_slice(), ZSTD_LEVEL)
+            .map_err(|e| AppError::Other(format!("Failed to compress chunk: {}", e)))?
+    } else {
+        data
+    };
+    let Some(cipher) = cipher else {
+        return Ok(data);
+    };
+
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), Payload { msg: &data, aad: aad.as_bytes() })
+        .map_err(|_| AppError::Other("Failed to encrypt chunk".to_string()))?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Reverse `seal_chunk`
+fn open_chunk(data: Vec<u8>, compression: bool, cipher: Option<&Aes256Gcm>, aad: &str) -> Result<Vec<u8>, AppError> {
+    let data = match cipher {
+        Some(cipher) => {
+            if data.len() < NONCE_LEN {
+                return Err(AppError::Other("Encrypted chunk is truncated".to_string()));
+            }
+            let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+            cipher.decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: aad.as_bytes() })
+                .map_err(|_| AppError::Other("Failed to decrypt chunk; wrong passphrase or corrupted data".to_string()))?
+        }
+        None => data,
+    };
+    if compression {
+        zstd::stream::decode_all(data.as_slice())
+            .map_err(|e| AppError::Other(format!("Failed to decompress chunk: {}", e)))
+    } else {
+        Ok(data)
+    }
+}
+
+/// Files below `dir` as `/`-separated relative paths with their sizes, sorted
+pub(crate) fn list_files(dir: &Path) -> Result<Vec<(String, u64)>, AppError> {
+    fn walk(dir: &Path, prefix: &str, files: &mut Vec<(String, u64)>) -> Result<(), AppError> {
+        let entries = fs::read_dir(dir)
+            .map_err(|e| AppError::Other(format!("Failed to read {}: {}", dir.display(), e)))?;
+        for entry in entries.flatten() {
+            let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+            let metadata = entry.metadata()
+                .map_err(|e| AppError::Other(format!("Failed to stat {}: {}", name, e)))?;
+            if metadata.is_dir() {
+                walk(&entry.path(), &format!("{}/", name), files)?;
+            } else {
+                files.push((name, metadata.len()));
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    walk(dir, "", &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+/// Write data to a fresh file in the temp directory
+fn temp_file(data: &[u8]) -> Result<PathBuf, AppError> {
+    let path = std::env::temp_dir().join(format!("kvm-manager-{}", uuid::Uuid::new_v4()));
+    write_private(&path, data)?;
+    Ok(path)
+}
+
+/// Quote a path for an sftp batch file
+fn quote(path: &str) -> String {
+    format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+}
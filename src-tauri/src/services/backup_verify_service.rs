@@ -0,0 +1,586 @@
+use crate::models::domain_xml::{Disk, DomainXml, XmlModel};
+use crate::models::event::{BackendEvent, TaskFailure, TaskKind};
+use crate::models::job::JobKind;
+use crate::services::backup_service::{BackupConfig, BackupMode, BackupRun, BackupService, BACKUP_CONNECTION, BACKUP_MANIFEST_FILE};
+use crate::services::backup_target_service::list_files;
+use crate::services::event_service::EventService;
+use crate::services::guest_agent_service::GuestAgentService;
+use crate::services::job_service::{self, JobHandle, JobService};
+use crate::services::libvirt::LibvirtService;
+use crate::services::restore_service::point_disk_at;
+use crate::utils::error::{map_libvirt_error, AppError};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use virt::domain::Domain;
+use virt::sys;
+
+/// File name of the checksum manifest written into every backup set
+pub const SET_MANIFEST_FILE: &str = "manifest.json";
+const HASH_BUFFER_SIZE: usize = 1024 * 1024;
+/// Interval between state and agent polls of a sandbox boot
+const BOOT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Devices removed from a sandbox so it can't reach the network, host hardware or shared state
+const SANDBOX_REMOVED_DEVICES: &[&str] = &["interface", "hostdev", "redirdev", "filesystem", "smartcard", "tpm"];
+/// Virtio-serial channel name of the QEMU guest agent
+const GUEST_AGENT_CHANNEL: &str = "org.qemu.guest_agent.0";
+
+fn default_boot_timeout() -> u64 {
+    180
+}
+
+/// How thoroughly a restore point is verified
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyOptions {
+    /// Boot a throwaway copy of the point without network access
+    #[serde(default)]
+    pub boot: bool,
+    /// Seconds the sandbox gets to boot; it passes if it is still running
+    /// then, or as soon as its guest agent answers
+    #[serde(default = "default_boot_timeout")]
+    pub boot_timeout_secs: u64,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        Self { boot: false, boot_timeout_secs: default_boot_timeout() }
+    }
+}
+
+/// A disk image of a set and its place in the chain
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestDisk {
+    pub target: String,
+    pub file: String,
+    /// Image in the parent set this file is an overlay of
+    pub backing: Option<String>,
+    pub size_bytes: u64,
+}
+
+/// A file of a set with its checksum
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestFile {
+    /// Path relative to the set directory
+    pub path: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+}
+
+/// Catalog entry of a backup set, written as `manifest.json` next to its files
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetManifest {
+    pub run_id: String,
+    pub vm_id: String,
+    pub vm_name: String,
+    pub kind: BackupMode,
+    pub parent_run_id: Option<String>,
+    pub created_at: i64,
+    /// Inactive domain XML the set was taken from
+    pub domain_xml: String,
+    pub disks: Vec<ManifestDisk>,
+    /// Every file of the set except the manifests themselves
+    pub files: Vec<ManifestFile>,
+    pub size_bytes: u64,
+}
+
+/// Outcome of a verification
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum VerifyStatus {
+    Passed,
+    Failed,
+}
+
+/// One check of a verification
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyCheck {
+    pub name: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
+/// Result of verifying a restore point, kept on the run
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupVerification {
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub status: VerifyStatus,
+    /// Whether a sandbox boot was part of the verification
+    pub booted: bool,
+    pub checks: Vec<VerifyCheck>,
+}
+
+impl BackupVerification {
+    /// Checks that failed, for messages and alerts
+    pub fn failures(&self) -> Vec<String> {
+        self.checks.iter()
+            .filter(|check| !check.ok)
+            .map(|check| format!("{}: {}", check.name, check.detail))
+            .collect()
+    }
+}
+
+/// BackupVerifyService writes the checksum manifests of backup sets and
+/// checks that restore points can still be restored.
+///
+/// A verification re-hashes every set of the point's chain against its
+/// manifest, runs `qemu-img check` on the images and, optionally, boots an
+/// overlay of the point as a transient domain with its network interfaces
+/// and passthrough devices removed. The sandbox is destroyed and its
+/// overlays deleted afterwards; the backup images are never written to.
+pub struct BackupVerifyService;
+
+impl BackupVerifyService {
+    /// Hash the files of a set and write its manifest
+    pub fn write_manifest(set_dir: &Path, run: &BackupRun, job: Option<&JobHandle>) -> Result<SetManifest, AppError> {
+        if let Some(job) = job {
+            job.set_step("Computing checksums");
+        }
+        let domain_xml = fs::read_to_string(set_dir.join("domain.xml"))
+            .map_err(|e| AppError::Other(format!("Failed to read domain XML of {}: {}", set_dir.display(), e)))?;
+
+        let entries: Vec<(String, u64)> = list_files(set_dir)?
+            .into_iter()
+            .filter(|(path, _)| !is_manifest(path))
+            .collect();
+        let total: u64 = entries.iter().map(|(_, size)| size).sum();
+        let mut processed = 0;
+        let mut files = Vec::with_capacity(entries.len());
+        for (path, size_bytes) in entries {
+            let sha256 = hash_file(&set_dir.join(&path), job, &mut processed, total)?;
+            files.push(ManifestFile { path, size_bytes, sha256 });
+        }
+
+        let manifest = SetManifest {
+            run_id: run.id.clone(),
+            vm_id: run.vm_id.clone(),
+            vm_name: run.vm_name.clone(),
+            kind: run.kind.clone(),
+            parent_run_id: run.parent_run_id.clone(),
+            created_at: run.started_at,
+            domain_xml,
+            disks: run.disks.iter()
+                .map(|disk| ManifestDisk {
+                    target: disk.target.clone(),
+                    file: disk.file.clone(),
+                    backing: disk.backing.clone(),
+                    size_bytes: disk.size_bytes,
+                })
+                .collect(),
+            size_bytes: total,
+            files,
+        };
+        let json = serde_json::to_string_pretty(&manifest)
+            .map_err(|e| AppError::Other(format!("Failed to serialize set manifest: {}", e)))?;
+        fs::write(set_dir.join(SET_MANIFEST_FILE), json)
+            .map_err(|e| AppError::Other(format!("Failed to write set manifest: {}", e)))?;
+        Ok(manifest)
+    }
+
+    /// Read the manifest of a set
+    pub fn read_manifest(set_dir: &Path) -> Result<SetManifest, AppError> {
+        let json = fs::read_to_string(set_dir.join(SET_MANIFEST_FILE))
+            .map_err(|_| AppError::NotFound(format!("Manifest of backup set {}", set_dir.display())))?;
+        serde_json::from_str(&json)
+            .map_err(|e| AppError::Other(format!("Failed to parse manifest of {}: {}", set_dir.display(), e)))
+    }
+
+    /// Start `verify_backup` as a background job and return the job ID.
+    ///
+    /// A restore point that fails verification is reported to the alert engine.
+    pub fn start_verify_job(
+        jobs: &Arc<JobService>,
+        events: Arc<EventService>,
+        libvirt: Arc<LibvirtService>,
+        config: BackupConfig,
+        run_id: String,
+        options: VerifyOptions,
+    ) -> String {
+        let description = format!("Verify backup of {}", config.name);
+        jobs.start(JobKind::VerifyBackup, description, config.vm_id.clone(), Some(BACKUP_CONNECTION.to_string()), move |job| {
+            let verification = Self::verify_backup(&libvirt, &config.id, &run_id, &options, Some(job))?;
+            if verification.status == VerifyStatus::Failed {
+                events.publish(BackendEvent::TaskFailed(TaskFailure {
+                    kind: TaskKind::BackupVerify,
+                    id: config.id.clone(),
+                    name: config.name.clone(),
+                    vm_id: Some(config.vm_id.clone()),
+                    error: verification.failures().join("; "),
+                }));
+            }
+            Ok(verification)
+        })
+    }
+
+    /// Verify a restore point and record the result on its run.
+    ///
+    /// Failed checks don't make this return an error; the verification is
+    /// returned with status `Failed`.
+    pub fn verify_backup(
+        libvirt: &LibvirtService,
+        config_id: &str,
+        run_id: &str,
+        options: &VerifyOptions,
+        job: Option<&JobHandle>,
+    ) -> Result<BackupVerification, AppError> {
+        let backups = BackupService::new()?;
+        let started_at = chrono::Utc::now().timestamp();
+        let mut checks = Vec::new();
+
+        // A point that can't be fetched is as unrestorable as a corrupt one
+        let chain = match backups.get_backup_point(config_id, run_id, job)
+            .and_then(|_| backups.point_chain(config_id, run_id))
+        {
+            Ok(chain) => chain,
+            Err(AppError::Cancelled) => return Err(AppError::Cancelled),
+            Err(e) => {
+                checks.push(VerifyCheck { name: "Restore point".to_string(), ok: false, detail: e.to_string() });
+                Vec::new()
+            }
+        };
+
+        let manifests: Vec<(&BackupRun, Option<SetManifest>)> = chain.iter()
+            .map(|point| {
+                let manifest = Self::read_manifest(Path::new(&point.path));
+                if let Err(e) = &manifest {
+                    checks.push(VerifyCheck { name: format!("Manifest of {}", set_name(point)), ok: false, detail: e.to_string() });
+                }
+                (point, manifest.ok())
+            })
+            .collect();
+
+        let total: u64 = manifests.iter()
+            .filter_map(|(_, manifest)| manifest.as_ref())
+            .map(|manifest| manifest.size_bytes)
+            .sum();
+        let mut processed = 0;
+        for (point, manifest) in &manifests {
+            let Some(manifest) = manifest else {
+                continue;
+            };
+            if let Some(job) = job {
+                job.set_step(format!("Verifying checksums of {}", set_name(point)));
+            }
+            checks.push(Self::check_checksums(point, manifest, job, &mut processed, total)?);
+        }
+
+        for (point, _) in &manifests {
+            if let Some(job) = job {
+                job.check_cancelled()?;
+                job.set_step(format!("Checking images of {}", set_name(point)));
+            }
+            for disk in &point.disks {
+                checks.push(VerifyCheck {
+                    name: format!("Image {} of {}", disk.target, set_name(point)),
+                    ..check_image(&Path::new(&point.path).join(&disk.file))
+                });
+            }
+        }
+
+        if let Some(point) = chain.last() {
+            checks.push(Self::check_domain_xml(point));
+        }
+
+        let mut booted = false;
+        if options.boot {
+            match chain.last() {
+                Some(point) if checks.iter().all(|check| check.ok) => {
+                    booted = true;
+                    let result = Self::boot_sandbox(libvirt, point, options, job);
+                    if let Err(AppError::Cancelled) = result {
+                        return Err(AppError::Cancelled);
+                    }
+                    checks.push(VerifyCheck {
+                        name: "Sandbox boot".to_string(),
+                        ok: result.is_ok(),
+                        detail: result.unwrap_or_else(|e| e.to_string()),
+                    });
+                }
+                _ => checks.push(VerifyCheck {
+                    name: "Sandbox boot".to_string(),
+                    ok: false,
+                    detail: "Skipped because earlier checks failed".to_string(),
+                }),
+            }
+        }
+
+        let status = if checks.iter().all(|check| check.ok) { VerifyStatus::Passed } else { VerifyStatus::Failed };
+        let verification = BackupVerification {
+            started_at,
+            finished_at: chrono::Utc::now().timestamp(),
+            status,
+            booted,
+            checks,
+        };
+        match verification.status {
+            VerifyStatus::Passed => tracing::info!("Backup run {} verified", run_id),
+            VerifyStatus::Failed => tracing::warn!("Backup run {} failed verification: {}", run_id, verification.failures().join("; ")),
+        }
+
+        backups.record_verification(config_id, run_id, &verification)?;
+        Ok(verification)
+    }
+
+    /// Re-hash the files of a set and compare them with its manifest
+    fn check_checksums(
+        point: &BackupRun,
+        manifest: &SetManifest,
+        job: Option<&JobHandle>,
+        processed: &mut u64,
+        total: u64,
+    ) -> Result<VerifyCheck, AppError> {
+        let set_dir = Path::new(&point.path);
+        let mut problems = Vec::new();
+        for file in &manifest.files {
+            let path = set_dir.join(&file.path);
+            match fs::metadata(&path) {
+                Err(_) => problems.push(format!("{} is missing", file.path)),
+                Ok(metadata) if metadata.len() != file.size_bytes => {
+                    problems.push(format!("{} is {} bytes, expected {}", file.path, metadata.len(), file.size_bytes));
+                }
+                Ok(_) => match hash_file(&path, job, processed, total) {
+                    Ok(sha256) if sha256 == file.sha256 => {}
+                    Ok(_) => problems.push(format!("{} has a different checksum", file.path)),
+                    Err(AppError::Cancelled) => return Err(AppError::Cancelled),
+                    Err(e) => problems.push(e.to_string()),
+                },
+            }
+        }
+
+        Ok(VerifyCheck {
+            name: format!("Checksums of {}", set_name(point)),
+            ok: problems.is_empty(),
+            detail: if problems.is_empty() {
+                format!("{} file(s) match the manifest", manifest.files.len())
+            } else {
+                problems.join("; ")
+            },
+        })
+    }
+
+    /// Check that the saved domain XML parses and still describes the backed-up disks
+    fn check_domain_xml(point: &BackupRun) -> VerifyCheck {
+        let result = fs::read_to_string(Path::new(&point.path).join("domain.xml"))
+            .map_err(|e| AppError::Other(format!("Failed to read domain.xml: {}", e)))
+            .and_then(|xml| DomainXml::parse(&xml))
+            .and_then(|xml| {
+                let targets: Vec<String> = xml.disks().into_iter().map(|disk| disk.target_dev).collect();
+                match point.disks.iter().find(|disk| !targets.contains(&disk.target)) {
+                    Some(disk) => Err(AppError::Other(format!("Disk {} is not in the domain XML", disk.target))),
+                    None => Ok(()),
+                }
+            });
+
+        VerifyCheck {
+            name: "Domain configuration".to_string(),
+            ok: result.is_ok(),
+            detail: match result {
+                Ok(()) => "Domain XML parses and lists every backed-up disk".to_string(),
+                Err(e) => e.to_string(),
+            },
+        }
+    }
+
+    /// Boot the point from throwaway overlays and wait for it to come up.
+    ///
+    /// Returns what was observed on success; the sandbox is always destroyed.
+    fn boot_sandbox(
+        libvirt: &LibvirtService,
+        point: &BackupRun,
+        options: &VerifyOptions,
+        job: Option<&JobHandle>,
+    ) -> Result<String, AppError> {
+        if !libvirt.is_local() {
+            return Err(AppError::Other(format!(
+                "Sandbox boots can only run on the local host, not {}", libvirt.get_uri()
+            )));
+        }
+        if let Some(job) = job {
+            job.set_step("Booting sandbox");
+        }
+
+        let set_dir = Path::new(&point.path);
+        let short_id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        // Next to the set, so QEMU can use the directory if it can read the set
+        let sandbox_dir = set_dir.parent().unwrap_or(set_dir).join(format!(".verify-{}", short_id));
+        fs::create_dir_all(&sandbox_dir)
+            .map_err(|e| AppError::Other(format!("Failed to create sandbox directory: {}", e)))?;
+
+        let result = Self::run_sandbox(libvirt, point, &sandbox_dir, &format!("verify-{}-{}", point.vm_name, short_id), options, job);
+        if let Err(e) = fs::remove_dir_all(&sandbox_dir) {
+            tracing::warn!("Failed to remove sandbox directory {}: {}", sandbox_dir.display(), e);
+        }
+        result
+    }
+
+    fn run_sandbox(
+        libvirt: &LibvirtService,
+        point: &BackupRun,
+        sandbox_dir: &Path,
+        name: &str,
+        options: &VerifyOptions,
+        job: Option<&JobHandle>,
+    ) -> Result<String, AppError> {
+        let set_dir = Path::new(&point.path);
+        let inactive = fs::read_to_string(set_dir.join("domain.xml"))
+            .map_err(|e| AppError::Other(format!("Failed to read domain XML: {}", e)))?;
+        let mut xml = DomainXml::parse(&inactive)?;
+        xml.set_name(name);
+        xml.set_uuid(None);
+
+        let targets: Vec<&str> = point.disks.iter().map(|disk| disk.target.as_str()).collect();
+        xml.remove_devices(|device| {
+            SANDBOX_REMOVED_DEVICES.contains(&device.name.as_str())
+                || (device.name == "disk" && !targets.contains(&Disk::from_element(device).target_dev.as_str()))
+        });
+        let has_agent = xml.devices_named("channel").iter()
+            .any(|channel| channel.child("target").and_then(|target| target.attr("name")) == Some(GUEST_AGENT_CHANNEL));
+
+        // Writes go to overlays; the backup images are only read
+        for disk in &point.disks {
+            let image = set_dir.join(&disk.file).to_string_lossy().to_string();
+            let overlay = sandbox_dir.join(&disk.file).to_string_lossy().to_string();
+            job_service::run_qemu_img(None, &["create", "-f", "qcow2", "-F", "qcow2", "-b", &image, &overlay], 0.0..100.0)?;
+            if let Some(element) = xml.disk_element_mut(&disk.target) {
+                point_disk_at(element, &overlay, "qcow2");
+            }
+        }
+
+        if let Some(nvram) = &point.nvram {
+            let copy = sandbox_dir.join(nvram);
+            fs::copy(set_dir.join(nvram), &copy)
+                .map_err(|e| AppError::Other(format!("Failed to copy NVRAM: {}", e)))?;
+            let mut os = xml.os();
+            if let Some(os_nvram) = os.nvram.as_mut() {
+                os_nvram.path = Some(copy.to_string_lossy().to_string());
+                xml.set_os(&os);
+            }
+        }
+
+        // Autodestroy tears the sandbox down if the app exits mid-verification
+        let domain = Domain::create_xml(libvirt.get_connection(), &xml.to_xml(), sys::VIR_DOMAIN_START_AUTODESTROY)
+            .map_err(map_libvirt_error)?;
+        tracing::info!("Started sandbox {} for backup run {}", name, point.id);
+
+        let result = Self::watch_sandbox(&domain, libvirt.get_uri(), name, has_agent, options, job);
+        if domain.is_active().unwrap_or(false) {
+            if let Err(e) = domain.destroy() {
+                tracing::error!("Failed to destroy sandbox {}: {}", name, e);
+            }
+        }
+        result
+    }
+
+    /// Poll a sandbox until its guest agent answers, the timeout passes or it stops.
+    ///
+    /// With a guest agent channel the boot only passes once the agent answers;
+    /// without one, still running at the timeout is the best evidence available.
+    fn watch_sandbox(
+        domain: &Domain,
+        uri: &str,
+        name: &str,
+        has_agent: bool,
+        options: &VerifyOptions,
+        job: Option<&JobHandle>,
+    ) -> Result<String, AppError> {
+        let agent = GuestAgentService::new();
+        let started = Instant::now();
+        let timeout = Duration::from_secs(options.boot_timeout_secs.max(1));
+        loop {
+            if let Some(job) = job {
+                job.check_cancelled()?;
+            }
+            let (state, _) = domain.get_state().map_err(map_libvirt_error)?;
+            match state {
+                sys::VIR_DOMAIN_RUNNING => {}
+                sys::VIR_DOMAIN_CRASHED | sys::VIR_DOMAIN_PMSUSPENDED | sys::VIR_DOMAIN_PAUSED => {
+                    return Err(AppError::Other(format!("Sandbox stopped making progress after {}s (state {})", started.elapsed().as_secs(), state)));
+                }
+                _ => {
+                    return Err(AppError::Other(format!("Sandbox shut down after {}s", started.elapsed().as_secs())));
+                }
+            }
+
+            if has_agent && agent.ping_on(Some(uri), name).is_ok() {
+                return Ok(format!("Guest agent answered after {}s", started.elapsed().as_secs()));
+            }
+            if started.elapsed() >= timeout {
+                // A guest configured with an agent that never answers did not finish booting
+                if has_agent {
+                    return Err(AppError::Other(format!(
+                        "Guest agent did not answer within {}s", timeout.as_secs()
+                    )));
+                }
+                return Ok(format!("Still running after {}s", timeout.as_secs()));
+            }
+            thread::sleep(BOOT_POLL_INTERVAL);
+        }
+    }
+}
+
+/// Whether a set file is one of the manifests, which are rewritten after the set is hashed
+fn is_manifest(path: &str) -> bool {
+    path == SET_MANIFEST_FILE || path == BACKUP_MANIFEST_FILE
+}
+
+/// Directory name of a run's set, used to label checks
+fn set_name(point: &BackupRun) -> String {
+    Path::new(&point.path).file_name().unwrap_or_default().to_string_lossy().to_string()
+}
+
+/// SHA-256 of a file as hex, reporting progress across a multi-file total
+fn hash_file(path: &Path, job: Option<&JobHandle>, processed: &mut u64, total: u64) -> Result<String, AppError> {
+    let mut file = fs::File::open(path)
+        .map_err(|e| AppError::Other(format!("Failed to open {}: {}", path.display(), e)))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    loop {
+        let read = file.read(&mut buffer)
+            .map_err(|e| AppError::Other(format!("Failed to read {}: {}", path.display(), e)))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        *processed += read as u64;
+        if let Some(job) = job {
+            job.check_cancelled()?;
+            job.set_bytes(*processed, total);
+        }
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Run `qemu-img check` on an image; leaked clusters waste space but don't lose data
+fn check_image(path: &Path) -> VerifyCheck {
+    let failed = |detail: String| VerifyCheck { name: String::new(), ok: false, detail };
+    let output = match Command::new("qemu-img")
+        .args(["check", "-f", "qcow2", "--output=json"])
+        .arg(path)
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => return failed(format!("Failed to run qemu-img: {}", e)),
+    };
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap_or_default();
+    let count = |key: &str| report.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    let (ok, detail) = match output.status.code() {
+        Some(0) => (true, "No errors".to_string()),
+        Some(3) => (true, format!("{} leaked cluster(s), no corruption", count("leaks"))),
+        Some(2) => (false, format!("{} corruption(s), {} leaked cluster(s)", count("corruptions"), count("leaks"))),
+        _ => (false, String::from_utf8_lossy(&output.stderr).trim().to_string()),
+    };
+    VerifyCheck { name: String::new(), ok, detail }
+}
@@ -1,7 +1,14 @@
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
 use virt::connect::Connect;
+use crate::models::event::{BackendEvent, ConnectionState, ConnectionStateEvent, CONNECTION_STATE_EVENT};
+use crate::services::event_service::EventService;
+use crate::services::libvirt::LibvirtService;
+use crate::services::secret_service::{ConnectionCredentials, SecretService};
 use crate::utils::error::AppError;
 
 /// Connection type enum
@@ -74,6 +81,36 @@ impl SavedConnection {
         }
     }
 
+    /// Build the libvirt URI for this connection, pointing libvirt at any
+    /// stored SSH key or TLS PKI directory
+    pub fn build_uri_with_credentials(&self, credentials: Option<&ConnectionCredentials>) -> String {
+        let uri = self.build_uri();
+        let Some(creds) = credentials else {
+            return uri;
+        };
+
+        let mut params = Vec::new();
+        match self.connection_type {
+            ConnectionType::Ssh => {
+                if let Some(key) = &creds.ssh_key_path {
+                    params.push(format!("keyfile={}", key));
+                }
+            }
+            ConnectionType::Tls => {
+                if let Some(pki) = &creds.tls_pki_path {
+                    params.push(format!("pkipath={}", pki));
+                }
+            }
+            _ => {}
+        }
+
+        if params.is_empty() {
+            uri
+        } else {
+            format!("{}?{}", uri, params.join("&"))
+        }
+    }
+
     /// Build the libvirt URI for this connection
     pub fn build_uri(&self) -> String {
         match self.connection_type {
@@ -108,38 +145,138 @@ impl SavedConnection {
 }
 
 /// Active connection wrapper
-#[allow(dead_code)]
 struct ActiveConnection {
     config: SavedConnection,
-    connection: Connect,
+    libvirt: Arc<LibvirtService>,
+}
+
+/// Reconnect bookkeeping for a supervised connection
+struct ConnectionHealth {
+    state: ConnectionState,
+    attempts: u32,
+    next_attempt: Instant,
+    last_error: Option<String>,
 }
 
+/// How often the supervisor checks connection health
+const SUPERVISOR_INTERVAL: Duration = Duration::from_secs(2);
+/// Upper bound for the reconnect backoff
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(60);
+
 /// Connection service manages multiple libvirt connections
 pub struct ConnectionService {
     /// Currently active connection ID
     active_connection_id: RwLock<Option<String>>,
-    /// Active connections (only one for now, but extensible)
+    /// Live connections keyed by saved connection ID
     connections: RwLock<HashMap<String, ActiveConnection>>,
     /// Saved connection configurations
     saved_connections: RwLock<Vec<SavedConnection>>,
+    /// Path of the persisted connection list
+    config_path: PathBuf,
+    /// Encrypted per-connection credentials
+    secrets: Arc<SecretService>,
+    /// Forwards libvirt events from every live connection
+    events: Arc<EventService>,
+    /// Connections the supervisor keeps alive, keyed by connection ID
+    health: RwLock<HashMap<String, ConnectionHealth>>,
 }
 
 impl ConnectionService {
-    /// Create a new connection service
-    pub fn new() -> Self {
-        let mut saved = Vec::new();
+    /// Create a new connection service, loading saved connections from disk
+    pub fn new(events: Arc<EventService>) -> Result<Self, AppError> {
+        let config_dir = dirs::config_dir()
+            .ok_or_else(|| AppError::InvalidConfig("Could not determine config directory".to_string()))?
+            .join("kvm-manager");
+
+        fs::create_dir_all(&config_dir)?;
+
+        let config_path = config_dir.join("connections.json");
+        let mut saved = Self::load_connections(&config_path)?;
+
         // Always include local connection
-        saved.push(SavedConnection::local());
+        if !saved.iter().any(|c| c.id == "local") {
+            saved.insert(0, SavedConnection::local());
+        }
 
-        Self {
+        // Credentials of remote hosts were lost with an unreadable store
+        let secrets = SecretService::shared()?;
+        if secrets.was_reset() {
+            secrets.mark_needs_credentials(saved.iter().filter(|c| c.id != "local").map(|c| c.id.clone()));
+        }
+
+        Ok(Self {
             active_connection_id: RwLock::new(None),
             connections: RwLock::new(HashMap::new()),
             saved_connections: RwLock::new(saved),
+            config_path,
+            secrets,
+            events,
+            health: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Load saved connections from disk
+    fn load_connections(path: &PathBuf) -> Result<Vec<SavedConnection>, AppError> {
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let contents = fs::read_to_string(path)?;
+
+        serde_json::from_str(&contents)
+            .map_err(|e| AppError::InvalidConfig(format!("Failed to parse saved connections: {}", e)))
+    }
+
+    /// Save connections to disk
+    fn save_connections(&self, saved: &[SavedConnection]) -> Result<(), AppError> {
+        let json = serde_json::to_string_pretty(saved)
+            .map_err(|e| AppError::InvalidConfig(format!("Failed to serialize connections: {}", e)))?;
+
+        fs::write(&self.config_path, json)?;
+
+        Ok(())
+    }
+
+    /// Connect to every saved connection marked for auto-connect.
+    ///
+    /// Failures are logged and returned rather than aborting, so one
+    /// unreachable host does not keep the others offline.
+    pub fn auto_connect(&self) -> Vec<(String, AppError)> {
+        let targets: Vec<String> = match self.saved_connections.read() {
+            Ok(saved) => saved.iter()
+                .filter(|c| c.auto_connect)
+                .map(|c| c.id.clone())
+                .collect(),
+            Err(_) => return Vec::new(),
+        };
+
+        let mut failures = Vec::new();
+        for id in targets {
+            if let Err(e) = self.open_connection(&id) {
+                tracing::warn!("Auto-connect to {} failed: {}", id, e);
+                self.supervise(&id, &e);
+                failures.push((id, e));
+            }
         }
+
+        failures
     }
 
-    /// Connect to a saved connection by ID
+    /// Connect to a saved connection by ID and make it the active connection
     pub fn connect(&self, connection_id: &str) -> Result<(), AppError> {
+        self.open_connection(connection_id)?;
+
+        let mut active = self.active_connection_id.write()
+            .map_err(|_| AppError::Other("Failed to lock active connection".to_string()))?;
+        *active = Some(connection_id.to_string());
+
+        Ok(())
+    }
+
+    /// Open a saved connection without changing the active connection.
+    ///
+    /// The first connection opened becomes active so commands have a default.
+    pub fn open_connection(&self, connection_id: &str) -> Result<(), AppError> {
         // Find the saved connection
         let saved = {
             let saved_conns = self.saved_connections.read()
@@ -151,37 +288,76 @@ impl ConnectionService {
             AppError::Other(format!("Connection '{}' not found", connection_id))
         })?;
 
-        let uri = config.build_uri();
-        tracing::info!("Connecting to libvirt: {}", uri);
+        // Reuse an existing live connection so switching hosts is cheap
+        let already_connected = {
+            let conns = self.connections.read()
+                .map_err(|_| AppError::Other("Failed to lock connections".to_string()))?;
+            conns.get(connection_id).map(|c| c.libvirt.is_alive()).unwrap_or(false)
+        };
 
-        // Attempt connection
-        let connection = Connect::open(Some(&uri))
-            .map_err(|e| AppError::LibvirtError(format!("Failed to connect to {}: {}", uri, e)))?;
+        if !already_connected {
+            let credentials = self.secrets.get(connection_id)?;
+            let uri = config.build_uri_with_credentials(credentials.as_ref());
+            tracing::info!("Connecting to libvirt: {}", uri);
+
+            // Attempt connection
+            let libvirt = match Self::open_libvirt(&uri, credentials.as_ref()) {
+                Ok(libvirt) => Arc::new(libvirt),
+                Err(e) if self.secrets.needs_credentials().iter().any(|id| id == connection_id) => {
+                    return Err(AppError::Other(format!(
+                        "{} (the stored credentials for this connection were lost, enter them again)", e
+                    )));
+                }
+                Err(e) => return Err(e),
+            };
 
-        tracing::info!("Successfully connected to {}", uri);
+            // Events are best effort; the connection is still usable without them
+            if let Err(e) = self.events.register(connection_id, libvirt.clone()) {
+                tracing::warn!("Failed to register libvirt events on {}: {}", connection_id, e);
+            }
 
-        // Store active connection
-        {
+            // Store active connection
             let mut conns = self.connections.write()
                 .map_err(|_| AppError::Other("Failed to lock connections".to_string()))?;
             conns.insert(connection_id.to_string(), ActiveConnection {
                 config: config.clone(),
-                connection,
+                libvirt,
             });
+            drop(conns);
+
+            self.set_health(connection_id, ConnectionState::Connected, 0, None, None);
         }
 
-        // Set as active
-        {
-            let mut active = self.active_connection_id.write()
-                .map_err(|_| AppError::Other("Failed to lock active connection".to_string()))?;
+        let mut active = self.active_connection_id.write()
+            .map_err(|_| AppError::Other("Failed to lock active connection".to_string()))?;
+        if active.is_none() {
             *active = Some(connection_id.to_string());
         }
 
         Ok(())
     }
 
+    /// Open a libvirt connection, answering SASL prompts when a password is stored
+    pub fn open_libvirt(uri: &str, credentials: Option<&ConnectionCredentials>) -> Result<LibvirtService, AppError> {
+        match credentials {
+            Some(ConnectionCredentials {
+                sasl_username: Some(username),
+                sasl_password: Some(password),
+                ..
+            }) => LibvirtService::open_with_auth(uri, username, password),
+            _ => LibvirtService::open(uri),
+        }
+    }
+
     /// Disconnect from a connection
     pub fn disconnect(&self, connection_id: &str) -> Result<(), AppError> {
+        self.events.deregister(connection_id);
+
+        // An explicit disconnect stops the supervisor from reconnecting
+        if let Ok(mut health) = self.health.write() {
+            health.remove(connection_id);
+        }
+
         let mut conns = self.connections.write()
             .map_err(|_| AppError::Other("Failed to lock connections".to_string()))?;
 
@@ -189,11 +365,11 @@ impl ConnectionService {
             tracing::info!("Disconnected from {}", connection_id);
         }
 
-        // If this was the active connection, clear it
+        // If this was the active connection, fall back to another live one
         let mut active = self.active_connection_id.write()
             .map_err(|_| AppError::Other("Failed to lock active connection".to_string()))?;
         if active.as_deref() == Some(connection_id) {
-            *active = None;
+            *active = conns.keys().min().cloned();
         }
 
         Ok(())
@@ -202,26 +378,199 @@ impl ConnectionService {
     /// Get the active libvirt connection
     #[allow(dead_code)]
     pub fn get_active_connection(&self) -> Result<Connect, AppError> {
-        let active_id = {
-            let active = self.active_connection_id.read()
-                .map_err(|_| AppError::Other("Failed to lock active connection".to_string()))?;
-            active.clone()
+        let libvirt = self.get_libvirt(None)?;
+
+        // Clone the connection for use
+        // Note: libvirt connections can be shared via cloning
+        Ok(libvirt.get_connection().clone())
+    }
+
+    /// Get the libvirt service for a connection.
+    ///
+    /// When `connection_id` is `None` the currently active connection is used,
+    /// so commands follow whatever host was last selected with `connect`.
+    pub fn get_libvirt(&self, connection_id: Option<&str>) -> Result<Arc<LibvirtService>, AppError> {
+        let connection_id = match connection_id {
+            Some(id) => id.to_string(),
+            None => {
+                let active = self.active_connection_id.read()
+                    .map_err(|_| AppError::Other("Failed to lock active connection".to_string()))?;
+                active.clone().ok_or_else(|| {
+                    AppError::Other("No active connection".to_string())
+                })?
+            }
         };
 
-        let connection_id = active_id.ok_or_else(|| {
-            AppError::Other("No active connection".to_string())
-        })?;
+        let conns = self.connections.read()
+            .map_err(|_| AppError::Other("Failed to lock connections".to_string()))?;
+
+        let libvirt = conns.get(&connection_id)
+            .map(|c| c.libvirt.clone())
+            .ok_or_else(|| AppError::NotConnected(format!("connection '{}' is not open", connection_id)))?;
+
+        // Fail fast while the supervisor is reconnecting instead of hanging on a dead socket
+        if !libvirt.is_alive() {
+            return Err(AppError::NotConnected(format!("connection '{}' was lost, reconnecting", connection_id)));
+        }
+
+        Ok(libvirt)
+    }
+
+    /// Keep retrying a connection that failed to open or was lost.
+    ///
+    /// If no connection is active yet this one becomes active, so commands
+    /// start working as soon as it comes up.
+    pub fn supervise(&self, connection_id: &str, error: &AppError) {
+        self.set_health(connection_id, ConnectionState::Reconnecting, 0, Some(Duration::ZERO), Some(error.to_string()));
+
+        if let Ok(mut active) = self.active_connection_id.write() {
+            if active.is_none() {
+                *active = Some(connection_id.to_string());
+            }
+        }
+    }
+
+    /// Get the supervised state of every connection
+    pub fn get_connection_states(&self) -> Result<Vec<ConnectionStateEvent>, AppError> {
+        let health = self.health.read()
+            .map_err(|_| AppError::Other("Failed to lock connection health".to_string()))?;
+
+        let now = Instant::now();
+        let mut states: Vec<ConnectionStateEvent> = health.iter()
+            .map(|(id, h)| ConnectionStateEvent {
+                connection_id: id.clone(),
+                state: h.state.clone(),
+                attempt: h.attempts,
+                retry_in_secs: match h.state {
+                    ConnectionState::Connected => None,
+                    _ => Some(h.next_attempt.saturating_duration_since(now).as_secs()),
+                },
+                error: h.last_error.clone(),
+                timestamp: chrono::Utc::now().timestamp_millis(),
+            })
+            .collect();
+        states.sort_by(|a, b| a.connection_id.cmp(&b.connection_id));
+
+        Ok(states)
+    }
+
+    /// Record a connection's health and notify the frontend
+    fn set_health(
+        &self,
+        connection_id: &str,
+        state: ConnectionState,
+        attempts: u32,
+        retry_in: Option<Duration>,
+        error: Option<String>,
+    ) {
+        if let Ok(mut health) = self.health.write() {
+            health.insert(connection_id.to_string(), ConnectionHealth {
+                state: state.clone(),
+                attempts,
+                next_attempt: Instant::now() + retry_in.unwrap_or(Duration::ZERO),
+                last_error: error.clone(),
+            });
+        }
+
+        let event = ConnectionStateEvent {
+            connection_id: connection_id.to_string(),
+            state,
+            attempt: attempts,
+            retry_in_secs: retry_in.map(|d| d.as_secs()),
+            error,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        };
+        self.events.emit(CONNECTION_STATE_EVENT, event.clone());
+        self.events.publish(BackendEvent::Connection(event));
+    }
+
+    /// Check every supervised connection and reconnect the dead ones
+    fn check_connections(&self) {
+        let supervised: Vec<(String, ConnectionState, u32, Instant)> = match self.health.read() {
+            Ok(health) => health.iter()
+                .map(|(id, h)| (id.clone(), h.state.clone(), h.attempts, h.next_attempt))
+                .collect(),
+            Err(_) => return,
+        };
+
+        for (id, state, attempts, next_attempt) in supervised {
+            let alive = self.connections.read().ok()
+                .and_then(|conns| conns.get(&id).map(|c| c.libvirt.is_alive()))
+                .unwrap_or(false);
+
+            if alive {
+                if state != ConnectionState::Connected {
+                    self.set_health(&id, ConnectionState::Connected, 0, None, None);
+                }
+                continue;
+            }
+
+            if state == ConnectionState::Connected {
+                tracing::warn!("Connection {} lost, starting reconnect", id);
+                self.events.deregister(&id);
+                self.set_health(&id, ConnectionState::Disconnected, 0, Some(Duration::ZERO), Some("Connection lost".to_string()));
+            } else if Instant::now() < next_attempt {
+                continue;
+            }
+
+            // Drop the dead handle so open_connection builds a fresh one
+            if let Ok(mut conns) = self.connections.write() {
+                conns.remove(&id);
+            }
 
+            match self.open_connection(&id) {
+                Ok(()) => tracing::info!("Reconnected to {}", id),
+                Err(e) => {
+                    let attempt = attempts + 1;
+                    let backoff = Duration::from_secs(1u64 << attempt.min(6)).min(MAX_RECONNECT_BACKOFF);
+                    tracing::warn!("Reconnect to {} failed (attempt {}), retrying in {:?}: {}", id, attempt, backoff, e);
+                    self.set_health(&id, ConnectionState::Reconnecting, attempt, Some(backoff), Some(e.to_string()));
+                }
+            }
+        }
+    }
+
+    /// Start background connection supervisor task
+    pub fn start_supervisor_task(self: Arc<Self>) {
+        tracing::info!("Starting connection supervisor task");
+
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(SUPERVISOR_INTERVAL).await;
+
+                // Reconnecting may block on the network, keep it off the async workers
+                let service = self.clone();
+                if let Err(e) = tauri::async_runtime::spawn_blocking(move || service.check_connections()).await {
+                    tracing::error!("Connection supervisor check failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Get the libvirt services of every live connection, keyed by connection ID
+    pub fn get_connected(&self) -> Result<Vec<(String, Arc<LibvirtService>)>, AppError> {
         let conns = self.connections.read()
             .map_err(|_| AppError::Other("Failed to lock connections".to_string()))?;
 
-        let conn = conns.get(&connection_id).ok_or_else(|| {
-            AppError::Other("Active connection not found".to_string())
-        })?;
+        let mut connected: Vec<(String, Arc<LibvirtService>)> = conns.iter()
+            .map(|(id, c)| (id.clone(), c.libvirt.clone()))
+            .collect();
+        connected.sort_by(|a, b| a.0.cmp(&b.0));
 
-        // Clone the connection for use
-        // Note: libvirt connections can be shared via cloning
-        Ok(conn.connection.clone())
+        Ok(connected)
+    }
+
+    /// Get the configurations of every live connection
+    pub fn get_connected_connections(&self) -> Result<Vec<SavedConnection>, AppError> {
+        let conns = self.connections.read()
+            .map_err(|_| AppError::Other("Failed to lock connections".to_string()))?;
+
+        let mut configs: Vec<SavedConnection> = conns.values()
+            .map(|c| c.config.clone())
+            .collect();
+        configs.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Ok(configs)
     }
 
     /// Get the currently active connection info
@@ -251,7 +600,7 @@ impl ConnectionService {
         if let Some(id) = active_id {
             if let Ok(conns) = self.connections.read() {
                 if let Some(conn) = conns.get(&id) {
-                    return conn.connection.is_alive().unwrap_or(false);
+                    return conn.libvirt.is_alive();
                 }
             }
         }
@@ -276,7 +625,7 @@ impl ConnectionService {
         }
 
         saved.push(config);
-        Ok(())
+        self.save_connections(&saved)
     }
 
     /// Update an existing connection
@@ -286,7 +635,7 @@ impl ConnectionService {
 
         if let Some(existing) = saved.iter_mut().find(|c| c.id == config.id) {
             *existing = config;
-            Ok(())
+            self.save_connections(&saved)
         } else {
             Err(AppError::Other(format!("Connection '{}' not found", config.id)))
         }
@@ -306,7 +655,38 @@ impl ConnectionService {
             .map_err(|_| AppError::Other("Failed to lock saved connections".to_string()))?;
 
         saved.retain(|c| c.id != connection_id);
-        Ok(())
+        self.save_connections(&saved)?;
+        drop(saved);
+
+        self.secrets.remove(connection_id)
+    }
+
+    /// Store credentials for a saved connection
+    pub fn set_credentials(&self, connection_id: &str, credentials: ConnectionCredentials) -> Result<(), AppError> {
+        let exists = self.saved_connections.read()
+            .map_err(|_| AppError::Other("Failed to lock saved connections".to_string()))?
+            .iter()
+            .any(|c| c.id == connection_id);
+        if !exists {
+            return Err(AppError::Other(format!("Connection '{}' not found", connection_id)));
+        }
+
+        self.secrets.set(connection_id, credentials)
+    }
+
+    /// Get the credentials stored for a saved connection
+    pub fn get_credentials(&self, connection_id: &str) -> Result<Option<ConnectionCredentials>, AppError> {
+        self.secrets.get(connection_id)
+    }
+
+    /// Saved connections whose credentials were lost with an unreadable secret store
+    pub fn connections_needing_credentials(&self) -> Vec<String> {
+        self.secrets.needs_credentials()
+    }
+
+    /// Remove the credentials stored for a saved connection
+    pub fn clear_credentials(&self, connection_id: &str) -> Result<(), AppError> {
+        self.secrets.remove(connection_id)
     }
 
     /// Get hostname of the active connection
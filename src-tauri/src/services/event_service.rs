@@ -0,0 +1,480 @@
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use virt::sys;
+use crate::models::event::{
+    BackendEvent, BlockJobStatus, BlockJobType, LifecycleEvent, NetworkEvent, ResourceLifecycleEvent,
+    StoragePoolEvent, VmEvent, VmEventKind, NETWORK_EVENT, STORAGE_POOL_EVENT, VM_EVENT,
+};
+use crate::services::libvirt::LibvirtService;
+use crate::services::vm_service::VmService;
+use crate::utils::error::AppError;
+
+/// Callback registered on a connection, kept so it can be removed again
+enum Registration {
+    Domain(c_int),
+    Network(c_int),
+    StoragePool(c_int),
+}
+
+/// Data handed to libvirt as the callback opaque pointer
+struct CallbackContext {
+    connection_id: String,
+    service: Arc<EventService>,
+}
+
+/// In-process handler for published backend events
+type Subscriber = Box<dyn Fn(&BackendEvent) + Send + Sync>;
+
+/// EventService forwards libvirt domain, network and storage pool events
+/// to the frontend as typed Tauri events.
+///
+/// libvirt only dispatches callbacks from its event loop, so
+/// `init_event_loop` must run before the first connection is opened.
+///
+/// Published backend events are queued and handed to subscribers on a
+/// worker thread, so a slow subscriber never stalls the libvirt event loop.
+pub struct EventService {
+    app: RwLock<Option<AppHandle>>,
+    registrations: Mutex<HashMap<String, (Arc<LibvirtService>, Vec<Registration>)>>,
+    subscribers: Arc<RwLock<Vec<Subscriber>>>,
+    queue: Mutex<Sender<BackendEvent>>,
+}
+
+impl EventService {
+    /// Create a new event service
+    pub fn new() -> Self {
+        let subscribers: Arc<RwLock<Vec<Subscriber>>> = Arc::new(RwLock::new(Vec::new()));
+        let (queue, events) = mpsc::channel::<BackendEvent>();
+
+        let worker_subscribers = subscribers.clone();
+        let worker = std::thread::Builder::new()
+            .name("event-subscribers".to_string())
+            .spawn(move || {
+                for event in events {
+                    if let Ok(subscribers) = worker_subscribers.read() {
+                        for subscriber in subscribers.iter() {
+                            subscriber(&event);
+                        }
+                    }
+                }
+            });
+        if let Err(e) = worker {
+            tracing::error!("Failed to start event subscriber thread: {}", e);
+        }
+
+        Self {
+            app: RwLock::new(None),
+            registrations: Mutex::new(HashMap::new()),
+            subscribers,
+            queue: Mutex::new(queue),
+        }
+    }
+
+    /// Register libvirt's default event loop and run it on a dedicated thread
+    pub fn init_event_loop() -> Result<(), AppError> {
+        let ret = unsafe { sys::virEventRegisterDefaultImpl() };
+        if ret < 0 {
+            return Err(AppError::LibvirtError("Failed to register libvirt event loop".to_string()));
+        }
+
+        std::thread::Builder::new()
+            .name("libvirt-events".to_string())
+            .spawn(|| loop {
+                if unsafe { sys::virEventRunDefaultImpl() } < 0 {
+                    tracing::error!("libvirt event loop iteration failed");
+                    std::thread::sleep(std::time::Duration::from_secs(1));
+                }
+            })?;
+
+        tracing::info!("libvirt event loop started");
+        Ok(())
+    }
+
+    /// Set the app handle used to emit events; events before this are dropped
+    pub fn set_app_handle(&self, app: AppHandle) {
+        if let Ok(mut slot) = self.app.write() {
+            *slot = Some(app);
+        }
+    }
+
+    /// Emit a Tauri event to all windows
+    pub fn emit<T: Serialize + Clone>(&self, event: &str, payload: T) {
+        if let Ok(app) = self.app.read() {
+            if let Some(app) = app.as_ref() {
+                if let Err(e) = app.emit(event, payload) {
+                    tracing::warn!("Failed to emit {}: {}", event, e);
+                }
+            }
+        }
+    }
+
+    /// Register a handler for backend events
+    pub fn subscribe(&self, subscriber: impl Fn(&BackendEvent) + Send + Sync + 'static) {
+        if let Ok(mut subscribers) = self.subscribers.write() {
+            subscribers.push(Box::new(subscriber));
+        }
+    }
+
+    /// Queue an event for the subscribers; returns without waiting for them
+    pub fn publish(&self, event: BackendEvent) {
+        let sent = self.queue.lock()
+            .map_err(|_| "queue lock poisoned".to_string())
+            .and_then(|queue| queue.send(event).map_err(|e| e.to_string()));
+        if let Err(e) = sent {
+            tracing::warn!("Failed to queue backend event: {}", e);
+        }
+    }
+
+    /// Show a native desktop notification
+    pub fn notify_desktop(&self, title: &str, body: &str) -> Result<(), AppError> {
+        use tauri_plugin_notification::NotificationExt;
+
+        let app = self.app.read()
+            .map_err(|_| AppError::Other("Failed to lock app handle".to_string()))?
+            .clone()
+            .ok_or_else(|| AppError::NotificationError("Application is not ready".to_string()))?;
+
+        app.notification()
+            .builder()
+            .title(title)
+            .body(body)
+            .show()
+            .map_err(|e| AppError::NotificationError(format!("Failed to show desktop notification: {}", e)))
+    }
+
+    /// Register event callbacks on a freshly opened connection
+    pub fn register(self: &Arc<Self>, connection_id: &str, libvirt: Arc<LibvirtService>) -> Result<(), AppError> {
+        // Drop callbacks left over from a previous connection with the same ID
+        self.deregister(connection_id);
+
+        let conn = libvirt.get_connection().as_ptr();
+        let mut registered = Vec::new();
+
+        let domain_callbacks: [(u32, unsafe extern "C" fn()); 7] = [
+            (sys::VIR_DOMAIN_EVENT_ID_LIFECYCLE, Self::erase(domain_lifecycle_cb as *const ())),
+            (sys::VIR_DOMAIN_EVENT_ID_REBOOT, Self::erase(domain_reboot_cb as *const ())),
+            (sys::VIR_DOMAIN_EVENT_ID_DEVICE_ADDED, Self::erase(domain_device_added_cb as *const ())),
+            (sys::VIR_DOMAIN_EVENT_ID_DEVICE_REMOVED, Self::erase(domain_device_removed_cb as *const ())),
+            (sys::VIR_DOMAIN_EVENT_ID_BLOCK_JOB_2, Self::erase(domain_block_job_cb as *const ())),
+            (sys::VIR_DOMAIN_EVENT_ID_AGENT_LIFECYCLE, Self::erase(domain_agent_lifecycle_cb as *const ())),
+            (sys::VIR_DOMAIN_EVENT_ID_WATCHDOG, Self::erase(domain_watchdog_cb as *const ())),
+        ];
+
+        for (event_id, callback) in domain_callbacks {
+            let id = unsafe {
+                sys::virConnectDomainEventRegisterAny(
+                    conn,
+                    std::ptr::null_mut(),
+                    event_id as c_int,
+                    Some(std::mem::transmute(callback)),
+                    self.context(connection_id),
+                    Some(free_context),
+                )
+            };
+            if id < 0 {
+                tracing::warn!("Failed to register domain event {} on {}", event_id, connection_id);
+            } else {
+                registered.push(Registration::Domain(id));
+            }
+        }
+
+        let network_id = unsafe {
+            sys::virConnectNetworkEventRegisterAny(
+                conn,
+                std::ptr::null_mut(),
+                sys::VIR_NETWORK_EVENT_ID_LIFECYCLE as c_int,
+                Some(std::mem::transmute(Self::erase(network_lifecycle_cb as *const ()))),
+                self.context(connection_id),
+                Some(free_context),
+            )
+        };
+        if network_id < 0 {
+            tracing::warn!("Failed to register network events on {}", connection_id);
+        } else {
+            registered.push(Registration::Network(network_id));
+        }
+
+        let pool_id = unsafe {
+            sys::virConnectStoragePoolEventRegisterAny(
+                conn,
+                std::ptr::null_mut(),
+                sys::VIR_STORAGE_POOL_EVENT_ID_LIFECYCLE as c_int,
+                Some(std::mem::transmute(Self::erase(pool_lifecycle_cb as *const ()))),
+                self.context(connection_id),
+                Some(free_context),
+            )
+        };
+        if pool_id < 0 {
+            tracing::warn!("Failed to register storage pool events on {}", connection_id);
+        } else {
+            registered.push(Registration::StoragePool(pool_id));
+        }
+
+        if registered.is_empty() {
+            return Err(AppError::LibvirtError(format!("No event callbacks could be registered on {}", connection_id)));
+        }
+
+        tracing::info!("Registered {} libvirt event callbacks on {}", registered.len(), connection_id);
+
+        let mut registrations = self.registrations.lock()
+            .map_err(|_| AppError::Other("Failed to lock event registrations".to_string()))?;
+        registrations.insert(connection_id.to_string(), (libvirt, registered));
+
+        Ok(())
+    }
+
+    /// Remove all event callbacks registered on a connection
+    pub fn deregister(&self, connection_id: &str) {
+        let entry = match self.registrations.lock() {
+            Ok(mut registrations) => registrations.remove(connection_id),
+            Err(_) => None,
+        };
+        let Some((libvirt, registered)) = entry else {
+            return;
+        };
+
+        let conn = libvirt.get_connection().as_ptr();
+        for registration in registered {
+            // Failures are expected when the connection is already dead
+            unsafe {
+                match registration {
+                    Registration::Domain(id) => sys::virConnectDomainEventDeregisterAny(conn, id),
+                    Registration::Network(id) => sys::virConnectNetworkEventDeregisterAny(conn, id),
+                    Registration::StoragePool(id) => sys::virConnectStoragePoolEventDeregisterAny(conn, id),
+                };
+            }
+        }
+    }
+
+    /// Allocate a callback context; libvirt frees it through `free_context`
+    fn context(self: &Arc<Self>, connection_id: &str) -> *mut c_void {
+        Box::into_raw(Box::new(CallbackContext {
+            connection_id: connection_id.to_string(),
+            service: self.clone(),
+        })) as *mut c_void
+    }
+
+    /// Erase a typed callback so it fits libvirt's generic callback slot
+    fn erase(callback: *const ()) -> unsafe extern "C" fn() {
+        unsafe { std::mem::transmute::<*const (), unsafe extern "C" fn()>(callback) }
+    }
+}
+
+impl Default for EventService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe extern "C" fn free_context(opaque: *mut c_void) {
+    if !opaque.is_null() {
+        drop(Box::from_raw(opaque as *mut CallbackContext));
+    }
+}
+
+unsafe fn context<'a>(opaque: *mut c_void) -> &'a CallbackContext {
+    &*(opaque as *const CallbackContext)
+}
+
+unsafe fn c_string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    }
+}
+
+/// Emit a domain event, resolving the VM id, name and current state
+unsafe fn emit_domain_event(opaque: *mut c_void, dom: sys::virDomainPtr, kind: VmEventKind) {
+    let ctx = context(opaque);
+
+    let mut uuid = [0 as c_char; sys::VIR_UUID_STRING_BUFLEN as usize];
+    let vm_id = if sys::virDomainGetUUIDString(dom, uuid.as_mut_ptr()) == 0 {
+        c_string(uuid.as_ptr())
+    } else {
+        String::new()
+    };
+    let vm_name = c_string(sys::virDomainGetName(dom));
+
+    let mut state: c_int = 0;
+    let mut reason: c_int = 0;
+    let state = if sys::virDomainGetState(dom, &mut state, &mut reason, 0) == 0 {
+        VmService::state_from_libvirt(state as sys::virDomainState)
+    } else {
+        // Undefined transient domains can no longer be queried
+        VmService::state_from_libvirt(sys::VIR_DOMAIN_SHUTOFF)
+    };
+
+    let event = VmEvent {
+        connection_id: ctx.connection_id.clone(),
+        vm_id,
+        vm_name,
+        state,
+        kind,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    };
+    ctx.service.emit(VM_EVENT, event.clone());
+    ctx.service.publish(BackendEvent::Vm(event));
+}
+
+unsafe extern "C" fn domain_lifecycle_cb(
+    _conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    event: c_int,
+    detail: c_int,
+    opaque: *mut c_void,
+) -> c_int {
+    let event = match event as u32 {
+        sys::VIR_DOMAIN_EVENT_DEFINED => LifecycleEvent::Defined,
+        sys::VIR_DOMAIN_EVENT_UNDEFINED => LifecycleEvent::Undefined,
+        sys::VIR_DOMAIN_EVENT_STARTED => LifecycleEvent::Started,
+        sys::VIR_DOMAIN_EVENT_SUSPENDED => LifecycleEvent::Suspended,
+        sys::VIR_DOMAIN_EVENT_RESUMED => LifecycleEvent::Resumed,
+        sys::VIR_DOMAIN_EVENT_STOPPED => LifecycleEvent::Stopped,
+        sys::VIR_DOMAIN_EVENT_SHUTDOWN => LifecycleEvent::Shutdown,
+        sys::VIR_DOMAIN_EVENT_PMSUSPENDED => LifecycleEvent::PmSuspended,
+        sys::VIR_DOMAIN_EVENT_CRASHED => LifecycleEvent::Crashed,
+        _ => LifecycleEvent::Unknown,
+    };
+    emit_domain_event(opaque, dom, VmEventKind::Lifecycle { event, detail });
+    0
+}
+
+unsafe extern "C" fn domain_reboot_cb(
+    _conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    opaque: *mut c_void,
+) {
+    emit_domain_event(opaque, dom, VmEventKind::Reboot);
+}
+
+unsafe extern "C" fn domain_device_added_cb(
+    _conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    dev_alias: *const c_char,
+    opaque: *mut c_void,
+) {
+    emit_domain_event(opaque, dom, VmEventKind::DeviceAdded { device_alias: c_string(dev_alias) });
+}
+
+unsafe extern "C" fn domain_device_removed_cb(
+    _conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    dev_alias: *const c_char,
+    opaque: *mut c_void,
+) {
+    emit_domain_event(opaque, dom, VmEventKind::DeviceRemoved { device_alias: c_string(dev_alias) });
+}
+
+unsafe extern "C" fn domain_block_job_cb(
+    _conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    disk: *const c_char,
+    job_type: c_int,
+    status: c_int,
+    opaque: *mut c_void,
+) {
+    let job_type = match job_type as u32 {
+        sys::VIR_DOMAIN_BLOCK_JOB_TYPE_PULL => BlockJobType::Pull,
+        sys::VIR_DOMAIN_BLOCK_JOB_TYPE_COPY => BlockJobType::Copy,
+        sys::VIR_DOMAIN_BLOCK_JOB_TYPE_COMMIT => BlockJobType::Commit,
+        sys::VIR_DOMAIN_BLOCK_JOB_TYPE_ACTIVE_COMMIT => BlockJobType::ActiveCommit,
+        sys::VIR_DOMAIN_BLOCK_JOB_TYPE_BACKUP => BlockJobType::Backup,
+        _ => BlockJobType::Unknown,
+    };
+    let status = match status as u32 {
+        sys::VIR_DOMAIN_BLOCK_JOB_COMPLETED => BlockJobStatus::Completed,
+        sys::VIR_DOMAIN_BLOCK_JOB_FAILED => BlockJobStatus::Failed,
+        sys::VIR_DOMAIN_BLOCK_JOB_CANCELED => BlockJobStatus::Canceled,
+        sys::VIR_DOMAIN_BLOCK_JOB_READY => BlockJobStatus::Ready,
+        _ => BlockJobStatus::Unknown,
+    };
+    emit_domain_event(opaque, dom, VmEventKind::BlockJob { disk: c_string(disk), job_type, status });
+}
+
+unsafe extern "C" fn domain_agent_lifecycle_cb(
+    _conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    state: c_int,
+    reason: c_int,
+    opaque: *mut c_void,
+) {
+    let connected = state as u32 == sys::VIR_CONNECT_DOMAIN_EVENT_AGENT_LIFECYCLE_STATE_CONNECTED;
+    emit_domain_event(opaque, dom, VmEventKind::AgentLifecycle { connected, reason });
+}
+
+unsafe extern "C" fn domain_watchdog_cb(
+    _conn: sys::virConnectPtr,
+    dom: sys::virDomainPtr,
+    action: c_int,
+    opaque: *mut c_void,
+) {
+    emit_domain_event(opaque, dom, VmEventKind::Watchdog { action });
+}
+
+unsafe extern "C" fn network_lifecycle_cb(
+    _conn: sys::virConnectPtr,
+    net: sys::virNetworkPtr,
+    event: c_int,
+    _detail: c_int,
+    opaque: *mut c_void,
+) {
+    let ctx = context(opaque);
+    let event = match event as u32 {
+        sys::VIR_NETWORK_EVENT_DEFINED => ResourceLifecycleEvent::Defined,
+        sys::VIR_NETWORK_EVENT_UNDEFINED => ResourceLifecycleEvent::Undefined,
+        sys::VIR_NETWORK_EVENT_STARTED => ResourceLifecycleEvent::Started,
+        sys::VIR_NETWORK_EVENT_STOPPED => ResourceLifecycleEvent::Stopped,
+        _ => ResourceLifecycleEvent::Unknown,
+    };
+
+    let event = NetworkEvent {
+        connection_id: ctx.connection_id.clone(),
+        network_name: c_string(sys::virNetworkGetName(net)),
+        event,
+        active: sys::virNetworkIsActive(net) == 1,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    };
+    ctx.service.emit(NETWORK_EVENT, event.clone());
+    ctx.service.publish(BackendEvent::Network(event));
+}
+
+unsafe extern "C" fn pool_lifecycle_cb(
+    _conn: sys::virConnectPtr,
+    pool: sys::virStoragePoolPtr,
+    event: c_int,
+    _detail: c_int,
+    opaque: *mut c_void,
+) {
+    let ctx = context(opaque);
+    let event = match event as u32 {
+        sys::VIR_STORAGE_POOL_EVENT_DEFINED => ResourceLifecycleEvent::Defined,
+        sys::VIR_STORAGE_POOL_EVENT_UNDEFINED => ResourceLifecycleEvent::Undefined,
+        sys::VIR_STORAGE_POOL_EVENT_STARTED => ResourceLifecycleEvent::Started,
+        sys::VIR_STORAGE_POOL_EVENT_STOPPED => ResourceLifecycleEvent::Stopped,
+        sys::VIR_STORAGE_POOL_EVENT_CREATED => ResourceLifecycleEvent::Created,
+        sys::VIR_STORAGE_POOL_EVENT_DELETED => ResourceLifecycleEvent::Deleted,
+        _ => ResourceLifecycleEvent::Unknown,
+    };
+
+    let mut uuid = [0 as c_char; sys::VIR_UUID_STRING_BUFLEN as usize];
+    let pool_id = if sys::virStoragePoolGetUUIDString(pool, uuid.as_mut_ptr()) == 0 {
+        c_string(uuid.as_ptr())
+    } else {
+        String::new()
+    };
+
+    ctx.service.emit(STORAGE_POOL_EVENT, StoragePoolEvent {
+        connection_id: ctx.connection_id.clone(),
+        pool_id,
+        pool_name: c_string(sys::virStoragePoolGetName(pool)),
+        event,
+        active: sys::virStoragePoolIsActive(pool) == 1,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    });
+}
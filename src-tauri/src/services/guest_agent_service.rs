@@ -6,8 +6,12 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::process::Command;
+use std::time::{Duration, Instant};
 use tracing::debug;
 
+/// How often `exec_and_wait` polls guest-exec-status
+const EXEC_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
 /// Guest agent service - uses virsh to communicate with QEMU Guest Agent
 pub struct GuestAgentService {}
 
@@ -151,8 +155,8 @@ impl GuestAgentService {
     }
 
     /// Check if a guest agent is available for a VM
-    pub async fn is_agent_available(&self, vm_name: &str) -> bool {
-        match self.ping(vm_name).await {
+    pub async fn is_agent_available(&self, uri: &str, vm_name: &str) -> bool {
+        match self.ping(uri, vm_name).await {
             Ok(_) => true,
             Err(e) => {
                 debug!("Guest agent not available for '{}': {}", vm_name, e);
@@ -161,9 +165,18 @@ impl GuestAgentService {
         }
     }
 
-    /// Send a command to the guest agent via virsh and get the response
-    fn send_agent_command(vm_name: &str, command: &str) -> Result<serde_json::Value> {
-        let output = Command::new("virsh")
+    /// Send a command to the guest agent of a VM on the connection at `uri` and get the response
+    fn send_agent_command(uri: &str, vm_name: &str, command: &str) -> Result<serde_json::Value> {
+        Self::send_agent_command_on(Some(uri), vm_name, command)
+    }
+
+    /// Send a command to the guest agent of a VM on a specific connection URI
+    fn send_agent_command_on(uri: Option<&str>, vm_name: &str, command: &str) -> Result<serde_json::Value> {
+        let mut virsh = Command::new("virsh");
+        if let Some(uri) = uri {
+            virsh.args(["-c", uri]);
+        }
+        let output = virsh
             .args(["qemu-agent-command", vm_name, command])
             .output()
             .context("Failed to execute virsh command")?;
@@ -186,16 +199,16 @@ impl GuestAgentService {
     }
 
     /// Ping the guest agent
-    pub async fn ping(&self, vm_name: &str) -> Result<bool> {
+    pub async fn ping(&self, uri: &str, vm_name: &str) -> Result<bool> {
         let command = r#"{"execute":"guest-ping"}"#;
-        Self::send_agent_command(vm_name, command)?;
+        Self::send_agent_command(uri, vm_name, command)?;
         Ok(true)
     }
 
     /// Get agent info
-    pub async fn get_agent_info(&self, vm_name: &str) -> Result<AgentInfo> {
+    pub async fn get_agent_info(&self, uri: &str, vm_name: &str) -> Result<AgentInfo> {
         let command = r#"{"execute":"guest-info"}"#;
-        let result = Self::send_agent_command(vm_name, command)?;
+        let result = Self::send_agent_command(uri, vm_name, command)?;
 
         let version = result.get("version")
             .and_then(|v| v.as_str())
@@ -228,19 +241,19 @@ impl GuestAgentService {
     }
 
     /// Get system information
-    pub async fn get_system_info(&self, vm_name: &str) -> Result<SystemInfo> {
+    pub async fn get_system_info(&self, uri: &str, vm_name: &str) -> Result<SystemInfo> {
         // Get OS info
         let os_command = r#"{"execute":"guest-get-osinfo"}"#;
-        let os_info = Self::send_agent_command(vm_name, os_command)?;
+        let os_info = Self::send_agent_command(uri, vm_name, os_command)?;
 
         // Get hostname
         let hostname_command = r#"{"execute":"guest-get-host-name"}"#;
-        let hostname_result = Self::send_agent_command(vm_name, hostname_command)
+        let hostname_result = Self::send_agent_command(uri, vm_name, hostname_command)
             .unwrap_or(serde_json::json!({"host-name": "unknown"}));
 
         // Get VCPUs
         let vcpu_command = r#"{"execute":"guest-get-vcpus"}"#;
-        let vcpus = Self::send_agent_command(vm_name, vcpu_command)
+        let vcpus = Self::send_agent_command(uri, vm_name, vcpu_command)
             .ok()
             .and_then(|v| v.as_array().map(|a| a.len() as u32))
             .unwrap_or(1);
@@ -297,9 +310,9 @@ impl GuestAgentService {
     }
 
     /// Get network information
-    pub async fn get_network_info(&self, vm_name: &str) -> Result<NetworkInfo> {
+    pub async fn get_network_info(&self, uri: &str, vm_name: &str) -> Result<NetworkInfo> {
         let command = r#"{"execute":"guest-network-get-interfaces"}"#;
-        let result = Self::send_agent_command(vm_name, command)?;
+        let result = Self::send_agent_command(uri, vm_name, command)?;
 
         let interfaces: Vec<NetworkInterface> = result.as_array()
             .map(|arr| {
@@ -345,9 +358,9 @@ impl GuestAgentService {
     }
 
     /// Get disk usage information
-    pub async fn get_disk_usage(&self, vm_name: &str) -> Result<DiskUsageInfo> {
+    pub async fn get_disk_usage(&self, uri: &str, vm_name: &str) -> Result<DiskUsageInfo> {
         let command = r#"{"execute":"guest-get-fsinfo"}"#;
-        let result = Self::send_agent_command(vm_name, command)?;
+        let result = Self::send_agent_command(uri, vm_name, command)?;
 
         let disks: Vec<DiskInfo> = result.as_array()
             .map(|arr| {
@@ -390,7 +403,7 @@ impl GuestAgentService {
     }
 
     /// Execute a command in the guest
-    pub async fn exec_command(&self, vm_name: &str, command: &str, args: Vec<String>) -> Result<ExecCommandResult> {
+    pub async fn exec_command(&self, uri: &str, vm_name: &str, command: &str, args: Vec<String>) -> Result<ExecCommandResult> {
         // Build the exec command
         let exec_cmd = serde_json::json!({
             "execute": "guest-exec",
@@ -401,7 +414,7 @@ impl GuestAgentService {
             }
         });
 
-        let result = Self::send_agent_command(vm_name, &exec_cmd.to_string())?;
+        let result = Self::send_agent_command(uri, vm_name, &exec_cmd.to_string())?;
 
         let pid = result.get("pid")
             .and_then(|v| v.as_i64())
@@ -418,45 +431,12 @@ impl GuestAgentService {
             }
         });
 
-        let status = Self::send_agent_command(vm_name, &status_cmd.to_string())?;
+        let status = Self::send_agent_command(uri, vm_name, &status_cmd.to_string())?;
 
         let exit_code = status.get("exitcode")
             .and_then(|v| v.as_i64())
             .unwrap_or(-1) as i32;
 
-        // Decode base64 output using simple decoder
-        fn decode_base64(input: &str) -> Option<String> {
-            let decoded = input.as_bytes().chunks(4).map(|chunk| {
-                let mut val = 0u32;
-                for (i, &byte) in chunk.iter().enumerate() {
-                    let ch = match byte {
-                        b'A'..=b'Z' => byte - b'A',
-                        b'a'..=b'z' => byte - b'a' + 26,
-                        b'0'..=b'9' => byte - b'0' + 52,
-                        b'+' => 62,
-                        b'/' => 63,
-                        b'=' => 0,
-                        _ => return None,
-                    };
-                    val |= (ch as u32) << (18 - 6 * i);
-                }
-                Some(val)
-            }).collect::<Option<Vec<u32>>>()?;
-
-            let mut bytes = Vec::new();
-            for val in decoded {
-                bytes.push((val >> 16) as u8);
-                if bytes.len() < input.trim_end_matches('=').len() * 3 / 4 + 1 {
-                    bytes.push((val >> 8) as u8);
-                }
-                if bytes.len() < input.trim_end_matches('=').len() * 3 / 4 + 1 {
-                    bytes.push(val as u8);
-                }
-            }
-            bytes.truncate(input.trim_end_matches('=').len() * 3 / 4);
-            String::from_utf8(bytes).ok()
-        }
-
         let stdout = status.get("out-data")
             .and_then(|v| v.as_str())
             .and_then(decode_base64)
@@ -476,23 +456,23 @@ impl GuestAgentService {
     }
 
     /// Shutdown the guest gracefully
-    pub async fn shutdown(&self, vm_name: &str, _force: bool) -> Result<()> {
+    pub async fn shutdown(&self, uri: &str, vm_name: &str, _force: bool) -> Result<()> {
         let command = r#"{"execute":"guest-shutdown"}"#;
-        Self::send_agent_command(vm_name, command)?;
+        Self::send_agent_command(uri, vm_name, command)?;
         Ok(())
     }
 
     /// Reboot the guest
-    pub async fn reboot(&self, vm_name: &str, _force: bool) -> Result<()> {
+    pub async fn reboot(&self, uri: &str, vm_name: &str, _force: bool) -> Result<()> {
         let command = r#"{"execute":"guest-shutdown","arguments":{"mode":"reboot"}}"#;
-        Self::send_agent_command(vm_name, command)?;
+        Self::send_agent_command(uri, vm_name, command)?;
         Ok(())
     }
 
     /// Get CPU statistics from guest
-    pub async fn get_cpu_stats(&self, vm_name: &str) -> Result<GuestCpuStats> {
+    pub async fn get_cpu_stats(&self, uri: &str, vm_name: &str) -> Result<GuestCpuStats> {
         let command = r#"{"execute":"guest-get-cpustats"}"#;
-        let result = Self::send_agent_command(vm_name, command)?;
+        let result = Self::send_agent_command(uri, vm_name, command)?;
 
         let cpus: Vec<CpuStats> = result.as_array()
             .map(|arr| {
@@ -535,9 +515,9 @@ impl GuestAgentService {
     }
 
     /// Get disk I/O statistics from guest
-    pub async fn get_disk_stats(&self, vm_name: &str) -> Result<GuestDiskStats> {
+    pub async fn get_disk_stats(&self, uri: &str, vm_name: &str) -> Result<GuestDiskStats> {
         let command = r#"{"execute":"guest-get-diskstats"}"#;
-        let result = Self::send_agent_command(vm_name, command)?;
+        let result = Self::send_agent_command(uri, vm_name, command)?;
 
         let disks: Vec<DiskStats> = result.as_array()
             .map(|arr| {
@@ -566,9 +546,9 @@ impl GuestAgentService {
     }
 
     /// Get logged-in users
-    pub async fn get_users(&self, vm_name: &str) -> Result<Vec<GuestUser>> {
+    pub async fn get_users(&self, uri: &str, vm_name: &str) -> Result<Vec<GuestUser>> {
         let command = r#"{"execute":"guest-get-users"}"#;
-        let result = Self::send_agent_command(vm_name, command)?;
+        let result = Self::send_agent_command(uri, vm_name, command)?;
 
         let users: Vec<GuestUser> = result.as_array()
             .map(|arr| {
@@ -585,9 +565,9 @@ impl GuestAgentService {
     }
 
     /// Get timezone info
-    pub async fn get_timezone(&self, vm_name: &str) -> Result<GuestTimezone> {
+    pub async fn get_timezone(&self, uri: &str, vm_name: &str) -> Result<GuestTimezone> {
         let command = r#"{"execute":"guest-get-timezone"}"#;
-        let result = Self::send_agent_command(vm_name, command)?;
+        let result = Self::send_agent_command(uri, vm_name, command)?;
 
         Ok(GuestTimezone {
             zone: result.get("zone").and_then(|v| v.as_str()).unwrap_or("UTC").to_string(),
@@ -596,24 +576,24 @@ impl GuestAgentService {
     }
 
     /// Get full guest information (combines multiple queries)
-    pub async fn get_full_info(&self, vm_name: &str) -> Result<GuestFullInfo> {
+    pub async fn get_full_info(&self, uri: &str, vm_name: &str) -> Result<GuestFullInfo> {
         // Get agent info
-        let agent_info = self.get_agent_info(vm_name).await?;
+        let agent_info = self.get_agent_info(uri, vm_name).await?;
 
         // Get system info
-        let system_info = self.get_system_info(vm_name).await?;
+        let system_info = self.get_system_info(uri, vm_name).await?;
 
         // Get timezone
-        let timezone = self.get_timezone(vm_name).await.ok();
+        let timezone = self.get_timezone(uri, vm_name).await.ok();
 
         // Get users
-        let users = self.get_users(vm_name).await.unwrap_or_default();
+        let users = self.get_users(uri, vm_name).await.unwrap_or_default();
 
         // Get disk usage
-        let disk_usage = self.get_disk_usage(vm_name).await?;
+        let disk_usage = self.get_disk_usage(uri, vm_name).await?;
 
         // Get network info
-        let network_info = self.get_network_info(vm_name).await?;
+        let network_info = self.get_network_info(uri, vm_name).await?;
 
         Ok(GuestFullInfo {
             agent_version: agent_info.version,
@@ -629,6 +609,127 @@ impl GuestAgentService {
             network_interfaces: network_info.interfaces,
         })
     }
+
+    // ===== Blocking calls for job threads =====
+    //
+    // These block on virsh and are meant for job threads; `uri` selects the
+    // connection the VM lives on, `None` uses virsh's default.
+
+    /// Ping the guest agent of a VM on a specific connection
+    pub fn ping_on(&self, uri: Option<&str>, vm_name: &str) -> Result<()> {
+        let command = r#"{"execute":"guest-ping"}"#;
+        Self::send_agent_command_on(uri, vm_name, command)?;
+        Ok(())
+    }
+
+    /// Freeze guest filesystems, or only those at `mountpoints`; returns how many were frozen
+    pub fn fs_freeze(&self, uri: Option<&str>, vm_name: &str, mountpoints: &[String]) -> Result<u32> {
+        let command = if mountpoints.is_empty() {
+            serde_json::json!({ "execute": "guest-fsfreeze-freeze" })
+        } else {
+            serde_json::json!({
+                "execute": "guest-fsfreeze-freeze-list",
+                "arguments": { "mountpoints": mountpoints }
+            })
+        };
+        let result = Self::send_agent_command_on(uri, vm_name, &command.to_string())?;
+        Ok(result.as_u64().unwrap_or(0) as u32)
+    }
+
+    /// Thaw guest filesystems; returns how many were thawed
+    pub fn fs_thaw(&self, uri: Option<&str>, vm_name: &str) -> Result<u32> {
+        let command = r#"{"execute":"guest-fsfreeze-thaw"}"#;
+        let result = Self::send_agent_command_on(uri, vm_name, command)?;
+        Ok(result.as_u64().unwrap_or(0) as u32)
+    }
+
+    /// Freeze state of guest filesystems: "thawed" or "frozen"
+    pub fn fs_freeze_status(&self, uri: Option<&str>, vm_name: &str) -> Result<String> {
+        let command = r#"{"execute":"guest-fsfreeze-status"}"#;
+        let result = Self::send_agent_command_on(uri, vm_name, command)?;
+        Ok(result.as_str().unwrap_or("unknown").to_string())
+    }
+
+    /// Run a program in the guest and wait up to `timeout` for it to exit
+    pub fn exec_and_wait(
+        &self,
+        uri: Option<&str>,
+        vm_name: &str,
+        path: &str,
+        args: &[String],
+        timeout: Duration,
+    ) -> Result<ExecCommandResult> {
+        let exec_cmd = serde_json::json!({
+            "execute": "guest-exec",
+            "arguments": {
+                "path": path,
+                "arg": args,
+                "capture-output": true
+            }
+        });
+        let started = Instant::now();
+        let result = Self::send_agent_command_on(uri, vm_name, &exec_cmd.to_string())?;
+        let pid = result.get("pid")
+            .and_then(|v| v.as_i64())
+            .context("No PID returned from guest-exec")?;
+
+        let status_cmd = serde_json::json!({
+            "execute": "guest-exec-status",
+            "arguments": { "pid": pid }
+        }).to_string();
+        loop {
+            let status = Self::send_agent_command_on(uri, vm_name, &status_cmd)?;
+            if status.get("exited").and_then(|v| v.as_bool()).unwrap_or(false) {
+                let output = |key: &str| status.get(key)
+                    .and_then(|v| v.as_str())
+                    .and_then(decode_base64)
+                    .unwrap_or_default();
+                return Ok(ExecCommandResult {
+                    exit_code: status.get("exitcode").and_then(|v| v.as_i64()).unwrap_or(-1) as i32,
+                    stdout: output("out-data"),
+                    stderr: output("err-data"),
+                    execution_time_ms: started.elapsed().as_millis() as u64,
+                });
+            }
+            if started.elapsed() >= timeout {
+                anyhow::bail!("{} did not finish within {}s", path, timeout.as_secs());
+            }
+            std::thread::sleep(EXEC_POLL_INTERVAL);
+        }
+    }
+}
+
+/// Decode base64 output of guest-exec-status using a simple decoder
+fn decode_base64(input: &str) -> Option<String> {
+    let decoded = input.as_bytes().chunks(4).map(|chunk| {
+        let mut val = 0u32;
+        for (i, &byte) in chunk.iter().enumerate() {
+            let ch = match byte {
+                b'A'..=b'Z' => byte - b'A',
+                b'a'..=b'z' => byte - b'a' + 26,
+                b'0'..=b'9' => byte - b'0' + 52,
+                b'+' => 62,
+                b'/' => 63,
+                b'=' => 0,
+                _ => return None,
+            };
+            val |= (ch as u32) << (18 - 6 * i);
+        }
+        Some(val)
+    }).collect::<Option<Vec<u32>>>()?;
+
+    let mut bytes = Vec::new();
+    for val in decoded {
+        bytes.push((val >> 16) as u8);
+        if bytes.len() < input.trim_end_matches('=').len() * 3 / 4 + 1 {
+            bytes.push((val >> 8) as u8);
+        }
+        if bytes.len() < input.trim_end_matches('=').len() * 3 / 4 + 1 {
+            bytes.push(val as u8);
+        }
+    }
+    bytes.truncate(input.trim_end_matches('=').len() * 3 / 4);
+    String::from_utf8(bytes).ok()
 }
 
 impl Default for GuestAgentService {
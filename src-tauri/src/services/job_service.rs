@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::ops::Range;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use uuid::Uuid;
+use crate::models::job::{Job, JobKind, JobStatus, JOB_EVENT};
+use crate::services::event_service::EventService;
+use crate::utils::error::AppError;
+
+/// Finished jobs kept for the history view
+const HISTORY_LIMIT: usize = 100;
+/// Minimum interval between progress events of one job
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+type CancelHook = Arc<dyn Fn() + Send + Sync>;
+
+struct JobEntry {
+    job: Job,
+    cancelled: Arc<AtomicBool>,
+    /// Interrupts the blocking call of the current step (kill qemu-img, abort a domain job)
+    cancel_hook: Option<CancelHook>,
+    last_emit: Instant,
+}
+
+/// JobService runs long operations (clone, migrate, volume transfer, OVA
+/// import) on the blocking thread pool and tracks them by ID.
+///
+/// Progress is pushed to the frontend as `JOB_EVENT`; finished jobs stay in
+/// an in-memory history with their result or final error.
+pub struct JobService {
+    events: Arc<EventService>,
+    jobs: Mutex<HashMap<String, JobEntry>>,
+}
+
+impl JobService {
+    /// Create a new job service
+    pub fn new(events: Arc<EventService>) -> Self {
+        Self {
+            events,
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Start a job in the background and return its ID.
+    ///
+    /// `work` runs on the blocking thread pool; its return value is stored as
+    /// the job result. Returning `AppError::Cancelled` marks the job cancelled.
+    pub fn start<R, F>(
+        self: &Arc<Self>,
+        kind: JobKind,
+        description: String,
+        target: String,
+        connection_id: Option<String>,
+        work: F,
+    ) -> String
+    where
+        R: Serialize,
+        F: FnOnce(&JobHandle) -> Result<R, AppError> + Send + 'static,
+    {
+        let id = Uuid::new_v4().to_string();
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let job = Job {
+            id: id.clone(),
+            kind,
+            description,
+            connection_id,
+            target,
+            status: JobStatus::Running,
+            step: None,
+            percent: 0.0,
+            bytes_processed: 0,
+            bytes_total: None,
+            result: None,
+            error: None,
+            started_at: chrono::Utc::now().timestamp(),
+            finished_at: None,
+        };
+
+        tracing::info!("Starting job {} ({})", id, job.description);
+        self.events.emit(JOB_EVENT, job.clone());
+
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.insert(id.clone(), JobEntry {
+                job,
+                cancelled: cancelled.clone(),
+                cancel_hook: None,
+                last_emit: Instant::now(),
+            });
+        }
+
+        let handle = JobHandle {
+            id: id.clone(),
+            service: self.clone(),
+            cancelled,
+        };
+
+        tauri::async_runtime::spawn_blocking(move || {
+            let result = work(&handle).and_then(|value| {
+                serde_json::to_value(value)
+                    .map_err(|e| AppError::Other(format!("Failed to serialize job result: {}", e)))
+            });
+            handle.service.finish(&handle.id, result);
+        });
+
+        id
+    }
+
+    /// List running and recent jobs, newest first
+    pub fn list_jobs(&self) -> Vec<Job> {
+        let mut jobs: Vec<Job> = match self.jobs.lock() {
+            Ok(jobs) => jobs.values().map(|entry| entry.job.clone()).collect(),
+            Err(_) => Vec::new(),
+        };
+        jobs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        jobs
+    }
+
+    /// Get a single job
+    pub fn get_job(&self, job_id: &str) -> Result<Job, AppError> {
+        let jobs = self.jobs.lock()
+            .map_err(|_| AppError::Other("Job registry lock poisoned".to_string()))?;
+        jobs.get(job_id)
+            .map(|entry| entry.job.clone())
+            .ok_or_else(|| AppError::NotFound(format!("Job {}", job_id)))
+    }
+
+    /// Request cancellation of a running job
+    pub fn cancel_job(&self, job_id: &str) -> Result<(), AppError> {
+        let hook = {
+            let jobs = self.jobs.lock()
+                .map_err(|_| AppError::Other("Job registry lock poisoned".to_string()))?;
+            let entry = jobs.get(job_id)
+                .ok_or_else(|| AppError::NotFound(format!("Job {}", job_id)))?;
+            if entry.job.status.is_finished() {
+                return Err(AppError::InvalidConfig(format!("Job {} has already finished", job_id)));
+            }
+            entry.cancelled.store(true, Ordering::SeqCst);
+            entry.cancel_hook.clone()
+        };
+
+        tracing::info!("Cancelling job {}", job_id);
+
+        // Run the hook outside the lock; it may block on libvirt or a child process
+        if let Some(hook) = hook {
+            hook();
+        }
+        Ok(())
+    }
+
+    /// Drop finished jobs from the history
+    pub fn clear_finished(&self) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.retain(|_, entry| !entry.job.status.is_finished());
+        }
+    }
+
+    /// Apply a change to a running job and emit it, throttled unless `force` is set
+    fn update(&self, job_id: &str, force: bool, change: impl FnOnce(&mut Job)) {
+        let snapshot = {
+            let Ok(mut jobs) = self.jobs.lock() else { return };
+            let Some(entry) = jobs.get_mut(job_id) else { return };
+            change(&mut entry.job);
+            if !force && entry.last_emit.elapsed() < PROGRESS_INTERVAL {
+                return;
+            }
+            entry.last_emit = Instant::now();
+            entry.job.clone()
+        };
+        self.events.emit(JOB_EVENT, snapshot);
+    }
+
+    fn set_cancel_hook(&self, job_id: &str, hook: Option<CancelHook>) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            if let Some(entry) = jobs.get_mut(job_id) {
+                entry.cancel_hook = hook;
+            }
+        }
+    }
+
+    /// Record the final state of a job and trim the history
+    fn finish(&self, job_id: &str, result: Result<serde_json::Value, AppError>) {
+        let cancelled = self.jobs.lock().ok()
+            .and_then(|jobs| jobs.get(job_id).map(|entry| entry.cancelled.load(Ordering::SeqCst)))
+            .unwrap_or(false);
+
+        self.update(job_id, true, |job| {
+            job.finished_at = Some(chrono::Utc::now().timestamp());
+            match result {
+                Ok(value) => {
+                    job.status = JobStatus::Completed;
+                    job.percent = 100.0;
+                    job.result = Some(value);
+                }
+                Err(AppError::Cancelled) => {
+                    job.status = JobStatus::Cancelled;
+                    job.error = Some(AppError::Cancelled.to_string());
+                }
+                // A failure caused by aborting the step counts as cancellation
+                Err(_) if cancelled => {
+                    job.status = JobStatus::Cancelled;
+                    job.error = Some(AppError::Cancelled.to_string());
+                }
+                Err(e) => {
+                    job.status = JobStatus::Failed;
+                    job.error = Some(e.to_string());
+                }
+            }
+        });
+
+        if let Ok(mut jobs) = self.jobs.lock() {
+            if let Some(entry) = jobs.get_mut(job_id) {
+                entry.cancel_hook = None;
+                match &entry.job.error {
+                    Some(error) => tracing::warn!("Job {} ended: {}", job_id, error),
+                    None => tracing::info!("Job {} completed", job_id),
+                }
+            }
+            Self::prune_history(&mut jobs);
+        }
+    }
+
+    /// Keep at most `HISTORY_LIMIT` finished jobs
+    fn prune_history(jobs: &mut HashMap<String, JobEntry>) {
+        let mut finished: Vec<(i64, String)> = jobs.values()
+            .filter(|entry| entry.job.status.is_finished())
+            .map(|entry| (entry.job.finished_at.unwrap_or(0), entry.job.id.clone()))
+            .collect();
+        if finished.len() <= HISTORY_LIMIT {
+            return;
+        }
+        finished.sort();
+        let excess = finished.len() - HISTORY_LIMIT;
+        for (_, id) in finished.into_iter().take(excess) {
+            jobs.remove(&id);
+        }
+    }
+}
+
+/// Handle passed to job work for reporting progress and observing cancellation
+#[derive(Clone)]
+pub struct JobHandle {
+    id: String,
+    service: Arc<JobService>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl JobHandle {
+    /// Whether cancellation was requested
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Return `AppError::Cancelled` if cancellation was requested
+    pub fn check_cancelled(&self) -> Result<(), AppError> {
+        if self.is_cancelled() {
+            Err(AppError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Describe the current step
+    pub fn set_step(&self, step: impl Into<String>) {
+        let step = step.into();
+        tracing::debug!("Job {}: {}", self.id, step);
+        self.service.update(&self.id, true, |job| job.step = Some(step));
+    }
+
+    /// Report overall progress as a percentage
+    pub fn set_percent(&self, percent: f64) {
+        self.service.update(&self.id, false, |job| job.percent = percent.clamp(0.0, 100.0));
+    }
+
+    /// Report byte progress; the percentage is derived from it
+    pub fn set_bytes(&self, processed: u64, total: u64) {
+        self.service.update(&self.id, false, |job| {
+            job.bytes_processed = processed;
+            job.bytes_total = Some(total);
+            if total > 0 {
+                job.percent = (processed as f64 / total as f64 * 100.0).min(100.0);
+            }
+        });
+    }
+
+    /// Register how to interrupt the current blocking step when the job is cancelled
+    pub fn on_cancel(&self, hook: impl Fn() + Send + Sync + 'static) {
+        let hook: CancelHook = Arc::new(hook);
+        self.service.set_cancel_hook(&self.id, Some(hook.clone()));
+        // Cancellation may have been requested before the hook was in place
+        if self.is_cancelled() {
+            hook();
+        }
+    }
+
+    /// Remove the cancel hook once the step it interrupts has finished
+    pub fn clear_cancel(&self) {
+        self.service.set_cancel_hook(&self.id, None);
+    }
+}
+
+/// Run `qemu-img` and map its `-p` progress output onto `span` of the job's percentage.
+///
+/// Without a job the command simply runs to completion. With a job, `-p` is
+/// added after the subcommand and cancelling the job kills the process.
+pub fn run_qemu_img(job: Option<&JobHandle>, args: &[&str], span: Range<f64>) -> Result<(), AppError> {
+    let Some(job) = job else {
+        let output = Command::new("qemu-img")
+            .args(args)
+            .output()
+            .map_err(|e| AppError::Other(format!("Failed to run qemu-img: {}", e)))?;
+        if !output.status.success() {
+            return Err(AppError::Other(format!(
+                "qemu-img {} failed: {}",
+                args.first().unwrap_or(&""),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        return Ok(());
+    };
+
+    job.check_cancelled()?;
+
+    let mut command = Command::new("qemu-img");
+    if let Some((subcommand, rest)) = args.split_first() {
+        command.arg(subcommand).arg("-p").args(rest);
+    }
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::Other(format!("Failed to run qemu-img: {}", e)))?;
+
+    let mut stdout = child.stdout.take()
+        .ok_or_else(|| AppError::Other("Failed to capture qemu-img output".to_string()))?;
+    let mut stderr = child.stderr.take()
+        .ok_or_else(|| AppError::Other("Failed to capture qemu-img output".to_string()))?;
+
+    let child: Arc<Mutex<Child>> = Arc::new(Mutex::new(child));
+    let killer = child.clone();
+    job.on_cancel(move || {
+        if let Ok(mut child) = killer.lock() {
+            let _ = child.kill();
+        }
+    });
+
+    // Drain stderr on its own thread so a chatty process cannot block on a full pipe
+    let stderr_reader = std::thread::spawn(move || {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text);
+        text
+    });
+
+    // Progress lines look like "    (42.17/100%)\r"
+    let mut buffer = [0u8; 256];
+    let mut line = String::new();
+    loop {
+        let read = stdout.read(&mut buffer).unwrap_or(0);
+        if read == 0 {
+            break;
+        }
+        for &byte in &buffer[..read] {
+            if byte == b'\r' || byte == b'\n' {
+                if let Some(percent) = parse_qemu_img_progress(&line) {
+                    job.set_percent(span.start + (span.end - span.start) * percent / 100.0);
+                }
+                line.clear();
+            } else {
+                line.push(byte as char);
+            }
+        }
+    }
+
+    let status = child.lock()
+        .map_err(|_| AppError::Other("qemu-img process lock poisoned".to_string()))?
+        .wait()
+        .map_err(|e| AppError::Other(format!("Failed to wait for qemu-img: {}", e)))?;
+    job.clear_cancel();
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    job.check_cancelled()?;
+    if !status.success() {
+        return Err(AppError::Other(format!(
+            "qemu-img {} failed: {}",
+            args.first().unwrap_or(&""),
+            stderr.trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Parse a qemu-img progress line such as `(42.17/100%)`
+fn parse_qemu_img_progress(line: &str) -> Option<f64> {
+    let start = line.find('(')? + 1;
+    let end = line[start..].find('/')? + start;
+    line[start..end].trim().parse().ok()
+}
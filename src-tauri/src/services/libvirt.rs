@@ -1,22 +1,108 @@
-use virt::connect::Connect;
+use std::sync::Mutex;
+use virt::connect::{Connect, ConnectAuth, ConnectCredential};
+use virt::sys;
 use crate::utils::error::{AppError, map_libvirt_error};
 
+lazy_static::lazy_static! {
+    // libvirt auth callbacks are plain fn pointers, so SASL credentials for the
+    // connection being opened are handed over through this slot.
+    static ref PENDING_AUTH: Mutex<Option<(String, String)>> = Mutex::new(None);
+    static ref AUTH_OPEN_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// Default libvirt URI used for the local hypervisor
+pub const DEFAULT_URI: &str = "qemu:///system";
+
+/// Seconds between keepalive probes on remote connections
+const KEEPALIVE_INTERVAL: i32 = 5;
+/// Unanswered probes before libvirt closes the connection
+const KEEPALIVE_COUNT: u32 = 3;
+
 /// LibvirtService manages the connection to libvirtd
 pub struct LibvirtService {
     connection: Connect,
+    uri: String,
 }
 
 impl LibvirtService {
     /// Create a new LibvirtService and connect to qemu:///system
+    #[allow(dead_code)]
     pub fn new() -> Result<Self, AppError> {
-        tracing::info!("Connecting to libvirt at qemu:///system");
+        Self::open(DEFAULT_URI)
+    }
+
+    /// Create a new LibvirtService connected to the given URI
+    pub fn open(uri: &str) -> Result<Self, AppError> {
+        tracing::info!("Connecting to libvirt at {}", uri);
 
-        let connection = Connect::open(Some("qemu:///system"))
+        let connection = Connect::open(Some(uri))
             .map_err(map_libvirt_error)?;
+        Self::enable_keepalive(&connection, uri);
 
-        tracing::info!("Successfully connected to libvirt");
+        tracing::info!("Successfully connected to libvirt at {}", uri);
 
-        Ok(Self { connection })
+        Ok(Self { connection, uri: uri.to_string() })
+    }
+
+    /// Create a new LibvirtService, answering SASL prompts with the given credentials
+    pub fn open_with_auth(uri: &str, username: &str, password: &str) -> Result<Self, AppError> {
+        tracing::info!("Connecting to libvirt at {} as {}", uri, username);
+
+        // Serialize authenticated opens so concurrent connects cannot swap credentials
+        let _guard = AUTH_OPEN_LOCK.lock()
+            .map_err(|_| AppError::Other("Failed to lock libvirt auth".to_string()))?;
+        *PENDING_AUTH.lock()
+            .map_err(|_| AppError::Other("Failed to lock pending credentials".to_string()))? =
+            Some((username.to_string(), password.to_string()));
+
+        let mut auth = ConnectAuth::new(
+            vec![sys::VIR_CRED_AUTHNAME, sys::VIR_CRED_PASSPHRASE],
+            Self::auth_callback,
+        );
+        let result = Connect::open_auth(Some(uri), &mut auth, 0);
+
+        if let Ok(mut pending) = PENDING_AUTH.lock() {
+            *pending = None;
+        }
+
+        let connection = result.map_err(map_libvirt_error)?;
+        Self::enable_keepalive(&connection, uri);
+
+        tracing::info!("Successfully connected to libvirt at {}", uri);
+
+        Ok(Self { connection, uri: uri.to_string() })
+    }
+
+    /// Turn on libvirt keepalive so dead peers are detected and closed
+    fn enable_keepalive(connection: &Connect, uri: &str) {
+        let ret = unsafe {
+            sys::virConnectSetKeepAlive(connection.as_ptr(), KEEPALIVE_INTERVAL, KEEPALIVE_COUNT)
+        };
+        match ret {
+            0 => tracing::debug!("Keepalive enabled for {}", uri),
+            // Local drivers do not support keepalive
+            1 => tracing::debug!("Keepalive not supported for {}", uri),
+            _ => tracing::warn!("Failed to enable keepalive for {}", uri),
+        }
+    }
+
+    /// Fill libvirt credential prompts from the pending SASL credentials
+    fn auth_callback(creds: &mut Vec<ConnectCredential>) {
+        let pending = match PENDING_AUTH.lock() {
+            Ok(pending) => pending.clone(),
+            Err(_) => None,
+        };
+        let Some((username, password)) = pending else {
+            return;
+        };
+
+        for cred in creds.iter_mut() {
+            match cred.typed as u32 {
+                sys::VIR_CRED_AUTHNAME => cred.result = Some(username.clone()),
+                sys::VIR_CRED_PASSPHRASE => cred.result = Some(password.clone()),
+                _ => {}
+            }
+        }
     }
 
     /// Get a reference to the libvirt connection
@@ -24,6 +110,28 @@ impl LibvirtService {
         &self.connection
     }
 
+    /// Get the URI this service is connected to
+    pub fn get_uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Start a `virsh` command bound to this connection's URI.
+    ///
+    /// Without `-c` virsh talks to its default URI, which is the wrong host
+    /// for VMs on remote connections.
+    pub fn virsh(&self) -> std::process::Command {
+        let mut virsh = std::process::Command::new("virsh");
+        virsh.args(["-c", &self.uri]);
+        virsh
+    }
+
+    /// Whether this connection talks to the local hypervisor.
+    ///
+    /// A URI without a host part (`qemu:///system`) is local.
+    pub fn is_local(&self) -> bool {
+        self.uri.split_once("://").is_some_and(|(_, rest)| rest.starts_with('/'))
+    }
+
     /// Check if the connection is alive
     pub fn is_alive(&self) -> bool {
         self.connection.is_alive().unwrap_or(false)
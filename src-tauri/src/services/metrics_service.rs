@@ -1,8 +1,23 @@
 use rusqlite::{Connection, params};
+use std::collections::HashMap;
+use std::ffi::CStr;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use virt::sys;
+use crate::models::vm::VmState;
+use crate::services::connection_service::ConnectionService;
+use crate::services::libvirt::LibvirtService;
+use crate::services::network_service::NetworkService;
+use crate::services::storage_service::StorageService;
+use crate::services::vm_service::VmService;
 use crate::utils::error::AppError;
 
+/// Seconds between host metric samples
+const HOST_SAMPLE_INTERVAL_SECS: u64 = 30;
+
+/// KSM counts base pages
+const PAGE_SIZE_BYTES: u64 = 4096;
+
 /// VM performance metrics for historical tracking
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct VmMetrics {
@@ -36,9 +51,81 @@ pub struct MetricDataPoint {
     pub network_tx_bytes: u64,
 }
 
-/// MetricsService handles persistence of VM performance metrics
+/// Hypervisor metrics for one connection.
+///
+/// CPU and memory come from libvirt and are available for every host; swap, load,
+/// hugepages and KSM are read from /proc and /sys and only exist for the local host.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostMetrics {
+    pub connection_id: String,
+    pub timestamp: i64,
+    /// Busy percentage across all host CPUs; `None` until two samples exist
+    pub cpu_usage: Option<f64>,
+    pub cpu_count: u32,
+    pub memory_total_mb: u64,
+    pub memory_free_mb: u64,
+    pub swap_total_mb: Option<u64>,
+    pub swap_free_mb: Option<u64>,
+    pub load_1: Option<f64>,
+    pub load_5: Option<f64>,
+    pub load_15: Option<f64>,
+    pub hugepages_total: Option<u64>,
+    pub hugepages_free: Option<u64>,
+    /// Memory saved by KSM page sharing
+    pub ksm_shared_mb: Option<u64>,
+    /// vCPUs of running VMs, to compare against `cpu_count`
+    pub vcpus_allocated: u32,
+    /// Memory of running VMs, to compare against `memory_total_mb`
+    pub memory_allocated_mb: u64,
+}
+
+/// Storage pool capacity sample
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolMetrics {
+    pub connection_id: String,
+    pub pool_name: String,
+    pub timestamp: i64,
+    pub capacity_bytes: u64,
+    pub allocation_bytes: u64,
+    pub available_bytes: u64,
+}
+
+/// Bridge throughput of a virtual network on the local host
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkThroughput {
+    pub connection_id: String,
+    pub network_name: String,
+    pub bridge: String,
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+}
+
+/// Latest host, pool and network sample of a connection
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostSnapshot {
+    pub host: HostMetrics,
+    pub pools: Vec<PoolMetrics>,
+    pub networks: Vec<NetworkThroughput>,
+}
+
+/// Cumulative counters from the previous sample, used to derive rates
+#[derive(Default)]
+struct HostCounters {
+    /// (busy, total) CPU time in nanoseconds
+    cpu: Option<(u64, u64)>,
+    /// Bridge (rx, tx) bytes per network name, with the sample time in millis
+    networks: HashMap<String, (u64, u64, i64)>,
+}
+
+/// MetricsService handles persistence of VM and host performance metrics
 pub struct MetricsService {
     conn: Arc<Mutex<Connection>>,
+    counters: Mutex<HashMap<String, HostCounters>>,
+    latest: Mutex<HashMap<String, HostSnapshot>>,
 }
 
 impl MetricsService {
@@ -82,13 +169,70 @@ impl MetricsService {
         )
         .map_err(|e| AppError::Other(format!("Failed to create index: {}", e)))?;
 
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS host_metrics (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                connection_id TEXT NOT NULL,
+                timestamp INTEGER NOT NULL,
+                cpu_usage REAL,
+                cpu_count INTEGER NOT NULL,
+                memory_total_mb INTEGER NOT NULL,
+                memory_free_mb INTEGER NOT NULL,
+                swap_total_mb INTEGER,
+                swap_free_mb INTEGER,
+                load_1 REAL,
+                load_5 REAL,
+                load_15 REAL,
+                hugepages_total INTEGER,
+                hugepages_free INTEGER,
+                ksm_shared_mb INTEGER,
+                vcpus_allocated INTEGER NOT NULL,
+                memory_allocated_mb INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Other(format!("Failed to create host metrics table: {}", e)))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_host_timestamp ON host_metrics(connection_id, timestamp)",
+            [],
+        )
+        .map_err(|e| AppError::Other(format!("Failed to create index: {}", e)))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS pool_metrics (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                connection_id TEXT NOT NULL,
+                pool_name TEXT NOT NULL,
+                timestamp INTEGER NOT NULL,
+                capacity_bytes INTEGER NOT NULL,
+                allocation_bytes INTEGER NOT NULL,
+                available_bytes INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Other(format!("Failed to create pool metrics table: {}", e)))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_pool_timestamp ON pool_metrics(connection_id, pool_name, timestamp)",
+            [],
+        )
+        .map_err(|e| AppError::Other(format!("Failed to create index: {}", e)))?;
+
         tracing::info!("Metrics database initialized successfully");
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            counters: Mutex::new(HashMap::new()),
+            latest: Mutex::new(HashMap::new()),
         })
     }
 
+    /// Shared handle to the metrics database, for services that keep their own tables in it
+    pub fn connection(&self) -> Arc<Mutex<Connection>> {
+        self.conn.clone()
+    }
+
     /// Store VM metrics
     pub fn store_metrics(&self, metrics: &VmMetrics) -> Result<(), AppError> {
         let conn = self.conn.lock()
@@ -193,16 +337,367 @@ impl MetricsService {
         let conn = self.conn.lock()
             .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
 
-        let deleted = conn.execute(
-            "DELETE FROM vm_metrics WHERE timestamp < ?1",
-            params![older_than],
-        )
-        .map_err(|e| AppError::Other(format!("Failed to cleanup metrics: {}", e)))?;
+        let mut deleted = 0;
+        for table in ["vm_metrics", "host_metrics", "pool_metrics"] {
+            deleted += conn.execute(
+                &format!("DELETE FROM {} WHERE timestamp < ?1", table),
+                params![older_than],
+            )
+            .map_err(|e| AppError::Other(format!("Failed to cleanup metrics: {}", e)))?;
+        }
 
         tracing::info!("Deleted {} old metric records", deleted);
         Ok(deleted)
     }
 
+    /// Latest host, pool and network sample per connection
+    pub fn latest_host_snapshots(&self) -> Vec<HostSnapshot> {
+        self.latest.lock()
+            .map(|latest| latest.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Latest sample of one connection
+    pub fn latest_host_snapshot(&self, connection_id: &str) -> Option<HostSnapshot> {
+        self.latest.lock().ok()?.get(connection_id).cloned()
+    }
+
+    /// Get historical host metrics for a connection within a time range
+    pub fn get_host_metrics_history(
+        &self,
+        connection_id: &str,
+        start_time: i64,
+        end_time: i64,
+        max_points: Option<usize>,
+    ) -> Result<Vec<HostMetrics>, AppError> {
+        let conn = self.conn.lock()
+            .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+        let interval = Self::sampling_interval(&conn, "host_metrics", connection_id, start_time, end_time, max_points);
+        let mut stmt = conn.prepare(&format!(
+            "SELECT connection_id, timestamp, cpu_usage, cpu_count, memory_total_mb, memory_free_mb,
+                    swap_total_mb, swap_free_mb, load_1, load_5, load_15, hugepages_total,
+                    hugepages_free, ksm_shared_mb, vcpus_allocated, memory_allocated_mb
+             FROM host_metrics
+             WHERE connection_id = ?1 AND timestamp >= ?2 AND timestamp <= ?3
+             AND id % {} = 0
+             ORDER BY timestamp ASC",
+            interval
+        ))
+        .map_err(|e| AppError::Other(format!("Failed to prepare query: {}", e)))?;
+
+        let metrics = stmt.query_map(params![connection_id, start_time, end_time], |row| {
+            Ok(HostMetrics {
+                connection_id: row.get(0)?,
+                timestamp: row.get(1)?,
+                cpu_usage: row.get(2)?,
+                cpu_count: row.get(3)?,
+                memory_total_mb: row.get(4)?,
+                memory_free_mb: row.get(5)?,
+                swap_total_mb: row.get(6)?,
+                swap_free_mb: row.get(7)?,
+                load_1: row.get(8)?,
+                load_5: row.get(9)?,
+                load_15: row.get(10)?,
+                hugepages_total: row.get(11)?,
+                hugepages_free: row.get(12)?,
+                ksm_shared_mb: row.get(13)?,
+                vcpus_allocated: row.get(14)?,
+                memory_allocated_mb: row.get(15)?,
+            })
+        })
+        .map_err(|e| AppError::Other(format!("Failed to query host metrics: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("Failed to collect host metrics: {}", e)))?;
+
+        Ok(metrics)
+    }
+
+    /// Get historical capacity of a storage pool within a time range
+    pub fn get_pool_metrics_history(
+        &self,
+        connection_id: &str,
+        pool_name: &str,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<Vec<PoolMetrics>, AppError> {
+        let conn = self.conn.lock()
+            .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT connection_id, pool_name, timestamp, capacity_bytes, allocation_bytes, available_bytes
+             FROM pool_metrics
+             WHERE connection_id = ?1 AND pool_name = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+             ORDER BY timestamp ASC",
+        )
+        .map_err(|e| AppError::Other(format!("Failed to prepare query: {}", e)))?;
+
+        let metrics = stmt.query_map(params![connection_id, pool_name, start_time, end_time], |row| {
+            Ok(PoolMetrics {
+                connection_id: row.get(0)?,
+                pool_name: row.get(1)?,
+                timestamp: row.get(2)?,
+                capacity_bytes: row.get(3)?,
+                allocation_bytes: row.get(4)?,
+                available_bytes: row.get(5)?,
+            })
+        })
+        .map_err(|e| AppError::Other(format!("Failed to query pool metrics: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("Failed to collect pool metrics: {}", e)))?;
+
+        Ok(metrics)
+    }
+
+    /// Row stride that keeps a history query under `max_points`
+    fn sampling_interval(
+        conn: &Connection,
+        table: &str,
+        connection_id: &str,
+        start_time: i64,
+        end_time: i64,
+        max_points: Option<usize>,
+    ) -> i64 {
+        let Some(max_points) = max_points.filter(|m| *m > 0) else {
+            return 1;
+        };
+
+        let count: i64 = conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM {} WHERE connection_id = ?1 AND timestamp >= ?2 AND timestamp <= ?3",
+                table
+            ),
+            params![connection_id, start_time, end_time],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+        (count / max_points as i64).max(1)
+    }
+
+    /// Start the background task that samples every connected host
+    pub fn start_host_collector_task(self: Arc<Self>, connections: Arc<ConnectionService>) {
+        tracing::info!("Starting host metrics collector task");
+
+        tauri::async_runtime::spawn(async move {
+            loop {
+                let metrics = self.clone();
+                let connections = connections.clone();
+                let pass = tauri::async_runtime::spawn_blocking(move || {
+                    metrics.collect_host_metrics(&connections)
+                }).await;
+
+                if let Err(e) = pass {
+                    tracing::error!("Host metrics collection failed: {}", e);
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_secs(HOST_SAMPLE_INTERVAL_SECS)).await;
+            }
+        });
+    }
+
+    /// Sample, store and cache host and pool metrics for every live connection
+    fn collect_host_metrics(&self, connections: &ConnectionService) {
+        let targets = match connections.get_connected() {
+            Ok(targets) => targets,
+            Err(e) => {
+                tracing::debug!("Skipping host metrics, no connection available: {}", e);
+                return;
+            }
+        };
+
+        // Forget hosts that went away so their rates restart cleanly
+        if let Ok(mut counters) = self.counters.lock() {
+            counters.retain(|id, _| targets.iter().any(|(target, _)| target == id));
+        }
+        if let Ok(mut latest) = self.latest.lock() {
+            latest.retain(|id, _| targets.iter().any(|(target, _)| target == id));
+        }
+
+        for (connection_id, libvirt) in &targets {
+            let snapshot = match self.sample_host(connection_id, libvirt) {
+                Ok(snapshot) => snapshot,
+                Err(e) => {
+                    tracing::warn!("Failed to sample host metrics for {}: {}", connection_id, e);
+                    continue;
+                }
+            };
+
+            if let Err(e) = self.store_snapshot(&snapshot) {
+                tracing::error!("Failed to store host metrics for {}: {}", connection_id, e);
+            }
+
+            if let Ok(mut latest) = self.latest.lock() {
+                latest.insert(connection_id.clone(), snapshot);
+            }
+        }
+    }
+
+    fn sample_host(&self, connection_id: &str, libvirt: &LibvirtService) -> Result<HostSnapshot, AppError> {
+        let conn = libvirt.get_connection();
+        let node_info = conn.get_node_info()
+            .map_err(|e| AppError::Other(format!("Failed to get node info: {}", e)))?;
+        let timestamp = chrono::Utc::now().timestamp();
+        let local = libvirt.is_local();
+        let meminfo = if local { read_meminfo() } else { HashMap::new() };
+
+        let memory_total_mb = node_info.memory / 1024;
+        let memory_free_mb = match meminfo.get("MemAvailable") {
+            Some(kb) => kb / 1024,
+            None => conn.get_free_memory()
+                .map_err(|e| AppError::Other(format!("Failed to get free memory: {}", e)))?
+                / (1024 * 1024),
+        };
+
+        let mut counters = self.counters.lock()
+            .map_err(|e| AppError::Other(format!("Failed to lock host counters: {}", e)))?;
+        let previous = counters.entry(connection_id.to_string()).or_default();
+
+        let cpu_times = node_cpu_times(libvirt);
+        let cpu_usage = match (previous.cpu, cpu_times) {
+            (Some((busy_before, total_before)), Some((busy, total))) if total > total_before => {
+                Some(busy.saturating_sub(busy_before) as f64 / (total - total_before) as f64 * 100.0)
+            }
+            _ => None,
+        };
+        previous.cpu = cpu_times;
+
+        let (vcpus_allocated, memory_allocated_mb) = VmService::list_vms(libvirt)
+            .map(|vms| {
+                vms.iter()
+                    .filter(|vm| vm.state == VmState::Running)
+                    .fold((0, 0), |(cpus, memory), vm| (cpus + vm.cpu_count, memory + vm.memory_mb))
+            })
+            .unwrap_or((0, 0));
+
+        let load = local.then(read_loadavg).flatten();
+        let host = HostMetrics {
+            connection_id: connection_id.to_string(),
+            timestamp,
+            cpu_usage,
+            cpu_count: node_info.cpus,
+            memory_total_mb,
+            memory_free_mb,
+            swap_total_mb: meminfo.get("SwapTotal").map(|kb| kb / 1024),
+            swap_free_mb: meminfo.get("SwapFree").map(|kb| kb / 1024),
+            load_1: load.map(|l| l.0),
+            load_5: load.map(|l| l.1),
+            load_15: load.map(|l| l.2),
+            hugepages_total: meminfo.get("HugePages_Total").copied(),
+            hugepages_free: meminfo.get("HugePages_Free").copied(),
+            ksm_shared_mb: local
+                .then(|| read_sys_counter("/sys/kernel/mm/ksm/pages_sharing"))
+                .flatten()
+                .map(|pages| pages * PAGE_SIZE_BYTES / (1024 * 1024)),
+            vcpus_allocated,
+            memory_allocated_mb,
+        };
+
+        let pools = StorageService::list_storage_pools(libvirt)
+            .map(|pools| {
+                pools.into_iter()
+                    .filter(|pool| pool.capacity_bytes > 0)
+                    .map(|pool| PoolMetrics {
+                        connection_id: connection_id.to_string(),
+                        pool_name: pool.name,
+                        timestamp,
+                        capacity_bytes: pool.capacity_bytes,
+                        allocation_bytes: pool.allocation_bytes,
+                        available_bytes: pool.available_bytes,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Bridge counters live in the local sysfs only
+        let mut networks = Vec::new();
+        if local {
+            let now = chrono::Utc::now().timestamp_millis();
+            let active = NetworkService::list_networks(libvirt)
+                .map(|networks| networks.into_iter().filter(|n| n.active && !n.bridge.is_empty()).collect())
+                .unwrap_or_else(|_| Vec::new());
+
+            let mut seen = HashMap::new();
+            for network in active {
+                let Some(rx) = read_sys_counter(&format!("/sys/class/net/{}/statistics/rx_bytes", network.bridge)) else {
+                    continue;
+                };
+                let Some(tx) = read_sys_counter(&format!("/sys/class/net/{}/statistics/tx_bytes", network.bridge)) else {
+                    continue;
+                };
+
+                if let Some((rx_before, tx_before, at)) = previous.networks.get(&network.name) {
+                    let elapsed = (now - at) as f64 / 1000.0;
+                    if elapsed > 0.0 && rx >= *rx_before && tx >= *tx_before {
+                        networks.push(NetworkThroughput {
+                            connection_id: connection_id.to_string(),
+                            network_name: network.name.clone(),
+                            bridge: network.bridge.clone(),
+                            rx_bytes_per_sec: (rx - rx_before) as f64 / elapsed,
+                            tx_bytes_per_sec: (tx - tx_before) as f64 / elapsed,
+                        });
+                    }
+                }
+                seen.insert(network.name, (rx, tx, now));
+            }
+            previous.networks = seen;
+        }
+
+        Ok(HostSnapshot { host, pools, networks })
+    }
+
+    fn store_snapshot(&self, snapshot: &HostSnapshot) -> Result<(), AppError> {
+        let conn = self.conn.lock()
+            .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+        let host = &snapshot.host;
+
+        conn.execute(
+            "INSERT INTO host_metrics (
+                connection_id, timestamp, cpu_usage, cpu_count, memory_total_mb, memory_free_mb,
+                swap_total_mb, swap_free_mb, load_1, load_5, load_15, hugepages_total,
+                hugepages_free, ksm_shared_mb, vcpus_allocated, memory_allocated_mb
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+            params![
+                host.connection_id,
+                host.timestamp,
+                host.cpu_usage,
+                host.cpu_count,
+                host.memory_total_mb,
+                host.memory_free_mb,
+                host.swap_total_mb,
+                host.swap_free_mb,
+                host.load_1,
+                host.load_5,
+                host.load_15,
+                host.hugepages_total,
+                host.hugepages_free,
+                host.ksm_shared_mb,
+                host.vcpus_allocated,
+                host.memory_allocated_mb,
+            ],
+        )
+        .map_err(|e| AppError::Other(format!("Failed to insert host metrics: {}", e)))?;
+
+        for pool in &snapshot.pools {
+            conn.execute(
+                "INSERT INTO pool_metrics (
+                    connection_id, pool_name, timestamp, capacity_bytes, allocation_bytes, available_bytes
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    pool.connection_id,
+                    pool.pool_name,
+                    pool.timestamp,
+                    pool.capacity_bytes,
+                    pool.allocation_bytes,
+                    pool.available_bytes,
+                ],
+            )
+            .map_err(|e| AppError::Other(format!("Failed to insert pool metrics: {}", e)))?;
+        }
+
+        Ok(())
+    }
+
     /// Get the total number of metrics stored
     pub fn get_metrics_count(&self) -> Result<i64, AppError> {
         let conn = self.conn.lock()
@@ -218,3 +713,63 @@ impl MetricsService {
         Ok(count)
     }
 }
+
+/// Busy and total CPU time across all host CPUs, in nanoseconds
+fn node_cpu_times(libvirt: &LibvirtService) -> Option<(u64, u64)> {
+    // VIR_NODE_CPU_STATS_ALL_CPUS
+    const ALL_CPUS: i32 = -1;
+
+    let conn = libvirt.get_connection().as_ptr();
+    let mut nparams: i32 = 0;
+    let ret = unsafe { sys::virNodeGetCPUStats(conn, ALL_CPUS, std::ptr::null_mut(), &mut nparams, 0) };
+    if ret < 0 || nparams <= 0 {
+        return None;
+    }
+
+    let mut stats: Vec<sys::virNodeCPUStats> = (0..nparams).map(|_| unsafe { std::mem::zeroed() }).collect();
+    let ret = unsafe { sys::virNodeGetCPUStats(conn, ALL_CPUS, stats.as_mut_ptr(), &mut nparams, 0) };
+    if ret < 0 {
+        return None;
+    }
+
+    let mut total = 0u64;
+    let mut idle = 0u64;
+    for stat in stats.iter().take(nparams as usize) {
+        let field = unsafe { CStr::from_ptr(stat.field.as_ptr()) }.to_string_lossy();
+        match field.as_ref() {
+            "kernel" | "user" => total += stat.value,
+            "idle" | "iowait" => {
+                total += stat.value;
+                idle += stat.value;
+            }
+            _ => {}
+        }
+    }
+
+    (total > 0).then(|| (total - idle, total))
+}
+
+/// /proc/meminfo values; sizes are KiB, HugePages_* are page counts
+fn read_meminfo() -> HashMap<String, u64> {
+    std::fs::read_to_string("/proc/meminfo")
+        .map(|text| {
+            text.lines()
+                .filter_map(|line| {
+                    let (key, value) = line.split_once(':')?;
+                    let value = value.split_whitespace().next()?.parse().ok()?;
+                    Some((key.to_string(), value))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn read_loadavg() -> Option<(f64, f64, f64)> {
+    let text = std::fs::read_to_string("/proc/loadavg").ok()?;
+    let mut fields = text.split_whitespace().map(|f| f.parse::<f64>().ok());
+    Some((fields.next()??, fields.next()??, fields.next()??))
+}
+
+fn read_sys_counter(path: &str) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
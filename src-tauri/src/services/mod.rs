@@ -1,17 +1,28 @@
 pub mod libvirt;
 pub mod connection_service;
+pub mod event_service;
+pub mod job_service;
+pub mod secret_service;
 pub mod vm_service;
 pub mod storage_service;
 pub mod network_service;
 pub mod snapshot_service;
+pub mod snapshot_retention_service;
 pub mod metrics_service;
 pub mod template_service;
 pub mod scheduler_service;
 pub mod alert_service;
+pub mod alert_rule_service;
+pub mod alert_history_service;
+pub mod notification_service;
 pub mod backup_service;
+pub mod backup_target_service;
+pub mod backup_verify_service;
+pub mod restore_service;
 pub mod optimization_service;
 pub mod retention_service;
 pub mod guest_agent_service;
+pub mod quiesce_service;
 pub mod pci_service;
 pub mod usb_service;
 pub mod mdev_service;
@@ -72,6 +72,7 @@ impl NetworkService {
             active,
             autostart,
             ip_range,
+            connection_id: None,
         })
     }
 
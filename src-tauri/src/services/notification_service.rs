@@ -0,0 +1,764 @@
+use crate::services::alert_history_service::AlertInstance;
+use crate::services::alert_rule_service::RuleAlertEvent;
+use crate::services::alert_service::{AlertEvent, AlertSeverity};
+use crate::services::event_service::EventService;
+use crate::services::secret_service::SecretService;
+use crate::utils::error::AppError;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Tauri event name emitted after every delivery (successful or not)
+pub const NOTIFICATION_DELIVERY_EVENT: &str = "notification-delivery";
+
+/// Header carrying the webhook body signature, `sha256=<hex>`
+const SIGNATURE_HEADER: &str = "X-KVM-Manager-Signature";
+
+/// Number of delivery records kept per channel
+const DELIVERY_LOG_LIMIT: usize = 100;
+
+/// Network timeout for webhook and SMTP deliveries
+const DELIVERY_TIMEOUT_SECS: u64 = 15;
+
+/// SMTP connection security
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum SmtpSecurity {
+    /// Plain SMTP, for local relays and test sinks
+    None,
+    #[default]
+    StartTls,
+    /// Implicit TLS (SMTPS)
+    Tls,
+}
+
+/// How a command hook receives the notification
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum CommandPayload {
+    /// `KVM_ALERT_*` environment variables
+    #[default]
+    Env,
+    /// Notification JSON written to stdin (environment variables are still set)
+    Stdin,
+}
+
+/// Transport-specific channel settings
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ChannelConfig {
+    /// POST the notification as JSON
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        /// Signs the body with HMAC-SHA256 when set
+        #[serde(default)]
+        hmac_secret: Option<String>,
+    },
+    /// Send a plain-text email
+    Smtp {
+        host: String,
+        port: u16,
+        #[serde(default)]
+        security: SmtpSecurity,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password: Option<String>,
+        from: String,
+        to: Vec<String>,
+    },
+    /// Run a local program
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        payload: CommandPayload,
+        #[serde(default = "default_command_timeout")]
+        timeout_secs: u64,
+    },
+    /// Native desktop notification
+    Desktop,
+}
+
+fn default_command_timeout() -> u64 {
+    30
+}
+
+impl ChannelConfig {
+    /// The webhook HMAC secret or SMTP password, kept in the secret store rather than the channel file
+    fn secret_mut(&mut self) -> Option<&mut Option<String>> {
+        match self {
+            ChannelConfig::Webhook { hmac_secret, .. } => Some(hmac_secret),
+            ChannelConfig::Smtp { password, .. } => Some(password),
+            ChannelConfig::Command { .. } | ChannelConfig::Desktop => None,
+        }
+    }
+}
+
+/// Retry behaviour for failed deliveries
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryPolicy {
+    /// Total attempts including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub backoff_secs: u64,
+    /// Factor applied to the delay after every retry
+    pub backoff_multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff_secs: 10,
+            backoff_multiplier: 2.0,
+        }
+    }
+}
+
+/// A configured notification channel
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationChannel {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    pub config: ChannelConfig,
+    /// Severities always routed to this channel, in addition to alerts that select it
+    #[serde(default)]
+    pub severities: Vec<AlertSeverity>,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    pub created_at: i64,
+}
+
+/// Request to create or update a channel
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationChannelRequest {
+    pub name: String,
+    pub config: ChannelConfig,
+    #[serde(default)]
+    pub severities: Vec<AlertSeverity>,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Message handed to channels; also the webhook and stdin JSON body
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    /// What raised the notification, e.g. "resourceAlert"
+    pub kind: String,
+    /// ID of the alert or rule that fired
+    pub source_id: String,
+    pub title: String,
+    pub message: String,
+    pub severity: AlertSeverity,
+    pub vm_id: Option<String>,
+    pub timestamp: i64,
+    /// The originating event
+    pub details: serde_json::Value,
+}
+
+impl Notification {
+    /// Build a notification for a triggered resource alert
+    pub fn from_alert(event: &AlertEvent) -> Self {
+        Self {
+            kind: "resourceAlert".to_string(),
+            source_id: event.alert_id.clone(),
+            title: format!("[{}] {}", severity_label(&event.severity), event.alert_name),
+            message: format!(
+                "{:?} on {} is {:.1}, threshold {:.1}",
+                event.threshold_type, event.subject, event.current_value, event.threshold_value
+            ),
+            severity: event.severity.clone(),
+            vm_id: Some(event.vm_id.clone()).filter(|id| !id.is_empty()),
+            timestamp: event.timestamp,
+            details: serde_json::to_value(event).unwrap_or(serde_json::Value::Null),
+        }
+    }
+
+    /// Build a notification for a triggered event rule
+    pub fn from_rule(event: &RuleAlertEvent) -> Self {
+        Self {
+            kind: "eventRule".to_string(),
+            source_id: event.rule_id.clone(),
+            title: format!("[{}] {}", severity_label(&event.severity), event.rule_name),
+            message: event.message.clone(),
+            severity: event.severity.clone(),
+            vm_id: None,
+            timestamp: event.timestamp,
+            details: serde_json::to_value(event).unwrap_or(serde_json::Value::Null),
+        }
+    }
+
+    /// Build a reminder for an alert instance that is still firing
+    pub fn reminder(instance: &AlertInstance) -> Self {
+        Self {
+            kind: "alertReminder".to_string(),
+            source_id: instance.source_id.clone(),
+            title: format!("[{}] {} (still firing)", severity_label(&instance.severity), instance.name),
+            message: instance.message.clone(),
+            severity: instance.severity.clone(),
+            vm_id: instance.vm_id.clone(),
+            timestamp: instance.last_notified_at,
+            details: serde_json::to_value(instance).unwrap_or(serde_json::Value::Null),
+        }
+    }
+
+    /// Build a recovery notification for a resolved alert instance
+    pub fn resolved(instance: &AlertInstance) -> Self {
+        Self {
+            kind: "alertResolved".to_string(),
+            source_id: instance.source_id.clone(),
+            title: format!("[RESOLVED] {}", instance.name),
+            message: format!("{} is back to normal", instance.subject_name),
+            severity: instance.severity.clone(),
+            vm_id: instance.vm_id.clone(),
+            timestamp: instance.resolved_at.unwrap_or(instance.last_notified_at),
+            details: serde_json::to_value(instance).unwrap_or(serde_json::Value::Null),
+        }
+    }
+}
+
+fn severity_label(severity: &AlertSeverity) -> &'static str {
+    match severity {
+        AlertSeverity::Info => "INFO",
+        AlertSeverity::Warning => "WARNING",
+        AlertSeverity::Critical => "CRITICAL",
+    }
+}
+
+/// Delivery outcome
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    Delivered,
+    Failed,
+}
+
+/// One delivery of a notification to a channel, after all retries
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryRecord {
+    pub id: String,
+    pub channel_id: String,
+    pub channel_name: String,
+    pub source_id: String,
+    pub title: String,
+    pub severity: AlertSeverity,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    /// Error of the last failed attempt
+    pub error: Option<String>,
+    pub timestamp: i64,
+}
+
+/// Routes notifications to webhook, SMTP, command and desktop channels
+pub struct NotificationService {
+    channels_dir: PathBuf,
+    events: Arc<EventService>,
+    secrets: Arc<SecretService>,
+    /// Serialises read-modify-write of the delivery logs across delivery threads
+    deliveries_lock: Mutex<()>,
+}
+
+impl NotificationService {
+    /// Create a new NotificationService instance
+    pub fn new(events: Arc<EventService>) -> Result<Self, AppError> {
+        let channels_dir = Self::get_channels_dir()?;
+
+        fs::create_dir_all(channels_dir.join("deliveries"))
+            .map_err(|e| AppError::NotificationError(format!("Failed to create notifications directory: {}", e)))?;
+
+        Ok(Self {
+            channels_dir,
+            events,
+            secrets: SecretService::shared()?,
+            deliveries_lock: Mutex::new(()),
+        })
+    }
+
+    /// Get the notification channels directory path
+    fn get_channels_dir() -> Result<PathBuf, AppError> {
+        let config_dir = dirs::config_dir()
+            .ok_or_else(|| AppError::NotificationError("Could not determine config directory".to_string()))?;
+
+        Ok(config_dir.join("kvm-manager").join("notifications"))
+    }
+
+    /// Create a new channel
+    pub fn create_channel(&self, request: NotificationChannelRequest) -> Result<NotificationChannel, AppError> {
+        Self::validate(&request)?;
+
+        let channel = NotificationChannel {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: request.name,
+            enabled: request.enabled,
+            config: request.config,
+            severities: request.severities,
+            retry: request.retry,
+            created_at: chrono::Utc::now().timestamp(),
+        };
+
+        self.save_channel(&channel)?;
+        Ok(channel)
+    }
+
+    /// Replace a channel's settings
+    pub fn update_channel(&self, id: &str, request: NotificationChannelRequest) -> Result<NotificationChannel, AppError> {
+        Self::validate(&request)?;
+
+        let mut channel = self.get_channel(id)?;
+        channel.name = request.name;
+        channel.enabled = request.enabled;
+        channel.config = request.config;
+        channel.severities = request.severities;
+        channel.retry = request.retry;
+
+        self.save_channel(&channel)?;
+        Ok(channel)
+    }
+
+    /// List all channels
+    pub fn list_channels(&self) -> Result<Vec<NotificationChannel>, AppError> {
+        let entries = fs::read_dir(&self.channels_dir)
+            .map_err(|e| AppError::NotificationError(format!("Failed to read notifications directory: {}", e)))?;
+
+        let mut channels = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+
+            match self.read_channel(&path) {
+                Ok(channel) => channels.push(channel),
+                Err(e) => tracing::warn!("Failed to load notification channel {:?}: {}", path, e),
+            }
+        }
+
+        channels.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(channels)
+    }
+
+    /// Get a channel by ID
+    pub fn get_channel(&self, id: &str) -> Result<NotificationChannel, AppError> {
+        let path = self.channels_dir.join(format!("{}.json", id));
+        if !path.exists() {
+            return Err(AppError::NotificationError(format!("Channel not found: {}", id)));
+        }
+
+        self.read_channel(&path)
+    }
+
+    /// Delete a channel and its delivery log
+    pub fn delete_channel(&self, id: &str) -> Result<(), AppError> {
+        let path = self.channels_dir.join(format!("{}.json", id));
+        if !path.exists() {
+            return Err(AppError::NotificationError(format!("Channel not found: {}", id)));
+        }
+
+        fs::remove_file(&path)
+            .map_err(|e| AppError::NotificationError(format!("Failed to delete channel: {}", e)))?;
+        let _ = fs::remove_file(self.delivery_log_path(id));
+        self.secrets.set_value(&Self::secret_reference(id), None)
+    }
+
+    /// Send a test notification once, without retries
+    pub fn test_channel(&self, id: &str) -> Result<DeliveryRecord, AppError> {
+        let channel = self.get_channel(id)?;
+        let notification = Notification {
+            kind: "test".to_string(),
+            source_id: channel.id.clone(),
+            title: "KVM Manager test notification".to_string(),
+            message: format!("Test notification for channel '{}'", channel.name),
+            severity: AlertSeverity::Info,
+            vm_id: None,
+            timestamp: chrono::Utc::now().timestamp(),
+            details: serde_json::Value::Null,
+        };
+
+        let result = self.send(&channel, &notification);
+        let record = Self::delivery_record(&channel, &notification, 1, result.err().map(|e| e.to_string()));
+        self.record_delivery(&record);
+        Ok(record)
+    }
+
+    /// Delivery log of one channel, or of all channels, newest first
+    pub fn get_deliveries(&self, channel_id: Option<&str>) -> Result<Vec<DeliveryRecord>, AppError> {
+        let channel_ids = match channel_id {
+            Some(id) => vec![id.to_string()],
+            None => self.list_channels()?.into_iter().map(|c| c.id).collect(),
+        };
+
+        let mut records: Vec<DeliveryRecord> = channel_ids
+            .iter()
+            .flat_map(|id| self.load_delivery_log(id))
+            .collect();
+        records.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        records.truncate(DELIVERY_LOG_LIMIT * 2);
+        Ok(records)
+    }
+
+    /// Deliver a notification to the given channels and to every channel subscribed to
+    /// its severity. Delivery runs on background threads, one per channel.
+    pub fn dispatch(self: &Arc<Self>, notification: Notification, channel_ids: &[String]) {
+        let channels = match self.list_channels() {
+            Ok(channels) => channels,
+            Err(e) => {
+                tracing::error!("Failed to load notification channels: {}", e);
+                return;
+            }
+        };
+
+        for channel in channels.into_iter().filter(|c| {
+            c.enabled && (channel_ids.contains(&c.id) || c.severities.contains(&notification.severity))
+        }) {
+            let service = self.clone();
+            let notification = notification.clone();
+            let spawned = std::thread::Builder::new()
+                .name("notification".to_string())
+                .spawn(move || {
+                    let record = service.deliver(&channel, &notification);
+                    service.record_delivery(&record);
+                    service.events.emit(NOTIFICATION_DELIVERY_EVENT, record);
+                });
+
+            if let Err(e) = spawned {
+                tracing::error!("Failed to start notification delivery: {}", e);
+            }
+        }
+    }
+
+    /// Send with the channel's retry policy
+    fn deliver(&self, channel: &NotificationChannel, notification: &Notification) -> DeliveryRecord {
+        let max_attempts = channel.retry.max_attempts.max(1);
+        let mut delay = Duration::from_secs(channel.retry.backoff_secs);
+        let mut last_error = None;
+
+        for attempt in 1..=max_attempts {
+            match self.send(channel, notification) {
+                Ok(()) => return Self::delivery_record(channel, notification, attempt, None),
+                Err(e) => {
+                    tracing::warn!(
+                        "Notification to '{}' failed (attempt {}/{}): {}",
+                        channel.name, attempt, max_attempts, e
+                    );
+                    last_error = Some(e.to_string());
+                }
+            }
+
+            if attempt < max_attempts {
+                std::thread::sleep(delay);
+                delay = delay.mul_f64(channel.retry.backoff_multiplier.max(1.0));
+            }
+        }
+
+        Self::delivery_record(channel, notification, max_attempts, last_error)
+    }
+
+    /// Perform a single delivery attempt
+    fn send(&self, channel: &NotificationChannel, notification: &Notification) -> Result<(), AppError> {
+        match &channel.config {
+            ChannelConfig::Webhook { url, headers, hmac_secret } => {
+                Self::send_webhook(url, headers, hmac_secret.as_deref(), notification)
+            }
+            ChannelConfig::Smtp { host, port, security, username, password, from, to } => {
+                Self::send_email(host, *port, security, username.as_deref(), password.as_deref(), from, to, notification)
+            }
+            ChannelConfig::Command { program, args, payload, timeout_secs } => {
+                Self::run_command(program, args, payload, *timeout_secs, notification)
+            }
+            ChannelConfig::Desktop => self.events.notify_desktop(&notification.title, &notification.message),
+        }
+    }
+
+    fn send_webhook(
+        url: &str,
+        headers: &HashMap<String, String>,
+        hmac_secret: Option<&str>,
+        notification: &Notification,
+    ) -> Result<(), AppError> {
+        let body = serde_json::to_string(notification)
+            .map_err(|e| AppError::NotificationError(format!("Failed to serialize notification: {}", e)))?;
+
+        let mut request = ureq::post(url)
+            .timeout(Duration::from_secs(DELIVERY_TIMEOUT_SECS))
+            .set("Content-Type", "application/json");
+        for (name, value) in headers {
+            request = request.set(name, value);
+        }
+
+        if let Some(secret) = hmac_secret.filter(|s| !s.is_empty()) {
+            let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+                .map_err(|e| AppError::NotificationError(format!("Invalid HMAC secret: {}", e)))?;
+            mac.update(body.as_bytes());
+            let signature = hex::encode(mac.finalize().into_bytes());
+            request = request.set(SIGNATURE_HEADER, &format!("sha256={}", signature));
+        }
+
+        request.send_string(&body).map_err(|e| match e {
+            ureq::Error::Status(code, _) => AppError::NotificationError(format!("Webhook returned HTTP {}", code)),
+            e => AppError::NotificationError(format!("Webhook request failed: {}", e)),
+        })?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn send_email(
+        host: &str,
+        port: u16,
+        security: &SmtpSecurity,
+        username: Option<&str>,
+        password: Option<&str>,
+        from: &str,
+        to: &[String],
+        notification: &Notification,
+    ) -> Result<(), AppError> {
+        use lettre::message::header::ContentType;
+        use lettre::message::Mailbox;
+        use lettre::transport::smtp::authentication::Credentials;
+        use lettre::{Message, SmtpTransport, Transport};
+
+        let smtp_error = |e: &dyn std::fmt::Display| AppError::NotificationError(format!("SMTP error: {}", e));
+
+        let mut builder = Message::builder()
+            .from(from.parse::<Mailbox>().map_err(|e| smtp_error(&e))?)
+            .subject(&notification.title)
+            .header(ContentType::TEXT_PLAIN);
+        for recipient in to {
+            builder = builder.to(recipient.parse::<Mailbox>().map_err(|e| smtp_error(&e))?);
+        }
+
+        let details = serde_json::to_string_pretty(&notification.details).unwrap_or_default();
+        let email = builder
+            .body(format!("{}\n\n{}\n", notification.message, details))
+            .map_err(|e| smtp_error(&e))?;
+
+        let mut transport = match security {
+            SmtpSecurity::None => SmtpTransport::builder_dangerous(host),
+            SmtpSecurity::StartTls => SmtpTransport::starttls_relay(host).map_err(|e| smtp_error(&e))?,
+            SmtpSecurity::Tls => SmtpTransport::relay(host).map_err(|e| smtp_error(&e))?,
+        }
+        .port(port)
+        .timeout(Some(Duration::from_secs(DELIVERY_TIMEOUT_SECS)));
+
+        if let Some(username) = username.filter(|u| !u.is_empty()) {
+            transport = transport.credentials(Credentials::new(
+                username.to_string(),
+                password.unwrap_or_default().to_string(),
+            ));
+        }
+
+        transport.build().send(&email).map_err(|e| smtp_error(&e))?;
+        Ok(())
+    }
+
+    fn run_command(
+        program: &str,
+        args: &[String],
+        payload: &CommandPayload,
+        timeout_secs: u64,
+        notification: &Notification,
+    ) -> Result<(), AppError> {
+        let mut command = Command::new(program);
+        command
+            .args(args)
+            .env("KVM_ALERT_KIND", &notification.kind)
+            .env("KVM_ALERT_ID", &notification.source_id)
+            .env("KVM_ALERT_TITLE", &notification.title)
+            .env("KVM_ALERT_MESSAGE", &notification.message)
+            .env("KVM_ALERT_SEVERITY", severity_label(&notification.severity).to_lowercase())
+            .env("KVM_ALERT_VM_ID", notification.vm_id.as_deref().unwrap_or_default())
+            .env("KVM_ALERT_TIMESTAMP", notification.timestamp.to_string())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+
+        command.stdin(match payload {
+            CommandPayload::Env => Stdio::null(),
+            CommandPayload::Stdin => Stdio::piped(),
+        });
+
+        let mut child = command.spawn()
+            .map_err(|e| AppError::NotificationError(format!("Failed to run {}: {}", program, e)))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            let json = serde_json::to_vec(notification).unwrap_or_default();
+            // A hook that exits without reading stdin is not an error
+            let _ = stdin.write_all(&json);
+        }
+
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs.max(1));
+        loop {
+            if let Some(status) = child.try_wait()? {
+                if status.success() {
+                    return Ok(());
+                }
+
+                let mut stderr = String::new();
+                if let Some(mut pipe) = child.stderr.take() {
+                    use std::io::Read;
+                    let _ = pipe.read_to_string(&mut stderr);
+                }
+                return Err(AppError::NotificationError(format!(
+                    "{} exited with {}: {}",
+                    program, status, stderr.trim()
+                )));
+            }
+
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(AppError::NotificationError(format!(
+                    "{} timed out after {}s",
+                    program, timeout_secs
+                )));
+            }
+
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    fn validate(request: &NotificationChannelRequest) -> Result<(), AppError> {
+        if request.name.trim().is_empty() {
+            return Err(AppError::NotificationError("Channel name is required".to_string()));
+        }
+        if request.retry.max_attempts == 0 {
+            return Err(AppError::NotificationError("Retry policy needs at least one attempt".to_string()));
+        }
+
+        match &request.config {
+            ChannelConfig::Webhook { url, .. } => {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(AppError::NotificationError("Webhook URL must use http or https".to_string()));
+                }
+            }
+            ChannelConfig::Smtp { host, from, to, .. } => {
+                if host.trim().is_empty() || from.trim().is_empty() {
+                    return Err(AppError::NotificationError("SMTP host and sender are required".to_string()));
+                }
+                if to.is_empty() {
+                    return Err(AppError::NotificationError("At least one recipient is required".to_string()));
+                }
+            }
+            ChannelConfig::Command { program, .. } => {
+                if program.trim().is_empty() {
+                    return Err(AppError::NotificationError("Command program is required".to_string()));
+                }
+            }
+            ChannelConfig::Desktop => {}
+        }
+
+        Ok(())
+    }
+
+    fn delivery_record(
+        channel: &NotificationChannel,
+        notification: &Notification,
+        attempts: u32,
+        error: Option<String>,
+    ) -> DeliveryRecord {
+        DeliveryRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            channel_id: channel.id.clone(),
+            channel_name: channel.name.clone(),
+            source_id: notification.source_id.clone(),
+            title: notification.title.clone(),
+            severity: notification.severity.clone(),
+            status: if error.is_none() { DeliveryStatus::Delivered } else { DeliveryStatus::Failed },
+            attempts,
+            error,
+            timestamp: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    fn delivery_log_path(&self, channel_id: &str) -> PathBuf {
+        self.channels_dir.join("deliveries").join(format!("{}.json", channel_id))
+    }
+
+    fn load_delivery_log(&self, channel_id: &str) -> Vec<DeliveryRecord> {
+        fs::read_to_string(self.delivery_log_path(channel_id))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Prepend a record to the channel's delivery log, keeping the newest `DELIVERY_LOG_LIMIT`
+    fn record_delivery(&self, record: &DeliveryRecord) {
+        let _guard = self.deliveries_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut log = self.load_delivery_log(&record.channel_id);
+        log.insert(0, record.clone());
+        log.truncate(DELIVERY_LOG_LIMIT);
+
+        let result = serde_json::to_string_pretty(&log)
+            .map_err(|e| e.to_string())
+            .and_then(|json| fs::write(self.delivery_log_path(&record.channel_id), json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::error!("Failed to write delivery log for channel {}: {}", record.channel_id, e);
+        }
+    }
+
+    /// Secret store reference of a channel's HMAC secret or SMTP password
+    fn secret_reference(channel_id: &str) -> String {
+        format!("notification/{}/secret", channel_id)
+    }
+
+    /// Load a channel file and fill in its secret from the secret store
+    fn read_channel(&self, path: &Path) -> Result<NotificationChannel, AppError> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| AppError::NotificationError(format!("Failed to read channel file: {}", e)))?;
+        let mut channel: NotificationChannel = serde_json::from_str(&json)
+            .map_err(|e| AppError::NotificationError(format!("Failed to parse channel file: {}", e)))?;
+
+        let stored = self.secrets.get_value(&Self::secret_reference(&channel.id))?;
+        let mut plaintext = false;
+        if let Some(secret) = channel.config.secret_mut() {
+            if secret.is_some() {
+                plaintext = true;
+            } else {
+                *secret = stored;
+            }
+        }
+
+        // Files written before secrets moved to the secret store
+        if plaintext {
+            self.save_channel(&channel)?;
+        }
+        Ok(channel)
+    }
+
+    /// Write a channel file, moving its secret into the secret store
+    fn save_channel(&self, channel: &NotificationChannel) -> Result<(), AppError> {
+        let mut stored = channel.clone();
+        let secret = stored.config.secret_mut().and_then(|secret| secret.take());
+        self.secrets.set_value(
+            &Self::secret_reference(&channel.id),
+            secret.as_deref().filter(|s| !s.is_empty()),
+        )?;
+
+        let json = serde_json::to_string_pretty(&stored)
+            .map_err(|e| AppError::NotificationError(format!("Failed to serialize channel: {}", e)))?;
+        fs::write(self.channels_dir.join(format!("{}.json", channel.id)), json)
+            .map_err(|e| AppError::NotificationError(format!("Failed to write channel file: {}", e)))
+    }
+}
@@ -230,7 +230,7 @@ impl OptimizationService {
     ) -> Result<Vec<OptimizationSuggestion>, AppError> {
         use crate::services::vm_service::VmService;
 
-        let vms = VmService::list_vms(&app_state.libvirt)?;
+        let vms = VmService::list_vms(&app_state.libvirt(None)?)?;
         let mut all_suggestions = Vec::new();
 
         for vm in vms {
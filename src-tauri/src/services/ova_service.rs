@@ -1,8 +1,8 @@
 use std::fs::{self, File};
 use std::io::{BufReader, Read};
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use serde::{Deserialize, Serialize};
+use crate::services::job_service::{self, JobHandle};
 use crate::utils::error::AppError;
 
 /// OVA/OVF Import Service
@@ -327,7 +327,10 @@ impl OvaService {
     }
 
     /// Import OVA/OVF and convert to libvirt VM
-    pub fn import_ova(config: OvaImportConfig) -> Result<PathBuf, AppError> {
+    ///
+    /// When run as a job, extraction and each disk conversion are reported as
+    /// steps and a cancelled import removes the disks it already produced.
+    pub fn import_ova(config: OvaImportConfig, job: Option<&JobHandle>) -> Result<PathBuf, AppError> {
         let source_path = Path::new(&config.source_path);
 
         if !source_path.exists() {
@@ -349,6 +352,10 @@ impl OvaService {
             .unwrap_or("")
             .to_lowercase();
 
+        if let Some(job) = job {
+            job.set_step("Extracting disks");
+        }
+
         // Extract or locate disk files
         let disk_files = if extension == "ova" {
             Self::extract_ova_disks(source_path, target_path, &metadata.disks)?
@@ -359,9 +366,17 @@ impl OvaService {
             Self::locate_ovf_disks(ovf_dir, target_path, &metadata.disks)?
         };
 
+        if let Some(job) = job {
+            if job.is_cancelled() {
+                Self::remove_disks(&disk_files);
+                return Err(AppError::Cancelled);
+            }
+            job.set_percent(if config.convert_to_qcow2 { 20.0 } else { 100.0 });
+        }
+
         // Convert disks to qcow2 if requested
         let converted_disks = if config.convert_to_qcow2 {
-            Self::convert_disks_to_qcow2(&disk_files, target_path, &vm_name)?
+            Self::convert_disks_to_qcow2(&disk_files, target_path, &vm_name, job)?
         } else {
             disk_files
         };
@@ -484,8 +499,16 @@ impl OvaService {
     }
 
     /// Convert disk files to qcow2 using qemu-img
-    fn convert_disks_to_qcow2(disks: &[PathBuf], target_dir: &Path, vm_name: &str) -> Result<Vec<PathBuf>, AppError> {
+    ///
+    /// Conversion covers 20-100% of a job's progress, split evenly between disks.
+    fn convert_disks_to_qcow2(
+        disks: &[PathBuf],
+        target_dir: &Path,
+        vm_name: &str,
+        job: Option<&JobHandle>,
+    ) -> Result<Vec<PathBuf>, AppError> {
         let mut converted = Vec::new();
+        let share = 80.0 / disks.len().max(1) as f64;
 
         for (i, disk) in disks.iter().enumerate() {
             let suffix = if i == 0 { String::new() } else { format!("-{}", i) };
@@ -493,23 +516,34 @@ impl OvaService {
             let dest_path = target_dir.join(&qcow2_name);
 
             tracing::info!("Converting disk: {:?} -> {:?}", disk, dest_path);
+            if let Some(job) = job {
+                job.set_step(format!("Converting disk {} of {}", i + 1, disks.len()));
+            }
 
-            // Use qemu-img to convert
-            let output = Command::new("qemu-img")
-                .args([
+            let start = 20.0 + share * i as f64;
+            let result = job_service::run_qemu_img(
+                job,
+                &[
                     "convert",
                     "-f", "vmdk",  // Source format (auto-detected if wrong)
                     "-O", "qcow2",
                     "-o", "compat=1.1",
                     disk.to_str().unwrap_or(""),
                     dest_path.to_str().unwrap_or(""),
-                ])
-                .output()
-                .map_err(|e| AppError::Other(format!("Failed to run qemu-img: {}", e)))?;
-
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                return Err(AppError::Other(format!("qemu-img conversion failed: {}", stderr)));
+                ],
+                start..start + share,
+            );
+
+            if let Err(e) = result {
+                // Drop the partial output and earlier conversions; a cancelled
+                // import also removes the extracted sources still pending
+                let _ = fs::remove_file(&dest_path);
+                Self::remove_disks(&converted);
+                if job.is_some_and(|job| job.is_cancelled()) {
+                    Self::remove_disks(&disks[i..]);
+                    return Err(AppError::Cancelled);
+                }
+                return Err(e);
             }
 
             // Remove the original VMDK
@@ -520,4 +554,13 @@ impl OvaService {
 
         Ok(converted)
     }
+
+    /// Best-effort removal of disks produced by an aborted import
+    fn remove_disks(disks: &[PathBuf]) {
+        for disk in disks {
+            if let Err(e) = fs::remove_file(disk) {
+                tracing::warn!("Failed to remove {:?}: {}", disk, e);
+            }
+        }
+    }
 }
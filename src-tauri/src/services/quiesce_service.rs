@@ -0,0 +1,176 @@
+use crate::models::quiesce::{GuestHook, QuiesceOptions};
+use crate::services::guest_agent_service::GuestAgentService;
+use crate::utils::error::AppError;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Attempts made to thaw the guest before giving up
+const THAW_ATTEMPTS: u32 = 3;
+const THAW_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// QuiesceService freezes guest filesystems around a snapshot or backup.
+///
+/// `freeze` returns a `FreezeGuard` that thaws the guest when it is thawed
+/// explicitly, dropped on an error path, or its watchdog fires, whichever
+/// comes first.
+pub struct QuiesceService;
+
+impl QuiesceService {
+    /// Run the pre-freeze hook and freeze the guest.
+    ///
+    /// If the guest can't be quiesced and `options.required` is off, the
+    /// returned guard is not frozen and carries a note explaining why; the
+    /// caller goes on with a crash-consistent image.
+    pub fn freeze(uri: Option<&str>, vm_name: &str, options: &QuiesceOptions) -> Result<FreezeGuard, AppError> {
+        let agent = GuestAgentService::new();
+        let mut guard = FreezeGuard {
+            uri: uri.map(str::to_string),
+            vm_name: vm_name.to_string(),
+            post_thaw_hook: None,
+            frozen: false,
+            thawed: Arc::new(AtomicBool::new(true)),
+            watchdog: None,
+            note: None,
+        };
+
+        if let Some(hook) = &options.pre_freeze_hook {
+            if let Err(e) = run_hook(&agent, uri, vm_name, hook) {
+                return guard.fall_back(options, format!("Pre-freeze hook failed: {}", e));
+            }
+        }
+        // Once the pre-freeze hook has run, the post-thaw hook must run too
+        guard.post_thaw_hook = options.post_thaw_hook.clone();
+
+        let count = match agent.fs_freeze(uri, vm_name, &options.mountpoints) {
+            Ok(count) => count,
+            Err(e) => {
+                // A partial freeze still needs a thaw
+                let _ = agent.fs_thaw(uri, vm_name);
+                return guard.fall_back(options, format!("Guest filesystems could not be frozen: {}", e));
+            }
+        };
+        tracing::info!("Froze {} filesystem(s) of {}", count, vm_name);
+
+        guard.frozen = true;
+        guard.thawed.store(false, Ordering::SeqCst);
+        guard.start_watchdog(Duration::from_secs(options.timeout_secs.max(1)));
+        Ok(guard)
+    }
+}
+
+/// Keeps a guest frozen until thawed or dropped
+pub struct FreezeGuard {
+    uri: Option<String>,
+    vm_name: String,
+    post_thaw_hook: Option<GuestHook>,
+    frozen: bool,
+    /// Set by whichever of the guard and the watchdog thaws first
+    thawed: Arc<AtomicBool>,
+    /// Dropping the sender wakes the watchdog so it exits
+    watchdog: Option<mpsc::Sender<()>>,
+    note: Option<String>,
+}
+
+impl FreezeGuard {
+    /// Whether the guest filesystems were frozen
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Why the guest was not quiesced, if it wasn't
+    pub fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    /// Thaw the guest and run the post-thaw hook.
+    ///
+    /// Returns false if the watchdog had to thaw the guest first, in which case
+    /// the image may have been taken after the thaw and is only crash-consistent.
+    pub fn thaw(mut self) -> bool {
+        self.release()
+    }
+
+    fn release(&mut self) -> bool {
+        self.watchdog.take();
+        let held = if self.frozen && !self.thawed.swap(true, Ordering::SeqCst) {
+            thaw_guest(self.uri.as_deref(), &self.vm_name);
+            true
+        } else {
+            !self.frozen
+        };
+        self.frozen = false;
+
+        if let Some(hook) = self.post_thaw_hook.take() {
+            if let Err(e) = run_hook(&GuestAgentService::new(), self.uri.as_deref(), &self.vm_name, &hook) {
+                tracing::warn!("Post-thaw hook of {} failed: {}", self.vm_name, e);
+            }
+        }
+        held
+    }
+
+    fn fall_back(mut self, options: &QuiesceOptions, reason: String) -> Result<FreezeGuard, AppError> {
+        // Runs the post-thaw hook if the pre-freeze hook already ran
+        self.release();
+        if options.required {
+            return Err(AppError::Other(format!("Could not quiesce {}: {}", self.vm_name, reason)));
+        }
+        tracing::warn!("Taking a crash-consistent image of {}: {}", self.vm_name, reason);
+        self.note = Some(format!("{}; the image is crash-consistent", reason));
+        Ok(self)
+    }
+
+    fn start_watchdog(&mut self, timeout: Duration) {
+        let (sender, receiver) = mpsc::channel::<()>();
+        let thawed = Arc::clone(&self.thawed);
+        let uri = self.uri.clone();
+        let vm_name = self.vm_name.clone();
+
+        thread::spawn(move || {
+            if let Err(RecvTimeoutError::Timeout) = receiver.recv_timeout(timeout) {
+                if !thawed.swap(true, Ordering::SeqCst) {
+                    tracing::warn!("{} was frozen for over {}s; thawing it", vm_name, timeout.as_secs());
+                    thaw_guest(uri.as_deref(), &vm_name);
+                }
+            }
+        });
+        self.watchdog = Some(sender);
+    }
+}
+
+impl Drop for FreezeGuard {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Thaw a guest, retrying since leaving it frozen hangs its I/O
+fn thaw_guest(uri: Option<&str>, vm_name: &str) {
+    let agent = GuestAgentService::new();
+    for attempt in 1..=THAW_ATTEMPTS {
+        match agent.fs_thaw(uri, vm_name) {
+            Ok(count) => {
+                tracing::info!("Thawed {} filesystem(s) of {}", count, vm_name);
+                return;
+            }
+            Err(e) if attempt < THAW_ATTEMPTS => {
+                tracing::warn!("Failed to thaw {} (attempt {}): {}", vm_name, attempt, e);
+                thread::sleep(THAW_RETRY_DELAY);
+            }
+            Err(e) => tracing::error!("Failed to thaw {}; its filesystems may still be frozen: {}", vm_name, e),
+        }
+    }
+}
+
+/// Run a hook in the guest, failing on a non-zero exit code
+fn run_hook(agent: &GuestAgentService, uri: Option<&str>, vm_name: &str, hook: &GuestHook) -> Result<(), String> {
+    let result = agent
+        .exec_and_wait(uri, vm_name, &hook.path, &hook.args, Duration::from_secs(hook.timeout_secs))
+        .map_err(|e| e.to_string())?;
+    if result.exit_code != 0 {
+        return Err(format!("{} exited with {}: {}", hook.path, result.exit_code, result.stderr.trim()));
+    }
+    Ok(())
+}
@@ -0,0 +1,398 @@
+use crate::models::domain_xml::{Disk, DiskSource, DomainXml, XmlModel};
+use crate::services::backup_service::{self, BackupDisk, BackupRun, BackupService};
+use crate::services::job_service::{self, JobHandle};
+use crate::services::libvirt::LibvirtService;
+use crate::services::vm_service::VmService;
+use crate::utils::error::{map_libvirt_error, AppError};
+use crate::utils::xml::XmlElement;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use virt::domain::Domain;
+use virt::storage_pool::StoragePool;
+use virt::sys;
+
+/// Directory libvirt keeps UEFI variable stores in
+const NVRAM_DIR: &str = "/var/lib/libvirt/qemu/nvram";
+
+/// Where a backup point is restored to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RestoreMode {
+    /// Define a new VM with a new name, UUID and MAC addresses
+    NewVm,
+    /// Replace the backed-up VM's definition and disks; the VM must be shut off
+    InPlace,
+}
+
+/// Request to restore a whole VM from a backup point
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreBackupRequest {
+    pub config_id: String,
+    pub run_id: String,
+    pub mode: RestoreMode,
+    /// Name of the new VM; required for `NewVm`
+    pub new_name: Option<String>,
+    /// Storage pool the disks are restored into
+    pub pool: String,
+    /// Must be set for `InPlace`, which overwrites the current VM
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Request to restore one disk of a backup point
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreDiskRequest {
+    pub config_id: String,
+    pub run_id: String,
+    /// Target device of the disk in the backup (vda, sdb, ...)
+    pub disk: String,
+    pub pool: String,
+    /// Point the VM's disk at the restored image; the VM must be shut off
+    #[serde(default)]
+    pub replace: bool,
+    /// Must be set together with `replace`
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// An image written by a restore
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredDisk {
+    pub target: String,
+    pub path: String,
+}
+
+/// Result of a whole-VM restore
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreResult {
+    pub vm_id: String,
+    pub vm_name: String,
+    pub disks: Vec<RestoredDisk>,
+}
+
+/// RestoreService recreates VMs and disks from backup sets written by
+/// `BackupService`.
+///
+/// Disk images are flattened with `qemu-img convert`, so points of
+/// incremental and differential chains restore to standalone images. Images
+/// a restore replaces are left in place for the user to delete.
+pub struct RestoreService;
+
+impl RestoreService {
+    /// Restore a VM from a backup point, as a new VM or over the existing one
+    pub fn restore_backup(
+        libvirt: &LibvirtService,
+        request: &RestoreBackupRequest,
+        job: Option<&JobHandle>,
+    ) -> Result<RestoreResult, AppError> {
+        Self::require_local(libvirt)?;
+        let conn = libvirt.get_connection();
+        let run = BackupService::new()?.get_backup_point(&request.config_id, &request.run_id, job)?;
+        let set_dir = PathBuf::from(&run.path);
+
+        let xml_text = fs::read_to_string(set_dir.join("domain.xml"))
+            .map_err(|e| AppError::Other(format!("Failed to read domain XML from backup: {}", e)))?;
+        let mut xml = DomainXml::parse(&xml_text)?;
+
+        let (vm_name, vm_id) = match request.mode {
+            RestoreMode::NewVm => {
+                let name = request.new_name.as_deref().map(str::trim).unwrap_or_default();
+                if name.is_empty() {
+                    return Err(AppError::InvalidConfig("A name is required for the restored VM".to_string()));
+                }
+                if Domain::lookup_by_name(conn, name).is_ok() {
+                    return Err(AppError::InvalidConfig(format!("A VM named {} already exists", name)));
+                }
+                (name.to_string(), uuid::Uuid::new_v4().to_string())
+            }
+            RestoreMode::InPlace => {
+                if !request.confirm {
+                    return Err(AppError::InvalidConfig(
+                        "Restoring in place replaces the VM's configuration and disks; confirm to continue".to_string(),
+                    ));
+                }
+                let domain = Domain::lookup_by_uuid_string(conn, &run.vm_id)
+                    .map_err(|_| AppError::VmNotFound(run.vm_id.clone()))?;
+                if domain.is_active().map_err(map_libvirt_error)? {
+                    return Err(AppError::InvalidConfig("Shut down the VM before restoring it in place".to_string()));
+                }
+                // The VM may have been renamed since the backup; the UUID identifies it
+                (domain.get_name().map_err(map_libvirt_error)?, run.vm_id.clone())
+            }
+        };
+
+        tracing::info!("Restoring backup {} of {} as {} ({:?})", run.id, run.vm_name, vm_name, request.mode);
+
+        let (pool, pool_dir) = Self::pool_dir(libvirt, &request.pool)?;
+        let suffix = match request.mode {
+            RestoreMode::NewVm => String::new(),
+            RestoreMode::InPlace => format!("-restored-{}", chrono::Local::now().format("%Y%m%d-%H%M%S")),
+        };
+
+        let mut restored = Vec::new();
+        let share = 100.0 / run.disks.len().max(1) as f64;
+        for (index, disk) in run.disks.iter().enumerate() {
+            if let Some(job) = job {
+                job.set_step(format!("Restoring disk {} of {} ({})", index + 1, run.disks.len(), disk.target));
+            }
+            let file_name = format!("{}-{}{}", vm_name, disk.target, suffix);
+            let span = index as f64 * share..(index + 1) as f64 * share;
+            match Self::restore_image(&run, disk, &pool_dir, &file_name, job, span) {
+                Ok(path) => restored.push(RestoredDisk { target: disk.target.clone(), path }),
+                Err(e) => {
+                    Self::remove_restored(&restored);
+                    return Err(e);
+                }
+            }
+        }
+
+        for (disk, image) in run.disks.iter().zip(&restored) {
+            if let Some(element) = xml.disk_element_mut(&disk.target) {
+                point_disk_at(element, &image.path, restored_format(disk));
+            }
+        }
+
+        if let Err(e) = Self::restore_state(&run, &mut xml, &vm_name, &vm_id, &request.mode) {
+            Self::remove_restored(&restored);
+            return Err(e);
+        }
+
+        if request.mode == RestoreMode::NewVm {
+            xml.set_name(&vm_name);
+            xml.set_uuid(Some(&vm_id));
+            VmService::regenerate_mac_addresses(&mut xml);
+        }
+
+        if let Some(job) = job {
+            job.set_step("Defining VM");
+        }
+        if let Err(e) = Domain::define_xml(conn, &xml.to_xml()) {
+            Self::remove_restored(&restored);
+            return Err(AppError::LibvirtError(format!("Failed to define restored VM: {}", e)));
+        }
+        let _ = pool.refresh(0);
+
+        tracing::info!("Restored backup {} as VM {} ({})", run.id, vm_name, vm_id);
+        Ok(RestoreResult { vm_id, vm_name, disks: restored })
+    }
+
+    /// Restore one disk of a backup point into a pool, optionally swapping it into the VM
+    pub fn restore_disk(
+        libvirt: &LibvirtService,
+        request: &RestoreDiskRequest,
+        job: Option<&JobHandle>,
+    ) -> Result<RestoredDisk, AppError> {
+        Self::require_local(libvirt)?;
+        let run = BackupService::new()?.get_backup_point(&request.config_id, &request.run_id, job)?;
+        let disk = run.disks.iter()
+            .find(|disk| disk.target == request.disk)
+            .ok_or_else(|| AppError::InvalidConfig(format!("Disk {} is not part of this backup", request.disk)))?;
+
+        // Check the VM before spending time on the copy
+        let domain = if request.replace {
+            if !request.confirm {
+                return Err(AppError::InvalidConfig(
+                    "Replacing the disk discards the VM's current image; confirm to continue".to_string(),
+                ));
+            }
+            let domain = Domain::lookup_by_uuid_string(libvirt.get_connection(), &run.vm_id)
+                .map_err(|_| AppError::VmNotFound(run.vm_id.clone()))?;
+            if domain.is_active().map_err(map_libvirt_error)? {
+                return Err(AppError::InvalidConfig("Shut down the VM before replacing its disk".to_string()));
+            }
+            Some(domain)
+        } else {
+            None
+        };
+
+        if let Some(job) = job {
+            job.set_step(format!("Restoring disk {}", disk.target));
+        }
+        let (pool, pool_dir) = Self::pool_dir(libvirt, &request.pool)?;
+        let file_name = format!(
+            "{}-{}-restored-{}", run.vm_name, disk.target, chrono::Local::now().format("%Y%m%d-%H%M%S")
+        );
+        let path = Self::restore_image(&run, disk, &pool_dir, &file_name, job, 0.0..100.0)?;
+        let restored = RestoredDisk { target: disk.target.clone(), path };
+
+        if let Some(domain) = domain {
+            let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE | sys::VIR_DOMAIN_XML_SECURE)?;
+            let element = xml.disk_element_mut(&disk.target)
+                .ok_or_else(|| AppError::InvalidConfig(format!("VM no longer has a disk {}", disk.target)))?;
+            point_disk_at(element, &restored.path, restored_format(disk));
+
+            if let Err(e) = Domain::define_xml(libvirt.get_connection(), &xml.to_xml()) {
+                Self::remove_restored(std::slice::from_ref(&restored));
+                return Err(AppError::LibvirtError(format!("Failed to update VM disk: {}", e)));
+            }
+        }
+        let _ = pool.refresh(0);
+
+        tracing::info!("Restored disk {} of backup {} to {}", disk.target, run.id, restored.path);
+        Ok(restored)
+    }
+
+    /// Attach a disk of a backup point read-only to a VM for file-level recovery.
+    ///
+    /// The image is used directly from the backup set, so it stays attached
+    /// only while the VM runs; a stopped VM gets it in its configuration until
+    /// it is detached. Returns the target device it was attached as.
+    pub fn attach_backup_disk(
+        libvirt: &LibvirtService,
+        config_id: &str,
+        run_id: &str,
+        disk: &str,
+        vm_id: &str,
+    ) -> Result<String, AppError> {
+        Self::require_local(libvirt)?;
+        let run = BackupService::new()?.get_backup_point(config_id, run_id, None)?;
+        let backup_disk = run.disks.iter()
+            .find(|d| d.target == disk)
+            .ok_or_else(|| AppError::InvalidConfig(format!("Disk {} is not part of this backup", disk)))?;
+        let path = Path::new(&run.path).join(&backup_disk.file).to_string_lossy().to_string();
+
+        let domain = Domain::lookup_by_uuid_string(libvirt.get_connection(), vm_id)
+            .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
+        let used: Vec<String> = DomainXml::from_domain(&domain)?
+            .disks()
+            .into_iter()
+            .map(|disk| disk.target_dev)
+            .collect();
+        let target = ('b'..='z')
+            .map(|letter| format!("vd{}", letter))
+            .find(|target| !used.contains(target))
+            .ok_or_else(|| AppError::InvalidConfig("No free virtio disk target left".to_string()))?;
+
+        let mut device = Disk::file("disk", &path, "qcow2", &target, "virtio");
+        device.readonly = true;
+
+        let flags = if domain.is_active().map_err(map_libvirt_error)? {
+            sys::VIR_DOMAIN_AFFECT_LIVE
+        } else {
+            sys::VIR_DOMAIN_AFFECT_CONFIG
+        };
+        domain.attach_device_flags(&device.to_element().to_xml(), flags)
+            .map_err(map_libvirt_error)?;
+
+        tracing::info!("Attached backup disk {} to VM {} as {} (read-only)", path, vm_id, target);
+        Ok(target)
+    }
+
+    /// Copy NVRAM and TPM state from the set and point the XML at the copies
+    fn restore_state(run: &BackupRun, xml: &mut DomainXml, vm_name: &str, vm_id: &str, mode: &RestoreMode) -> Result<(), AppError> {
+        let set_dir = Path::new(&run.path);
+
+        if let Some(nvram) = &run.nvram {
+            let mut os = xml.os();
+            let current = os.nvram.as_ref().and_then(|nvram| nvram.path.clone());
+            let destination = match (mode, current) {
+                (RestoreMode::InPlace, Some(path)) => path,
+                _ => format!("{}/{}_VARS.fd", NVRAM_DIR, vm_name),
+            };
+            if *mode == RestoreMode::NewVm && Path::new(&destination).exists() {
+                return Err(AppError::InvalidConfig(format!("NVRAM file {} already exists", destination)));
+            }
+
+            fs::copy(set_dir.join(nvram), &destination)
+                .map_err(|e| AppError::Other(format!("Failed to restore NVRAM to {}: {}", destination, e)))?;
+            if let Some(os_nvram) = os.nvram.as_mut() {
+                os_nvram.path = Some(destination);
+                xml.set_os(&os);
+            }
+        }
+
+        if run.tpm {
+            let destination = Path::new(backup_service::SWTPM_STATE_DIR).join(vm_id);
+            if destination.exists() {
+                fs::remove_dir_all(&destination)
+                    .map_err(|e| AppError::Other(format!("Failed to replace TPM state: {}", e)))?;
+            }
+            backup_service::copy_dir(&set_dir.join("tpm"), &destination)?;
+        }
+
+        Ok(())
+    }
+
+    /// Flatten a backed-up disk into `<dir>/<file_name>.<ext>` in its original format
+    fn restore_image(
+        run: &BackupRun,
+        disk: &BackupDisk,
+        dir: &Path,
+        file_name: &str,
+        job: Option<&JobHandle>,
+        span: std::ops::Range<f64>,
+    ) -> Result<String, AppError> {
+        let format = restored_format(disk);
+        let extension = if format == "raw" { "img" } else { format };
+        let destination = dir.join(format!("{}.{}", file_name, extension));
+        if destination.exists() {
+            return Err(AppError::InvalidConfig(format!("{} already exists", destination.display())));
+        }
+
+        let source = Path::new(&run.path).join(&disk.file).to_string_lossy().to_string();
+        let destination = destination.to_string_lossy().to_string();
+        if let Err(e) = job_service::run_qemu_img(job, &["convert", "-f", "qcow2", "-O", format, &source, &destination], span) {
+            let _ = fs::remove_file(&destination);
+            return Err(e);
+        }
+        Ok(destination)
+    }
+
+    /// Look up a pool and the directory its volumes live in
+    fn pool_dir(libvirt: &LibvirtService, name: &str) -> Result<(StoragePool, PathBuf), AppError> {
+        let pool = StoragePool::lookup_by_name(libvirt.get_connection(), name)
+            .map_err(|_| AppError::InvalidConfig(format!("Storage pool not found: {}", name)))?;
+        let pool_xml = pool.get_xml_desc(0).map_err(map_libvirt_error)?;
+        let path = XmlElement::parse(&pool_xml)?
+            .find("target/path")
+            .map(|path| PathBuf::from(path.text()))
+            .filter(|path| path.is_dir())
+            .ok_or_else(|| AppError::InvalidConfig(format!("Storage pool {} is not a local directory", name)))?;
+        Ok((pool, path))
+    }
+
+    fn require_local(libvirt: &LibvirtService) -> Result<(), AppError> {
+        if libvirt.is_local() {
+            Ok(())
+        } else {
+            Err(AppError::Other(format!("Backups can only be restored on the local host, not {}", libvirt.get_uri())))
+        }
+    }
+
+    fn remove_restored(disks: &[RestoredDisk]) {
+        for disk in disks {
+            if let Err(e) = fs::remove_file(&disk.path) {
+                tracing::warn!("Failed to remove restored image {}: {}", disk.path, e);
+            }
+        }
+    }
+}
+
+/// Format a disk is restored in: the format it had when backed up
+fn restored_format(disk: &BackupDisk) -> &str {
+    match disk.source_format.as_deref() {
+        Some("raw") => "raw",
+        _ => "qcow2",
+    }
+}
+
+/// Point a disk element at a local image file, dropping any backing chain it had
+pub(crate) fn point_disk_at(element: &mut XmlElement, path: &str, format: &str) {
+    let mut disk = Disk::from_element(element);
+    disk.disk_type = "file".to_string();
+    disk.source = Some(DiskSource {
+        file: Some(path.to_string()),
+        ..Default::default()
+    });
+    let mut driver = disk.driver.take().unwrap_or_default();
+    driver.name.get_or_insert_with(|| "qemu".to_string());
+    driver.format = Some(format.to_string());
+    disk.driver = Some(driver);
+    disk.apply_to(element);
+    element.remove_children("backingStore");
+}
@@ -1,7 +1,43 @@
+use crate::models::event::{BackendEvent, TaskFailure, TaskKind};
+use crate::models::snapshot::{SnapshotConfig, SnapshotLocation};
+use crate::models::quiesce::QuiesceOptions;
+use crate::services::backup_service::{BackupService, BACKUP_CONNECTION};
+use crate::services::backup_verify_service::{BackupVerifyService, VerifyOptions};
+use crate::services::connection_service::ConnectionService;
+use crate::services::event_service::EventService;
+use crate::services::guest_agent_service::GuestAgentService;
+use crate::services::job_service::JobService;
+use crate::services::libvirt::LibvirtService;
+use crate::services::snapshot_service::SnapshotService;
+use crate::services::vm_service::VmService;
 use crate::utils::error::AppError;
+use chrono::{DateTime, TimeZone, Utc};
+use croner::Cron;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Tauri event emitted with a `ScheduleRun` after every executed schedule
+pub const SCHEDULE_RUN_EVENT: &str = "schedule-run";
+
+/// How often the executor looks for due schedules
+const EXECUTOR_INTERVAL_SECS: u64 = 30;
+
+/// Number of runs kept per schedule
+const RUN_HISTORY_LIMIT: usize = 50;
+
+/// A run that fell due this long before the executor was last watching was missed
+/// (app closed, host asleep) rather than just picked up late
+const MISSED_RUN_GRACE_SECS: i64 = 120;
+
+/// Upper bound on missed runs replayed by `MissedRunPolicy::RunAll`
+const MISSED_RUN_LIMIT: usize = 100;
+
+/// How long a scheduled guest command may run before the run fails
+const GUEST_COMMAND_TIMEOUT: Duration = Duration::from_secs(600);
 
 /// Schedule frequency type
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -11,16 +47,165 @@ pub enum ScheduleFrequency {
     Daily,
     Weekly,
     Monthly,
+    /// Standard 5-field cron expression in `cron_expression`
+    Cron,
+}
+
+/// What to do with runs that fell due while the application was not running
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum MissedRunPolicy {
+    /// Drop missed runs and wait for the next occurrence
+    Skip,
+    /// Run once to catch up, however many runs were missed
+    #[default]
+    RunOnce,
+    /// Replay every missed run in order
+    RunAll,
 }
 
 /// Scheduled operation type
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "camelCase")]
 pub enum OperationType {
     Start,
     Stop,
     Reboot,
     Snapshot,
+    /// Managed save
+    Hibernate,
+    GuestCommand,
+    SetResources,
+    PruneSnapshots,
+    Migrate,
+    /// Full backup of a backup config
+    Backup,
+    /// Verify the newest restore point of a backup config
+    VerifyBackup,
+}
+
+/// Parameters of operation types that need them; the variant must match the operation
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum OperationParams {
+    GuestCommand(GuestCommandParams),
+    SetResources(SetResourcesParams),
+    PruneSnapshots(PruneSnapshotsParams),
+    Migrate(MigrateParams),
+    Backup(BackupParams),
+    Snapshot(SnapshotParams),
+    VerifyBackup(VerifyBackupParams),
+}
+
+/// Command run through the guest agent
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GuestCommandParams {
+    /// Absolute path of the program inside the guest
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// Optional settings of scheduled snapshots
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotParams {
+    #[serde(default)]
+    pub quiesce: QuiesceOptions,
+    #[serde(default)]
+    pub location: SnapshotLocation,
+}
+
+/// New vCPU count and/or memory size
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetResourcesParams {
+    pub vcpus: Option<u32>,
+    pub memory_mb: Option<u64>,
+}
+
+/// Delete old scheduled snapshots whose name starts with `name_prefix`, keeping the newest `keep_last`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneSnapshotsParams {
+    pub keep_last: u32,
+    /// Only snapshots with this prefix are pruned; it must start with
+    /// `SCHEDULED_SNAPSHOT_PREFIX` so manual snapshots survive
+    #[serde(default = "default_snapshot_prefix")]
+    pub name_prefix: String,
+}
+
+/// Migrate to the host of a saved connection
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrateParams {
+    pub target_connection_id: String,
+    #[serde(default)]
+    pub live: bool,
+    #[serde(default)]
+    pub unsafe_migration: bool,
+}
+
+/// Backup config whose VM is backed up to its target directory
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupParams {
+    pub backup_config_id: String,
+}
+
+/// Backup config whose newest restore point is verified
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyBackupParams {
+    pub backup_config_id: String,
+    #[serde(default)]
+    pub options: VerifyOptions,
+}
+
+/// Name prefix of snapshots created by scheduled `Snapshot` operations.
+///
+/// Snapshot retention policies only ever prune snapshots carrying it, so
+/// manual snapshots may not use it.
+pub const SCHEDULED_SNAPSHOT_PREFIX: &str = "scheduled-";
+
+fn default_snapshot_prefix() -> String {
+    SCHEDULED_SNAPSHOT_PREFIX.to_string()
+}
+
+/// Selects the VMs a schedule operates on by tag, resolved each time it fires
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TagSelector {
+    pub tags: Vec<String>,
+    /// Require every tag instead of any one of them
+    #[serde(default)]
+    pub match_all: bool,
+}
+
+impl TagSelector {
+    /// Whether a VM with `vm_tags` is selected
+    pub fn matches(&self, vm_tags: &[String]) -> bool {
+        if self.match_all {
+            self.tags.iter().all(|tag| vm_tags.contains(tag))
+        } else {
+            self.tags.iter().any(|tag| vm_tags.contains(tag))
+        }
+    }
+}
+
+fn default_parallelism() -> u32 {
+    1
+}
+
+/// Outcome of a schedule run
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleRunStatus {
+    Success,
+    Failed,
+    /// Missed run dropped by `MissedRunPolicy::Skip`
+    Skipped,
 }
 
 /// Scheduled operation
@@ -29,29 +214,119 @@ pub enum OperationType {
 pub struct ScheduledOperation {
     pub id: String,
     pub name: String,
+    /// VM to operate on; empty when `tag_selector` is set
     pub vm_id: String,
+    /// Saved connection the VM lives on; `None` targets the active connection,
+    /// or every connected host for tag schedules
+    #[serde(default)]
+    pub connection_id: Option<String>,
+    #[serde(default)]
+    pub tag_selector: Option<TagSelector>,
+    /// Maximum number of VMs a tag schedule operates on at once
+    #[serde(default = "default_parallelism")]
+    pub parallelism: u32,
+    /// Delay between starting the operation on consecutive VMs of a tag schedule
+    #[serde(default)]
+    pub stagger_secs: u64,
     pub operation: OperationType,
+    #[serde(default)]
+    pub params: Option<OperationParams>,
     pub frequency: ScheduleFrequency,
     pub scheduled_time: String, // Format: "HH:MM" for time of day
     pub day_of_week: Option<u8>, // 0-6 for weekly (0=Sunday)
-    pub day_of_month: Option<u8>, // 1-31 for monthly
+    pub day_of_month: Option<u8>, // 1-28 for monthly
+    #[serde(default)]
+    pub cron_expression: Option<String>,
+    /// IANA timezone the schedule is evaluated in; `None` uses the system timezone
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub missed_run_policy: MissedRunPolicy,
     pub enabled: bool,
     pub last_run: Option<i64>,
+    #[serde(default)]
+    pub last_status: Option<ScheduleRunStatus>,
     pub next_run: i64,
     pub created_at: i64,
 }
 
+/// Result of a tag schedule's operation on one VM
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VmRunResult {
+    pub vm_id: String,
+    pub vm_name: String,
+    pub connection_id: Option<String>,
+    pub status: ScheduleRunStatus,
+    pub message: String,
+}
+
+/// Result of one execution of a scheduled operation
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleRun {
+    pub id: String,
+    pub schedule_id: String,
+    pub schedule_name: String,
+    pub vm_id: String,
+    pub operation: OperationType,
+    pub status: ScheduleRunStatus,
+    /// What was done, or the error for a failed run
+    pub message: String,
+    /// Per-VM results of a tag schedule
+    #[serde(default)]
+    pub vm_results: Vec<VmRunResult>,
+    /// Occurrence the run belongs to; earlier than `started_at` for missed runs
+    #[serde(default)]
+    pub scheduled_for: i64,
+    pub started_at: i64,
+    pub finished_at: i64,
+}
+
 /// Request to create a scheduled operation
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateScheduleRequest {
     pub name: String,
+    #[serde(default)]
     pub vm_id: String,
+    #[serde(default)]
+    pub connection_id: Option<String>,
+    #[serde(default)]
+    pub tag_selector: Option<TagSelector>,
+    #[serde(default = "default_parallelism")]
+    pub parallelism: u32,
+    #[serde(default)]
+    pub stagger_secs: u64,
     pub operation: OperationType,
+    #[serde(default)]
+    pub params: Option<OperationParams>,
     pub frequency: ScheduleFrequency,
+    #[serde(default)]
     pub scheduled_time: String,
     pub day_of_week: Option<u8>,
     pub day_of_month: Option<u8>,
+    #[serde(default)]
+    pub cron_expression: Option<String>,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub missed_run_policy: MissedRunPolicy,
+}
+
+/// Services a schedule run uses besides the target's connection
+struct RunContext {
+    connections: Arc<ConnectionService>,
+    events: Arc<EventService>,
+    jobs: Arc<JobService>,
+}
+
+/// A VM a schedule run operates on
+struct RunTarget {
+    connection_id: Option<String>,
+    libvirt: Arc<LibvirtService>,
+    vm_id: String,
+    vm_name: String,
 }
 
 /// Scheduler service
@@ -81,31 +356,56 @@ impl SchedulerService {
         Ok(config_dir.join("kvm-manager").join("schedules"))
     }
 
+    /// Path of the run log for a schedule
+    fn runs_path(&self, id: &str) -> PathBuf {
+        self.schedules_dir.join("runs").join(format!("{}.json", id))
+    }
+
     /// Create a new scheduled operation
     pub fn create_schedule(&self, request: CreateScheduleRequest) -> Result<ScheduledOperation, AppError> {
+        match &request.tag_selector {
+            Some(selector) if selector.tags.iter().all(|t| t.trim().is_empty()) => {
+                return Err(AppError::ScheduleError("Tag selector needs at least one tag".to_string()));
+            }
+            Some(_) => {}
+            None if request.vm_id.is_empty() => {
+                return Err(AppError::ScheduleError("Either vm_id or tag_selector is required".to_string()));
+            }
+            None => {}
+        }
+        if request.parallelism == 0 {
+            return Err(AppError::ScheduleError("Parallelism must be at least 1".to_string()));
+        }
+        Self::validate_params(&request.operation, request.params.as_ref())?;
+
         let id = uuid::Uuid::new_v4().to_string();
         let now = chrono::Utc::now().timestamp();
-        let next_run = self.calculate_next_run(
-            &request.frequency,
-            &request.scheduled_time,
-            request.day_of_week,
-            request.day_of_month,
-        )?;
-
-        let schedule = ScheduledOperation {
+
+        let mut schedule = ScheduledOperation {
             id: id.clone(),
             name: request.name,
             vm_id: request.vm_id,
+            connection_id: request.connection_id,
+            tag_selector: request.tag_selector,
+            parallelism: request.parallelism,
+            stagger_secs: request.stagger_secs,
             operation: request.operation,
+            params: request.params,
             frequency: request.frequency,
             scheduled_time: request.scheduled_time,
             day_of_week: request.day_of_week,
             day_of_month: request.day_of_month,
+            cron_expression: request.cron_expression,
+            timezone: request.timezone,
+            missed_run_policy: request.missed_run_policy,
             enabled: true,
             last_run: None,
-            next_run,
+            last_status: None,
+            next_run: 0,
             created_at: now,
         };
+        // Also validates the time spec, cron expression and timezone
+        schedule.next_run = self.calculate_next_run(&schedule, now)?;
 
         // Save schedule to file
         self.save_schedule(&schedule)?;
@@ -113,6 +413,64 @@ impl SchedulerService {
         Ok(schedule)
     }
 
+    /// Check that `params` is the payload `operation` requires, and that its values are usable
+    fn validate_params(operation: &OperationType, params: Option<&OperationParams>) -> Result<(), AppError> {
+        let invalid = |message: &str| Err(AppError::ScheduleError(message.to_string()));
+
+        match (operation, params) {
+            (OperationType::GuestCommand, Some(OperationParams::GuestCommand(p))) => {
+                if p.command.trim().is_empty() {
+                    return invalid("Guest command must not be empty");
+                }
+            }
+            (OperationType::SetResources, Some(OperationParams::SetResources(p))) => {
+                if p.vcpus.is_none() && p.memory_mb.is_none() {
+                    return invalid("Set vcpus, memory_mb or both");
+                }
+                if p.vcpus == Some(0) {
+                    return invalid("vCPU count must be at least 1");
+                }
+                if p.memory_mb.is_some_and(|mb| mb < 128) {
+                    return invalid("Memory must be at least 128 MB");
+                }
+            }
+            (OperationType::PruneSnapshots, Some(OperationParams::PruneSnapshots(p))) => {
+                if !p.name_prefix.starts_with(SCHEDULED_SNAPSHOT_PREFIX) {
+                    return Err(AppError::ScheduleError(format!(
+                        "Snapshot name prefix must start with '{}' so manual snapshots are never pruned",
+                        SCHEDULED_SNAPSHOT_PREFIX
+                    )));
+                }
+            }
+            (OperationType::Migrate, Some(OperationParams::Migrate(p))) => {
+                if p.target_connection_id.is_empty() {
+                    return invalid("Target connection is required");
+                }
+            }
+            (OperationType::Backup, Some(OperationParams::Backup(p))) => {
+                if p.backup_config_id.is_empty() {
+                    return invalid("Backup config is required");
+                }
+            }
+            (OperationType::VerifyBackup, Some(OperationParams::VerifyBackup(p))) => {
+                if p.backup_config_id.is_empty() {
+                    return invalid("Backup config is required");
+                }
+            }
+            (OperationType::Snapshot, None | Some(OperationParams::Snapshot(_))) => {}
+            (OperationType::GuestCommand | OperationType::SetResources | OperationType::PruneSnapshots | OperationType::Migrate
+                | OperationType::Backup | OperationType::VerifyBackup, _) => {
+                return Err(AppError::ScheduleError(format!("{:?} requires matching parameters", operation)));
+            }
+            (_, Some(_)) => {
+                return Err(AppError::ScheduleError(format!("{:?} takes no parameters", operation)));
+            }
+            (_, None) => {}
+        }
+
+        Ok(())
+    }
+
     /// List all schedules
     pub fn list_schedules(&self) -> Result<Vec<ScheduledOperation>, AppError> {
         let mut schedules = Vec::new();
@@ -162,12 +520,7 @@ impl SchedulerService {
 
         // Recalculate next run if enabling
         if enabled {
-            schedule.next_run = self.calculate_next_run(
-                &schedule.frequency,
-                &schedule.scheduled_time,
-                schedule.day_of_week,
-                schedule.day_of_month,
-            )?;
+            schedule.next_run = self.calculate_next_run(&schedule, chrono::Utc::now().timestamp())?;
         }
 
         self.save_schedule(&schedule)?;
@@ -185,106 +538,631 @@ impl SchedulerService {
         fs::remove_file(&path)
             .map_err(|e| AppError::ScheduleError(format!("Failed to delete schedule: {}", e)))?;
 
+        let runs_path = self.runs_path(id);
+        if runs_path.exists() {
+            if let Err(e) = fs::remove_file(&runs_path) {
+                tracing::warn!("Failed to delete run log for schedule {}: {}", id, e);
+            }
+        }
+
         Ok(())
     }
 
+    /// Get the run log of a schedule, newest first
+    pub fn get_schedule_runs(&self, id: &str) -> Result<Vec<ScheduleRun>, AppError> {
+        let path = self.runs_path(id);
+
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = fs::read_to_string(&path)
+            .map_err(|e| AppError::ScheduleError(format!("Failed to read run log: {}", e)))?;
+
+        serde_json::from_str(&json)
+            .map_err(|e| AppError::ScheduleError(format!("Failed to parse run log: {}", e)))
+    }
+
     /// Get schedules for a specific VM
     pub fn get_vm_schedules(&self, vm_id: &str) -> Result<Vec<ScheduledOperation>, AppError> {
         let schedules = self.list_schedules()?;
         Ok(schedules.into_iter().filter(|s| s.vm_id == vm_id).collect())
     }
 
-    /// Calculate next run timestamp
-    fn calculate_next_run(
-        &self,
-        frequency: &ScheduleFrequency,
-        scheduled_time: &str,
-        day_of_week: Option<u8>,
-        day_of_month: Option<u8>,
-    ) -> Result<i64, AppError> {
-        use chrono::{Datelike, Duration, NaiveTime};
-
-        // Parse time
-        let parts: Vec<&str> = scheduled_time.split(':').collect();
-        if parts.len() != 2 {
-            return Err(AppError::ScheduleError("Invalid time format. Use HH:MM".to_string()));
-        }
+    /// Start the background executor that fires due schedules
+    pub fn start_executor_task(self: Arc<Self>, connections: Arc<ConnectionService>, events: Arc<EventService>, jobs: Arc<JobService>) {
+        tracing::info!("Starting schedule executor task");
+        let context = Arc::new(RunContext { connections, events, jobs });
 
-        let hour: u32 = parts[0].parse()
-            .map_err(|_| AppError::ScheduleError("Invalid hour".to_string()))?;
-        let minute: u32 = parts[1].parse()
-            .map_err(|_| AppError::ScheduleError("Invalid minute".to_string()))?;
+        tauri::async_runtime::spawn(async move {
+            // Start of the previous pass; runs falling due since then were only
+            // delayed by the sequential pass, not missed
+            let mut watched_since = chrono::Utc::now().timestamp();
+            let mut last_pass_end = watched_since;
+            loop {
+                tokio::time::sleep(tokio::time::Duration::from_secs(EXECUTOR_INTERVAL_SECS)).await;
 
-        let scheduled = NaiveTime::from_hms_opt(hour, minute, 0)
-            .ok_or_else(|| AppError::ScheduleError("Invalid time".to_string()))?;
+                let tick = chrono::Utc::now().timestamp();
+                // A longer gap than the interval means the host slept through it
+                if tick - last_pass_end > EXECUTOR_INTERVAL_SECS as i64 + MISSED_RUN_GRACE_SECS {
+                    watched_since = tick;
+                }
 
-        let now = chrono::Local::now();
-        let today = now.date_naive();
-        let current_time = now.time();
+                // Operations talk to libvirt, so run the whole pass off the async runtime
+                let scheduler = self.clone();
+                let context = context.clone();
+                let pass = tauri::async_runtime::spawn_blocking(move || {
+                    scheduler.run_due_schedules(&context, tick, watched_since)
+                }).await;
 
-        let next_run = match frequency {
-            ScheduleFrequency::Once => {
-                // If time has passed today, schedule for tomorrow
-                let mut target_date = today;
-                if current_time > scheduled {
-                    target_date = today + Duration::days(1);
+                if let Err(e) = pass {
+                    tracing::error!("Schedule executor pass failed: {}", e);
                 }
-                target_date.and_time(scheduled)
+                watched_since = tick;
+                last_pass_end = chrono::Utc::now().timestamp();
             }
-            ScheduleFrequency::Daily => {
-                // Schedule for today if time hasn't passed, otherwise tomorrow
-                let mut target_date = today;
-                if current_time > scheduled {
-                    target_date = today + Duration::days(1);
+        });
+    }
+
+    /// Execute every enabled schedule whose `next_run` had passed when the tick started.
+    ///
+    /// Runs due since `watched_since` run normally however long earlier runs of
+    /// the pass took; older ones go through the schedule's missed-run policy.
+    fn run_due_schedules(&self, context: &RunContext, now: i64, watched_since: i64) {
+        let schedules = match self.list_schedules() {
+            Ok(schedules) => schedules,
+            Err(e) => {
+                tracing::error!("Failed to load schedules: {}", e);
+                return;
+            }
+        };
+
+        for schedule in schedules.into_iter().filter(|s| s.enabled && s.next_run <= now) {
+            for run in self.fire_schedule(&schedule, context, now, watched_since) {
+                if run.status == ScheduleRunStatus::Failed {
+                    context.events.publish(BackendEvent::TaskFailed(self.task_failure(&run)));
                 }
-                target_date.and_time(scheduled)
+                context.events.emit(SCHEDULE_RUN_EVENT, run);
             }
-            ScheduleFrequency::Weekly => {
-                let target_weekday = day_of_week
-                    .ok_or_else(|| AppError::ScheduleError("day_of_week required for weekly schedules".to_string()))?;
+        }
+    }
 
-                let current_weekday = today.weekday().num_days_from_sunday() as u8;
-                let days_until_target = if target_weekday >= current_weekday {
-                    (target_weekday - current_weekday) as i64
-                } else {
-                    (7 - current_weekday + target_weekday) as i64
+    /// Describe a failed run for the alert engine; backup and verification
+    /// schedules report as their backup config
+    fn task_failure(&self, run: &ScheduleRun) -> TaskFailure {
+        let vm_id = Some(run.vm_id.clone()).filter(|id| !id.is_empty());
+        if run.operation == OperationType::VerifyBackup {
+            let config = self.get_schedule(&run.schedule_id).ok()
+                .and_then(|schedule| match schedule.params {
+                    Some(OperationParams::VerifyBackup(params)) => Some(params.backup_config_id),
+                    _ => None,
+                })
+                .and_then(|config_id| BackupService::new().and_then(|service| service.get_backup_config(&config_id)).ok());
+            if let Some(config) = config {
+                return TaskFailure {
+                    kind: TaskKind::BackupVerify,
+                    id: config.id,
+                    name: config.name,
+                    vm_id: vm_id.or(Some(config.vm_id)),
+                    error: run.message.clone(),
                 };
+            }
+        }
 
-                let mut target_date = today + Duration::days(days_until_target);
+        let backup = BackupService::new()
+            .and_then(|service| service.list_backup_configs())
+            .ok()
+            .and_then(|configs| configs.into_iter().find(|c| c.schedule_id == run.schedule_id));
 
-                // If it's the same day but time has passed, add a week
-                if days_until_target == 0 && current_time > scheduled {
-                    target_date = target_date + Duration::days(7);
+        match backup {
+            Some(config) => TaskFailure {
+                kind: TaskKind::Backup,
+                id: config.id,
+                name: config.name,
+                vm_id,
+                error: run.message.clone(),
+            },
+            None => TaskFailure {
+                kind: TaskKind::Schedule,
+                id: run.schedule_id.clone(),
+                name: run.schedule_name.clone(),
+                vm_id,
+                error: run.message.clone(),
+            },
+        }
+    }
+
+    /// Run a due schedule, applying its missed-run policy when its window has passed,
+    /// then advance it to the next occurrence
+    fn fire_schedule(&self, schedule: &ScheduledOperation, context: &RunContext, now: i64, watched_since: i64) -> Vec<ScheduleRun> {
+        let runs = if schedule.next_run >= watched_since - MISSED_RUN_GRACE_SECS {
+            vec![self.execute_schedule(schedule, schedule.next_run, context)]
+        } else {
+            let missed = self.missed_occurrences(schedule, now);
+            let latest = missed.last().copied().unwrap_or(schedule.next_run);
+            tracing::warn!(
+                "Schedule '{}' missed {} run(s), policy {:?}",
+                schedule.name, missed.len(), schedule.missed_run_policy
+            );
+
+            match schedule.missed_run_policy {
+                MissedRunPolicy::Skip => vec![self.skip_missed_runs(schedule, missed.len(), latest)],
+                MissedRunPolicy::RunOnce => vec![self.execute_schedule(schedule, latest, context)],
+                MissedRunPolicy::RunAll => missed.iter()
+                    .map(|&scheduled_for| self.execute_schedule(schedule, scheduled_for, context))
+                    .collect(),
+            }
+        };
+
+        if let Some(last) = runs.last() {
+            if let Err(e) = self.advance_schedule(&schedule.id, last) {
+                tracing::error!("Failed to update schedule '{}' after run: {}", schedule.name, e);
+            }
+        }
+
+        runs
+    }
+
+    /// Occurrences from `next_run` up to `now`, oldest first
+    fn missed_occurrences(&self, schedule: &ScheduledOperation, now: i64) -> Vec<i64> {
+        let mut missed = vec![schedule.next_run];
+        if schedule.frequency == ScheduleFrequency::Once {
+            return missed;
+        }
+
+        let mut last = schedule.next_run;
+        while missed.len() < MISSED_RUN_LIMIT {
+            match self.calculate_next_run(schedule, last) {
+                Ok(next) if next <= now => {
+                    missed.push(next);
+                    last = next;
                 }
+                _ => break,
+            }
+        }
+
+        missed
+    }
 
-                target_date.and_time(scheduled)
+    /// Record that missed runs were dropped
+    fn skip_missed_runs(&self, schedule: &ScheduledOperation, count: usize, latest: i64) -> ScheduleRun {
+        let now = chrono::Utc::now().timestamp();
+        let run = ScheduleRun {
+            id: uuid::Uuid::new_v4().to_string(),
+            schedule_id: schedule.id.clone(),
+            schedule_name: schedule.name.clone(),
+            vm_id: schedule.vm_id.clone(),
+            operation: schedule.operation.clone(),
+            status: ScheduleRunStatus::Skipped,
+            message: format!("Skipped {} run(s) missed while the application was not running", count),
+            vm_results: Vec::new(),
+            scheduled_for: latest,
+            started_at: now,
+            finished_at: now,
+        };
+
+        if let Err(e) = self.record_run(&run) {
+            tracing::error!("Failed to record skipped runs of schedule '{}': {}", schedule.name, e);
+        }
+
+        run
+    }
+
+    /// Run a schedule's operation for one occurrence and record the result
+    fn execute_schedule(&self, schedule: &ScheduledOperation, scheduled_for: i64, context: &RunContext) -> ScheduleRun {
+        tracing::info!("Executing schedule '{}' ({:?})", schedule.name, schedule.operation);
+
+        let started_at = chrono::Utc::now().timestamp();
+        let (status, message, vm_results) = match Self::resolve_targets(schedule, &context.connections) {
+            Err(e) => (ScheduleRunStatus::Failed, e.to_string(), Vec::new()),
+            Ok(targets) if schedule.tag_selector.is_none() => {
+                // Single-VM schedules report the result directly
+                let result = Self::run_targets(schedule, targets, context).pop();
+                match result {
+                    Some(result) => (result.status, result.message, Vec::new()),
+                    None => (ScheduleRunStatus::Failed, "No target VM".to_string(), Vec::new()),
+                }
             }
-            ScheduleFrequency::Monthly => {
-                let target_day = day_of_month
-                    .ok_or_else(|| AppError::ScheduleError("day_of_month required for monthly schedules".to_string()))?;
+            Ok(targets) if targets.is_empty() => {
+                (ScheduleRunStatus::Success, "No VMs matched the tag selector".to_string(), Vec::new())
+            }
+            Ok(targets) => {
+                let results = Self::run_targets(schedule, targets, context);
+                let failed = results.iter().filter(|r| r.status == ScheduleRunStatus::Failed).count();
+                let status = if failed == 0 { ScheduleRunStatus::Success } else { ScheduleRunStatus::Failed };
+                let message = format!("{} of {} VMs succeeded", results.len() - failed, results.len());
+                (status, message, results)
+            }
+        };
+
+        if status == ScheduleRunStatus::Failed {
+            tracing::error!("Schedule '{}' failed: {}", schedule.name, message);
+        }
+
+        let run = ScheduleRun {
+            id: uuid::Uuid::new_v4().to_string(),
+            schedule_id: schedule.id.clone(),
+            schedule_name: schedule.name.clone(),
+            vm_id: schedule.vm_id.clone(),
+            operation: schedule.operation.clone(),
+            status,
+            message,
+            vm_results,
+            scheduled_for,
+            started_at,
+            finished_at: chrono::Utc::now().timestamp(),
+        };
+
+        if let Err(e) = self.record_run(&run) {
+            tracing::error!("Failed to record run of schedule '{}': {}", schedule.name, e);
+        }
+
+        run
+    }
+
+    /// Resolve the VMs a run operates on; tag selectors are matched against the current inventory
+    fn resolve_targets(schedule: &ScheduledOperation, connections: &ConnectionService) -> Result<Vec<RunTarget>, AppError> {
+        let Some(selector) = &schedule.tag_selector else {
+            return Ok(vec![RunTarget {
+                connection_id: schedule.connection_id.clone(),
+                libvirt: connections.get_libvirt(schedule.connection_id.as_deref())?,
+                vm_id: schedule.vm_id.clone(),
+                vm_name: schedule.vm_id.clone(),
+            }]);
+        };
+
+        let hosts = match &schedule.connection_id {
+            Some(id) => vec![(id.clone(), connections.get_libvirt(Some(id))?)],
+            None => connections.get_connected()?,
+        };
+
+        let mut targets = Vec::new();
+        for (connection_id, libvirt) in hosts {
+            let vms = match VmService::list_vms(&libvirt) {
+                Ok(vms) => vms,
+                Err(e) => {
+                    tracing::warn!("Schedule '{}': failed to list VMs on {}: {}", schedule.name, connection_id, e);
+                    continue;
+                }
+            };
+
+            for vm in vms.into_iter().filter(|vm| selector.matches(&vm.tags)) {
+                targets.push(RunTarget {
+                    connection_id: Some(connection_id.clone()),
+                    libvirt: libvirt.clone(),
+                    vm_id: vm.id,
+                    vm_name: vm.name,
+                });
+            }
+        }
+
+        Ok(targets)
+    }
+
+    /// Run the operation on every target, at most `parallelism` at a time and
+    /// starting consecutive VMs `stagger_secs` apart. Results keep target order.
+    fn run_targets(schedule: &ScheduledOperation, targets: Vec<RunTarget>, context: &RunContext) -> Vec<VmRunResult> {
+        let workers = (schedule.parallelism.max(1) as usize).min(targets.len());
+        let stagger = Duration::from_secs(schedule.stagger_secs);
+        let next_index = AtomicUsize::new(0);
+        let next_start = Mutex::new(Instant::now());
+        let results: Mutex<Vec<Option<VmRunResult>>> = Mutex::new(vec![None; targets.len()]);
 
-                let current_day = today.day() as u8;
-                let mut target_date = today;
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let index = next_index.fetch_add(1, Ordering::SeqCst);
+                    let Some(target) = targets.get(index) else { break };
 
-                if target_day < current_day || (target_day == current_day && current_time > scheduled) {
-                    // Next month
-                    let next_month = if today.month() == 12 {
-                        today.with_year(today.year() + 1).and_then(|d| d.with_month(1))
-                    } else {
-                        today.with_month(today.month() + 1)
+                    // Hold the slot while waiting so starts stay in order and spaced out
+                    if let Ok(mut next_start) = next_start.lock() {
+                        let now = Instant::now();
+                        if *next_start > now {
+                            std::thread::sleep(*next_start - now);
+                        }
+                        *next_start = Instant::now() + stagger;
+                    }
+
+                    let (status, message) = match Self::run_operation(schedule, target, context) {
+                        Ok(message) => (ScheduleRunStatus::Success, message),
+                        Err(e) => (ScheduleRunStatus::Failed, e.to_string()),
                     };
-                    target_date = next_month.ok_or_else(|| AppError::ScheduleError("Failed to calculate next month".to_string()))?;
+
+                    if let Ok(mut results) = results.lock() {
+                        results[index] = Some(VmRunResult {
+                            vm_id: target.vm_id.clone(),
+                            vm_name: target.vm_name.clone(),
+                            connection_id: target.connection_id.clone(),
+                            status,
+                            message,
+                        });
+                    }
+                });
+            }
+        });
+
+        results.into_inner()
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Perform the scheduled operation on one VM, returning a short description of what was done
+    fn run_operation(schedule: &ScheduledOperation, target: &RunTarget, context: &RunContext) -> Result<String, AppError> {
+        let libvirt = target.libvirt.as_ref();
+        let vm_id = target.vm_id.as_str();
+
+        match (&schedule.operation, &schedule.params) {
+            (OperationType::Start, _) => {
+                VmService::start_vm(libvirt, vm_id)?;
+                Ok("VM started".to_string())
+            }
+            (OperationType::Stop, _) => {
+                VmService::stop_vm(libvirt, vm_id)?;
+                Ok("Shutdown requested".to_string())
+            }
+            (OperationType::Reboot, _) => {
+                VmService::reboot_vm(libvirt, vm_id)?;
+                Ok("Reboot requested".to_string())
+            }
+            (OperationType::Snapshot, params) => {
+                // Schedules firing in the same second must not collide on one VM
+                let name = format!(
+                    "{}{}-{}",
+                    SCHEDULED_SNAPSHOT_PREFIX,
+                    chrono::Local::now().format("%Y%m%d-%H%M%S"),
+                    &uuid::Uuid::new_v4().simple().to_string()[..8],
+                );
+                let (quiesce, location) = match params {
+                    Some(OperationParams::Snapshot(params)) => (params.quiesce.clone(), params.location),
+                    _ => (QuiesceOptions::default(), SnapshotLocation::default()),
+                };
+                let config = SnapshotConfig {
+                    name: name.clone(),
+                    description: Some(format!("Created by schedule '{}'", schedule.name)),
+                    include_memory: false,
+                    quiesce,
+                    location,
+                };
+                SnapshotService::create_snapshot(libvirt, vm_id, config)?;
+                Ok(format!("Created snapshot {}", name))
+            }
+            (OperationType::Hibernate, _) => {
+                VmService::hibernate_vm(libvirt, vm_id)?;
+                Ok("VM state saved".to_string())
+            }
+            (OperationType::GuestCommand, Some(OperationParams::GuestCommand(params))) => {
+                // The guest agent addresses domains by name
+                let vm_name = VmService::get_vm(libvirt, vm_id)?.name;
+                let result = GuestAgentService::new()
+                    .exec_and_wait(Some(libvirt.get_uri()), &vm_name, &params.command, &params.args, GUEST_COMMAND_TIMEOUT)
+                    .map_err(|e| AppError::Other(format!("Guest agent error: {}", e)))?;
+
+                if result.exit_code != 0 {
+                    return Err(AppError::Other(format!(
+                        "{} exited with code {}: {}",
+                        params.command, result.exit_code, result.stderr.trim()
+                    )));
                 }
+                Ok(format!("{} exited with code 0", params.command))
+            }
+            (OperationType::SetResources, Some(OperationParams::SetResources(params))) => {
+                let mut changes = Vec::new();
+                if let Some(vcpus) = params.vcpus {
+                    VmService::set_vcpus(libvirt, vm_id, vcpus)?;
+                    changes.push(format!("{} vCPUs", vcpus));
+                }
+                if let Some(memory_mb) = params.memory_mb {
+                    VmService::set_memory(libvirt, vm_id, memory_mb)?;
+                    changes.push(format!("{} MB memory", memory_mb));
+                }
+                Ok(format!("Set {}", changes.join(" and ")))
+            }
+            (OperationType::PruneSnapshots, Some(OperationParams::PruneSnapshots(params))) => {
+                let mut snapshots: Vec<_> = SnapshotService::list_snapshots(libvirt, vm_id)?
+                    .into_iter()
+                    .filter(|snapshot| snapshot.name.starts_with(&params.name_prefix))
+                    .collect();
+                snapshots.sort_by(|a, b| b.creation_time.cmp(&a.creation_time));
 
-                // Set the day, handling month-end edge cases
-                target_date = target_date.with_day(target_day.min(28) as u32)
-                    .ok_or_else(|| AppError::ScheduleError("Invalid day of month".to_string()))?;
+                let mut deleted = 0;
+                for snapshot in snapshots.iter().skip(params.keep_last as usize) {
+                    SnapshotService::delete_snapshot(libvirt, vm_id, &snapshot.name)?;
+                    deleted += 1;
+                }
+                Ok(format!("Deleted {} snapshot(s), kept {}", deleted, snapshots.len() - deleted))
+            }
+            (OperationType::Migrate, Some(OperationParams::Migrate(params))) => {
+                let destination = context.connections.get_saved_connections()?
+                    .into_iter()
+                    .find(|c| c.id == params.target_connection_id)
+                    .ok_or_else(|| AppError::ScheduleError(format!(
+                        "Saved connection not found: {}", params.target_connection_id
+                    )))?;
+
+                // A schedule without a connection runs on the active one, which defaults to local
+                let current = match &target.connection_id {
+                    Some(id) => id.clone(),
+                    None => context.connections.get_active_connection_info()?
+                        .map(|c| c.id)
+                        .unwrap_or_else(|| "local".to_string()),
+                };
+                if current == destination.id {
+                    return Ok(format!("Already on {}", destination.name));
+                }
 
-                target_date.and_time(scheduled)
+                VmService::migrate_vm(libvirt, vm_id, &destination.build_uri(), params.live, params.unsafe_migration, None)?;
+                Ok(format!("Migrated to {}", destination.name))
             }
+            // Backups and verifications take long, so they run as jobs the user can
+            // follow and cancel; the job reports its own failure to the alert engine
+            (OperationType::Backup, Some(OperationParams::Backup(params))) => {
+                let config = BackupService::new()?.get_backup_config(&params.backup_config_id)?;
+                let libvirt = context.connections.get_libvirt(Some(BACKUP_CONNECTION))?;
+                let job_id = BackupService::start_backup_job(&context.jobs, context.events.clone(), libvirt, config)?;
+                Ok(format!("Started backup job {}", job_id))
+            }
+            (OperationType::VerifyBackup, Some(OperationParams::VerifyBackup(params))) => {
+                let backups = BackupService::new()?;
+                let config = backups.get_backup_config(&params.backup_config_id)?;
+                let point = backups.latest_point(&config.id)?;
+                let libvirt = context.connections.get_libvirt(Some(BACKUP_CONNECTION))?;
+                let job_id = BackupVerifyService::start_verify_job(
+                    &context.jobs, context.events.clone(), libvirt, config, point.id, params.options.clone(),
+                );
+                Ok(format!("Started verification job {}", job_id))
+            }
+            (operation, _) => Err(AppError::ScheduleError(format!("Missing parameters for {:?}", operation))),
+        }
+    }
+
+    /// Append a run to the schedule's log, keeping the newest `RUN_HISTORY_LIMIT` entries
+    fn record_run(&self, run: &ScheduleRun) -> Result<(), AppError> {
+        let path = self.runs_path(&run.schedule_id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::ScheduleError(format!("Failed to create run log directory: {}", e)))?;
+        }
+
+        let mut runs = self.get_schedule_runs(&run.schedule_id).unwrap_or_default();
+        runs.insert(0, run.clone());
+        runs.truncate(RUN_HISTORY_LIMIT);
+
+        let json = serde_json::to_string_pretty(&runs)
+            .map_err(|e| AppError::ScheduleError(format!("Failed to serialize run log: {}", e)))?;
+
+        fs::write(&path, json)
+            .map_err(|e| AppError::ScheduleError(format!("Failed to write run log: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Set `last_run` and compute the following `next_run`; one-off schedules are disabled
+    fn advance_schedule(&self, id: &str, run: &ScheduleRun) -> Result<(), AppError> {
+        // Reload: the schedule may have been edited or deleted while the operation ran
+        let mut schedule = match self.get_schedule(id) {
+            Ok(schedule) => schedule,
+            Err(_) => return Ok(()),
         };
 
-        Ok(next_run.and_utc().timestamp())
+        schedule.last_run = Some(run.started_at);
+        schedule.last_status = Some(run.status.clone());
+
+        if schedule.frequency == ScheduleFrequency::Once {
+            schedule.enabled = false;
+        } else {
+            match self.calculate_next_run(&schedule, chrono::Utc::now().timestamp()) {
+                Ok(next_run) => schedule.next_run = next_run,
+                Err(e) => {
+                    // Without a valid next run the schedule would fire on every pass
+                    tracing::error!("Disabling schedule '{}': {}", schedule.name, e);
+                    schedule.enabled = false;
+                }
+            }
+        }
+
+        self.save_schedule(&schedule)
+    }
+
+    /// Calculate the first run strictly after `after` (Unix seconds).
+    ///
+    /// Every frequency is evaluated as a cron pattern in the schedule's timezone,
+    /// so runs stay on the configured wall-clock time across DST changes. A time
+    /// skipped by a spring-forward change runs at the first valid instant after it;
+    /// a time repeated by a fall-back change runs once.
+    fn calculate_next_run(&self, schedule: &ScheduledOperation, after: i64) -> Result<i64, AppError> {
+        let pattern = Self::cron_pattern(schedule)?;
+        let cron = Cron::new(&pattern)
+            .parse()
+            .map_err(|e| AppError::ScheduleError(format!("Invalid cron expression '{}': {}", pattern, e)))?;
+
+        let after = DateTime::from_timestamp(after, 0)
+            .ok_or_else(|| AppError::ScheduleError("Invalid timestamp".to_string()))?;
+
+        match schedule.timezone.as_deref() {
+            Some(name) => {
+                let tz: chrono_tz::Tz = name.parse()
+                    .map_err(|_| AppError::ScheduleError(format!("Unknown timezone: {}", name)))?;
+                Self::next_occurrence(&cron, after, &tz)
+            }
+            None => Self::next_occurrence(&cron, after, &chrono::Local),
+        }
+    }
+
+    fn next_occurrence<Tz: TimeZone>(cron: &Cron, after: DateTime<Utc>, tz: &Tz) -> Result<i64, AppError> {
+        let next = cron.find_next_occurrence(&after.with_timezone(tz), false)
+            .map_err(|e| AppError::ScheduleError(format!("Failed to calculate next run: {}", e)))?;
+
+        if next.timestamp() > after.timestamp() {
+            return Ok(next.timestamp());
+        }
+
+        // Searching from inside a repeated (fall-back) hour can resolve an ambiguous
+        // wall time to its earlier offset, which is already in the past
+        tz.from_local_datetime(&next.naive_local())
+            .latest()
+            .map(|dt| dt.timestamp())
+            .filter(|&ts| ts > after.timestamp())
+            .ok_or_else(|| AppError::ScheduleError("Failed to calculate next run".to_string()))
+    }
+
+    /// Express a schedule as a 5-field cron pattern
+    fn cron_pattern(schedule: &ScheduledOperation) -> Result<String, AppError> {
+        let pattern = match schedule.frequency {
+            ScheduleFrequency::Cron => {
+                return schedule.cron_expression.as_deref()
+                    .map(|expr| expr.trim().to_string())
+                    .filter(|expr| !expr.is_empty())
+                    .ok_or_else(|| AppError::ScheduleError("cron_expression required for cron schedules".to_string()));
+            }
+            ScheduleFrequency::Once | ScheduleFrequency::Daily => {
+                let (hour, minute) = Self::parse_time(&schedule.scheduled_time)?;
+                format!("{} {} * * *", minute, hour)
+            }
+            ScheduleFrequency::Weekly => {
+                let (hour, minute) = Self::parse_time(&schedule.scheduled_time)?;
+                let target_weekday = schedule.day_of_week
+                    .ok_or_else(|| AppError::ScheduleError("day_of_week required for weekly schedules".to_string()))?;
+                if target_weekday > 6 {
+                    return Err(AppError::ScheduleError("Invalid day of week".to_string()));
+                }
+                format!("{} {} * * {}", minute, hour, target_weekday)
+            }
+            ScheduleFrequency::Monthly => {
+                let (hour, minute) = Self::parse_time(&schedule.scheduled_time)?;
+                let target_day = schedule.day_of_month
+                    .ok_or_else(|| AppError::ScheduleError("day_of_month required for monthly schedules".to_string()))?;
+                // Later days don't occur every month; a cron schedule covers those
+                if !(1..=28).contains(&target_day) {
+                    return Err(AppError::ScheduleError(
+                        "Day of month must be between 1 and 28; use a cron schedule for later days".to_string()
+                    ));
+                }
+                format!("{} {} {} * *", minute, hour, target_day)
+            }
+        };
+
+        Ok(pattern)
+    }
+
+    /// Parse an "HH:MM" time of day
+    fn parse_time(scheduled_time: &str) -> Result<(u32, u32), AppError> {
+        let parts: Vec<&str> = scheduled_time.split(':').collect();
+        if parts.len() != 2 {
+            return Err(AppError::ScheduleError("Invalid time format. Use HH:MM".to_string()));
+        }
+
+        let hour: u32 = parts[0].parse()
+            .map_err(|_| AppError::ScheduleError("Invalid hour".to_string()))?;
+        let minute: u32 = parts[1].parse()
+            .map_err(|_| AppError::ScheduleError("Invalid minute".to_string()))?;
+
+        if hour > 23 || minute > 59 {
+            return Err(AppError::ScheduleError("Invalid time".to_string()));
+        }
+
+        Ok((hour, minute))
     }
 
     /// Save a schedule to disk
@@ -0,0 +1,332 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use crate::utils::error::AppError;
+
+const NONCE_LEN: usize = 12;
+
+lazy_static::lazy_static! {
+    // Every instance caches and rewrites the whole file, so the process shares one
+    static ref SHARED: Mutex<Option<Arc<SecretService>>> = Mutex::new(None);
+}
+
+/// Credentials used when opening a saved connection
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionCredentials {
+    /// Private key used for qemu+ssh connections
+    #[serde(default)]
+    pub ssh_key_path: Option<String>,
+    /// Directory holding cacert.pem, clientcert.pem and clientkey.pem for qemu+tls
+    #[serde(default)]
+    pub tls_pki_path: Option<String>,
+    /// SASL username for TCP/TLS connections
+    #[serde(default)]
+    pub sasl_username: Option<String>,
+    /// SASL password for TCP/TLS connections
+    #[serde(default)]
+    pub sasl_password: Option<String>,
+}
+
+/// Credential summary that is safe to send to the frontend
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialsInfo {
+    pub ssh_key_path: Option<String>,
+    pub tls_pki_path: Option<String>,
+    pub sasl_username: Option<String>,
+    pub has_sasl_password: bool,
+}
+
+impl From<&ConnectionCredentials> for CredentialsInfo {
+    fn from(creds: &ConnectionCredentials) -> Self {
+        Self {
+            ssh_key_path: creds.ssh_key_path.clone(),
+            tls_pki_path: creds.tls_pki_path.clone(),
+            sasl_username: creds.sasl_username.clone(),
+            has_sasl_password: creds.sasl_password.is_some(),
+        }
+    }
+}
+
+/// Decrypted contents of the secrets file
+#[derive(Serialize, Deserialize, Default)]
+struct SecretStore {
+    /// Credentials keyed by connection ID
+    connections: HashMap<String, ConnectionCredentials>,
+    /// Other services' secrets keyed by reference, e.g. `notification/<id>/password`
+    #[serde(default)]
+    values: HashMap<String, String>,
+}
+
+/// Encrypted store for per-connection credentials and other services' secrets.
+///
+/// Secrets live in `secrets.bin` next to the connection config, sealed with
+/// AES-256-GCM. The key is kept in the local data directory rather than the
+/// config directory so copying or syncing the config does not leak it.
+///
+/// A store that can't be read (corrupt file, lost or rotated key) is moved
+/// aside and replaced by an empty one so the app still starts; connections
+/// marked with `mark_needs_credentials` then ask for their credentials again.
+pub struct SecretService {
+    secrets_path: PathBuf,
+    key_path: PathBuf,
+    secrets: RwLock<SecretStore>,
+    /// Whether an unreadable store was discarded at startup
+    reset: bool,
+    /// Connections whose credentials were lost with a discarded store
+    needs_credentials: RwLock<HashSet<String>>,
+}
+
+impl SecretService {
+    /// The secret store shared by every service, loaded on first use
+    pub fn shared() -> Result<Arc<Self>, AppError> {
+        let mut shared = SHARED.lock()
+            .map_err(|_| AppError::Other("Failed to lock secret store".to_string()))?;
+        if let Some(service) = shared.as_ref() {
+            return Ok(service.clone());
+        }
+
+        let service = Arc::new(Self::new()?);
+        *shared = Some(service.clone());
+        Ok(service)
+    }
+
+    /// Create a new secret service, loading any stored credentials
+    fn new() -> Result<Self, AppError> {
+        let config_dir = dirs::config_dir()
+            .ok_or_else(|| AppError::InvalidConfig("Could not determine config directory".to_string()))?
+            .join("kvm-manager");
+        let data_dir = dirs::data_local_dir()
+            .ok_or_else(|| AppError::InvalidConfig("Could not determine data directory".to_string()))?
+            .join("kvm-manager");
+
+        fs::create_dir_all(&config_dir)?;
+        fs::create_dir_all(&data_dir)?;
+
+        let mut service = Self {
+            secrets_path: config_dir.join("secrets.bin"),
+            key_path: data_dir.join("secrets.key"),
+            secrets: RwLock::new(SecretStore::default()),
+            reset: false,
+            needs_credentials: RwLock::new(HashSet::new()),
+        };
+
+        let loaded = match service.load() {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                tracing::error!("Secret store is unreadable, starting with an empty one: {}", e);
+                service.quarantine();
+                service.reset = true;
+                SecretStore::default()
+            }
+        };
+        *service.secrets.write()
+            .map_err(|_| AppError::Other("Failed to lock secrets".to_string()))? = loaded;
+
+        Ok(service)
+    }
+
+    /// Whether an unreadable store was discarded at startup, losing every stored credential
+    pub fn was_reset(&self) -> bool {
+        self.reset
+    }
+
+    /// Flag connections whose credentials were lost and must be entered again
+    pub fn mark_needs_credentials(&self, connection_ids: impl IntoIterator<Item = String>) {
+        if let Ok(mut needs) = self.needs_credentials.write() {
+            needs.extend(connection_ids);
+        }
+    }
+
+    /// Connections that need their credentials entered again
+    pub fn needs_credentials(&self) -> Vec<String> {
+        self.needs_credentials.read()
+            .map(|needs| needs.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Get the credentials stored for a connection
+    pub fn get(&self, connection_id: &str) -> Result<Option<ConnectionCredentials>, AppError> {
+        let secrets = self.secrets.read()
+            .map_err(|_| AppError::Other("Failed to lock secrets".to_string()))?;
+        Ok(secrets.connections.get(connection_id).cloned())
+    }
+
+    /// Store credentials for a connection, replacing any existing ones
+    pub fn set(&self, connection_id: &str, credentials: ConnectionCredentials) -> Result<(), AppError> {
+        let mut secrets = self.secrets.write()
+            .map_err(|_| AppError::Other("Failed to lock secrets".to_string()))?;
+        secrets.connections.insert(connection_id.to_string(), credentials);
+        self.save(&secrets)?;
+        if let Ok(mut needs) = self.needs_credentials.write() {
+            needs.remove(connection_id);
+        }
+        Ok(())
+    }
+
+    /// Remove the credentials stored for a connection
+    pub fn remove(&self, connection_id: &str) -> Result<(), AppError> {
+        let mut secrets = self.secrets.write()
+            .map_err(|_| AppError::Other("Failed to lock secrets".to_string()))?;
+        if secrets.connections.remove(connection_id).is_some() {
+            self.save(&secrets)?;
+        }
+        Ok(())
+    }
+
+    /// Get a secret stored under a reference
+    pub fn get_value(&self, reference: &str) -> Result<Option<String>, AppError> {
+        let secrets = self.secrets.read()
+            .map_err(|_| AppError::Other("Failed to lock secrets".to_string()))?;
+        Ok(secrets.values.get(reference).cloned())
+    }
+
+    /// Store a secret under a reference, removing it when `value` is `None`
+    pub fn set_value(&self, reference: &str, value: Option<&str>) -> Result<(), AppError> {
+        let mut secrets = self.secrets.write()
+            .map_err(|_| AppError::Other("Failed to lock secrets".to_string()))?;
+        let changed = match value {
+            Some(value) => secrets.values.insert(reference.to_string(), value.to_string()).as_deref() != Some(value),
+            None => secrets.values.remove(reference).is_some(),
+        };
+        if changed {
+            self.save(&secrets)?;
+        }
+        Ok(())
+    }
+
+    /// Remove every secret whose reference starts with `prefix`
+    pub fn remove_values(&self, prefix: &str) -> Result<(), AppError> {
+        let mut secrets = self.secrets.write()
+            .map_err(|_| AppError::Other("Failed to lock secrets".to_string()))?;
+        let before = secrets.values.len();
+        secrets.values.retain(|reference, _| !reference.starts_with(prefix));
+        if secrets.values.len() != before {
+            self.save(&secrets)?;
+        }
+        Ok(())
+    }
+
+    /// Load and decrypt the secrets file
+    fn load(&self) -> Result<SecretStore, AppError> {
+        if !self.secrets_path.exists() {
+            return Ok(SecretStore::default());
+        }
+
+        // A fresh key could never open the existing file
+        if !self.key_path.exists() {
+            return Err(AppError::InvalidConfig("Secrets key is missing".to_string()));
+        }
+
+        let sealed = fs::read(&self.secrets_path)?;
+        if sealed.len() < NONCE_LEN {
+            return Err(AppError::InvalidConfig("Secrets file is truncated".to_string()));
+        }
+
+        let cipher = self.cipher()?;
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let plaintext = cipher.decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| AppError::InvalidConfig("Failed to decrypt secrets file".to_string()))?;
+
+        // Older files hold only the connection credentials map
+        if let Ok(store) = serde_json::from_slice::<SecretStore>(&plaintext) {
+            return Ok(store);
+        }
+        let connections = serde_json::from_slice(&plaintext)
+            .map_err(|e| AppError::InvalidConfig(format!("Failed to parse secrets: {}", e)))?;
+        Ok(SecretStore { connections, values: HashMap::new() })
+    }
+
+    /// Move an unreadable secrets file aside so it can be recovered by hand
+    fn quarantine(&self) {
+        let aside = self.secrets_path.with_extension(format!("bin.unreadable-{}", chrono::Utc::now().timestamp()));
+        match fs::rename(&self.secrets_path, &aside) {
+            Ok(()) => tracing::warn!("Moved unreadable secrets file to {}", aside.display()),
+            Err(e) => tracing::error!("Failed to move unreadable secrets file aside: {}", e),
+        }
+    }
+
+    /// Encrypt and write the secrets file
+    fn save(&self, secrets: &SecretStore) -> Result<(), AppError> {
+        let plaintext = serde_json::to_vec(secrets)
+            .map_err(|e| AppError::InvalidConfig(format!("Failed to serialize secrets: {}", e)))?;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let cipher = self.cipher()?;
+        let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|_| AppError::Other("Failed to encrypt secrets".to_string()))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        write_private(&self.secrets_path, &sealed)
+    }
+
+    /// Build the cipher, generating the key on first use
+    fn cipher(&self) -> Result<Aes256Gcm, AppError> {
+        let key = if self.key_path.exists() {
+            let key = fs::read(&self.key_path)?;
+            if key.len() != 32 {
+                return Err(AppError::InvalidConfig("Secrets key has an invalid length".to_string()));
+            }
+            key
+        } else {
+            let mut key = vec![0u8; 32];
+            rand::thread_rng().fill_bytes(&mut key);
+            write_private(&self.key_path, &key)?;
+            key
+        };
+
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+    }
+}
+
+/// Write a file readable only by the current user
+///
+/// The contents go to a sibling temp file created with mode 0600, which is
+/// synced and renamed over `path`, so readers never see a partial file and an
+/// existing file's permissions are never relied on.
+pub(crate) fn write_private(path: &Path, contents: &[u8]) -> Result<(), AppError> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| AppError::Other(format!("Invalid private file path: {}", path.display())))?;
+    let temp = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        uuid::Uuid::new_v4().simple()
+    ));
+
+    let result = (|| -> std::io::Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&temp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&temp, path)
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp);
+        return Err(e.into());
+    }
+
+    // Persist the rename itself
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        if let Ok(dir) = fs::File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
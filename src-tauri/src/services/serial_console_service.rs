@@ -105,7 +105,8 @@ impl SerialConsoleService {
     }
 
     /// Open a serial console connection using portable-pty with virsh console
-    pub fn open_connection(&self, vm_id: &str, vm_name: &str, pty_path: &str) -> Result<(), AppError> {
+    /// against the connection at `uri`
+    pub fn open_connection(&self, uri: &str, vm_id: &str, vm_name: &str, pty_path: &str) -> Result<(), AppError> {
         tracing::info!("Opening serial console for VM {} ({}) at {}", vm_id, vm_name, pty_path);
 
         // Check if already connected and clean up stale connections
@@ -128,7 +129,7 @@ impl SerialConsoleService {
 
         // Build the virsh console command
         let mut cmd = CommandBuilder::new("virsh");
-        cmd.args(["console", vm_name, "--force"]);
+        cmd.args(["-c", uri, "console", vm_name, "--force"]);
 
         // Spawn the command in the PTY
         let _child = pair.slave.spawn_command(cmd)
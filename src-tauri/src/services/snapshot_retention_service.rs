@@ -0,0 +1,485 @@
+use crate::models::event::{BackendEvent, TaskFailure, TaskKind};
+use crate::models::snapshot::Snapshot;
+use crate::services::connection_service::ConnectionService;
+use crate::services::event_service::EventService;
+use crate::services::libvirt::LibvirtService;
+use crate::services::scheduler_service::{TagSelector, SCHEDULED_SNAPSHOT_PREFIX};
+use crate::services::snapshot_service::SnapshotService;
+use crate::services::vm_service::VmService;
+use crate::utils::error::AppError;
+use chrono::{Datelike, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Tauri event emitted with a `PruneReport` after every pruning run of a policy
+pub const SNAPSHOT_PRUNE_EVENT: &str = "snapshot-prune";
+
+/// How often the background task applies the enabled policies
+const PRUNE_INTERVAL_SECS: u64 = 3600;
+
+/// What a retention policy keeps of a VM's scheduled snapshots.
+///
+/// A snapshot survives if any of `keep_last`, `keep_daily_days` or
+/// `keep_weekly_weeks` selects it; with none of them set every snapshot does.
+/// `max_age_days` is applied last and removes snapshots the other rules kept.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionRules {
+    /// Newest N snapshots
+    #[serde(default)]
+    pub keep_last: Option<u32>,
+    /// Newest snapshot of each of the last D days, today included
+    #[serde(default)]
+    pub keep_daily_days: Option<u32>,
+    /// Newest snapshot of each of the last W ISO weeks, this week included
+    #[serde(default)]
+    pub keep_weekly_weeks: Option<u32>,
+    /// Snapshots older than this are always deleted
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+}
+
+/// Prunes the scheduled snapshots of one VM, or of every VM matching a tag selector.
+///
+/// A VM with its own enabled policy is skipped by tag policies.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotRetentionPolicy {
+    pub id: String,
+    pub name: String,
+    /// VM the policy applies to; empty for tag policies
+    #[serde(default)]
+    pub vm_id: String,
+    #[serde(default)]
+    pub tag_selector: Option<TagSelector>,
+    /// Host of `vm_id`, or the host tag selectors are resolved on (`None` = every connected host)
+    #[serde(default)]
+    pub connection_id: Option<String>,
+    pub rules: RetentionRules,
+    pub enabled: bool,
+    pub created_at: i64,
+    #[serde(default)]
+    pub last_report: Option<PruneReport>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRetentionPolicyRequest {
+    pub name: String,
+    #[serde(default)]
+    pub vm_id: String,
+    #[serde(default)]
+    pub tag_selector: Option<TagSelector>,
+    #[serde(default)]
+    pub connection_id: Option<String>,
+    pub rules: RetentionRules,
+}
+
+/// Outcome of applying a policy to one VM
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VmPruneResult {
+    pub vm_id: String,
+    pub vm_name: String,
+    pub connection_id: Option<String>,
+    /// Scheduled snapshots left in place
+    pub kept: usize,
+    /// Snapshots deleted, or that would be deleted by a dry run
+    pub deleted: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Outcome of one pruning run of a policy
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneReport {
+    pub policy_id: String,
+    pub policy_name: String,
+    pub started_at: i64,
+    pub finished_at: i64,
+    /// Nothing was deleted; `deleted` lists what a real run would remove
+    pub dry_run: bool,
+    pub vms: Vec<VmPruneResult>,
+    /// Set when the policy's VMs could not be resolved at all
+    pub error: Option<String>,
+}
+
+/// A VM a policy prunes
+struct PruneTarget {
+    connection_id: Option<String>,
+    libvirt: Arc<LibvirtService>,
+    vm_id: String,
+    vm_name: String,
+}
+
+pub struct SnapshotRetentionService {
+    policies_dir: PathBuf,
+    /// Serialises runs so a manual run never races the background pass on the same VM
+    run_lock: Mutex<()>,
+}
+
+impl SnapshotRetentionService {
+    pub fn new() -> Result<Self, AppError> {
+        let policies_dir = dirs::config_dir()
+            .ok_or_else(|| AppError::Other("Could not determine config directory".to_string()))?
+            .join("kvm-manager")
+            .join("snapshot-retention");
+        fs::create_dir_all(&policies_dir)
+            .map_err(|e| AppError::Other(format!("Failed to create snapshot retention directory: {}", e)))?;
+
+        Ok(Self { policies_dir, run_lock: Mutex::new(()) })
+    }
+
+    /// Create a retention policy
+    pub fn create_policy(&self, request: CreateRetentionPolicyRequest) -> Result<SnapshotRetentionPolicy, AppError> {
+        if request.name.trim().is_empty() {
+            return Err(AppError::InvalidConfig("Policy name is required".to_string()));
+        }
+        match &request.tag_selector {
+            Some(selector) if selector.tags.iter().all(|t| t.trim().is_empty()) => {
+                return Err(AppError::InvalidConfig("Tag selector needs at least one tag".to_string()));
+            }
+            Some(_) if !request.vm_id.is_empty() => {
+                return Err(AppError::InvalidConfig("A policy applies to either a VM or a tag selector".to_string()));
+            }
+            Some(_) => {}
+            None if request.vm_id.is_empty() => {
+                return Err(AppError::InvalidConfig("Either vm_id or tag_selector is required".to_string()));
+            }
+            None => {}
+        }
+
+        let rules = &request.rules;
+        let values = [rules.keep_last, rules.keep_daily_days, rules.keep_weekly_weeks, rules.max_age_days];
+        if values.iter().all(Option::is_none) {
+            return Err(AppError::InvalidConfig("At least one retention rule is required".to_string()));
+        }
+        if values.contains(&Some(0)) {
+            return Err(AppError::InvalidConfig("Retention rule values must be at least 1".to_string()));
+        }
+
+        let policy = SnapshotRetentionPolicy {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: request.name,
+            vm_id: request.vm_id,
+            tag_selector: request.tag_selector,
+            connection_id: request.connection_id,
+            rules: request.rules,
+            enabled: true,
+            created_at: chrono::Utc::now().timestamp(),
+            last_report: None,
+        };
+        self.save_policy(&policy)?;
+        tracing::info!("Created snapshot retention policy {} ({})", policy.name, policy.id);
+        Ok(policy)
+    }
+
+    /// List retention policies, oldest first
+    pub fn list_policies(&self) -> Result<Vec<SnapshotRetentionPolicy>, AppError> {
+        let entries = fs::read_dir(&self.policies_dir)
+            .map_err(|e| AppError::Other(format!("Failed to read snapshot retention policies: {}", e)))?;
+        let mut policies: Vec<SnapshotRetentionPolicy> = entries
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| {
+                let json = fs::read_to_string(entry.path()).ok()?;
+                serde_json::from_str(&json).ok()
+            })
+            .collect();
+        policies.sort_by_key(|policy| policy.created_at);
+        Ok(policies)
+    }
+
+    pub fn get_policy(&self, id: &str) -> Result<SnapshotRetentionPolicy, AppError> {
+        let json = fs::read_to_string(self.policy_path(id))
+            .map_err(|_| AppError::NotFound(format!("Snapshot retention policy {}", id)))?;
+        serde_json::from_str(&json)
+            .map_err(|e| AppError::Other(format!("Failed to parse snapshot retention policy: {}", e)))
+    }
+
+    pub fn update_policy_status(&self, id: &str, enabled: bool) -> Result<SnapshotRetentionPolicy, AppError> {
+        let mut policy = self.get_policy(id)?;
+        policy.enabled = enabled;
+        self.save_policy(&policy)?;
+        Ok(policy)
+    }
+
+    /// Delete a policy; the snapshots it kept are left alone
+    pub fn delete_policy(&self, id: &str) -> Result<(), AppError> {
+        fs::remove_file(self.policy_path(id))
+            .map_err(|_| AppError::NotFound(format!("Snapshot retention policy {}", id)))
+    }
+
+    /// Apply one policy now, enabled or not. A dry run only reports what would be deleted
+    /// and leaves the policy's last report untouched.
+    pub fn run_policy(&self, id: &str, connections: &ConnectionService, dry_run: bool) -> Result<PruneReport, AppError> {
+        let policy = self.get_policy(id)?;
+        let overridden = vm_policy_targets(&self.list_policies()?);
+        let _guard = self.run_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        let report = Self::apply_policy(&policy, connections, &overridden, dry_run);
+        if !dry_run {
+            self.record_report(&policy.id, &report);
+        }
+        Ok(report)
+    }
+
+    /// Start the background task that applies every enabled policy
+    pub fn start_pruning_task(self: Arc<Self>, connections: Arc<ConnectionService>, events: Arc<EventService>) {
+        tracing::info!("Starting snapshot pruning task");
+
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(tokio::time::Duration::from_secs(PRUNE_INTERVAL_SECS)).await;
+
+                // Deleting external snapshots runs block jobs, so keep the pass off the async runtime
+                let service = self.clone();
+                let connections = connections.clone();
+                let events = events.clone();
+                let pass = tauri::async_runtime::spawn_blocking(move || {
+                    service.run_enabled_policies(&connections, &events)
+                }).await;
+
+                if let Err(e) = pass {
+                    tracing::error!("Snapshot pruning pass failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Apply every enabled policy, reporting failures to the alert engine
+    fn run_enabled_policies(&self, connections: &ConnectionService, events: &EventService) {
+        let policies = match self.list_policies() {
+            Ok(policies) => policies,
+            Err(e) => {
+                tracing::error!("Failed to load snapshot retention policies: {}", e);
+                return;
+            }
+        };
+        let overridden = vm_policy_targets(&policies);
+        let _guard = self.run_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        for policy in policies.into_iter().filter(|p| p.enabled) {
+            let report = Self::apply_policy(&policy, connections, &overridden, false);
+            self.record_report(&policy.id, &report);
+
+            if let Some(error) = &report.error {
+                events.publish(BackendEvent::TaskFailed(TaskFailure {
+                    kind: TaskKind::SnapshotPrune,
+                    id: policy.id.clone(),
+                    name: policy.name.clone(),
+                    vm_id: Some(policy.vm_id.clone()).filter(|id| !id.is_empty()),
+                    error: error.clone(),
+                }));
+            }
+            for vm in report.vms.iter().filter(|vm| vm.error.is_some()) {
+                events.publish(BackendEvent::TaskFailed(TaskFailure {
+                    kind: TaskKind::SnapshotPrune,
+                    id: policy.id.clone(),
+                    name: policy.name.clone(),
+                    vm_id: Some(vm.vm_id.clone()),
+                    error: vm.error.clone().unwrap_or_default(),
+                }));
+            }
+            events.emit(SNAPSHOT_PRUNE_EVENT, report);
+        }
+    }
+
+    fn apply_policy(
+        policy: &SnapshotRetentionPolicy,
+        connections: &ConnectionService,
+        overridden: &HashSet<String>,
+        dry_run: bool,
+    ) -> PruneReport {
+        let started_at = chrono::Utc::now().timestamp();
+        let (vms, error) = match Self::resolve_targets(policy, connections) {
+            Ok(targets) => {
+                let vms = targets
+                    .into_iter()
+                    .filter(|target| policy.tag_selector.is_none() || !overridden.contains(&target.vm_id))
+                    .map(|target| Self::prune_vm(&policy.rules, target, dry_run))
+                    .collect();
+                (vms, None)
+            }
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+
+        let report = PruneReport {
+            policy_id: policy.id.clone(),
+            policy_name: policy.name.clone(),
+            started_at,
+            finished_at: chrono::Utc::now().timestamp(),
+            dry_run,
+            vms,
+            error,
+        };
+        let deleted: usize = report.vms.iter().map(|vm| vm.deleted.len()).sum();
+        tracing::info!(
+            "Snapshot retention policy '{}'{}: {} snapshot(s) deleted across {} VM(s)",
+            policy.name, if dry_run { " (dry run)" } else { "" }, deleted, report.vms.len()
+        );
+        report
+    }
+
+    /// Resolve the VMs a policy prunes; tag selectors are matched against the current inventory
+    fn resolve_targets(policy: &SnapshotRetentionPolicy, connections: &ConnectionService) -> Result<Vec<PruneTarget>, AppError> {
+        let Some(selector) = &policy.tag_selector else {
+            let libvirt = connections.get_libvirt(policy.connection_id.as_deref())?;
+            let vm = VmService::get_vm(&libvirt, &policy.vm_id)?;
+            return Ok(vec![PruneTarget {
+                connection_id: policy.connection_id.clone(),
+                libvirt,
+                vm_id: vm.id,
+                vm_name: vm.name,
+            }]);
+        };
+
+        let hosts = match &policy.connection_id {
+            Some(id) => vec![(id.clone(), connections.get_libvirt(Some(id))?)],
+            None => connections.get_connected()?,
+        };
+
+        let mut targets = Vec::new();
+        for (connection_id, libvirt) in hosts {
+            let vms = match VmService::list_vms(&libvirt) {
+                Ok(vms) => vms,
+                Err(e) => {
+                    tracing::warn!("Retention policy '{}': failed to list VMs on {}: {}", policy.name, connection_id, e);
+                    continue;
+                }
+            };
+
+            for vm in vms.into_iter().filter(|vm| selector.matches(&vm.tags)) {
+                targets.push(PruneTarget {
+                    connection_id: Some(connection_id.clone()),
+                    libvirt: libvirt.clone(),
+                    vm_id: vm.id,
+                    vm_name: vm.name,
+                });
+            }
+        }
+        Ok(targets)
+    }
+
+    /// Delete the scheduled snapshots of a VM the rules don't keep, oldest first
+    fn prune_vm(rules: &RetentionRules, target: PruneTarget, dry_run: bool) -> VmPruneResult {
+        let mut result = VmPruneResult {
+            vm_id: target.vm_id.clone(),
+            vm_name: target.vm_name.clone(),
+            connection_id: target.connection_id.clone(),
+            kept: 0,
+            deleted: Vec::new(),
+            error: None,
+        };
+
+        let mut snapshots: Vec<Snapshot> = match SnapshotService::list_snapshots(&target.libvirt, &target.vm_id) {
+            Ok(snapshots) => snapshots
+                .into_iter()
+                .filter(|snapshot| snapshot.name.starts_with(SCHEDULED_SNAPSHOT_PREFIX))
+                .collect(),
+            Err(e) => {
+                result.error = Some(e.to_string());
+                return result;
+            }
+        };
+        snapshots.sort_by(|a, b| b.creation_time.cmp(&a.creation_time));
+
+        let keep = select_kept(&snapshots, rules, chrono::Utc::now().timestamp());
+
+        for snapshot in snapshots.iter().rev().filter(|snapshot| !keep.contains(&snapshot.name)) {
+            if !dry_run {
+                if let Err(e) = SnapshotService::delete_snapshot(&target.libvirt, &target.vm_id, &snapshot.name) {
+                    // Leave the rest for the next pass; later snapshots may depend on this one's files
+                    result.error = Some(format!("Failed to delete {}: {}", snapshot.name, e));
+                    break;
+                }
+            }
+            result.deleted.push(snapshot.name.clone());
+        }
+        result.kept = snapshots.len() - result.deleted.len();
+        result
+    }
+
+    fn record_report(&self, id: &str, report: &PruneReport) {
+        // The policy may have been deleted while it ran
+        let Ok(mut policy) = self.get_policy(id) else { return };
+        policy.last_report = Some(report.clone());
+        if let Err(e) = self.save_policy(&policy) {
+            tracing::warn!("Failed to record prune report for policy {}: {}", id, e);
+        }
+    }
+
+    fn policy_path(&self, id: &str) -> PathBuf {
+        self.policies_dir.join(format!("{}.json", id))
+    }
+
+    fn save_policy(&self, policy: &SnapshotRetentionPolicy) -> Result<(), AppError> {
+        let json = serde_json::to_string_pretty(policy)
+            .map_err(|e| AppError::Other(format!("Failed to serialize snapshot retention policy: {}", e)))?;
+        fs::write(self.policy_path(&policy.id), json)
+            .map_err(|e| AppError::Other(format!("Failed to save snapshot retention policy: {}", e)))
+    }
+}
+
+/// VMs with an enabled policy of their own, which tag policies leave alone
+fn vm_policy_targets(policies: &[SnapshotRetentionPolicy]) -> HashSet<String> {
+    policies
+        .iter()
+        .filter(|p| p.enabled && p.tag_selector.is_none())
+        .map(|p| p.vm_id.clone())
+        .collect()
+}
+
+/// Names of the snapshots the rules keep; `snapshots` must be sorted newest first.
+///
+/// Days and weeks are calendar days and ISO weeks in local time, so a snapshot
+/// taken just after midnight counts for the new day.
+fn select_kept(snapshots: &[Snapshot], rules: &RetentionRules, now: i64) -> HashSet<String> {
+    let local_date = |time: i64| Local.timestamp_opt(time, 0).single().map(|t| t.date_naive());
+    let week_start = |date: NaiveDate| date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64);
+    let today = local_date(now).unwrap_or_else(|| Local::now().date_naive());
+
+    let mut keep = HashSet::new();
+    if rules.keep_last.is_none() && rules.keep_daily_days.is_none() && rules.keep_weekly_weeks.is_none() {
+        keep.extend(snapshots.iter().map(|s| s.name.clone()));
+    }
+
+    if let Some(count) = rules.keep_last {
+        keep.extend(snapshots.iter().take(count as usize).map(|s| s.name.clone()));
+    }
+
+    if let Some(days) = rules.keep_daily_days {
+        let first_day = today - chrono::Duration::days(days as i64 - 1);
+        let mut seen = HashSet::new();
+        for snapshot in snapshots {
+            let Some(date) = local_date(snapshot.creation_time) else { continue };
+            if date >= first_day && seen.insert(date) {
+                keep.insert(snapshot.name.clone());
+            }
+        }
+    }
+
+    if let Some(weeks) = rules.keep_weekly_weeks {
+        let first_week = week_start(today) - chrono::Duration::weeks(weeks as i64 - 1);
+        let mut seen = HashSet::new();
+        for snapshot in snapshots {
+            let Some(date) = local_date(snapshot.creation_time) else { continue };
+            let week = week_start(date);
+            if week >= first_week && seen.insert(week) {
+                keep.insert(snapshot.name.clone());
+            }
+        }
+    }
+
+    if let Some(days) = rules.max_age_days {
+        let cutoff = now - days as i64 * 86400;
+        for snapshot in snapshots.iter().filter(|s| s.creation_time < cutoff) {
+            keep.remove(&snapshot.name);
+        }
+    }
+
+    keep
+}
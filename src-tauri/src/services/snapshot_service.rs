@@ -1,8 +1,31 @@
+use std::collections::{HashMap, HashSet};
+use std::ffi::CString;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
 use virt::domain::Domain;
 use virt::domain_snapshot::DomainSnapshot;
-use crate::models::snapshot::{Snapshot, SnapshotConfig, SnapshotState};
+use virt::sys;
+use crate::models::domain_xml::{Disk, DomainXml, XmlModel};
+use crate::models::snapshot::{
+    ConfigChange, ConfigChangeKind, Snapshot, SnapshotConfig, SnapshotConfigDiff, SnapshotDisk, SnapshotLocation,
+    SnapshotNode, SnapshotState,
+};
+use crate::services::job_service;
 use crate::services::libvirt::LibvirtService;
+use crate::services::quiesce_service::QuiesceService;
 use crate::utils::error::{AppError, map_libvirt_error};
+use crate::utils::xml::{XmlElement, XmlNode};
+
+/// How often a block commit or pull is polled
+const BLOCK_JOB_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// A block job that makes no progress for this long is aborted
+const BLOCK_JOB_STALL_TIMEOUT: Duration = Duration::from_secs(300);
+/// Deepest backing chain followed when looking for an image
+const MAX_CHAIN_DEPTH: usize = 64;
+/// Top-level domain elements that identify the VM or hold runtime state
+const IGNORED_SECTIONS: &[&str] = &["id", "uuid", "metadata", "seclabel", "resource", "devices"];
 
 /// SnapshotService provides VM snapshot management operations
 pub struct SnapshotService;
@@ -40,6 +63,72 @@ impl SnapshotService {
         Ok(snapshots)
     }
 
+    /// Get the snapshots of a VM as a tree, oldest first at every level
+    pub fn get_snapshot_tree(libvirt: &LibvirtService, vm_id: &str) -> Result<Vec<SnapshotNode>, AppError> {
+        tracing::debug!("Building snapshot tree for VM: {}", vm_id);
+
+        let conn = libvirt.get_connection();
+        let domain = Domain::lookup_by_uuid_string(conn, vm_id)
+            .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
+
+        let domain_snapshots = domain.list_all_snapshots(0)
+            .map_err(map_libvirt_error)?;
+
+        // Sizes come from the image files, which are only reachable on the local host
+        let local = libvirt.is_local();
+        let mut state_sizes = HashMap::new();
+        let mut nodes = Vec::new();
+        for snap in domain_snapshots {
+            let xml = snap.get_xml_desc(0)
+                .map_err(map_libvirt_error)?;
+            let root = XmlElement::parse(&xml)?;
+            let snapshot = Self::parse_snapshot(&root, snap.is_current(0).unwrap_or(false));
+            let has_memory = match root.child("memory").and_then(|memory| memory.attr("snapshot")) {
+                Some(mode) => mode == "internal" || mode == "external",
+                None => matches!(snapshot.state, SnapshotState::Running | SnapshotState::Paused),
+            };
+            let size_bytes = if local { snapshot_size(&snapshot, &mut state_sizes) } else { None };
+            nodes.push(SnapshotNode {
+                domain_xml: root.child("domain").map(XmlElement::to_xml).unwrap_or_default(),
+                snapshot,
+                has_memory,
+                size_bytes,
+                children: Vec::new(),
+            });
+        }
+
+        Ok(build_tree(nodes))
+    }
+
+    /// Compare the definition saved with a snapshot to the VM's current one
+    pub fn diff_snapshot_config(
+        libvirt: &LibvirtService,
+        vm_id: &str,
+        snapshot_name: &str,
+    ) -> Result<SnapshotConfigDiff, AppError> {
+        let conn = libvirt.get_connection();
+        let domain = Domain::lookup_by_uuid_string(conn, vm_id)
+            .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
+
+        let snapshot = DomainSnapshot::lookup_by_name(&domain, snapshot_name, 0)
+            .map_err(|_| AppError::LibvirtError(format!("Snapshot not found: {}", snapshot_name)))?;
+
+        let root = XmlElement::parse(&snapshot.get_xml_desc(0).map_err(map_libvirt_error)?)?;
+        let saved = root.child("domain")
+            .ok_or_else(|| AppError::XmlError(format!("Snapshot {} has no saved definition", snapshot_name)))?;
+        let current = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
+
+        let mut changes = Vec::new();
+        diff_elements("domain", &sections(saved), &sections(current.root()), &mut changes);
+        let device_list = |element: &XmlElement| element.child("devices").map(devices).unwrap_or_default();
+        diff_elements("devices", &device_list(saved), &device_list(current.root()), &mut changes);
+
+        Ok(SnapshotConfigDiff {
+            snapshot_name: snapshot_name.to_string(),
+            changes,
+        })
+    }
+
     /// Create a new snapshot
     pub fn create_snapshot(
         libvirt: &LibvirtService,
@@ -52,39 +141,118 @@ impl SnapshotService {
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        let description = config.description.unwrap_or_default();
+        let description = config.description.clone().unwrap_or_default();
 
         // Build snapshot XML
-        let xml = if config.include_memory {
-            format!(
+        let (xml, flags) = match config.location {
+            SnapshotLocation::Internal if config.include_memory => (format!(
                 r#"<domainsnapshot>
   <name>{}</name>
   <description>{}</description>
   <memory snapshot='internal'/>
 </domainsnapshot>"#,
                 config.name, description
-            )
-        } else {
-            format!(
+            ), 0),
+            SnapshotLocation::Internal => (format!(
                 r#"<domainsnapshot>
   <name>{}</name>
   <description>{}</description>
   <memory snapshot='no'/>
 </domainsnapshot>"#,
                 config.name, description
-            )
+            ), 0),
+            SnapshotLocation::External => Self::external_snapshot_xml(&domain, &config, &description)?,
+        };
+
+        // A memory snapshot already captures a consistent state, and one taken
+        // while frozen would revert into a frozen guest
+        let quiesce = config.quiesce.enabled && domain.is_active().map_err(map_libvirt_error)?;
+        if quiesce && config.include_memory {
+            return Err(AppError::InvalidConfig("Quiescing only applies to disk-only snapshots".to_string()));
+        }
+        let freeze = if quiesce {
+            let vm_name = domain.get_name().map_err(map_libvirt_error)?;
+            Some(QuiesceService::freeze(Some(libvirt.get_uri()), &vm_name, &config.quiesce)?)
+        } else {
+            None
         };
 
-        let snapshot = DomainSnapshot::create_xml(&domain, &xml, 0)
+        let snapshot = DomainSnapshot::create_xml(&domain, &xml, flags)
             .map_err(map_libvirt_error)?;
 
         let snap_name = snapshot.get_name()
             .map_err(map_libvirt_error)?;
 
+        if let Some(freeze) = freeze {
+            if let Some(note) = freeze.note() {
+                tracing::warn!("Snapshot {} is not quiesced: {}", snap_name, note);
+            } else if !freeze.thaw() {
+                tracing::warn!("Snapshot {} outlasted the freeze timeout and may not be application-consistent", snap_name);
+            }
+        }
+
         tracing::info!("Snapshot created successfully: {}", snap_name);
         Ok(snap_name)
     }
 
+    /// Build the XML of an external snapshot.
+    ///
+    /// Every writable file-backed disk gets a qcow2 overlay next to its current
+    /// image, which is frozen from then on. A running VM's memory goes to a file
+    /// beside the first overlay when requested. Nothing is written into the
+    /// existing images, so this also works for raw disks and VMs with UEFI pflash.
+    fn external_snapshot_xml(
+        domain: &Domain,
+        config: &SnapshotConfig,
+        description: &str,
+    ) -> Result<(String, u32), AppError> {
+        validate_file_name(&config.name)?;
+        let vm_name = domain.get_name().map_err(map_libvirt_error)?;
+        let active = domain.is_active().map_err(map_libvirt_error)?;
+        let xml = DomainXml::from_domain(domain)?;
+
+        let mut disks = XmlElement::new("disks");
+        let mut memory_dir = None;
+        for disk in xml.disks() {
+            let element = XmlElement::new("disk").with_attr("name", &disk.target_dev);
+            let Some(source) = overlay_source(&disk) else {
+                disks.push_child(element.with_attr("snapshot", "no"));
+                continue;
+            };
+            let dir = Path::new(source).parent().unwrap_or(Path::new("/")).to_path_buf();
+            let overlay = dir.join(format!("{}-{}.{}.qcow2", vm_name, disk.target_dev, config.name));
+            memory_dir.get_or_insert(dir);
+            disks.push_child(element
+                .with_attr("snapshot", "external")
+                .with_attr("type", "file")
+                .with_child(XmlElement::new("driver").with_attr("type", "qcow2"))
+                .with_child(XmlElement::new("source").with_attr("file", overlay.to_string_lossy())));
+        }
+        let Some(memory_dir) = memory_dir else {
+            return Err(AppError::InvalidConfig(
+                "External snapshots need at least one writable file-backed disk".to_string(),
+            ));
+        };
+
+        let mut flags = sys::VIR_DOMAIN_SNAPSHOT_CREATE_ATOMIC;
+        let memory = if config.include_memory && active {
+            let file = memory_dir.join(format!("{}.{}.mem", vm_name, config.name));
+            XmlElement::new("memory")
+                .with_attr("snapshot", "external")
+                .with_attr("file", file.to_string_lossy())
+        } else {
+            flags |= sys::VIR_DOMAIN_SNAPSHOT_CREATE_DISK_ONLY;
+            XmlElement::new("memory").with_attr("snapshot", "no")
+        };
+
+        let snapshot = XmlElement::new("domainsnapshot")
+            .with_child(XmlElement::new("name").with_text(&config.name))
+            .with_child(XmlElement::new("description").with_text(description))
+            .with_child(memory)
+            .with_child(disks);
+        Ok((snapshot.to_xml(), flags))
+    }
+
     /// Delete a snapshot
     pub fn delete_snapshot(
         libvirt: &LibvirtService,
@@ -100,8 +268,13 @@ impl SnapshotService {
         let snapshot = DomainSnapshot::lookup_by_name(&domain, snapshot_name, 0)
             .map_err(|_| AppError::LibvirtError(format!("Snapshot not found: {}", snapshot_name)))?;
 
-        snapshot.delete(0)
-            .map_err(map_libvirt_error)?;
+        let model = Self::snapshot_to_model(&snapshot, &domain)?;
+        if model.location == SnapshotLocation::External {
+            Self::delete_external(libvirt, &domain, &snapshot, &model)?;
+        } else {
+            snapshot.delete(0)
+                .map_err(map_libvirt_error)?;
+        }
 
         tracing::info!("Snapshot deleted successfully: {}", snapshot_name);
         Ok(())
@@ -122,94 +295,823 @@ impl SnapshotService {
         let snapshot = DomainSnapshot::lookup_by_name(&domain, snapshot_name, 0)
             .map_err(|_| AppError::LibvirtError(format!("Snapshot not found: {}", snapshot_name)))?;
 
-        snapshot.revert(0)
-            .map_err(map_libvirt_error)?;
+        let model = Self::snapshot_to_model(&snapshot, &domain)?;
+        if model.location == SnapshotLocation::External {
+            Self::revert_external(libvirt, &domain, &snapshot, &model)?;
+        } else {
+            snapshot.revert(0)
+                .map_err(map_libvirt_error)?;
+        }
 
         tracing::info!("VM reverted to snapshot successfully: {}", snapshot_name);
         Ok(())
     }
 
-    /// Convert libvirt snapshot to our model
-    fn snapshot_to_model(snap: &DomainSnapshot, _domain: &Domain) -> Result<Snapshot, AppError> {
-        let name = snap.get_name()
+    /// Delete an external snapshot by merging each of its overlays with the
+    /// image it froze, then drop its memory file and metadata.
+    ///
+    /// The overlay is block-committed into the frozen image when nothing else
+    /// is backed by that image. When another branch left by a revert still
+    /// uses it, the frozen image is kept and, if the overlay is the disk's
+    /// active layer, its data is block-pulled up into the overlay instead so
+    /// the current chain still gets shorter.
+    fn delete_external(
+        libvirt: &LibvirtService,
+        domain: &Domain,
+        snapshot: &DomainSnapshot,
+        model: &Snapshot,
+    ) -> Result<(), AppError> {
+        require_local(libvirt, "deleted")?;
+
+        let running = domain.is_active().map_err(map_libvirt_error)?;
+        let current = DomainXml::from_domain(domain)?;
+        let snapshots = Self::external_snapshots(domain)?;
+        let images = chain_images(&snapshots, &current);
+
+        for disk in model.disks.iter().filter(|disk| disk.snapshot == "external") {
+            let (Some(overlay), Some(backing)) = (&disk.overlay, &disk.backing) else {
+                continue;
+            };
+            let active = current.disks().into_iter()
+                .find(|current| current.target_dev == disk.target)
+                .and_then(|current| current.source_path().map(String::from));
+            Self::merge_overlay(
+                libvirt, domain, running, &disk.target, overlay, backing, active.as_deref(), &images, &snapshots,
+            )?;
+        }
+
+        if let Some(file) = &model.memory_file {
+            remove_image(file);
+        }
+        // Children of the snapshot are re-parented onto its parent
+        snapshot.delete(sys::VIR_DOMAIN_SNAPSHOT_DELETE_METADATA_ONLY)
             .map_err(map_libvirt_error)?;
+        Ok(())
+    }
 
-        let xml = snap.get_xml_desc(0)
+    /// Merge one disk's `overlay` with the `backing` image the snapshot froze
+    #[allow(clippy::too_many_arguments)]
+    fn merge_overlay(
+        libvirt: &LibvirtService,
+        domain: &Domain,
+        running: bool,
+        target: &str,
+        overlay: &str,
+        backing: &str,
+        active: Option<&str>,
+        images: &[String],
+        snapshots: &[Snapshot],
+    ) -> Result<(), AppError> {
+        if !Path::new(overlay).exists() {
+            return Ok(());
+        }
+        let above = consumers(overlay, images);
+        let is_active = active.is_some_and(|active| same_file(active, overlay));
+        if above.is_empty() && !is_active {
+            // Nothing builds on the overlay: it only holds writes a revert discarded
+            remove_image(overlay);
+            return Ok(());
+        }
+
+        let in_use = running && active.is_some_and(|active| chain_contains(active, overlay));
+        let shared = consumers(backing, images).iter().any(|image| !same_file(image, overlay));
+        let backing_format = image_info(backing)?.format;
+
+        if shared {
+            if !is_active {
+                tracing::info!("Keeping {}: its frozen image {} is shared with another branch", overlay, backing);
+                return Ok(());
+            }
+            let base = image_info(backing)?.backing;
+            tracing::info!("Pulling {} into {}", backing, overlay);
+            if in_use {
+                block_pull(domain, target, base.as_deref())?;
+            } else {
+                let base_format = base.as_deref().map(image_info).transpose()?.map(|info| info.format);
+                let mut args = vec!["rebase", "-q", "-f", "qcow2", "-b", base.as_deref().unwrap_or("")];
+                if let Some(format) = base_format.as_deref() {
+                    args.extend(["-F", format]);
+                }
+                args.push(overlay);
+                job_service::run_qemu_img(None, &args, 0.0..100.0)?;
+            }
+            return Ok(());
+        }
+
+        tracing::info!("Committing {} into {}", overlay, backing);
+        if in_use {
+            block_commit(domain, target, backing, overlay, is_active)?;
+            if active.is_some_and(|active| !is_active && chain_contains(active, overlay)) {
+                return Err(AppError::LibvirtError(format!("Block commit of {} did not complete", overlay)));
+            }
+        } else {
+            job_service::run_qemu_img(None, &["commit", "-q", "-f", "qcow2", overlay], 0.0..100.0)?;
+            if is_active {
+                Self::set_disk_source(libvirt, domain, target, backing, &backing_format)?;
+            }
+        }
+        // libvirt only rebases the image above the overlay in the running chain
+        for image in &above {
+            if image_info(image)?.backing.is_some_and(|file| same_file(&file, overlay)) {
+                job_service::run_qemu_img(
+                    None,
+                    &["rebase", "-q", "-u", "-f", "qcow2", "-b", backing, "-F", &backing_format, image],
+                    0.0..100.0,
+                )?;
+            }
+        }
+        // Snapshots taken on top of the overlay recorded it as their frozen image
+        for child in snapshots.iter().filter(|child| {
+            child.disks.iter().any(|disk| {
+                disk.target == target && disk.backing.as_deref().is_some_and(|file| same_file(file, overlay))
+            })
+        }) {
+            Self::redefine_backing(domain, &child.name, target, backing, &backing_format)?;
+        }
+        remove_image(overlay);
+        Ok(())
+    }
+
+    /// Revert to an external snapshot.
+    ///
+    /// libvirt cannot do this itself, so the VM is stopped, a fresh overlay is
+    /// created on every image the snapshot froze and the definition saved with
+    /// the snapshot is restored on top of them. A snapshot with memory is then
+    /// resumed from its memory file; a disk-only one leaves the VM shut off.
+    /// Writes made since the snapshot are discarded, and so are the overlays
+    /// that held them unless a later snapshot still builds on them. UEFI
+    /// variables are not part of the snapshot and keep their current values.
+    fn revert_external(
+        libvirt: &LibvirtService,
+        domain: &Domain,
+        snapshot: &DomainSnapshot,
+        model: &Snapshot,
+    ) -> Result<(), AppError> {
+        require_local(libvirt, "reverted")?;
+
+        let snapshot_xml = snapshot.get_xml_desc(sys::VIR_DOMAIN_SNAPSHOT_XML_SECURE)
             .map_err(map_libvirt_error)?;
+        let root = XmlElement::parse(&snapshot_xml)?;
+        let mut saved = root.child("domain")
+            .map(|element| DomainXml::parse(&element.to_xml()))
+            .transpose()?
+            .ok_or_else(|| AppError::XmlError(format!("Snapshot {} has no saved definition", model.name)))?;
+
+        let vm_name = domain.get_name().map_err(map_libvirt_error)?;
+        let current = DomainXml::from_domain_flags(domain, sys::VIR_DOMAIN_XML_INACTIVE | sys::VIR_DOMAIN_XML_SECURE)?;
+        let snapshots = Self::external_snapshots(domain)?;
+        let images = chain_images(&snapshots, &current);
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+
+        // Create every fresh overlay while the VM still runs untouched, so a
+        // failure leaves it as it was
+        let mut staged: Vec<StagedOverlay> = Vec::new();
+        let mut replaced: Vec<String> = Vec::new();
+        for disk in model.disks.iter().filter(|disk| disk.snapshot == "external") {
+            let (Some(overlay), Some(backing)) = (&disk.overlay, &disk.backing) else {
+                continue;
+            };
+            let active = current.disks().into_iter()
+                .find(|current| current.target_dev == disk.target)
+                .and_then(|current| current.source_path().map(String::from));
+
+            // Reverting to the latest snapshot again just empties its overlay,
+            // swapped in for the old one once the VM is stopped
+            let reuse = active.as_deref().is_some_and(|active| same_file(active, overlay))
+                && consumers(overlay, &images).is_empty();
+            let fresh = if reuse {
+                overlay.clone()
+            } else {
+                let stem = Path::new(overlay).with_extension("");
+                format!("{}.revert-{}.qcow2", stem.to_string_lossy(), stamp)
+            };
+            let created = if reuse { format!("{}.revert-{}.partial", overlay, stamp) } else { fresh.clone() };
+
+            let result = saved.disk_element_mut(&disk.target)
+                .ok_or_else(|| AppError::XmlError(format!("Snapshot {} has no disk {}", model.name, disk.target)))
+                .and_then(|element| {
+                    point_at(element, &fresh, "qcow2");
+                    image_info(backing)
+                })
+                .and_then(|info| job_service::run_qemu_img(
+                    None,
+                    &["create", "-q", "-f", "qcow2", "-F", &info.format, "-b", backing, &created],
+                    0.0..100.0,
+                ));
+            if let Err(e) = result {
+                remove_image(&created);
+                staged.iter().for_each(|overlay| remove_image(&overlay.created));
+                return Err(e);
+            }
+
+            if let Some(active) = active.filter(|active| !same_file(active, &fresh)) {
+                replaced.push(active);
+            }
+            staged.push(StagedOverlay { created, fresh });
+        }
+
+        if domain.is_active().map_err(map_libvirt_error)? {
+            if let Err(e) = domain.destroy() {
+                staged.iter().for_each(|overlay| remove_image(&overlay.created));
+                return Err(map_libvirt_error(e));
+            }
+        }
+        for overlay in staged.iter().filter(|overlay| overlay.created != overlay.fresh) {
+            fs::rename(&overlay.created, &overlay.fresh)
+                .map_err(|e| AppError::Other(format!("Failed to replace {}: {}", overlay.fresh, e)))?;
+        }
+
+        let conn = libvirt.get_connection();
+        let xml = saved.to_xml();
+        Domain::define_xml(conn, &xml).map_err(map_libvirt_error)?;
 
-        // Parse creation time from XML
-        let creation_time = Self::parse_creation_time(&xml);
+        // Only now is nothing pointing at the layers holding the discarded writes
+        for active in replaced.iter().filter(|active| discardable(active, &images, &snapshots)) {
+            remove_image(active);
+        }
 
-        // Parse description from XML
-        let description = Self::parse_description(&xml);
+        if let Some(file) = model.memory_file.as_deref().filter(|file| Path::new(file).exists()) {
+            let flags = match model.state {
+                SnapshotState::Paused => sys::VIR_DOMAIN_SAVE_PAUSED,
+                _ => sys::VIR_DOMAIN_SAVE_RUNNING,
+            };
+            let c_file = CString::new(file)
+                .map_err(|e| AppError::Other(format!("Invalid memory file path: {}", e)))?;
+            let c_xml = CString::new(xml)
+                .map_err(|e| AppError::Other(format!("Invalid domain XML: {}", e)))?;
+            // The memory file is left in place, so the snapshot can be reverted to again
+            let ret = unsafe { sys::virDomainRestoreFlags(conn.as_ptr(), c_file.as_ptr(), c_xml.as_ptr(), flags) };
+            if ret < 0 {
+                return Err(AppError::LibvirtError(format!(
+                    "Failed to restore memory of {} from {}: {}", vm_name, file, virt::error::Error::last_error()
+                )));
+            }
+        }
+
+        DomainSnapshot::create_xml(
+            domain,
+            &snapshot_xml,
+            sys::VIR_DOMAIN_SNAPSHOT_CREATE_REDEFINE | sys::VIR_DOMAIN_SNAPSHOT_CREATE_CURRENT,
+        ).map_err(map_libvirt_error)?;
+        Ok(())
+    }
 
-        // Parse parent from XML
-        let parent = Self::parse_parent(&xml);
+    /// All external snapshots of a domain
+    fn external_snapshots(domain: &Domain) -> Result<Vec<Snapshot>, AppError> {
+        let snapshots = domain.list_all_snapshots(0)
+            .map_err(map_libvirt_error)?;
+        Ok(snapshots.iter()
+            .filter_map(|snap| Self::snapshot_to_model(snap, domain).ok())
+            .filter(|snapshot| snapshot.location == SnapshotLocation::External)
+            .collect())
+    }
 
-        // Determine snapshot state
-        let state = Self::parse_state(&xml);
+    /// Point a stopped VM's disk at another image in its persistent definition
+    fn set_disk_source(
+        libvirt: &LibvirtService,
+        domain: &Domain,
+        target: &str,
+        file: &str,
+        format: &str,
+    ) -> Result<(), AppError> {
+        let mut xml = DomainXml::from_domain_flags(domain, sys::VIR_DOMAIN_XML_INACTIVE | sys::VIR_DOMAIN_XML_SECURE)?;
+        let element = xml.disk_element_mut(target)
+            .ok_or_else(|| AppError::NotFound(format!("Disk {}", target)))?;
+        point_at(element, file, format);
+        Domain::define_xml(libvirt.get_connection(), &xml.to_xml())
+            .map_err(map_libvirt_error)?;
+        Ok(())
+    }
 
-        // Check if this is the current snapshot
-        let is_current = snap.is_current(0).unwrap_or(false);        Ok(Snapshot {
-            name,
-            description,
-            creation_time,
+    /// Rewrite the frozen image a snapshot recorded for one disk
+    fn redefine_backing(
+        domain: &Domain,
+        snapshot_name: &str,
+        target: &str,
+        file: &str,
+        format: &str,
+    ) -> Result<(), AppError> {
+        let snapshot = DomainSnapshot::lookup_by_name(domain, snapshot_name, 0)
+            .map_err(map_libvirt_error)?;
+        let mut root = XmlElement::parse(
+            &snapshot.get_xml_desc(sys::VIR_DOMAIN_SNAPSHOT_XML_SECURE).map_err(map_libvirt_error)?,
+        )?;
+        let disk = root.child_mut("domain")
+            .and_then(|domain| domain.child_mut("devices"))
+            .and_then(|devices| devices.children_named_mut("disk").find(|disk| {
+                disk.child("target").and_then(|t| t.attr("dev")) == Some(target)
+            }));
+        let Some(disk) = disk else {
+            return Ok(());
+        };
+        point_at(disk, file, format);
+
+        let mut flags = sys::VIR_DOMAIN_SNAPSHOT_CREATE_REDEFINE;
+        if snapshot.is_current(0).unwrap_or(false) {
+            flags |= sys::VIR_DOMAIN_SNAPSHOT_CREATE_CURRENT;
+        }
+        DomainSnapshot::create_xml(domain, &root.to_xml(), flags)
+            .map_err(map_libvirt_error)?;
+        Ok(())
+    }
+
+    /// Convert libvirt snapshot to our model
+    fn snapshot_to_model(snap: &DomainSnapshot, _domain: &Domain) -> Result<Snapshot, AppError> {
+        let xml = snap.get_xml_desc(0)
+            .map_err(map_libvirt_error)?;
+        let root = XmlElement::parse(&xml)?;
+        Ok(Self::parse_snapshot(&root, snap.is_current(0).unwrap_or(false)))
+    }
+
+    /// Read the model from a snapshot's XML
+    fn parse_snapshot(root: &XmlElement, is_current: bool) -> Snapshot {
+        let state = match root.child_text("state").as_deref() {
+            Some("running") => SnapshotState::Running,
+            Some("paused") => SnapshotState::Paused,
+            Some("shutoff") | Some("shut off") => SnapshotState::Shutoff,
+            _ => SnapshotState::DiskSnapshot,
+        };
+
+        // Disks and memory location, for the overlay chain of external snapshots
+        let disks = Self::parse_disks(root);
+        let memory_file = root.child("memory")
+            .filter(|memory| memory.attr("snapshot") == Some("external"))
+            .and_then(|memory| memory.attr("file"))
+            .map(String::from);
+        let location = if memory_file.is_some() || disks.iter().any(|disk| disk.snapshot == "external") {
+            SnapshotLocation::External
+        } else {
+            SnapshotLocation::Internal
+        };
+
+        Snapshot {
+            name: root.child_text("name").unwrap_or_default(),
+            description: root.child_text("description")
+                .map(|description| description.trim().to_string())
+                .filter(|description| !description.is_empty()),
+            creation_time: root.child_text("creationTime")
+                .and_then(|time| time.trim().parse().ok())
+                .unwrap_or(0),
             state,
-            parent,
+            parent: root.find("parent/name").map(|name| name.text()),
             is_current,
+            location,
+            disks,
+            memory_file,
+        }
+    }
+
+    /// Pair each `<disks>` entry with the disk's source in the saved definition,
+    /// which is the image the snapshot froze
+    fn parse_disks(root: &XmlElement) -> Vec<SnapshotDisk> {
+        let saved: Vec<Disk> = root.find("domain/devices")
+            .map(|devices| devices.children_named("disk").map(Disk::from_element).collect())
+            .unwrap_or_default();
+        let Some(disks) = root.child("disks") else {
+            return Vec::new();
+        };
+        disks.children_named("disk")
+            .map(|disk| {
+                let target = disk.attr("name").unwrap_or_default().to_string();
+                let snapshot = disk.attr("snapshot").unwrap_or("internal").to_string();
+                let overlay = disk.child("source")
+                    .filter(|_| snapshot == "external")
+                    .and_then(|source| source.attr("file"))
+                    .map(String::from);
+                let backing = saved.iter()
+                    .find(|saved| saved.target_dev == target)
+                    .and_then(|saved| saved.source_path())
+                    .map(String::from);
+                SnapshotDisk { target, snapshot, overlay, backing }
+            })
+            .collect()
+    }
+}
+
+/// Format and direct backing file of a disk image
+struct ImageInfo {
+    format: String,
+    backing: Option<String>,
+}
+
+/// Read an image header with `qemu-img info`, sharing the image if a VM has it open
+fn qemu_img_info(path: &str) -> Result<serde_json::Value, AppError> {
+    let output = Command::new("qemu-img")
+        .args(["info", "-U", "--output=json", path])
+        .output()
+        .map_err(|e| AppError::Other(format!("Failed to run qemu-img: {}", e)))?;
+    if !output.status.success() {
+        return Err(AppError::Other(format!(
+            "qemu-img info failed for {}: {}", path, String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| AppError::Other(format!("Invalid qemu-img info output for {}: {}", path, e)))
+}
+
+fn image_info(path: &str) -> Result<ImageInfo, AppError> {
+    let info = qemu_img_info(path)?;
+    Ok(ImageInfo {
+        format: info["format"].as_str().unwrap_or("raw").to_string(),
+        backing: info["full-backing-filename"].as_str()
+            .or(info["backing-filename"].as_str())
+            .map(String::from),
+    })
+}
+
+/// Whether `image` is `top` or one of its backing files
+fn chain_contains(top: &str, image: &str) -> bool {
+    let mut current = Some(top.to_string());
+    for _ in 0..MAX_CHAIN_DEPTH {
+        let Some(file) = current else {
+            return false;
+        };
+        if same_file(&file, image) {
+            return true;
+        }
+        current = image_info(&file).ok().and_then(|info| info.backing);
+    }
+    false
+}
+
+/// Every image the VM's snapshots and current disks refer to
+fn chain_images(snapshots: &[Snapshot], current: &DomainXml) -> Vec<String> {
+    let mut images: Vec<String> = snapshots.iter()
+        .flat_map(|snapshot| snapshot.disks.iter())
+        .flat_map(|disk| [disk.overlay.clone(), disk.backing.clone()])
+        .flatten()
+        .chain(current.disks().iter().filter_map(|disk| disk.source_path().map(String::from)))
+        .collect();
+    images.sort();
+    images.dedup();
+    images
+}
+
+/// The images among `images` directly backed by `image`
+fn consumers(image: &str, images: &[String]) -> Vec<String> {
+    images.iter()
+        .filter(|candidate| !same_file(candidate, image) && Path::new(candidate).exists())
+        .filter(|candidate| {
+            image_info(candidate).ok()
+                .and_then(|info| info.backing)
+                .is_some_and(|backing| same_file(&backing, image))
         })
+        .cloned()
+        .collect()
+}
+
+/// Whether an active layer left behind by a revert can be deleted: it must be
+/// an overlay this service created that nothing builds on or reverts to
+fn discardable(image: &str, images: &[String], snapshots: &[Snapshot]) -> bool {
+    let disks = || snapshots.iter().flat_map(|snapshot| snapshot.disks.iter());
+    let created = disks().any(|disk| disk.overlay.as_deref().is_some_and(|file| same_file(file, image)))
+        || Path::new(image).file_name().is_some_and(|name| name.to_string_lossy().contains(".revert-"));
+    let frozen = disks().any(|disk| disk.backing.as_deref().is_some_and(|file| same_file(file, image)));
+    created && !frozen && consumers(image, images).is_empty()
+}
+
+fn same_file(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
     }
+}
 
-    fn parse_creation_time(xml: &str) -> i64 {
-        // Look for: <creationTime>1234567890</creationTime>
-        if let Some(start) = xml.find("<creationTime>") {
-            let time_section = &xml[start + 14..];
-            if let Some(end) = time_section.find("</creationTime>") {
-                if let Ok(time) = time_section[..end].parse::<i64>() {
-                    return time;
-                }
-            }
+fn remove_image(path: &str) {
+    if let Err(e) = fs::remove_file(path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("Failed to remove {}: {}", path, e);
         }
-        0
     }
+}
 
-    fn parse_description(xml: &str) -> Option<String> {
-        if let Some(start) = xml.find("<description>") {
-            let desc_section = &xml[start + 13..];
-            if let Some(end) = desc_section.find("</description>") {
-                let desc = desc_section[..end].trim().to_string();
-                if !desc.is_empty() {
-                    return Some(desc);
-                }
+/// Source file of a disk that can take an external overlay
+fn overlay_source(disk: &Disk) -> Option<&str> {
+    if disk.device != "disk" || disk.disk_type != "file" || disk.readonly || disk.shareable {
+        return None;
+    }
+    disk.source.as_ref()?.file.as_deref()
+}
+
+/// Point a `<disk>` element at a file image
+fn point_at(disk: &mut XmlElement, file: &str, format: &str) {
+    disk.set_attr("type", "file");
+    disk.child_or_insert("driver").set_attr("type", format);
+    let source = disk.child_or_insert("source");
+    source.remove_attr("dev");
+    source.set_attr("file", file);
+    // A stale chain would override the image's own backing file
+    disk.remove_children("backingStore");
+}
+
+/// Overlay created for a revert before the VM is stopped
+struct StagedOverlay {
+    /// File qemu-img wrote
+    created: String,
+    /// Path the disk points at after the revert; differs from `created` when
+    /// an overlay is emptied in place
+    fresh: String,
+}
+
+/// External snapshot names become part of overlay and memory file names
+fn validate_file_name(name: &str) -> Result<(), AppError> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) || name.chars().any(char::is_control) {
+        return Err(AppError::InvalidConfig(format!(
+            "Snapshot name '{}' cannot be used in file names", name
+        )));
+    }
+    Ok(())
+}
+
+/// Overlay files of external snapshots live on the host, so merging and
+/// reverting them needs direct access to it
+fn require_local(libvirt: &LibvirtService, action: &str) -> Result<(), AppError> {
+    if !libvirt.is_local() {
+        return Err(AppError::Other(format!(
+            "External snapshots can only be {} on the local host, not {}", action, libvirt.get_uri()
+        )));
+    }
+    Ok(())
+}
+
+/// Commit `top` into `base` on a running VM's disk and wait for it.
+///
+/// An active commit is pivoted onto `base` once the two are in sync.
+fn block_commit(domain: &Domain, target: &str, base: &str, top: &str, active: bool) -> Result<(), AppError> {
+    let c_target = c_string(target)?;
+    let c_base = c_string(base)?;
+    let c_top = c_string(top)?;
+    let flags = if active { sys::VIR_DOMAIN_BLOCK_COMMIT_ACTIVE } else { 0 };
+    let ret = unsafe {
+        sys::virDomainBlockCommit(domain.as_ptr(), c_target.as_ptr(), c_base.as_ptr(), c_top.as_ptr(), 0, flags)
+    };
+    if ret < 0 {
+        return Err(AppError::LibvirtError(format!(
+            "Failed to start block commit on {}: {}", target, virt::error::Error::last_error()
+        )));
+    }
+    wait_block_job(domain, &c_target, active)
+}
+
+/// Pull the backing chain above `base` into a running VM's active layer and wait for it
+fn block_pull(domain: &Domain, target: &str, base: Option<&str>) -> Result<(), AppError> {
+    let c_target = c_string(target)?;
+    let c_base = base.map(c_string).transpose()?;
+    let ret = unsafe {
+        sys::virDomainBlockRebase(
+            domain.as_ptr(),
+            c_target.as_ptr(),
+            c_base.as_ref().map_or(std::ptr::null(), |base| base.as_ptr()),
+            0,
+            0,
+        )
+    };
+    if ret < 0 {
+        return Err(AppError::LibvirtError(format!(
+            "Failed to start block pull on {}: {}", target, virt::error::Error::last_error()
+        )));
+    }
+    wait_block_job(domain, &c_target, false)
+}
+
+/// Poll the block job on a disk until it is gone, pivoting when asked.
+///
+/// A job that stops making progress, or never becomes ready to pivot, is
+/// aborted after `BLOCK_JOB_STALL_TIMEOUT`.
+fn wait_block_job(domain: &Domain, target: &CString, pivot: bool) -> Result<(), AppError> {
+    let mut progress = (0, 0);
+    let mut progressed_at = Instant::now();
+    loop {
+        let mut info: sys::virDomainBlockJobInfo = unsafe { std::mem::zeroed() };
+        let ret = unsafe { sys::virDomainGetBlockJobInfo(domain.as_ptr(), target.as_ptr(), &mut info, 0) };
+        if ret < 0 {
+            return Err(AppError::LibvirtError(format!(
+                "Failed to query block job: {}", virt::error::Error::last_error()
+            )));
+        }
+        if ret == 0 {
+            return Ok(());
+        }
+        // Pivoting fails until qemu reports the job ready, shortly after it catches up
+        if pivot && info.end > 0 && info.cur == info.end {
+            let ret = unsafe {
+                sys::virDomainBlockJobAbort(domain.as_ptr(), target.as_ptr(), sys::VIR_DOMAIN_BLOCK_JOB_ABORT_PIVOT)
+            };
+            if ret == 0 {
+                return Ok(());
             }
         }
-        None
-    }
 
-    fn parse_parent(xml: &str) -> Option<String> {
-        if let Some(start) = xml.find("<parent>") {
-            let parent_section = &xml[start + 8..];
-            if let Some(name_start) = parent_section.find("<name>") {
-                let name_section = &parent_section[name_start + 6..];
-                if let Some(name_end) = name_section.find("</name>") {
-                    return Some(name_section[..name_end].to_string());
-                }
+        if (info.cur, info.end) != progress {
+            progress = (info.cur, info.end);
+            progressed_at = Instant::now();
+        } else if progressed_at.elapsed() >= BLOCK_JOB_STALL_TIMEOUT {
+            let ret = unsafe { sys::virDomainBlockJobAbort(domain.as_ptr(), target.as_ptr(), 0) };
+            if ret < 0 {
+                tracing::error!("Failed to abort stalled block job: {}", virt::error::Error::last_error());
             }
+            return Err(AppError::LibvirtError(format!(
+                "Block job on {} made no progress for {}s and was aborted",
+                target.to_string_lossy(), BLOCK_JOB_STALL_TIMEOUT.as_secs()
+            )));
         }
-        None
+        std::thread::sleep(BLOCK_JOB_POLL_INTERVAL);
     }
+}
 
-    fn parse_state(xml: &str) -> SnapshotState {
-        if xml.contains("<state>running</state>") {
-            SnapshotState::Running
-        } else if xml.contains("<state>paused</state>") {
-            SnapshotState::Paused
-        } else if xml.contains("<state>shutoff</state>") || xml.contains("<state>shut off</state>") {
-            SnapshotState::Shutoff
-        } else if xml.contains("<memory snapshot='no'/>") || xml.contains("<memory snapshot='external'/>") {
-            SnapshotState::DiskSnapshot
-        } else {
-            SnapshotState::DiskSnapshot
+fn c_string(value: &str) -> Result<CString, AppError> {
+    CString::new(value).map_err(|e| AppError::Other(format!("Invalid disk path: {}", e)))
+}
+
+/// Approximate host space a snapshot holds.
+///
+/// An external snapshot holds the overlays it created and its memory file.
+/// For internal snapshots qemu only reports the saved memory per image, so
+/// the disk clusters they keep alive are not counted and a disk-only
+/// internal snapshot has no size.
+fn snapshot_size(snapshot: &Snapshot, state_sizes: &mut HashMap<String, HashMap<String, u64>>) -> Option<u64> {
+    match snapshot.location {
+        SnapshotLocation::External => Some(
+            snapshot.disks.iter()
+                .filter_map(|disk| disk.overlay.as_deref())
+                .chain(snapshot.memory_file.as_deref())
+                .filter_map(|file| fs::metadata(file).ok())
+                .map(|metadata| metadata.len())
+                .sum(),
+        ),
+        SnapshotLocation::Internal => {
+            let total: u64 = snapshot.disks.iter()
+                .filter(|disk| disk.snapshot == "internal")
+                .filter_map(|disk| disk.backing.as_deref())
+                .filter_map(|image| {
+                    state_sizes.entry(image.to_string())
+                        .or_insert_with(|| internal_state_sizes(image))
+                        .get(&snapshot.name)
+                        .copied()
+                })
+                .sum();
+            (total > 0).then_some(total)
+        }
+    }
+}
+
+/// Saved memory size of each internal snapshot in an image
+fn internal_state_sizes(image: &str) -> HashMap<String, u64> {
+    let Ok(info) = qemu_img_info(image) else {
+        return HashMap::new();
+    };
+    info["snapshots"].as_array()
+        .map(|snapshots| {
+            snapshots.iter()
+                .filter_map(|snapshot| {
+                    Some((snapshot["name"].as_str()?.to_string(), snapshot["vm-state-size"].as_u64().unwrap_or(0)))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Nest snapshots under their parents, oldest first at every level
+fn build_tree(nodes: Vec<SnapshotNode>) -> Vec<SnapshotNode> {
+    let names: HashSet<String> = nodes.iter().map(|node| node.snapshot.name.clone()).collect();
+    let mut children: HashMap<Option<String>, Vec<SnapshotNode>> = HashMap::new();
+    for node in nodes {
+        // A snapshot whose parent is gone becomes a root
+        let parent = node.snapshot.parent.clone().filter(|parent| names.contains(parent));
+        children.entry(parent).or_default().push(node);
+    }
+
+    let mut roots = children.remove(&None).unwrap_or_default();
+    for root in roots.iter_mut() {
+        attach_children(root, &mut children);
+    }
+    roots.sort_by_key(|node| node.snapshot.creation_time);
+    roots
+}
+
+fn attach_children(node: &mut SnapshotNode, children: &mut HashMap<Option<String>, Vec<SnapshotNode>>) {
+    let Some(mut nodes) = children.remove(&Some(node.snapshot.name.clone())) else {
+        return;
+    };
+    for child in nodes.iter_mut() {
+        attach_children(child, children);
+    }
+    nodes.sort_by_key(|node| node.snapshot.creation_time);
+    node.children = nodes;
+}
+
+/// Top-level configuration elements keyed by name, numbered when repeated
+fn sections(domain: &XmlElement) -> Vec<(String, XmlElement)> {
+    keyed(domain.elements().filter(|element| !IGNORED_SECTIONS.contains(&element.name.as_str())), |element| {
+        element.name.clone()
+    })
+}
+
+/// Devices keyed by what identifies them across definitions
+fn devices(devices: &XmlElement) -> Vec<(String, XmlElement)> {
+    keyed(devices.elements(), |device| {
+        let identity = match device.name.as_str() {
+            "disk" => device.child("target").and_then(|target| target.attr("dev")).map(String::from),
+            "interface" => device.child("mac").and_then(|mac| mac.attr("address")).map(String::from),
+            "controller" => Some(format!(
+                "{} {}", device.attr("type").unwrap_or_default(), device.attr("index").unwrap_or("0")
+            )),
+            "graphics" | "video" => device.attr("type")
+                .or_else(|| device.child("model").and_then(|model| model.attr("type")))
+                .map(String::from),
+            _ => None,
+        };
+        match identity {
+            Some(identity) => format!("{} {}", device.name, identity),
+            None => device.name.clone(),
+        }
+    })
+}
+
+/// Pair elements with a key, numbering keys that repeat
+fn keyed<'a>(
+    elements: impl Iterator<Item = &'a XmlElement>,
+    key: impl Fn(&XmlElement) -> String,
+) -> Vec<(String, XmlElement)> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    elements
+        .map(|element| {
+            let base = key(element);
+            let count = seen.entry(base.clone()).or_insert(0);
+            *count += 1;
+            let key = if *count > 1 { format!("{} {}", base, count) } else { base };
+            (key, normalize(element))
+        })
+        .collect()
+}
+
+/// Drop runtime details the live XML of a running VM carries, so a snapshot
+/// taken while running compares cleanly with the inactive definition
+fn normalize(element: &XmlElement) -> XmlElement {
+    let mut element = element.clone();
+    element.retain_elements(|child| child.name != "alias" && child.name != "backingStore");
+    match element.name.as_str() {
+        "interface" => element.remove_children("target"),
+        "graphics" if element.attr("autoport") == Some("yes") => {
+            element.remove_attr("port");
+            element.remove_attr("websocket");
+        }
+        "serial" | "console" if element.attr("type") == Some("pty") => {
+            element.remove_attr("tty");
+            element.remove_children("source");
+        }
+        _ => {}
+    }
+    element
+}
+
+/// Serialize without indentation, which differs between a definition saved
+/// inside snapshot XML and a standalone one
+fn canonical(element: &XmlElement) -> String {
+    fn strip(element: &mut XmlElement) {
+        element.children.retain(|node| !matches!(node, XmlNode::Text(text) if text.trim().is_empty()));
+        for child in element.elements_mut() {
+            strip(child);
+        }
+    }
+    let mut element = element.clone();
+    strip(&mut element);
+    element.to_xml()
+}
+
+fn diff_elements(
+    section: &str,
+    saved: &[(String, XmlElement)],
+    current: &[(String, XmlElement)],
+    changes: &mut Vec<ConfigChange>,
+) {
+    for (key, before) in saved {
+        let after = current.iter().find(|(other, _)| other == key).map(|(_, element)| element);
+        let kind = match after {
+            None => ConfigChangeKind::Removed,
+            Some(after) if canonical(after) != canonical(before) => ConfigChangeKind::Modified,
+            Some(_) => continue,
+        };
+        changes.push(ConfigChange {
+            section: section.to_string(),
+            item: key.clone(),
+            kind,
+            snapshot: Some(before.to_xml()),
+            current: after.map(XmlElement::to_xml),
+        });
+    }
+    for (key, after) in current {
+        if !saved.iter().any(|(other, _)| other == key) {
+            changes.push(ConfigChange {
+                section: section.to_string(),
+                item: key.clone(),
+                kind: ConfigChangeKind::Added,
+                snapshot: None,
+                current: Some(after.to_xml()),
+            });
         }
     }
 }
@@ -3,7 +3,12 @@ use virt::storage_vol::StorageVol;
 use virt::sys;
 use crate::models::storage::{StoragePool as StoragePoolModel, Volume, VolumeConfig, PoolState, PoolType};
 use crate::services::libvirt::LibvirtService;
+use crate::services::job_service::JobHandle;
 use crate::utils::error::{AppError, map_libvirt_error};
+use crate::utils::xml::XmlElement;
+
+/// Chunk size for volume upload/download streams
+const STREAM_CHUNK_SIZE: usize = 1024 * 1024;
 
 /// StorageService provides storage pool and volume management operations
 pub struct StorageService;
@@ -80,6 +85,7 @@ impl StorageService {
             available_bytes,
             path,
             autostart,
+            connection_id: None,
         })
     }
 
@@ -651,15 +657,19 @@ impl StorageService {
     }
 
     /// Upload a file to a storage volume
-    /// This creates or overwrites a volume with the contents of a local file
+    /// This creates or overwrites a volume with the contents of a local file.
+    /// Data is sent over a libvirt stream so remote pools work too; when run
+    /// as a job, cancelling aborts the stream and removes the partial volume.
     pub fn upload_volume(
         libvirt: &LibvirtService,
         pool_id: &str,
         volume_name: &str,
         source_path: &str,
         format: Option<&str>,
+        job: Option<&JobHandle>,
     ) -> Result<Volume, AppError> {
         use std::fs;
+        use std::io::Read;
         use std::path::Path;
 
         tracing::info!("Uploading file {} to volume {} in pool {}", source_path, volume_name, pool_id);
@@ -704,42 +714,73 @@ impl StorageService {
         }
 
         // Create volume with appropriate size
-        let volume_xml = format!(
-            r#"<volume type='file'>
-  <name>{}</name>
-  <capacity unit='bytes'>{}</capacity>
-  <target>
-    <format type='{}'/>
-  </target>
-</volume>"#,
-            volume_name, file_size, vol_format
-        );
+        let volume_xml = XmlElement::new("volume")
+            .with_attr("type", "file")
+            .with_child(XmlElement::new("name").with_text(volume_name))
+            .with_child(XmlElement::new("capacity").with_attr("unit", "bytes").with_text(file_size))
+            .with_child(XmlElement::new("target")
+                .with_child(XmlElement::new("format").with_attr("type", vol_format)))
+            .to_xml();
 
         let volume = StorageVol::create_xml(&pool, &volume_xml, 0)
             .map_err(|e| AppError::LibvirtError(format!("Failed to create volume: {}", e)))?;
 
-        // Get the volume path
-        let vol_path = volume.get_path()
-            .map_err(map_libvirt_error)?;
+        if let Some(job) = job {
+            job.set_step(format!("Uploading {}", volume_name));
+        }
 
-        // Copy the file to the volume location
-        fs::copy(source, &vol_path)
-            .map_err(|e| AppError::Other(format!("Failed to copy file to volume: {}", e)))?;
+        let mut file = fs::File::open(source)
+            .map_err(|e| AppError::Other(format!("Failed to open source file: {}", e)))?;
+        let stream = VolumeStream::new(libvirt)?;
+        stream.start_upload(&volume, file_size)?;
+
+        let mut buffer = vec![0u8; STREAM_CHUNK_SIZE];
+        let mut sent: u64 = 0;
+        let transfer = loop {
+            if let Some(job) = job {
+                if job.is_cancelled() {
+                    break Err(AppError::Cancelled);
+                }
+            }
+            let read = match file.read(&mut buffer) {
+                Ok(0) => break Ok(()),
+                Ok(read) => read,
+                Err(e) => break Err(AppError::Other(format!("Failed to read source file: {}", e))),
+            };
+            if let Err(e) = stream.send(&buffer[..read]) {
+                break Err(e);
+            }
+            sent += read as u64;
+            if let Some(job) = job {
+                job.set_bytes(sent, file_size);
+            }
+        };
 
-        tracing::info!("Successfully uploaded {} bytes to volume {}", file_size, volume_name);
+        if let Err(e) = transfer.and_then(|_| stream.finish()) {
+            if let Err(delete_err) = volume.delete(0) {
+                tracing::warn!("Failed to remove partial volume {}: {}", volume_name, delete_err);
+            }
+            return Err(e);
+        }
+
+        tracing::info!("Successfully uploaded {} bytes to volume {}", sent, volume_name);
 
         // Convert to our Volume model
         Self::volume_to_model(&volume, &pool_name)
     }
 
     /// Download a volume to a local file
+    /// Data is received over a libvirt stream so remote pools work too; when
+    /// run as a job, cancelling aborts the stream and removes the partial file.
     pub fn download_volume(
         libvirt: &LibvirtService,
         pool_id: &str,
         volume_name: &str,
         dest_path: &str,
+        job: Option<&JobHandle>,
     ) -> Result<u64, AppError> {
         use std::fs;
+        use std::io::Write;
         use std::path::Path;
 
         tracing::info!("Downloading volume {} from pool {} to {}", volume_name, pool_id, dest_path);
@@ -751,13 +792,9 @@ impl StorageService {
         let volume = StorageVol::lookup_by_name(&pool, volume_name)
             .map_err(|_| AppError::LibvirtError(format!("Volume not found: {}", volume_name)))?;
 
-        let vol_path = volume.get_path()
-            .map_err(map_libvirt_error)?;
-
-        let source = Path::new(&vol_path);
-        if !source.exists() {
-            return Err(AppError::Other(format!("Volume file not found: {}", vol_path)));
-        }
+        // Without the sparse flag the stream carries the full capacity
+        let info = volume.get_info().map_err(map_libvirt_error)?;
+        let total_bytes = info.capacity;
 
         let dest = Path::new(dest_path);
 
@@ -767,12 +804,45 @@ impl StorageService {
                 .map_err(|e| AppError::Other(format!("Failed to create destination directory: {}", e)))?;
         }
 
-        // Copy the volume to destination
-        let bytes_copied = fs::copy(source, dest)
-            .map_err(|e| AppError::Other(format!("Failed to copy volume to destination: {}", e)))?;
+        if let Some(job) = job {
+            job.set_step(format!("Downloading {}", volume_name));
+        }
+
+        let mut file = fs::File::create(dest)
+            .map_err(|e| AppError::Other(format!("Failed to create destination file: {}", e)))?;
+        let stream = VolumeStream::new(libvirt)?;
+        stream.start_download(&volume)?;
+
+        let mut buffer = vec![0u8; STREAM_CHUNK_SIZE];
+        let mut received: u64 = 0;
+        let transfer = loop {
+            if let Some(job) = job {
+                if job.is_cancelled() {
+                    break Err(AppError::Cancelled);
+                }
+            }
+            let read = match stream.recv(&mut buffer) {
+                Ok(0) => break Ok(()),
+                Ok(read) => read,
+                Err(e) => break Err(e),
+            };
+            if let Err(e) = file.write_all(&buffer[..read]) {
+                break Err(AppError::Other(format!("Failed to write destination file: {}", e)));
+            }
+            received += read as u64;
+            if let Some(job) = job {
+                job.set_bytes(received, total_bytes.max(received));
+            }
+        };
+
+        if let Err(e) = transfer.and_then(|_| stream.finish()) {
+            drop(file);
+            let _ = fs::remove_file(dest);
+            return Err(e);
+        }
 
-        tracing::info!("Successfully downloaded {} bytes from volume {}", bytes_copied, volume_name);
-        Ok(bytes_copied)
+        tracing::info!("Successfully downloaded {} bytes from volume {}", received, volume_name);
+        Ok(received)
     }
 
     /// Get the path of a volume (useful for direct file operations)
@@ -791,3 +861,82 @@ impl StorageService {
         volume.get_path().map_err(map_libvirt_error)
     }
 }
+
+/// Blocking libvirt stream used to move volume data to and from the host
+struct VolumeStream {
+    ptr: sys::virStreamPtr,
+}
+
+impl VolumeStream {
+    fn new(libvirt: &LibvirtService) -> Result<Self, AppError> {
+        let ptr = unsafe { sys::virStreamNew(libvirt.get_connection().as_ptr(), 0) };
+        if ptr.is_null() {
+            return Err(map_libvirt_error(virt::error::Error::last_error()));
+        }
+        Ok(Self { ptr })
+    }
+
+    /// Attach the stream to a volume upload of `length` bytes
+    fn start_upload(&self, volume: &StorageVol, length: u64) -> Result<(), AppError> {
+        let ret = unsafe { sys::virStorageVolUpload(volume.as_ptr(), self.ptr, 0, length, 0) };
+        Self::check(ret)
+    }
+
+    /// Attach the stream to a download of the whole volume
+    fn start_download(&self, volume: &StorageVol) -> Result<(), AppError> {
+        let ret = unsafe { sys::virStorageVolDownload(volume.as_ptr(), self.ptr, 0, 0, 0) };
+        Self::check(ret)
+    }
+
+    /// Send all of `data`
+    fn send(&self, mut data: &[u8]) -> Result<(), AppError> {
+        while !data.is_empty() {
+            let sent = unsafe {
+                sys::virStreamSend(self.ptr, data.as_ptr() as *const std::os::raw::c_char, data.len())
+            };
+            if sent < 0 {
+                return Err(map_libvirt_error(virt::error::Error::last_error()));
+            }
+            data = &data[sent as usize..];
+        }
+        Ok(())
+    }
+
+    /// Receive into `buffer`; returns 0 at end of stream
+    fn recv(&self, buffer: &mut [u8]) -> Result<usize, AppError> {
+        let received = unsafe {
+            sys::virStreamRecv(self.ptr, buffer.as_mut_ptr() as *mut std::os::raw::c_char, buffer.len())
+        };
+        if received < 0 {
+            return Err(map_libvirt_error(virt::error::Error::last_error()));
+        }
+        Ok(received as usize)
+    }
+
+    /// Complete the transfer; on failure or when dropped unfinished the stream is aborted
+    fn finish(self) -> Result<(), AppError> {
+        let ret = unsafe { sys::virStreamFinish(self.ptr) };
+        let result = Self::check(ret);
+        if result.is_ok() {
+            unsafe { sys::virStreamFree(self.ptr) };
+            std::mem::forget(self);
+        }
+        result
+    }
+
+    fn check(ret: i32) -> Result<(), AppError> {
+        if ret < 0 {
+            return Err(map_libvirt_error(virt::error::Error::last_error()));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for VolumeStream {
+    fn drop(&mut self) {
+        unsafe {
+            sys::virStreamAbort(self.ptr);
+            sys::virStreamFree(self.ptr);
+        }
+    }
+}
@@ -1,7 +1,11 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use virt::domain::Domain;
 use virt::sys;
 use crate::models::vm::{VM, VmState, HostNetworkInterface};
+use crate::models::domain_xml::{self, DomainXml, XmlModel};
+use crate::utils::xml::{XmlElement, XmlNode};
 use crate::services::libvirt::LibvirtService;
+use crate::services::job_service::{self, JobHandle};
 use crate::utils::error::{AppError, map_libvirt_error};
 
 /// VmService provides VM management operations
@@ -21,7 +25,7 @@ impl VmService {
             .map_err(map_libvirt_error)?;
 
         for domain in domains {
-            match Self::domain_to_vm(&domain) {
+            match Self::domain_to_vm(libvirt, &domain) {
                 Ok(vm) => vms.push(vm),
                 Err(e) => {
                     tracing::warn!("Failed to convert domain to VM: {}", e);
@@ -42,11 +46,11 @@ impl VmService {
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        Self::domain_to_vm(&domain)
+        Self::domain_to_vm(libvirt, &domain)
     }
 
     /// Convert a libvirt Domain to our VM model
-    fn domain_to_vm(domain: &Domain) -> Result<VM, AppError> {
+    fn domain_to_vm(libvirt: &LibvirtService, domain: &Domain) -> Result<VM, AppError> {
         let uuid = domain.get_uuid_string()
             .map_err(map_libvirt_error)?;
 
@@ -63,28 +67,29 @@ impl VmService {
         let max_memory_mb = info.max_mem / 1024; // Convert from KiB to MiB
         let memory_mb = info.memory / 1024; // Current memory in MiB
 
+        // Parse the definition once for everything read from XML
+        let xml = DomainXml::from_domain(domain)?;
+
         // Extract network interfaces from XML
-        let network_interfaces = Self::get_vm_network_interfaces(domain).unwrap_or_default();
+        let network_interfaces = Self::get_vm_network_interfaces(libvirt, &xml, &name);
 
         // Extract disk information from XML
-        let disks = Self::get_vm_disks(domain).unwrap_or_default();
+        let disks = Self::get_vm_disks(&xml);
 
         // Calculate total disk size using virsh (works with proper permissions)
-        let disk_size_gb = Self::calculate_disk_size_from_domain(domain);
+        let disk_size_gb = Self::calculate_disk_size_from_domain(libvirt, domain);
 
         // Extract tags from metadata
-        let tags = Self::get_vm_tags(domain).unwrap_or_default();
+        let tags = xml.tags();
 
         // Extract firmware, TPM, and chipset from XML
-        let (firmware, tpm_enabled, chipset) = Self::get_vm_hardware_config(domain).unwrap_or_else(|_| {
-            ("bios".to_string(), false, "pc".to_string())
-        });
+        let (firmware, tpm_enabled, chipset) = Self::get_vm_hardware_config(&xml);
 
         // Extract CPU topology from XML
-        let (cpu_sockets, cpu_cores, cpu_threads) = Self::get_cpu_topology(domain).unwrap_or_else(|_| {
-            // If no topology found, default to 1 socket, N cores, 1 thread
-            (1, cpu_count, 1)
-        });
+        // If no topology found, default to 1 socket, N cores, 1 thread
+        let (cpu_sockets, cpu_cores, cpu_threads) = xml.cpu().topology
+            .map(|topology| (topology.sockets, topology.cores, topology.threads))
+            .unwrap_or((1, cpu_count, 1));
 
         Ok(VM {
             id: uuid,
@@ -104,6 +109,7 @@ impl VmService {
             cpu_cores,
             cpu_threads,
             cloud_init: None,
+            connection_id: None,
         })
     }
 
@@ -112,15 +118,18 @@ impl VmService {
         let (state, _reason) = domain.get_state()
             .map_err(map_libvirt_error)?;
 
-        let vm_state = match state {
+        Ok(Self::state_from_libvirt(state))
+    }
+
+    /// Map a raw libvirt domain state to a VmState
+    pub fn state_from_libvirt(state: sys::virDomainState) -> VmState {
+        match state {
             sys::VIR_DOMAIN_RUNNING => VmState::Running,
             sys::VIR_DOMAIN_PAUSED => VmState::Paused,
             sys::VIR_DOMAIN_SHUTDOWN | sys::VIR_DOMAIN_SHUTOFF | sys::VIR_DOMAIN_CRASHED => VmState::Stopped,
             sys::VIR_DOMAIN_PMSUSPENDED => VmState::Suspended,
             _ => VmState::Stopped,
-        };
-
-        Ok(vm_state)
+        }
     }
 
     /// Start a VM
@@ -357,183 +366,60 @@ impl VmService {
 
     /// Extract disk paths from domain XML
     fn get_vm_disk_paths(domain: &Domain) -> Result<Vec<String>, AppError> {
-        let xml = domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
+        let xml = DomainXml::from_domain(domain)?;
 
-        let mut disk_paths = Vec::new();
-
-        // Simple XML parsing to find disk source files
-        for line in xml.lines() {
-            if line.contains("<source file=") {
-                if let Some(start) = line.find("file=\"") {
-                    let start_idx = start + 6;
-                    if let Some(end) = line[start_idx..].find('\"') {
-                        let path = line[start_idx..start_idx + end].to_string();
-                        disk_paths.push(path);
-                    }
-                }
-            }
-        }
-
-        Ok(disk_paths)
+        // Only writable disks: install media attached as cdroms is not owned by the VM
+        Ok(xml.disks()
+            .iter()
+            .filter(|disk| disk.device != "cdrom" && disk.disk_type == "file")
+            .filter_map(|disk| disk.source_path().map(String::from))
+            .collect())
     }
 
     /// Get network interfaces from a VM
-    fn get_vm_network_interfaces(domain: &Domain) -> Result<Vec<crate::models::vm::NetworkInterface>, AppError> {
-        let xml = domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
-
-        let vm_name = domain.get_name().map_err(map_libvirt_error)?;
-
+    fn get_vm_network_interfaces(libvirt: &LibvirtService, xml: &DomainXml, vm_name: &str) -> Vec<crate::models::vm::NetworkInterface> {
         // Get IP addresses from virsh domifaddr
-        let ip_map = Self::get_interface_ips(&vm_name);
+        let ip_map = Self::get_interface_ips(libvirt, vm_name);
 
         let mut interfaces = Vec::new();
-        let mut in_interface = false;
-        let mut in_bandwidth = false;
-        let mut current_mac = String::new();
-        let mut current_network = String::new();
-        let mut current_target = String::new();
-        let mut current_model = String::new();
-        // QoS settings
-        let mut inbound_average: Option<u64> = None;
-        let mut inbound_peak: Option<u64> = None;
-        let mut inbound_burst: Option<u64> = None;
-        let mut outbound_average: Option<u64> = None;
-        let mut outbound_peak: Option<u64> = None;
-        let mut outbound_burst: Option<u64> = None;
-
-        for line in xml.lines() {
-            let trimmed = line.trim();
-
-            // Start of interface element (type='network' or type='bridge')
-            if trimmed.starts_with("<interface type=") {
-                in_interface = true;
-                in_bandwidth = false;
-                current_mac = String::new();
-                current_network = String::new();
-                current_target = String::new();
-                current_model = String::new();
-                inbound_average = None;
-                inbound_peak = None;
-                inbound_burst = None;
-                outbound_average = None;
-                outbound_peak = None;
-                outbound_burst = None;
-            }
-
-            // Extract MAC address (handle both single and double quotes)
-            if in_interface && trimmed.contains("<mac address=") {
-                let mac = Self::extract_xml_attribute(trimmed, "address");
-                if let Some(m) = mac {
-                    current_mac = m;
-                }
-            }
-
-            // Extract network name from source (network='...' or bridge='...')
-            if in_interface && trimmed.contains("<source") {
-                // Try network first
-                if let Some(net) = Self::extract_xml_attribute(trimmed, "network") {
-                    current_network = net;
-                } else if let Some(br) = Self::extract_xml_attribute(trimmed, "bridge") {
-                    current_network = format!("bridge:{}", br);
-                }
-            }
-
-            // Extract target device name (e.g., vnet0)
-            if in_interface && trimmed.contains("<target dev=") {
-                if let Some(dev) = Self::extract_xml_attribute(trimmed, "dev") {
-                    current_target = dev;
-                }
-            }
-
-            // Extract model type (e.g., virtio, e1000)
-            if in_interface && trimmed.contains("<model type=") {
-                if let Some(model) = Self::extract_xml_attribute(trimmed, "type") {
-                    current_model = model;
-                }
-            }
-
-            // Bandwidth/QoS section
-            if in_interface && trimmed.starts_with("<bandwidth") {
-                in_bandwidth = true;
-            }
-
-            if in_bandwidth && trimmed.starts_with("<inbound") {
-                if let Some(avg) = Self::extract_xml_attribute(trimmed, "average") {
-                    inbound_average = avg.parse().ok();
-                }
-                if let Some(peak) = Self::extract_xml_attribute(trimmed, "peak") {
-                    inbound_peak = peak.parse().ok();
-                }
-                if let Some(burst) = Self::extract_xml_attribute(trimmed, "burst") {
-                    inbound_burst = burst.parse().ok();
-                }
-            }
-
-            if in_bandwidth && trimmed.starts_with("<outbound") {
-                if let Some(avg) = Self::extract_xml_attribute(trimmed, "average") {
-                    outbound_average = avg.parse().ok();
-                }
-                if let Some(peak) = Self::extract_xml_attribute(trimmed, "peak") {
-                    outbound_peak = peak.parse().ok();
-                }
-                if let Some(burst) = Self::extract_xml_attribute(trimmed, "burst") {
-                    outbound_burst = burst.parse().ok();
-                }
-            }
-
-            if in_bandwidth && trimmed.starts_with("</bandwidth") {
-                in_bandwidth = false;
-            }
-
-            // End of interface - save it
-            if trimmed == "</interface>" && in_interface {
-                if !current_mac.is_empty() {
-                    // Look up IP address by MAC address
-                    let ip_address = ip_map.get(&current_mac.to_lowercase()).cloned();
+        for iface in xml.interfaces() {
+            let mac = match iface.mac {
+                Some(ref mac) if !mac.is_empty() => mac.clone(),
+                _ => continue,
+            };
 
-                    interfaces.push(crate::models::vm::NetworkInterface {
-                        name: if current_target.is_empty() {
-                            format!("nic{}", interfaces.len() + 1)
-                        } else {
-                            current_target.clone()
-                        },
-                        mac_address: current_mac.clone(),
-                        mac: current_mac.clone(),  // Alias for frontend compatibility
-                        network: if current_network.is_empty() {
-                            "unknown".to_string()
-                        } else {
-                            current_network.clone()
-                        },
-                        ip_address,
-                        model_type: if current_model.is_empty() {
-                            None
-                        } else {
-                            Some(current_model.clone())
-                        },
-                        inbound_average,
-                        inbound_peak,
-                        inbound_burst,
-                        outbound_average,
-                        outbound_peak,
-                        outbound_burst,
-                    });
-                }
-                in_interface = false;
-                in_bandwidth = false;
-            }
+            // Look up IP address by MAC address
+            let ip_address = ip_map.get(&mac.to_lowercase()).cloned();
+            let bandwidth = iface.bandwidth.clone().unwrap_or_default();
+            let inbound = bandwidth.inbound.unwrap_or_default();
+            let outbound = bandwidth.outbound.unwrap_or_default();
+
+            interfaces.push(crate::models::vm::NetworkInterface {
+                name: iface.target_dev.clone()
+                    .unwrap_or_else(|| format!("nic{}", interfaces.len() + 1)),
+                mac_address: mac.clone(),
+                mac,  // Alias for frontend compatibility
+                network: iface.source_label().unwrap_or_else(|| "unknown".to_string()),
+                ip_address,
+                model_type: iface.model.clone(),
+                inbound_average: inbound.average,
+                inbound_peak: inbound.peak,
+                inbound_burst: inbound.burst,
+                outbound_average: outbound.average,
+                outbound_peak: outbound.peak,
+                outbound_burst: outbound.burst,
+            });
         }
 
-        Ok(interfaces)
+        interfaces
     }
 
     /// Get IP addresses for network interfaces using virsh domifaddr
-    fn get_interface_ips(vm_name: &str) -> std::collections::HashMap<String, String> {
+    fn get_interface_ips(libvirt: &LibvirtService, vm_name: &str) -> std::collections::HashMap<String, String> {
         let mut ip_map = std::collections::HashMap::new();
 
         // Try virsh domifaddr (uses QEMU guest agent or lease info)
-        let output = std::process::Command::new("virsh")
+        let output = libvirt.virsh()
             .args(["domifaddr", vm_name])
             .output();
 
@@ -574,188 +460,36 @@ impl VmService {
         ip_map
     }
 
-    /// Helper to extract an XML attribute value (handles both single and double quotes)
-    fn extract_xml_attribute(line: &str, attr_name: &str) -> Option<String> {
-        // Try single quotes first
-        let single_pattern = format!("{}='", attr_name);
-        if let Some(start) = line.find(&single_pattern) {
-            let start_idx = start + single_pattern.len();
-            if let Some(end) = line[start_idx..].find('\'') {
-                return Some(line[start_idx..start_idx + end].to_string());
-            }
-        }
-
-        // Try double quotes
-        let double_pattern = format!("{}=\"", attr_name);
-        if let Some(start) = line.find(&double_pattern) {
-            let start_idx = start + double_pattern.len();
-            if let Some(end) = line[start_idx..].find('\"') {
-                return Some(line[start_idx..start_idx + end].to_string());
-            }
-        }
-
-        None
-    }
-
     /// Get disk devices from a VM
-    fn get_vm_disks(domain: &Domain) -> Result<Vec<crate::models::vm::DiskDevice>, AppError> {
-        let xml = domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
-
-        let mut disks = Vec::new();
-
-        // Helper to extract attribute value from a line
-        let extract_attr = |line: &str, attr: &str| -> Option<String> {
-            // Try single quotes first
-            let pattern1 = format!("{}='", attr);
-            let pattern2 = format!("{}=\"", attr);
-
-            if let Some(start) = line.find(&pattern1) {
-                let start_idx = start + pattern1.len();
-                if let Some(end) = line[start_idx..].find('\'') {
-                    return Some(line[start_idx..start_idx + end].to_string());
-                }
-            }
-            if let Some(start) = line.find(&pattern2) {
-                let start_idx = start + pattern2.len();
-                if let Some(end) = line[start_idx..].find('\"') {
-                    return Some(line[start_idx..start_idx + end].to_string());
-                }
-            }
-            None
-        };
-
-        // Track current disk being parsed
-        struct DiskBuilder {
-            device: String,
-            path: String,
-            disk_type: String,
-            bus: String,
-            cache: Option<String>,
-            io: Option<String>,
-            discard: Option<String>,
-            detect_zeroes: Option<String>,
-            read_iops_sec: Option<u64>,
-            write_iops_sec: Option<u64>,
-            read_bytes_sec: Option<u64>,
-            write_bytes_sec: Option<u64>,
-        }
-
-        let mut current_disk: Option<DiskBuilder> = None;
-        let mut in_iotune = false;
-
-        for line in xml.lines() {
-            let trimmed = line.trim();
-
-            // Start of a disk device (only device='disk', skip cdrom)
-            if trimmed.starts_with("<disk type=") && trimmed.contains("device='disk'") {
-                let disk_type = extract_attr(trimmed, "type").unwrap_or_else(|| "file".to_string());
-                current_disk = Some(DiskBuilder {
-                    device: String::new(),
-                    path: String::new(),
-                    disk_type,
-                    bus: String::new(),
-                    cache: None,
-                    io: None,
-                    discard: None,
-                    detect_zeroes: None,
-                    read_iops_sec: None,
-                    write_iops_sec: None,
-                    read_bytes_sec: None,
-                    write_bytes_sec: None,
-                });
-            }
-
-            if current_disk.is_none() {
-                continue;
-            }
-
-            // Extract driver attributes (cache, io, discard, detect_zeroes)
-            if trimmed.starts_with("<driver ") {
-                if let Some(ref mut disk) = current_disk {
-                    disk.cache = extract_attr(trimmed, "cache");
-                    disk.io = extract_attr(trimmed, "io");
-                    disk.discard = extract_attr(trimmed, "discard");
-                    disk.detect_zeroes = extract_attr(trimmed, "detect_zeroes");
-                }
-            }
-
-            // Extract source path
-            if trimmed.contains("<source file=") {
-                if let Some(ref mut disk) = current_disk {
-                    if let Some(path) = extract_attr(trimmed, "file") {
-                        disk.path = path;
-                    }
-                }
-            }
-
-            // Extract target device and bus
-            if trimmed.contains("<target dev=") {
-                if let Some(ref mut disk) = current_disk {
-                    if let Some(dev) = extract_attr(trimmed, "dev") {
-                        disk.device = dev;
-                    }
-                    disk.bus = extract_attr(trimmed, "bus").unwrap_or_else(|| "virtio".to_string());
-                }
-            }
-
-            // Parse iotune section
-            if trimmed == "<iotune>" {
-                in_iotune = true;
-            }
-            if trimmed == "</iotune>" {
-                in_iotune = false;
-            }
-
-            if in_iotune {
-                if let Some(ref mut disk) = current_disk {
-                    if trimmed.starts_with("<read_iops_sec>") {
-                        let val = trimmed.replace("<read_iops_sec>", "").replace("</read_iops_sec>", "");
-                        disk.read_iops_sec = val.parse().ok();
-                    }
-                    if trimmed.starts_with("<write_iops_sec>") {
-                        let val = trimmed.replace("<write_iops_sec>", "").replace("</write_iops_sec>", "");
-                        disk.write_iops_sec = val.parse().ok();
-                    }
-                    if trimmed.starts_with("<read_bytes_sec>") {
-                        let val = trimmed.replace("<read_bytes_sec>", "").replace("</read_bytes_sec>", "");
-                        disk.read_bytes_sec = val.parse().ok();
-                    }
-                    if trimmed.starts_with("<write_bytes_sec>") {
-                        let val = trimmed.replace("<write_bytes_sec>", "").replace("</write_bytes_sec>", "");
-                        disk.write_bytes_sec = val.parse().ok();
-                    }
-                }
-            }
-
-            // End of disk device - save it
-            if trimmed == "</disk>" {
-                if let Some(disk) = current_disk.take() {
-                    if !disk.device.is_empty() && !disk.path.is_empty() {
-                        disks.push(crate::models::vm::DiskDevice {
-                            device: disk.device,
-                            path: disk.path,
-                            disk_type: disk.disk_type,
-                            bus: disk.bus,
-                            cache: disk.cache,
-                            io: disk.io,
-                            discard: disk.discard,
-                            detect_zeroes: disk.detect_zeroes,
-                            read_iops_sec: disk.read_iops_sec,
-                            write_iops_sec: disk.write_iops_sec,
-                            read_bytes_sec: disk.read_bytes_sec,
-                            write_bytes_sec: disk.write_bytes_sec,
-                        });
-                    }
-                }
-            }
-        }
-
-        Ok(disks)
+    fn get_vm_disks(xml: &DomainXml) -> Vec<crate::models::vm::DiskDevice> {
+        // Only device='disk' entries backed by a path; cdroms are reported separately
+        xml.disks()
+            .into_iter()
+            .filter(|disk| disk.device == "disk" && !disk.target_dev.is_empty())
+            .filter_map(|disk| {
+                let path = disk.source_path()?.to_string();
+                let driver = disk.driver.clone().unwrap_or_default();
+                let iotune = disk.iotune.clone().unwrap_or_default();
+                Some(crate::models::vm::DiskDevice {
+                    device: disk.target_dev,
+                    path,
+                    disk_type: disk.disk_type,
+                    bus: disk.target_bus.unwrap_or_else(|| "virtio".to_string()),
+                    cache: driver.cache,
+                    io: driver.io,
+                    discard: driver.discard,
+                    detect_zeroes: driver.detect_zeroes,
+                    read_iops_sec: iotune.read_iops_sec,
+                    write_iops_sec: iotune.write_iops_sec,
+                    read_bytes_sec: iotune.read_bytes_sec,
+                    write_bytes_sec: iotune.write_bytes_sec,
+                })
+            })
+            .collect()
     }
 
     /// Calculate total disk size using virsh domblkinfo (works through libvirt with proper permissions)
-    fn calculate_disk_size_from_domain(domain: &Domain) -> u64 {
+    fn calculate_disk_size_from_domain(libvirt: &LibvirtService, domain: &Domain) -> u64 {
         let vm_name = match domain.get_name() {
             Ok(name) => name,
             Err(_) => return 0,
@@ -764,7 +498,7 @@ impl VmService {
         let mut total_bytes: u64 = 0;
 
         // Get list of block devices using virsh domblklist
-        let output = std::process::Command::new("virsh")
+        let output = libvirt.virsh()
             .args(["domblklist", &vm_name, "--details"])
             .output();
 
@@ -782,7 +516,7 @@ impl VmService {
                         // Only count disks, not cdroms
                         if device_type == "file" || device_type == "block" {
                             // Get size using domblkinfo
-                            let info_output = std::process::Command::new("virsh")
+                            let info_output = libvirt.virsh()
                                 .args(["domblkinfo", &vm_name, target])
                                 .output();
 
@@ -812,130 +546,25 @@ impl VmService {
     }
 
     /// Get VM hardware configuration (firmware, TPM, chipset) from XML
-    fn get_vm_hardware_config(domain: &Domain) -> Result<(String, bool, String), AppError> {
-        let xml = domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
-
-        let mut firmware = "bios".to_string();
-        let mut tpm_enabled = false;
-        let mut chipset = "pc".to_string();
-
-        for line in xml.lines() {
-            let trimmed = line.trim();
-
-            // Check for UEFI loader
-            if trimmed.contains("<loader") {
-                if trimmed.contains("secure='yes'") || trimmed.contains("secboot") {
-                    firmware = "uefi-secure".to_string();
-                } else {
-                    firmware = "uefi".to_string();
-                }
-            }
-
-            // Check for TPM device
-            if trimmed.contains("<tpm ") || trimmed.contains("<tpm>") {
-                tpm_enabled = true;
-            }
-
-            // Check for machine type/chipset
-            if trimmed.contains("<type arch=") && trimmed.contains("machine=") {
-                if let Some(start) = trimmed.find("machine='") {
-                    let start_idx = start + 9;
-                    if let Some(end) = trimmed[start_idx..].find('\'') {
-                        let machine = &trimmed[start_idx..start_idx + end];
-                        if machine.contains("q35") {
-                            chipset = "q35".to_string();
-                        } else {
-                            chipset = "pc".to_string();
-                        }
-                    }
-                } else if let Some(start) = trimmed.find("machine=\"") {
-                    let start_idx = start + 9;
-                    if let Some(end) = trimmed[start_idx..].find('\"') {
-                        let machine = &trimmed[start_idx..start_idx + end];
-                        if machine.contains("q35") {
-                            chipset = "q35".to_string();
-                        } else {
-                            chipset = "pc".to_string();
-                        }
-                    }
-                }
-            }
-        }
-
-        Ok((firmware, tpm_enabled, chipset))
-    }
-
-    /// Get CPU topology from domain XML
-    fn get_cpu_topology(domain: &Domain) -> Result<(u32, u32, u32), AppError> {
-        let xml = domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
-
-        let mut sockets = 1;
-        let mut cores = 1;
-        let mut threads = 1;
-
-        for line in xml.lines() {
-            let trimmed = line.trim();
-
-            // Look for <topology sockets='X' cores='Y' threads='Z'/>
-            if trimmed.contains("<topology") {
-                // Parse sockets
-                if let Some(start) = trimmed.find("sockets='") {
-                    let start_idx = start + 9;
-                    if let Some(end) = trimmed[start_idx..].find('\'') {
-                        if let Ok(val) = trimmed[start_idx..start_idx + end].parse::<u32>() {
-                            sockets = val;
-                        }
-                    }
-                } else if let Some(start) = trimmed.find("sockets=\"") {
-                    let start_idx = start + 9;
-                    if let Some(end) = trimmed[start_idx..].find('\"') {
-                        if let Ok(val) = trimmed[start_idx..start_idx + end].parse::<u32>() {
-                            sockets = val;
-                        }
-                    }
-                }
-
-                // Parse cores
-                if let Some(start) = trimmed.find("cores='") {
-                    let start_idx = start + 7;
-                    if let Some(end) = trimmed[start_idx..].find('\'') {
-                        if let Ok(val) = trimmed[start_idx..start_idx + end].parse::<u32>() {
-                            cores = val;
-                        }
-                    }
-                } else if let Some(start) = trimmed.find("cores=\"") {
-                    let start_idx = start + 7;
-                    if let Some(end) = trimmed[start_idx..].find('\"') {
-                        if let Ok(val) = trimmed[start_idx..start_idx + end].parse::<u32>() {
-                            cores = val;
-                        }
-                    }
-                }
+    fn get_vm_hardware_config(xml: &DomainXml) -> (String, bool, String) {
+        let os = xml.os();
+
+        // UEFI is either an explicit loader or firmware auto-selection
+        let firmware = match (&os.loader, os.firmware.as_deref()) {
+            (Some(loader), _) if loader.secure || loader.path.contains("secboot") => "uefi-secure",
+            (Some(_), _) | (None, Some("efi")) => "uefi",
+            _ => "bios",
+        };
 
-                // Parse threads
-                if let Some(start) = trimmed.find("threads='") {
-                    let start_idx = start + 9;
-                    if let Some(end) = trimmed[start_idx..].find('\'') {
-                        if let Ok(val) = trimmed[start_idx..start_idx + end].parse::<u32>() {
-                            threads = val;
-                        }
-                    }
-                } else if let Some(start) = trimmed.find("threads=\"") {
-                    let start_idx = start + 9;
-                    if let Some(end) = trimmed[start_idx..].find('\"') {
-                        if let Ok(val) = trimmed[start_idx..start_idx + end].parse::<u32>() {
-                            threads = val;
-                        }
-                    }
-                }
+        let tpm_enabled = xml.has_device("tpm");
 
-                break; // Found topology, no need to continue
-            }
-        }
+        let chipset = if os.machine.as_deref().is_some_and(|machine| machine.contains("q35")) {
+            "q35"
+        } else {
+            "pc"
+        };
 
-        Ok((sockets, cores, threads))
+        (firmware.to_string(), tpm_enabled, chipset.to_string())
     }
 
     /// Create a new VM
@@ -1038,20 +667,14 @@ impl VmService {
         // If boot_order is empty, use intelligent defaults based on ISO presence
         let boot_devices = if config.boot_order.is_empty() {
             if config.iso_path.is_some() {
-                vec!["cdrom", "hd"]
+                vec!["cdrom".to_string(), "hd".to_string()]
             } else {
-                vec!["hd"]
+                vec!["hd".to_string()]
             }
         } else {
-            config.boot_order.iter().map(|s| s.as_str()).collect()
+            config.boot_order.clone()
         };
 
-        let boot_order = boot_devices
-            .iter()
-            .map(|dev| format!("    <boot dev='{}'/>", dev))
-            .collect::<Vec<_>>()
-            .join("\n");
-
         // Detect available OVMF firmware paths (try common locations)
         let (uefi_code_path, uefi_vars_path) = if std::path::Path::new("/usr/share/OVMF/OVMF_CODE_4M.fd").exists() {
             ("/usr/share/OVMF/OVMF_CODE_4M.fd", "/usr/share/OVMF/OVMF_VARS_4M.fd")
@@ -1072,8 +695,8 @@ impl VmService {
             (uefi_code_path, uefi_vars_path)
         };
 
-        // Build firmware/UEFI loader XML
-        let firmware_xml = match config.firmware.as_str() {
+        // Build firmware/UEFI loader
+        let firmware = match config.firmware.as_str() {
             "uefi" => {
                 // Verify firmware files exist
                 if !std::path::Path::new(uefi_code_path).exists() {
@@ -1082,11 +705,7 @@ impl VmService {
                     ));
                 }
                 tracing::info!("Using UEFI firmware: {} with NVRAM template: {}", uefi_code_path, uefi_vars_path);
-                format!(
-                    r#"    <loader readonly='yes' type='pflash'>{}</loader>
-    <nvram template='{}'>/var/lib/libvirt/qemu/nvram/{}_VARS.fd</nvram>"#,
-                    uefi_code_path, uefi_vars_path, config.name
-                )
+                Some((uefi_code_path, uefi_vars_path, false))
             },
             "uefi-secure" => {
                 // Verify secure boot firmware files exist
@@ -1096,17 +715,11 @@ impl VmService {
                     ));
                 }
                 tracing::info!("Using UEFI Secure Boot firmware: {} with NVRAM template: {}", uefi_secboot_code_path, uefi_secboot_vars_path);
-                format!(
-                    r#"    <loader readonly='yes' secure='yes' type='pflash'>{}</loader>
-    <nvram template='{}'>/var/lib/libvirt/qemu/nvram/{}_VARS.fd</nvram>"#,
-                    uefi_secboot_code_path, uefi_secboot_vars_path, config.name
-                )
+                Some((uefi_secboot_code_path, uefi_secboot_vars_path, true))
             },
-            _ => String::new() // BIOS (default)
+            _ => None // BIOS (default)
         };
 
-        let firmware_section = firmware_xml;
-
         // Determine disk format - for imports, detect from file extension
         let actual_disk_format = if config.installation_type == "import" {
             // Try to detect format from file extension
@@ -1124,308 +737,184 @@ impl VmService {
             &config.disk_format
         };
 
-        // Build disk XML for the main system disk
-        let main_disk_xml = format!(
-            r#"    <disk type='file' device='disk'>
-      <driver name='qemu' type='{}'/>
-      <source file='{}'/>
-      <target dev='vda' bus='virtio'/>
-    </disk>"#,
-            actual_disk_format, disk_path
-        );
-
-        // Build CDROM/ISO disk XML if ISO path is provided
-        // Q35 chipset doesn't support IDE, use SATA instead
-        let (cdrom_dev, cdrom_bus) = if config.chipset == "q35" {
-            ("sda", "sata")
-        } else {
-            ("hdc", "ide")
-        };
-
-        let cdrom_xml = if let Some(ref iso_path) = config.iso_path {
-            format!(
-                r#"    <disk type='file' device='cdrom'>
-      <driver name='qemu' type='raw'/>
-      <source file='{}'/>
-      <target dev='{}' bus='{}'/>
-      <readonly/>
-    </disk>"#,
-                iso_path, cdrom_dev, cdrom_bus
-            )
-        } else {
-            String::new()
-        };
-
-        // Build network interface XML
-        // For network installation, add boot order to the interface
-        let interface_xml = if config.installation_type == "network" {
-            format!(
-                r#"    <interface type='network'>
-      <source network='{}'/>
-      <model type='virtio'/>
-      <boot order='1'/>
-    </interface>"#,
-                config.network
-            )
-        } else {
-            format!(
-                r#"    <interface type='network'>
-      <source network='{}'/>
-      <model type='virtio'/>
-    </interface>"#,
-                config.network
-            )
-        };
-
-        // Build boot menu option
-        let boot_menu = if config.boot_menu {
-            "    <bootmenu enable='yes' timeout='3000'/>"
-        } else {
-            ""
-        };
-
-        // Build direct kernel boot XML if enabled
+        // Validate direct kernel boot files
         // This allows booting directly from a kernel/initrd on the host
-        let direct_kernel_boot_xml = if config.direct_kernel_boot {
-            let mut kernel_xml = String::new();
-
-            if let Some(ref kernel_path) = config.kernel_path {
-                if !kernel_path.is_empty() {
-                    // Verify kernel file exists
-                    if !std::path::Path::new(kernel_path).exists() {
-                        return Err(AppError::InvalidConfig(
-                            format!("Kernel file not found: {}", kernel_path)
-                        ));
-                    }
-                    kernel_xml.push_str(&format!("    <kernel>{}</kernel>\n", kernel_path));
-                }
-            }
-
-            if let Some(ref initrd_path) = config.initrd_path {
-                if !initrd_path.is_empty() {
-                    // Verify initrd file exists
-                    if !std::path::Path::new(initrd_path).exists() {
-                        return Err(AppError::InvalidConfig(
-                            format!("Initrd file not found: {}", initrd_path)
-                        ));
+        let kernel_file = |path: &Option<String>, what: &str| -> Result<Option<String>, AppError> {
+            match path {
+                Some(path) if config.direct_kernel_boot && !path.is_empty() => {
+                    if !std::path::Path::new(path).exists() {
+                        return Err(AppError::InvalidConfig(format!("{} not found: {}", what, path)));
                     }
-                    kernel_xml.push_str(&format!("    <initrd>{}</initrd>\n", initrd_path));
+                    Ok(Some(path.clone()))
                 }
+                _ => Ok(None),
             }
-
-            if let Some(ref kernel_args) = config.kernel_args {
-                if !kernel_args.is_empty() {
-                    // Escape XML special characters in kernel args
-                    let escaped_args = kernel_args
-                        .replace("&", "&amp;")
-                        .replace("<", "&lt;")
-                        .replace(">", "&gt;")
-                        .replace("\"", "&quot;")
-                        .replace("'", "&apos;");
-                    kernel_xml.push_str(&format!("    <cmdline>{}</cmdline>\n", escaped_args));
-                }
-            }
-
-            if let Some(ref dtb_path) = config.dtb_path {
-                if !dtb_path.is_empty() {
-                    // Verify DTB file exists (for ARM systems)
-                    if !std::path::Path::new(dtb_path).exists() {
-                        return Err(AppError::InvalidConfig(
-                            format!("Device tree blob file not found: {}", dtb_path)
-                        ));
-                    }
-                    kernel_xml.push_str(&format!("    <dtb>{}</dtb>\n", dtb_path));
-                }
-            }
-
-            kernel_xml.trim_end().to_string()
-        } else {
-            String::new()
         };
+        let kernel = kernel_file(&config.kernel_path, "Kernel file")?;
+        let initrd = kernel_file(&config.initrd_path, "Initrd file")?;
+        // Device tree blob is only used on ARM systems
+        let dtb = kernel_file(&config.dtb_path, "Device tree blob file")?;
+        let cmdline = config.kernel_args.clone()
+            .filter(|args| config.direct_kernel_boot && !args.is_empty());
 
-        // Build TPM device XML if enabled
-        let tpm_xml = if config.tpm_enabled {
-            r#"    <tpm model='tpm-tis'>
-      <backend type='emulator' version='2.0'/>
-    </tpm>"#
-        } else {
-            ""
-        };
+        // Generate XML configuration for the VM
+        // Set max memory to 2x current for ballooning headroom (capped at 128GB)
+        let max_memory_mb = std::cmp::min(config.memory_mb * 2, 131072);
 
-        // Build PCI device passthrough XML
-        let mut pci_devices_xml = String::new();
-        for pci_address in &config.pci_devices {
-            // Parse PCI address (format: 0000:01:00.0)
-            let parts: Vec<&str> = pci_address.split(':').collect();
-            if parts.len() == 3 {
-                let domain_part = parts[0];
-                let bus = parts[1];
-                let slot_func: Vec<&str> = parts[2].split('.').collect();
-
-                if slot_func.len() == 2 {
-                    let slot = slot_func[0];
-                    let func = slot_func[1];
-
-                    let hostdev = format!(
-                        r#"    <hostdev mode='subsystem' type='pci' managed='yes'>
-      <source>
-        <address domain='0x{}' bus='0x{}' slot='0x{}' function='0x{}'/>
-      </source>
-    </hostdev>
-"#,
-                        domain_part, bus, slot, func
-                    );
-                    pci_devices_xml.push_str(&hostdev);
-                    tracing::info!("Adding PCI device {} to VM configuration", pci_address);
-                }
-            }
-        }
+        let mut definition = DomainXml::new("qemu", &config.name);
+        definition.set_memory_kib(max_memory_mb * 1024);
+        definition.set_current_memory_kib(config.memory_mb * 1024);
+
+        // Hugepages memoryBacking if enabled (no page entry uses the default size)
+        if config.hugepages_enabled {
+            definition.set_memory_backing(Some(&domain_xml::MemoryBacking {
+                hugepages: Some(config.hugepage_size.map(|size| vec![domain_xml::HugePage {
+                    size,
+                    unit: Some("KiB".to_string()),
+                    nodeset: None,
+                }]).unwrap_or_default()),
+                ..Default::default()
+            }));
+        }
+
+        definition.set_vcpus(config.cpu_count);
+        definition.set_cpu(&domain_xml::Cpu {
+            topology: Some(domain_xml::CpuTopology {
+                sockets: config.cpu_sockets,
+                dies: None,
+                cores: config.cpu_cores,
+                threads: config.cpu_threads,
+            }),
+            ..Default::default()
+        });
 
-        // Determine OS type
-        let os_type_str = "hvm";
+        definition.set_os(&domain_xml::Os {
+            os_type: "hvm".to_string(),
+            arch: Some("x86_64".to_string()),
+            machine: Some(machine_type.to_string()),
+            loader: firmware.map(|(code, _, secure)| domain_xml::Loader {
+                path: code.to_string(),
+                readonly: true,
+                secure,
+                loader_type: Some("pflash".to_string()),
+            }),
+            nvram: firmware.map(|(_, vars, _)| domain_xml::Nvram {
+                path: Some(format!("/var/lib/libvirt/qemu/nvram/{}_VARS.fd", config.name)),
+                template: Some(vars.to_string()),
+            }),
+            boot_devices,
+            boot_menu: config.boot_menu.then_some(domain_xml::BootMenu { enable: true, timeout: Some(3000) }),
+            kernel,
+            initrd,
+            cmdline,
+            dtb,
+            ..Default::default()
+        });
 
-        // Build features section - ACPI is required for UEFI
-        let features_xml = if config.firmware != "bios" {
-            r#"  <features>
-    <acpi/>
-    <apic/>
-  </features>"#
-        } else {
-            ""
-        };
+        // ACPI is required for UEFI
+        if config.firmware != "bios" {
+            definition.enable_feature("acpi");
+            definition.enable_feature("apic");
+        }
 
-        // Build CPU topology XML
-        let cpu_topology_xml = format!(
-            r#"  <cpu>
-    <topology sockets='{}' cores='{}' threads='{}'/>
-  </cpu>"#,
-            config.cpu_sockets, config.cpu_cores, config.cpu_threads
-        );
+        let devices = definition.devices_mut();
+        devices.push_child(XmlElement::new("emulator").with_text("/usr/bin/qemu-system-x86_64"));
 
-        // Build graphics XML based on config
-        let graphics_xml = match config.graphics_type.as_str() {
-            "spice" => r#"    <graphics type='spice' autoport='yes'>
-      <listen type='address'/>
-    </graphics>"#.to_string(),
-            "vnc" | _ => "    <graphics type='vnc' port='-1' autoport='yes'/>".to_string(),
-        };
+        // Main system disk
+        devices.push_child(domain_xml::Disk::file("disk", &disk_path, actual_disk_format, "vda", "virtio").to_element());
 
-        // Build video XML based on config
-        let video_xml = format!(
-            r#"    <video>
-      <model type='{}'/>
-    </video>"#,
-            config.video_model
-        );
+        // CDROM/ISO disk if ISO path is provided
+        // Q35 chipset doesn't support IDE, use SATA instead
+        if let Some(ref iso_path) = config.iso_path {
+            let (cdrom_dev, cdrom_bus) = if config.chipset == "q35" {
+                ("sda", "sata")
+            } else {
+                ("hdc", "ide")
+            };
+            devices.push_child(domain_xml::Disk::file("cdrom", iso_path, "raw", cdrom_dev, cdrom_bus).to_element());
+        }
 
-        // Build RNG device XML if enabled
-        let rng_xml = if config.rng_enabled {
-            r#"    <rng model='virtio'>
-      <backend model='random'>/dev/urandom</backend>
-    </rng>"#.to_string()
-        } else {
-            String::new()
-        };
+        // Network interface; for network installation boot from it first
+        let mut interface = domain_xml::Interface::network(&config.network, "virtio");
+        if config.installation_type == "network" {
+            interface.boot_order = Some(1);
+        }
+        devices.push_child(interface.to_element());
 
-        // Build watchdog device XML if specified
-        let watchdog_xml = if let Some(ref model) = config.watchdog_model {
-            format!(
-                r#"    <watchdog model='{}' action='{}'/>"#,
-                model, config.watchdog_action
-            )
-        } else {
-            String::new()
+        // Graphics based on config
+        let graphics = match config.graphics_type.as_str() {
+            "spice" => domain_xml::Graphics {
+                graphics_type: "spice".to_string(),
+                autoport: true,
+                ..Default::default()
+            },
+            _ => domain_xml::Graphics {
+                graphics_type: "vnc".to_string(),
+                port: Some(-1),
+                autoport: true,
+                ..Default::default()
+            },
         };
-
-        // Build hugepages memoryBacking XML if enabled
-        let memory_backing_xml = if config.hugepages_enabled {
-            if let Some(size) = config.hugepage_size {
-                format!(
-                    r#"  <memoryBacking>
-    <hugepages>
-      <page size='{}' unit='KiB'/>
-    </hugepages>
-  </memoryBacking>"#,
-                    size
-                )
-            } else {
-                // Use default hugepage size
-                r#"  <memoryBacking>
-    <hugepages/>
-  </memoryBacking>"#.to_string()
+        devices.push_child(graphics.to_element());
+
+        devices.push_child(domain_xml::Video { model: config.video_model.clone(), ..Default::default() }.to_element());
+
+        devices.push_child(domain_xml::CharDevice {
+            kind: "serial".to_string(),
+            source_type: "pty".to_string(),
+            target_port: Some(0),
+            ..Default::default()
+        }.to_element());
+        devices.push_child(domain_xml::CharDevice {
+            kind: "console".to_string(),
+            source_type: "pty".to_string(),
+            target_type: Some("serial".to_string()),
+            target_port: Some(0),
+            ..Default::default()
+        }.to_element());
+        devices.push_child(domain_xml::Controller {
+            controller_type: "virtio-serial".to_string(),
+            index: Some(0),
+            ..Default::default()
+        }.to_element());
+        devices.push_child(XmlElement::new("memballoon").with_attr("model", "virtio"));
+
+        // TPM device if enabled
+        if config.tpm_enabled {
+            devices.push_child(domain_xml::Tpm {
+                model: "tpm-tis".to_string(),
+                backend_type: "emulator".to_string(),
+                version: Some("2.0".to_string()),
+                device_path: None,
+            }.to_element());
+        }
+
+        // PCI device passthrough (address format: 0000:01:00.0)
+        for pci_address in &config.pci_devices {
+            if let Some(hostdev) = domain_xml::PciHostdev::from_address(pci_address, true) {
+                devices.push_child(hostdev.to_element());
+                tracing::info!("Adding PCI device {} to VM configuration", pci_address);
             }
-        } else {
-            String::new()
-        };
+        }
 
-        // Generate XML configuration for the VM
-        // Set max memory to 2x current for ballooning headroom (capped at 128GB)
-        let max_memory_mb = std::cmp::min(config.memory_mb * 2, 131072);
+        // RNG device if enabled
+        if config.rng_enabled {
+            devices.push_child(domain_xml::Rng {
+                model: "virtio".to_string(),
+                backend_model: "random".to_string(),
+                source: Some("/dev/urandom".to_string()),
+            }.to_element());
+        }
 
-        let xml = format!(
-            r#"<domain type='qemu'>
-  <name>{}</name>
-  <memory unit='MiB'>{}</memory>
-  <currentMemory unit='MiB'>{}</currentMemory>
-{}
-  <vcpu>{}</vcpu>
-{}
-  <os>
-    <type arch='x86_64' machine='{}'>{}</type>
-{}
-{}
-{}
-{}
-  </os>
-{}
-  <devices>
-    <emulator>/usr/bin/qemu-system-x86_64</emulator>
-{}
-{}
-{}
-{}
-{}
-    <serial type='pty'>
-      <target port='0'/>
-    </serial>
-    <console type='pty'>
-      <target type='serial' port='0'/>
-    </console>
-    <controller type='virtio-serial' index='0'>
-      <address type='pci' domain='0x0000' bus='0x00' slot='0x08' function='0x0'/>
-    </controller>
-    <memballoon model='virtio'>
-      <address type='pci' domain='0x0000' bus='0x00' slot='0x07' function='0x0'/>
-    </memballoon>
-{}
-{}
-{}
-{}
-  </devices>
-</domain>"#,
-            config.name,
-            max_memory_mb,  // max memory
-            config.memory_mb,  // current memory
-            memory_backing_xml,  // hugepages if enabled
-            config.cpu_count,
-            cpu_topology_xml,
-            machine_type,
-            os_type_str,
-            firmware_section,
-            boot_order,
-            boot_menu,
-            direct_kernel_boot_xml,
-            features_xml,
-            main_disk_xml,
-            cdrom_xml,
-            interface_xml,
-            graphics_xml,
-            video_xml,
-            tpm_xml,
-            pci_devices_xml,
-            rng_xml,
-            watchdog_xml
-        );
+        // Watchdog device if specified
+        if let Some(ref model) = config.watchdog_model {
+            devices.push_child(domain_xml::Watchdog {
+                model: model.clone(),
+                action: config.watchdog_action.clone(),
+            }.to_element());
+        }
+
+        let xml = definition.to_xml();
 
         tracing::debug!("VM XML:\n{}", xml);
 
@@ -1486,21 +975,21 @@ impl VmService {
         }
 
         // Get actual memory usage from dommemstat (not just allocated memory)
-        let (memory_used_mb, memory_available_mb) = Self::get_libvirt_memory_stats(&vm_name)
+        let (memory_used_mb, memory_available_mb) = Self::get_libvirt_memory_stats(libvirt, &vm_name)
             .unwrap_or_else(|| {
                 // Fallback to basic info (allocated memory)
                 ((info.memory / 1024) as u64, (info.max_mem / 1024) as u64)
             });
 
         // Try to get CPU usage from guest agent
-        let cpu_usage_percent = Self::get_cpu_from_guest_agent(&vm_name)
+        let cpu_usage_percent = Self::get_cpu_from_guest_agent(libvirt, &vm_name)
             .unwrap_or_else(|_| Self::calculate_libvirt_cpu_usage(&domain, info.nr_virt_cpu as u32));
 
         // Get disk stats from libvirt
-        let (disk_read_bytes, disk_write_bytes) = Self::get_libvirt_disk_stats(&vm_name);
+        let (disk_read_bytes, disk_write_bytes) = Self::get_libvirt_disk_stats(libvirt, &vm_name);
 
         // Get network stats from libvirt
-        let (network_rx_bytes, network_tx_bytes) = Self::get_libvirt_network_stats(&vm_name);
+        let (network_rx_bytes, network_tx_bytes) = Self::get_libvirt_network_stats(libvirt, &vm_name);
 
         Ok(crate::models::vm::VmStats {
             vm_id: vm_id.to_string(),
@@ -1516,9 +1005,9 @@ impl VmService {
     }
 
     /// Try to get CPU usage from guest agent
-    fn get_cpu_from_guest_agent(vm_name: &str) -> Result<f64, AppError> {
+    fn get_cpu_from_guest_agent(libvirt: &LibvirtService, vm_name: &str) -> Result<f64, AppError> {
         // Use virsh qemu-agent-command to get CPU stats
-        let output = std::process::Command::new("virsh")
+        let output = libvirt.virsh()
             .args(["qemu-agent-command", vm_name, r#"{"execute":"guest-get-cpustats"}"#])
             .output()
             .map_err(|e| AppError::Other(format!("Failed to execute virsh: {}", e)))?;
@@ -1604,9 +1093,9 @@ impl VmService {
     }
 
     /// Get disk I/O stats from libvirt using virsh domblkstat
-    fn get_libvirt_disk_stats(vm_name: &str) -> (u64, u64) {
+    fn get_libvirt_disk_stats(libvirt: &LibvirtService, vm_name: &str) -> (u64, u64) {
         // Use virsh domblkstat to get disk stats
-        let output = std::process::Command::new("virsh")
+        let output = libvirt.virsh()
             .args(["domblkstat", vm_name, "--human"])
             .output();
 
@@ -1636,8 +1125,8 @@ impl VmService {
     }
 
     /// Get actual memory usage from libvirt using virsh dommemstat
-    fn get_libvirt_memory_stats(vm_name: &str) -> Option<(u64, u64)> {
-        let output = std::process::Command::new("virsh")
+    fn get_libvirt_memory_stats(libvirt: &LibvirtService, vm_name: &str) -> Option<(u64, u64)> {
+        let output = libvirt.virsh()
             .args(["dommemstat", vm_name])
             .output()
             .ok()?;
@@ -1680,9 +1169,9 @@ impl VmService {
     }
 
     /// Get network I/O stats from libvirt using virsh domifstat
-    fn get_libvirt_network_stats(vm_name: &str) -> (u64, u64) {
+    fn get_libvirt_network_stats(libvirt: &LibvirtService, vm_name: &str) -> (u64, u64) {
         // First get list of interfaces
-        let iface_list = std::process::Command::new("virsh")
+        let iface_list = libvirt.virsh()
             .args(["domiflist", vm_name])
             .output();
 
@@ -1710,7 +1199,7 @@ impl VmService {
         let mut total_tx: u64 = 0;
 
         for iface in &ifaces {
-            let output = std::process::Command::new("virsh")
+            let output = libvirt.virsh()
                 .args(["domifstat", vm_name, iface])
                 .output();
 
@@ -1735,10 +1224,14 @@ impl VmService {
     }
 
     /// Clone a VM with disk and snapshot options
+    ///
+    /// When run as a job, disk copies report progress and cancelling removes
+    /// the disks created so far.
     pub fn clone_vm_with_options(
         libvirt: &LibvirtService,
         source_vm_id: &str,
         config: &crate::models::vm::CloneConfig,
+        job: Option<&JobHandle>,
     ) -> Result<String, AppError> {
         use std::process::Command;
         use std::path::Path;
@@ -1758,8 +1251,7 @@ impl VmService {
         }
 
         // Get source VM XML
-        let source_xml = source_domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
+        let source_xml = DomainXml::from_domain_flags(&source_domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
         // Extract disk paths from source XML
         let disk_paths = Self::extract_disk_paths(&source_xml);
@@ -1768,7 +1260,18 @@ impl VmService {
         if config.clone_disks && !disk_paths.is_empty() {
             tracing::info!("Cloning {} disk(s)", disk_paths.len());
 
+            // Disk copies account for the bulk of the job's progress
+            let share = 90.0 / disk_paths.len() as f64;
+
             for (index, source_path) in disk_paths.iter().enumerate() {
+                if let Some(job) = job {
+                    if let Err(e) = job.check_cancelled() {
+                        Self::remove_cloned_disks(&disk_mappings);
+                        return Err(e);
+                    }
+                    job.set_step(format!("Cloning disk {} of {}", index + 1, disk_paths.len()));
+                }
+
                 let source_path_obj = Path::new(source_path);
 
                 // Skip CDROM devices or non-existent paths
@@ -1815,26 +1318,37 @@ impl VmService {
                 if !output.status.success() {
                     // If backing file method fails, try full copy
                     tracing::info!("Backing file clone failed, attempting full copy");
-                    let output = Command::new("qemu-img")
-                        .args(["convert", "-O", "qcow2", source_path, &target_path])
-                        .output()
-                        .map_err(|e| AppError::Other(format!("Failed to run qemu-img: {}", e)))?;
-
-                    if !output.status.success() {
-                        let stderr = String::from_utf8_lossy(&output.stderr);
-                        return Err(AppError::Other(format!("Disk clone failed: {}", stderr)));
+                    let span = index as f64 * share..(index + 1) as f64 * share;
+                    if let Err(e) = job_service::run_qemu_img(job, &["convert", "-O", "qcow2", source_path, &target_path], span) {
+                        let _ = std::fs::remove_file(&target_path);
+                        Self::remove_cloned_disks(&disk_mappings);
+                        return Err(match e {
+                            AppError::Cancelled => e,
+                            e => AppError::Other(format!("Disk clone failed: {}", e)),
+                        });
                     }
                 }
 
                 disk_mappings.push((source_path.clone(), target_path));
+                if let Some(job) = job {
+                    job.set_percent((index + 1) as f64 * share);
+                }
             }
         }
 
+        if let Some(job) = job {
+            if let Err(e) = job.check_cancelled() {
+                Self::remove_cloned_disks(&disk_mappings);
+                return Err(e);
+            }
+            job.set_step("Defining cloned VM");
+        }
+
         // Modify XML for clone (new name, MAC addresses, disk paths if cloned)
-        let clone_xml = Self::modify_xml_for_clone_extended(&source_xml, &config.new_name, &disk_mappings, &config.description)?;
+        let clone_xml = Self::modify_xml_for_clone_extended(source_xml, &config.new_name, &disk_mappings, &config.description);
 
         // Define the cloned domain
-        let clone_domain = Domain::define_xml(conn, &clone_xml)
+        let clone_domain = Domain::define_xml(conn, &clone_xml.to_xml())
             .map_err(map_libvirt_error)?;
 
         let clone_uuid = clone_domain.get_uuid_string()
@@ -1852,128 +1366,73 @@ impl VmService {
         Ok(clone_uuid)
     }
 
-    /// Extract disk paths from VM XML
-    fn extract_disk_paths(xml: &str) -> Vec<String> {
-        let mut paths = Vec::new();
-        let mut search_pos = 0;
-
-        while let Some(disk_start) = xml[search_pos..].find("<disk ") {
-            let abs_disk_start = search_pos + disk_start;
-
-            // Find the end of this disk element
-            if let Some(disk_end) = xml[abs_disk_start..].find("</disk>") {
-                let disk_section = &xml[abs_disk_start..abs_disk_start + disk_end + 7];
-
-                // Check if it's a disk device (not cdrom)
-                if disk_section.contains("device='disk'") || disk_section.contains("device=\"disk\"") {
-                    // Extract source file path
-                    if let Some(source_match) = Self::extract_source_file(disk_section) {
-                        paths.push(source_match);
-                    }
-                }
-
-                search_pos = abs_disk_start + disk_end + 7;
-            } else {
-                break;
+    /// Remove disks created by an aborted clone
+    fn remove_cloned_disks(disk_mappings: &[(String, String)]) {
+        for (_, target_path) in disk_mappings {
+            if let Err(e) = std::fs::remove_file(target_path) {
+                tracing::warn!("Failed to remove partial clone disk {}: {}", target_path, e);
             }
         }
-
-        paths
     }
 
-    /// Extract source file from disk XML section
-    fn extract_source_file(disk_section: &str) -> Option<String> {
-        // Look for <source file='...'> or <source file="...">
-        if let Some(source_start) = disk_section.find("<source ") {
-            let source_section = &disk_section[source_start..];
-
-            // Try single quotes first
-            if let Some(file_start) = source_section.find("file='") {
-                let path_start = source_section[file_start + 6..].find("'")
-                    .map(|end| &source_section[file_start + 6..file_start + 6 + end]);
-                if let Some(path) = path_start {
-                    return Some(path.to_string());
-                }
-            }
-
-            // Try double quotes
-            if let Some(file_start) = source_section.find("file=\"") {
-                let path_start = source_section[file_start + 6..].find("\"")
-                    .map(|end| &source_section[file_start + 6..file_start + 6 + end]);
-                if let Some(path) = path_start {
-                    return Some(path.to_string());
-                }
-            }
-        }
-        None
+    /// Extract file-backed disk paths (not cdroms) from VM XML
+    fn extract_disk_paths(xml: &DomainXml) -> Vec<String> {
+        xml.disks().iter()
+            .filter(|disk| disk.device == "disk")
+            .filter_map(|disk| disk.source_path().map(String::from))
+            .collect()
     }
 
     /// Extract pool path from pool XML
     fn extract_pool_path(xml: &str) -> Option<String> {
-        if let Some(target_start) = xml.find("<target>") {
-            if let Some(path_start) = xml[target_start..].find("<path>") {
-                let abs_path_start = target_start + path_start + 6;
-                if let Some(path_end) = xml[abs_path_start..].find("</path>") {
-                    return Some(xml[abs_path_start..abs_path_start + path_end].to_string());
-                }
-            }
-        }
-        None
+        XmlElement::parse(xml).ok()?
+            .find("target/path")
+            .map(|path| path.text())
     }
 
     /// Modify VM XML for cloning with disk path updates
     fn modify_xml_for_clone_extended(
-        xml: &str,
+        mut xml: DomainXml,
         new_name: &str,
         disk_mappings: &[(String, String)],
         description: &Option<String>,
-    ) -> Result<String, AppError> {
-        let mut modified = xml.to_string();
-
+    ) -> DomainXml {
         // Remove UUID to let libvirt generate a new one
-        if let Some(uuid_start) = modified.find("<uuid>") {
-            if let Some(uuid_end) = modified[uuid_start..].find("</uuid>") {
-                let full_end = uuid_start + uuid_end + 7;
-                modified.replace_range(uuid_start..full_end, "");
-            }
-        }
+        xml.set_uuid(None);
+        xml.set_name(new_name);
 
-        // Change name
-        if let Some(name_start) = modified.find("<name>") {
-            if let Some(name_end) = modified[name_start..].find("</name>") {
-                let content_start = name_start + 6;
-                let content_end = name_start + name_end;
-                modified.replace_range(content_start..content_end, new_name);
-            }
-        }
-
-        // Add or update description
+        // Add or update description, keeping it next to the name
         if let Some(desc) = description {
-            if let Some(desc_start) = modified.find("<description>") {
-                if let Some(desc_end) = modified[desc_start..].find("</description>") {
-                    let content_start = desc_start + 13;
-                    let content_end = desc_start + desc_end;
-                    modified.replace_range(content_start..content_end, desc);
-                }
-            } else {
-                // Insert description after name
-                if let Some(name_end) = modified.find("</name>") {
-                    let insert_pos = name_end + 7;
-                    let desc_xml = format!("\n  <description>{}</description>", desc);
-                    modified.insert_str(insert_pos, &desc_xml);
+            let root = xml.root_mut();
+            match root.child_mut("description") {
+                Some(existing) => existing.set_text(desc),
+                None => {
+                    let position = root.children.iter()
+                        .position(|node| matches!(node, XmlNode::Element(e) if e.name == "name"))
+                        .map(|index| index + 1)
+                        .unwrap_or(0);
+                    root.children.insert(position, XmlNode::Element(
+                        XmlElement::new("description").with_text(desc),
+                    ));
                 }
             }
         }
 
         // Update disk paths
         for (old_path, new_path) in disk_mappings {
-            modified = modified.replace(old_path, new_path);
+            for disk in xml.devices_mut().children_named_mut("disk") {
+                if let Some(source) = disk.child_mut("source") {
+                    if source.attr("file") == Some(old_path.as_str()) {
+                        source.set_attr("file", new_path);
+                    }
+                }
+            }
         }
 
         // Generate new MAC addresses for network interfaces
-        modified = Self::regenerate_mac_addresses(&modified);
+        Self::regenerate_mac_addresses(&mut xml);
 
-        Ok(modified)
+        xml
     }
 
     /// Clone snapshots from source VM to target VM
@@ -2009,26 +1468,16 @@ impl VmService {
                 .map_err(map_libvirt_error)?;
             let description = snap.get_xml_desc(0)
                 .ok()
-                .and_then(|xml| {
-                    if let Some(start) = xml.find("<description>") {
-                        let section = &xml[start + 13..];
-                        if let Some(end) = section.find("</description>") {
-                            return Some(section[..end].to_string());
-                        }
-                    }
-                    None
-                })
+                .and_then(|xml| XmlElement::parse(&xml).ok())
+                .and_then(|snapshot| snapshot.child_text("description"))
                 .unwrap_or_else(|| format!("Cloned from {}", source_vm_id));
 
             // Create disk-only snapshot on target
-            let snap_xml = format!(
-                r#"<domainsnapshot>
-  <name>{}</name>
-  <description>{}</description>
-  <memory snapshot='no'/>
-</domainsnapshot>"#,
-                name, description
-            );
+            let snap_xml = XmlElement::new("domainsnapshot")
+                .with_child(XmlElement::new("name").with_text(&name))
+                .with_child(XmlElement::new("description").with_text(description))
+                .with_child(XmlElement::new("memory").with_attr("snapshot", "no"))
+                .to_xml();
 
             match DomainSnapshot::create_xml(&target_domain, &snap_xml, 0) {
                 Ok(_) => tracing::info!("Cloned snapshot: {}", name),
@@ -2052,20 +1501,16 @@ impl VmService {
             target_pool: None,
             description: None,
         };
-        Self::clone_vm_with_options(libvirt, source_vm_id, &config)
+        Self::clone_vm_with_options(libvirt, source_vm_id, &config, None)
     }
 
-    /// Regenerate MAC addresses in XML
-    fn regenerate_mac_addresses(xml: &str) -> String {
+    /// Regenerate MAC addresses of every network interface
+    pub fn regenerate_mac_addresses(xml: &mut DomainXml) {
         use rand::Rng;
         let mut rng = rand::thread_rng();
-        let mut result = xml.to_string();
-
-        while let Some(mac_start) = result.find("<mac address='") {
-            let mac_content_start = mac_start + 14;
-            if let Some(mac_end) = result[mac_content_start..].find("'/>") {
-                let mac_content_end = mac_content_start + mac_end;
 
+        for interface in xml.devices_mut().children_named_mut("interface") {
+            if let Some(mac) = interface.child_mut("mac") {
                 // Generate new MAC address (52:54:00 prefix for QEMU)
                 let new_mac = format!(
                     "52:54:00:{:02x}:{:02x}:{:02x}",
@@ -2073,44 +1518,9 @@ impl VmService {
                     rng.gen::<u8>(),
                     rng.gen::<u8>()
                 );
-
-                result.replace_range(mac_content_start..mac_content_end, &new_mac);
-            } else {
-                break;
-            }
-        }
-
-        result
-    }
-
-    /// Get tags for a VM from its metadata
-    fn get_vm_tags(domain: &Domain) -> Result<Vec<String>, AppError> {
-        let xml = domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
-
-        // Look for our custom metadata tags
-        let mut tags = Vec::new();
-
-        if let Some(metadata_start) = xml.find("<metadata>") {
-            if let Some(metadata_end) = xml[metadata_start..].find("</metadata>") {
-                let metadata_section = &xml[metadata_start..metadata_start + metadata_end + 11];
-
-                // Extract tags from <kvm:tag> elements
-                let mut search_pos = 0;
-                while let Some(tag_start) = metadata_section[search_pos..].find("<kvm:tag>") {
-                    let abs_tag_start = search_pos + tag_start + 9;
-                    if let Some(tag_end) = metadata_section[abs_tag_start..].find("</kvm:tag>") {
-                        let tag = metadata_section[abs_tag_start..abs_tag_start + tag_end].to_string();
-                        tags.push(tag);
-                        search_pos = abs_tag_start + tag_end + 10;
-                    } else {
-                        break;
-                    }
-                }
+                mac.set_attr("address", new_mac);
             }
         }
-
-        Ok(tags)
     }
 
     /// Add tags to a VM
@@ -2121,48 +1531,21 @@ impl VmService {
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        // Get current XML
-        let xml = domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
-
-        // Get existing tags
-        let mut existing_tags = Self::get_vm_tags(&domain)?;
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
         // Add new tags (avoiding duplicates)
+        let mut existing_tags = xml.tags();
         for tag in tags {
             if !existing_tags.contains(&tag) {
                 existing_tags.push(tag);
             }
         }
 
-        // Build metadata XML
-        let tags_xml = existing_tags.iter()
-            .map(|t| format!("    <kvm:tag>{}</kvm:tag>", t))
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        let metadata_xml = format!(
-            r#"  <metadata>
-  <kvm:tags xmlns:kvm="http://kvm-manager.local/metadata">
-{}
-  </kvm:tags>
-</metadata>"#,
-            tags_xml
-        );
-
-        // Replace or add metadata section
-        let new_xml = if xml.contains("<metadata>") {
-            // Replace existing metadata
-            let start = xml.find("<metadata>").unwrap();
-            let end = xml.find("</metadata>").unwrap() + 11;
-            format!("{}{}{}", &xml[..start], metadata_xml, &xml[end..])
-        } else {
-            // Add metadata before </domain>
-            xml.replace("</domain>", &format!("{}\n</domain>", metadata_xml))
-        };
+        // Only our metadata element is replaced; other applications' metadata is kept
+        xml.set_tags(&existing_tags);
 
         // Redefine domain with new XML
-        let _new_domain = Domain::define_xml(conn, &new_xml)
+        let _new_domain = Domain::define_xml(conn, &xml.to_xml())
             .map_err(map_libvirt_error)?;
 
         tracing::info!("Tags added successfully to VM {}", vm_id);
@@ -2177,35 +1560,15 @@ impl VmService {
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        // Get current XML
-        let xml = domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
-
-        // Get existing tags
-        let mut existing_tags = Self::get_vm_tags(&domain)?;
-
-        // Remove specified tags
-        existing_tags.retain(|t| !tags_to_remove.contains(t));
-
-        // Build new metadata XML
-        if existing_tags.is_empty() {
-            // Remove metadata section entirely if no tags left
-            let new_xml = if let Some(start) = xml.find("<metadata>") {
-                if let Some(end) = xml.find("</metadata>") {
-                    format!("{}{}", &xml[..start], &xml[end + 11..])
-                } else {
-                    xml
-                }
-            } else {
-                xml
-            };
-
-            let _new_domain = Domain::define_xml(conn, &new_xml)
-                .map_err(map_libvirt_error)?;
-        } else {
-            // Update with remaining tags
-            Self::add_vm_tags(libvirt, vm_id, existing_tags)?;
-        }
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
+
+        // Remove specified tags; the tags element is dropped when none are left
+        let mut existing_tags = xml.tags();
+        existing_tags.retain(|t| !tags_to_remove.contains(t));
+        xml.set_tags(&existing_tags);
+
+        let _new_domain = Domain::define_xml(conn, &xml.to_xml())
+            .map_err(map_libvirt_error)?;
 
         tracing::info!("Tags removed successfully from VM {}", vm_id);
         Ok(())
@@ -2268,27 +1631,8 @@ impl VmService {
             )));
         }
 
-        // Build disk XML - NVMe requires different target naming
-        let disk_xml = if bus_type == "nvme" {
-            // NVMe disks use nvmeXnY naming (e.g., nvme0n1)
-            format!(
-                r#"<disk type='file' device='disk'>
-  <driver name='qemu' type='qcow2'/>
-  <source file='{}'/>
-  <target dev='{}' bus='nvme'/>
-</disk>"#,
-                disk_path, device_target
-            )
-        } else {
-            format!(
-                r#"<disk type='file' device='disk'>
-  <driver name='qemu' type='qcow2'/>
-  <source file='{}'/>
-  <target dev='{}' bus='{}'/>
-</disk>"#,
-                disk_path, device_target, bus_type
-            )
-        };
+        // NVMe disks use nvmeXnY target naming (e.g., nvme0n1), supplied by the caller
+        let disk_xml = domain_xml::Disk::file("disk", disk_path, "qcow2", device_target, bus_type).to_xml();
 
         // Attach disk (persistent and live if VM is running)
         let flags = if domain.is_active().map_err(map_libvirt_error)? {
@@ -2316,37 +1660,13 @@ impl VmService {
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        // Get current XML to find the disk
-        let xml = domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
-
         // Find disk device XML by target
-        let disk_start_pattern = format!("<disk");
-        let target_pattern = format!("target dev='{}'", device_target);
-
-        let mut search_pos = 0;
-        let mut disk_xml = None;
-
-        while let Some(disk_pos) = xml[search_pos..].find(&disk_start_pattern) {
-            let abs_disk_pos = search_pos + disk_pos;
-            if let Some(disk_end) = xml[abs_disk_pos..].find("</disk>") {
-                let disk_section = &xml[abs_disk_pos..abs_disk_pos + disk_end + 7];
-
-                // Check if this disk has the target we're looking for
-                if disk_section.contains(&target_pattern) {
-                    disk_xml = Some(disk_section.to_string());
-                    break;
-                }
-
-                search_pos = abs_disk_pos + disk_end + 7;
-            } else {
-                break;
-            }
-        }
-
-        let disk_xml = disk_xml.ok_or_else(|| {
-            AppError::InvalidConfig(format!("Disk with target '{}' not found", device_target))
-        })?;
+        let xml = DomainXml::from_domain(&domain)?;
+        let disk_xml = xml.disk_element(device_target)
+            .map(|disk| disk.to_xml())
+            .ok_or_else(|| {
+                AppError::InvalidConfig(format!("Disk with target '{}' not found", device_target))
+            })?;
 
         // Detach disk (persistent and live if VM is running)
         let flags = if domain.is_active().map_err(map_libvirt_error)? {
@@ -2415,163 +1735,33 @@ impl VmService {
             }
         }
 
-        // Get current XML
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-
-        // Find the disk in the XML
-        let target_pattern = format!("target dev='{}'", device_target);
-
-        let mut search_pos = 0;
-        let mut disk_start_pos = None;
-        let mut disk_end_pos = None;
-
-        while let Some(disk_pos) = xml[search_pos..].find("<disk") {
-            let abs_disk_pos = search_pos + disk_pos;
-            if let Some(disk_end) = xml[abs_disk_pos..].find("</disk>") {
-                let disk_section = &xml[abs_disk_pos..abs_disk_pos + disk_end + 7];
-
-                if disk_section.contains(&target_pattern) {
-                    disk_start_pos = Some(abs_disk_pos);
-                    disk_end_pos = Some(abs_disk_pos + disk_end + 7);
-                    break;
-                }
-
-                search_pos = abs_disk_pos + disk_end + 7;
-            } else {
-                break;
-            }
-        }
-
-        let (disk_start, disk_end) = match (disk_start_pos, disk_end_pos) {
-            (Some(s), Some(e)) => (s, e),
-            _ => {
-                return Err(AppError::InvalidConfig(format!(
-                    "Disk with target '{}' not found",
-                    device_target
-                )));
-            }
-        };
-
-        let old_disk_xml = &xml[disk_start..disk_end];
-
-        // Build new driver line
-        let mut driver_attrs = vec!["name='qemu'".to_string()];
-
-        // Extract existing driver type from old XML
-        if let Some(type_start) = old_disk_xml.find("<driver") {
-            if let Some(type_pos) = old_disk_xml[type_start..].find("type='") {
-                let start = type_start + type_pos + 6;
-                if let Some(end) = old_disk_xml[start..].find("'") {
-                    driver_attrs.push(format!("type='{}'", &old_disk_xml[start..start + end]));
-                }
-            }
-        } else {
-            driver_attrs.push("type='qcow2'".to_string());
-        }
-
-        // Add I/O settings
-        if let Some(ref c) = cache {
-            driver_attrs.push(format!("cache='{}'", c));
-        }
-        if let Some(ref i) = io {
-            driver_attrs.push(format!("io='{}'", i));
-        }
-        if let Some(ref d) = discard {
-            driver_attrs.push(format!("discard='{}'", d));
-        }
-        if let Some(ref dz) = detect_zeroes {
-            driver_attrs.push(format!("detect_zeroes='{}'", dz));
-        }
-
-        // Build iotune section if needed
-        let iotune_xml = if read_iops_sec.is_some()
-            || write_iops_sec.is_some()
-            || read_bytes_sec.is_some()
-            || write_bytes_sec.is_some()
-        {
-            let mut iotune_parts = Vec::new();
-            if let Some(val) = read_iops_sec {
-                iotune_parts.push(format!("    <read_iops_sec>{}</read_iops_sec>", val));
-            }
-            if let Some(val) = write_iops_sec {
-                iotune_parts.push(format!("    <write_iops_sec>{}</write_iops_sec>", val));
-            }
-            if let Some(val) = read_bytes_sec {
-                iotune_parts.push(format!("    <read_bytes_sec>{}</read_bytes_sec>", val));
-            }
-            if let Some(val) = write_bytes_sec {
-                iotune_parts.push(format!("    <write_bytes_sec>{}</write_bytes_sec>", val));
-            }
-            if iotune_parts.is_empty() {
-                String::new()
-            } else {
-                format!("\n  <iotune>\n{}\n  </iotune>", iotune_parts.join("\n"))
-            }
-        } else {
-            String::new()
-        };
-
-        // Extract source and target from old disk XML
-        let source_line = if let Some(source_start) = old_disk_xml.find("<source") {
-            if let Some(source_end) = old_disk_xml[source_start..].find("/>") {
-                Some(&old_disk_xml[source_start..source_start + source_end + 2])
-            } else if let Some(source_end) = old_disk_xml[source_start..].find(">") {
-                // Handle <source ...></source> format
-                if let Some(close_end) = old_disk_xml[source_start..].find("</source>") {
-                    Some(&old_disk_xml[source_start..source_start + close_end + 9])
-                } else {
-                    Some(&old_disk_xml[source_start..source_start + source_end + 1])
-                }
-            } else {
-                None
-            }
-        } else {
-            None
-        };
-
-        let _target_line = if let Some(target_start) = old_disk_xml.find("<target") {
-            if let Some(target_end) = old_disk_xml[target_start..].find("/>") {
-                Some(&old_disk_xml[target_start..target_start + target_end + 2])
-            } else {
-                None
-            }
-        } else {
-            None
-        };
-
-        // Extract disk type and device from opening tag
-        let disk_type = if old_disk_xml.contains("type='file'") {
-            "file"
-        } else if old_disk_xml.contains("type='block'") {
-            "block"
-        } else {
-            "file"
-        };
-
-        // Build new disk XML
-        let new_disk_xml = format!(
-            r#"<disk type='{}' device='disk'>
-  <driver {}/>
-  {}{}
-  <target dev='{}' bus='{}'/>
-</disk>"#,
-            disk_type,
-            driver_attrs.join(" "),
-            source_line.unwrap_or("<source file=''/>"),
-            iotune_xml,
-            device_target,
-            if old_disk_xml.contains("bus='virtio'") {
-                "virtio"
-            } else if old_disk_xml.contains("bus='scsi'") {
-                "scsi"
-            } else if old_disk_xml.contains("bus='sata'") {
-                "sata"
-            } else if old_disk_xml.contains("bus='ide'") {
-                "ide"
-            } else {
-                "virtio"
-            }
-        );
+        // Get current (persistent) definition and find the disk
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
+        let mut disk = xml.disk_element(device_target)
+            .map(domain_xml::Disk::from_element)
+            .ok_or_else(|| AppError::InvalidConfig(format!(
+                "Disk with target '{}' not found",
+                device_target
+            )))?;
+
+        // Keep the image format, replace the tunables
+        let format = disk.driver.as_ref()
+            .and_then(|driver| driver.format.clone())
+            .unwrap_or_else(|| "qcow2".to_string());
+        disk.driver = Some(domain_xml::DiskDriver {
+            name: Some("qemu".to_string()),
+            format: Some(format),
+            cache: cache.clone(),
+            io: io.clone(),
+            discard: discard.clone(),
+            detect_zeroes: detect_zeroes.clone(),
+        });
+        disk.iotune = Some(domain_xml::IoTune {
+            read_iops_sec,
+            write_iops_sec,
+            read_bytes_sec,
+            write_bytes_sec,
+        });
 
         // VM must be stopped to change disk settings (unless using blkdeviotune for throttling)
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
@@ -2614,7 +1804,7 @@ impl VmService {
                 args.push("--live".to_string());
                 args.push("--config".to_string());
 
-                let output = std::process::Command::new("virsh")
+                let output = libvirt.virsh()
                     .args(&args)
                     .output()
                     .map_err(|e| AppError::LibvirtError(format!("Failed to run virsh: {}", e)))?;
@@ -2631,16 +1821,12 @@ impl VmService {
                 return Ok(());
             }
         } else {
-            // For a stopped VM, update the persistent config
-            // We need to detach and reattach with new settings
-            let flags = sys::VIR_DOMAIN_AFFECT_CONFIG;
-
-            // Detach old disk
-            domain.detach_device_flags(old_disk_xml, flags)
-                .map_err(map_libvirt_error)?;
+            // For a stopped VM, update the disk in place in the persistent config
+            if let Some(element) = xml.disk_element_mut(device_target) {
+                disk.apply_to(element);
+            }
 
-            // Attach new disk
-            domain.attach_device_flags(&new_disk_xml, flags)
+            Domain::define_xml(conn, &xml.to_xml())
                 .map_err(map_libvirt_error)?;
 
             tracing::info!("Successfully updated disk settings for {} on VM {}", device_target, vm_id);
@@ -2669,65 +1855,53 @@ impl VmService {
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
 
         // Get current XML to check for existing CDROM
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
+        let xml = DomainXml::from_domain(&domain)?;
 
         // Check if there's already a CDROM device we can update
-        if let Some(cdrom_start) = xml.find("<disk type='file' device='cdrom'")
-            .or_else(|| xml.find("<disk type='block' device='cdrom'")) {
-
-            if let Some(cdrom_end) = xml[cdrom_start..].find("</disk>") {
-                let existing_cdrom = &xml[cdrom_start..cdrom_start + cdrom_end + 7];
-
-                // Extract target device and bus from existing CDROM
-                let target_dev = if let Some(target_start) = existing_cdrom.find("target dev='") {
-                    let dev_start = target_start + 12;
-                    if let Some(dev_end) = existing_cdrom[dev_start..].find("'") {
-                        &existing_cdrom[dev_start..dev_start + dev_end]
-                    } else { "hda" }
-                } else { "hda" };
-
-                let bus_type = if let Some(bus_start) = existing_cdrom.find("bus='") {
-                    let bus_val_start = bus_start + 5;
-                    if let Some(bus_end) = existing_cdrom[bus_val_start..].find("'") {
-                        &existing_cdrom[bus_val_start..bus_val_start + bus_end]
-                    } else { "ide" }
-                } else { "ide" };
-
-                // Create updated CDROM XML with the ISO
-                let updated_cdrom_xml = format!(
-                    r#"<disk type='file' device='cdrom'>
-  <driver name='qemu' type='raw'/>
-  <source file='{}'/>
-  <target dev='{}' bus='{}'/>
-  <readonly/>
-</disk>"#,
-                    iso_path, target_dev, bus_type
-                );
+        if let Some(mut cdrom) = xml.disks().into_iter().find(|disk| disk.device == "cdrom") {
+            // Point the existing drive (same target and bus) at the ISO
+            cdrom.disk_type = "file".to_string();
+            cdrom.driver = Some(domain_xml::DiskDriver {
+                name: Some("qemu".to_string()),
+                format: Some("raw".to_string()),
+                ..Default::default()
+            });
+            cdrom.source = Some(domain_xml::DiskSource { file: Some(iso_path.to_string()), ..Default::default() });
+            cdrom.readonly = true;
 
-                // Update the device
-                let flags = if is_running {
-                    sys::VIR_DOMAIN_DEVICE_MODIFY_LIVE | sys::VIR_DOMAIN_DEVICE_MODIFY_CONFIG
-                } else {
-                    sys::VIR_DOMAIN_DEVICE_MODIFY_CONFIG
-                };
+            let mut updated = xml.disk_element(&cdrom.target_dev).cloned()
+                .unwrap_or_else(|| XmlElement::new("disk"));
+            cdrom.apply_to(&mut updated);
 
-                domain.update_device_flags(&updated_cdrom_xml, flags)
-                    .map_err(map_libvirt_error)?;
+            // Update the device
+            let flags = if is_running {
+                sys::VIR_DOMAIN_DEVICE_MODIFY_LIVE | sys::VIR_DOMAIN_DEVICE_MODIFY_CONFIG
+            } else {
+                sys::VIR_DOMAIN_DEVICE_MODIFY_CONFIG
+            };
 
-                tracing::info!("Successfully mounted ISO to VM {} using existing CDROM", vm_id);
-                return Ok(());
-            }
+            domain.update_device_flags(&updated.to_xml(), flags)
+                .map_err(map_libvirt_error)?;
+
+            tracing::info!("Successfully mounted ISO to VM {} using existing CDROM", vm_id);
+            return Ok(());
         }
 
         // No existing CDROM - for running VMs, we need to use virtio-scsi which can be hotplugged
         // First, check if we need to add a SCSI controller
         if is_running {
             // Check if SCSI controller exists
-            if !xml.contains("controller type='scsi'") {
+            let has_scsi = xml.devices_named("controller").iter()
+                .any(|controller| controller.attr("type") == Some("scsi"));
+            if !has_scsi {
                 tracing::info!("Adding SCSI controller for hotplug CDROM support");
-                let scsi_controller_xml = r#"<controller type='scsi' model='virtio-scsi'/>"#;
+                let scsi_controller_xml = domain_xml::Controller {
+                    controller_type: "scsi".to_string(),
+                    model: Some("virtio-scsi".to_string()),
+                    ..Default::default()
+                }.to_xml();
                 if let Err(e) = domain.attach_device_flags(
-                    scsi_controller_xml,
+                    &scsi_controller_xml,
                     sys::VIR_DOMAIN_AFFECT_LIVE | sys::VIR_DOMAIN_AFFECT_CONFIG
                 ) {
                     tracing::warn!("Could not add SCSI controller: {}", e);
@@ -2735,15 +1909,7 @@ impl VmService {
             }
 
             // Try to add a SCSI CDROM (hotpluggable)
-            let cdrom_xml = format!(
-                r#"<disk type='file' device='cdrom'>
-  <driver name='qemu' type='raw'/>
-  <source file='{}'/>
-  <target dev='sda' bus='scsi'/>
-  <readonly/>
-</disk>"#,
-                iso_path
-            );
+            let cdrom_xml = domain_xml::Disk::file("cdrom", iso_path, "raw", "sda", "scsi").to_xml();
 
             match domain.attach_device_flags(
                 &cdrom_xml,
@@ -2764,15 +1930,7 @@ impl VmService {
         }
 
         // VM is not running - add SATA CDROM (better compatibility)
-        let cdrom_xml = format!(
-            r#"<disk type='file' device='cdrom'>
-  <driver name='qemu' type='raw'/>
-  <source file='{}'/>
-  <target dev='sda' bus='sata'/>
-  <readonly/>
-</disk>"#,
-            iso_path
-        );
+        let cdrom_xml = domain_xml::Disk::file("cdrom", iso_path, "raw", "sda", "sata").to_xml();
 
         domain.attach_device_flags(&cdrom_xml, sys::VIR_DOMAIN_AFFECT_CONFIG)
             .map_err(map_libvirt_error)?;
@@ -2792,20 +1950,13 @@ impl VmService {
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        // Get current XML to find the CDROM device
-        let xml = domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
-
         // Find CDROM device
-        let disk_start = xml.find("<disk type='file' device='cdrom'")
-            .or_else(|| xml.find("<disk type='block' device='cdrom'"))
+        let xml = DomainXml::from_domain(&domain)?;
+        let cdrom_xml = xml.devices_named("disk").into_iter()
+            .find(|disk| disk.attr("device") == Some("cdrom"))
+            .map(|disk| disk.to_xml())
             .ok_or_else(|| AppError::InvalidConfig("No CD ROM device found".to_string()))?;
 
-        let disk_end = xml[disk_start..].find("</disk>")
-            .ok_or_else(|| AppError::InvalidConfig("Malformed CDROM device XML".to_string()))?;
-
-        let cdrom_xml = &xml[disk_start..disk_start + disk_end + 7];
-
         // Detach CD (persistent and live if VM is running)
         let flags = if domain.is_active().map_err(map_libvirt_error)? {
             sys::VIR_DOMAIN_AFFECT_LIVE | sys::VIR_DOMAIN_AFFECT_CONFIG
@@ -2813,7 +1964,7 @@ impl VmService {
             sys::VIR_DOMAIN_AFFECT_CONFIG
         };
 
-        domain.detach_device_flags(cdrom_xml, flags)
+        domain.detach_device_flags(&cdrom_xml, flags)
             .map_err(map_libvirt_error)?;
 
         tracing::info!("Successfully ejected CD from VM {}", vm_id);
@@ -2844,14 +1995,11 @@ impl VmService {
 
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
 
-        // Build sound device XML
-        // For SPICE connections, we also add an audio element
-        let sound_xml = format!(
-            r#"<sound model='{}'>
-  <audio id='1'/>
-</sound>"#,
-            model
-        );
+        // For SPICE connections the card is wired to the first audio backend
+        let sound_xml = domain_xml::Sound {
+            model: model.to_string(),
+            audio_id: Some(1),
+        }.to_xml();
 
         let flags = if is_running {
             sys::VIR_DOMAIN_AFFECT_LIVE | sys::VIR_DOMAIN_AFFECT_CONFIG
@@ -2885,24 +2033,12 @@ impl VmService {
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        // Get current XML to find the sound device
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-
-        // Find sound device
-        let sound_start = xml.find("<sound model='")
+        // Find the sound device in the current definition
+        let xml = DomainXml::from_domain(&domain)?;
+        let sound_xml = xml.devices_named("sound").first()
+            .map(|sound| sound.to_xml())
             .ok_or_else(|| AppError::InvalidConfig("No sound device found".to_string()))?;
 
-        let sound_end = xml[sound_start..].find("</sound>")
-            .or_else(|| xml[sound_start..].find("/>")) // For self-closing tags
-            .ok_or_else(|| AppError::InvalidConfig("Malformed sound device XML".to_string()))?;
-
-        // Determine if it's self-closing or has a closing tag
-        let sound_xml = if xml[sound_start + sound_end..].starts_with("/>") {
-            &xml[sound_start..sound_start + sound_end + 2]
-        } else {
-            &xml[sound_start..sound_start + sound_end + 8] // includes </sound>
-        };
-
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
 
         let flags = if is_running {
@@ -2911,7 +2047,7 @@ impl VmService {
             sys::VIR_DOMAIN_AFFECT_CONFIG
         };
 
-        domain.detach_device_flags(sound_xml, flags)
+        domain.detach_device_flags(&sound_xml, flags)
             .map_err(|e| {
                 if is_running {
                     AppError::InvalidConfig(
@@ -2961,11 +2097,11 @@ impl VmService {
 
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
 
-        // Build input device XML
-        let input_xml = format!(
-            r#"<input type='{}' bus='{}'/>"#,
-            device_type, bus
-        );
+        let input_xml = domain_xml::Input {
+            input_type: device_type.to_string(),
+            bus: Some(bus.to_string()),
+            ..Default::default()
+        }.to_xml();
 
         let flags = if is_running {
             sys::VIR_DOMAIN_AFFECT_LIVE | sys::VIR_DOMAIN_AFFECT_CONFIG
@@ -3011,12 +2147,11 @@ impl VmService {
 
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
 
-        let rng_xml = format!(
-            r#"<rng model='virtio'>
-  <backend model='random'>{}</backend>
-</rng>"#,
-            backend
-        );
+        let rng_xml = domain_xml::Rng {
+            model: "virtio".to_string(),
+            backend_model: "random".to_string(),
+            source: Some(backend.to_string()),
+        }.to_xml();
 
         let flags = if is_running {
             sys::VIR_DOMAIN_AFFECT_LIVE | sys::VIR_DOMAIN_AFFECT_CONFIG
@@ -3074,10 +2209,10 @@ impl VmService {
 
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
 
-        let watchdog_xml = format!(
-            r#"<watchdog model='{}' action='{}'/>"#,
-            model, action
-        );
+        let watchdog_xml = domain_xml::Watchdog {
+            model: model.to_string(),
+            action: action.to_string(),
+        }.to_xml();
 
         let flags = if is_running {
             sys::VIR_DOMAIN_AFFECT_LIVE | sys::VIR_DOMAIN_AFFECT_CONFIG
@@ -3115,23 +2250,11 @@ impl VmService {
 
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
 
-        // Generate channel XML based on type
-        let channel_xml = match channel_type {
-            "qemu-ga" => {
-                r#"<channel type='unix'>
-  <target type='virtio' name='org.qemu.guest_agent.0'/>
-</channel>"#.to_string()
-            }
-            "kvmmanager-agent" => {
-                r#"<channel type='unix'>
-  <target type='virtio' name='org.kvmmanager.agent.0'/>
-</channel>"#.to_string()
-            }
-            "spice" => {
-                r#"<channel type='spicevmc'>
-  <target type='virtio' name='com.redhat.spice.0'/>
-</channel>"#.to_string()
-            }
+        // Pick the host side and channel name based on type
+        let (source_type, target_name) = match channel_type {
+            "qemu-ga" => ("unix", "org.qemu.guest_agent.0"),
+            "kvmmanager-agent" => ("unix", "org.kvmmanager.agent.0"),
+            "spice" => ("spicevmc", "com.redhat.spice.0"),
             _ => {
                 return Err(AppError::InvalidConfig(format!(
                     "Invalid channel type '{}'. Valid options: qemu-ga, kvmmanager-agent, spice",
@@ -3140,6 +2263,14 @@ impl VmService {
             }
         };
 
+        let channel_xml = domain_xml::CharDevice {
+            kind: "channel".to_string(),
+            source_type: source_type.to_string(),
+            target_type: Some("virtio".to_string()),
+            target_name: Some(target_name.to_string()),
+            ..Default::default()
+        }.to_xml();
+
         let flags = if is_running {
             sys::VIR_DOMAIN_AFFECT_LIVE | sys::VIR_DOMAIN_AFFECT_CONFIG
         } else {
@@ -3189,29 +2320,17 @@ impl VmService {
 
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
 
-        let readonly_attr = if readonly { " readonly='yes'" } else { "" };
-
         // Generate filesystem XML based on type
-        let fs_xml = match fs_type {
-            "virtio-9p" => {
-                format!(
-                    r#"<filesystem type='mount' accessmode='mapped'>
-  <source dir='{}'/>
-  <target dir='{}'/>
-</filesystem>"#,
-                    source_path, target_mount
-                )
-            }
+        let filesystem = XmlElement::new("filesystem").with_attr("type", "mount");
+        let filesystem = match fs_type {
+            "virtio-9p" => filesystem.with_attr("accessmode", "mapped"),
             "virtiofs" => {
                 // virtiofs requires a socket
-                format!(
-                    r#"<filesystem type='mount'>
-  <driver type='virtiofs'{} queue='1024'/>
-  <source dir='{}'/>
-  <target dir='{}'/>
-</filesystem>"#,
-                    readonly_attr, source_path, target_mount
-                )
+                let mut driver = XmlElement::new("driver").with_attr("type", "virtiofs");
+                if readonly {
+                    driver.set_attr("readonly", "yes");
+                }
+                filesystem.with_child(driver.with_attr("queue", 1024))
             }
             _ => {
                 return Err(AppError::InvalidConfig(format!(
@@ -3220,6 +2339,10 @@ impl VmService {
                 )));
             }
         };
+        let fs_xml = filesystem
+            .with_child(XmlElement::new("source").with_attr("dir", source_path))
+            .with_child(XmlElement::new("target").with_attr("dir", target_mount))
+            .to_xml();
 
         let flags = if is_running {
             sys::VIR_DOMAIN_AFFECT_LIVE | sys::VIR_DOMAIN_AFFECT_CONFIG
@@ -3258,29 +2381,33 @@ impl VmService {
 
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
 
+        let serial = domain_xml::CharDevice {
+            kind: "serial".to_string(),
+            source_type: port_type.to_string(),
+            target_port: Some(target_port),
+            ..Default::default()
+        };
+
         // Generate serial XML based on type
         let serial_xml = match port_type {
-            "pty" => format!(
-                r#"<serial type='pty'>
-  <target port='{}'/>
-</serial>"#,
-                target_port
-            ),
-            "tcp" => format!(
-                r#"<serial type='tcp'>
-  <source mode='bind' host='127.0.0.1' service='0'/>
-  <protocol type='raw'/>
-  <target port='{}'/>
-</serial>"#,
-                target_port
-            ),
-            "unix" => format!(
-                r#"<serial type='unix'>
-  <source mode='bind'/>
-  <target port='{}'/>
-</serial>"#,
-                target_port
-            ),
+            "pty" => serial.to_xml(),
+            "tcp" => {
+                let mut element = serial.to_element();
+                element.children.insert(0, XmlNode::Element(
+                    XmlElement::new("source")
+                        .with_attr("mode", "bind")
+                        .with_attr("host", "127.0.0.1")
+                        .with_attr("service", 0),
+                ));
+                element.children.insert(1, XmlNode::Element(
+                    XmlElement::new("protocol").with_attr("type", "raw"),
+                ));
+                element.to_xml()
+            }
+            "unix" => domain_xml::CharDevice {
+                source_mode: Some("bind".to_string()),
+                ..serial
+            }.to_xml(),
             _ => {
                 return Err(AppError::InvalidConfig(format!(
                     "Invalid serial port type '{}'. Valid options: pty, tcp, unix",
@@ -3325,27 +2452,20 @@ impl VmService {
 
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
 
-        // Generate console XML
-        let console_xml = match target_type {
-            "virtio" => format!(
-                r#"<console type='pty'>
-  <target type='virtio' port='{}'/>
-</console>"#,
-                target_port
-            ),
-            "serial" => format!(
-                r#"<console type='pty'>
-  <target type='serial' port='{}'/>
-</console>"#,
-                target_port
-            ),
-            _ => {
-                return Err(AppError::InvalidConfig(format!(
-                    "Invalid console target type '{}'. Valid options: virtio, serial",
-                    target_type
-                )));
-            }
-        };
+        if !["virtio", "serial"].contains(&target_type) {
+            return Err(AppError::InvalidConfig(format!(
+                "Invalid console target type '{}'. Valid options: virtio, serial",
+                target_type
+            )));
+        }
+
+        let console_xml = domain_xml::CharDevice {
+            kind: "console".to_string(),
+            source_type: "pty".to_string(),
+            target_type: Some(target_type.to_string()),
+            target_port: Some(target_port),
+            ..Default::default()
+        }.to_xml();
 
         let flags = if is_running {
             sys::VIR_DOMAIN_AFFECT_LIVE | sys::VIR_DOMAIN_AFFECT_CONFIG
@@ -3409,12 +2529,12 @@ impl VmService {
             )));
         }
 
-        let tpm_xml = format!(
-            r#"<tpm model='{}'>
-  <backend type='emulator' version='{}'/>
-</tpm>"#,
-            model, version
-        );
+        let tpm_xml = domain_xml::Tpm {
+            model: model.to_string(),
+            backend_type: "emulator".to_string(),
+            version: Some(version.to_string()),
+            device_path: None,
+        }.to_xml();
 
         domain.attach_device_flags(&tpm_xml, sys::VIR_DOMAIN_AFFECT_CONFIG)
             .map_err(map_libvirt_error)?;
@@ -3461,11 +2581,11 @@ impl VmService {
             }
         };
 
-        let controller_xml = format!(
-            r#"<controller type='{}' model='{}'/>
-"#,
-            controller_type, model_name
-        );
+        let controller_xml = domain_xml::Controller {
+            controller_type: controller_type.to_string(),
+            model: Some(model_name.to_string()),
+            ..Default::default()
+        }.to_xml();
 
         domain.attach_device_flags(&controller_xml, sys::VIR_DOMAIN_AFFECT_CONFIG)
             .map_err(map_libvirt_error)?;
@@ -3498,8 +2618,8 @@ impl VmService {
         }
 
         // Check if the VM has SPICE graphics
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-        if !xml.contains("type='spice'") {
+        let xml = DomainXml::from_domain(&domain)?;
+        if !Self::has_spice_graphics(&xml) {
             return Err(AppError::InvalidConfig(
                 "USB redirection requires SPICE graphics. Please add SPICE graphics first.".to_string()
             ));
@@ -3507,12 +2627,11 @@ impl VmService {
 
         // Add USB redirection channels
         for i in 0..count {
-            let redir_xml = format!(
-                r#"<redirdev bus='usb' type='spicevmc'>
-  <alias name='redir{}'/>
-</redirdev>"#,
-                i
-            );
+            let redir_xml = XmlElement::new("redirdev")
+                .with_attr("bus", "usb")
+                .with_attr("type", "spicevmc")
+                .with_child(XmlElement::new("alias").with_attr("name", format!("redir{}", i)))
+                .to_xml();
 
             match domain.attach_device_flags(&redir_xml, sys::VIR_DOMAIN_AFFECT_CONFIG) {
                 Ok(_) => {
@@ -3529,17 +2648,25 @@ impl VmService {
         Ok(())
     }
 
+    /// Whether the definition has a SPICE graphics console
+    fn has_spice_graphics(xml: &DomainXml) -> bool {
+        xml.devices_named("graphics").iter()
+            .any(|graphics| graphics.attr("type") == Some("spice"))
+    }
+
     /// Get USB redirection configuration for a VM
     pub fn get_usb_redirection(libvirt: &LibvirtService, vm_id: &str) -> Result<UsbRedirectionInfo, AppError> {
         let conn = libvirt.get_connection();
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
+        let xml = DomainXml::from_domain(&domain)?;
 
-        // Count redirdev elements with type='spicevmc'
-        let count = xml.matches("<redirdev bus='usb' type='spicevmc'").count() as u32;
-        let has_spice = xml.contains("type='spice'");
+        // Count USB redirdev elements with type='spicevmc'
+        let count = xml.devices_named("redirdev").iter()
+            .filter(|redir| redir.attr("bus") == Some("usb") && redir.attr("type") == Some("spicevmc"))
+            .count() as u32;
+        let has_spice = Self::has_spice_graphics(&xml);
 
         Ok(UsbRedirectionInfo {
             enabled: count > 0,
@@ -3564,26 +2691,13 @@ impl VmService {
             ));
         }
 
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-        let mut new_xml = xml.clone();
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
         // Remove all redirdev elements
-        while let Some(start) = new_xml.find("<redirdev") {
-            if let Some(end) = new_xml[start..].find("</redirdev>") {
-                let before = &new_xml[..start];
-                let after = &new_xml[start + end + 11..];
-                new_xml = format!("{}{}", before.trim_end(), after);
-            } else if let Some(end) = new_xml[start..].find("/>") {
-                let before = &new_xml[..start];
-                let after = &new_xml[start + end + 2..];
-                new_xml = format!("{}{}", before.trim_end(), after);
-            } else {
-                break;
-            }
-        }
+        xml.remove_devices(|device| device.name == "redirdev");
 
         // Redefine the domain with updated XML
-        Domain::define_xml(conn, &new_xml)
+        Domain::define_xml(conn, &xml.to_xml())
             .map_err(|e| AppError::Other(format!("Failed to remove USB redirection: {}", e)))?;
 
         tracing::info!("Successfully removed USB redirection channels from VM {}", vm_id);
@@ -3620,11 +2734,11 @@ impl VmService {
             )));
         }
 
-        let controller_xml = format!(
-            r#"<controller type='scsi' model='{}'/>
-"#,
-            model
-        );
+        let controller_xml = domain_xml::Controller {
+            controller_type: "scsi".to_string(),
+            model: Some(model.to_string()),
+            ..Default::default()
+        }.to_xml();
 
         domain.attach_device_flags(&controller_xml, sys::VIR_DOMAIN_AFFECT_CONFIG)
             .map_err(map_libvirt_error)?;
@@ -3653,43 +2767,20 @@ impl VmService {
             ));
         }
 
-        // Get current XML
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-
-        // Parse and rebuild XML with new boot order
-        // Find the <os> section
-        let os_start = xml.find("<os>")
-            .ok_or_else(|| AppError::InvalidConfig("No <os> section found in VM XML".to_string()))?;
-        let os_end = xml[os_start..].find("</os>")
-            .ok_or_else(|| AppError::InvalidConfig("Malformed <os> section in VM XML".to_string()))?;
-        let os_section = &xml[os_start..os_start + os_end + 5];
-
-        // Build new boot order XML
-        let boot_lines = boot_order
-            .iter()
-            .map(|dev| format!("    <boot dev='{}'/>"  , dev))
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        // Extract everything from <os> except old boot entries and bootmenu
-        let mut new_os_content = String::new();
-        for line in os_section.lines() {
-            let trimmed = line.trim();
-            if !trimmed.starts_with("<boot ") && !trimmed.starts_with("<bootmenu ") {
-                new_os_content.push_str(line);
-                new_os_content.push('\n');
-            }
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
+        if xml.root().child("os").is_none() {
+            return Err(AppError::InvalidConfig("No <os> section found in VM XML".to_string()));
         }
 
-        // Insert new boot order before </os>
-        let new_os_section = new_os_content.replace("</os>", &format!("{}\n  </os>", boot_lines));
-
-        // Replace old <os> section with new one in full XML
-        let new_xml = xml.replace(os_section, &new_os_section);
+        // Replace old boot entries; the boot menu is dropped as before
+        let mut os = xml.os();
+        os.boot_devices = boot_order;
+        os.boot_menu = None;
+        xml.set_os(&os);
 
         // Undefine and redefine domain with new XML
         domain.undefine().map_err(map_libvirt_error)?;
-        Domain::define_xml(conn, &new_xml).map_err(map_libvirt_error)?;
+        Domain::define_xml(conn, &xml.to_xml()).map_err(map_libvirt_error)?;
 
         tracing::info!("Successfully updated boot order for VM {}", vm_id);
         Ok(())
@@ -3733,53 +2824,52 @@ impl VmService {
             ));
         }
 
-        // Get current XML
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
         // Extract old name for disk renaming
         let old_name = domain.get_name().map_err(map_libvirt_error)?;
 
-        // Replace name in XML
-        let new_xml = if let Some(name_start) = xml.find("<name>") {
-            let name_end = xml[name_start..].find("</name>")
-                .ok_or_else(|| AppError::InvalidConfig("Malformed XML: no closing </name> tag".to_string()))?;
-
-            let before = &xml[..name_start + 6];
-            let after = &xml[name_start + name_end..];
-
-            format!("{}{}{}", before, new_name, after)
-        } else {
-            return Err(AppError::InvalidConfig("No <name> tag found in VM XML".to_string()));
-        };
+        xml.set_name(new_name);
 
         // Undefine the old domain
         domain.undefine().map_err(map_libvirt_error)?;
 
         // Define domain with new XML (new name)
-        let _new_domain = Domain::define_xml(conn, &new_xml).map_err(map_libvirt_error)?;
+        let _new_domain = Domain::define_xml(conn, &xml.to_xml()).map_err(map_libvirt_error)?;
 
         // Optionally rename disk files (best effort, don't fail if it doesn't work)
         // This is a convenience feature - we'll try to rename disk files to match the new VM name
-        let disk_paths = Self::get_vm_disk_paths(&Domain::lookup_by_name(conn, new_name).map_err(map_libvirt_error)?)?;
-        for disk_path in disk_paths {
-            if disk_path.contains(&old_name) {
-                let new_disk_path = disk_path.replace(&old_name, new_name);
-                if let Err(e) = std::fs::rename(&disk_path, &new_disk_path) {
-                    tracing::warn!("Failed to rename disk file {} to {}: {}. You may want to rename it manually.", disk_path, new_disk_path, e);
-                } else {
-                    tracing::info!("Renamed disk file {} to {}", disk_path, new_disk_path);
+        let mut renamed_any = false;
+        for mut disk in xml.disks() {
+            if disk.device == "cdrom" {
+                continue;
+            }
+            let disk_path = match disk.source.as_ref().and_then(|s| s.file.clone()) {
+                Some(path) if path.contains(&old_name) => path,
+                _ => continue,
+            };
 
-                    // Update XML with new disk path
-                    let updated_domain = Domain::lookup_by_name(conn, new_name).map_err(map_libvirt_error)?;
-                    let updated_xml = updated_domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-                    let final_xml = updated_xml.replace(&disk_path, &new_disk_path);
+            let new_disk_path = disk_path.replace(&old_name, new_name);
+            if let Err(e) = std::fs::rename(&disk_path, &new_disk_path) {
+                tracing::warn!("Failed to rename disk file {} to {}: {}. You may want to rename it manually.", disk_path, new_disk_path, e);
+                continue;
+            }
+            tracing::info!("Renamed disk file {} to {}", disk_path, new_disk_path);
 
-                    updated_domain.undefine().map_err(map_libvirt_error)?;
-                    Domain::define_xml(conn, &final_xml).map_err(map_libvirt_error)?;
+            if let Some(element) = xml.disk_element_mut(&disk.target_dev) {
+                if let Some(source) = disk.source.as_mut() {
+                    source.file = Some(new_disk_path);
                 }
+                disk.apply_to(element);
+                renamed_any = true;
             }
         }
 
+        // Update XML with new disk paths
+        if renamed_any {
+            Domain::define_xml(conn, &xml.to_xml()).map_err(map_libvirt_error)?;
+        }
+
         tracing::info!("Successfully renamed VM from {} to {}", old_name, new_name);
         Ok(())
     }
@@ -3849,15 +2939,10 @@ impl VmService {
             )
         });
 
-        // Build interface XML
-        let interface_xml = format!(
-            r#"<interface type='network'>
-  <mac address='{}'/>
-  <source network='{}'/>
-  <model type='{}'/>
-</interface>"#,
-            mac, network, model
-        );
+        let interface_xml = domain_xml::Interface {
+            mac: Some(mac.clone()),
+            ..domain_xml::Interface::network(network, model)
+        }.to_xml();
 
         // Attach interface (persistent and live if VM is running)
         let flags = if domain.is_active().map_err(map_libvirt_error)? {
@@ -3914,28 +2999,21 @@ impl VmService {
             )
         });
 
-        // Build interface XML based on type
-        let interface_xml = match interface_type {
+        let mut interface = domain_xml::Interface {
+            mac: Some(mac.clone()),
+            model: Some(model.to_string()),
+            ..Default::default()
+        };
+
+        // Fill in the source based on type
+        match interface_type {
             "network" => {
-                let pg_attr = portgroup.map(|p| format!(" portgroup='{}'", p)).unwrap_or_default();
-                format!(
-                    r#"<interface type='network'>
-  <mac address='{}'/>
-  <source network='{}'{}/>
-  <model type='{}'/>
-</interface>"#,
-                    mac, source, pg_attr, model
-                )
+                interface.interface_type = "network".to_string();
+                interface.source_network = Some(source.to_string());
             }
-            "bridge" => {
-                format!(
-                    r#"<interface type='bridge'>
-  <mac address='{}'/>
-  <source bridge='{}'/>
-  <model type='{}'/>
-</interface>"#,
-                    mac, source, model
-                )
+            "bridge" | "ovs" => {
+                interface.interface_type = "bridge".to_string();
+                interface.source_bridge = Some(source.to_string());
             }
             "direct" => {
                 // macvtap direct attachment to host interface
@@ -3947,27 +3025,9 @@ impl VmService {
                         mode, valid_modes
                     )));
                 }
-                format!(
-                    r#"<interface type='direct'>
-  <mac address='{}'/>
-  <source dev='{}' mode='{}'/>
-  <model type='{}'/>
-</interface>"#,
-                    mac, source, mode, model
-                )
-            }
-            "ovs" => {
-                // Open vSwitch bridge
-                let vlan_xml = vlan_id.map(|v| format!("\n  <vlan>\n    <tag id='{}'/>\n  </vlan>", v)).unwrap_or_default();
-                format!(
-                    r#"<interface type='bridge'>
-  <mac address='{}'/>
-  <source bridge='{}'/>
-  <virtualport type='openvswitch'/>
-  <model type='{}'/>{}
-</interface>"#,
-                    mac, source, model, vlan_xml
-                )
+                interface.interface_type = "direct".to_string();
+                interface.source_dev = Some(source.to_string());
+                interface.source_mode = Some(mode.to_string());
             }
             _ => {
                 return Err(AppError::InvalidConfig(format!(
@@ -3975,15 +3035,28 @@ impl VmService {
                     interface_type
                 )));
             }
-        };
+        }
+
+        let mut element = interface.to_element();
+        if interface_type == "network" {
+            if let (Some(portgroup), Some(source)) = (portgroup, element.child_mut("source")) {
+                source.set_attr("portgroup", portgroup);
+            }
+        }
+        if interface_type == "ovs" {
+            // Open vSwitch bridge
+            element.push_child(XmlElement::new("virtualport").with_attr("type", "openvswitch"));
+            if let Some(vlan) = vlan_id {
+                element.push_child(XmlElement::new("vlan")
+                    .with_child(XmlElement::new("tag").with_attr("id", vlan)));
+            }
+        }
 
         // Add MTU if specified
-        let interface_xml = if let Some(mtu_val) = mtu {
-            // Insert MTU before </interface>
-            interface_xml.replace("</interface>", &format!("  <mtu size='{}'/>\n</interface>", mtu_val))
-        } else {
-            interface_xml
-        };
+        if let Some(mtu_val) = mtu {
+            element.push_child(XmlElement::new("mtu").with_attr("size", mtu_val));
+        }
+        let interface_xml = element.to_xml();
 
         // Attach interface (persistent and live if VM is running)
         let flags = if domain.is_active().map_err(map_libvirt_error)? {
@@ -4090,35 +3163,13 @@ impl VmService {
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        // Get current XML to find the interface
-        let xml = domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
-
-        // Find interface by MAC address
-        let mac_pattern = format!("address='{}'", mac_address.to_lowercase());
-        let mut search_pos = 0;
-        let mut interface_xml = None;
-
-        while let Some(iface_pos) = xml[search_pos..].find("<interface") {
-            let abs_pos = search_pos + iface_pos;
-            if let Some(iface_end) = xml[abs_pos..].find("</interface>") {
-                let iface_section = &xml[abs_pos..abs_pos + iface_end + 12];
-
-                // Check if this interface has the MAC we're looking for
-                if iface_section.to_lowercase().contains(&mac_pattern) {
-                    interface_xml = Some(iface_section.to_string());
-                    break;
-                }
-
-                search_pos = abs_pos + iface_end + 12;
-            } else {
-                break;
-            }
-        }
-
-        let interface_xml = interface_xml.ok_or_else(|| {
-            AppError::InvalidConfig(format!("Interface with MAC '{}' not found", mac_address))
-        })?;
+        // Find interface by MAC address in the current definition
+        let xml = DomainXml::from_domain(&domain)?;
+        let interface_xml = xml.interface_element(mac_address)
+            .map(|interface| interface.to_xml())
+            .ok_or_else(|| {
+                AppError::InvalidConfig(format!("Interface with MAC '{}' not found", mac_address))
+            })?;
 
         // Detach interface (persistent and live if VM is running)
         let flags = if domain.is_active().map_err(map_libvirt_error)? {
@@ -4160,30 +3211,22 @@ impl VmService {
 
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
 
-        let listen = listen_address.unwrap_or("127.0.0.1");
-        let port_attr = match port {
-            Some(p) if p > 0 => format!("port='{}'", p),
-            _ => "port='-1' autoport='yes'".to_string(),
-        };
+        let port = port.filter(|p| *p > 0);
+        let mut graphics = domain_xml::Graphics {
+            graphics_type: graphics_type.to_string(),
+            port: Some(port.unwrap_or(-1)),
+            autoport: port.is_none(),
+            listen: Some(listen_address.unwrap_or("127.0.0.1").to_string()),
+            password: None,
+        }.to_element();
 
-        let graphics_xml = match graphics_type {
-            "spice" => format!(
-                r#"<graphics type='spice' {} listen='{}'>
-  <listen type='address' address='{}'/>
-  <image compression='auto_glz'/>
-  <streaming mode='filter'/>
-  <clipboard copypaste='yes'/>
-  <mouse mode='client'/>
-</graphics>"#,
-                port_attr, listen, listen
-            ),
-            _ => format!(
-                r#"<graphics type='vnc' {} listen='{}'>
-  <listen type='address' address='{}'/>
-</graphics>"#,
-                port_attr, listen, listen
-            ),
-        };
+        if graphics_type == "spice" {
+            graphics.push_child(XmlElement::new("image").with_attr("compression", "auto_glz"));
+            graphics.push_child(XmlElement::new("streaming").with_attr("mode", "filter"));
+            graphics.push_child(XmlElement::new("clipboard").with_attr("copypaste", "yes"));
+            graphics.push_child(XmlElement::new("mouse").with_attr("mode", "client"));
+        }
+        let graphics_xml = graphics.to_xml();
 
         let flags = if is_running {
             sys::VIR_DOMAIN_AFFECT_LIVE | sys::VIR_DOMAIN_AFFECT_CONFIG
@@ -4237,42 +3280,32 @@ impl VmService {
         let vram_kb = vram.unwrap_or(65536); // Default 64MB
         let heads_count = heads.unwrap_or(1);
 
-        let video_xml = match model {
-            "virtio" => {
-                let accel = if acceleration_3d { " accel3d='yes'" } else { "" };
-                format!(
-                    r#"<video>
-  <model type='virtio' heads='{}' primary='yes'{}>
-    <acceleration accel3d='{}'/>
-  </model>
-</video>"#,
-                    heads_count, accel, if acceleration_3d { "yes" } else { "no" }
-                )
-            }
-            "qxl" => format!(
-                r#"<video>
-  <model type='qxl' ram='{}' vram='{}' vgamem='16384' heads='{}' primary='yes'/>
-</video>"#,
-                vram_kb * 2, vram_kb, heads_count
-            ),
-            "vga" | "bochs" | "cirrus" => format!(
-                r#"<video>
-  <model type='{}' vram='{}' heads='{}' primary='yes'/>
-</video>"#,
-                model, vram_kb, heads_count
-            ),
-            "ramfb" => format!(
-                r#"<video>
-  <model type='ramfb' primary='yes'/>
-</video>"#
-            ),
-            _ => format!(
-                r#"<video>
-  <model type='{}' vram='{}' heads='{}' primary='yes'/>
-</video>"#,
-                model, vram_kb, heads_count
-            ),
+        let mut video = domain_xml::Video {
+            model: model.to_string(),
+            ..Default::default()
         };
+        match model {
+            "virtio" => video.heads = Some(heads_count),
+            "ramfb" => {}
+            _ => {
+                video.vram = Some(vram_kb as u64);
+                video.heads = Some(heads_count);
+            }
+        }
+
+        let mut element = video.to_element();
+        if let Some(model_element) = element.child_mut("model") {
+            if model == "qxl" {
+                model_element.set_attr("ram", vram_kb * 2);
+                model_element.set_attr("vgamem", 16384);
+            }
+            model_element.set_attr("primary", "yes");
+            if model == "virtio" {
+                model_element.push_child(XmlElement::new("acceleration")
+                    .with_attr("accel3d", if acceleration_3d { "yes" } else { "no" }));
+            }
+        }
+        let video_xml = element.to_xml();
 
         let flags = if is_running {
             sys::VIR_DOMAIN_AFFECT_LIVE | sys::VIR_DOMAIN_AFFECT_CONFIG
@@ -4313,30 +3346,14 @@ impl VmService {
                 )))?;
         } else {
             // For stopped VMs, update the domain XML
-            let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-
-            // Update vcpu count in XML
-            let new_xml = if let Some(start) = xml.find("<vcpu") {
-                if let Some(end) = xml[start..].find("</vcpu>") {
-                    let before = &xml[..start];
-                    let after = &xml[start + end + 7..];
-                    format!("{}<vcpu placement='static'>{}</vcpu>{}", before, vcpus, after)
-                } else {
-                    // Self-closing vcpu tag
-                    if let Some(end) = xml[start..].find("/>") {
-                        let before = &xml[..start];
-                        let after = &xml[start + end + 2..];
-                        format!("{}<vcpu placement='static'>{}</vcpu>{}", before, vcpus, after)
-                    } else {
-                        return Err(AppError::InvalidConfig("Invalid VM XML format".to_string()));
-                    }
-                }
-            } else {
+            let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
+            if xml.vcpus().is_none() {
                 return Err(AppError::InvalidConfig("No vcpu element found in VM XML".to_string()));
-            };
+            }
+            xml.set_vcpus(vcpus);
 
             // Redefine the domain with updated XML
-            Domain::define_xml(conn, &new_xml)
+            Domain::define_xml(conn, &xml.to_xml())
                 .map_err(|e| AppError::Other(format!("Failed to update VM vCPUs: {}", e)))?;
         }
 
@@ -4363,31 +3380,12 @@ impl VmService {
                 )))?;
         } else {
             // For stopped VMs, update the domain XML
-            let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-
-            // Update memory elements in XML
-            let mut new_xml = xml.clone();
-
-            // Update <memory> tag
-            if let Some(start) = new_xml.find("<memory") {
-                if let Some(end) = new_xml[start..].find("</memory>") {
-                    let before = &new_xml[..start];
-                    let after = &new_xml[start + end + 9..];
-                    new_xml = format!("{}<memory unit='KiB'>{}</memory>{}", before, memory_kb, after);
-                }
-            }
-
-            // Update <currentMemory> tag
-            if let Some(start) = new_xml.find("<currentMemory") {
-                if let Some(end) = new_xml[start..].find("</currentMemory>") {
-                    let before = &new_xml[..start];
-                    let after = &new_xml[start + end + 16..];
-                    new_xml = format!("{}<currentMemory unit='KiB'>{}</currentMemory>{}", before, memory_kb, after);
-                }
-            }
+            let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
+            xml.set_memory_kib(memory_kb);
+            xml.set_current_memory_kib(memory_kb);
 
             // Redefine the domain with updated XML
-            Domain::define_xml(conn, &new_xml)
+            Domain::define_xml(conn, &xml.to_xml())
                 .map_err(|e| AppError::Other(format!("Failed to update VM memory: {}", e)))?;
         }
 
@@ -4417,44 +3415,22 @@ impl VmService {
         }
 
         let total_vcpus = sockets * cores * threads;
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-        let mut new_xml = xml.clone();
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
         // Update vcpu count to match topology
-        if let Some(start) = new_xml.find("<vcpu") {
-            if let Some(end) = new_xml[start..].find("</vcpu>") {
-                let before = &new_xml[..start];
-                let after = &new_xml[start + end + 7..];
-                new_xml = format!("{}<vcpu placement='static'>{}</vcpu>{}", before, total_vcpus, after);
-            }
-        }
-
-        // Update or add CPU topology
-        let topology_xml = format!(
-            r#"<cpu mode='host-passthrough' check='none'>
-    <topology sockets='{}' cores='{}' threads='{}'/>
-  </cpu>"#,
-            sockets, cores, threads
-        );
+        xml.set_vcpus(total_vcpus);
 
-        if let Some(start) = new_xml.find("<cpu") {
-            // Replace existing CPU element
-            if let Some(end) = new_xml[start..].find("</cpu>") {
-                let before = &new_xml[..start];
-                let after = &new_xml[start + end + 6..];
-                new_xml = format!("{}{}{}", before, topology_xml, after);
-            }
-        } else {
-            // Insert CPU element after vcpu
-            if let Some(start) = new_xml.find("</vcpu>") {
-                let before = &new_xml[..start + 7];
-                let after = &new_xml[start + 7..];
-                new_xml = format!("{}\n  {}{}", before, topology_xml, after);
-            }
+        // Update or add CPU topology, keeping the existing mode, model and features
+        let mut cpu = xml.cpu();
+        if cpu.mode.is_none() {
+            cpu.mode = Some("host-passthrough".to_string());
+            cpu.check = Some("none".to_string());
         }
+        cpu.topology = Some(domain_xml::CpuTopology { sockets, dies: None, cores, threads });
+        xml.set_cpu(&cpu);
 
         // Redefine the domain with updated XML
-        Domain::define_xml(conn, &new_xml)
+        Domain::define_xml(conn, &xml.to_xml())
             .map_err(|e| AppError::Other(format!("Failed to update CPU topology: {}", e)))?;
 
         tracing::info!("Successfully set CPU topology to {}s/{}c/{}t for VM {}", sockets, cores, threads, vm_id);
@@ -4467,43 +3443,12 @@ impl VmService {
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
+        let cpu = DomainXml::from_domain(&domain)?.cpu();
+        let mode = cpu.mode.unwrap_or_else(|| "host-passthrough".to_string());
 
-        // Extract CPU mode
-        let mode = if let Some(cpu_start) = xml.find("<cpu") {
-            let cpu_section = &xml[cpu_start..];
-            if let Some(mode_start) = cpu_section.find("mode=") {
-                let after_mode = &cpu_section[mode_start + 6..];
-                let quote_char = cpu_section.chars().nth(mode_start + 5).unwrap_or('\'');
-                if let Some(mode_end) = after_mode.find(quote_char) {
-                    after_mode[..mode_end].to_string()
-                } else {
-                    "host-passthrough".to_string()
-                }
-            } else {
-                "host-passthrough".to_string()
-            }
-        } else {
-            "host-passthrough".to_string()
-        };
-
-        // Extract CPU model name (for custom mode)
+        // CPU model name is only meaningful in custom mode
         let model = if mode == "custom" {
-            if let Some(model_start) = xml.find("<model") {
-                let model_section = &xml[model_start..];
-                if let Some(content_start) = model_section.find('>') {
-                    let content = &model_section[content_start + 1..];
-                    if let Some(content_end) = content.find("</model>") {
-                        Some(content[..content_end].trim().to_string())
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
+            cpu.model.map(|model| model.name)
         } else {
             None
         };
@@ -4529,84 +3474,34 @@ impl VmService {
             ));
         }
 
-        let mut xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-
-        // Extract existing topology if present
-        let topology_xml = if let Some(topo_start) = xml.find("<topology") {
-            let topo_section = &xml[topo_start..];
-            if let Some(topo_end) = topo_section.find("/>") {
-                Some(topo_section[..topo_end + 2].to_string())
-            } else if let Some(topo_end) = topo_section.find("</topology>") {
-                Some(topo_section[..topo_end + 11].to_string())
-            } else {
-                None
-            }
-        } else {
-            None
-        };
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
-        // Build new CPU element
-        let new_cpu_xml = match mode {
-            "host-passthrough" => {
-                if let Some(topo) = topology_xml {
-                    format!(
-                        "<cpu mode='host-passthrough' check='none'>\n    {}\n  </cpu>",
-                        topo
-                    )
-                } else {
-                    "<cpu mode='host-passthrough' check='none'/>".to_string()
-                }
-            }
-            "host-model" => {
-                if let Some(topo) = topology_xml {
-                    format!(
-                        "<cpu mode='host-model' check='none'>\n    {}\n  </cpu>",
-                        topo
-                    )
-                } else {
-                    "<cpu mode='host-model' check='none'/>".to_string()
-                }
+        // Topology, NUMA cells and features are kept as they are
+        let mut cpu = xml.cpu();
+        cpu.mode = Some(mode.to_string());
+        cpu.check = Some("none".to_string());
+        match mode {
+            "host-passthrough" | "host-model" => {
+                cpu.match_mode = None;
+                cpu.model = None;
             }
             "custom" => {
-                let model_name = model.unwrap_or("qemu64");
-                if let Some(topo) = topology_xml {
-                    format!(
-                        "<cpu mode='custom' match='exact' check='none'>\n    <model fallback='forbid'>{}</model>\n    {}\n  </cpu>",
-                        model_name, topo
-                    )
-                } else {
-                    format!(
-                        "<cpu mode='custom' match='exact' check='none'>\n    <model fallback='forbid'>{}</model>\n  </cpu>",
-                        model_name
-                    )
-                }
+                cpu.match_mode = Some("exact".to_string());
+                cpu.model = Some(domain_xml::CpuModel {
+                    name: model.unwrap_or("qemu64").to_string(),
+                    fallback: Some("forbid".to_string()),
+                });
             }
             _ => {
                 return Err(AppError::InvalidConfig(
                     format!("Invalid CPU mode '{}'. Valid modes: host-passthrough, host-model, custom", mode)
                 ));
             }
-        };
-
-        // Replace or insert CPU element
-        if let Some(cpu_start) = xml.find("<cpu") {
-            if let Some(cpu_end_offset) = xml[cpu_start..].find("</cpu>") {
-                let cpu_end = cpu_start + cpu_end_offset + 6;
-                xml = format!("{}{}{}", &xml[..cpu_start], new_cpu_xml, &xml[cpu_end..]);
-            } else if let Some(cpu_end_offset) = xml[cpu_start..].find("/>") {
-                let cpu_end = cpu_start + cpu_end_offset + 2;
-                xml = format!("{}{}{}", &xml[..cpu_start], new_cpu_xml, &xml[cpu_end..]);
-            }
-        } else {
-            // Insert after </vcpu>
-            if let Some(vcpu_end) = xml.find("</vcpu>") {
-                let insert_pos = vcpu_end + 7;
-                xml = format!("{}\n  {}{}", &xml[..insert_pos], new_cpu_xml, &xml[insert_pos..]);
-            }
         }
+        xml.set_cpu(&cpu);
 
         // Redefine the domain
-        Domain::define_xml(conn, &xml)
+        Domain::define_xml(conn, &xml.to_xml())
             .map_err(|e| AppError::Other(format!("Failed to update CPU model: {}", e)))?;
 
         tracing::info!("Successfully set CPU mode to {} for VM {}", mode, vm_id);
@@ -4614,9 +3509,9 @@ impl VmService {
     }
 
     /// Get available CPU models from libvirt capabilities
-    pub fn get_available_cpu_models(_libvirt: &LibvirtService) -> Result<Vec<String>, AppError> {
+    pub fn get_available_cpu_models(libvirt: &LibvirtService) -> Result<Vec<String>, AppError> {
         // Try to get CPU models from capabilities
-        let output = std::process::Command::new("virsh")
+        let output = libvirt.virsh()
             .args(["cpu-models", "x86_64"])
             .output()
             .map_err(|e| AppError::Other(format!("Failed to run virsh: {}", e)))?;
@@ -4662,51 +3557,12 @@ impl VmService {
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-        let mut pinnings = Vec::new();
-
-        // Parse cputune/vcpupin elements from XML
-        // <cputune>
-        //   <vcpupin vcpu='0' cpuset='0-1'/>
-        //   <vcpupin vcpu='1' cpuset='2-3'/>
-        // </cputune>
-        if let Some(cputune_start) = xml.find("<cputune>") {
-            if let Some(cputune_end) = xml[cputune_start..].find("</cputune>") {
-                let cputune_section = &xml[cputune_start..cputune_start + cputune_end];
-
-                // Find all vcpupin elements
-                let mut search_start = 0;
-                while let Some(pin_start) = cputune_section[search_start..].find("<vcpupin") {
-                    let pin_abs_start = search_start + pin_start;
-                    if let Some(pin_end) = cputune_section[pin_abs_start..].find("/>") {
-                        let pin_element = &cputune_section[pin_abs_start..pin_abs_start + pin_end];
-
-                        // Extract vcpu number
-                        if let Some(vcpu_start) = pin_element.find("vcpu='") {
-                            let vcpu_val_start = vcpu_start + 6;
-                            if let Some(vcpu_end) = pin_element[vcpu_val_start..].find("'") {
-                                if let Ok(vcpu) = pin_element[vcpu_val_start..vcpu_val_start + vcpu_end].parse::<u32>() {
-                                    // Extract cpuset
-                                    if let Some(cpuset_start) = pin_element.find("cpuset='") {
-                                        let cpuset_val_start = cpuset_start + 8;
-                                        if let Some(cpuset_end) = pin_element[cpuset_val_start..].find("'") {
-                                            let cpuset_str = &pin_element[cpuset_val_start..cpuset_val_start + cpuset_end];
-                                            let cpus = Self::parse_cpuset(cpuset_str);
-                                            pinnings.push((vcpu, cpus));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        search_start = pin_abs_start + pin_end + 2;
-                    } else {
-                        break;
-                    }
-                }
-            }
-        }
+        let cputune = DomainXml::from_domain(&domain)?.cputune();
 
-        Ok(pinnings)
+        Ok(cputune.vcpupins
+            .iter()
+            .map(|pin| (pin.vcpu, Self::parse_cpuset(&pin.cpuset)))
+            .collect())
     }
 
     /// Parse a cpuset string like "0-3,5,7-9" into a vector of CPU numbers
@@ -4792,71 +3648,20 @@ impl VmService {
 
         tracing::info!("Setting CPU pin: vCPU {} -> host CPUs {} for VM {}", vcpu, cpuset, vm_id);
 
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-        let mut new_xml = xml.clone();
-
-        let vcpupin_xml = format!("<vcpupin vcpu='{}' cpuset='{}'/>", vcpu, cpuset);
-
-        // Check if cputune section exists
-        if let Some(cputune_start) = new_xml.find("<cputune>") {
-            // Check if this vcpu already has a pin
-            let vcpu_pattern = format!("vcpu='{}'", vcpu);
-            if let Some(cputune_end_pos) = new_xml[cputune_start..].find("</cputune>") {
-                let cputune_section = &new_xml[cputune_start..cputune_start + cputune_end_pos];
-
-                if cputune_section.contains(&vcpu_pattern) {
-                    // Replace existing vcpupin for this vcpu
-                    let mut search_start = cputune_start;
-                    while let Some(pin_start) = new_xml[search_start..].find("<vcpupin") {
-                        let pin_abs_start = search_start + pin_start;
-                        if let Some(pin_end) = new_xml[pin_abs_start..].find("/>") {
-                            let pin_element = &new_xml[pin_abs_start..pin_abs_start + pin_end + 2];
-                            if pin_element.contains(&vcpu_pattern) {
-                                let before = &new_xml[..pin_abs_start];
-                                let after = &new_xml[pin_abs_start + pin_end + 2..];
-                                new_xml = format!("{}{}{}", before, vcpupin_xml, after);
-                                break;
-                            }
-                            search_start = pin_abs_start + pin_end + 2;
-                        } else {
-                            break;
-                        }
-                    }
-                } else {
-                    // Add new vcpupin before </cputune>
-                    let insert_pos = cputune_start + cputune_end_pos;
-                    let before = &new_xml[..insert_pos];
-                    let after = &new_xml[insert_pos..];
-                    new_xml = format!("{}    {}\n  {}", before, vcpupin_xml, after);
-                }
-            }
-        } else {
-            // Create cputune section
-            let cputune_section = format!(
-                "\n  <cputune>\n    {}\n  </cputune>",
-                vcpupin_xml
-            );
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
-            // Insert after </vcpu> or </cpu>
-            if let Some(pos) = new_xml.find("</cpu>") {
-                let insert_pos = pos + 6;
-                let before = &new_xml[..insert_pos];
-                let after = &new_xml[insert_pos..];
-                new_xml = format!("{}{}{}", before, cputune_section, after);
-            } else if let Some(pos) = new_xml.find("</vcpu>") {
-                let insert_pos = pos + 7;
-                let before = &new_xml[..insert_pos];
-                let after = &new_xml[insert_pos..];
-                new_xml = format!("{}{}{}", before, cputune_section, after);
-            } else {
-                return Err(AppError::InvalidConfig("Cannot find suitable location for cputune in VM XML".to_string()));
-            }
+        // Replace an existing pin for this vCPU or add a new one
+        let mut cputune = xml.cputune();
+        match cputune.vcpupins.iter_mut().find(|pin| pin.vcpu == vcpu) {
+            Some(pin) => pin.cpuset = cpuset.clone(),
+            None => cputune.vcpupins.push(domain_xml::VcpuPin { vcpu, cpuset: cpuset.clone() }),
         }
+        xml.set_cputune(&cputune);
 
         // Apply changes
         // Note: For running VMs, ideally we'd use virDomainPinVcpu for live pinning
         // but for now we update the persistent config which takes effect on restart
-        Domain::define_xml(conn, &new_xml)
+        Domain::define_xml(conn, &xml.to_xml())
             .map_err(|e| AppError::Other(format!("Failed to update CPU pinning: {}", e)))?;
 
         tracing::info!("Successfully set CPU pin for vCPU {} to {} for VM {}", vcpu, cpuset, vm_id);
@@ -4882,65 +3687,14 @@ impl VmService {
 
         tracing::info!("Clearing CPU pin for vCPU {} on VM {}", vcpu, vm_id);
 
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-        let mut new_xml = xml.clone();
-
-        // Find and remove the vcpupin element for this vcpu
-        let vcpu_pattern = format!("vcpu='{}'", vcpu);
-
-        if let Some(cputune_start) = new_xml.find("<cputune>") {
-            let mut search_start = cputune_start;
-            while let Some(pin_start) = new_xml[search_start..].find("<vcpupin") {
-                let pin_abs_start = search_start + pin_start;
-                if let Some(pin_end) = new_xml[pin_abs_start..].find("/>") {
-                    let pin_element = &new_xml[pin_abs_start..pin_abs_start + pin_end + 2];
-                    if pin_element.contains(&vcpu_pattern) {
-                        // Remove this vcpupin element (including any preceding whitespace)
-                        let mut remove_start = pin_abs_start;
-                        // Look back for whitespace/newline
-                        while remove_start > 0 {
-                            let prev_char = new_xml.chars().nth(remove_start - 1);
-                            if prev_char == Some(' ') || prev_char == Some('\t') || prev_char == Some('\n') {
-                                remove_start -= 1;
-                            } else {
-                                break;
-                            }
-                        }
-                        let before = &new_xml[..remove_start];
-                        let after = &new_xml[pin_abs_start + pin_end + 2..];
-                        new_xml = format!("{}{}", before, after);
-                        break;
-                    }
-                    search_start = pin_abs_start + pin_end + 2;
-                } else {
-                    break;
-                }
-            }
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
-            // Check if cputune section is now empty
-            if let Some(new_cputune_start) = new_xml.find("<cputune>") {
-                if let Some(new_cputune_end) = new_xml[new_cputune_start..].find("</cputune>") {
-                    let cputune_content = &new_xml[new_cputune_start + 9..new_cputune_start + new_cputune_end];
-                    if cputune_content.trim().is_empty() {
-                        // Remove empty cputune section
-                        let mut remove_start = new_cputune_start;
-                        while remove_start > 0 {
-                            let prev_char = new_xml.chars().nth(remove_start - 1);
-                            if prev_char == Some(' ') || prev_char == Some('\t') || prev_char == Some('\n') {
-                                remove_start -= 1;
-                            } else {
-                                break;
-                            }
-                        }
-                        let before = &new_xml[..remove_start];
-                        let after = &new_xml[new_cputune_start + new_cputune_end + 10..];
-                        new_xml = format!("{}{}", before, after);
-                    }
-                }
-            }
-        }
+        // Remove the pin for this vCPU; an empty <cputune> is dropped entirely
+        let mut cputune = xml.cputune();
+        cputune.vcpupins.retain(|pin| pin.vcpu != vcpu);
+        xml.set_cputune(&cputune);
 
-        Domain::define_xml(conn, &new_xml)
+        Domain::define_xml(conn, &xml.to_xml())
             .map_err(|e| AppError::Other(format!("Failed to clear CPU pinning: {}", e)))?;
 
         tracing::info!("Successfully cleared CPU pin for vCPU {} on VM {}", vcpu, vm_id);
@@ -4978,12 +3732,7 @@ impl VmService {
             )));
         }
 
-        // Generate panic XML
-        let panic_xml = format!(
-            r#"<panic model='{}'/>
-"#,
-            model
-        );
+        let panic_xml = XmlElement::new("panic").with_attr("model", model).to_xml();
 
         domain.attach_device_flags(&panic_xml, sys::VIR_DOMAIN_AFFECT_CONFIG)
             .map_err(map_libvirt_error)?;
@@ -5017,13 +3766,10 @@ impl VmService {
             )));
         }
 
-        // Generate VSOCK XML
-        let vsock_xml = format!(
-            r#"<vsock model='virtio'>
-  <cid auto='no' value='{}'/>
-</vsock>"#,
-            cid
-        );
+        let vsock_xml = XmlElement::new("vsock")
+            .with_attr("model", "virtio")
+            .with_child(XmlElement::new("cid").with_attr("auto", "no").with_attr("value", cid))
+            .to_xml();
 
         let flags = if is_running {
             sys::VIR_DOMAIN_AFFECT_LIVE | sys::VIR_DOMAIN_AFFECT_CONFIG
@@ -5067,13 +3813,13 @@ impl VmService {
             ));
         }
 
-        // Generate parallel port XML (pty backend is most common)
-        let parallel_xml = format!(
-            r#"<parallel type='pty'>
-  <target port='{}'/>
-</parallel>"#,
-            target_port
-        );
+        // pty backend is most common
+        let parallel_xml = domain_xml::CharDevice {
+            kind: "parallel".to_string(),
+            source_type: "pty".to_string(),
+            target_port: Some(target_port),
+            ..Default::default()
+        }.to_xml();
 
         domain.attach_device_flags(&parallel_xml, sys::VIR_DOMAIN_AFFECT_CONFIG)
             .map_err(map_libvirt_error)?;
@@ -5249,47 +3995,31 @@ impl VmService {
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        let xml = domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
-
-        // Parse numatune section
-        let mut mode = "strict".to_string();
-        let mut nodeset: Option<String> = None;
-        let mut in_numatune = false;
+        let xml = DomainXml::from_domain(&domain)?;
+        let numatune = xml.numatune().unwrap_or_default();
 
-        for line in xml.lines() {
-            let trimmed = line.trim();
-            if trimmed == "<numatune>" {
-                in_numatune = true;
-            } else if trimmed == "</numatune>" {
-                in_numatune = false;
-            } else if in_numatune && trimmed.starts_with("<memory") {
-                // Parse mode
-                if let Some(mode_start) = trimmed.find("mode='") {
-                    let start = mode_start + 6;
-                    if let Some(end) = trimmed[start..].find('\'') {
-                        mode = trimmed[start..start + end].to_string();
-                    }
-                }
-                // Parse nodeset
-                if let Some(nodeset_start) = trimmed.find("nodeset='") {
-                    let start = nodeset_start + 9;
-                    if let Some(end) = trimmed[start..].find('\'') {
-                        nodeset = Some(trimmed[start..start + end].to_string());
-                    }
-                }
-            }
-        }
+        // Guest cells come from <cpu><numa>, their host placement from <numatune><memnode>
+        let cells: Vec<crate::models::vm::VmNumaCell> = xml.cpu().numa_cells
+            .into_iter()
+            .map(|cell| crate::models::vm::VmNumaCell {
+                id: cell.id,
+                cpus: cell.cpus,
+                memory_mb: cell.memory_kib / 1024,
+                host_nodes: numatune.memnodes.iter()
+                    .find(|node| node.cellid == cell.id)
+                    .map(|node| node.nodeset.clone()),
+            })
+            .collect();
 
         // Check if any NUMA config exists
-        if nodeset.is_none() {
+        if numatune.nodeset.is_none() && cells.is_empty() {
             return Ok(None);
         }
 
         Ok(Some(crate::models::vm::VmNumaConfig {
-            mode,
-            nodeset,
-            cells: Vec::new(), // Would need more parsing for guest NUMA cells
+            mode: numatune.mode.unwrap_or_else(|| "strict".to_string()),
+            nodeset: numatune.nodeset,
+            cells,
         }))
     }
 
@@ -5315,47 +4045,40 @@ impl VmService {
             )));
         }
 
-        // Get current XML
-        let xml = domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
-        // Build numatune XML
-        let nodeset_str = config.nodeset.as_deref().unwrap_or("0");
-        let numatune_xml = format!(
-            r#"<numatune>
-    <memory mode='{}' nodeset='{}'/>
-  </numatune>"#,
-            config.mode, nodeset_str
-        );
+        let mut numatune = xml.numatune().unwrap_or_default();
+        numatune.mode = Some(config.mode.clone());
+        numatune.nodeset = Some(config.nodeset.clone().unwrap_or_else(|| "0".to_string()));
 
-        // Replace or add numatune section
-        let new_xml = if xml.contains("<numatune>") {
-            // Replace existing numatune
-            let mut result = String::new();
-            let mut skip_until_close = false;
-            for line in xml.lines() {
-                let trimmed = line.trim();
-                if trimmed == "<numatune>" {
-                    skip_until_close = true;
-                    result.push_str("  ");
-                    result.push_str(&numatune_xml);
-                    result.push('\n');
-                } else if trimmed == "</numatune>" {
-                    skip_until_close = false;
-                } else if !skip_until_close {
-                    result.push_str(line);
-                    result.push('\n');
-                }
-            }
-            result
-        } else {
-            // Add numatune before <devices>
-            xml.replace("<devices>", &format!("{}\n  <devices>", numatune_xml))
-        };
+        // Guest cells are only rewritten when the caller supplies them
+        if !config.cells.is_empty() {
+            let mut cpu = xml.cpu();
+            cpu.numa_cells = config.cells.iter()
+                .map(|cell| domain_xml::NumaCell {
+                    id: cell.id,
+                    cpus: cell.cpus.clone(),
+                    memory_kib: cell.memory_mb * 1024,
+                    mem_access: cpu.numa_cells.iter()
+                        .find(|existing| existing.id == cell.id)
+                        .and_then(|existing| existing.mem_access.clone()),
+                })
+                .collect();
+            xml.set_cpu(&cpu);
+
+            numatune.memnodes = config.cells.iter()
+                .filter_map(|cell| cell.host_nodes.as_ref().map(|nodes| domain_xml::MemNode {
+                    cellid: cell.id,
+                    mode: config.mode.clone(),
+                    nodeset: nodes.clone(),
+                }))
+                .collect();
+        }
+        xml.set_numatune(Some(&numatune));
 
         // Undefine and redefine the domain with new XML
         domain.undefine().map_err(map_libvirt_error)?;
-        Domain::define_xml(conn, &new_xml)
+        Domain::define_xml(conn, &xml.to_xml())
             .map_err(|e| AppError::LibvirtError(format!("Failed to redefine VM with NUMA config: {}", e)))?;
 
         tracing::info!("Successfully set NUMA config for VM {}: mode={}, nodeset={:?}",
@@ -5376,33 +4099,17 @@ impl VmService {
             ));
         }
 
-        // Get current XML
-        let xml = domain.get_xml_desc(0)
-            .map_err(map_libvirt_error)?;
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
         // Remove numatune section if it exists
-        if !xml.contains("<numatune>") {
+        if xml.numatune().is_none() {
             return Ok(()); // Nothing to clear
         }
-
-        // Remove numatune section
-        let mut result = String::new();
-        let mut skip_until_close = false;
-        for line in xml.lines() {
-            let trimmed = line.trim();
-            if trimmed == "<numatune>" {
-                skip_until_close = true;
-            } else if trimmed == "</numatune>" {
-                skip_until_close = false;
-            } else if !skip_until_close {
-                result.push_str(line);
-                result.push('\n');
-            }
-        }
+        xml.set_numatune(None);
 
         // Undefine and redefine the domain with new XML
         domain.undefine().map_err(map_libvirt_error)?;
-        Domain::define_xml(conn, &result)
+        Domain::define_xml(conn, &xml.to_xml())
             .map_err(|e| AppError::LibvirtError(format!("Failed to redefine VM: {}", e)))?;
 
         tracing::info!("Successfully cleared NUMA config for VM {}", vm_id);
@@ -5411,12 +4118,16 @@ impl VmService {
 
     /// Migrate a VM to another host
     /// Supports both live (running) and offline (stopped) migration
+    ///
+    /// When run as a job, live migration progress is polled from the domain
+    /// job and cancelling aborts it with virDomainAbortJob.
     pub fn migrate_vm(
         libvirt: &LibvirtService,
         vm_id: &str,
         dest_uri: &str,
         live: bool,
         unsafe_migration: bool,
+        job: Option<&JobHandle>,
     ) -> Result<(), AppError> {
         let conn = libvirt.get_connection();
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
@@ -5429,6 +4140,9 @@ impl VmService {
                       if live { "live" } else { "offline" }, vm_name, dest_uri);
 
         // Connect to destination
+        if let Some(job) = job {
+            job.set_step(format!("Connecting to {}", dest_uri));
+        }
         let dest_conn = virt::connect::Connect::open(Some(dest_uri))
             .map_err(|e| AppError::LibvirtError(format!("Failed to connect to destination {}: {}", dest_uri, e)))?;
 
@@ -5441,9 +4155,24 @@ impl VmService {
                 flags |= sys::VIR_MIGRATE_UNSAFE;
             }
 
-            // Perform live migration
-            domain.migrate(&dest_conn, flags, None, None, 0)
-                .map_err(|e| AppError::LibvirtError(format!("Live migration failed: {}", e)))?;
+            // Perform live migration; a watcher thread reports progress and handles cancellation
+            if let Some(job) = job {
+                job.check_cancelled()?;
+                job.set_step("Migrating memory and state");
+            }
+            let finished = AtomicBool::new(false);
+            let result = std::thread::scope(|scope| {
+                if let Some(job) = job {
+                    scope.spawn(|| Self::watch_migration(conn, vm_id, job, &finished));
+                }
+                let result = domain.migrate(&dest_conn, flags, None, None, 0);
+                finished.store(true, Ordering::SeqCst);
+                result
+            });
+            result.map_err(|e| match job {
+                Some(job) if job.is_cancelled() => AppError::Cancelled,
+                _ => AppError::LibvirtError(format!("Live migration failed: {}", e)),
+            })?;
 
             tracing::info!("Live migration of VM {} completed successfully", vm_name);
         } else {
@@ -5466,6 +4195,46 @@ impl VmService {
         Ok(())
     }
 
+    /// Report migration progress until `finished` is set, aborting the domain job on cancel
+    fn watch_migration(conn: &virt::connect::Connect, vm_id: &str, job: &JobHandle, finished: &AtomicBool) {
+        let Ok(domain) = Domain::lookup_by_uuid_string(conn, vm_id) else {
+            return;
+        };
+        let mut aborted = false;
+
+        while !finished.load(Ordering::SeqCst) {
+            if job.is_cancelled() && !aborted {
+                match Self::abort_domain_job(&domain) {
+                    Ok(()) => tracing::info!("Aborted migration of VM {}", vm_id),
+                    Err(e) => tracing::warn!("Failed to abort migration of VM {}: {}", vm_id, e),
+                }
+                aborted = true;
+            }
+            if let Some((processed, total)) = Self::domain_job_progress(&domain) {
+                job.set_bytes(processed, total);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(500));
+        }
+    }
+
+    /// Bytes processed and total of the domain's active job (migration, save, dump)
+    pub fn domain_job_progress(domain: &Domain) -> Option<(u64, u64)> {
+        let mut info: sys::virDomainJobInfo = unsafe { std::mem::zeroed() };
+        let ret = unsafe { sys::virDomainGetJobInfo(domain.as_ptr(), &mut info) };
+        if ret < 0 || info.type_ == sys::VIR_DOMAIN_JOB_NONE as i32 || info.dataTotal == 0 {
+            return None;
+        }
+        Some((info.dataProcessed, info.dataTotal))
+    }
+
+    /// Abort the domain's active job
+    pub fn abort_domain_job(domain: &Domain) -> Result<(), AppError> {
+        if unsafe { sys::virDomainAbortJob(domain.as_ptr()) } < 0 {
+            return Err(map_libvirt_error(virt::error::Error::last_error()));
+        }
+        Ok(())
+    }
+
     /// Get migration capabilities and estimated time
     pub fn get_migration_info(libvirt: &LibvirtService, vm_id: &str) -> Result<MigrationInfo, AppError> {
         let conn = libvirt.get_connection();
@@ -5476,23 +4245,12 @@ impl VmService {
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
 
         // Get disk paths to estimate data transfer size
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-        let mut disk_size_bytes: u64 = 0;
-
-        for line in xml.lines() {
-            let trimmed = line.trim();
-            if trimmed.starts_with("<source file='") {
-                if let Some(start) = trimmed.find("file='") {
-                    let path_start = start + 6;
-                    if let Some(end) = trimmed[path_start..].find("'") {
-                        let path = &trimmed[path_start..path_start + end];
-                        if let Ok(metadata) = std::fs::metadata(path) {
-                            disk_size_bytes += metadata.len();
-                        }
-                    }
-                }
-            }
-        }
+        let xml = DomainXml::from_domain(&domain)?;
+        let disk_size_bytes: u64 = xml.disks().iter()
+            .filter_map(|disk| disk.source_path())
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum();
 
         Ok(MigrationInfo {
             can_live_migrate: is_running,
@@ -5508,62 +4266,15 @@ impl VmService {
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-
         // Extract kernel, initrd, cmdline, and dtb from OS section
-        let mut kernel_path: Option<String> = None;
-        let mut initrd_path: Option<String> = None;
-        let mut kernel_args: Option<String> = None;
-        let mut dtb_path: Option<String> = None;
-
-        // Find <kernel> element
-        if let Some(start) = xml.find("<kernel>") {
-            let start_pos = start + 8;
-            if let Some(end) = xml[start_pos..].find("</kernel>") {
-                kernel_path = Some(xml[start_pos..start_pos + end].to_string());
-            }
-        }
-
-        // Find <initrd> element
-        if let Some(start) = xml.find("<initrd>") {
-            let start_pos = start + 8;
-            if let Some(end) = xml[start_pos..].find("</initrd>") {
-                initrd_path = Some(xml[start_pos..start_pos + end].to_string());
-            }
-        }
-
-        // Find <cmdline> element
-        if let Some(start) = xml.find("<cmdline>") {
-            let start_pos = start + 9;
-            if let Some(end) = xml[start_pos..].find("</cmdline>") {
-                let args = xml[start_pos..start_pos + end].to_string();
-                // Unescape XML entities
-                let unescaped = args
-                    .replace("&amp;", "&")
-                    .replace("&lt;", "<")
-                    .replace("&gt;", ">")
-                    .replace("&quot;", "\"")
-                    .replace("&apos;", "'");
-                kernel_args = Some(unescaped);
-            }
-        }
-
-        // Find <dtb> element (for ARM systems)
-        if let Some(start) = xml.find("<dtb>") {
-            let start_pos = start + 5;
-            if let Some(end) = xml[start_pos..].find("</dtb>") {
-                dtb_path = Some(xml[start_pos..start_pos + end].to_string());
-            }
-        }
-
-        let enabled = kernel_path.is_some() || initrd_path.is_some() || kernel_args.is_some();
+        let os = DomainXml::from_domain(&domain)?.os();
 
         Ok(KernelBootSettings {
-            enabled,
-            kernel_path,
-            initrd_path,
-            kernel_args,
-            dtb_path,
+            enabled: os.has_direct_kernel(),
+            kernel_path: os.kernel,
+            initrd_path: os.initrd,
+            kernel_args: os.cmdline,
+            dtb_path: os.dtb,
         })
     }
 
@@ -5609,76 +4320,30 @@ impl VmService {
             }
         }
 
-        let mut xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
-        // Remove existing kernel boot elements
-        for tag in &["kernel", "initrd", "cmdline", "dtb"] {
-            let open_tag = format!("<{}>", tag);
-            let close_tag = format!("</{}>", tag);
-            while let Some(start) = xml.find(&open_tag) {
-                if let Some(end_offset) = xml[start..].find(&close_tag) {
-                    let end = start + end_offset + close_tag.len();
-                    // Remove the element and any trailing newline
-                    let remove_end = if xml[end..].starts_with('\n') { end + 1 } else { end };
-                    xml = format!("{}{}", &xml[..start], &xml[remove_end..]);
-                } else {
-                    break;
-                }
-            }
-        }
-
-        // Add new kernel boot elements if enabled
-        if settings.enabled {
-            // Find insertion point (after </type> in <os> section)
-            if let Some(type_end) = xml.find("</type>") {
-                let insert_pos = type_end + 7; // After </type>
-                let mut kernel_xml = String::new();
-
-                if let Some(ref kernel_path) = settings.kernel_path {
-                    if !kernel_path.is_empty() {
-                        kernel_xml.push_str(&format!("\n    <kernel>{}</kernel>", kernel_path));
-                    }
-                }
-
-                if let Some(ref initrd_path) = settings.initrd_path {
-                    if !initrd_path.is_empty() {
-                        kernel_xml.push_str(&format!("\n    <initrd>{}</initrd>", initrd_path));
-                    }
-                }
-
-                if let Some(ref kernel_args) = settings.kernel_args {
-                    if !kernel_args.is_empty() {
-                        // Escape XML special characters
-                        let escaped = kernel_args
-                            .replace("&", "&amp;")
-                            .replace("<", "&lt;")
-                            .replace(">", "&gt;")
-                            .replace("\"", "&quot;")
-                            .replace("'", "&apos;");
-                        kernel_xml.push_str(&format!("\n    <cmdline>{}</cmdline>", escaped));
-                    }
-                }
-
-                if let Some(ref dtb_path) = settings.dtb_path {
-                    if !dtb_path.is_empty() {
-                        kernel_xml.push_str(&format!("\n    <dtb>{}</dtb>", dtb_path));
-                    }
-                }
+        // Empty strings clear a field, as does disabling direct kernel boot
+        let non_empty = |value: &Option<String>| {
+            value.as_ref().filter(|v| settings.enabled && !v.is_empty()).cloned()
+        };
 
-                if !kernel_xml.is_empty() {
-                    xml = format!("{}{}{}", &xml[..insert_pos], kernel_xml, &xml[insert_pos..]);
-                }
-            }
-        }
+        let mut os = xml.os();
+        os.kernel = non_empty(&settings.kernel_path);
+        os.initrd = non_empty(&settings.initrd_path);
+        os.cmdline = non_empty(&settings.kernel_args);
+        os.dtb = non_empty(&settings.dtb_path);
+        xml.set_os(&os);
 
         // Undefine and redefine the domain with updated XML
         domain.undefine().map_err(map_libvirt_error)?;
-        Domain::define_xml(conn, &xml).map_err(map_libvirt_error)?;
+        Domain::define_xml(conn, &xml.to_xml()).map_err(map_libvirt_error)?;
 
         tracing::info!("Updated kernel boot settings for VM {}", vm_id);
         Ok(())
     }
 
+    /// Update network interface bandwidth/QoS settings
+    /// Units: average/peak in KB/s, burst in KB
     /// Update network interface bandwidth/QoS settings
     /// Units: average/peak in KB/s, burst in KB
     pub fn update_interface_bandwidth(
@@ -5697,127 +4362,34 @@ impl VmService {
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
-        let mut xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
         // Find the interface with matching MAC address
-        let mac_search = format!("<mac address='{}'", mac_address.to_lowercase());
-        let mac_search_alt = format!("<mac address=\"{}\"", mac_address.to_lowercase());
-
-        let interface_start = xml.to_lowercase()
-            .find(&mac_search.to_lowercase())
-            .or_else(|| xml.to_lowercase().find(&mac_search_alt.to_lowercase()));
-
-        if interface_start.is_none() {
-            return Err(AppError::InvalidConfig(
+        let element = xml.interface_element_mut(mac_address)
+            .ok_or_else(|| AppError::InvalidConfig(
                 format!("Network interface with MAC {} not found", mac_address)
-            ));
-        }
-
-        let interface_start = interface_start.unwrap();
-
-        // Find the <interface> start by searching backwards
-        let interface_open = xml[..interface_start].rfind("<interface")
-            .ok_or_else(|| AppError::InvalidConfig("Malformed interface XML".to_string()))?;
-
-        // Find </interface> end
-        let interface_close = xml[interface_start..].find("</interface>")
-            .map(|pos| interface_start + pos + 12)
-            .ok_or_else(|| AppError::InvalidConfig("Malformed interface XML".to_string()))?;
-
-        let interface_xml = xml[interface_open..interface_close].to_string();
+            ))?;
 
-        // Build new bandwidth element
-        let has_inbound = inbound_average.is_some() || inbound_peak.is_some() || inbound_burst.is_some();
-        let has_outbound = outbound_average.is_some() || outbound_peak.is_some() || outbound_burst.is_some();
-
-        let bandwidth_xml = if has_inbound || has_outbound {
-            let mut bw = String::from("      <bandwidth>\n");
-
-            if has_inbound {
-                bw.push_str("        <inbound");
-                if let Some(avg) = inbound_average {
-                    bw.push_str(&format!(" average='{}'", avg));
-                }
-                if let Some(peak) = inbound_peak {
-                    bw.push_str(&format!(" peak='{}'", peak));
-                }
-                if let Some(burst) = inbound_burst {
-                    bw.push_str(&format!(" burst='{}'", burst));
-                }
-                bw.push_str("/>\n");
-            }
-
-            if has_outbound {
-                bw.push_str("        <outbound");
-                if let Some(avg) = outbound_average {
-                    bw.push_str(&format!(" average='{}'", avg));
-                }
-                if let Some(peak) = outbound_peak {
-                    bw.push_str(&format!(" peak='{}'", peak));
-                }
-                if let Some(burst) = outbound_burst {
-                    bw.push_str(&format!(" burst='{}'", burst));
-                }
-                bw.push_str("/>\n");
+        // Build new bandwidth element; an empty one removes the limits
+        let limit = |average: Option<u64>, peak: Option<u64>, burst: Option<u64>| {
+            if average.is_some() || peak.is_some() || burst.is_some() {
+                Some(domain_xml::BandwidthLimit { average, peak, burst })
+            } else {
+                None
             }
-
-            bw.push_str("      </bandwidth>");
-            bw
-        } else {
-            String::new()
         };
-
-        // Remove existing bandwidth element if present
-        let mut new_interface_xml = interface_xml.clone();
-        if let Some(bw_start) = new_interface_xml.find("<bandwidth") {
-            if let Some(bw_end_offset) = new_interface_xml[bw_start..].find("</bandwidth>") {
-                let bw_end = bw_start + bw_end_offset + 12;
-                // Remove bandwidth and trailing whitespace/newline
-                let remove_end = if new_interface_xml[bw_end..].starts_with('\n') {
-                    bw_end + 1
-                } else {
-                    bw_end
-                };
-                new_interface_xml = format!("{}{}", &new_interface_xml[..bw_start], &new_interface_xml[remove_end..]);
-            }
-        }
-
-        // Insert new bandwidth before </interface>
-        if !bandwidth_xml.is_empty() {
-            let insert_pos = new_interface_xml.rfind("</interface>")
-                .ok_or_else(|| AppError::InvalidConfig("Malformed interface XML".to_string()))?;
-            new_interface_xml = format!(
-                "{}\n{}\n    {}",
-                &new_interface_xml[..insert_pos].trim_end(),
-                bandwidth_xml,
-                &new_interface_xml[insert_pos..]
-            );
-        }
-
-        // Replace the interface in the full XML
-        xml = format!(
-            "{}{}{}",
-            &xml[..interface_open],
-            new_interface_xml,
-            &xml[interface_close..]
-        );
+        let mut interface = domain_xml::Interface::from_element(element);
+        interface.bandwidth = Some(domain_xml::Bandwidth {
+            inbound: limit(inbound_average, inbound_peak, inbound_burst),
+            outbound: limit(outbound_average, outbound_peak, outbound_burst),
+        });
+        interface.apply_to(element);
 
         if is_running {
             // For running VMs, we need to use domiftune to apply bandwidth limits live
             // and also update the persistent config
             let vm_name = domain.get_name().map_err(map_libvirt_error)?;
-
-            // Find target device for this interface
-            let target_dev = new_interface_xml
-                .find("<target dev=")
-                .and_then(|pos| {
-                    let start = pos + 13;
-                    let quote_char = new_interface_xml.chars().nth(start - 1)?;
-                    new_interface_xml[start..].find(quote_char).map(|end| {
-                        new_interface_xml[start..start + end].to_string()
-                    })
-                })
-                .unwrap_or_else(|| mac_address.replace(":", ""));
+            let target_dev = Self::live_interface_target(&domain, mac_address)?;
 
             // Apply using virsh domiftune for live changes
             let mut args = vec![
@@ -5846,7 +4418,7 @@ impl VmService {
 
             if args.len() > 3 {
                 let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-                let output = std::process::Command::new("virsh")
+                let output = libvirt.virsh()
                     .args(&args_ref)
                     .output()
                     .map_err(|e| AppError::Other(format!("Failed to run virsh: {}", e)))?;
@@ -5860,16 +4432,11 @@ impl VmService {
                     tracing::info!("Applied live bandwidth limits to interface {}", target_dev);
                 }
             }
-
-            // Also update persistent config
-            domain.undefine().map_err(map_libvirt_error)?;
-            Domain::define_xml(conn, &xml).map_err(map_libvirt_error)?;
-        } else {
-            // For stopped VMs, just update the definition
-            domain.undefine().map_err(map_libvirt_error)?;
-            Domain::define_xml(conn, &xml).map_err(map_libvirt_error)?;
         }
 
+        // Update the persistent definition
+        Domain::define_xml(conn, &xml.to_xml()).map_err(map_libvirt_error)?;
+
         tracing::info!(
             "Updated bandwidth settings for interface {} on VM {}",
             mac_address, vm_id
@@ -5877,6 +4444,8 @@ impl VmService {
         Ok(())
     }
 
+    /// Set the link state (up/down) for a network interface
+    /// This simulates connecting/disconnecting the network cable
     /// Set the link state (up/down) for a network interface
     /// This simulates connecting/disconnecting the network cable
     pub fn set_interface_link_state(
@@ -5890,90 +4459,26 @@ impl VmService {
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
-        let mut xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
         // Find the interface with matching MAC address
-        let mac_search = format!("<mac address='{}'", mac_address.to_lowercase());
-        let mac_search_alt = format!("<mac address=\"{}\"", mac_address.to_lowercase());
-
-        let interface_start = xml.to_lowercase()
-            .find(&mac_search.to_lowercase())
-            .or_else(|| xml.to_lowercase().find(&mac_search_alt.to_lowercase()));
-
-        if interface_start.is_none() {
-            return Err(AppError::InvalidConfig(
+        let element = xml.interface_element_mut(mac_address)
+            .ok_or_else(|| AppError::InvalidConfig(
                 format!("Network interface with MAC {} not found", mac_address)
-            ));
-        }
-
-        let interface_start = interface_start.unwrap();
-
-        // Find the <interface> start by searching backwards
-        let interface_open = xml[..interface_start].rfind("<interface")
-            .ok_or_else(|| AppError::InvalidConfig("Malformed interface XML".to_string()))?;
+            ))?;
 
-        // Find </interface> end
-        let interface_close = xml[interface_start..].find("</interface>")
-            .map(|pos| interface_start + pos + 12)
-            .ok_or_else(|| AppError::InvalidConfig("Malformed interface XML".to_string()))?;
-
-        let interface_xml = xml[interface_open..interface_close].to_string();
-
-        // Remove existing link element if present
-        let mut new_interface_xml = interface_xml.clone();
-        if let Some(link_start) = new_interface_xml.find("<link ") {
-            if let Some(link_end_offset) = new_interface_xml[link_start..].find("/>") {
-                let link_end = link_start + link_end_offset + 2;
-                // Remove link and any trailing whitespace/newline
-                let remove_end = if new_interface_xml[link_end..].starts_with('\n') {
-                    link_end + 1
-                } else {
-                    link_end
-                };
-                new_interface_xml = format!("{}{}", &new_interface_xml[..link_start], &new_interface_xml[remove_end..]);
-            }
-        }
-
-        // Build new link element
         let link_state = if link_up { "up" } else { "down" };
-        let link_xml = format!("      <link state='{}'/>", link_state);
-
-        // Insert link element before </interface>
-        let insert_pos = new_interface_xml.rfind("</interface>")
-            .ok_or_else(|| AppError::InvalidConfig("Malformed interface XML".to_string()))?;
-        new_interface_xml = format!(
-            "{}\n{}\n    {}",
-            &new_interface_xml[..insert_pos].trim_end(),
-            link_xml,
-            &new_interface_xml[insert_pos..]
-        );
-
-        // Replace the interface in the full XML
-        xml = format!(
-            "{}{}{}",
-            &xml[..interface_open],
-            new_interface_xml,
-            &xml[interface_close..]
-        );
+        let mut interface = domain_xml::Interface::from_element(element);
+        interface.link_state = Some(link_state.to_string());
+        interface.apply_to(element);
 
         if is_running {
             // For running VMs, use virsh domif-setlink for live change
             let vm_name = domain.get_name().map_err(map_libvirt_error)?;
-
-            // Find target device for this interface
-            let target_dev = new_interface_xml
-                .find("<target dev=")
-                .and_then(|pos| {
-                    let start = pos + 13;
-                    let quote_char = new_interface_xml.chars().nth(start - 1)?;
-                    new_interface_xml[start..].find(quote_char).map(|end| {
-                        new_interface_xml[start..start + end].to_string()
-                    })
-                })
-                .unwrap_or_else(|| mac_address.replace(":", ""));
+            let target_dev = Self::live_interface_target(&domain, mac_address)?;
 
             // Apply using virsh domif-setlink for live changes
-            let output = std::process::Command::new("virsh")
+            let output = libvirt.virsh()
                 .args(["domif-setlink", &vm_name, &target_dev, link_state, "--live"])
                 .output()
                 .map_err(|e| AppError::Other(format!("Failed to run virsh: {}", e)))?;
@@ -5986,16 +4491,11 @@ impl VmService {
             }
 
             tracing::info!("Set live link state {} for interface {} on VM {}", link_state, target_dev, vm_id);
-
-            // Also update persistent config
-            domain.undefine().map_err(map_libvirt_error)?;
-            Domain::define_xml(conn, &xml).map_err(map_libvirt_error)?;
-        } else {
-            // For stopped VMs, just update the definition
-            domain.undefine().map_err(map_libvirt_error)?;
-            Domain::define_xml(conn, &xml).map_err(map_libvirt_error)?;
         }
 
+        // Update the persistent definition
+        Domain::define_xml(conn, &xml.to_xml()).map_err(map_libvirt_error)?;
+
         tracing::info!(
             "Set link state to {} for interface {} on VM {}",
             link_state, mac_address, vm_id
@@ -6003,6 +4503,17 @@ impl VmService {
         Ok(())
     }
 
+    /// Host-side target device of an interface in the live definition,
+    /// falling back to the MAC without separators
+    fn live_interface_target(domain: &Domain, mac_address: &str) -> Result<String, AppError> {
+        let xml = DomainXml::from_domain(domain)?;
+        Ok(xml.interface_element(mac_address)
+            .map(domain_xml::Interface::from_element)
+            .and_then(|interface| interface.target_dev)
+            .unwrap_or_else(|| mac_address.replace(":", "")))
+    }
+
+    /// Get the current link state of a network interface
     /// Get the current link state of a network interface
     pub fn get_interface_link_state(
         libvirt: &LibvirtService,
@@ -6014,63 +4525,31 @@ impl VmService {
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
         let is_running = domain.is_active().map_err(map_libvirt_error)?;
+        let interface = DomainXml::from_domain(&domain)?
+            .interface_element(mac_address)
+            .map(domain_xml::Interface::from_element);
 
         if is_running {
             // For running VMs, use virsh domif-getlink
             let vm_name = domain.get_name().map_err(map_libvirt_error)?;
 
-            // Find target device from XML
-            let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-            let mac_search = format!("<mac address='{}'", mac_address.to_lowercase());
-
-            if let Some(mac_pos) = xml.to_lowercase().find(&mac_search.to_lowercase()) {
-                // Find target dev after mac
-                let search_area = &xml[mac_pos..];
-                if let Some(target_pos) = search_area.find("<target dev=") {
-                    let start = target_pos + 13;
-                    let quote_char = search_area.chars().nth(start - 1).unwrap_or('\'');
-                    if let Some(end) = search_area[start..].find(quote_char) {
-                        let target_dev = &search_area[start..start + end];
-
-                        let output = std::process::Command::new("virsh")
-                            .args(["domif-getlink", &vm_name, target_dev])
-                            .output()
-                            .map_err(|e| AppError::Other(format!("Failed to run virsh: {}", e)))?;
-
-                        if output.status.success() {
-                            let stdout = String::from_utf8_lossy(&output.stdout);
-                            return Ok(!stdout.contains("down"));
-                        }
-                    }
-                }
-            }
-        }
+            if let Some(target_dev) = interface.as_ref().and_then(|i| i.target_dev.as_deref()) {
+                let output = libvirt.virsh()
+                    .args(["domif-getlink", &vm_name, target_dev])
+                    .output()
+                    .map_err(|e| AppError::Other(format!("Failed to run virsh: {}", e)))?;
 
-        // Fall back to reading from XML definition
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-        let mac_search = format!("<mac address='{}'", mac_address.to_lowercase());
-
-        if let Some(mac_pos) = xml.to_lowercase().find(&mac_search.to_lowercase()) {
-            // Find the interface block
-            let interface_start = xml[..mac_pos].rfind("<interface").unwrap_or(0);
-            let interface_end = xml[mac_pos..].find("</interface>").map(|p| mac_pos + p).unwrap_or(xml.len());
-            let interface_block = &xml[interface_start..interface_end];
-
-            // Look for link state
-            if let Some(link_pos) = interface_block.find("<link ") {
-                if let Some(state_pos) = interface_block[link_pos..].find("state=") {
-                    let state_start = link_pos + state_pos + 7; // skip state='
-                    let state_value = &interface_block[state_start..];
-                    if let Some(end) = state_value.find(['\'', '"']) {
-                        let state = &state_value[..end];
-                        return Ok(state != "down");
-                    }
+                if output.status.success() {
+                    let stdout = String::from_utf8_lossy(&output.stdout);
+                    return Ok(!stdout.contains("down"));
                 }
             }
         }
 
-        // Default to link up if not specified
-        Ok(true)
+        // Fall back to the XML definition; default to link up if not specified
+        Ok(interface
+            .and_then(|i| i.link_state)
+            .is_none_or(|state| state != "down"))
     }
 
     /// Get hugepages memory backing configuration for a VM
@@ -6079,33 +4558,13 @@ impl VmService {
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-
-        // Check for <memoryBacking><hugepages> element
-        let enabled = xml.contains("<hugepages");
-        let mut size: Option<u64> = None;
-
-        if enabled {
-            // Try to extract page size if specified
-            // <page size='2048' unit='KiB'/>
-            if let Some(page_start) = xml.find("<page size='") {
-                let rest = &xml[page_start + 12..];
-                if let Some(end) = rest.find("'") {
-                    if let Ok(page_size) = rest[..end].parse::<u64>() {
-                        // Check unit and convert to KiB if needed
-                        if rest.contains("unit='KiB'") || rest.contains("unit='kib'") {
-                            size = Some(page_size);
-                        } else if rest.contains("unit='MiB'") || rest.contains("unit='mib'") {
-                            size = Some(page_size * 1024);
-                        } else if rest.contains("unit='GiB'") || rest.contains("unit='gib'") {
-                            size = Some(page_size * 1024 * 1024);
-                        }
-                    }
-                }
-            }
-        }
+        // Check for <memoryBacking><hugepages> element; page size is reported in KiB
+        let backing = DomainXml::from_domain(&domain)?.memory_backing().unwrap_or_default();
 
-        Ok(HugepagesSettings { enabled, size })
+        Ok(HugepagesSettings {
+            enabled: backing.hugepages.is_some(),
+            size: backing.hugepage_size_kib(),
+        })
     }
 
     /// Set hugepages memory backing for a VM (requires VM to be stopped)
@@ -6126,57 +4585,23 @@ impl VmService {
             ));
         }
 
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-        let mut new_xml = xml.clone();
-
-        // Remove existing memoryBacking element if present
-        if let Some(start) = new_xml.find("<memoryBacking>") {
-            if let Some(end) = new_xml.find("</memoryBacking>") {
-                let before = &new_xml[..start];
-                let after = &new_xml[end + 16..];
-                new_xml = format!("{}{}", before.trim_end(), after);
-            }
-        } else if let Some(start) = new_xml.find("<memoryBacking/>") {
-            let before = &new_xml[..start];
-            let after = &new_xml[start + 16..];
-            new_xml = format!("{}{}", before.trim_end(), after);
-        }
-
-        // Add new memoryBacking if enabled
-        if enabled {
-            let memory_backing_xml = if let Some(page_size) = size {
-                format!(
-                    r#"
-  <memoryBacking>
-    <hugepages>
-      <page size='{}' unit='KiB'/>
-    </hugepages>
-  </memoryBacking>"#,
-                    page_size
-                )
-            } else {
-                r#"
-  <memoryBacking>
-    <hugepages/>
-  </memoryBacking>"#.to_string()
-            };
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
-            // Insert after currentMemory element
-            if let Some(pos) = new_xml.find("</currentMemory>") {
-                let insert_pos = pos + 16;
-                let before = &new_xml[..insert_pos];
-                let after = &new_xml[insert_pos..];
-                new_xml = format!("{}{}{}", before, memory_backing_xml, after);
-            } else if let Some(pos) = new_xml.find("</memory>") {
-                let insert_pos = pos + 9;
-                let before = &new_xml[..insert_pos];
-                let after = &new_xml[insert_pos..];
-                new_xml = format!("{}{}{}", before, memory_backing_xml, after);
-            }
-        }
+        // Other memoryBacking options (locked, nosharepages, source, access) are kept
+        let mut backing = xml.memory_backing().unwrap_or_default();
+        backing.hugepages = if enabled {
+            Some(size.map(|page_size| vec![domain_xml::HugePage {
+                size: page_size,
+                unit: Some("KiB".to_string()),
+                nodeset: None,
+            }]).unwrap_or_default())
+        } else {
+            None
+        };
+        xml.set_memory_backing(Some(&backing));
 
         // Redefine the domain with updated XML
-        Domain::define_xml(conn, &new_xml)
+        Domain::define_xml(conn, &xml.to_xml())
             .map_err(|e| AppError::Other(format!("Failed to update hugepages settings: {}", e)))?;
 
         tracing::info!(
@@ -6351,14 +4776,13 @@ impl VmService {
             "passthrough"
         };
 
-        let grab_attr = if grab_all { " grab='all'" } else { "" };
-
-        let evdev_xml = format!(
-            r#"<input type='{}' bus='virtio'>
-  <source dev='{}'{}/>
-</input>"#,
-            input_type, device_path, grab_attr
-        );
+        let evdev_xml = domain_xml::Input {
+            input_type: input_type.to_string(),
+            bus: Some("virtio".to_string()),
+            source_dev: Some(device_path.to_string()),
+            grab: grab_all.then(|| "all".to_string()),
+            repeat: None,
+        }.to_xml();
 
         domain.attach_device_flags(&evdev_xml, sys::VIR_DOMAIN_AFFECT_CONFIG)
             .map_err(|e| AppError::Other(format!("Failed to attach evdev device: {}", e)))?;
@@ -6373,32 +4797,11 @@ impl VmService {
         let domain = Domain::lookup_by_uuid_string(conn, vm_id)
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-        let mut devices = Vec::new();
-
-        // Find all <input> elements with <source dev=.../>
-        let mut search_start = 0;
-        while let Some(input_start) = xml[search_start..].find("<input") {
-            let abs_start = search_start + input_start;
-            if let Some(input_end) = xml[abs_start..].find("</input>") {
-                let input_section = &xml[abs_start..abs_start + input_end];
-
-                // Look for source dev attribute
-                if let Some(source_start) = input_section.find("<source dev='") {
-                    let dev_start = source_start + 13;
-                    if let Some(dev_end) = input_section[dev_start..].find("'") {
-                        let device_path = &input_section[dev_start..dev_start + dev_end];
-                        devices.push(device_path.to_string());
-                    }
-                }
-
-                search_start = abs_start + input_end + 8;
-            } else {
-                break;
-            }
-        }
-
-        Ok(devices)
+        // All <input> elements with <source dev=.../>
+        let xml = DomainXml::from_domain(&domain)?;
+        Ok(xml.devices_named("input").into_iter()
+            .filter_map(|input| domain_xml::Input::from_element(input).source_dev)
+            .collect())
     }
 
     /// Remove an evdev device from a VM
@@ -6421,25 +4824,16 @@ impl VmService {
             ));
         }
 
-        let xml = domain.get_xml_desc(0).map_err(map_libvirt_error)?;
-        let mut new_xml = xml.clone();
+        let mut xml = DomainXml::from_domain_flags(&domain, sys::VIR_DOMAIN_XML_INACTIVE)?;
 
-        // Find and remove the input element with this device path
-        let search_pattern = format!("dev='{}'", device_path);
-        if let Some(source_pos) = new_xml.find(&search_pattern) {
-            // Find the enclosing <input> element
-            let before_source = &new_xml[..source_pos];
-            if let Some(input_start) = before_source.rfind("<input") {
-                if let Some(input_end) = new_xml[input_start..].find("</input>") {
-                    let before = &new_xml[..input_start];
-                    let after = &new_xml[input_start + input_end + 8..];
-                    new_xml = format!("{}{}", before.trim_end(), after);
-                }
-            }
-        }
+        // Remove the input element with this device path
+        xml.remove_devices(|device| {
+            device.name == "input"
+                && domain_xml::Input::from_element(device).source_dev.as_deref() == Some(device_path)
+        });
 
         // Redefine the domain
-        Domain::define_xml(conn, &new_xml)
+        Domain::define_xml(conn, &xml.to_xml())
             .map_err(|e| AppError::Other(format!("Failed to detach evdev device: {}", e)))?;
 
         tracing::info!("Successfully detached evdev device {} from VM {}", device_path, vm_id);
@@ -6532,51 +4926,46 @@ impl VmService {
             .map_err(|_| AppError::VmNotFound(vm_id.to_string()))?;
 
         // Get domain XML to check for migration blockers
-        let xml = domain.get_xml_desc(0)
-            .map_err(|e| AppError::LibvirtError(format!("Failed to get VM XML: {}", e)))?;
+        let xml = DomainXml::from_domain(&domain)?;
 
-        // Simple XML parsing to check for migration blockers
         // Check for local file-based disks
-        for line in xml.lines() {
-            let trimmed = line.trim();
-
-            // Check for local disk files
-            if trimmed.starts_with("<source file='") {
-                if let Some(start) = trimmed.find("file='") {
-                    let path_start = start + 6;
-                    if let Some(end) = trimmed[path_start..].find("'") {
-                        let path = &trimmed[path_start..path_start + end];
-                        // Local files may need shared storage for migration
-                        if path.starts_with("/var/lib/libvirt/images/") ||
-                           path.starts_with("/home/") ||
-                           (!path.contains("://") && !path.starts_with("/dev/")) {
-                            warnings.push(format!("Local disk '{}' - may need shared storage", path));
-                        }
-                    }
+        for disk in xml.disks() {
+            if let Some(path) = disk.source_path() {
+                // Local files may need shared storage for migration
+                if path.starts_with("/var/lib/libvirt/images/") ||
+                   path.starts_with("/home/") ||
+                   (!path.contains("://") && !path.starts_with("/dev/")) {
+                    warnings.push(format!("Local disk '{}' - may need shared storage", path));
                 }
             }
+        }
 
-            // Check for PCI host device passthrough
-            if trimmed.contains("<hostdev mode='subsystem' type='pci'") {
-                can_migrate = false;
-                warnings.push("PCI passthrough device detected - prevents live migration".to_string());
-            }
-
-            // Check for USB host device passthrough
-            if trimmed.contains("<hostdev mode='subsystem' type='usb'") {
-                warnings.push("USB passthrough device detected - may affect migration".to_string());
+        for hostdev in xml.devices_named("hostdev") {
+            if hostdev.attr("mode") != Some("subsystem") {
+                continue;
             }
-
-            // Check for TPM
-            if trimmed.starts_with("<tpm ") {
-                warnings.push("TPM device detected - state may not transfer correctly".to_string());
+            match hostdev.attr("type") {
+                // Check for PCI host device passthrough
+                Some("pci") => {
+                    can_migrate = false;
+                    warnings.push("PCI passthrough device detected - prevents live migration".to_string());
+                }
+                // Check for USB host device passthrough
+                Some("usb") => {
+                    warnings.push("USB passthrough device detected - may affect migration".to_string());
+                }
+                // Check for MDEV passthrough
+                Some("mdev") => {
+                    can_migrate = false;
+                    warnings.push("MDEV passthrough device detected - prevents live migration".to_string());
+                }
+                _ => {}
             }
+        }
 
-            // Check for MDEV passthrough
-            if trimmed.contains("<hostdev mode='subsystem' type='mdev'") {
-                can_migrate = false;
-                warnings.push("MDEV passthrough device detected - prevents live migration".to_string());
-            }
+        // Check for TPM
+        if xml.has_device("tpm") {
+            warnings.push("TPM device detected - state may not transfer correctly".to_string());
         }
 
         Ok((can_migrate, warnings))
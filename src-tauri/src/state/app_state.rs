@@ -1,8 +1,16 @@
 use std::sync::Arc;
 use crate::services::libvirt::LibvirtService;
+use crate::services::alert_service::AlertService;
+use crate::services::alert_history_service::AlertHistoryService;
+use crate::services::alert_rule_service::AlertRuleService;
+use crate::services::notification_service::NotificationService;
 use crate::services::connection_service::ConnectionService;
+use crate::services::event_service::EventService;
+use crate::services::job_service::JobService;
 use crate::services::metrics_service::MetricsService;
 use crate::services::retention_service::RetentionService;
+use crate::services::scheduler_service::SchedulerService;
+use crate::services::snapshot_retention_service::SnapshotRetentionService;
 use crate::services::guest_agent_service::GuestAgentService;
 use crate::services::ws_proxy::WsProxyService;
 use crate::services::serial_console_service::SerialConsoleService;
@@ -10,10 +18,17 @@ use crate::utils::error::AppError;
 
 /// Application state shared across all Tauri commands
 pub struct AppState {
-    pub libvirt: Arc<LibvirtService>,
     pub connections: Arc<ConnectionService>,
+    pub events: Arc<EventService>,
+    pub jobs: Arc<JobService>,
     pub metrics: Arc<MetricsService>,
     pub retention_service: Arc<RetentionService>,
+    pub scheduler: Arc<SchedulerService>,
+    pub snapshot_retention: Arc<SnapshotRetentionService>,
+    pub alerts: Arc<AlertService>,
+    pub alert_rules: Arc<AlertRuleService>,
+    pub alert_history: Arc<AlertHistoryService>,
+    pub notifications: Arc<NotificationService>,
     pub guest_agent: Arc<GuestAgentService>,
     pub ws_proxy: Arc<WsProxyService>,
     pub serial_console: Arc<SerialConsoleService>,
@@ -24,14 +39,28 @@ impl AppState {
     pub fn new() -> Result<Self, AppError> {
         tracing::info!("Initializing AppState");
 
-        let libvirt = Arc::new(LibvirtService::new()?);
-        let connections = Arc::new(ConnectionService::new());
+        // The event loop must exist before any connection is opened
+        EventService::init_event_loop()?;
+        let events = Arc::new(EventService::new());
 
-        // Auto-connect to local
-        connections.connect("local")?;
+        let connections = Arc::new(ConnectionService::new(events.clone())?);
 
+        // Auto-connect to local; if libvirtd is down start disconnected and let
+        // the supervisor bring the connection up once it is reachable
+        if let Err(e) = connections.connect("local") {
+            tracing::error!("Failed to connect to local libvirt, starting in disconnected mode: {}", e);
+            connections.supervise("local", &e);
+        }
+
+        let jobs = Arc::new(JobService::new(events.clone()));
         let metrics = Arc::new(MetricsService::new(None)?);
         let retention_service = Arc::new(RetentionService::new(metrics.clone())?);
+        let scheduler = Arc::new(SchedulerService::new()?);
+        let snapshot_retention = Arc::new(SnapshotRetentionService::new()?);
+        let notifications = Arc::new(NotificationService::new(events.clone())?);
+        let alert_history = Arc::new(AlertHistoryService::new(metrics.connection(), events.clone())?);
+        let alerts = Arc::new(AlertService::new(notifications.clone(), alert_history.clone(), metrics.clone())?);
+        let alert_rules = Arc::new(AlertRuleService::new(notifications.clone(), events.clone(), alert_history.clone())?);
         let guest_agent = Arc::new(GuestAgentService::new());
         let ws_proxy = Arc::new(WsProxyService::new());
         let serial_console = Arc::new(SerialConsoleService::new());
@@ -39,20 +68,113 @@ impl AppState {
         tracing::info!("AppState initialized successfully");
 
         Ok(Self {
-            libvirt,
             connections,
+            events,
+            jobs,
             metrics,
             retention_service,
+            scheduler,
+            snapshot_retention,
+            alerts,
+            alert_rules,
+            alert_history,
+            notifications,
             guest_agent,
             ws_proxy,
             serial_console,
         })
     }
 
+    /// Get the libvirt service for a command.
+    ///
+    /// Commands pass their optional `connection_id` argument straight through;
+    /// `None` resolves to the connection currently selected with `connect_to`.
+    pub fn libvirt(&self, connection_id: Option<&str>) -> Result<Arc<LibvirtService>, AppError> {
+        self.connections.get_libvirt(connection_id)
+    }
+
+    /// Get the libvirt services an inventory command should query.
+    ///
+    /// An explicit `connection_id` narrows the result to that host; `None`
+    /// returns every live connection so list commands can build a fleet view.
+    pub fn libvirt_targets(&self, connection_id: Option<&str>) -> Result<Vec<(String, Arc<LibvirtService>)>, AppError> {
+        match connection_id {
+            Some(id) => Ok(vec![(id.to_string(), self.connections.get_libvirt(Some(id))?)]),
+            None => self.connections.get_connected(),
+        }
+    }
+
+    /// List inventory across the hosts picked by `libvirt_targets`.
+    ///
+    /// Each item is tagged with the connection it came from. When a single
+    /// host was asked for its error is returned; in the fleet view a host that
+    /// fails is logged and skipped so it does not hide the rest.
+    pub fn list_from_targets<T>(
+        &self,
+        connection_id: Option<&str>,
+        what: &str,
+        list: impl Fn(&LibvirtService) -> Result<Vec<T>, AppError>,
+        tag: impl Fn(&mut T, String),
+    ) -> Result<Vec<T>, AppError> {
+        let targets = self.libvirt_targets(connection_id)?;
+
+        let mut all = Vec::new();
+        for (id, libvirt) in targets {
+            match list(&libvirt) {
+                Ok(items) => {
+                    all.extend(items.into_iter().map(|mut item| {
+                        tag(&mut item, id.clone());
+                        item
+                    }));
+                }
+                Err(e) if connection_id.is_some() => return Err(e),
+                Err(e) => tracing::warn!("Failed to list {} on connection {}: {}", what, id, e),
+            }
+        }
+
+        Ok(all)
+    }
+
     /// Start background tasks
     pub fn start_background_tasks(&self) {
         // Start retention policy cleanup task
         let retention = self.retention_service.clone();
         retention.start_cleanup_task();
+
+        // Start scheduled operation executor
+        let scheduler = self.scheduler.clone();
+        scheduler.start_executor_task(self.connections.clone(), self.events.clone(), self.jobs.clone());
+
+        // Start snapshot pruning (applies retention policies to scheduled snapshots)
+        let snapshot_retention = self.snapshot_retention.clone();
+        snapshot_retention.start_pruning_task(self.connections.clone(), self.events.clone());
+
+        // Start host metrics collector (hypervisor CPU, memory, pools)
+        let metrics = self.metrics.clone();
+        metrics.start_host_collector_task(self.connections.clone());
+
+        // Start alert evaluator (samples VM stats for enabled alerts)
+        let alerts = self.alerts.clone();
+        alerts.start_evaluator_task(self.connections.clone(), self.events.clone());
+
+        // Match libvirt, connection and task events against event rules, and poll
+        // the conditions libvirt does not report as events
+        let alert_rules = self.alert_rules.clone();
+        self.events.subscribe(move |event| alert_rules.handle_event(event));
+        let alert_rules = self.alert_rules.clone();
+        alert_rules.start_poll_task(self.connections.clone());
+
+        // Start connection supervisor (keepalive checks and reconnects)
+        let supervisor = self.connections.clone();
+        supervisor.start_supervisor_task();
+
+        // Bring up the remaining auto-connect hosts without blocking startup
+        let connections = self.connections.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let failures = connections.auto_connect();
+            if !failures.is_empty() {
+                tracing::warn!("{} saved connection(s) failed to auto-connect", failures.len());
+            }
+        });
     }
 }
@@ -24,12 +24,18 @@ pub enum AppError {
     #[error("libvirtd is not running")]
     LibvirtdNotRunning,
 
+    #[error("Not connected: {0}")]
+    NotConnected(String),
+
     #[error("Permission denied. Add user to libvirt group")]
     PermissionDenied,
 
     #[error("Invalid configuration: {0}")]
     InvalidConfig(String),
 
+    #[error("XML error: {0}")]
+    XmlError(String),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
@@ -42,6 +48,12 @@ pub enum AppError {
     #[error("Alert error: {0}")]
     AlertError(String),
 
+    #[error("Notification error: {0}")]
+    NotificationError(String),
+
+    #[error("Operation cancelled")]
+    Cancelled,
+
     #[error("Other error: {0}")]
     Other(String),
 }
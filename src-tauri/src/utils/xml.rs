@@ -0,0 +1,337 @@
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use crate::utils::error::AppError;
+
+/// A node in an XML document
+#[derive(Debug, Clone, PartialEq)]
+pub enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+    CData(String),
+    Comment(String),
+}
+
+/// An XML element that keeps attribute order, namespaces, comments and
+/// whitespace so that a parse/serialize round trip only changes what the
+/// caller edited.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct XmlElement {
+    /// Qualified name, including any namespace prefix (e.g. `kvm:tags`)
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<XmlNode>,
+}
+
+impl XmlElement {
+    /// Create an empty element
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Builder-style attribute setter
+    pub fn with_attr(mut self, name: &str, value: impl ToString) -> Self {
+        self.set_attr(name, value);
+        self
+    }
+
+    /// Builder-style child appender
+    pub fn with_child(mut self, child: XmlElement) -> Self {
+        self.push_child(child);
+        self
+    }
+
+    /// Builder-style text setter
+    pub fn with_text(mut self, text: impl ToString) -> Self {
+        self.set_text(text);
+        self
+    }
+
+    /// Parse a document and return its root element
+    pub fn parse(xml: &str) -> Result<Self, AppError> {
+        let mut reader = Reader::from_str(xml);
+        let mut stack: Vec<XmlElement> = Vec::new();
+
+        loop {
+            let event = reader.read_event()
+                .map_err(|e| AppError::XmlError(format!("at position {}: {}", reader.buffer_position(), e)))?;
+
+            match event {
+                Event::Start(start) => {
+                    stack.push(Self::from_start(&start)?);
+                }
+                Event::Empty(start) => {
+                    let element = Self::from_start(&start)?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(XmlNode::Element(element)),
+                        None => return Ok(element),
+                    }
+                }
+                Event::End(_) => {
+                    let element = stack.pop()
+                        .ok_or_else(|| AppError::XmlError("Unbalanced closing tag".to_string()))?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(XmlNode::Element(element)),
+                        None => return Ok(element),
+                    }
+                }
+                Event::Text(text) => {
+                    if let Some(parent) = stack.last_mut() {
+                        let text = text.unescape()
+                            .map_err(|e| AppError::XmlError(e.to_string()))?;
+                        parent.children.push(XmlNode::Text(text.into_owned()));
+                    }
+                }
+                Event::CData(data) => {
+                    if let Some(parent) = stack.last_mut() {
+                        let data = String::from_utf8_lossy(&data.into_inner()).into_owned();
+                        parent.children.push(XmlNode::CData(data));
+                    }
+                }
+                Event::Comment(comment) => {
+                    if let Some(parent) = stack.last_mut() {
+                        let comment = String::from_utf8_lossy(&comment.into_inner()).into_owned();
+                        parent.children.push(XmlNode::Comment(comment));
+                    }
+                }
+                Event::Eof => {
+                    return Err(AppError::XmlError("Document has no root element".to_string()));
+                }
+                // Declarations, processing instructions and doctypes outside the root are dropped
+                _ => {}
+            }
+        }
+    }
+
+    fn from_start(start: &quick_xml::events::BytesStart) -> Result<Self, AppError> {
+        let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+        let mut attributes = Vec::new();
+        for attr in start.attributes() {
+            let attr = attr.map_err(|e| AppError::XmlError(e.to_string()))?;
+            let key = String::from_utf8_lossy(attr.key.as_ref()).into_owned();
+            let value = attr.unescape_value()
+                .map_err(|e| AppError::XmlError(e.to_string()))?
+                .into_owned();
+            attributes.push((key, value));
+        }
+        Ok(Self { name, attributes, children: Vec::new() })
+    }
+
+    /// Serialize the element and its subtree
+    pub fn to_xml(&self) -> String {
+        let mut out = String::new();
+        self.write_to(&mut out);
+        out
+    }
+
+    fn write_to(&self, out: &mut String) {
+        out.push('<');
+        out.push_str(&self.name);
+        for (key, value) in &self.attributes {
+            out.push(' ');
+            out.push_str(key);
+            out.push_str("='");
+            out.push_str(&escape(value, true));
+            out.push('\'');
+        }
+
+        if self.children.is_empty() {
+            out.push_str("/>");
+            return;
+        }
+
+        out.push('>');
+        for child in &self.children {
+            match child {
+                XmlNode::Element(element) => element.write_to(out),
+                XmlNode::Text(text) => out.push_str(&escape(text, false)),
+                XmlNode::CData(data) => {
+                    out.push_str("<![CDATA[");
+                    out.push_str(data);
+                    out.push_str("]]>");
+                }
+                XmlNode::Comment(comment) => {
+                    out.push_str("<!--");
+                    out.push_str(comment);
+                    out.push_str("-->");
+                }
+            }
+        }
+        out.push_str("</");
+        out.push_str(&self.name);
+        out.push('>');
+    }
+
+    /// Get an attribute value
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Get an attribute parsed into a number or other `FromStr` type
+    pub fn attr_parse<T: std::str::FromStr>(&self, name: &str) -> Option<T> {
+        self.attr(name).and_then(|value| value.trim().parse().ok())
+    }
+
+    /// Set an attribute, keeping its position if it already exists
+    pub fn set_attr(&mut self, name: &str, value: impl ToString) {
+        let value = value.to_string();
+        match self.attributes.iter_mut().find(|(key, _)| key == name) {
+            Some(existing) => existing.1 = value,
+            None => self.attributes.push((name.to_string(), value)),
+        }
+    }
+
+    /// Set an attribute, or remove it when `value` is `None`
+    pub fn set_opt_attr<T: ToString>(&mut self, name: &str, value: Option<T>) {
+        match value {
+            Some(value) => self.set_attr(name, value),
+            None => self.remove_attr(name),
+        }
+    }
+
+    /// Remove an attribute
+    pub fn remove_attr(&mut self, name: &str) {
+        self.attributes.retain(|(key, _)| key != name);
+    }
+
+    /// Iterate over child elements
+    pub fn elements(&self) -> impl Iterator<Item = &XmlElement> {
+        self.children.iter().filter_map(|node| match node {
+            XmlNode::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+
+    /// Iterate mutably over child elements
+    pub fn elements_mut(&mut self) -> impl Iterator<Item = &mut XmlElement> {
+        self.children.iter_mut().filter_map(|node| match node {
+            XmlNode::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+
+    /// First child element with the given name
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    /// First child element with the given name, mutably
+    pub fn child_mut(&mut self, name: &str) -> Option<&mut XmlElement> {
+        self.elements_mut().find(|element| element.name == name)
+    }
+
+    /// All child elements with the given name
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> + 'a {
+        self.elements().filter(move |element| element.name == name)
+    }
+
+    /// All child elements with the given name, mutably
+    pub fn children_named_mut<'a>(&'a mut self, name: &'a str) -> impl Iterator<Item = &'a mut XmlElement> + 'a {
+        self.elements_mut().filter(move |element| element.name == name)
+    }
+
+    /// Follow a `/`-separated path of child element names
+    pub fn find(&self, path: &str) -> Option<&XmlElement> {
+        path.split('/').try_fold(self, |element, name| element.child(name))
+    }
+
+    /// Get a child element, appending an empty one if it does not exist
+    pub fn child_or_insert(&mut self, name: &str) -> &mut XmlElement {
+        if self.child(name).is_none() {
+            self.push_child(XmlElement::new(name));
+        }
+        self.child_mut(name).expect("child was just inserted")
+    }
+
+    /// Append a child element
+    pub fn push_child(&mut self, child: XmlElement) {
+        self.children.push(XmlNode::Element(child));
+    }
+
+    /// Remove every child element with the given name
+    pub fn remove_children(&mut self, name: &str) {
+        self.retain_elements(|element| element.name != name);
+    }
+
+    /// Keep only the child elements matching the predicate.
+    ///
+    /// Whitespace directly preceding a removed element is dropped with it so
+    /// repeated edits do not accumulate blank lines.
+    pub fn retain_elements<F: FnMut(&XmlElement) -> bool>(&mut self, mut keep: F) {
+        let mut result = Vec::with_capacity(self.children.len());
+        for node in self.children.drain(..) {
+            match node {
+                XmlNode::Element(ref element) if !keep(element) => {
+                    if let Some(XmlNode::Text(text)) = result.last() {
+                        if text.trim().is_empty() {
+                            result.pop();
+                        }
+                    }
+                }
+                node => result.push(node),
+            }
+        }
+        self.children = result;
+    }
+
+    /// Concatenated text content of this element
+    pub fn text(&self) -> String {
+        self.children.iter()
+            .filter_map(|node| match node {
+                XmlNode::Text(text) | XmlNode::CData(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+
+    /// Text content of a child element
+    pub fn child_text(&self, name: &str) -> Option<String> {
+        self.child(name).map(|child| child.text())
+    }
+
+    /// Replace the content of this element with text
+    pub fn set_text(&mut self, text: impl ToString) {
+        self.children = vec![XmlNode::Text(text.to_string())];
+    }
+
+    /// Set the text of a child element, creating it if needed, or remove it for `None`
+    pub fn set_child_text<T: ToString>(&mut self, name: &str, text: Option<T>) {
+        match text {
+            Some(text) => self.child_or_insert(name).set_text(text),
+            None => self.remove_children(name),
+        }
+    }
+
+    /// Depth-first iterator over every descendant element
+    pub fn descendants(&self) -> Vec<&XmlElement> {
+        let mut found = Vec::new();
+        for child in self.elements() {
+            found.push(child);
+            found.extend(child.descendants());
+        }
+        found
+    }
+}
+
+/// Escape text or attribute content
+fn escape(value: &str, attribute: bool) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\'' if attribute => out.push_str("&apos;"),
+            '"' if attribute => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,281 @@
+import { useState } from 'react'
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query'
+import { api } from '@/lib/tauri'
+import type { AlertHistoryQuery, AlertInstance, AlertSeverity, AlertState, VM } from '@/lib/types'
+import { Button } from '@/components/ui/button'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { Badge } from '@/components/ui/badge'
+import { toast } from 'sonner'
+import { BellRing, Check, CheckCheck, History } from 'lucide-react'
+
+const selectClassName =
+  'flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background'
+
+const stateVariants: Record<AlertState, 'destructive' | 'secondary' | 'outline'> = {
+  firing: 'destructive',
+  acknowledged: 'secondary',
+  resolved: 'outline',
+}
+
+// datetime-local values are local time without a zone; the backend stores seconds
+const toSeconds = (value: string) => (value ? Math.floor(new Date(value).getTime() / 1000) : undefined)
+
+export function AlertHistoryPanel() {
+  const queryClient = useQueryClient()
+  const [vmFilter, setVmFilter] = useState('')
+  const [stateFilter, setStateFilter] = useState<AlertState | ''>('')
+  const [startTime, setStartTime] = useState('')
+  const [endTime, setEndTime] = useState('')
+
+  const historyQuery: AlertHistoryQuery = {
+    vmId: vmFilter || undefined,
+    state: stateFilter || undefined,
+    startTime: toSeconds(startTime),
+    endTime: toSeconds(endTime),
+  }
+
+  const { data: vms } = useQuery<VM[]>({
+    queryKey: ['vms'],
+    queryFn: () => api.getVms(),
+  })
+
+  const { data: active } = useQuery<AlertInstance[]>({
+    queryKey: ['active-alerts'],
+    queryFn: () => api.listActiveAlerts(),
+  })
+
+  const { data: history, isLoading } = useQuery<AlertInstance[]>({
+    queryKey: ['alert-history', historyQuery],
+    queryFn: () => api.queryAlertHistory(historyQuery),
+  })
+
+  const invalidate = () => {
+    queryClient.invalidateQueries({ queryKey: ['active-alerts'] })
+    queryClient.invalidateQueries({ queryKey: ['alert-history'] })
+  }
+
+  const acknowledgeMutation = useMutation({
+    mutationFn: (id: string) => api.acknowledgeAlert(id),
+    onSuccess: () => {
+      invalidate()
+      toast.success('Alert acknowledged')
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to acknowledge alert: ${error.message}`)
+    },
+  })
+
+  const resolveMutation = useMutation({
+    mutationFn: (id: string) => api.resolveAlert(id),
+    onSuccess: () => {
+      invalidate()
+      toast.success('Alert resolved')
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to resolve alert: ${error.message}`)
+    },
+  })
+
+  const getSeverityColor = (severity: AlertSeverity) => {
+    switch (severity) {
+      case 'info':
+        return 'default'
+      case 'warning':
+        return 'secondary'
+      case 'critical':
+        return 'destructive'
+    }
+  }
+
+  const formatTimestamp = (timestamp: number) => new Date(timestamp * 1000).toLocaleString()
+
+  const getVmName = (vmId: string) => vms?.find((vm) => vm.id === vmId)?.name ?? vmId
+
+  const describeSubject = (instance: AlertInstance) =>
+    instance.vmId ? getVmName(instance.vmId) : instance.subjectName
+
+  return (
+    <div className="space-y-4">
+      <div>
+        <h2 className="text-2xl font-bold">Active Alerts</h2>
+        <p className="text-muted-foreground">Acknowledge alerts to stop reminders, or resolve them by hand</p>
+      </div>
+
+      {!active || active.length === 0 ? (
+        <Card>
+          <CardContent className="flex flex-col items-center justify-center p-8">
+            <BellRing className="h-12 w-12 text-muted-foreground mb-4" />
+            <p className="text-muted-foreground text-center">No alerts are firing.</p>
+          </CardContent>
+        </Card>
+      ) : (
+        <div className="grid gap-4">
+          {active.map((instance) => (
+            <Card key={instance.id}>
+              <CardHeader>
+                <div className="flex justify-between items-start">
+                  <div className="flex-1">
+                    <CardTitle className="text-lg">{instance.name}</CardTitle>
+                    <CardDescription className="mt-1">
+                      {describeSubject(instance)} • {instance.message}
+                    </CardDescription>
+                  </div>
+                  <div className="flex items-center gap-2">
+                    <Badge variant={getSeverityColor(instance.severity)} className="capitalize">
+                      {instance.severity}
+                    </Badge>
+                    <Badge variant={stateVariants[instance.state]} className="capitalize">
+                      {instance.state}
+                    </Badge>
+                  </div>
+                </div>
+              </CardHeader>
+              <CardContent>
+                <div className="flex justify-between items-center text-sm text-muted-foreground">
+                  <div>
+                    Fired {formatTimestamp(instance.firedAt)} • notified {instance.notifyCount}×
+                    {instance.acknowledgedAt && (
+                      <> • acknowledged by {instance.acknowledgedBy} at {formatTimestamp(instance.acknowledgedAt)}</>
+                    )}
+                  </div>
+                  <div className="flex gap-2">
+                    {instance.state === 'firing' && (
+                      <Button
+                        size="sm"
+                        variant="outline"
+                        onClick={() => acknowledgeMutation.mutate(instance.id)}
+                        disabled={acknowledgeMutation.isPending}
+                      >
+                        <Check className="mr-1 h-3 w-3" />
+                        Acknowledge
+                      </Button>
+                    )}
+                    <Button
+                      size="sm"
+                      variant="outline"
+                      onClick={() => resolveMutation.mutate(instance.id)}
+                      disabled={resolveMutation.isPending}
+                    >
+                      <CheckCheck className="mr-1 h-3 w-3" />
+                      Resolve
+                    </Button>
+                  </div>
+                </div>
+              </CardContent>
+            </Card>
+          ))}
+        </div>
+      )}
+
+      <Card>
+        <CardHeader>
+          <CardTitle className="text-lg flex items-center gap-2">
+            <History className="h-4 w-4" />
+            Alert History
+          </CardTitle>
+          <CardDescription>What alerted, who acknowledged it and when it cleared</CardDescription>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="grid grid-cols-4 gap-4">
+            <div>
+              <Label htmlFor="history-vm">Virtual Machine</Label>
+              <select
+                id="history-vm"
+                value={vmFilter}
+                onChange={(e) => setVmFilter(e.target.value)}
+                className={selectClassName}
+              >
+                <option value="">All</option>
+                {vms?.map((vm) => (
+                  <option key={vm.id} value={vm.id}>
+                    {vm.name}
+                  </option>
+                ))}
+              </select>
+            </div>
+            <div>
+              <Label htmlFor="history-state">State</Label>
+              <select
+                id="history-state"
+                value={stateFilter}
+                onChange={(e) => setStateFilter(e.target.value as AlertState | '')}
+                className={selectClassName}
+              >
+                <option value="">All</option>
+                <option value="firing">Firing</option>
+                <option value="acknowledged">Acknowledged</option>
+                <option value="resolved">Resolved</option>
+              </select>
+            </div>
+            <div>
+              <Label htmlFor="history-start">From</Label>
+              <Input
+                id="history-start"
+                type="datetime-local"
+                value={startTime}
+                onChange={(e) => setStartTime(e.target.value)}
+              />
+            </div>
+            <div>
+              <Label htmlFor="history-end">To</Label>
+              <Input
+                id="history-end"
+                type="datetime-local"
+                value={endTime}
+                onChange={(e) => setEndTime(e.target.value)}
+              />
+            </div>
+          </div>
+
+          {isLoading ? (
+            <div className="text-muted-foreground text-sm">Loading history...</div>
+          ) : !history || history.length === 0 ? (
+            <div className="text-muted-foreground text-sm">No alerts in this range.</div>
+          ) : (
+            <table className="w-full text-sm">
+              <thead>
+                <tr className="text-left text-muted-foreground border-b">
+                  <th className="py-2">Alert</th>
+                  <th>Subject</th>
+                  <th>State</th>
+                  <th>Fired</th>
+                  <th>Acknowledged</th>
+                  <th>Resolved</th>
+                </tr>
+              </thead>
+              <tbody>
+                {history.map((instance) => (
+                  <tr key={instance.id} className="border-b last:border-0">
+                    <td className="py-2">
+                      <div className="font-medium">{instance.name}</div>
+                      <div className="text-xs text-muted-foreground">{instance.message}</div>
+                    </td>
+                    <td>{describeSubject(instance)}</td>
+                    <td>
+                      <Badge variant={stateVariants[instance.state]} className="capitalize">
+                        {instance.state}
+                      </Badge>
+                    </td>
+                    <td>{formatTimestamp(instance.firedAt)}</td>
+                    <td>
+                      {instance.acknowledgedAt
+                        ? `${instance.acknowledgedBy} • ${formatTimestamp(instance.acknowledgedAt)}`
+                        : '—'}
+                    </td>
+                    <td>
+                      {instance.resolvedAt
+                        ? `${instance.resolvedBy ?? 'auto'} • ${formatTimestamp(instance.resolvedAt)}`
+                        : '—'}
+                    </td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          )}
+        </CardContent>
+      </Card>
+    </div>
+  )
+}
@@ -0,0 +1,463 @@
+import { useState } from 'react'
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query'
+import { api } from '@/lib/tauri'
+import type { AlertSeverity, CreateEventRuleRequest, EventAlertRule, EventRuleKind, NotificationChannel } from '@/lib/types'
+import { Button } from '@/components/ui/button'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Dialog, DialogContent, DialogDescription, DialogFooter, DialogHeader, DialogTitle } from '@/components/ui/dialog'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { Badge } from '@/components/ui/badge'
+import { Checkbox } from '@/components/ui/checkbox'
+import { toast } from 'sonner'
+import { Zap, Plus, Trash2, PlayCircle, PauseCircle, AlertTriangle } from 'lucide-react'
+
+const ruleKindLabels: Record<EventRuleKind, string> = {
+  vmCrashed: 'VM crashed or shut off unexpectedly',
+  guestAgentUnresponsive: 'Guest agent unresponsive',
+  guestPanic: 'Guest panic (pvpanic) or watchdog',
+  storagePoolUsage: 'Storage pool above threshold',
+  networkInactive: 'Network went inactive',
+  connectionLost: 'Libvirt connection lost',
+  backupFailed: 'Backup failed',
+  backupVerifyFailed: 'Backup verification failed',
+  scheduleFailed: 'Scheduled operation failed',
+  snapshotPruneFailed: 'Snapshot pruning failed',
+}
+
+const vmRuleKinds: EventRuleKind[] = ['vmCrashed', 'guestAgentUnresponsive', 'guestPanic']
+
+const emptyRequest = (): CreateEventRuleRequest => ({
+  name: '',
+  kind: 'vmCrashed',
+  severity: 'critical',
+  target: null,
+  connectionId: null,
+  thresholdPercent: null,
+  clearThresholdPercent: null,
+  channelIds: [],
+  cooldownSecs: 0,
+  renotifySecs: 0,
+})
+
+const selectClassName =
+  'flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background'
+
+export function AlertRuleManager() {
+  const queryClient = useQueryClient()
+  const [isCreateDialogOpen, setIsCreateDialogOpen] = useState(false)
+  const [ruleForm, setRuleForm] = useState<CreateEventRuleRequest>(emptyRequest())
+
+  const { data: rules, isLoading } = useQuery<EventAlertRule[]>({
+    queryKey: ['alert-rules'],
+    queryFn: () => api.listAlertRules(),
+  })
+
+  const { data: channels } = useQuery<NotificationChannel[]>({
+    queryKey: ['notification-channels'],
+    queryFn: () => api.listNotificationChannels(),
+  })
+
+  // Target pickers, loaded only for the rule kind being created
+  const kind = ruleForm.kind
+  const { data: vms } = useQuery({
+    queryKey: ['vms'],
+    queryFn: () => api.getVms(),
+    enabled: isCreateDialogOpen && vmRuleKinds.includes(kind),
+  })
+  const { data: pools } = useQuery({
+    queryKey: ['storage-pools'],
+    queryFn: () => api.getStoragePools(),
+    enabled: isCreateDialogOpen && kind === 'storagePoolUsage',
+  })
+  const { data: networks } = useQuery({
+    queryKey: ['networks'],
+    queryFn: () => api.getNetworks(),
+    enabled: isCreateDialogOpen && kind === 'networkInactive',
+  })
+  const { data: connections } = useQuery({
+    queryKey: ['saved-connections'],
+    queryFn: () => api.getSavedConnections(),
+    enabled: isCreateDialogOpen && kind === 'connectionLost',
+  })
+  const { data: backups } = useQuery({
+    queryKey: ['backupConfigs'],
+    queryFn: () => api.listBackupConfigs(),
+    enabled: isCreateDialogOpen && (kind === 'backupFailed' || kind === 'backupVerifyFailed'),
+  })
+  const { data: schedules } = useQuery({
+    queryKey: ['schedules'],
+    queryFn: () => api.listSchedules(),
+    enabled: isCreateDialogOpen && kind === 'scheduleFailed',
+  })
+  const { data: retentionPolicies } = useQuery({
+    queryKey: ['snapshot-retention-policies'],
+    queryFn: () => api.listSnapshotRetentionPolicies(),
+    enabled: isCreateDialogOpen && kind === 'snapshotPruneFailed',
+  })
+
+  const createMutation = useMutation({
+    mutationFn: (request: CreateEventRuleRequest) => api.createAlertRule(request),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['alert-rules'] })
+      toast.success('Event rule created')
+      setIsCreateDialogOpen(false)
+      setRuleForm(emptyRequest())
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to create rule: ${error.message}`)
+    },
+  })
+
+  const updateStatusMutation = useMutation({
+    mutationFn: ({ id, enabled }: { id: string; enabled: boolean }) => api.updateAlertRuleStatus(id, enabled),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['alert-rules'] })
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to update rule: ${error.message}`)
+    },
+  })
+
+  const deleteMutation = useMutation({
+    mutationFn: (id: string) => api.deleteAlertRule(id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['alert-rules'] })
+      toast.success('Event rule deleted')
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to delete rule: ${error.message}`)
+    },
+  })
+
+  // Options for the target select; an empty value matches every subject
+  const targetOptions = (): { value: string; label: string }[] => {
+    switch (kind) {
+      case 'vmCrashed':
+      case 'guestAgentUnresponsive':
+      case 'guestPanic':
+        return vms?.map((vm) => ({ value: vm.id, label: vm.name })) ?? []
+      case 'storagePoolUsage':
+        return pools?.map((pool) => ({ value: pool.name, label: pool.name })) ?? []
+      case 'networkInactive':
+        return networks?.map((network) => ({ value: network.name, label: network.name })) ?? []
+      case 'connectionLost':
+        return connections?.map((connection) => ({ value: connection.id, label: connection.name })) ?? []
+      case 'backupFailed':
+      case 'backupVerifyFailed':
+        return backups?.map((backup) => ({ value: backup.id, label: backup.name })) ?? []
+      case 'scheduleFailed':
+        return schedules?.map((schedule) => ({ value: schedule.id, label: schedule.name })) ?? []
+      case 'snapshotPruneFailed':
+        return retentionPolicies?.map((policy) => ({ value: policy.id, label: policy.name })) ?? []
+    }
+  }
+
+  const handleCreate = () => {
+    if (!ruleForm.name.trim()) {
+      toast.error('Please enter a rule name')
+      return
+    }
+    if (kind === 'storagePoolUsage') {
+      const percent = ruleForm.thresholdPercent ?? -1
+      if (percent < 0 || percent > 100) {
+        toast.error('Threshold must be between 0 and 100')
+        return
+      }
+      if (ruleForm.clearThresholdPercent != null && ruleForm.clearThresholdPercent > percent) {
+        toast.error('Clear threshold must not be above the alert threshold')
+        return
+      }
+    }
+    createMutation.mutate(ruleForm)
+  }
+
+  const handleDelete = (rule: EventAlertRule) => {
+    if (confirm(`Are you sure you want to delete rule "${rule.name}"?`)) {
+      deleteMutation.mutate(rule.id)
+    }
+  }
+
+  const toggleChannel = (channelId: string, checked: boolean) => {
+    const selected = ruleForm.channelIds ?? []
+    setRuleForm({
+      ...ruleForm,
+      channelIds: checked ? [...selected, channelId] : selected.filter((id) => id !== channelId),
+    })
+  }
+
+  const getSeverityColor = (severity: AlertSeverity) => {
+    switch (severity) {
+      case 'info':
+        return 'default'
+      case 'warning':
+        return 'secondary'
+      case 'critical':
+        return 'destructive'
+    }
+  }
+
+  const describeTarget = (rule: EventAlertRule) => {
+    const scope = rule.target ? rule.target : 'any'
+    const threshold = rule.kind === 'storagePoolUsage' ? ` • ≥ ${rule.thresholdPercent}%` : ''
+    const clear = rule.clearThresholdPercent != null ? `, clears < ${rule.clearThresholdPercent}%` : ''
+    const renotify = rule.renotifySecs > 0 ? ` • re-notify every ${Math.round(rule.renotifySecs / 60)} min` : ''
+    return `Target: ${scope}${threshold}${clear}${renotify}`
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="flex justify-between items-center">
+        <div>
+          <h2 className="text-2xl font-bold">Event Rules</h2>
+          <p className="text-muted-foreground">Alert on crashes, failed jobs, lost connections and full storage</p>
+        </div>
+        <Button onClick={() => setIsCreateDialogOpen(true)}>
+          <Plus className="mr-2 h-4 w-4" />
+          Create Rule
+        </Button>
+      </div>
+
+      {isLoading ? (
+        <div className="text-muted-foreground">Loading rules...</div>
+      ) : !rules || rules.length === 0 ? (
+        <Card>
+          <CardContent className="py-8 text-center text-muted-foreground">
+            No event rules configured
+          </CardContent>
+        </Card>
+      ) : (
+        <div className="grid gap-4">
+          {rules.map((rule) => (
+            <Card key={rule.id}>
+              <CardHeader>
+                <div className="flex justify-between items-start">
+                  <div className="flex-1">
+                    <CardTitle className="text-lg flex items-center gap-2">
+                      <Zap className="h-4 w-4" />
+                      {rule.name}
+                    </CardTitle>
+                    <CardDescription className="mt-1">
+                      {ruleKindLabels[rule.kind]} • {describeTarget(rule)}
+                    </CardDescription>
+                  </div>
+                  <div className="flex items-center gap-2">
+                    <Badge variant={getSeverityColor(rule.severity)} className="capitalize">
+                      {rule.severity}
+                    </Badge>
+                    <Badge variant={rule.enabled ? 'default' : 'secondary'}>
+                      {rule.enabled ? 'Enabled' : 'Disabled'}
+                    </Badge>
+                  </div>
+                </div>
+              </CardHeader>
+              <CardContent>
+                <div className="flex items-center justify-between">
+                  <div className="flex items-center text-sm text-muted-foreground">
+                    {rule.lastTriggered ? (
+                      <>
+                        <AlertTriangle className="mr-2 h-4 w-4" />
+                        Last triggered: {new Date(rule.lastTriggered * 1000).toLocaleString()}
+                      </>
+                    ) : (
+                      'Never triggered'
+                    )}
+                  </div>
+                  <div className="flex gap-2">
+                    <Button
+                      size="sm"
+                      variant="outline"
+                      onClick={() => updateStatusMutation.mutate({ id: rule.id, enabled: !rule.enabled })}
+                    >
+                      {rule.enabled ? <PauseCircle className="h-3 w-3" /> : <PlayCircle className="h-3 w-3" />}
+                    </Button>
+                    <Button size="sm" variant="outline" onClick={() => handleDelete(rule)}>
+                      <Trash2 className="h-3 w-3" />
+                    </Button>
+                  </div>
+                </div>
+              </CardContent>
+            </Card>
+          ))}
+        </div>
+      )}
+
+      <Dialog open={isCreateDialogOpen} onOpenChange={setIsCreateDialogOpen}>
+        <DialogContent className="max-w-2xl">
+          <DialogHeader>
+            <DialogTitle>Create Event Rule</DialogTitle>
+            <DialogDescription>Raise an alert when a VM, host or background task reports a problem</DialogDescription>
+          </DialogHeader>
+          <div className="space-y-4">
+            <div className="grid grid-cols-2 gap-4">
+              <div>
+                <Label htmlFor="rule-name">Rule Name</Label>
+                <Input
+                  id="rule-name"
+                  value={ruleForm.name}
+                  onChange={(e) => setRuleForm({ ...ruleForm, name: e.target.value })}
+                  placeholder="e.g., Production VM crashed"
+                />
+              </div>
+              <div>
+                <Label htmlFor="rule-severity">Severity</Label>
+                <select
+                  id="rule-severity"
+                  value={ruleForm.severity}
+                  onChange={(e) => setRuleForm({ ...ruleForm, severity: e.target.value as AlertSeverity })}
+                  className={selectClassName}
+                >
+                  <option value="info">Info</option>
+                  <option value="warning">Warning</option>
+                  <option value="critical">Critical</option>
+                </select>
+              </div>
+            </div>
+
+            <div className="grid grid-cols-2 gap-4">
+              <div>
+                <Label htmlFor="rule-kind">Condition</Label>
+                <select
+                  id="rule-kind"
+                  value={kind}
+                  onChange={(e) => {
+                    const nextKind = e.target.value as EventRuleKind
+                    setRuleForm({
+                      ...ruleForm,
+                      kind: nextKind,
+                      target: null,
+                      thresholdPercent: nextKind === 'storagePoolUsage' ? 90 : null,
+                      clearThresholdPercent: null,
+                    })
+                  }}
+                  className={selectClassName}
+                >
+                  {(Object.keys(ruleKindLabels) as EventRuleKind[]).map((ruleKind) => (
+                    <option key={ruleKind} value={ruleKind}>{ruleKindLabels[ruleKind]}</option>
+                  ))}
+                </select>
+              </div>
+              <div>
+                <Label htmlFor="rule-target">Target</Label>
+                <select
+                  id="rule-target"
+                  value={ruleForm.target ?? ''}
+                  onChange={(e) => setRuleForm({ ...ruleForm, target: e.target.value || null })}
+                  className={selectClassName}
+                >
+                  <option value="">Any</option>
+                  {targetOptions().map((option) => (
+                    <option key={option.value} value={option.value}>{option.label}</option>
+                  ))}
+                </select>
+                {kind === 'guestAgentUnresponsive' && (
+                  <p className="text-xs text-muted-foreground mt-1">
+                    Agents of a selected VM are also pinged periodically; "Any" only reacts to disconnects
+                  </p>
+                )}
+              </div>
+            </div>
+
+            {kind === 'storagePoolUsage' && (
+              <div className="grid grid-cols-2 gap-4">
+                <div>
+                  <Label htmlFor="rule-threshold">Usage Threshold (%)</Label>
+                  <Input
+                    id="rule-threshold"
+                    type="number"
+                    min="0"
+                    max="100"
+                    value={ruleForm.thresholdPercent ?? ''}
+                    onChange={(e) => setRuleForm({ ...ruleForm, thresholdPercent: parseFloat(e.target.value) })}
+                  />
+                </div>
+                <div>
+                  <Label htmlFor="rule-clear-threshold">Clear Below (%)</Label>
+                  <Input
+                    id="rule-clear-threshold"
+                    type="number"
+                    min="0"
+                    max="100"
+                    value={ruleForm.clearThresholdPercent ?? ''}
+                    placeholder={String(ruleForm.thresholdPercent ?? '')}
+                    onChange={(e) =>
+                      setRuleForm({
+                        ...ruleForm,
+                        clearThresholdPercent: e.target.value === '' ? null : parseFloat(e.target.value),
+                      })
+                    }
+                  />
+                </div>
+              </div>
+            )}
+
+            <div className="grid grid-cols-2 gap-4">
+              <div>
+                <Label htmlFor="rule-cooldown">Cooldown (minutes)</Label>
+                <Input
+                  id="rule-cooldown"
+                  type="number"
+                  min="0"
+                  value={(ruleForm.cooldownSecs ?? 0) / 60}
+                  onChange={(e) =>
+                    setRuleForm({
+                      ...ruleForm,
+                      cooldownSecs: Math.max(0, Math.round(parseFloat(e.target.value || '0') * 60)),
+                    })
+                  }
+                />
+                <p className="text-xs text-muted-foreground mt-1">
+                  Quiet period after the alert resolves
+                </p>
+              </div>
+              <div>
+                <Label htmlFor="rule-renotify">Re-notify (minutes)</Label>
+                <Input
+                  id="rule-renotify"
+                  type="number"
+                  min="0"
+                  value={(ruleForm.renotifySecs ?? 0) / 60}
+                  onChange={(e) =>
+                    setRuleForm({
+                      ...ruleForm,
+                      renotifySecs: Math.max(0, Math.round(parseFloat(e.target.value || '0') * 60)),
+                    })
+                  }
+                />
+                <p className="text-xs text-muted-foreground mt-1">
+                  Reminder interval until acknowledged, 0 to notify once
+                </p>
+              </div>
+            </div>
+
+            {channels && channels.length > 0 && (
+              <div>
+                <Label>Notification Channels</Label>
+                <div className="flex flex-wrap gap-4 mt-2">
+                  {channels.map((channel) => (
+                    <div key={channel.id} className="flex items-center space-x-2">
+                      <Checkbox
+                        id={`rule-channel-${channel.id}`}
+                        checked={ruleForm.channelIds?.includes(channel.id) ?? false}
+                        onCheckedChange={(checked) => toggleChannel(channel.id, checked === true)}
+                      />
+                      <Label htmlFor={`rule-channel-${channel.id}`}>{channel.name}</Label>
+                    </div>
+                  ))}
+                </div>
+              </div>
+            )}
+          </div>
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setIsCreateDialogOpen(false)}>
+              Cancel
+            </Button>
+            <Button onClick={handleCreate} disabled={createMutation.isPending}>
+              {createMutation.isPending ? 'Creating...' : 'Create Rule'}
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+    </div>
+  )
+}
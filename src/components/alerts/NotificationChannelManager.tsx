@@ -0,0 +1,589 @@
+import { useEffect, useState } from 'react'
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query'
+import { listen, UnlistenFn } from '@tauri-apps/api/event'
+import { api } from '@/lib/tauri'
+import type {
+  AlertSeverity,
+  ChannelConfig,
+  ChannelType,
+  DeliveryRecord,
+  NotificationChannel,
+  NotificationChannelRequest,
+} from '@/lib/types'
+import { Button } from '@/components/ui/button'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Dialog, DialogContent, DialogDescription, DialogFooter, DialogHeader, DialogTitle } from '@/components/ui/dialog'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { Badge } from '@/components/ui/badge'
+import { Checkbox } from '@/components/ui/checkbox'
+import { toast } from 'sonner'
+import { Globe, Mail, Terminal, Monitor, Plus, Trash2, Send, Pencil, PlayCircle, PauseCircle, CheckCircle2, XCircle } from 'lucide-react'
+
+const severities: AlertSeverity[] = ['info', 'warning', 'critical']
+
+const channelLabels: Record<ChannelType, string> = {
+  webhook: 'Webhook',
+  smtp: 'Email (SMTP)',
+  command: 'Command Hook',
+  desktop: 'Desktop',
+}
+
+const defaultConfig = (type: ChannelType): ChannelConfig => {
+  switch (type) {
+    case 'webhook':
+      return { type: 'webhook', url: '', headers: {}, hmacSecret: null }
+    case 'smtp':
+      return { type: 'smtp', host: 'localhost', port: 587, security: 'startTls', username: null, password: null, from: '', to: [] }
+    case 'command':
+      return { type: 'command', program: '', args: [], payload: 'env', timeoutSecs: 30 }
+    case 'desktop':
+      return { type: 'desktop' }
+  }
+}
+
+const emptyRequest = (): NotificationChannelRequest => ({
+  name: '',
+  config: defaultConfig('desktop'),
+  severities: ['critical'],
+  retry: { maxAttempts: 3, backoffSecs: 10, backoffMultiplier: 2 },
+  enabled: true,
+})
+
+const selectClassName =
+  'flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background'
+
+export function NotificationChannelManager() {
+  const queryClient = useQueryClient()
+  const [isDialogOpen, setIsDialogOpen] = useState(false)
+  const [editingId, setEditingId] = useState<string | null>(null)
+  const [form, setForm] = useState<NotificationChannelRequest>(emptyRequest())
+  const [recipientsInput, setRecipientsInput] = useState('')
+  const [argsInput, setArgsInput] = useState('')
+
+  const { data: channels, isLoading } = useQuery<NotificationChannel[]>({
+    queryKey: ['notification-channels'],
+    queryFn: () => api.listNotificationChannels(),
+  })
+
+  const { data: deliveries } = useQuery<DeliveryRecord[]>({
+    queryKey: ['notification-deliveries'],
+    queryFn: () => api.getNotificationDeliveries(),
+  })
+
+  // Refresh the delivery log as background deliveries finish
+  useEffect(() => {
+    let unlisten: UnlistenFn | undefined
+
+    const setupListener = async () => {
+      unlisten = await listen<DeliveryRecord>('notification-delivery', (event) => {
+        queryClient.invalidateQueries({ queryKey: ['notification-deliveries'] })
+        if (event.payload.status === 'failed') {
+          toast.error(`Notification to ${event.payload.channelName} failed: ${event.payload.error}`)
+        }
+      })
+    }
+
+    setupListener()
+
+    return () => {
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [queryClient])
+
+  const saveMutation = useMutation({
+    mutationFn: (request: NotificationChannelRequest) =>
+      editingId ? api.updateNotificationChannel(editingId, request) : api.createNotificationChannel(request),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['notification-channels'] })
+      toast.success(editingId ? 'Channel updated' : 'Channel created')
+      setIsDialogOpen(false)
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to save channel: ${error.message}`)
+    },
+  })
+
+  const toggleMutation = useMutation({
+    mutationFn: (channel: NotificationChannel) =>
+      api.updateNotificationChannel(channel.id, {
+        name: channel.name,
+        config: channel.config,
+        severities: channel.severities,
+        retry: channel.retry,
+        enabled: !channel.enabled,
+      }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['notification-channels'] })
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to update channel: ${error.message}`)
+    },
+  })
+
+  const deleteMutation = useMutation({
+    mutationFn: (id: string) => api.deleteNotificationChannel(id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['notification-channels'] })
+      queryClient.invalidateQueries({ queryKey: ['notification-deliveries'] })
+      toast.success('Channel deleted')
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to delete channel: ${error.message}`)
+    },
+  })
+
+  const testMutation = useMutation({
+    mutationFn: (id: string) => api.testNotificationChannel(id),
+    onSuccess: (record) => {
+      queryClient.invalidateQueries({ queryKey: ['notification-deliveries'] })
+      if (record.status === 'delivered') {
+        toast.success(`Test notification sent via ${record.channelName}`)
+      } else {
+        toast.error(`Test notification failed: ${record.error}`)
+      }
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to test channel: ${error.message}`)
+    },
+  })
+
+  const openCreate = () => {
+    setEditingId(null)
+    setForm(emptyRequest())
+    setRecipientsInput('')
+    setArgsInput('')
+    setIsDialogOpen(true)
+  }
+
+  const openEdit = (channel: NotificationChannel) => {
+    setEditingId(channel.id)
+    setForm({
+      name: channel.name,
+      config: channel.config,
+      severities: channel.severities,
+      retry: channel.retry,
+      enabled: channel.enabled,
+    })
+    setRecipientsInput(channel.config.type === 'smtp' ? channel.config.to.join(', ') : '')
+    setArgsInput(channel.config.type === 'command' ? channel.config.args.join(' ') : '')
+    setIsDialogOpen(true)
+  }
+
+  const handleSave = () => {
+    if (!form.name.trim()) {
+      toast.error('Please enter a channel name')
+      return
+    }
+
+    let config = form.config
+    if (config.type === 'smtp') {
+      config = { ...config, to: recipientsInput.split(',').map((r) => r.trim()).filter((r) => r.length > 0) }
+    } else if (config.type === 'command') {
+      config = { ...config, args: argsInput.split(' ').filter((a) => a.length > 0) }
+    }
+    saveMutation.mutate({ ...form, config })
+  }
+
+  const handleDelete = (channel: NotificationChannel) => {
+    if (confirm(`Are you sure you want to delete channel "${channel.name}"?`)) {
+      deleteMutation.mutate(channel.id)
+    }
+  }
+
+  const toggleSeverity = (severity: AlertSeverity, checked: boolean) => {
+    setForm({
+      ...form,
+      severities: checked ? [...form.severities, severity] : form.severities.filter((s) => s !== severity),
+    })
+  }
+
+  const setConfig = (config: ChannelConfig) => setForm({ ...form, config })
+
+  const getChannelIcon = (type: ChannelType) => {
+    switch (type) {
+      case 'webhook':
+        return <Globe className="h-4 w-4" />
+      case 'smtp':
+        return <Mail className="h-4 w-4" />
+      case 'command':
+        return <Terminal className="h-4 w-4" />
+      case 'desktop':
+        return <Monitor className="h-4 w-4" />
+    }
+  }
+
+  const describeChannel = (config: ChannelConfig) => {
+    switch (config.type) {
+      case 'webhook':
+        return config.url + (config.hmacSecret ? ' (signed)' : '')
+      case 'smtp':
+        return `${config.to.join(', ')} via ${config.host}:${config.port}`
+      case 'command':
+        return [config.program, ...config.args].join(' ')
+      case 'desktop':
+        return 'Native desktop notification'
+    }
+  }
+
+  const config = form.config
+
+  return (
+    <div className="space-y-4">
+      <div className="flex justify-between items-center">
+        <div>
+          <h2 className="text-2xl font-bold">Notification Channels</h2>
+          <p className="text-muted-foreground">Deliver triggered alerts by webhook, email, command or desktop notification</p>
+        </div>
+        <Button onClick={openCreate}>
+          <Plus className="mr-2 h-4 w-4" />
+          Add Channel
+        </Button>
+      </div>
+
+      {isLoading ? (
+        <div className="text-muted-foreground">Loading channels...</div>
+      ) : !channels || channels.length === 0 ? (
+        <Card>
+          <CardContent className="py-8 text-center text-muted-foreground">
+            No notification channels configured. Alerts are only shown inside the app.
+          </CardContent>
+        </Card>
+      ) : (
+        <div className="grid gap-4">
+          {channels.map((channel) => (
+            <Card key={channel.id}>
+              <CardHeader>
+                <div className="flex justify-between items-start">
+                  <div className="flex-1 min-w-0">
+                    <CardTitle className="text-lg flex items-center gap-2">
+                      {getChannelIcon(channel.config.type)}
+                      {channel.name}
+                    </CardTitle>
+                    <CardDescription className="mt-1 truncate">
+                      {channelLabels[channel.config.type]} • {describeChannel(channel.config)}
+                    </CardDescription>
+                  </div>
+                  <div className="flex items-center gap-2">
+                    {channel.severities.map((severity) => (
+                      <Badge key={severity} variant="outline">{severity}</Badge>
+                    ))}
+                    <Badge variant={channel.enabled ? 'default' : 'secondary'}>
+                      {channel.enabled ? 'Enabled' : 'Disabled'}
+                    </Badge>
+                  </div>
+                </div>
+              </CardHeader>
+              <CardContent>
+                <div className="flex items-center justify-between">
+                  <div className="text-sm text-muted-foreground">
+                    Up to {channel.retry.maxAttempts} attempt(s), {channel.retry.backoffSecs}s backoff ×{channel.retry.backoffMultiplier}
+                  </div>
+                  <div className="flex gap-2">
+                    <Button
+                      size="sm"
+                      variant="outline"
+                      onClick={() => testMutation.mutate(channel.id)}
+                      disabled={testMutation.isPending}
+                    >
+                      <Send className="mr-1 h-3 w-3" />
+                      Test
+                    </Button>
+                    <Button size="sm" variant="outline" onClick={() => toggleMutation.mutate(channel)}>
+                      {channel.enabled ? <PauseCircle className="h-3 w-3" /> : <PlayCircle className="h-3 w-3" />}
+                    </Button>
+                    <Button size="sm" variant="outline" onClick={() => openEdit(channel)}>
+                      <Pencil className="h-3 w-3" />
+                    </Button>
+                    <Button size="sm" variant="outline" onClick={() => handleDelete(channel)}>
+                      <Trash2 className="h-3 w-3" />
+                    </Button>
+                  </div>
+                </div>
+              </CardContent>
+            </Card>
+          ))}
+        </div>
+      )}
+
+      {deliveries && deliveries.length > 0 && (
+        <Card>
+          <CardHeader>
+            <CardTitle className="text-lg">Delivery Log</CardTitle>
+            <CardDescription>Most recent deliveries across all channels</CardDescription>
+          </CardHeader>
+          <CardContent>
+            <div className="space-y-2">
+              {deliveries.slice(0, 20).map((record) => (
+                <div key={record.id} className="flex items-start gap-2 text-sm">
+                  {record.status === 'delivered' ? (
+                    <CheckCircle2 className="h-4 w-4 mt-0.5 text-green-600" />
+                  ) : (
+                    <XCircle className="h-4 w-4 mt-0.5 text-destructive" />
+                  )}
+                  <div className="flex-1 min-w-0">
+                    <div className="truncate">
+                      <span className="font-medium">{record.channelName}</span> — {record.title}
+                    </div>
+                    {record.error && <div className="text-xs text-destructive truncate">{record.error}</div>}
+                  </div>
+                  <div className="text-xs text-muted-foreground whitespace-nowrap">
+                    {record.attempts > 1 && `${record.attempts} attempts • `}
+                    {new Date(record.timestamp * 1000).toLocaleString()}
+                  </div>
+                </div>
+              ))}
+            </div>
+          </CardContent>
+        </Card>
+      )}
+
+      <Dialog open={isDialogOpen} onOpenChange={setIsDialogOpen}>
+        <DialogContent className="max-w-2xl">
+          <DialogHeader>
+            <DialogTitle>{editingId ? 'Edit Notification Channel' : 'Add Notification Channel'}</DialogTitle>
+            <DialogDescription>
+              Alerts are delivered to channels they select and to channels subscribed to their severity
+            </DialogDescription>
+          </DialogHeader>
+          <div className="space-y-4">
+            <div className="grid grid-cols-2 gap-4">
+              <div>
+                <Label htmlFor="channel-name">Name</Label>
+                <Input
+                  id="channel-name"
+                  value={form.name}
+                  onChange={(e) => setForm({ ...form, name: e.target.value })}
+                  placeholder="e.g., On-call webhook"
+                />
+              </div>
+              <div>
+                <Label htmlFor="channel-type">Type</Label>
+                <select
+                  id="channel-type"
+                  value={config.type}
+                  onChange={(e) => setConfig(defaultConfig(e.target.value as ChannelType))}
+                  className={selectClassName}
+                >
+                  {(Object.keys(channelLabels) as ChannelType[]).map((type) => (
+                    <option key={type} value={type}>{channelLabels[type]}</option>
+                  ))}
+                </select>
+              </div>
+            </div>
+
+            {config.type === 'webhook' && (
+              <div className="grid grid-cols-2 gap-4">
+                <div>
+                  <Label htmlFor="webhook-url">URL</Label>
+                  <Input
+                    id="webhook-url"
+                    value={config.url}
+                    onChange={(e) => setConfig({ ...config, url: e.target.value })}
+                    placeholder="https://hooks.example.com/alerts"
+                  />
+                </div>
+                <div>
+                  <Label htmlFor="webhook-secret">HMAC Secret (optional)</Label>
+                  <Input
+                    id="webhook-secret"
+                    type="password"
+                    value={config.hmacSecret ?? ''}
+                    onChange={(e) => setConfig({ ...config, hmacSecret: e.target.value || null })}
+                  />
+                </div>
+              </div>
+            )}
+
+            {config.type === 'smtp' && (
+              <div className="space-y-4">
+                <div className="grid grid-cols-3 gap-4">
+                  <div>
+                    <Label htmlFor="smtp-host">Host</Label>
+                    <Input
+                      id="smtp-host"
+                      value={config.host}
+                      onChange={(e) => setConfig({ ...config, host: e.target.value })}
+                    />
+                  </div>
+                  <div>
+                    <Label htmlFor="smtp-port">Port</Label>
+                    <Input
+                      id="smtp-port"
+                      type="number"
+                      value={config.port}
+                      onChange={(e) => setConfig({ ...config, port: parseInt(e.target.value) || 25 })}
+                    />
+                  </div>
+                  <div>
+                    <Label htmlFor="smtp-security">Security</Label>
+                    <select
+                      id="smtp-security"
+                      value={config.security}
+                      onChange={(e) => setConfig({ ...config, security: e.target.value as typeof config.security })}
+                      className={selectClassName}
+                    >
+                      <option value="none">None</option>
+                      <option value="startTls">STARTTLS</option>
+                      <option value="tls">TLS</option>
+                    </select>
+                  </div>
+                </div>
+                <div className="grid grid-cols-2 gap-4">
+                  <div>
+                    <Label htmlFor="smtp-username">Username (optional)</Label>
+                    <Input
+                      id="smtp-username"
+                      value={config.username ?? ''}
+                      onChange={(e) => setConfig({ ...config, username: e.target.value || null })}
+                    />
+                  </div>
+                  <div>
+                    <Label htmlFor="smtp-password">Password</Label>
+                    <Input
+                      id="smtp-password"
+                      type="password"
+                      value={config.password ?? ''}
+                      onChange={(e) => setConfig({ ...config, password: e.target.value || null })}
+                    />
+                  </div>
+                </div>
+                <div className="grid grid-cols-2 gap-4">
+                  <div>
+                    <Label htmlFor="smtp-from">From</Label>
+                    <Input
+                      id="smtp-from"
+                      value={config.from}
+                      onChange={(e) => setConfig({ ...config, from: e.target.value })}
+                      placeholder="kvm-manager@example.com"
+                    />
+                  </div>
+                  <div>
+                    <Label htmlFor="smtp-to">Recipients (comma-separated)</Label>
+                    <Input
+                      id="smtp-to"
+                      value={recipientsInput}
+                      onChange={(e) => setRecipientsInput(e.target.value)}
+                      placeholder="oncall@example.com"
+                    />
+                  </div>
+                </div>
+              </div>
+            )}
+
+            {config.type === 'command' && (
+              <div className="space-y-4">
+                <div className="grid grid-cols-2 gap-4">
+                  <div>
+                    <Label htmlFor="command-program">Program</Label>
+                    <Input
+                      id="command-program"
+                      className="font-mono"
+                      value={config.program}
+                      onChange={(e) => setConfig({ ...config, program: e.target.value })}
+                      placeholder="/usr/local/bin/page-oncall"
+                    />
+                  </div>
+                  <div>
+                    <Label htmlFor="command-args">Arguments</Label>
+                    <Input
+                      id="command-args"
+                      className="font-mono"
+                      value={argsInput}
+                      onChange={(e) => setArgsInput(e.target.value)}
+                    />
+                  </div>
+                </div>
+                <div className="grid grid-cols-2 gap-4">
+                  <div>
+                    <Label htmlFor="command-payload">Event Delivery</Label>
+                    <select
+                      id="command-payload"
+                      value={config.payload}
+                      onChange={(e) => setConfig({ ...config, payload: e.target.value as typeof config.payload })}
+                      className={selectClassName}
+                    >
+                      <option value="env">Environment variables (KVM_ALERT_*)</option>
+                      <option value="stdin">JSON on stdin</option>
+                    </select>
+                  </div>
+                  <div>
+                    <Label htmlFor="command-timeout">Timeout (seconds)</Label>
+                    <Input
+                      id="command-timeout"
+                      type="number"
+                      min="1"
+                      value={config.timeoutSecs}
+                      onChange={(e) => setConfig({ ...config, timeoutSecs: parseInt(e.target.value) || 30 })}
+                    />
+                  </div>
+                </div>
+              </div>
+            )}
+
+            <div>
+              <Label>Always Deliver Severities</Label>
+              <div className="flex gap-4 mt-2">
+                {severities.map((severity) => (
+                  <div key={severity} className="flex items-center space-x-2">
+                    <Checkbox
+                      id={`severity-${severity}`}
+                      checked={form.severities.includes(severity)}
+                      onCheckedChange={(checked) => toggleSeverity(severity, checked === true)}
+                    />
+                    <Label htmlFor={`severity-${severity}`} className="capitalize">{severity}</Label>
+                  </div>
+                ))}
+              </div>
+            </div>
+
+            <div className="grid grid-cols-3 gap-4">
+              <div>
+                <Label htmlFor="retry-attempts">Max Attempts</Label>
+                <Input
+                  id="retry-attempts"
+                  type="number"
+                  min="1"
+                  max="10"
+                  value={form.retry.maxAttempts}
+                  onChange={(e) => setForm({ ...form, retry: { ...form.retry, maxAttempts: parseInt(e.target.value) || 1 } })}
+                />
+              </div>
+              <div>
+                <Label htmlFor="retry-backoff">Backoff (seconds)</Label>
+                <Input
+                  id="retry-backoff"
+                  type="number"
+                  min="0"
+                  value={form.retry.backoffSecs}
+                  onChange={(e) => setForm({ ...form, retry: { ...form.retry, backoffSecs: parseInt(e.target.value) || 0 } })}
+                />
+              </div>
+              <div>
+                <Label htmlFor="retry-multiplier">Backoff Multiplier</Label>
+                <Input
+                  id="retry-multiplier"
+                  type="number"
+                  min="1"
+                  step="0.5"
+                  value={form.retry.backoffMultiplier}
+                  onChange={(e) => setForm({ ...form, retry: { ...form.retry, backoffMultiplier: parseFloat(e.target.value) || 1 } })}
+                />
+              </div>
+            </div>
+          </div>
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setIsDialogOpen(false)}>
+              Cancel
+            </Button>
+            <Button onClick={handleSave} disabled={saveMutation.isPending}>
+              {saveMutation.isPending ? 'Saving...' : editingId ? 'Save Changes' : 'Add Channel'}
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+    </div>
+  )
+}
@@ -62,6 +62,13 @@ export function ConnectionManager({ open, onOpenChange }: ConnectionManagerProps
     enabled: open,
   })
 
+  // Connections whose credentials were lost with an unreadable secret store
+  const { data: needsCredentials = [] } = useQuery({
+    queryKey: ['connections-needing-credentials'],
+    queryFn: api.getConnectionsNeedingCredentials,
+    enabled: open,
+  })
+
   // Connect mutation
   const connectMutation = useMutation({
     mutationFn: (connectionId: string) => api.connectTo(connectionId),
@@ -170,6 +177,11 @@ export function ConnectionManager({ open, onOpenChange }: ConnectionManagerProps
                               Active
                             </span>
                           )}
+                          {needsCredentials.includes(conn.id) && (
+                            <span className="text-xs bg-amber-500 text-white px-2 py-0.5 rounded-full">
+                              Credentials needed
+                            </span>
+                          )}
                         </div>
                         <p className="text-sm text-muted-foreground truncate">
                           {getConnectionDescription(conn)}
@@ -265,6 +277,7 @@ export function ConnectionManager({ open, onOpenChange }: ConnectionManagerProps
           }}
           onSave={() => {
             queryClient.invalidateQueries({ queryKey: ['saved-connections'] })
+            queryClient.invalidateQueries({ queryKey: ['connections-needing-credentials'] })
             setEditingConnection(null)
             setIsAddingNew(false)
           }}
@@ -9,10 +9,11 @@ import { Terminal, Power, PowerOff, RefreshCw } from 'lucide-react'
 
 interface SerialConsoleProps {
   vmId: string
+  connectionId?: string | null
   vmName: string
 }
 
-export function SerialConsole({ vmId, vmName }: SerialConsoleProps) {
+export function SerialConsole({ vmId, connectionId, vmName }: SerialConsoleProps) {
   const [output, setOutput] = useState<string>('')
   const [inputBuffer, setInputBuffer] = useState<string>('')
   const outputRef = useRef<HTMLDivElement>(null)
@@ -22,7 +23,7 @@ export function SerialConsole({ vmId, vmName }: SerialConsoleProps) {
   // Get serial console info
   const { data: consoleInfo, isLoading: infoLoading } = useQuery({
     queryKey: ['serialConsoleInfo', vmId],
-    queryFn: () => api.getSerialConsoleInfo(vmId),
+    queryFn: () => api.getSerialConsoleInfo(vmId, connectionId ?? undefined),
     enabled: !!vmId,
   })
 
@@ -36,7 +37,7 @@ export function SerialConsole({ vmId, vmName }: SerialConsoleProps) {
 
   // Open console mutation
   const openMutation = useMutation({
-    mutationFn: () => api.openSerialConsole(vmId),
+    mutationFn: () => api.openSerialConsole(vmId, connectionId ?? undefined),
     onSuccess: () => {
       toast.success('Serial console connected')
       refetchConnected()
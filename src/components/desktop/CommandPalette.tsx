@@ -55,7 +55,7 @@ export function CommandPalette({ open: controlledOpen, onOpenChange }: CommandPa
   // Fetch VMs for VM-specific commands
   const { data: vms } = useQuery({
     queryKey: ['vms'],
-    queryFn: api.getVms,
+    queryFn: () => api.getVms(),
   })
 
   // Get currently selected/focused VM
@@ -64,7 +64,7 @@ export function CommandPalette({ open: controlledOpen, onOpenChange }: CommandPa
 
   // VM mutations
   const startMutation = useMutation({
-    mutationFn: (vmId: string) => api.startVm(vmId),
+    mutationFn: (vm: VM) => api.startVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success('VM started')
@@ -73,7 +73,7 @@ export function CommandPalette({ open: controlledOpen, onOpenChange }: CommandPa
   })
 
   const stopMutation = useMutation({
-    mutationFn: (vmId: string) => api.forceStopVm(vmId),
+    mutationFn: (vm: VM) => api.forceStopVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success('VM stopped')
@@ -82,7 +82,7 @@ export function CommandPalette({ open: controlledOpen, onOpenChange }: CommandPa
   })
 
   const pauseMutation = useMutation({
-    mutationFn: (vmId: string) => api.pauseVm(vmId),
+    mutationFn: (vm: VM) => api.pauseVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success('VM paused')
@@ -91,7 +91,7 @@ export function CommandPalette({ open: controlledOpen, onOpenChange }: CommandPa
   })
 
   const resumeMutation = useMutation({
-    mutationFn: (vmId: string) => api.resumeVm(vmId),
+    mutationFn: (vm: VM) => api.resumeVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success('VM resumed')
@@ -100,7 +100,7 @@ export function CommandPalette({ open: controlledOpen, onOpenChange }: CommandPa
   })
 
   const rebootMutation = useMutation({
-    mutationFn: (vmId: string) => api.rebootVm(vmId),
+    mutationFn: (vm: VM) => api.rebootVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success('VM rebooted')
@@ -129,7 +129,7 @@ export function CommandPalette({ open: controlledOpen, onOpenChange }: CommandPa
   // Helper to open VM details
   const openVmDetails = async (vm: VM) => {
     try {
-      await api.openVmDetailsWindow(vm.id, vm.name)
+      await api.openVmDetailsWindow(vm.id, vm.name, vm.connectionId ?? undefined)
     } catch (err) {
       toast.error(`Failed to open VM details: ${err}`)
     }
@@ -138,7 +138,7 @@ export function CommandPalette({ open: controlledOpen, onOpenChange }: CommandPa
   // Helper to open console
   const openConsole = async (vm: VM) => {
     try {
-      await api.openConsoleWindow(vm.id, vm.name)
+      await api.openConsoleWindow(vm.id, vm.name, vm.connectionId ?? undefined)
     } catch (err) {
       toast.error(`Failed to open console: ${err}`)
     }
@@ -170,7 +170,7 @@ export function CommandPalette({ open: controlledOpen, onOpenChange }: CommandPa
           <>
             <CommandGroup heading={`VM: ${activeVm.name}`}>
               {activeVm.state === 'stopped' && (
-                <CommandItem onSelect={() => runCommand(() => startMutation.mutate(activeVm.id))}>
+                <CommandItem onSelect={() => runCommand(() => startMutation.mutate(activeVm))}>
                   <Play className="mr-2 h-4 w-4 text-green-500" />
                   <span>Start VM</span>
                   <CommandShortcut>⌃P</CommandShortcut>
@@ -178,16 +178,16 @@ export function CommandPalette({ open: controlledOpen, onOpenChange }: CommandPa
               )}
               {activeVm.state === 'running' && (
                 <>
-                  <CommandItem onSelect={() => runCommand(() => stopMutation.mutate(activeVm.id))}>
+                  <CommandItem onSelect={() => runCommand(() => stopMutation.mutate(activeVm))}>
                     <Square className="mr-2 h-4 w-4 text-red-500" />
                     <span>Stop VM</span>
                     <CommandShortcut>⌃S</CommandShortcut>
                   </CommandItem>
-                  <CommandItem onSelect={() => runCommand(() => pauseMutation.mutate(activeVm.id))}>
+                  <CommandItem onSelect={() => runCommand(() => pauseMutation.mutate(activeVm))}>
                     <Pause className="mr-2 h-4 w-4 text-yellow-500" />
                     <span>Pause VM</span>
                   </CommandItem>
-                  <CommandItem onSelect={() => runCommand(() => rebootMutation.mutate(activeVm.id))}>
+                  <CommandItem onSelect={() => runCommand(() => rebootMutation.mutate(activeVm))}>
                     <RotateCw className="mr-2 h-4 w-4" />
                     <span>Reboot VM</span>
                   </CommandItem>
@@ -199,7 +199,7 @@ export function CommandPalette({ open: controlledOpen, onOpenChange }: CommandPa
                 </>
               )}
               {activeVm.state === 'paused' && (
-                <CommandItem onSelect={() => runCommand(() => resumeMutation.mutate(activeVm.id))}>
+                <CommandItem onSelect={() => runCommand(() => resumeMutation.mutate(activeVm))}>
                   <Play className="mr-2 h-4 w-4 text-green-500" />
                   <span>Resume VM</span>
                 </CommandItem>
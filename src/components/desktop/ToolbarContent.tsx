@@ -3,7 +3,8 @@ import { Toolbar, ToolbarButton, ToolbarSeparator, ToolbarSpacer } from './Toolb
 import { useToolbarStore } from '@/hooks/useToolbarActions'
 import type { VmSortField } from '@/hooks/useToolbarActions'
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
-import { api } from '@/lib/tauri'
+import { api, batchPerConnection } from '@/lib/tauri'
+import type { VM } from '@/lib/types'
 import { toast } from 'sonner'
 import { useNavigate } from 'react-router-dom'
 import { Button } from '@/components/ui/button'
@@ -161,7 +162,7 @@ export function ToolbarContent({ onOpenCommandPalette }: ToolbarContentProps) {
   // Get VMs data to check focused VM state
   const { data: vms } = useQuery({
     queryKey: ['vms'],
-    queryFn: api.getVms,
+    queryFn: () => api.getVms(),
   })
 
   // Get selected VMs
@@ -184,7 +185,7 @@ export function ToolbarContent({ onOpenCommandPalette }: ToolbarContentProps) {
 
   // Single VM mutations
   const startMutation = useMutation({
-    mutationFn: (vmId: string) => api.startVm(vmId),
+    mutationFn: (vm: VM) => api.startVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success('VM started successfully')
@@ -193,7 +194,7 @@ export function ToolbarContent({ onOpenCommandPalette }: ToolbarContentProps) {
   })
 
   const stopMutation = useMutation({
-    mutationFn: (vmId: string) => api.forceStopVm(vmId),
+    mutationFn: (vm: VM) => api.forceStopVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success('VM stopped successfully')
@@ -202,7 +203,7 @@ export function ToolbarContent({ onOpenCommandPalette }: ToolbarContentProps) {
   })
 
   const pauseMutation = useMutation({
-    mutationFn: (vmId: string) => api.pauseVm(vmId),
+    mutationFn: (vm: VM) => api.pauseVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success('VM paused successfully')
@@ -212,7 +213,7 @@ export function ToolbarContent({ onOpenCommandPalette }: ToolbarContentProps) {
 
   // Batch mutations
   const batchStartMutation = useMutation({
-    mutationFn: (vmIds: string[]) => api.batchStartVms(vmIds),
+    mutationFn: (targets: VM[]) => batchPerConnection(targets, api.batchStartVms),
     onSuccess: (data) => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       const successCount = data.filter(r => r.success).length
@@ -222,7 +223,8 @@ export function ToolbarContent({ onOpenCommandPalette }: ToolbarContentProps) {
   })
 
   const batchStopMutation = useMutation({
-    mutationFn: (vmIds: string[]) => api.batchStopVms(vmIds, true),
+    mutationFn: (targets: VM[]) =>
+      batchPerConnection(targets, (vmIds, connectionId) => api.batchStopVms(vmIds, true, connectionId)),
     onSuccess: (data) => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       const successCount = data.filter(r => r.success).length
@@ -232,42 +234,42 @@ export function ToolbarContent({ onOpenCommandPalette }: ToolbarContentProps) {
   })
 
   const openConsoleMutation = useMutation({
-    mutationFn: ({ vmId, vmName }: { vmId: string; vmName: string }) => api.openConsoleWindow(vmId, vmName),
+    mutationFn: (vm: VM) => api.openConsoleWindow(vm.id, vm.name, vm.connectionId ?? undefined),
     onSuccess: () => toast.success('Console window opened'),
     onError: (error) => toast.error(`Failed to open console: ${error}`)
   })
 
   const handleStart = () => {
     if (isBatchMode) {
-      const stoppedIds = selectedVms.filter(vm => vm.state === 'stopped').map(vm => vm.id)
-      if (stoppedIds.length > 0) {
-        batchStartMutation.mutate(stoppedIds)
+      const stopped = selectedVms.filter(vm => vm.state === 'stopped')
+      if (stopped.length > 0) {
+        batchStartMutation.mutate(stopped)
       }
-    } else if (selectedVmIds.length === 1 && canStart) {
-      startMutation.mutate(selectedVmIds[0])
+    } else if (selectedVms.length === 1 && canStart) {
+      startMutation.mutate(selectedVms[0])
     }
   }
 
   const handleStop = () => {
     if (isBatchMode) {
-      const runningIds = selectedVms.filter(vm => vm.state === 'running').map(vm => vm.id)
-      if (runningIds.length > 0) {
-        batchStopMutation.mutate(runningIds)
+      const running = selectedVms.filter(vm => vm.state === 'running')
+      if (running.length > 0) {
+        batchStopMutation.mutate(running)
       }
-    } else if (selectedVmIds.length === 1 && canStop) {
-      stopMutation.mutate(selectedVmIds[0])
+    } else if (selectedVms.length === 1 && canStop) {
+      stopMutation.mutate(selectedVms[0])
     }
   }
 
   const handlePause = () => {
-    if (selectedVmIds.length === 1 && canPause) {
-      pauseMutation.mutate(selectedVmIds[0])
+    if (selectedVmIds.length === 1 && canPause && activeVm) {
+      pauseMutation.mutate(activeVm)
     }
   }
 
   const handleConsole = () => {
     if (selectedVmIds.length === 1 && canConsole && activeVm) {
-      openConsoleMutation.mutate({ vmId: selectedVmIds[0], vmName: activeVm.name })
+      openConsoleMutation.mutate(activeVm)
     }
   }
 
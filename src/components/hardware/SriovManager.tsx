@@ -122,7 +122,7 @@ export function SriovManager({ vm, children }: SriovManagerProps) {
 
   // Attach VF to VM mutation
   const attachVfMutation = useMutation({
-    mutationFn: (vfAddress: string) => api.attachSriovVf(vm.id, vfAddress),
+    mutationFn: (vfAddress: string) => api.attachSriovVf(vm.id, vfAddress, vm.connectionId ?? undefined),
     onSuccess: () => {
       toast.success('VF attached to VM')
       queryClient.invalidateQueries({ queryKey: ['sriov-vfs'] })
@@ -135,7 +135,7 @@ export function SriovManager({ vm, children }: SriovManagerProps) {
 
   // Detach VF from VM mutation
   const detachVfMutation = useMutation({
-    mutationFn: (vfAddress: string) => api.detachSriovVf(vm.id, vfAddress),
+    mutationFn: (vfAddress: string) => api.detachSriovVf(vm.id, vfAddress, vm.connectionId ?? undefined),
     onSuccess: () => {
       toast.success('VF detached from VM')
       queryClient.invalidateQueries({ queryKey: ['sriov-vfs'] })
@@ -0,0 +1,204 @@
+import { useState } from 'react'
+import { useQuery } from '@tanstack/react-query'
+import { api } from '@/lib/tauri'
+import type { HostMetrics, HostSnapshot } from '@/lib/types'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Badge } from '@/components/ui/badge'
+import { Progress } from '@/components/ui/progress'
+import { LineChart, Line, XAxis, YAxis, CartesianGrid, Tooltip, ResponsiveContainer, Legend } from 'recharts'
+import { Server } from 'lucide-react'
+
+const HISTORY_WINDOW_SECS = 6 * 3600
+const HISTORY_MAX_POINTS = 120
+
+const formatBytes = (bytes: number) => {
+  const gb = bytes / 1024 ** 3
+  return gb >= 1024 ? `${(gb / 1024).toFixed(1)} TB` : `${gb.toFixed(1)} GB`
+}
+
+const percent = (used: number, total: number) => (total > 0 ? (used / total) * 100 : 0)
+
+function UsageRow({ label, value, detail }: { label: string; value: number; detail: string }) {
+  return (
+    <div className="space-y-1">
+      <div className="flex justify-between text-sm">
+        <span className="text-muted-foreground">{label}</span>
+        <span className="font-medium">{detail}</span>
+      </div>
+      <Progress value={Math.min(value, 100)} className="h-2" />
+    </div>
+  )
+}
+
+function HostCard({ snapshot }: { snapshot: HostSnapshot }) {
+  const { host, pools, networks } = snapshot
+  const memoryUsed = host.memoryTotalMb - host.memoryFreeMb
+  const cpuOvercommit = host.cpuCount > 0 ? host.vcpusAllocated / host.cpuCount : 0
+  const memoryOvercommit = host.memoryTotalMb > 0 ? host.memoryAllocatedMb / host.memoryTotalMb : 0
+
+  return (
+    <Card>
+      <CardHeader>
+        <div className="flex justify-between items-start">
+          <div>
+            <CardTitle className="text-lg flex items-center gap-2">
+              <Server className="h-4 w-4" />
+              {host.connectionId}
+            </CardTitle>
+            <CardDescription>
+              {host.cpuCount} CPUs • {(host.memoryTotalMb / 1024).toFixed(1)} GB RAM
+              {host.load1 != null && ` • load ${host.load1.toFixed(2)} / ${host.load5?.toFixed(2)} / ${host.load15?.toFixed(2)}`}
+            </CardDescription>
+          </div>
+          <div className="flex gap-2">
+            <Badge variant={cpuOvercommit > 1 ? 'destructive' : 'secondary'}>
+              vCPU {cpuOvercommit.toFixed(2)}×
+            </Badge>
+            <Badge variant={memoryOvercommit > 1 ? 'destructive' : 'secondary'}>
+              RAM {memoryOvercommit.toFixed(2)}×
+            </Badge>
+          </div>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        <UsageRow
+          label="CPU"
+          value={host.cpuUsage ?? 0}
+          detail={host.cpuUsage != null ? `${host.cpuUsage.toFixed(1)}%` : 'Sampling...'}
+        />
+        <UsageRow
+          label="Memory"
+          value={percent(memoryUsed, host.memoryTotalMb)}
+          detail={`${(memoryUsed / 1024).toFixed(1)} / ${(host.memoryTotalMb / 1024).toFixed(1)} GB`}
+        />
+        {host.swapTotalMb != null && host.swapTotalMb > 0 && (
+          <UsageRow
+            label="Swap"
+            value={percent(host.swapTotalMb - (host.swapFreeMb ?? 0), host.swapTotalMb)}
+            detail={`${((host.swapTotalMb - (host.swapFreeMb ?? 0)) / 1024).toFixed(1)} / ${(host.swapTotalMb / 1024).toFixed(1)} GB`}
+          />
+        )}
+        {host.hugepagesTotal != null && host.hugepagesTotal > 0 && (
+          <UsageRow
+            label="Hugepages"
+            value={percent(host.hugepagesTotal - (host.hugepagesFree ?? 0), host.hugepagesTotal)}
+            detail={`${host.hugepagesFree} of ${host.hugepagesTotal} free`}
+          />
+        )}
+        {host.ksmSharedMb != null && host.ksmSharedMb > 0 && (
+          <div className="text-sm text-muted-foreground">KSM saves {(host.ksmSharedMb / 1024).toFixed(2)} GB</div>
+        )}
+
+        {pools.length > 0 && (
+          <div className="space-y-2 pt-2">
+            <div className="text-sm font-medium">Storage Pools</div>
+            {pools.map((pool) => (
+              <UsageRow
+                key={pool.poolName}
+                label={pool.poolName}
+                value={percent(pool.allocationBytes, pool.capacityBytes)}
+                detail={`${formatBytes(pool.allocationBytes)} / ${formatBytes(pool.capacityBytes)}`}
+              />
+            ))}
+          </div>
+        )}
+
+        {networks.length > 0 && (
+          <div className="space-y-1 pt-2">
+            <div className="text-sm font-medium">Networks</div>
+            {networks.map((network) => (
+              <div key={network.networkName} className="flex justify-between text-sm">
+                <span className="text-muted-foreground">
+                  {network.networkName} ({network.bridge})
+                </span>
+                <span>
+                  ↓ {(network.rxBytesPerSec / 1024 / 1024).toFixed(2)} MB/s • ↑ {(network.txBytesPerSec / 1024 / 1024).toFixed(2)} MB/s
+                </span>
+              </div>
+            ))}
+          </div>
+        )}
+      </CardContent>
+    </Card>
+  )
+}
+
+/**
+ * Hypervisor metrics collected by the backend: usage, overcommit and pool capacity per host
+ */
+export function HostMetricsPanel() {
+  const [selectedHost, setSelectedHost] = useState<string>('local')
+
+  const { data: snapshots } = useQuery<HostSnapshot[]>({
+    queryKey: ['host-metrics'],
+    queryFn: () => api.getHostMetrics(),
+    refetchInterval: 30000,
+  })
+
+  const { data: history } = useQuery<HostMetrics[]>({
+    queryKey: ['host-metrics-history', selectedHost],
+    queryFn: () => {
+      const now = Math.floor(Date.now() / 1000)
+      return api.getHostMetricsHistory(selectedHost, now - HISTORY_WINDOW_SECS, now, HISTORY_MAX_POINTS)
+    },
+    refetchInterval: 60000,
+  })
+
+  const chartData = (history ?? []).map((point) => ({
+    time: new Date(point.timestamp * 1000).toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' }),
+    cpu: point.cpuUsage ?? null,
+    memory: percent(point.memoryTotalMb - point.memoryFreeMb, point.memoryTotalMb),
+  }))
+
+  if (!snapshots || snapshots.length === 0) {
+    return null
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="flex justify-between items-center">
+        <h2 className="text-lg font-semibold">Hosts</h2>
+        {snapshots.length > 1 && (
+          <select
+            value={selectedHost}
+            onChange={(e) => setSelectedHost(e.target.value)}
+            className="h-9 rounded-md border border-input bg-background px-3 text-sm"
+          >
+            {snapshots.map((snapshot) => (
+              <option key={snapshot.host.connectionId} value={snapshot.host.connectionId}>
+                {snapshot.host.connectionId}
+              </option>
+            ))}
+          </select>
+        )}
+      </div>
+
+      <div className="grid gap-4 md:grid-cols-2">
+        {snapshots.map((snapshot) => (
+          <HostCard key={snapshot.host.connectionId} snapshot={snapshot} />
+        ))}
+      </div>
+
+      {chartData.length > 1 && (
+        <Card>
+          <CardHeader>
+            <CardTitle className="text-sm">Host CPU and memory, last 6 hours ({selectedHost})</CardTitle>
+          </CardHeader>
+          <CardContent>
+            <ResponsiveContainer width="100%" height={200}>
+              <LineChart data={chartData}>
+                <CartesianGrid strokeDasharray="3 3" className="stroke-muted" />
+                <XAxis dataKey="time" tick={{ fontSize: 11 }} />
+                <YAxis domain={[0, 100]} tick={{ fontSize: 11 }} unit="%" />
+                <Tooltip />
+                <Legend />
+                <Line type="monotone" dataKey="cpu" name="CPU" stroke="#3b82f6" dot={false} connectNulls />
+                <Line type="monotone" dataKey="memory" name="Memory" stroke="#10b981" dot={false} />
+              </LineChart>
+            </ResponsiveContainer>
+          </CardContent>
+        </Card>
+      )}
+    </div>
+  )
+}
@@ -6,6 +6,7 @@ import { KeyboardShortcutsDialog } from '@/components/ui/keyboard-shortcuts-dial
 import { Breadcrumbs } from '@/components/layout/Breadcrumbs'
 import { useQuery } from '@tanstack/react-query'
 import { api } from '@/lib/tauri'
+import { useAlertEvents } from '@/hooks/useAlertEvents'
 import { Wifi, WifiOff, Cpu, MemoryStick } from 'lucide-react'
 
 interface LayoutProps {
@@ -16,6 +17,9 @@ export function Layout({ children }: LayoutProps) {
   const [commandPaletteOpen, setCommandPaletteOpen] = useState(false)
   const [showShortcuts, setShowShortcuts] = useState(false)
 
+  // Alerts are evaluated in the backend; show them whichever page is open
+  useAlertEvents()
+
   // Listen for keyboard shortcuts dialog event
   useEffect(() => {
     const handler = () => setShowShortcuts(true)
@@ -26,7 +30,7 @@ export function Layout({ children }: LayoutProps) {
   // Get VM stats for status bar
   const { data: vms } = useQuery({
     queryKey: ['vms'],
-    queryFn: api.getVms,
+    queryFn: () => api.getVms(),
     refetchInterval: 5000,
   })
 
@@ -1,6 +1,6 @@
 import { useState } from 'react'
 import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query'
-import { api } from '@/lib/tauri'
+import { api, waitForJob } from '@/lib/tauri'
 import { toast } from 'sonner'
 import { Button } from '@/components/ui/button'
 import { Card, CardContent, CardHeader, CardTitle, CardDescription } from '@/components/ui/card'
@@ -30,7 +30,7 @@ export function ImportOvaDialog({ onClose, onSuccess }: ImportOvaDialogProps) {
   // Query storage pools
   const { data: pools = [] } = useQuery({
     queryKey: ['storage-pools'],
-    queryFn: api.getStoragePools,
+    queryFn: () => api.getStoragePools(),
   })
 
   // Select file mutation
@@ -75,17 +75,16 @@ export function ImportOvaDialog({ onClose, onSuccess }: ImportOvaDialogProps) {
   const importMutation = useMutation({
     mutationFn: async () => {
       setStep('importing')
-      setImportProgress(10)
+      setImportProgress(0)
 
-      const result = await api.importOva({
+      const jobId = await api.importOva({
         sourcePath,
         targetPoolPath: selectedPool,
         vmName: vmName !== metadata?.name ? vmName : undefined,
         convertToQcow2,
       })
 
-      setImportProgress(80)
-      return result
+      return waitForJob<string>(jobId, (job) => setImportProgress(Math.round(job.percent)))
     },
     onSuccess: (diskPath) => {
       setImportProgress(100)
@@ -477,7 +477,8 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
       vm.id,
       networkConfig.network,
       networkConfig.model,
-      networkConfig.macAddress || undefined
+      networkConfig.macAddress || undefined,
+      vm.connectionId ?? undefined
     ),
     onSuccess: (mac) => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -497,7 +498,8 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
       vm.id,
       diskConfig.diskPath,
       diskConfig.deviceTarget,
-      diskConfig.busType
+      diskConfig.busType,
+      vm.connectionId ?? undefined
     ),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -528,7 +530,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching sound device
   const attachSoundMutation = useMutation({
-    mutationFn: () => api.attachSound(vm.id, soundConfig.model),
+    mutationFn: () => api.attachSound(vm.id, soundConfig.model, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -543,7 +545,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching input device
   const attachInputMutation = useMutation({
-    mutationFn: () => api.attachInput(vm.id, inputConfig.deviceType, inputConfig.bus),
+    mutationFn: () => api.attachInput(vm.id, inputConfig.deviceType, inputConfig.bus, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -558,7 +560,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching PCI device
   const attachPciMutation = useMutation({
-    mutationFn: () => api.attachPciDevice(vm.id, pciConfig.selectedDevice, pciConfig.managed),
+    mutationFn: () => api.attachPciDevice(vm.id, pciConfig.selectedDevice, pciConfig.managed, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -600,7 +602,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching RNG device
   const attachRngMutation = useMutation({
-    mutationFn: () => api.attachRng(vm.id, rngConfig.backend),
+    mutationFn: () => api.attachRng(vm.id, rngConfig.backend, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -615,7 +617,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching Watchdog device
   const attachWatchdogMutation = useMutation({
-    mutationFn: () => api.attachWatchdog(vm.id, watchdogConfig.model, watchdogConfig.action),
+    mutationFn: () => api.attachWatchdog(vm.id, watchdogConfig.model, watchdogConfig.action, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -630,7 +632,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching USB device
   const attachUsbMutation = useMutation({
-    mutationFn: () => api.attachUsbDevice(vm.id, usbConfig.vendorId, usbConfig.productId),
+    mutationFn: () => api.attachUsbDevice(vm.id, usbConfig.vendorId, usbConfig.productId, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -646,7 +648,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching Channel device
   const attachChannelMutation = useMutation({
-    mutationFn: () => api.attachChannel(vm.id, channelConfig.channelType),
+    mutationFn: () => api.attachChannel(vm.id, channelConfig.channelType, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -667,7 +669,8 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
       filesystemConfig.sourcePath,
       filesystemConfig.targetMount,
       filesystemConfig.fsType,
-      filesystemConfig.readonly
+      filesystemConfig.readonly,
+      vm.connectionId ?? undefined
     ),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -687,7 +690,8 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
       vm.id,
       graphicsConfig.type,
       graphicsConfig.listenAddress,
-      graphicsConfig.port === -1 ? undefined : graphicsConfig.port
+      graphicsConfig.port === -1 ? undefined : graphicsConfig.port,
+      vm.connectionId ?? undefined
     ),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -708,7 +712,8 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
       videoConfig.model,
       videoConfig.vram,
       videoConfig.heads,
-      videoConfig.acceleration3d
+      videoConfig.acceleration3d,
+      vm.connectionId ?? undefined
     ),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -724,7 +729,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching MDEV device
   const attachMdevMutation = useMutation({
-    mutationFn: () => api.attachMdev(vm.id, mdevConfig.mdevUuid),
+    mutationFn: () => api.attachMdev(vm.id, mdevConfig.mdevUuid, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -740,7 +745,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching Serial port
   const attachSerialMutation = useMutation({
-    mutationFn: () => api.attachSerial(vm.id, serialConfig.portType, serialConfig.targetPort),
+    mutationFn: () => api.attachSerial(vm.id, serialConfig.portType, serialConfig.targetPort, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -755,7 +760,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching Console device
   const attachConsoleMutation = useMutation({
-    mutationFn: () => api.attachConsole(vm.id, consoleConfig.targetPort, consoleConfig.targetType),
+    mutationFn: () => api.attachConsole(vm.id, consoleConfig.targetPort, consoleConfig.targetType, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -770,7 +775,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching TPM device
   const attachTpmMutation = useMutation({
-    mutationFn: () => api.attachTpm(vm.id, tpmConfig.model, tpmConfig.version),
+    mutationFn: () => api.attachTpm(vm.id, tpmConfig.model, tpmConfig.version, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -785,7 +790,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching USB Controller
   const attachUsbControllerMutation = useMutation({
-    mutationFn: () => api.attachUsbController(vm.id, usbControllerConfig.model),
+    mutationFn: () => api.attachUsbController(vm.id, usbControllerConfig.model, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -800,7 +805,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching SCSI Controller
   const attachScsiControllerMutation = useMutation({
-    mutationFn: () => api.attachScsiController(vm.id, scsiControllerConfig.model),
+    mutationFn: () => api.attachScsiController(vm.id, scsiControllerConfig.model, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -815,7 +820,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching Panic Notifier
   const attachPanicMutation = useMutation({
-    mutationFn: () => api.attachPanicNotifier(vm.id, panicConfig.model),
+    mutationFn: () => api.attachPanicNotifier(vm.id, panicConfig.model, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -830,7 +835,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching VirtIO VSOCK
   const attachVsockMutation = useMutation({
-    mutationFn: () => api.attachVsock(vm.id, vsockConfig.cid),
+    mutationFn: () => api.attachVsock(vm.id, vsockConfig.cid, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -845,7 +850,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching Parallel Port
   const attachParallelMutation = useMutation({
-    mutationFn: () => api.attachParallel(vm.id, parallelConfig.targetPort),
+    mutationFn: () => api.attachParallel(vm.id, parallelConfig.targetPort, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -860,7 +865,7 @@ export function AddHardwareDialog({ vm, open, onOpenChange, filter = 'all' }: Ad
 
   // Mutation for attaching Smartcard
   const attachSmartcardMutation = useMutation({
-    mutationFn: () => api.attachSmartcard(vm.id, smartcardConfig.mode),
+    mutationFn: () => api.attachSmartcard(vm.id, smartcardConfig.mode, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
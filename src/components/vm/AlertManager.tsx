@@ -1,30 +1,53 @@
 import { useState } from 'react'
 import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query'
 import { api } from '@/lib/tauri'
-import type { ResourceAlert, CreateAlertRequest, ThresholdType, AlertSeverity, VM } from '@/lib/types'
+import type { ResourceAlert, CreateAlertRequest, ThresholdType, AlertScope, AlertSeverity, NotificationChannel, SavedConnection, VM } from '@/lib/types'
 import { Button } from '@/components/ui/button'
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
 import { Dialog, DialogContent, DialogDescription, DialogFooter, DialogHeader, DialogTitle } from '@/components/ui/dialog'
 import { Input } from '@/components/ui/input'
 import { Label } from '@/components/ui/label'
 import { Badge } from '@/components/ui/badge'
+import { Checkbox } from '@/components/ui/checkbox'
 import { toast } from 'sonner'
-import { Bell, Plus, Trash2, Cpu, HardDrive, Network as NetworkIcon, Activity, PlayCircle, PauseCircle, AlertTriangle } from 'lucide-react'
+import { Bell, Plus, Trash2, Cpu, HardDrive, Network as NetworkIcon, Activity, PlayCircle, PauseCircle, AlertTriangle, Server, Database, Gauge, Layers } from 'lucide-react'
 
 interface AlertManagerProps {
   vmId?: string // Optional: show alerts for specific VM
 }
 
+// Metrics each alert scope can watch
+const scopeThresholds: Record<AlertScope, ThresholdType[]> = {
+  vm: ['cpu', 'memory', 'disk', 'network'],
+  host: ['cpu', 'memory', 'swap', 'load', 'hugepages'],
+  pool: ['storage'],
+  network: ['network'],
+}
+
+const scopeLabels: Record<AlertScope, string> = {
+  vm: 'Virtual machine',
+  host: 'Host',
+  pool: 'Storage pool',
+  network: 'Virtual network',
+}
+
 export function AlertManager({ vmId }: AlertManagerProps) {
   const queryClient = useQueryClient()
   const [isCreateDialogOpen, setIsCreateDialogOpen] = useState(false)
   const [alertForm, setAlertForm] = useState<CreateAlertRequest>({
     name: '',
+    scope: 'vm',
     vmId: vmId || '',
+    connectionId: null,
+    target: null,
     thresholdType: 'cpu',
     thresholdValue: 80,
     severity: 'warning',
     consecutiveChecks: 3,
+    channelIds: [],
+    clearThreshold: null,
+    cooldownSecs: 0,
+    renotifySecs: 0,
   })
 
   // Query for VMs (to populate dropdown)
@@ -33,6 +56,31 @@ export function AlertManager({ vmId }: AlertManagerProps) {
     queryFn: () => api.getVms(),
   })
 
+  // Hosts, pools and networks for host-level alerts
+  const scope = alertForm.scope ?? 'vm'
+  const formConnectionId = alertForm.connectionId || 'local'
+  const { data: savedConnections } = useQuery<SavedConnection[]>({
+    queryKey: ['saved-connections'],
+    queryFn: () => api.getSavedConnections(),
+    enabled: !vmId,
+  })
+  const { data: pools } = useQuery({
+    queryKey: ['storage-pools', formConnectionId],
+    queryFn: () => api.getStoragePools(formConnectionId),
+    enabled: isCreateDialogOpen && scope === 'pool',
+  })
+  const { data: networks } = useQuery({
+    queryKey: ['networks', formConnectionId],
+    queryFn: () => api.getNetworks(formConnectionId),
+    enabled: isCreateDialogOpen && scope === 'network',
+  })
+
+  // Query for notification channels alerts can be delivered to
+  const { data: channels } = useQuery<NotificationChannel[]>({
+    queryKey: ['notification-channels'],
+    queryFn: () => api.listNotificationChannels(),
+  })
+
   // Query for alerts
   const { data: alerts, isLoading } = useQuery<ResourceAlert[]>({
     queryKey: vmId ? ['alerts', vmId] : ['alerts'],
@@ -81,23 +129,54 @@ export function AlertManager({ vmId }: AlertManagerProps) {
   const resetForm = () => {
     setAlertForm({
       name: '',
+      scope: 'vm',
       vmId: vmId || '',
+      connectionId: null,
+      target: null,
       thresholdType: 'cpu',
       thresholdValue: 80,
       severity: 'warning',
       consecutiveChecks: 3,
+      channelIds: [],
+      clearThreshold: null,
+      cooldownSecs: 0,
+      renotifySecs: 0,
     })
   }
 
+  const toggleChannel = (channelId: string, checked: boolean) => {
+    const selected = alertForm.channelIds ?? []
+    setAlertForm({
+      ...alertForm,
+      channelIds: checked ? [...selected, channelId] : selected.filter((id) => id !== channelId),
+    })
+  }
+
+  const getChannelNames = (channelIds: string[]) =>
+    channelIds.map((id) => channels?.find((c) => c.id === id)?.name ?? id).join(', ')
+
   const handleCreate = () => {
-    if (!alertForm.vmId) {
+    if (scope === 'vm' && !alertForm.vmId) {
       toast.error('Please select a VM')
       return
     }
-    if (alertForm.thresholdValue < 0 || alertForm.thresholdValue > 100) {
+    if ((scope === 'pool' || scope === 'network') && !alertForm.target) {
+      toast.error(`Please select a ${scope === 'pool' ? 'storage pool' : 'network'}`)
+      return
+    }
+    if (thresholdUnit(alertForm.thresholdType) !== '%') {
+      if (alertForm.thresholdValue < 0) {
+        toast.error('Threshold must not be negative')
+        return
+      }
+    } else if (alertForm.thresholdValue < 0 || alertForm.thresholdValue > 100) {
       toast.error('Threshold value must be between 0 and 100')
       return
     }
+    if (alertForm.clearThreshold != null && alertForm.clearThreshold > alertForm.thresholdValue) {
+      toast.error('Clear threshold must not be above the alert threshold')
+      return
+    }
     createMutation.mutate(alertForm)
   }
 
@@ -121,6 +200,14 @@ export function AlertManager({ vmId }: AlertManagerProps) {
         return <HardDrive className="h-4 w-4" />
       case 'network':
         return <NetworkIcon className="h-4 w-4" />
+      case 'swap':
+        return <Layers className="h-4 w-4" />
+      case 'load':
+        return <Gauge className="h-4 w-4" />
+      case 'hugepages':
+        return <Server className="h-4 w-4" />
+      case 'storage':
+        return <Database className="h-4 w-4" />
     }
   }
 
@@ -128,12 +215,27 @@ export function AlertManager({ vmId }: AlertManagerProps) {
     const labels: Record<ThresholdType, string> = {
       cpu: 'CPU Usage',
       memory: 'Memory Usage',
-      disk: 'Disk Usage',
-      network: 'Network Usage',
+      disk: 'Disk I/O',
+      network: 'Network Throughput',
+      swap: 'Swap Usage',
+      load: 'Load Average',
+      hugepages: 'Hugepages Usage',
+      storage: 'Pool Usage',
     }
     return labels[type]
   }
 
+  // Disk and network alerts compare against MB/s rates, CPU and memory against percentages
+  const isRateThreshold = (type: ThresholdType) => type === 'disk' || type === 'network'
+
+  const thresholdUnit = (type: ThresholdType) =>
+    isRateThreshold(type) ? 'MB/s' : type === 'load' ? '' : '%'
+
+  const formatThreshold = (type: ThresholdType, value: number) => {
+    const unit = thresholdUnit(type)
+    return unit === '%' ? `${value}%` : `${value}${unit ? ` ${unit}` : ''}`
+  }
+
   const getSeverityColor = (severity: AlertSeverity) => {
     switch (severity) {
       case 'info':
@@ -149,6 +251,8 @@ export function AlertManager({ vmId }: AlertManagerProps) {
     return severity.charAt(0).toUpperCase() + severity.slice(1)
   }
 
+  const formatMinutes = (secs: number) => (secs > 0 ? `${Math.round(secs / 60)} min` : 'Off')
+
   const formatTimestamp = (timestamp: number) => {
     return new Date(timestamp * 1000).toLocaleString()
   }
@@ -158,6 +262,35 @@ export function AlertManager({ vmId }: AlertManagerProps) {
     return vm?.name || vmId
   }
 
+  const getConnectionName = (connectionId?: string | null) => {
+    const id = connectionId || 'local'
+    return savedConnections?.find((c) => c.id === id)?.name ?? id
+  }
+
+  const describeSubject = (alert: ResourceAlert) => {
+    switch (alert.scope ?? 'vm') {
+      case 'vm':
+        return `VM: ${getVmName(alert.vmId)}`
+      case 'host':
+        return `Host: ${getConnectionName(alert.connectionId)}`
+      case 'pool':
+        return `Pool: ${alert.target} on ${getConnectionName(alert.connectionId)}`
+      case 'network':
+        return `Network: ${alert.target} on ${getConnectionName(alert.connectionId)}`
+    }
+  }
+
+  const changeScope = (nextScope: AlertScope) => {
+    setAlertForm({
+      ...alertForm,
+      scope: nextScope,
+      vmId: nextScope === 'vm' ? vmId || '' : '',
+      connectionId: nextScope === 'vm' ? null : alertForm.connectionId ?? 'local',
+      target: null,
+      thresholdType: scopeThresholds[nextScope][0],
+    })
+  }
+
   if (isLoading) {
     return (
       <div className="flex items-center justify-center p-8">
@@ -171,7 +304,7 @@ export function AlertManager({ vmId }: AlertManagerProps) {
       <div className="flex justify-between items-center">
         <div>
           <h2 className="text-2xl font-bold">Resource Alerts</h2>
-          <p className="text-muted-foreground">Set thresholds and get notified when resources exceed limits</p>
+          <p className="text-muted-foreground">Set thresholds on VMs, hosts, pools and networks and get notified when they exceed limits</p>
         </div>
         <Button onClick={() => setIsCreateDialogOpen(true)}>
           <Plus className="mr-2 h-4 w-4" />
@@ -200,8 +333,8 @@ export function AlertManager({ vmId }: AlertManagerProps) {
                       {alert.name}
                     </CardTitle>
                     <CardDescription className="mt-1">
-                      {!vmId && `VM: ${getVmName(alert.vmId)} • `}
-                      {getThresholdLabel(alert.thresholdType)} threshold: {alert.thresholdValue}%
+                      {!vmId && `${describeSubject(alert)} • `}
+                      {getThresholdLabel(alert.thresholdType)} threshold: {formatThreshold(alert.thresholdType, alert.thresholdValue)}
                     </CardDescription>
                   </div>
                   <div className="flex items-center gap-2">
@@ -219,7 +352,7 @@ export function AlertManager({ vmId }: AlertManagerProps) {
                   <div className="grid grid-cols-3 gap-4 text-sm">
                     <div>
                       <div className="text-muted-foreground">Threshold</div>
-                      <div className="font-medium">{alert.thresholdValue}%</div>
+                      <div className="font-medium">{formatThreshold(alert.thresholdType, alert.thresholdValue)}</div>
                     </div>
                     <div>
                       <div className="text-muted-foreground">Consecutive Checks</div>
@@ -231,6 +364,29 @@ export function AlertManager({ vmId }: AlertManagerProps) {
                     </div>
                   </div>
 
+                  <div className="grid grid-cols-3 gap-4 text-sm">
+                    <div>
+                      <div className="text-muted-foreground">Clears Below</div>
+                      <div className="font-medium">
+                        {formatThreshold(alert.thresholdType, alert.clearThreshold ?? alert.thresholdValue)}
+                      </div>
+                    </div>
+                    <div>
+                      <div className="text-muted-foreground">Cooldown</div>
+                      <div className="font-medium">{formatMinutes(alert.cooldownSecs ?? 0)}</div>
+                    </div>
+                    <div>
+                      <div className="text-muted-foreground">Re-notify Every</div>
+                      <div className="font-medium">{formatMinutes(alert.renotifySecs ?? 0)}</div>
+                    </div>
+                  </div>
+
+                  {alert.channelIds?.length > 0 && (
+                    <div className="text-sm text-muted-foreground">
+                      Notifies: {getChannelNames(alert.channelIds)}
+                    </div>
+                  )}
+
                   {alert.lastTriggered && (
                     <div className="flex items-center text-sm text-muted-foreground">
                       <AlertTriangle className="mr-2 h-4 w-4" />
@@ -301,6 +457,63 @@ export function AlertManager({ vmId }: AlertManagerProps) {
             </div>
 
             {!vmId && (
+              <div>
+                <Label htmlFor="alert-scope">Watch</Label>
+                <select
+                  id="alert-scope"
+                  value={scope}
+                  onChange={(e) => changeScope(e.target.value as AlertScope)}
+                  className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background"
+                >
+                  {(Object.keys(scopeLabels) as AlertScope[]).map((option) => (
+                    <option key={option} value={option}>
+                      {scopeLabels[option]}
+                    </option>
+                  ))}
+                </select>
+              </div>
+            )}
+
+            {scope !== 'vm' && (
+              <div className="grid grid-cols-2 gap-4">
+                <div>
+                  <Label htmlFor="alert-connection">Host</Label>
+                  <select
+                    id="alert-connection"
+                    value={formConnectionId}
+                    onChange={(e) => setAlertForm({ ...alertForm, connectionId: e.target.value, target: null })}
+                    className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background"
+                  >
+                    {(savedConnections ?? [{ id: 'local', name: 'local' } as SavedConnection]).map((connection) => (
+                      <option key={connection.id} value={connection.id}>
+                        {connection.name}
+                      </option>
+                    ))}
+                  </select>
+                </div>
+
+                {scope !== 'host' && (
+                  <div>
+                    <Label htmlFor="alert-target">{scope === 'pool' ? 'Storage Pool' : 'Network'}</Label>
+                    <select
+                      id="alert-target"
+                      value={alertForm.target ?? ''}
+                      onChange={(e) => setAlertForm({ ...alertForm, target: e.target.value || null })}
+                      className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background"
+                    >
+                      <option value="">Select...</option>
+                      {(scope === 'pool' ? pools : networks)?.map((item) => (
+                        <option key={item.name} value={item.name}>
+                          {item.name}
+                        </option>
+                      ))}
+                    </select>
+                  </div>
+                )}
+              </div>
+            )}
+
+            {!vmId && scope === 'vm' && (
               <div>
                 <Label htmlFor="vm-select">Virtual Machine</Label>
                 <select
@@ -335,20 +548,28 @@ export function AlertManager({ vmId }: AlertManagerProps) {
                   }
                   className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background"
                 >
-                  <option value="cpu">CPU Usage</option>
-                  <option value="memory">Memory Usage</option>
-                  <option value="disk">Disk Usage</option>
-                  <option value="network">Network Usage</option>
+                  {scopeThresholds[scope].map((type) => (
+                    <option key={type} value={type}>
+                      {getThresholdLabel(type)}
+                    </option>
+                  ))}
                 </select>
+                {scope === 'host' && ['swap', 'load', 'hugepages'].includes(alertForm.thresholdType) && (
+                  <p className="text-xs text-muted-foreground mt-1">
+                    Only available for the local host
+                  </p>
+                )}
               </div>
 
               <div>
-                <Label htmlFor="threshold-value">Threshold (%)</Label>
+                <Label htmlFor="threshold-value">
+                  Threshold{thresholdUnit(alertForm.thresholdType) && ` (${thresholdUnit(alertForm.thresholdType)})`}
+                </Label>
                 <Input
                   id="threshold-value"
                   type="number"
                   min="0"
-                  max="100"
+                  max={thresholdUnit(alertForm.thresholdType) === '%' ? '100' : undefined}
                   step="5"
                   value={alertForm.thresholdValue}
                   onChange={(e) =>
@@ -402,11 +623,95 @@ export function AlertManager({ vmId }: AlertManagerProps) {
               </div>
             </div>
 
+            <div className="grid grid-cols-3 gap-4">
+              <div>
+                <Label htmlFor="clear-threshold">
+                  Clear Below{thresholdUnit(alertForm.thresholdType) && ` (${thresholdUnit(alertForm.thresholdType)})`}
+                </Label>
+                <Input
+                  id="clear-threshold"
+                  type="number"
+                  min="0"
+                  step="5"
+                  value={alertForm.clearThreshold ?? ''}
+                  placeholder={String(alertForm.thresholdValue)}
+                  onChange={(e) =>
+                    setAlertForm({
+                      ...alertForm,
+                      clearThreshold: e.target.value === '' ? null : parseFloat(e.target.value),
+                    })
+                  }
+                />
+                <p className="text-xs text-muted-foreground mt-1">
+                  Resolves once usage drops below this value
+                </p>
+              </div>
+
+              <div>
+                <Label htmlFor="cooldown">Cooldown (minutes)</Label>
+                <Input
+                  id="cooldown"
+                  type="number"
+                  min="0"
+                  value={(alertForm.cooldownSecs ?? 0) / 60}
+                  onChange={(e) =>
+                    setAlertForm({
+                      ...alertForm,
+                      cooldownSecs: Math.max(0, Math.round(parseFloat(e.target.value || '0') * 60)),
+                    })
+                  }
+                />
+                <p className="text-xs text-muted-foreground mt-1">
+                  Quiet period after the alert resolves
+                </p>
+              </div>
+
+              <div>
+                <Label htmlFor="renotify">Re-notify (minutes)</Label>
+                <Input
+                  id="renotify"
+                  type="number"
+                  min="0"
+                  value={(alertForm.renotifySecs ?? 0) / 60}
+                  onChange={(e) =>
+                    setAlertForm({
+                      ...alertForm,
+                      renotifySecs: Math.max(0, Math.round(parseFloat(e.target.value || '0') * 60)),
+                    })
+                  }
+                />
+                <p className="text-xs text-muted-foreground mt-1">
+                  Reminder interval until acknowledged, 0 to notify once
+                </p>
+              </div>
+            </div>
+
+            {channels && channels.length > 0 && (
+              <div>
+                <Label>Notification Channels</Label>
+                <div className="flex flex-wrap gap-4 mt-2">
+                  {channels.map((channel) => (
+                    <div key={channel.id} className="flex items-center space-x-2">
+                      <Checkbox
+                        id={`channel-${channel.id}`}
+                        checked={alertForm.channelIds?.includes(channel.id) ?? false}
+                        onCheckedChange={(checked) => toggleChannel(channel.id, checked === true)}
+                      />
+                      <Label htmlFor={`channel-${channel.id}`}>{channel.name}</Label>
+                    </div>
+                  ))}
+                </div>
+                <p className="text-xs text-muted-foreground mt-1">
+                  Channels subscribed to this alert's severity are notified as well
+                </p>
+              </div>
+            )}
+
             <div className="bg-muted p-3 rounded-md text-sm">
               <p className="font-medium mb-1">Alert Preview:</p>
               <p>
                 Alert will trigger when <strong>{getThresholdLabel(alertForm.thresholdType)}</strong> exceeds{' '}
-                <strong>{alertForm.thresholdValue}%</strong> for{' '}
+                <strong>{formatThreshold(alertForm.thresholdType, alertForm.thresholdValue)}</strong> for{' '}
                 <strong>{alertForm.consecutiveChecks}</strong> consecutive check(s).
               </p>
             </div>
@@ -415,7 +720,7 @@ export function AlertManager({ vmId }: AlertManagerProps) {
             <Button variant="outline" onClick={() => setIsCreateDialogOpen(false)}>
               Cancel
             </Button>
-            <Button onClick={handleCreate} disabled={!alertForm.name || !alertForm.vmId}>
+            <Button onClick={handleCreate} disabled={!alertForm.name || (scope === 'vm' && !alertForm.vmId)}>
               Create Alert
             </Button>
           </DialogFooter>
@@ -1,7 +1,7 @@
 import { useState } from 'react'
 import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query'
-import { api } from '@/lib/tauri'
-import type { BackupConfig, CreateBackupRequest, ScheduleFrequency, VM } from '@/lib/types'
+import { api, waitForJob } from '@/lib/tauri'
+import type { BackupChain, BackupConfig, BackupMode, BackupRun, BackupRunStatus, BackupTarget, CreateBackupRequest, RemoteStatus, ScheduleFrequency, VM } from '@/lib/types'
 import { Button } from '@/components/ui/button'
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
 import { Dialog, DialogContent, DialogDescription, DialogFooter, DialogHeader, DialogTitle } from '@/components/ui/dialog'
@@ -9,7 +9,114 @@ import { Input } from '@/components/ui/input'
 import { Label } from '@/components/ui/label'
 import { Badge } from '@/components/ui/badge'
 import { toast } from 'sonner'
-import { HardDrive, Plus, Trash2, PlayCircle, PauseCircle, Calendar, Camera } from 'lucide-react'
+import { RestoreBackupDialog } from './RestoreBackupDialog'
+import { VerifyBackupDialog } from './VerifyBackupDialog'
+import { DEFAULT_QUIESCE, QuiesceFields } from './QuiesceFields'
+import { HardDrive, Plus, Trash2, PlayCircle, PauseCircle, Calendar, Archive, History, Play, CloudUpload } from 'lucide-react'
+
+const DEFAULT_TARGET_DIR = '/var/lib/libvirt/backups'
+
+const runStatusVariants: Record<BackupRunStatus, 'default' | 'destructive' | 'secondary'> = {
+  completed: 'default',
+  failed: 'destructive',
+  pruned: 'secondary',
+}
+
+const remoteStatusVariants: Record<RemoteStatus, 'outline' | 'destructive' | 'secondary'> = {
+  uploaded: 'outline',
+  pending: 'secondary',
+  failed: 'destructive',
+}
+
+const modeLabels: Record<BackupMode, string> = {
+  full: 'Full',
+  incremental: 'Incremental',
+  differential: 'Differential',
+}
+
+const formatSize = (bytes: number) => {
+  const gb = bytes / 1024 ** 3
+  return gb >= 1 ? `${gb.toFixed(2)} GB` : `${(bytes / 1024 ** 2).toFixed(1)} MB`
+}
+
+const formatDuration = (secs: number) =>
+  secs >= 60 ? `${Math.floor(secs / 60)}m ${secs % 60}s` : `${secs}s`
+
+function BackupRunHistory({ configId }: { configId: string }) {
+  const { data: runs, isLoading } = useQuery<BackupRun[]>({
+    queryKey: ['backup-runs', configId],
+    queryFn: () => api.listBackupRuns(configId),
+  })
+
+  const { data: chains } = useQuery<BackupChain[]>({
+    queryKey: ['backup-chains', configId],
+    queryFn: () => api.listBackupChains(configId),
+  })
+
+  if (isLoading) {
+    return <div className="text-sm text-muted-foreground">Loading history...</div>
+  }
+
+  if (!runs || runs.length === 0) {
+    return <div className="text-sm text-muted-foreground">This backup has not run yet.</div>
+  }
+
+  return (
+    <div className="space-y-1 text-sm">
+      {chains && chains.length > 0 && (
+        <div className="pb-2 space-y-1">
+          <div className="font-medium">Restore points</div>
+          {chains.map((chain) => (
+            <div key={chain.id} className="text-muted-foreground">
+              {modeLabels[chain.mode]} chain from {new Date(chain.points[0].startedAt * 1000).toLocaleString()}:{' '}
+              {chain.points.length} point(s), {formatSize(chain.sizeBytes)}
+            </div>
+          ))}
+        </div>
+      )}
+      {runs.map((run) => (
+        <div key={run.id} className="flex items-center gap-2">
+          <Badge variant={runStatusVariants[run.status]} className="capitalize">
+            {run.status}
+          </Badge>
+          <Badge variant="outline">{modeLabels[run.kind]}</Badge>
+          <span className="text-muted-foreground">{new Date(run.startedAt * 1000).toLocaleString()}</span>
+          {run.status === 'failed' ? (
+            <span className="truncate">{run.error}</span>
+          ) : (
+            <span className="truncate">
+              {formatSize(run.sizeBytes)} in {formatDuration(run.durationSecs)} • {run.disks.length} disk(s)
+              {run.live ? ' • live' : ''}
+              {run.quiesced ? ' • quiesced' : ''}
+              {run.note && <span className="text-muted-foreground"> • {run.note}</span>}
+              {run.status === 'completed' && <span className="text-muted-foreground"> • {run.path}</span>}
+            </span>
+          )}
+          {run.remote && run.status !== 'failed' && (
+            <Badge
+              variant={remoteStatusVariants[run.remote.status]}
+              title={run.remote.error ?? run.remote.key}
+            >
+              {run.remote.status === 'uploaded'
+                ? `Uploaded ${formatSize(run.remote.storedBytes)}`
+                : run.remote.status === 'failed' ? 'Upload failed' : 'Upload pending'}
+            </Badge>
+          )}
+          {run.verification && run.status === 'completed' && (
+            <Badge
+              variant={run.verification.status === 'passed' ? 'outline' : 'destructive'}
+              title={run.verification.checks.filter((check) => !check.ok).map((check) => `${check.name}: ${check.detail}`).join('\n')}
+            >
+              {run.verification.status === 'passed' ? 'Verified' : 'Verification failed'}
+            </Badge>
+          )}
+          {run.status === 'completed' && <VerifyBackupDialog run={run} />}
+          {run.status === 'completed' && <RestoreBackupDialog run={run} />}
+        </div>
+      ))}
+    </div>
+  )
+}
 
 interface BackupManagerProps {
   vmId?: string // Optional: show backups for specific VM
@@ -18,6 +125,9 @@ interface BackupManagerProps {
 export function BackupManager({ vmId }: BackupManagerProps) {
   const queryClient = useQueryClient()
   const [isCreateDialogOpen, setIsCreateDialogOpen] = useState(false)
+  const [historyConfigId, setHistoryConfigId] = useState<string | null>(null)
+  const [runningIds, setRunningIds] = useState<string[]>([])
+  const [syncingIds, setSyncingIds] = useState<string[]>([])
   const [backupForm, setBackupForm] = useState<CreateBackupRequest>({
     name: '',
     vmId: vmId || '',
@@ -26,6 +136,11 @@ export function BackupManager({ vmId }: BackupManagerProps) {
     dayOfWeek: undefined,
     dayOfMonth: undefined,
     retentionCount: 7,
+    targetDir: DEFAULT_TARGET_DIR,
+    mode: 'full',
+    chainLength: 0,
+    quiesce: DEFAULT_QUIESCE,
+    targetId: null,
   })
 
   // Query for VMs (to populate dropdown)
@@ -33,6 +148,12 @@ export function BackupManager({ vmId }: BackupManagerProps) {
     queryKey: ['vms'],
     queryFn: () => api.getVms(),
   })
+  const localVms = vms?.filter((vm) => !vm.connectionId || vm.connectionId === 'local') ?? []
+
+  const { data: targets } = useQuery<BackupTarget[]>({
+    queryKey: ['backup-targets'],
+    queryFn: () => api.listBackupTargets(),
+  })
 
   // Query for backup configs
   const { data: backupConfigs, isLoading } = useQuery<BackupConfig[]>({
@@ -42,7 +163,10 @@ export function BackupManager({ vmId }: BackupManagerProps) {
 
   // Create backup config mutation
   const createMutation = useMutation({
-    mutationFn: (request: CreateBackupRequest) => api.createBackupConfig(request),
+    mutationFn: (request: CreateBackupRequest) => api.createBackupConfig({
+      ...request,
+      connectionId: vms?.find((v) => v.id === request.vmId)?.connectionId ?? null,
+    }),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['backupConfigs'] })
       queryClient.invalidateQueries({ queryKey: ['schedules'] })
@@ -82,6 +206,46 @@ export function BackupManager({ vmId }: BackupManagerProps) {
     },
   })
 
+  // Runs as a job; the history refreshes when it finishes
+  const runNow = async (backup: BackupConfig) => {
+    setRunningIds((ids) => [...ids, backup.id])
+    toast.info(`Backup "${backup.name}" started`)
+    try {
+      const run = await waitForJob<BackupRun>(await api.runBackupNow(backup.id))
+      toast.success(`Backup "${backup.name}" completed: ${formatSize(run.sizeBytes)} in ${formatDuration(run.durationSecs)}`)
+    } catch (error) {
+      toast.error(`Backup "${backup.name}" failed: ${(error as Error).message}`)
+    } finally {
+      setRunningIds((ids) => ids.filter((id) => id !== backup.id))
+      queryClient.invalidateQueries({ queryKey: ['backupConfigs'] })
+      queryClient.invalidateQueries({ queryKey: ['backup-runs', backup.id] })
+      queryClient.invalidateQueries({ queryKey: ['backup-chains', backup.id] })
+    }
+  }
+
+  // Uploads whatever is missing on the target; interrupted uploads resume where they stopped
+  const syncTarget = async (backup: BackupConfig) => {
+    setSyncingIds((ids) => [...ids, backup.id])
+    try {
+      const runs = await waitForJob<BackupRun[]>(await api.syncBackupTarget(backup.id))
+      const failed = runs.filter((run) => run.remote?.status === 'failed').length
+      if (failed > 0) {
+        toast.error(`${failed} restore point(s) of "${backup.name}" failed to upload`)
+      } else {
+        toast.success(`Backups of "${backup.name}" are up to date on the target`)
+      }
+    } catch (error) {
+      toast.error(`Upload of "${backup.name}" failed: ${(error as Error).message}`)
+    } finally {
+      setSyncingIds((ids) => ids.filter((id) => id !== backup.id))
+      queryClient.invalidateQueries({ queryKey: ['backup-runs', backup.id] })
+      queryClient.invalidateQueries({ queryKey: ['backup-target-usage'] })
+    }
+  }
+
+  const getTargetName = (targetId: string) =>
+    targets?.find((t) => t.id === targetId)?.name || targetId
+
   const resetForm = () => {
     setBackupForm({
       name: '',
@@ -91,6 +255,11 @@ export function BackupManager({ vmId }: BackupManagerProps) {
       dayOfWeek: undefined,
       dayOfMonth: undefined,
       retentionCount: 7,
+      targetDir: DEFAULT_TARGET_DIR,
+      mode: 'full',
+      chainLength: 0,
+      quiesce: DEFAULT_QUIESCE,
+      targetId: null,
     })
   }
 
@@ -103,6 +272,10 @@ export function BackupManager({ vmId }: BackupManagerProps) {
       toast.error('Retention count must be at least 1')
       return
     }
+    if (!backupForm.targetDir.startsWith('/')) {
+      toast.error('Backup target must be an absolute path')
+      return
+    }
     createMutation.mutate(backupForm)
   }
 
@@ -142,7 +315,7 @@ export function BackupManager({ vmId }: BackupManagerProps) {
       <div className="flex justify-between items-center">
         <div>
           <h2 className="text-2xl font-bold">Backup Schedules</h2>
-          <p className="text-muted-foreground">Automated snapshot backups with retention management</p>
+          <p className="text-muted-foreground">Full VM backups of disks, configuration and firmware state with retention management</p>
         </div>
         <Button onClick={() => setIsCreateDialogOpen(true)}>
           <Plus className="mr-2 h-4 w-4" />
@@ -155,7 +328,7 @@ export function BackupManager({ vmId }: BackupManagerProps) {
           <CardContent className="flex flex-col items-center justify-center p-8">
             <HardDrive className="h-12 w-12 text-muted-foreground mb-4" />
             <p className="text-muted-foreground text-center">
-              No backup schedules configured. Create a schedule to automate VM snapshots.
+              No backup schedules configured. Create a schedule to automate VM backups.
             </p>
           </CardContent>
         </Card>
@@ -167,12 +340,13 @@ export function BackupManager({ vmId }: BackupManagerProps) {
                 <div className="flex justify-between items-start">
                   <div className="flex-1">
                     <CardTitle className="text-lg flex items-center gap-2">
-                      <Camera className="h-4 w-4" />
+                      <Archive className="h-4 w-4" />
                       {backup.name}
                     </CardTitle>
                     <CardDescription className="mt-1">
                       {!vmId && `VM: ${getVmName(backup.vmId)} • `}
-                      Automated snapshot backups
+                      Backs up to {backup.targetDir}
+                      {backup.targetId && ` • copies to ${getTargetName(backup.targetId)}`}
                     </CardDescription>
                   </div>
                   <Badge variant={backup.enabled ? 'default' : 'secondary'}>
@@ -185,7 +359,9 @@ export function BackupManager({ vmId }: BackupManagerProps) {
                   <div className="grid grid-cols-3 gap-4 text-sm">
                     <div>
                       <div className="text-muted-foreground">Retention</div>
-                      <div className="font-medium">{backup.retentionCount} backups</div>
+                      <div className="font-medium">
+                        {backup.retentionCount} restore points • {modeLabels[backup.mode]}
+                      </div>
                     </div>
                     <div>
                       <div className="text-muted-foreground">Total Backups</div>
@@ -204,13 +380,41 @@ export function BackupManager({ vmId }: BackupManagerProps) {
                     </div>
                   )}
 
-                  <div className="bg-muted p-3 rounded-md text-sm">
-                    <p className="text-muted-foreground">
-                      This backup is scheduled via the Schedules system. View the full schedule details in the Schedules page.
-                    </p>
-                  </div>
+                  {historyConfigId === backup.id && (
+                    <div className="bg-muted p-3 rounded-md">
+                      <BackupRunHistory configId={backup.id} />
+                    </div>
+                  )}
 
                   <div className="flex gap-2 pt-2">
+                    <Button
+                      size="sm"
+                      variant="outline"
+                      onClick={() => runNow(backup)}
+                      disabled={runningIds.includes(backup.id)}
+                    >
+                      <Play className="mr-1 h-3 w-3" />
+                      {runningIds.includes(backup.id) ? 'Backing up...' : 'Run Now'}
+                    </Button>
+                    {backup.targetId && (
+                      <Button
+                        size="sm"
+                        variant="outline"
+                        onClick={() => syncTarget(backup)}
+                        disabled={syncingIds.includes(backup.id)}
+                      >
+                        <CloudUpload className="mr-1 h-3 w-3" />
+                        {syncingIds.includes(backup.id) ? 'Uploading...' : 'Upload'}
+                      </Button>
+                    )}
+                    <Button
+                      size="sm"
+                      variant="outline"
+                      onClick={() => setHistoryConfigId(historyConfigId === backup.id ? null : backup.id)}
+                    >
+                      <History className="mr-1 h-3 w-3" />
+                      History
+                    </Button>
                     <Button
                       size="sm"
                       variant={backup.enabled ? 'outline' : 'default'}
@@ -249,7 +453,7 @@ export function BackupManager({ vmId }: BackupManagerProps) {
           <DialogHeader>
             <DialogTitle>Create Backup Schedule</DialogTitle>
             <DialogDescription>
-              Set up automated snapshot backups with retention management
+              Set up automated full VM backups with retention management
             </DialogDescription>
           </DialogHeader>
           <div className="space-y-4">
@@ -277,7 +481,8 @@ export function BackupManager({ vmId }: BackupManagerProps) {
                   className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background"
                 >
                   <option value="">Select a VM...</option>
-                  {vms?.map((vm) => (
+                  {/* Backup sets are written on this host, so only its VMs can be backed up */}
+                  {localVms.map((vm) => (
                     <option key={vm.id} value={vm.id}>
                       {vm.name}
                     </option>
@@ -366,6 +571,92 @@ export function BackupManager({ vmId }: BackupManagerProps) {
               </div>
             )}
 
+            <div>
+              <Label htmlFor="target-dir">Backup Target Directory</Label>
+              <Input
+                id="target-dir"
+                value={backupForm.targetDir}
+                onChange={(e) =>
+                  setBackupForm({ ...backupForm, targetDir: e.target.value })
+                }
+                placeholder={DEFAULT_TARGET_DIR}
+              />
+              <p className="text-xs text-muted-foreground mt-1">
+                Local directory on the host; QEMU must be able to write to it for running VMs
+              </p>
+            </div>
+
+            <div>
+              <Label htmlFor="backup-target">Copy To Backup Target</Label>
+              <select
+                id="backup-target"
+                value={backupForm.targetId ?? ''}
+                onChange={(e) =>
+                  setBackupForm({ ...backupForm, targetId: e.target.value || null })
+                }
+                className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background"
+              >
+                <option value="">None (keep backups on the host only)</option>
+                {targets?.map((target) => (
+                  <option key={target.id} value={target.id}>
+                    {target.name} ({target.config.type})
+                  </option>
+                ))}
+              </select>
+              <p className="text-xs text-muted-foreground mt-1">
+                Each restore point is uploaded after the run and deleted from the target when it is pruned
+              </p>
+            </div>
+
+            <div className="grid grid-cols-2 gap-4">
+              <div>
+                <Label htmlFor="backup-mode">Backup Mode</Label>
+                <select
+                  id="backup-mode"
+                  value={backupForm.mode}
+                  onChange={(e) =>
+                    setBackupForm({ ...backupForm, mode: e.target.value as BackupMode })
+                  }
+                  className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background"
+                >
+                  <option value="full">Full (copy all disks every run)</option>
+                  <option value="incremental">Incremental (changes since the last run)</option>
+                  <option value="differential">Differential (changes since the last full)</option>
+                </select>
+              </div>
+
+              {backupForm.mode !== 'full' && (
+                <div>
+                  <Label htmlFor="chain-length">New Full Backup After</Label>
+                  <Input
+                    id="chain-length"
+                    type="number"
+                    min="0"
+                    value={backupForm.chainLength}
+                    onChange={(e) =>
+                      setBackupForm({ ...backupForm, chainLength: parseInt(e.target.value) || 0 })
+                    }
+                  />
+                  <p className="text-xs text-muted-foreground mt-1">
+                    {modeLabels[backupForm.mode]} runs per chain; 0 for no limit
+                  </p>
+                </div>
+              )}
+            </div>
+
+            {backupForm.mode !== 'full' && (
+              <p className="text-xs text-muted-foreground">
+                Changed blocks are tracked with libvirt checkpoints and need qcow2 disks and a running VM; otherwise
+                a full backup starts a new chain.
+              </p>
+            )}
+
+            <QuiesceFields
+              id="backup"
+              value={backupForm.quiesce ?? DEFAULT_QUIESCE}
+              onChange={(quiesce) => setBackupForm({ ...backupForm, quiesce })}
+            />
+
             <div>
               <Label htmlFor="retention-count">Retention Count</Label>
               <Input
@@ -382,14 +673,14 @@ export function BackupManager({ vmId }: BackupManagerProps) {
                 }
               />
               <p className="text-xs text-muted-foreground mt-1">
-                Number of backup snapshots to retain (older backups will be automatically removed)
+                Number of restore points to retain; older points are merged into the next one so every chain stays restorable
               </p>
             </div>
 
             <div className="bg-muted p-3 rounded-md text-sm">
               <p className="font-medium mb-1">Backup Schedule Preview:</p>
               <p>
-                Snapshots will be created <strong>{getFrequencyLabel(backupForm.frequency)}</strong> at{' '}
+                Backups will run <strong>{getFrequencyLabel(backupForm.frequency)}</strong> at{' '}
                 <strong>{backupForm.scheduledTime}</strong>, keeping the most recent{' '}
                 <strong>{backupForm.retentionCount}</strong> backup(s).
               </p>
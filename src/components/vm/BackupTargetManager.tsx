@@ -0,0 +1,529 @@
+import { useState } from 'react'
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query'
+import { api, waitForJob } from '@/lib/tauri'
+import type { BackupTarget, CatalogImport, CreateBackupTargetRequest, TargetConfig, TargetUsage } from '@/lib/types'
+import { Button } from '@/components/ui/button'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Dialog, DialogContent, DialogDescription, DialogFooter, DialogHeader, DialogTitle } from '@/components/ui/dialog'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { Badge } from '@/components/ui/badge'
+import { Checkbox } from '@/components/ui/checkbox'
+import { toast } from 'sonner'
+import { Cloud, FolderOpen, Server, Plus, Trash2, Activity, Lock, Download } from 'lucide-react'
+
+type TargetType = TargetConfig['type']
+
+const targetLabels: Record<TargetType, string> = {
+  local: 'Local / NFS',
+  sftp: 'SFTP',
+  s3: 'S3 / MinIO',
+}
+
+const defaultConfig = (type: TargetType): TargetConfig => {
+  switch (type) {
+    case 'local':
+      return { type: 'local', path: '', requireMount: true }
+    case 'sftp':
+      return { type: 'sftp', host: '', port: 22, username: '', sshKeyPath: null, path: '' }
+    case 's3':
+      return {
+        type: 's3',
+        endpoint: '',
+        region: 'us-east-1',
+        bucket: '',
+        prefix: '',
+        accessKey: '',
+        secretKey: '',
+        pathStyle: true,
+      }
+  }
+}
+
+const emptyRequest = (): CreateBackupTargetRequest => ({
+  name: '',
+  config: defaultConfig('local'),
+  compression: true,
+  encryptionPassphrase: null,
+  chunkSizeMb: 64,
+})
+
+const selectClassName =
+  'flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background'
+
+const formatSize = (bytes: number) => {
+  const gb = bytes / 1024 ** 3
+  return gb >= 1 ? `${gb.toFixed(2)} GB` : `${(bytes / 1024 ** 2).toFixed(1)} MB`
+}
+
+function TargetUsageSummary({ targetId }: { targetId: string }) {
+  const { data: usage, isLoading, error } = useQuery<TargetUsage>({
+    queryKey: ['backup-target-usage', targetId],
+    queryFn: () => api.getBackupTargetUsage(targetId),
+  })
+
+  if (isLoading) {
+    return <span>Loading usage...</span>
+  }
+  if (error || !usage) {
+    return <span>Usage unavailable</span>
+  }
+
+  return (
+    <span>
+      {usage.sets} set(s), {formatSize(usage.storedBytes)} stored
+      {usage.rawBytes > 0 && ` (${Math.round((usage.storedBytes / usage.rawBytes) * 100)}% of ${formatSize(usage.rawBytes)})`}
+      {usage.pendingSets > 0 && ` • ${usage.pendingSets} waiting to upload`}
+      {usage.availableBytes != null && ` • ${formatSize(usage.availableBytes)} free`}
+    </span>
+  )
+}
+
+export function BackupTargetManager() {
+  const queryClient = useQueryClient()
+  const [isDialogOpen, setIsDialogOpen] = useState(false)
+  const [form, setForm] = useState<CreateBackupTargetRequest>(emptyRequest())
+
+  const { data: targets, isLoading } = useQuery<BackupTarget[]>({
+    queryKey: ['backup-targets'],
+    queryFn: () => api.listBackupTargets(),
+  })
+
+  const createMutation = useMutation({
+    mutationFn: (request: CreateBackupTargetRequest) => api.createBackupTarget(request),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['backup-targets'] })
+      toast.success('Backup target added')
+      setIsDialogOpen(false)
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to add backup target: ${error.message}`)
+    },
+  })
+
+  const deleteMutation = useMutation({
+    mutationFn: (id: string) => api.deleteBackupTarget(id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['backup-targets'] })
+      toast.success('Backup target deleted')
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to delete backup target: ${error.message}`)
+    },
+  })
+
+  const checkMutation = useMutation({
+    mutationFn: (id: string) => api.checkBackupTarget(id),
+    onSuccess: (check) => {
+      queryClient.invalidateQueries({ queryKey: ['backup-targets'] })
+      if (check.ok) {
+        toast.success(`Target reachable (${check.latencyMs} ms)`)
+      } else {
+        toast.error(`Target check failed: ${check.error}`)
+      }
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to check target: ${error.message}`)
+    },
+  })
+
+  const importMutation = useMutation({
+    mutationFn: async (id: string) => waitForJob<CatalogImport>(await api.importBackupCatalog(id)),
+    onSuccess: (result) => {
+      queryClient.invalidateQueries({ queryKey: ['backupConfigs'] })
+      queryClient.invalidateQueries({ queryKey: ['schedules'] })
+      queryClient.invalidateQueries({ queryKey: ['backup-runs'] })
+      queryClient.invalidateQueries({ queryKey: ['backup-target-usage'] })
+      const created = result.createdConfigs > 0 ? `, ${result.createdConfigs} config(s) recreated disabled` : ''
+      toast.success(`Imported ${result.importedRuns} restore point(s) from ${result.sets} set(s)${created}`)
+      if (result.skippedSets > 0) {
+        toast.error(`${result.skippedSets} set(s) could not be read`)
+      }
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to import catalog: ${error.message}`)
+    },
+  })
+
+  const openCreate = () => {
+    setForm(emptyRequest())
+    setIsDialogOpen(true)
+  }
+
+  const handleCreate = () => {
+    if (!form.name.trim()) {
+      toast.error('Please enter a target name')
+      return
+    }
+    if (form.chunkSizeMb < 1) {
+      toast.error('Chunk size must be at least 1 MB')
+      return
+    }
+    createMutation.mutate(form)
+  }
+
+  const handleDelete = (target: BackupTarget) => {
+    if (confirm(`Delete backup target "${target.name}"? Sets already uploaded stay on the target.`)) {
+      deleteMutation.mutate(target.id)
+    }
+  }
+
+  const setConfig = (config: TargetConfig) => setForm({ ...form, config })
+
+  const getTargetIcon = (type: TargetType) => {
+    switch (type) {
+      case 'local':
+        return <FolderOpen className="h-4 w-4" />
+      case 'sftp':
+        return <Server className="h-4 w-4" />
+      case 's3':
+        return <Cloud className="h-4 w-4" />
+    }
+  }
+
+  const describeTarget = (config: TargetConfig) => {
+    switch (config.type) {
+      case 'local':
+        return config.path + (config.requireMount ? ' (must be mounted)' : '')
+      case 'sftp':
+        return `${config.username}@${config.host}:${config.port}${config.path}`
+      case 's3':
+        return `${config.endpoint}/${config.bucket}${config.prefix ? `/${config.prefix}` : ''}`
+    }
+  }
+
+  const config = form.config
+
+  return (
+    <div className="space-y-4">
+      <div className="flex justify-between items-center">
+        <div>
+          <h2 className="text-2xl font-bold">Backup Targets</h2>
+          <p className="text-muted-foreground">Off-host copies of restore points on NFS, SFTP or S3-compatible storage</p>
+        </div>
+        <Button onClick={openCreate}>
+          <Plus className="mr-2 h-4 w-4" />
+          Add Target
+        </Button>
+      </div>
+
+      {isLoading ? (
+        <div className="text-muted-foreground">Loading targets...</div>
+      ) : !targets || targets.length === 0 ? (
+        <Card>
+          <CardContent className="py-8 text-center text-muted-foreground">
+            No backup targets configured. Backups are kept on the host only.
+          </CardContent>
+        </Card>
+      ) : (
+        <div className="grid gap-4">
+          {targets.map((target) => (
+            <Card key={target.id}>
+              <CardHeader>
+                <div className="flex justify-between items-start">
+                  <div className="flex-1 min-w-0">
+                    <CardTitle className="text-lg flex items-center gap-2">
+                      {getTargetIcon(target.config.type)}
+                      {target.name}
+                    </CardTitle>
+                    <CardDescription className="mt-1 truncate">
+                      {targetLabels[target.config.type]} • {describeTarget(target.config)}
+                    </CardDescription>
+                  </div>
+                  <div className="flex items-center gap-2">
+                    {target.compression && <Badge variant="outline">zstd</Badge>}
+                    {target.encryptionPassphrase && (
+                      <Badge variant="outline">
+                        <Lock className="mr-1 h-3 w-3" />
+                        Encrypted
+                      </Badge>
+                    )}
+                    {target.lastCheck && (
+                      <Badge
+                        variant={target.lastCheck.ok ? 'default' : 'destructive'}
+                        title={target.lastCheck.error ?? undefined}
+                      >
+                        {target.lastCheck.ok ? 'Reachable' : 'Unreachable'}
+                      </Badge>
+                    )}
+                  </div>
+                </div>
+              </CardHeader>
+              <CardContent>
+                <div className="flex items-center justify-between">
+                  <div className="text-sm text-muted-foreground">
+                    <TargetUsageSummary targetId={target.id} />
+                    {target.lastCheck && (
+                      <div className="text-xs">
+                        Last checked {new Date(target.lastCheck.checkedAt * 1000).toLocaleString()}
+                      </div>
+                    )}
+                  </div>
+                  <div className="flex gap-2">
+                    <Button
+                      size="sm"
+                      variant="outline"
+                      onClick={() => checkMutation.mutate(target.id)}
+                      disabled={checkMutation.isPending}
+                    >
+                      <Activity className="mr-1 h-3 w-3" />
+                      Check
+                    </Button>
+                    <Button
+                      size="sm"
+                      variant="outline"
+                      onClick={() => importMutation.mutate(target.id)}
+                      disabled={importMutation.isPending}
+                      title="Add the restore points stored on this target to this host's catalog"
+                    >
+                      <Download className="mr-1 h-3 w-3" />
+                      Import
+                    </Button>
+                    <Button size="sm" variant="outline" onClick={() => handleDelete(target)}>
+                      <Trash2 className="h-3 w-3" />
+                    </Button>
+                  </div>
+                </div>
+              </CardContent>
+            </Card>
+          ))}
+        </div>
+      )}
+
+      <Dialog open={isDialogOpen} onOpenChange={setIsDialogOpen}>
+        <DialogContent className="max-w-2xl">
+          <DialogHeader>
+            <DialogTitle>Add Backup Target</DialogTitle>
+            <DialogDescription>
+              Restore points are uploaded in chunks; an interrupted upload resumes at the first missing chunk
+            </DialogDescription>
+          </DialogHeader>
+          <div className="space-y-4">
+            <div className="grid grid-cols-2 gap-4">
+              <div>
+                <Label htmlFor="target-name">Name</Label>
+                <Input
+                  id="target-name"
+                  value={form.name}
+                  onChange={(e) => setForm({ ...form, name: e.target.value })}
+                  placeholder="e.g., Offsite MinIO"
+                />
+              </div>
+              <div>
+                <Label htmlFor="target-type">Type</Label>
+                <select
+                  id="target-type"
+                  value={config.type}
+                  onChange={(e) => setConfig(defaultConfig(e.target.value as TargetType))}
+                  className={selectClassName}
+                >
+                  {(Object.keys(targetLabels) as TargetType[]).map((type) => (
+                    <option key={type} value={type}>{targetLabels[type]}</option>
+                  ))}
+                </select>
+              </div>
+            </div>
+
+            {config.type === 'local' && (
+              <div className="space-y-2">
+                <div>
+                  <Label htmlFor="local-path">Directory</Label>
+                  <Input
+                    id="local-path"
+                    className="font-mono"
+                    value={config.path}
+                    onChange={(e) => setConfig({ ...config, path: e.target.value })}
+                    placeholder="/mnt/backup-nfs"
+                  />
+                </div>
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="local-require-mount"
+                    checked={config.requireMount}
+                    onCheckedChange={(checked) => setConfig({ ...config, requireMount: checked === true })}
+                  />
+                  <Label htmlFor="local-require-mount" className="text-sm cursor-pointer">
+                    Only write when the directory is a mountpoint
+                  </Label>
+                </div>
+              </div>
+            )}
+
+            {config.type === 'sftp' && (
+              <div className="space-y-4">
+                <div className="grid grid-cols-3 gap-4">
+                  <div className="col-span-2">
+                    <Label htmlFor="sftp-host">Host</Label>
+                    <Input
+                      id="sftp-host"
+                      value={config.host}
+                      onChange={(e) => setConfig({ ...config, host: e.target.value })}
+                    />
+                  </div>
+                  <div>
+                    <Label htmlFor="sftp-port">Port</Label>
+                    <Input
+                      id="sftp-port"
+                      type="number"
+                      value={config.port}
+                      onChange={(e) => setConfig({ ...config, port: parseInt(e.target.value) || 22 })}
+                    />
+                  </div>
+                </div>
+                <div className="grid grid-cols-2 gap-4">
+                  <div>
+                    <Label htmlFor="sftp-username">Username</Label>
+                    <Input
+                      id="sftp-username"
+                      value={config.username}
+                      onChange={(e) => setConfig({ ...config, username: e.target.value })}
+                    />
+                  </div>
+                  <div>
+                    <Label htmlFor="sftp-key">SSH Key (optional)</Label>
+                    <Input
+                      id="sftp-key"
+                      className="font-mono"
+                      value={config.sshKeyPath ?? ''}
+                      onChange={(e) => setConfig({ ...config, sshKeyPath: e.target.value || null })}
+                      placeholder="~/.ssh/id_ed25519"
+                    />
+                  </div>
+                </div>
+                <div>
+                  <Label htmlFor="sftp-path">Remote Directory</Label>
+                  <Input
+                    id="sftp-path"
+                    className="font-mono"
+                    value={config.path}
+                    onChange={(e) => setConfig({ ...config, path: e.target.value })}
+                    placeholder="/srv/backups"
+                  />
+                </div>
+              </div>
+            )}
+
+            {config.type === 's3' && (
+              <div className="space-y-4">
+                <div className="grid grid-cols-3 gap-4">
+                  <div className="col-span-2">
+                    <Label htmlFor="s3-endpoint">Endpoint</Label>
+                    <Input
+                      id="s3-endpoint"
+                      value={config.endpoint}
+                      onChange={(e) => setConfig({ ...config, endpoint: e.target.value })}
+                      placeholder="http://minio.local:9000"
+                    />
+                  </div>
+                  <div>
+                    <Label htmlFor="s3-region">Region</Label>
+                    <Input
+                      id="s3-region"
+                      value={config.region}
+                      onChange={(e) => setConfig({ ...config, region: e.target.value })}
+                    />
+                  </div>
+                </div>
+                <div className="grid grid-cols-2 gap-4">
+                  <div>
+                    <Label htmlFor="s3-bucket">Bucket</Label>
+                    <Input
+                      id="s3-bucket"
+                      value={config.bucket}
+                      onChange={(e) => setConfig({ ...config, bucket: e.target.value })}
+                    />
+                  </div>
+                  <div>
+                    <Label htmlFor="s3-prefix">Key Prefix (optional)</Label>
+                    <Input
+                      id="s3-prefix"
+                      value={config.prefix}
+                      onChange={(e) => setConfig({ ...config, prefix: e.target.value })}
+                      placeholder="kvm-backups"
+                    />
+                  </div>
+                </div>
+                <div className="grid grid-cols-2 gap-4">
+                  <div>
+                    <Label htmlFor="s3-access-key">Access Key</Label>
+                    <Input
+                      id="s3-access-key"
+                      value={config.accessKey}
+                      onChange={(e) => setConfig({ ...config, accessKey: e.target.value })}
+                    />
+                  </div>
+                  <div>
+                    <Label htmlFor="s3-secret-key">Secret Key</Label>
+                    <Input
+                      id="s3-secret-key"
+                      type="password"
+                      value={config.secretKey}
+                      onChange={(e) => setConfig({ ...config, secretKey: e.target.value })}
+                    />
+                  </div>
+                </div>
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="s3-path-style"
+                    checked={config.pathStyle}
+                    onCheckedChange={(checked) => setConfig({ ...config, pathStyle: checked === true })}
+                  />
+                  <Label htmlFor="s3-path-style" className="text-sm cursor-pointer">
+                    Path-style addressing (required by MinIO)
+                  </Label>
+                </div>
+              </div>
+            )}
+
+            <div className="grid grid-cols-2 gap-4">
+              <div>
+                <Label htmlFor="target-passphrase">Encryption Passphrase (optional)</Label>
+                <Input
+                  id="target-passphrase"
+                  type="password"
+                  value={form.encryptionPassphrase ?? ''}
+                  onChange={(e) => setForm({ ...form, encryptionPassphrase: e.target.value || null })}
+                />
+              </div>
+              <div>
+                <Label htmlFor="target-chunk-size">Chunk Size (MB)</Label>
+                <Input
+                  id="target-chunk-size"
+                  type="number"
+                  min="1"
+                  value={form.chunkSizeMb}
+                  onChange={(e) => setForm({ ...form, chunkSizeMb: parseInt(e.target.value) || 0 })}
+                />
+              </div>
+            </div>
+            {form.encryptionPassphrase && (
+              <p className="text-xs text-muted-foreground">
+                Chunks are encrypted with AES-256-GCM before they leave the host. Keep the passphrase elsewhere too;
+                sets can't be restored without it.
+              </p>
+            )}
+
+            <div className="flex items-center space-x-2">
+              <Checkbox
+                id="target-compression"
+                checked={form.compression}
+                onCheckedChange={(checked) => setForm({ ...form, compression: checked === true })}
+              />
+              <Label htmlFor="target-compression" className="text-sm cursor-pointer">
+                Compress chunks with zstd
+              </Label>
+            </div>
+          </div>
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setIsDialogOpen(false)}>
+              Cancel
+            </Button>
+            <Button onClick={handleCreate} disabled={createMutation.isPending}>
+              {createMutation.isPending ? 'Adding...' : 'Add Target'}
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+    </div>
+  )
+}
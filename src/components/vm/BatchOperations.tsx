@@ -1,7 +1,7 @@
 import { useState } from 'react'
 import { useMutation, useQueryClient } from '@tanstack/react-query'
-import { api } from '@/lib/tauri'
-import type { BatchOperationResult } from '@/lib/types'
+import { api, batchPerConnection } from '@/lib/tauri'
+import type { BatchOperationResult, VM } from '@/lib/types'
 import { Button } from '@/components/ui/button'
 import { Dialog, DialogContent, DialogDescription, DialogFooter, DialogHeader, DialogTitle } from '@/components/ui/dialog'
 import { Badge } from '@/components/ui/badge'
@@ -9,11 +9,11 @@ import { toast } from 'sonner'
 import { PlayCircle, StopCircle, RotateCw, CheckCircle, XCircle } from 'lucide-react'
 
 interface BatchOperationsProps {
-  selectedVmIds: string[]
+  selectedVms: VM[]
   onClearSelection: () => void
 }
 
-export function BatchOperations({ selectedVmIds, onClearSelection }: BatchOperationsProps) {
+export function BatchOperations({ selectedVms, onClearSelection }: BatchOperationsProps) {
   const queryClient = useQueryClient()
   const [showResults, setShowResults] = useState(false)
   const [results, setResults] = useState<BatchOperationResult[]>([])
@@ -21,7 +21,7 @@ export function BatchOperations({ selectedVmIds, onClearSelection }: BatchOperat
 
   // Batch start mutation
   const batchStartMutation = useMutation({
-    mutationFn: (vmIds: string[]) => api.batchStartVms(vmIds),
+    mutationFn: (vms: VM[]) => batchPerConnection(vms, api.batchStartVms),
     onSuccess: (data) => {
       setResults(data)
       setOperationType('Start')
@@ -37,8 +37,8 @@ export function BatchOperations({ selectedVmIds, onClearSelection }: BatchOperat
 
   // Batch stop mutation
   const batchStopMutation = useMutation({
-    mutationFn: ({ vmIds, force }: { vmIds: string[]; force: boolean }) =>
-      api.batchStopVms(vmIds, force),
+    mutationFn: ({ vms, force }: { vms: VM[]; force: boolean }) =>
+      batchPerConnection(vms, (vmIds, connectionId) => api.batchStopVms(vmIds, force, connectionId)),
     onSuccess: (data) => {
       setResults(data)
       setOperationType('Stop')
@@ -54,7 +54,7 @@ export function BatchOperations({ selectedVmIds, onClearSelection }: BatchOperat
 
   // Batch reboot mutation
   const batchRebootMutation = useMutation({
-    mutationFn: (vmIds: string[]) => api.batchRebootVms(vmIds),
+    mutationFn: (vms: VM[]) => batchPerConnection(vms, api.batchRebootVms),
     onSuccess: (data) => {
       setResults(data)
       setOperationType('Reboot')
@@ -69,33 +69,33 @@ export function BatchOperations({ selectedVmIds, onClearSelection }: BatchOperat
   })
 
   const handleBatchStart = () => {
-    if (selectedVmIds.length === 0) {
+    if (selectedVms.length === 0) {
       toast.error('No VMs selected')
       return
     }
-    if (confirm(`Are you sure you want to start ${selectedVmIds.length} VMs?`)) {
-      batchStartMutation.mutate(selectedVmIds)
+    if (confirm(`Are you sure you want to start ${selectedVms.length} VMs?`)) {
+      batchStartMutation.mutate(selectedVms)
     }
   }
 
   const handleBatchStop = (force: boolean = false) => {
-    if (selectedVmIds.length === 0) {
+    if (selectedVms.length === 0) {
       toast.error('No VMs selected')
       return
     }
     const action = force ? 'force stop' : 'shutdown'
-    if (confirm(`Are you sure you want to ${action} ${selectedVmIds.length} VMs?`)) {
-      batchStopMutation.mutate({ vmIds: selectedVmIds, force })
+    if (confirm(`Are you sure you want to ${action} ${selectedVms.length} VMs?`)) {
+      batchStopMutation.mutate({ vms: selectedVms, force })
     }
   }
 
   const handleBatchReboot = () => {
-    if (selectedVmIds.length === 0) {
+    if (selectedVms.length === 0) {
       toast.error('No VMs selected')
       return
     }
-    if (confirm(`Are you sure you want to reboot ${selectedVmIds.length} VMs?`)) {
-      batchRebootMutation.mutate(selectedVmIds)
+    if (confirm(`Are you sure you want to reboot ${selectedVms.length} VMs?`)) {
+      batchRebootMutation.mutate(selectedVms)
     }
   }
 
@@ -110,7 +110,7 @@ export function BatchOperations({ selectedVmIds, onClearSelection }: BatchOperat
     batchStopMutation.isPending ||
     batchRebootMutation.isPending
 
-  if (selectedVmIds.length === 0) {
+  if (selectedVms.length === 0) {
     return null
   }
 
@@ -119,7 +119,7 @@ export function BatchOperations({ selectedVmIds, onClearSelection }: BatchOperat
       <div className="flex-shrink-0 px-4 py-2 border-b border-primary/20 bg-primary/5">
         <div className="flex items-center gap-3">
           <span className="text-sm font-medium">
-            {selectedVmIds.length} VM{selectedVmIds.length !== 1 ? 's' : ''} selected
+            {selectedVms.length} VM{selectedVms.length !== 1 ? 's' : ''} selected
           </span>
           <button
             onClick={onClearSelection}
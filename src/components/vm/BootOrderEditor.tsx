@@ -9,17 +9,18 @@ import { GripVertical, Save } from 'lucide-react'
 
 interface BootOrderEditorProps {
   vmId: string
+  connectionId?: string | null
   vmName: string
   currentBootOrder: string[]
 }
 
-export function BootOrderEditor({ vmId, vmName, currentBootOrder }: BootOrderEditorProps) {
+export function BootOrderEditor({ vmId, connectionId, vmName, currentBootOrder }: BootOrderEditorProps) {
   const queryClient = useQueryClient()
   const [bootOrder, setBootOrder] = useState<string[]>(currentBootOrder.length > 0 ? currentBootOrder : ['hd'])
   const [hasChanges, setHasChanges] = useState(false)
 
   const updateMutation = useMutation({
-    mutationFn: (newOrder: string[]) => api.updateVmBootOrder(vmId, newOrder),
+    mutationFn: (newOrder: string[]) => api.updateVmBootOrder(vmId, newOrder, connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
       toast.success('Boot order updated successfully')
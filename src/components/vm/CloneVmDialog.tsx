@@ -1,6 +1,6 @@
 import { useState, useEffect } from 'react'
 import { useMutation, useQueryClient, useQuery } from '@tanstack/react-query'
-import { api } from '@/lib/tauri'
+import { api, waitForJob } from '@/lib/tauri'
 import { Button } from '@/components/ui/button'
 import { Input } from '@/components/ui/input'
 import { Label } from '@/components/ui/label'
@@ -34,11 +34,12 @@ import { Alert, AlertDescription } from '@/components/ui/alert'
 
 interface CloneVmDialogProps {
   vmId: string
+  connectionId?: string | null
   vmName: string
   trigger?: React.ReactNode
 }
 
-export function CloneVmDialog({ vmId, vmName, trigger }: CloneVmDialogProps) {
+export function CloneVmDialog({ vmId, connectionId, vmName, trigger }: CloneVmDialogProps) {
   const [open, setOpen] = useState(false)
   const [newName, setNewName] = useState(`${vmName}-clone`)
   const [cloneDisks, setCloneDisks] = useState(true)
@@ -67,7 +68,7 @@ export function CloneVmDialog({ vmId, vmName, trigger }: CloneVmDialogProps) {
   }, [open, vmName])
 
   const cloneMutation = useMutation({
-    mutationFn: () => {
+    mutationFn: async () => {
       const config: CloneConfig = {
         newName,
         cloneDisks,
@@ -75,7 +76,7 @@ export function CloneVmDialog({ vmId, vmName, trigger }: CloneVmDialogProps) {
         targetPool: targetPool || undefined,
         description: description || undefined,
       }
-      return api.cloneVmWithOptions(vmId, config)
+      return waitForJob<string>(await api.cloneVmWithOptions(vmId, config, connectionId ?? undefined))
     },
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -28,7 +28,7 @@ export function DeleteVmDialog({ vm, open, onOpenChange }: DeleteVmDialogProps)
   const queryClient = useQueryClient()
 
   const deleteMutation = useMutation({
-    mutationFn: () => api.deleteVm(vm!.id, deleteDisks, deleteSnapshots),
+    mutationFn: () => api.deleteVm(vm!.id, deleteDisks, deleteSnapshots, vm!.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm?.name} deleted`)
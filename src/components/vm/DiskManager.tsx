@@ -30,11 +30,12 @@ import type { DiskDevice } from '@/lib/types'
 
 interface DiskManagerProps {
   vmId: string
+  connectionId?: string | null
   vmName: string
   disks?: DiskDevice[]
 }
 
-export function DiskManager({ vmId, vmName, disks = [] }: DiskManagerProps) {
+export function DiskManager({ vmId, connectionId, vmName, disks = [] }: DiskManagerProps) {
   const queryClient = useQueryClient()
   const [showAttachDialog, setShowAttachDialog] = useState(false)
   const [diskPath, setDiskPath] = useState('')
@@ -45,7 +46,7 @@ export function DiskManager({ vmId, vmName, disks = [] }: DiskManagerProps) {
   // Attach disk mutation
   const attachMutation = useMutation({
     mutationFn: ({ diskPath, deviceTarget, busType }: { diskPath: string; deviceTarget: string; busType: string }) =>
-      api.attachDisk(vmId, diskPath, deviceTarget, busType),
+      api.attachDisk(vmId, diskPath, deviceTarget, busType, connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
       toast.success(`Disk attached to ${vmName}`)
@@ -66,7 +67,7 @@ export function DiskManager({ vmId, vmName, disks = [] }: DiskManagerProps) {
 
   // Detach disk mutation
   const detachMutation = useMutation({
-    mutationFn: (deviceTarget: string) => api.detachDisk(vmId, deviceTarget),
+    mutationFn: (deviceTarget: string) => api.detachDisk(vmId, deviceTarget, connectionId ?? undefined),
     onSuccess: (_data, deviceTarget) => {
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
       toast.success(`Disk ${deviceTarget} detached from ${vmName}`)
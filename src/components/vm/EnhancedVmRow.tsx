@@ -2,6 +2,7 @@ import { useState, useEffect } from 'react'
 import { useNavigate } from 'react-router-dom'
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
 import { api } from '@/lib/tauri'
+import { vmDetailsPath } from '@/lib/utils'
 import { toast } from 'sonner'
 import { Badge } from '@/components/ui/badge'
 import { Button } from '@/components/ui/button'
@@ -64,7 +65,7 @@ export function EnhancedVmRow({ vm, isSelected, onToggleSelect, isFocused = fals
   // Poll stats for running VMs
   const { data: vmStats } = useQuery({
     queryKey: ['vm-stats', vm.id],
-    queryFn: () => api.getVmStats(vm.id),
+    queryFn: () => api.getVmStats(vm.id, vm.connectionId ?? undefined),
     enabled: vm.state === 'running',
     refetchInterval: 2000,
   })
@@ -79,7 +80,7 @@ export function EnhancedVmRow({ vm, isSelected, onToggleSelect, isFocused = fals
   }, [vmStats])
 
   const startMutation = useMutation({
-    mutationFn: () => api.startVm(vm.id),
+    mutationFn: () => api.startVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} started`)
@@ -95,7 +96,7 @@ export function EnhancedVmRow({ vm, isSelected, onToggleSelect, isFocused = fals
   })
 
   const stopMutation = useMutation({
-    mutationFn: () => api.stopVm(vm.id),
+    mutationFn: () => api.stopVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} stopped`)
@@ -104,7 +105,7 @@ export function EnhancedVmRow({ vm, isSelected, onToggleSelect, isFocused = fals
   })
 
   const pauseMutation = useMutation({
-    mutationFn: () => api.pauseVm(vm.id),
+    mutationFn: () => api.pauseVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} paused`)
@@ -113,7 +114,7 @@ export function EnhancedVmRow({ vm, isSelected, onToggleSelect, isFocused = fals
   })
 
   const resumeMutation = useMutation({
-    mutationFn: () => api.resumeVm(vm.id),
+    mutationFn: () => api.resumeVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} resumed`)
@@ -122,7 +123,7 @@ export function EnhancedVmRow({ vm, isSelected, onToggleSelect, isFocused = fals
   })
 
   const rebootMutation = useMutation({
-    mutationFn: () => api.rebootVm(vm.id),
+    mutationFn: () => api.rebootVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} rebooting`)
@@ -131,14 +132,14 @@ export function EnhancedVmRow({ vm, isSelected, onToggleSelect, isFocused = fals
   })
 
   const openConsoleMutation = useMutation({
-    mutationFn: () => api.openVncConsole(vm.id),
+    mutationFn: () => api.openVncConsole(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => toast.success('Console opened'),
     onError: (error) => toast.error(`Failed to open console: ${error}`),
   })
 
   const deleteMutation = useMutation({
     mutationFn: ({ deleteDisks, deleteSnapshots }: { deleteDisks: boolean; deleteSnapshots: boolean }) =>
-      api.deleteVm(vm.id, deleteDisks, deleteSnapshots),
+      api.deleteVm(vm.id, deleteDisks, deleteSnapshots, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} deleted`)
@@ -150,7 +151,7 @@ export function EnhancedVmRow({ vm, isSelected, onToggleSelect, isFocused = fals
   })
 
   const renameMutation = useMutation({
-    mutationFn: (newName: string) => api.renameVm(vm.id, newName),
+    mutationFn: (newName: string) => api.renameVm(vm.id, newName, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`VM renamed to ${newName}`)
@@ -214,7 +215,7 @@ export function EnhancedVmRow({ vm, isSelected, onToggleSelect, isFocused = fals
           <div className="flex-1 min-w-0">
             <div className="flex items-center gap-2">
               <button
-                onClick={() => navigate(`/vms/${vm.id}`)}
+                onClick={() => navigate(vmDetailsPath(vm))}
                 className="font-semibold hover:text-primary transition-colors text-left truncate"
               >
                 {vm.name}
@@ -336,7 +337,7 @@ export function EnhancedVmRow({ vm, isSelected, onToggleSelect, isFocused = fals
           <Button
             variant="ghost"
             size="icon"
-            onClick={() => navigate(`/vms/${vm.id}`)}
+            onClick={() => navigate(vmDetailsPath(vm))}
             title="VM Details (Ctrl+D)"
           >
             <Info className="h-4 w-4" />
@@ -374,7 +375,7 @@ export function EnhancedVmRow({ vm, isSelected, onToggleSelect, isFocused = fals
                   </DropdownMenuItem>
                 </>
               )}
-              <DropdownMenuItem onClick={() => navigate(`/vms/${vm.id}`)}>
+              <DropdownMenuItem onClick={() => navigate(vmDetailsPath(vm))}>
                 <Info className="mr-2 h-4 w-4" />
                 View Details
               </DropdownMenuItem>
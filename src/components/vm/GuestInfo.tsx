@@ -11,12 +11,13 @@ import { useState } from 'react'
 interface GuestInfoProps {
   vmId?: string  // Kept for backwards compatibility, not currently used
   vmName: string
+  connectionId?: string | null  // Connection the VM lives on; defaults to the selected one
   vmState?: string
   osType?: string  // Kept for backwards compatibility, not currently used
   compact?: boolean
 }
 
-export function GuestInfo({ vmName, vmState, compact }: GuestInfoProps) {
+export function GuestInfo({ vmName, connectionId, vmState, compact }: GuestInfoProps) {
   const [showInstallHelp, setShowInstallHelp] = useState(false)
   const isRunning = vmState === 'running'
 
@@ -27,8 +28,8 @@ export function GuestInfo({ vmName, vmState, compact }: GuestInfoProps) {
 
   // Only check agent status if VM is running
   const { data: agentStatus, isLoading: statusLoading } = useQuery({
-    queryKey: ['guestAgentStatus', vmName],
-    queryFn: () => api.checkGuestAgentStatus(vmName),
+    queryKey: ['guestAgentStatus', connectionId, vmName],
+    queryFn: () => api.checkGuestAgentStatus(vmName, connectionId ?? undefined),
     refetchInterval: 10000, // Check every 10 seconds
     retry: false,
     enabled: isRunning,
@@ -36,8 +37,8 @@ export function GuestInfo({ vmName, vmState, compact }: GuestInfoProps) {
 
   // Get system info if agent is available
   const { data: systemInfo, isLoading: systemLoading } = useQuery({
-    queryKey: ['guestSystemInfo', vmName],
-    queryFn: () => api.getGuestSystemInfo(vmName),
+    queryKey: ['guestSystemInfo', connectionId, vmName],
+    queryFn: () => api.getGuestSystemInfo(vmName, connectionId ?? undefined),
     enabled: agentStatus?.available === true,
     refetchInterval: 5000, // Refresh every 5 seconds
     retry: false,
@@ -45,8 +46,8 @@ export function GuestInfo({ vmName, vmState, compact }: GuestInfoProps) {
 
   // Get network info if agent is available
   const { data: networkInfo } = useQuery({
-    queryKey: ['guestNetworkInfo', vmName],
-    queryFn: () => api.getGuestNetworkInfo(vmName),
+    queryKey: ['guestNetworkInfo', connectionId, vmName],
+    queryFn: () => api.getGuestNetworkInfo(vmName, connectionId ?? undefined),
     enabled: agentStatus?.available === true,
     refetchInterval: 10000,
     retry: false,
@@ -54,8 +55,8 @@ export function GuestInfo({ vmName, vmState, compact }: GuestInfoProps) {
 
   // Get disk usage if agent is available
   const { data: diskUsage } = useQuery({
-    queryKey: ['guestDiskUsage', vmName],
-    queryFn: () => api.getGuestDiskUsage(vmName),
+    queryKey: ['guestDiskUsage', connectionId, vmName],
+    queryFn: () => api.getGuestDiskUsage(vmName, connectionId ?? undefined),
     enabled: agentStatus?.available === true,
     refetchInterval: 30000, // Refresh every 30 seconds
     retry: false,
@@ -63,8 +64,8 @@ export function GuestInfo({ vmName, vmState, compact }: GuestInfoProps) {
 
   // Get CPU stats for performance monitoring
   const { data: cpuStats } = useQuery({
-    queryKey: ['guestCpuStats', vmName],
-    queryFn: () => api.getGuestCpuStats(vmName),
+    queryKey: ['guestCpuStats', connectionId, vmName],
+    queryFn: () => api.getGuestCpuStats(vmName, connectionId ?? undefined),
     enabled: agentStatus?.available === true,
     refetchInterval: 3000, // Refresh every 3 seconds for realtime feel
     retry: false,
@@ -72,8 +73,8 @@ export function GuestInfo({ vmName, vmState, compact }: GuestInfoProps) {
 
   // Get disk I/O stats
   const { data: diskStats } = useQuery({
-    queryKey: ['guestDiskStats', vmName],
-    queryFn: () => api.getGuestDiskStats(vmName),
+    queryKey: ['guestDiskStats', connectionId, vmName],
+    queryFn: () => api.getGuestDiskStats(vmName, connectionId ?? undefined),
     enabled: agentStatus?.available === true,
     refetchInterval: 5000,
     retry: false,
@@ -81,8 +82,8 @@ export function GuestInfo({ vmName, vmState, compact }: GuestInfoProps) {
 
   // Get logged-in users
   const { data: users } = useQuery({
-    queryKey: ['guestUsers', vmName],
-    queryFn: () => api.getGuestUsers(vmName),
+    queryKey: ['guestUsers', connectionId, vmName],
+    queryFn: () => api.getGuestUsers(vmName, connectionId ?? undefined),
     enabled: agentStatus?.available === true,
     refetchInterval: 30000,
     retry: false,
@@ -90,8 +91,8 @@ export function GuestInfo({ vmName, vmState, compact }: GuestInfoProps) {
 
   // Get timezone
   const { data: timezone } = useQuery({
-    queryKey: ['guestTimezone', vmName],
-    queryFn: () => api.getGuestTimezone(vmName),
+    queryKey: ['guestTimezone', connectionId, vmName],
+    queryFn: () => api.getGuestTimezone(vmName, connectionId ?? undefined),
     enabled: agentStatus?.available === true,
     refetchInterval: 60000, // Timezone rarely changes
     retry: false,
@@ -27,6 +27,7 @@ import type { SavedConnection } from '@/lib/types'
 
 interface MigrationDialogProps {
   vmId: string
+  connectionId?: string | null
   vmName: string
   isRunning: boolean
   open?: boolean
@@ -34,7 +35,7 @@ interface MigrationDialogProps {
   trigger?: React.ReactNode
 }
 
-export function MigrationDialog({ vmId, vmName, isRunning, open: controlledOpen, onOpenChange, trigger }: MigrationDialogProps) {
+export function MigrationDialog({ vmId, connectionId, vmName, isRunning, open: controlledOpen, onOpenChange, trigger }: MigrationDialogProps) {
   const [internalOpen, setInternalOpen] = useState(false)
   const open = controlledOpen !== undefined ? controlledOpen : internalOpen
   const setOpen = (value: boolean) => {
@@ -61,7 +62,7 @@ export function MigrationDialog({ vmId, vmName, isRunning, open: controlledOpen,
   // Fetch migration info for the VM
   const { data: migrationInfo } = useQuery({
     queryKey: ['migrationInfo', vmId],
-    queryFn: () => api.getMigrationInfo(vmId),
+    queryFn: () => api.getMigrationInfo(vmId, connectionId ?? undefined),
     enabled: open,
   })
 
@@ -73,7 +74,7 @@ export function MigrationDialog({ vmId, vmName, isRunning, open: controlledOpen,
   const migrateMutation = useMutation({
     mutationFn: () => {
       const uri = useCustomUri ? destUri : getConnectionUri(selectedConnection)
-      return api.migrateVm(vmId, uri, liveMigration, unsafeMigration)
+      return api.migrateVm(vmId, uri, liveMigration, unsafeMigration, connectionId ?? undefined)
     },
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -0,0 +1,121 @@
+import { useState } from 'react'
+import type { GuestHook, QuiesceOptions } from '@/lib/types'
+import { Checkbox } from '@/components/ui/checkbox'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+
+export const DEFAULT_QUIESCE: QuiesceOptions = {
+  enabled: false,
+  mountpoints: [],
+  timeoutSecs: 60,
+  required: false,
+  preFreezeHook: null,
+  postThawHook: null,
+}
+
+const splitList = (value: string, separator: RegExp) =>
+  value.split(separator).map((item) => item.trim()).filter(Boolean)
+
+// A hook is entered as a command line: program path followed by space-separated arguments
+const hookToText = (hook?: GuestHook | null) =>
+  hook ? [hook.path, ...(hook.args ?? [])].join(' ') : ''
+
+const textToHook = (text: string): GuestHook | null => {
+  const [path, ...args] = splitList(text, /\s+/)
+  return path ? { path, args } : null
+}
+
+interface QuiesceFieldsProps {
+  id: string
+  value: QuiesceOptions
+  onChange: (value: QuiesceOptions) => void
+}
+
+export function QuiesceFields({ id, value, onChange }: QuiesceFieldsProps) {
+  // Raw text is kept so separators can be typed before the next item
+  const [mountpoints, setMountpoints] = useState((value.mountpoints ?? []).join(', '))
+  const [preHook, setPreHook] = useState(hookToText(value.preFreezeHook))
+  const [postHook, setPostHook] = useState(hookToText(value.postThawHook))
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center space-x-2">
+        <Checkbox
+          id={`${id}-quiesce`}
+          checked={value.enabled}
+          onCheckedChange={(checked) => onChange({ ...value, enabled: checked === true })}
+        />
+        <Label htmlFor={`${id}-quiesce`} className="text-sm cursor-pointer">
+          Quiesce guest filesystems (needs the QEMU guest agent)
+        </Label>
+      </div>
+
+      {value.enabled && (
+        <div className="space-y-3 ml-6">
+          <div className="grid grid-cols-2 gap-4">
+            <div>
+              <Label htmlFor={`${id}-mountpoints`}>Mountpoints</Label>
+              <Input
+                id={`${id}-mountpoints`}
+                value={mountpoints}
+                onChange={(e) => {
+                  setMountpoints(e.target.value)
+                  onChange({ ...value, mountpoints: splitList(e.target.value, /,/) })
+                }}
+                placeholder="All filesystems"
+              />
+            </div>
+            <div>
+              <Label htmlFor={`${id}-freeze-timeout`}>Thaw After (seconds)</Label>
+              <Input
+                id={`${id}-freeze-timeout`}
+                type="number"
+                min="1"
+                value={value.timeoutSecs ?? 60}
+                onChange={(e) => onChange({ ...value, timeoutSecs: parseInt(e.target.value) || 60 })}
+              />
+            </div>
+          </div>
+          <div>
+            <Label htmlFor={`${id}-pre-hook`}>Pre-freeze Hook</Label>
+            <Input
+              id={`${id}-pre-hook`}
+              value={preHook}
+              onChange={(e) => {
+                setPreHook(e.target.value)
+                onChange({ ...value, preFreezeHook: textToHook(e.target.value) })
+              }}
+              placeholder="/usr/local/bin/db-flush --lock"
+            />
+          </div>
+          <div>
+            <Label htmlFor={`${id}-post-hook`}>Post-thaw Hook</Label>
+            <Input
+              id={`${id}-post-hook`}
+              value={postHook}
+              onChange={(e) => {
+                setPostHook(e.target.value)
+                onChange({ ...value, postThawHook: textToHook(e.target.value) })
+              }}
+              placeholder="/usr/local/bin/db-flush --unlock"
+            />
+          </div>
+          <div className="flex items-center space-x-2">
+            <Checkbox
+              id={`${id}-quiesce-required`}
+              checked={value.required ?? false}
+              onCheckedChange={(checked) => onChange({ ...value, required: checked === true })}
+            />
+            <Label htmlFor={`${id}-quiesce-required`} className="text-sm cursor-pointer">
+              Fail instead of taking a crash-consistent image
+            </Label>
+          </div>
+          <p className="text-xs text-muted-foreground">
+            Hooks run inside the guest through guest-exec. The guest is always thawed, even if the operation fails
+            or hangs past the timeout.
+          </p>
+        </div>
+      )}
+    </div>
+  )
+}
@@ -32,7 +32,7 @@ export function RenameVmDialog({ vm, open, onOpenChange }: RenameVmDialogProps)
   }, [vm, open])
 
   const renameMutation = useMutation({
-    mutationFn: () => api.renameVm(vm!.id, newName.trim()),
+    mutationFn: () => api.renameVm(vm!.id, newName.trim(), vm!.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`VM renamed to ${newName.trim()}`)
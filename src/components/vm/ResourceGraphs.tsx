@@ -10,6 +10,7 @@ import { Button } from '@/components/ui/button'
 
 interface ResourceGraphsProps {
   vmId: string
+  connectionId?: string | null
   vmName: string
   compact?: boolean
   dataOnly?: boolean
@@ -31,14 +32,14 @@ type TimeRange = 'live' | '1h' | '6h' | '24h' | '7d' | '30d'
 
 const MAX_POINTS = 60 // Keep last 60 data points (1 minute at 1s interval)
 
-export function ResourceGraphs({ vmId, compact, dataOnly }: ResourceGraphsProps) {
+export function ResourceGraphs({ vmId, connectionId, compact, dataOnly }: ResourceGraphsProps) {
   const [metrics, setMetrics] = useState<MetricPoint[]>([])
   const [timeRange, setTimeRange] = useState<TimeRange>('live')
 
   // Poll VM stats every second (only for live view)
   const { data: stats } = useQuery({
     queryKey: ['vm-stats', vmId],
-    queryFn: () => api.getVmStats(vmId),
+    queryFn: () => api.getVmStats(vmId, connectionId ?? undefined),
     refetchInterval: 1000,
     enabled: timeRange === 'live',
   })
@@ -0,0 +1,311 @@
+import { useState, useEffect } from 'react'
+import { useMutation, useQueryClient, useQuery } from '@tanstack/react-query'
+import { api, waitForJob } from '@/lib/tauri'
+import { Button } from '@/components/ui/button'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { Checkbox } from '@/components/ui/checkbox'
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+  DialogTrigger,
+} from '@/components/ui/dialog'
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select'
+import { RotateCcw, AlertTriangle } from 'lucide-react'
+import { toast } from 'sonner'
+import type { BackupRun, RestoredDisk, RestoreResult, StoragePool, VM } from '@/lib/types'
+import { Alert, AlertDescription } from '@/components/ui/alert'
+
+type RestoreAction = 'newVm' | 'inPlace' | 'disk' | 'attach'
+
+const actionLabels: Record<RestoreAction, string> = {
+  newVm: 'Restore as a new VM',
+  inPlace: 'Restore over the existing VM',
+  disk: 'Restore a single disk',
+  attach: 'Attach a disk read-only to a VM',
+}
+
+interface RestoreBackupDialogProps {
+  run: BackupRun
+  trigger?: React.ReactNode
+}
+
+export function RestoreBackupDialog({ run, trigger }: RestoreBackupDialogProps) {
+  const [open, setOpen] = useState(false)
+  const [action, setAction] = useState<RestoreAction>('newVm')
+  const [newName, setNewName] = useState(`${run.vmName}-restored`)
+  const [pool, setPool] = useState('')
+  const [disk, setDisk] = useState(run.disks[0]?.target ?? '')
+  const [replaceDisk, setReplaceDisk] = useState(false)
+  const [attachVmId, setAttachVmId] = useState('')
+  const [confirmed, setConfirmed] = useState(false)
+  const queryClient = useQueryClient()
+
+  const { data: storagePools = [] } = useQuery<StoragePool[]>({
+    queryKey: ['storage-pools'],
+    queryFn: () => api.getStoragePools(),
+    enabled: open,
+  })
+
+  const { data: vms = [] } = useQuery<VM[]>({
+    queryKey: ['vms'],
+    queryFn: () => api.getVms(),
+    enabled: open && action === 'attach',
+  })
+
+  // Reset form when dialog opens
+  useEffect(() => {
+    if (open) {
+      setAction('newVm')
+      setNewName(`${run.vmName}-restored`)
+      setPool('')
+      setDisk(run.disks[0]?.target ?? '')
+      setReplaceDisk(false)
+      setAttachVmId('')
+      setConfirmed(false)
+    }
+  }, [open, run])
+
+  // Anything that overwrites the VM needs an explicit confirmation
+  const destructive = action === 'inPlace' || (action === 'disk' && replaceDisk)
+
+  const restoreMutation = useMutation({
+    mutationFn: async (): Promise<string> => {
+      switch (action) {
+        case 'newVm':
+        case 'inPlace': {
+          const result = await waitForJob<RestoreResult>(await api.restoreBackup({
+            configId: run.configId,
+            runId: run.id,
+            mode: action,
+            newName: action === 'newVm' ? newName.trim() : null,
+            pool,
+            confirm: confirmed,
+          }))
+          return `Restored ${result.vmName} with ${result.disks.length} disk(s)`
+        }
+        case 'disk': {
+          const result = await waitForJob<RestoredDisk>(await api.restoreBackupDisk({
+            configId: run.configId,
+            runId: run.id,
+            disk,
+            pool,
+            replace: replaceDisk,
+            confirm: confirmed,
+          }))
+          return replaceDisk
+            ? `Disk ${result.target} of ${run.vmName} now uses ${result.path}`
+            : `Restored disk ${result.target} to ${result.path}`
+        }
+        case 'attach': {
+          const target = await api.attachBackupDisk(run.configId, run.id, disk, attachVmId)
+          const vm = vms.find((v) => v.id === attachVmId)
+          return `Attached ${disk} read-only to ${vm?.name ?? attachVmId} as ${target}`
+        }
+      }
+    },
+    onSuccess: (message) => {
+      queryClient.invalidateQueries({ queryKey: ['vms'] })
+      queryClient.invalidateQueries({ queryKey: ['storage-pools'] })
+      toast.success('Restore Complete', { description: message })
+      setOpen(false)
+    },
+    onError: (error: Error) => {
+      toast.error('Restore Failed', { description: error.message })
+    },
+  })
+
+  const activePools = storagePools.filter(p => p.state === 'active')
+  const needsPool = action !== 'attach'
+  const needsDisk = action === 'disk' || action === 'attach'
+  const canSubmit =
+    (!needsPool || pool !== '') &&
+    (!needsDisk || disk !== '') &&
+    (action !== 'newVm' || newName.trim() !== '') &&
+    (action !== 'attach' || attachVmId !== '') &&
+    (!destructive || confirmed)
+
+  return (
+    <Dialog open={open} onOpenChange={setOpen}>
+      <DialogTrigger asChild>
+        {trigger || (
+          <Button variant="ghost" size="sm">
+            <RotateCcw className="mr-2 h-4 w-4" />
+            Restore
+          </Button>
+        )}
+      </DialogTrigger>
+      <DialogContent className="sm:max-w-[500px]">
+        <DialogHeader>
+          <DialogTitle>Restore Backup</DialogTitle>
+          <DialogDescription>
+            Restore point of "{run.vmName}" from {new Date(run.startedAt * 1000).toLocaleString()}.
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="grid gap-4 py-4">
+          <div className="grid gap-2">
+            <Label>Action</Label>
+            <Select
+              value={action}
+              onValueChange={(value) => {
+                setAction(value as RestoreAction)
+                setConfirmed(false)
+              }}
+            >
+              <SelectTrigger>
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {(Object.keys(actionLabels) as RestoreAction[]).map((key) => (
+                  <SelectItem key={key} value={key}>
+                    {actionLabels[key]}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+
+          {action === 'newVm' && (
+            <div className="grid gap-2">
+              <Label htmlFor="restore-name">New VM Name</Label>
+              <Input
+                id="restore-name"
+                value={newName}
+                onChange={(e) => setNewName(e.target.value)}
+                disabled={restoreMutation.isPending}
+              />
+              <p className="text-xs text-muted-foreground">
+                The restored VM gets a new UUID and MAC addresses.
+              </p>
+            </div>
+          )}
+
+          {needsDisk && (
+            <div className="grid gap-2">
+              <Label>Disk</Label>
+              <Select value={disk} onValueChange={setDisk}>
+                <SelectTrigger>
+                  <SelectValue placeholder="Select a disk" />
+                </SelectTrigger>
+                <SelectContent>
+                  {run.disks.map((d) => (
+                    <SelectItem key={d.target} value={d.target}>
+                      {d.target} ({d.source})
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+            </div>
+          )}
+
+          {needsPool && (
+            <div className="grid gap-2">
+              <Label>Target Storage Pool</Label>
+              <Select value={pool} onValueChange={setPool}>
+                <SelectTrigger>
+                  <SelectValue placeholder="Select a pool" />
+                </SelectTrigger>
+                <SelectContent>
+                  {activePools.map((p) => (
+                    <SelectItem key={p.name} value={p.name}>
+                      {p.name} ({p.poolType})
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+              <p className="text-xs text-muted-foreground">
+                Disk images are flattened into standalone files in this pool.
+              </p>
+            </div>
+          )}
+
+          {action === 'disk' && (
+            <div className="flex items-center space-x-2">
+              <Checkbox
+                id="replace-disk"
+                checked={replaceDisk}
+                onCheckedChange={(checked) => {
+                  setReplaceDisk(checked === true)
+                  setConfirmed(false)
+                }}
+                disabled={restoreMutation.isPending}
+              />
+              <Label htmlFor="replace-disk">Use the restored image as the VM's {disk} disk</Label>
+            </div>
+          )}
+
+          {action === 'attach' && (
+            <div className="grid gap-2">
+              <Label>Attach To</Label>
+              <Select value={attachVmId} onValueChange={setAttachVmId}>
+                <SelectTrigger>
+                  <SelectValue placeholder="Select a VM" />
+                </SelectTrigger>
+                <SelectContent>
+                  {vms.map((vm) => (
+                    <SelectItem key={vm.id} value={vm.id}>
+                      {vm.name}
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+              <p className="text-xs text-muted-foreground">
+                The image is used straight from the backup set; detach it before the backup is pruned.
+              </p>
+            </div>
+          )}
+
+          {destructive && (
+            <Alert>
+              <AlertTriangle className="h-4 w-4" />
+              <AlertDescription className="space-y-2">
+                <p>
+                  {action === 'inPlace'
+                    ? `This replaces the configuration and disks of "${run.vmName}" with the backup.`
+                    : `This points disk ${disk} of "${run.vmName}" at the restored image.`}{' '}
+                  The VM must be shut off. The current disk images are kept in place.
+                </p>
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="confirm-restore"
+                    checked={confirmed}
+                    onCheckedChange={(checked) => setConfirmed(checked === true)}
+                  />
+                  <Label htmlFor="confirm-restore">I understand, restore over the VM</Label>
+                </div>
+              </AlertDescription>
+            </Alert>
+          )}
+        </div>
+
+        <DialogFooter>
+          <Button
+            variant="outline"
+            onClick={() => setOpen(false)}
+            disabled={restoreMutation.isPending}
+          >
+            Cancel
+          </Button>
+          <Button
+            variant={destructive ? 'destructive' : 'default'}
+            onClick={() => restoreMutation.mutate()}
+            disabled={restoreMutation.isPending || !canSubmit}
+          >
+            {restoreMutation.isPending ? 'Restoring...' : action === 'attach' ? 'Attach' : 'Restore'}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  )
+}
@@ -1,23 +1,106 @@
-import { useState } from 'react'
+import { useEffect, useState } from 'react'
 import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query'
+import { listen, UnlistenFn } from '@tauri-apps/api/event'
 import { api } from '@/lib/tauri'
-import type { ScheduledOperation, CreateScheduleRequest, OperationType, ScheduleFrequency, VM } from '@/lib/types'
+import type { BackupConfig, ScheduledOperation, CreateScheduleRequest, OperationType, OperationParams, ScheduleFrequency, ScheduleRun, MissedRunPolicy, SavedConnection, SnapshotLocation, VM } from '@/lib/types'
 import { Button } from '@/components/ui/button'
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
 import { Dialog, DialogContent, DialogDescription, DialogFooter, DialogHeader, DialogTitle } from '@/components/ui/dialog'
 import { Input } from '@/components/ui/input'
 import { Label } from '@/components/ui/label'
 import { Badge } from '@/components/ui/badge'
+import { Checkbox } from '@/components/ui/checkbox'
 import { toast } from 'sonner'
-import { Clock, Plus, Trash2, Power, PowerOff, RotateCw, Camera, Calendar, PlayCircle, PauseCircle } from 'lucide-react'
+import { DEFAULT_QUIESCE, QuiesceFields } from './QuiesceFields'
+import { Clock, Plus, Trash2, Power, PowerOff, RotateCw, Camera, Calendar, PlayCircle, PauseCircle, History, Moon, Terminal, Cpu, Scissors, ArrowRightLeft, HardDrive, ShieldCheck } from 'lucide-react'
+
+const systemTimezone = Intl.DateTimeFormat().resolvedOptions().timeZone
+
+const runStatusLabels = {
+  success: 'Success',
+  failed: 'Failed',
+  skipped: 'Skipped',
+} as const
+
+const operationLabels: Record<OperationType, string> = {
+  start: 'Start',
+  stop: 'Stop',
+  reboot: 'Reboot',
+  snapshot: 'Snapshot',
+  hibernate: 'Hibernate',
+  guestCommand: 'Guest Command',
+  setResources: 'Set Resources',
+  pruneSnapshots: 'Prune Snapshots',
+  migrate: 'Migrate',
+  backup: 'Backup',
+  verifyBackup: 'Verify Backup',
+}
+
+// Initial payload for operation types that require parameters
+const defaultParams = (operation: OperationType): OperationParams | null => {
+  switch (operation) {
+    case 'guestCommand':
+      return { type: 'guestCommand', command: '', args: [] }
+    case 'setResources':
+      return { type: 'setResources', vcpus: 2, memoryMb: 2048 }
+    case 'pruneSnapshots':
+      return { type: 'pruneSnapshots', keepLast: 7, namePrefix: 'scheduled-' }
+    case 'migrate':
+      return { type: 'migrate', targetConnectionId: '', live: true, unsafeMigration: false }
+    case 'snapshot':
+      return { type: 'snapshot', quiesce: DEFAULT_QUIESCE }
+    case 'verifyBackup':
+      return { type: 'verifyBackup', backupConfigId: '', options: { boot: false, bootTimeoutSecs: 180 } }
+    default:
+      return null
+  }
+}
 
 interface ScheduleManagerProps {
   vmId?: string // Optional: show schedules for specific VM
 }
 
+function ScheduleRunHistory({ scheduleId }: { scheduleId: string }) {
+  const { data: runs, isLoading } = useQuery<ScheduleRun[]>({
+    queryKey: ['schedule-runs', scheduleId],
+    queryFn: () => api.getScheduleRuns(scheduleId),
+  })
+
+  if (isLoading) {
+    return <div className="text-sm text-muted-foreground">Loading history...</div>
+  }
+
+  if (!runs || runs.length === 0) {
+    return <div className="text-sm text-muted-foreground">This schedule has not run yet.</div>
+  }
+
+  return (
+    <div className="space-y-1 text-sm">
+      {runs.map((run) => (
+        <div key={run.id} className="flex items-center gap-2">
+          <Badge variant={run.status === 'failed' ? 'destructive' : run.status === 'skipped' ? 'secondary' : 'default'}>
+            {runStatusLabels[run.status]}
+          </Badge>
+          <span className="text-muted-foreground">{new Date(run.startedAt * 1000).toLocaleString()}</span>
+          <span className="truncate">{run.message}</span>
+          {run.vmResults.length > 0 && (
+            <span className="text-muted-foreground truncate">
+              {run.vmResults.map((r) => `${r.vmName}: ${r.status === 'success' ? 'ok' : r.message}`).join(', ')}
+            </span>
+          )}
+        </div>
+      ))}
+    </div>
+  )
+}
+
 export function ScheduleManager({ vmId }: ScheduleManagerProps) {
   const queryClient = useQueryClient()
   const [isCreateDialogOpen, setIsCreateDialogOpen] = useState(false)
+  const [historyScheduleId, setHistoryScheduleId] = useState<string | null>(null)
+  const [targetMode, setTargetMode] = useState<'vm' | 'tags'>('vm')
+  const [tagInput, setTagInput] = useState('')
+  const [matchAllTags, setMatchAllTags] = useState(false)
   const [scheduleForm, setScheduleForm] = useState<CreateScheduleRequest>({
     name: '',
     vmId: vmId || '',
@@ -26,6 +109,9 @@ export function ScheduleManager({ vmId }: ScheduleManagerProps) {
     scheduledTime: '09:00',
     dayOfWeek: undefined,
     dayOfMonth: undefined,
+    cronExpression: undefined,
+    timezone: systemTimezone,
+    missedRunPolicy: 'runOnce',
   })
 
   // Query for VMs (to populate dropdown)
@@ -34,12 +120,44 @@ export function ScheduleManager({ vmId }: ScheduleManagerProps) {
     queryFn: () => api.getVms(),
   })
 
+  // Saved connections are the destinations for scheduled migrations
+  const { data: savedConnections } = useQuery<SavedConnection[]>({
+    queryKey: ['saved-connections'],
+    queryFn: () => api.getSavedConnections(),
+  })
+
+  // Backup configs whose restore points can be verified on a schedule
+  const { data: backupConfigs } = useQuery<BackupConfig[]>({
+    queryKey: ['backupConfigs'],
+    queryFn: () => api.listBackupConfigs(),
+  })
+
   // Query for schedules
   const { data: schedules, isLoading } = useQuery<ScheduledOperation[]>({
     queryKey: vmId ? ['schedules', vmId] : ['schedules'],
     queryFn: () => vmId ? api.getVmSchedules(vmId) : api.listSchedules(),
   })
 
+  // Refresh schedules whenever the backend executor runs one
+  useEffect(() => {
+    let unlisten: UnlistenFn | undefined
+
+    const setupListener = async () => {
+      unlisten = await listen<ScheduleRun>('schedule-run', (event) => {
+        queryClient.invalidateQueries({ queryKey: ['schedules'] })
+        queryClient.invalidateQueries({ queryKey: ['schedule-runs', event.payload.scheduleId] })
+      })
+    }
+
+    setupListener()
+
+    return () => {
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [queryClient])
+
   // Create schedule mutation
   const createMutation = useMutation({
     mutationFn: (request: CreateScheduleRequest) => api.createSchedule(request),
@@ -80,6 +198,9 @@ export function ScheduleManager({ vmId }: ScheduleManagerProps) {
   })
 
   const resetForm = () => {
+    setTargetMode('vm')
+    setTagInput('')
+    setMatchAllTags(false)
     setScheduleForm({
       name: '',
       vmId: vmId || '',
@@ -88,15 +209,39 @@ export function ScheduleManager({ vmId }: ScheduleManagerProps) {
       scheduledTime: '09:00',
       dayOfWeek: undefined,
       dayOfMonth: undefined,
+      cronExpression: undefined,
+      timezone: systemTimezone,
+      missedRunPolicy: 'runOnce',
     })
   }
 
+  const parseTags = (input: string) =>
+    input.split(',').map((tag) => tag.trim()).filter((tag) => tag.length > 0)
+
   const handleCreate = () => {
+    if (targetMode === 'tags') {
+      const tags = parseTags(tagInput)
+      if (tags.length === 0) {
+        toast.error('Please enter at least one tag')
+        return
+      }
+      // Tag schedules resolve matching VMs across all connected hosts when they fire
+      createMutation.mutate({
+        ...scheduleForm,
+        vmId: '',
+        connectionId: null,
+        tagSelector: { tags, matchAll: matchAllTags },
+      })
+      return
+    }
+
     if (!scheduleForm.vmId) {
       toast.error('Please select a VM')
       return
     }
-    createMutation.mutate(scheduleForm)
+    // Run the operation on the host the VM was listed from
+    const vm = vms?.find(v => v.id === scheduleForm.vmId)
+    createMutation.mutate({ ...scheduleForm, connectionId: vm?.connectionId ?? null })
   }
 
   const handleToggleEnabled = (id: string, currentlyEnabled: boolean) => {
@@ -119,13 +264,37 @@ export function ScheduleManager({ vmId }: ScheduleManagerProps) {
         return <RotateCw className="h-4 w-4" />
       case 'snapshot':
         return <Camera className="h-4 w-4" />
+      case 'hibernate':
+        return <Moon className="h-4 w-4" />
+      case 'guestCommand':
+        return <Terminal className="h-4 w-4" />
+      case 'setResources':
+        return <Cpu className="h-4 w-4" />
+      case 'pruneSnapshots':
+        return <Scissors className="h-4 w-4" />
+      case 'migrate':
+        return <ArrowRightLeft className="h-4 w-4" />
+      case 'backup':
+        return <HardDrive className="h-4 w-4" />
+      case 'verifyBackup':
+        return <ShieldCheck className="h-4 w-4" />
     }
   }
 
   const getOperationLabel = (operation: OperationType) => {
-    return operation.charAt(0).toUpperCase() + operation.slice(1)
+    return operationLabels[operation]
   }
 
+  const params = scheduleForm.params ?? null
+  const setParams = (next: OperationParams) => setScheduleForm({ ...scheduleForm, params: next })
+
+  const paramsIncomplete =
+    (params?.type === 'guestCommand' && !params.command.trim()) ||
+    (params?.type === 'setResources' && !params.vcpus && !params.memoryMb) ||
+    (params?.type === 'migrate' && !params.targetConnectionId) ||
+    (params?.type === 'pruneSnapshots' && !params.namePrefix.startsWith('scheduled-')) ||
+    (params?.type === 'verifyBackup' && !params.backupConfigId)
+
   const getFrequencyLabel = (frequency: ScheduleFrequency) => {
     return frequency.charAt(0).toUpperCase() + frequency.slice(1)
   }
@@ -181,7 +350,9 @@ export function ScheduleManager({ vmId }: ScheduleManagerProps) {
                       {schedule.name}
                     </CardTitle>
                     <CardDescription className="mt-1">
-                      {!vmId && `VM: ${getVmName(schedule.vmId)} • `}
+                      {schedule.tagSelector
+                        ? `Tags (${schedule.tagSelector.matchAll ? 'all' : 'any'}): ${schedule.tagSelector.tags.join(', ')} • `
+                        : !vmId && `VM: ${getVmName(schedule.vmId)} • `}
                       {getOperationLabel(schedule.operation)} • {getFrequencyLabel(schedule.frequency)}
                     </CardDescription>
                   </div>
@@ -195,9 +366,20 @@ export function ScheduleManager({ vmId }: ScheduleManagerProps) {
               <CardContent>
                 <div className="space-y-3">
                   <div className="grid grid-cols-2 gap-4 text-sm">
+                    {schedule.frequency === 'cron' ? (
+                      <div>
+                        <div className="text-muted-foreground">Cron Expression</div>
+                        <div className="font-mono font-medium">{schedule.cronExpression}</div>
+                      </div>
+                    ) : (
+                      <div>
+                        <div className="text-muted-foreground">Time</div>
+                        <div className="font-medium">{schedule.scheduledTime}</div>
+                      </div>
+                    )}
                     <div>
-                      <div className="text-muted-foreground">Time</div>
-                      <div className="font-medium">{schedule.scheduledTime}</div>
+                      <div className="text-muted-foreground">Timezone</div>
+                      <div className="font-medium">{schedule.timezone || 'System'}</div>
                     </div>
                     {schedule.frequency === 'weekly' && schedule.dayOfWeek !== undefined && (
                       <div>
@@ -221,11 +403,20 @@ export function ScheduleManager({ vmId }: ScheduleManagerProps) {
                   </div>
 
                   {schedule.lastRun && (
-                    <div className="text-sm text-muted-foreground">
+                    <div className="flex items-center gap-2 text-sm text-muted-foreground">
                       Last run: {formatNextRun(schedule.lastRun)}
+                      {schedule.lastStatus && (
+                        <Badge variant={schedule.lastStatus === 'failed' ? 'destructive' : 'outline'}>
+                          {runStatusLabels[schedule.lastStatus]}
+                        </Badge>
+                      )}
                     </div>
                   )}
 
+                  {historyScheduleId === schedule.id && (
+                    <ScheduleRunHistory scheduleId={schedule.id} />
+                  )}
+
                   <div className="flex gap-2 pt-2">
                     <Button
                       size="sm"
@@ -244,6 +435,14 @@ export function ScheduleManager({ vmId }: ScheduleManagerProps) {
                         </>
                       )}
                     </Button>
+                    <Button
+                      size="sm"
+                      variant="outline"
+                      onClick={() => setHistoryScheduleId(historyScheduleId === schedule.id ? null : schedule.id)}
+                    >
+                      <History className="mr-1 h-3 w-3" />
+                      History
+                    </Button>
                     <Button
                       size="sm"
                       variant="outline"
@@ -282,6 +481,69 @@ export function ScheduleManager({ vmId }: ScheduleManagerProps) {
             </div>
 
             {!vmId && (
+              <div>
+                <Label htmlFor="target-mode">Target</Label>
+                <select
+                  id="target-mode"
+                  value={targetMode}
+                  onChange={(e) => setTargetMode(e.target.value as 'vm' | 'tags')}
+                  className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background"
+                >
+                  <option value="vm">A single VM</option>
+                  <option value="tags">All VMs with tags</option>
+                </select>
+              </div>
+            )}
+
+            {!vmId && targetMode === 'tags' && (
+              <div className="space-y-3">
+                <div>
+                  <Label htmlFor="tag-selector">Tags</Label>
+                  <Input
+                    id="tag-selector"
+                    value={tagInput}
+                    onChange={(e) => setTagInput(e.target.value)}
+                    placeholder="e.g. ci-runner, weekend-off"
+                  />
+                </div>
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="match-all-tags"
+                    checked={matchAllTags}
+                    onCheckedChange={(checked) => setMatchAllTags(checked === true)}
+                  />
+                  <Label htmlFor="match-all-tags">VM must have every tag</Label>
+                </div>
+                <div className="grid grid-cols-2 gap-4">
+                  <div>
+                    <Label htmlFor="parallelism">Parallel VMs</Label>
+                    <Input
+                      id="parallelism"
+                      type="number"
+                      min="1"
+                      value={scheduleForm.parallelism ?? 1}
+                      onChange={(e) =>
+                        setScheduleForm({ ...scheduleForm, parallelism: Math.max(1, parseInt(e.target.value) || 1) })
+                      }
+                    />
+                  </div>
+                  <div>
+                    <Label htmlFor="stagger">Stagger (seconds)</Label>
+                    <Input
+                      id="stagger"
+                      type="number"
+                      min="0"
+                      value={scheduleForm.staggerSecs ?? 0}
+                      onChange={(e) =>
+                        setScheduleForm({ ...scheduleForm, staggerSecs: Math.max(0, parseInt(e.target.value) || 0) })
+                      }
+                    />
+                  </div>
+                </div>
+              </div>
+            )}
+
+            {!vmId && targetMode === 'vm' && (
               <div>
                 <Label htmlFor="vm-select">Virtual Machine</Label>
                 <select
@@ -312,6 +574,7 @@ export function ScheduleManager({ vmId }: ScheduleManagerProps) {
                     setScheduleForm({
                       ...scheduleForm,
                       operation: e.target.value as OperationType,
+                      params: defaultParams(e.target.value as OperationType),
                     })
                   }
                   className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background"
@@ -320,6 +583,12 @@ export function ScheduleManager({ vmId }: ScheduleManagerProps) {
                   <option value="stop">Stop VM</option>
                   <option value="reboot">Reboot VM</option>
                   <option value="snapshot">Create Snapshot</option>
+                  <option value="hibernate">Hibernate VM (managed save)</option>
+                  <option value="guestCommand">Run Guest Command</option>
+                  <option value="setResources">Change vCPUs / Memory</option>
+                  <option value="pruneSnapshots">Prune Snapshots</option>
+                  <option value="migrate">Migrate VM</option>
+                  <option value="verifyBackup">Verify Latest Backup</option>
                 </select>
               </div>
 
@@ -343,22 +612,38 @@ export function ScheduleManager({ vmId }: ScheduleManagerProps) {
                   <option value="daily">Daily</option>
                   <option value="weekly">Weekly</option>
                   <option value="monthly">Monthly</option>
+                  <option value="cron">Cron Expression</option>
                 </select>
               </div>
             </div>
 
             <div className="grid grid-cols-2 gap-4">
-              <div>
-                <Label htmlFor="scheduled-time">Time</Label>
-                <Input
-                  id="scheduled-time"
-                  type="time"
-                  value={scheduleForm.scheduledTime}
-                  onChange={(e) =>
-                    setScheduleForm({ ...scheduleForm, scheduledTime: e.target.value })
-                  }
-                />
-              </div>
+              {scheduleForm.frequency === 'cron' ? (
+                <div className="col-span-2">
+                  <Label htmlFor="cron-expression">Cron Expression</Label>
+                  <Input
+                    id="cron-expression"
+                    className="font-mono"
+                    value={scheduleForm.cronExpression || ''}
+                    onChange={(e) =>
+                      setScheduleForm({ ...scheduleForm, cronExpression: e.target.value })
+                    }
+                    placeholder="minute hour day-of-month month day-of-week, e.g. 30 1 * * 1-5"
+                  />
+                </div>
+              ) : (
+                <div>
+                  <Label htmlFor="scheduled-time">Time</Label>
+                  <Input
+                    id="scheduled-time"
+                    type="time"
+                    value={scheduleForm.scheduledTime}
+                    onChange={(e) =>
+                      setScheduleForm({ ...scheduleForm, scheduledTime: e.target.value })
+                    }
+                  />
+                </div>
+              )}
 
               {scheduleForm.frequency === 'weekly' && (
                 <div>
@@ -404,12 +689,230 @@ export function ScheduleManager({ vmId }: ScheduleManagerProps) {
                 </div>
               )}
             </div>
+
+            {params?.type === 'guestCommand' && (
+              <div className="grid grid-cols-2 gap-4">
+                <div>
+                  <Label htmlFor="guest-command">Command</Label>
+                  <Input
+                    id="guest-command"
+                    className="font-mono"
+                    value={params.command}
+                    onChange={(e) => setParams({ ...params, command: e.target.value })}
+                    placeholder="/usr/bin/systemctl"
+                  />
+                </div>
+                <div>
+                  <Label htmlFor="guest-args">Arguments</Label>
+                  <Input
+                    id="guest-args"
+                    className="font-mono"
+                    value={params.args.join(' ')}
+                    onChange={(e) => setParams({ ...params, args: e.target.value.split(' ').filter((a) => a.length > 0) })}
+                    placeholder="restart nginx"
+                  />
+                </div>
+              </div>
+            )}
+
+            {params?.type === 'setResources' && (
+              <div className="grid grid-cols-2 gap-4">
+                <div>
+                  <Label htmlFor="resource-vcpus">vCPUs</Label>
+                  <Input
+                    id="resource-vcpus"
+                    type="number"
+                    min="1"
+                    value={params.vcpus ?? ''}
+                    onChange={(e) => setParams({ ...params, vcpus: e.target.value ? parseInt(e.target.value) : null })}
+                    placeholder="Unchanged"
+                  />
+                </div>
+                <div>
+                  <Label htmlFor="resource-memory">Memory (MB)</Label>
+                  <Input
+                    id="resource-memory"
+                    type="number"
+                    min="128"
+                    value={params.memoryMb ?? ''}
+                    onChange={(e) => setParams({ ...params, memoryMb: e.target.value ? parseInt(e.target.value) : null })}
+                    placeholder="Unchanged"
+                  />
+                </div>
+              </div>
+            )}
+
+            {params?.type === 'pruneSnapshots' && (
+              <div className="grid grid-cols-2 gap-4">
+                <div>
+                  <Label htmlFor="prune-keep">Keep Newest</Label>
+                  <Input
+                    id="prune-keep"
+                    type="number"
+                    min="0"
+                    value={params.keepLast}
+                    onChange={(e) => setParams({ ...params, keepLast: Math.max(0, parseInt(e.target.value) || 0) })}
+                  />
+                </div>
+                <div>
+                  <Label htmlFor="prune-prefix">Snapshot Name Prefix</Label>
+                  <Input
+                    id="prune-prefix"
+                    className="font-mono"
+                    value={params.namePrefix}
+                    onChange={(e) => setParams({ ...params, namePrefix: e.target.value })}
+                  />
+                  <p className="mt-1 text-xs text-muted-foreground">Must start with scheduled- so manual snapshots are never pruned</p>
+                </div>
+              </div>
+            )}
+
+            {params?.type === 'migrate' && (
+              <div className="space-y-3">
+                <div>
+                  <Label htmlFor="migrate-target">Destination</Label>
+                  <select
+                    id="migrate-target"
+                    value={params.targetConnectionId}
+                    onChange={(e) => setParams({ ...params, targetConnectionId: e.target.value })}
+                    className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background"
+                  >
+                    <option value="">Select a connection...</option>
+                    {savedConnections?.map((connection) => (
+                      <option key={connection.id} value={connection.id}>
+                        {connection.name}
+                      </option>
+                    ))}
+                  </select>
+                </div>
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="migrate-live"
+                    checked={params.live}
+                    onCheckedChange={(checked) => setParams({ ...params, live: checked === true })}
+                  />
+                  <Label htmlFor="migrate-live">Live migration</Label>
+                </div>
+              </div>
+            )}
+
+            {params?.type === 'verifyBackup' && (
+              <div className="space-y-3">
+                <div>
+                  <Label htmlFor="verify-config">Backup</Label>
+                  <select
+                    id="verify-config"
+                    value={params.backupConfigId}
+                    onChange={(e) => setParams({ ...params, backupConfigId: e.target.value })}
+                    className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background"
+                  >
+                    <option value="">Select a backup...</option>
+                    {backupConfigs
+                      ?.filter((config) => !scheduleForm.vmId || config.vmId === scheduleForm.vmId)
+                      .map((config) => (
+                        <option key={config.id} value={config.id}>
+                          {config.name}
+                        </option>
+                      ))}
+                  </select>
+                </div>
+                <div className="grid grid-cols-2 gap-4 items-end">
+                  <div className="flex items-center space-x-2">
+                    <Checkbox
+                      id="verify-boot"
+                      checked={params.options.boot}
+                      onCheckedChange={(checked) =>
+                        setParams({ ...params, options: { ...params.options, boot: checked === true } })
+                      }
+                    />
+                    <Label htmlFor="verify-boot">Boot a network-less sandbox copy</Label>
+                  </div>
+                  {params.options.boot && (
+                    <div>
+                      <Label htmlFor="verify-boot-timeout">Boot Timeout (seconds)</Label>
+                      <Input
+                        id="verify-boot-timeout"
+                        type="number"
+                        min="10"
+                        value={params.options.bootTimeoutSecs}
+                        onChange={(e) =>
+                          setParams({ ...params, options: { ...params.options, bootTimeoutSecs: parseInt(e.target.value) || 180 } })
+                        }
+                      />
+                    </div>
+                  )}
+                </div>
+              </div>
+            )}
+
+            {params?.type === 'snapshot' && (
+              <div className="space-y-4">
+                <div>
+                  <Label htmlFor="schedule-snapshot-location">Snapshot Type</Label>
+                  <select
+                    id="schedule-snapshot-location"
+                    value={params.location ?? 'internal'}
+                    onChange={(e) => setParams({ ...params, location: e.target.value as SnapshotLocation })}
+                    className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background"
+                  >
+                    <option value="internal">Internal (inside qcow2 images)</option>
+                    <option value="external">External (overlay files)</option>
+                  </select>
+                </div>
+                <QuiesceFields
+                  id="schedule-snapshot"
+                  value={params.quiesce}
+                  onChange={(quiesce) => setParams({ ...params, quiesce })}
+                />
+              </div>
+            )}
+
+            <div className="grid grid-cols-2 gap-4">
+              <div>
+                <Label htmlFor="timezone">Timezone</Label>
+                <Input
+                  id="timezone"
+                  value={scheduleForm.timezone || ''}
+                  onChange={(e) =>
+                    setScheduleForm({ ...scheduleForm, timezone: e.target.value || undefined })
+                  }
+                  placeholder="e.g. Europe/Berlin"
+                />
+              </div>
+
+              <div>
+                <Label htmlFor="missed-run-policy">Missed Runs</Label>
+                <select
+                  id="missed-run-policy"
+                  value={scheduleForm.missedRunPolicy}
+                  onChange={(e) =>
+                    setScheduleForm({
+                      ...scheduleForm,
+                      missedRunPolicy: e.target.value as MissedRunPolicy,
+                    })
+                  }
+                  className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background"
+                >
+                  <option value="skip">Skip missed runs</option>
+                  <option value="runOnce">Run once on startup</option>
+                  <option value="runAll">Run every missed run</option>
+                </select>
+              </div>
+            </div>
           </div>
           <DialogFooter>
             <Button variant="outline" onClick={() => setIsCreateDialogOpen(false)}>
               Cancel
             </Button>
-            <Button onClick={handleCreate} disabled={!scheduleForm.name || !scheduleForm.vmId}>
+            <Button
+              onClick={handleCreate}
+              disabled={
+                !scheduleForm.name ||
+                (targetMode === 'vm' ? !scheduleForm.vmId : parseTags(tagInput).length === 0) ||
+                (scheduleForm.frequency === 'cron' && !scheduleForm.cronExpression) ||
+                paramsIncomplete
+              }
+            >
               Create Schedule
             </Button>
           </DialogFooter>
@@ -9,14 +9,31 @@ import { Label } from '@/components/ui/label'
 import { AlertDialog, AlertDialogAction, AlertDialogCancel, AlertDialogContent, AlertDialogDescription, AlertDialogFooter, AlertDialogHeader, AlertDialogTitle } from '@/components/ui/alert-dialog'
 import { Checkbox } from '@/components/ui/checkbox'
 import { Camera, Trash2, RotateCcw, Clock } from 'lucide-react'
-import type { Snapshot } from '@/lib/types'
+import type { ConfigChangeKind, QuiesceOptions, Snapshot, SnapshotLocation, SnapshotNode } from '@/lib/types'
+import { DEFAULT_QUIESCE, QuiesceFields } from './QuiesceFields'
+
+const formatSize = (bytes: number) => {
+  const gb = bytes / 1024 ** 3
+  return gb >= 1 ? `${gb.toFixed(2)} GB` : `${(bytes / 1024 ** 2).toFixed(1)} MB`
+}
+
+const changeLabels: Record<ConfigChangeKind, string> = {
+  added: 'Added since',
+  removed: 'Removed since',
+  modified: 'Changed since',
+}
+
+// Depth-first, so every snapshot follows its parent
+const flattenTree = (nodes: SnapshotNode[], depth = 0): { node: SnapshotNode; depth: number }[] =>
+  nodes.flatMap((node) => [{ node, depth }, ...flattenTree(node.children, depth + 1)])
 
 interface SnapshotManagerProps {
   vmId: string
+  connectionId?: string | null
   vmName: string
 }
 
-export function SnapshotManager({ vmId, vmName }: SnapshotManagerProps) {
+export function SnapshotManager({ vmId, connectionId, vmName }: SnapshotManagerProps) {
   const queryClient = useQueryClient()
   const [showCreateDialog, setShowCreateDialog] = useState(false)
   const [showDeleteDialog, setShowDeleteDialog] = useState(false)
@@ -26,13 +43,23 @@ export function SnapshotManager({ vmId, vmName }: SnapshotManagerProps) {
   const [snapshotName, setSnapshotName] = useState('')
   const [snapshotDescription, setSnapshotDescription] = useState('')
   const [includeMemory, setIncludeMemory] = useState(false)
+  const [location, setLocation] = useState<SnapshotLocation>('internal')
+  const [quiesce, setQuiesce] = useState<QuiesceOptions>(DEFAULT_QUIESCE)
 
   // Query snapshots
-  const { data: snapshots = [], isLoading, error } = useQuery({
+  const { data: tree = [], isLoading, error } = useQuery({
     queryKey: ['snapshots', vmId],
-    queryFn: () => api.getSnapshots(vmId),
+    queryFn: () => api.getSnapshotTree(vmId, connectionId ?? undefined),
     refetchInterval: 30000, // Refresh every 30 seconds
   })
+  const snapshots = flattenTree(tree)
+
+  // Hardware that reverting would change back
+  const { data: configDiff, isLoading: diffLoading } = useQuery({
+    queryKey: ['snapshot-diff', vmId, selectedSnapshot?.name],
+    queryFn: () => api.diffSnapshotConfig(vmId, selectedSnapshot!.name, connectionId ?? undefined),
+    enabled: showRevertDialog && !!selectedSnapshot,
+  })
 
   // Create snapshot mutation
   const createMutation = useMutation({
@@ -40,7 +67,9 @@ export function SnapshotManager({ vmId, vmName }: SnapshotManagerProps) {
       name: snapshotName,
       description: snapshotDescription || undefined,
       includeMemory,
-    }),
+      quiesce: includeMemory ? DEFAULT_QUIESCE : quiesce,
+      location,
+    }, connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['snapshots', vmId] })
       toast.success(`Snapshot "${snapshotName}" created successfully`)
@@ -48,6 +77,8 @@ export function SnapshotManager({ vmId, vmName }: SnapshotManagerProps) {
       setSnapshotName('')
       setSnapshotDescription('')
       setIncludeMemory(false)
+      setLocation('internal')
+      setQuiesce(DEFAULT_QUIESCE)
     },
     onError: (error) => {
       toast.error(`Failed to create snapshot: ${error}`)
@@ -56,7 +87,7 @@ export function SnapshotManager({ vmId, vmName }: SnapshotManagerProps) {
 
   // Delete snapshot mutation
   const deleteMutation = useMutation({
-    mutationFn: (snapshotName: string) => api.deleteSnapshot(vmId, snapshotName),
+    mutationFn: (snapshotName: string) => api.deleteSnapshot(vmId, snapshotName, connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['snapshots', vmId] })
       toast.success(`Snapshot deleted successfully`)
@@ -70,7 +101,7 @@ export function SnapshotManager({ vmId, vmName }: SnapshotManagerProps) {
 
   // Revert snapshot mutation
   const revertMutation = useMutation({
-    mutationFn: (snapshotName: string) => api.revertSnapshot(vmId, snapshotName),
+    mutationFn: (snapshotName: string) => api.revertSnapshot(vmId, snapshotName, connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['snapshots', vmId] })
@@ -138,12 +169,13 @@ export function SnapshotManager({ vmId, vmName }: SnapshotManagerProps) {
             <p className="text-sm text-muted-foreground">No snapshots yet. Create one to save the current VM state.</p>
           ) : (
             <div className="space-y-3">
-              {snapshots.map((snapshot) => (
+              {snapshots.map(({ node: snapshot, depth }) => (
                 <div
                   key={snapshot.name}
                   className={`flex items-center justify-between p-3 border rounded-lg ${
                     snapshot.isCurrent ? 'border-primary bg-primary/5' : ''
                   }`}
+                  style={{ marginLeft: depth * 24 }}
                 >
                   <div className="flex-1">
                     <div className="flex items-center gap-2">
@@ -156,6 +188,25 @@ export function SnapshotManager({ vmId, vmName }: SnapshotManagerProps) {
                               Current
                             </span>
                           )}
+                          {snapshot.location === 'external' && (
+                            <span
+                              className="text-xs border px-2 py-0.5 rounded"
+                              title={snapshot.disks
+                                .filter((disk) => disk.overlay)
+                                .map((disk) => `${disk.target}: ${disk.overlay}`)
+                                .join('\n')}
+                            >
+                              External
+                            </span>
+                          )}
+                          {snapshot.name.startsWith('scheduled-') && (
+                            <span
+                              className="text-xs border px-2 py-0.5 rounded"
+                              title="Taken by a schedule; snapshot retention policies may prune it"
+                            >
+                              Scheduled
+                            </span>
+                          )}
                         </p>
                         {snapshot.description && (
                           <p className="text-sm text-muted-foreground">{snapshot.description}</p>
@@ -166,9 +217,10 @@ export function SnapshotManager({ vmId, vmName }: SnapshotManagerProps) {
                             {formatDate(snapshot.creationTime)}
                           </span>
                           <span className="capitalize">{snapshot.state}</span>
-                          {snapshot.parent && (
-                            <span className="flex items-center gap-1">
-                              Parent: {snapshot.parent}
+                          {snapshot.hasMemory && <span>With memory</span>}
+                          {snapshot.sizeBytes != null && (
+                            <span title="Approximate host space held by the snapshot">
+                              {formatSize(snapshot.sizeBytes)}
                             </span>
                           )}
                         </div>
@@ -251,6 +303,25 @@ export function SnapshotManager({ vmId, vmName }: SnapshotManagerProps) {
                 <>Disk-only snapshots are faster and smaller, but VMs will restart from shutdown state.</>
               )}
             </p>
+            {!includeMemory && (
+              <QuiesceFields id="snapshot" value={quiesce} onChange={setQuiesce} />
+            )}
+            <div className="flex items-center space-x-2">
+              <Checkbox
+                id="external-snapshot"
+                checked={location === 'external'}
+                onCheckedChange={(checked) => setLocation(checked ? 'external' : 'internal')}
+              />
+              <Label htmlFor="external-snapshot" className="text-sm cursor-pointer">
+                External snapshot (overlay files)
+              </Label>
+            </div>
+            {location === 'external' && (
+              <p className="text-xs text-muted-foreground">
+                Each disk is frozen and later writes go to a new qcow2 overlay next to it, with memory saved to a
+                separate file. Works with raw disks and UEFI VMs.
+              </p>
+            )}
           </div>
           <AlertDialogFooter>
             <AlertDialogCancel>Cancel</AlertDialogCancel>
@@ -271,6 +342,9 @@ export function SnapshotManager({ vmId, vmName }: SnapshotManagerProps) {
             <AlertDialogTitle>Delete Snapshot</AlertDialogTitle>
             <AlertDialogDescription>
               Are you sure you want to delete snapshot "{selectedSnapshot?.name}"? This action cannot be undone.
+              {selectedSnapshot?.location === 'external' && (
+                <> Its overlays are merged into the disk images with block jobs, which can take a while.</>
+              )}
             </AlertDialogDescription>
           </AlertDialogHeader>
           <AlertDialogFooter>
@@ -288,14 +362,50 @@ export function SnapshotManager({ vmId, vmName }: SnapshotManagerProps) {
 
       {/* Revert Snapshot Confirmation */}
       <AlertDialog open={showRevertDialog} onOpenChange={setShowRevertDialog}>
-        <AlertDialogContent>
+        <AlertDialogContent className="max-w-2xl">
           <AlertDialogHeader>
             <AlertDialogTitle>Revert to Snapshot</AlertDialogTitle>
             <AlertDialogDescription>
               Are you sure you want to revert VM "{vmName}" to snapshot "{selectedSnapshot?.name}"?
               All changes made after this snapshot will be lost.
+              {selectedSnapshot?.location === 'external' && (
+                <>
+                  {' '}The VM is stopped and gets new overlays on the snapshot's images.
+                  {selectedSnapshot.memoryFile ? ' It resumes from the saved memory.' : ' It stays shut off.'}
+                </>
+              )}
             </AlertDialogDescription>
           </AlertDialogHeader>
+          <div className="space-y-2 text-sm">
+            <p className="font-medium">Configuration changes since the snapshot</p>
+            {diffLoading ? (
+              <p className="text-muted-foreground">Comparing configurations...</p>
+            ) : !configDiff || configDiff.changes.length === 0 ? (
+              <p className="text-muted-foreground">The VM configuration is unchanged.</p>
+            ) : (
+              <div className="max-h-64 overflow-y-auto space-y-2">
+                {configDiff.changes.map((change) => (
+                  <details key={`${change.section}-${change.item}`} className="border rounded p-2">
+                    <summary className="cursor-pointer">
+                      {changeLabels[change.kind]} snapshot: <span className="font-mono">{change.item}</span>
+                    </summary>
+                    {change.snapshot && (
+                      <div className="mt-2">
+                        <p className="text-xs text-muted-foreground">In snapshot</p>
+                        <pre className="text-xs whitespace-pre-wrap break-all bg-muted p-2 rounded">{change.snapshot}</pre>
+                      </div>
+                    )}
+                    {change.current && (
+                      <div className="mt-2">
+                        <p className="text-xs text-muted-foreground">Current</p>
+                        <pre className="text-xs whitespace-pre-wrap break-all bg-muted p-2 rounded">{change.current}</pre>
+                      </div>
+                    )}
+                  </details>
+                ))}
+              </div>
+            )}
+          </div>
           <AlertDialogFooter>
             <AlertDialogCancel onClick={() => setSelectedSnapshot(null)}>Cancel</AlertDialogCancel>
             <AlertDialogAction
@@ -0,0 +1,429 @@
+import { useEffect, useState } from 'react'
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query'
+import { listen, UnlistenFn } from '@tauri-apps/api/event'
+import { api } from '@/lib/tauri'
+import type { CreateRetentionPolicyRequest, PruneReport, RetentionRules, SnapshotRetentionPolicy, VM } from '@/lib/types'
+import { Button } from '@/components/ui/button'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Dialog, DialogContent, DialogDescription, DialogFooter, DialogHeader, DialogTitle } from '@/components/ui/dialog'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { Badge } from '@/components/ui/badge'
+import { Checkbox } from '@/components/ui/checkbox'
+import { toast } from 'sonner'
+import { Scissors, Plus, Trash2, PlayCircle, PauseCircle, Eye } from 'lucide-react'
+
+const selectClassName =
+  'flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background'
+
+const ruleFields: { key: keyof RetentionRules; label: string; placeholder: string }[] = [
+  { key: 'keepLast', label: 'Keep Last', placeholder: 'snapshots' },
+  { key: 'keepDailyDays', label: 'Keep Daily For', placeholder: 'days' },
+  { key: 'keepWeeklyWeeks', label: 'Keep Weekly For', placeholder: 'weeks' },
+  { key: 'maxAgeDays', label: 'Max Age', placeholder: 'days' },
+]
+
+const emptyRequest = (): CreateRetentionPolicyRequest => ({
+  name: '',
+  vmId: '',
+  tagSelector: null,
+  connectionId: null,
+  rules: { keepLast: 7, keepDailyDays: null, keepWeeklyWeeks: null, maxAgeDays: null },
+})
+
+const describeRules = (rules: RetentionRules) => {
+  const parts = []
+  if (rules.keepLast) parts.push(`last ${rules.keepLast}`)
+  if (rules.keepDailyDays) parts.push(`daily for ${rules.keepDailyDays}d`)
+  if (rules.keepWeeklyWeeks) parts.push(`weekly for ${rules.keepWeeklyWeeks}w`)
+  const keep = parts.length > 0 ? `Keep ${parts.join(', ')}` : 'Keep all'
+  return rules.maxAgeDays ? `${keep}, none older than ${rules.maxAgeDays}d` : keep
+}
+
+function PruneReportSummary({ report }: { report: PruneReport }) {
+  const deleted = report.vms.reduce((sum, vm) => sum + vm.deleted.length, 0)
+  const kept = report.vms.reduce((sum, vm) => sum + vm.kept, 0)
+
+  return (
+    <div className="space-y-1 text-sm">
+      <div className="flex items-center gap-2 text-muted-foreground">
+        {report.dryRun ? 'Preview' : 'Last run'} {new Date(report.finishedAt * 1000).toLocaleString()}:{' '}
+        {report.dryRun ? 'would delete' : 'deleted'} {deleted}, kept {kept} across {report.vms.length} VM(s)
+        {(report.error || report.vms.some((vm) => vm.error)) && <Badge variant="destructive">Errors</Badge>}
+      </div>
+      {report.error && <div className="text-destructive">{report.error}</div>}
+      {report.vms
+        .filter((vm) => vm.deleted.length > 0 || vm.error)
+        .map((vm) => (
+          <div key={vm.vmId} className="text-xs">
+            <span className="font-medium">{vm.vmName}</span>
+            {vm.deleted.length > 0 && (
+              <span className="text-muted-foreground font-mono"> — {vm.deleted.join(', ')}</span>
+            )}
+            {vm.error && <span className="text-destructive"> — {vm.error}</span>}
+          </div>
+        ))}
+    </div>
+  )
+}
+
+export function SnapshotRetentionManager() {
+  const queryClient = useQueryClient()
+  const [isDialogOpen, setIsDialogOpen] = useState(false)
+  const [form, setForm] = useState<CreateRetentionPolicyRequest>(emptyRequest())
+  const [targetMode, setTargetMode] = useState<'vm' | 'tags'>('vm')
+  const [tagInput, setTagInput] = useState('')
+  const [matchAllTags, setMatchAllTags] = useState(false)
+  const [previews, setPreviews] = useState<Record<string, PruneReport>>({})
+
+  const { data: policies, isLoading } = useQuery<SnapshotRetentionPolicy[]>({
+    queryKey: ['snapshot-retention-policies'],
+    queryFn: () => api.listSnapshotRetentionPolicies(),
+  })
+
+  const { data: vms } = useQuery<VM[]>({
+    queryKey: ['vms'],
+    queryFn: () => api.getVms(),
+  })
+
+  // Pick up reports of the background pruning pass
+  useEffect(() => {
+    let unlisten: UnlistenFn | undefined
+
+    const setupListener = async () => {
+      unlisten = await listen<PruneReport>('snapshot-prune', () => {
+        queryClient.invalidateQueries({ queryKey: ['snapshot-retention-policies'] })
+      })
+    }
+
+    setupListener()
+
+    return () => {
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [queryClient])
+
+  const createMutation = useMutation({
+    mutationFn: (request: CreateRetentionPolicyRequest) => api.createSnapshotRetentionPolicy(request),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['snapshot-retention-policies'] })
+      toast.success('Retention policy created')
+      setIsDialogOpen(false)
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to create retention policy: ${error.message}`)
+    },
+  })
+
+  const updateStatusMutation = useMutation({
+    mutationFn: ({ id, enabled }: { id: string; enabled: boolean }) =>
+      api.updateSnapshotRetentionPolicyStatus(id, enabled),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['snapshot-retention-policies'] })
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to update retention policy: ${error.message}`)
+    },
+  })
+
+  const deleteMutation = useMutation({
+    mutationFn: (id: string) => api.deleteSnapshotRetentionPolicy(id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['snapshot-retention-policies'] })
+      toast.success('Retention policy deleted')
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to delete retention policy: ${error.message}`)
+    },
+  })
+
+  const runMutation = useMutation({
+    mutationFn: ({ id, dryRun }: { id: string; dryRun: boolean }) => api.runSnapshotRetentionPolicy(id, dryRun),
+    onSuccess: (report) => {
+      // A preview replaces the stored report on the card until the policy really runs
+      setPreviews((current) => {
+        const next = { ...current }
+        if (report.dryRun) {
+          next[report.policyId] = report
+        } else {
+          delete next[report.policyId]
+        }
+        return next
+      })
+      if (report.dryRun) {
+        return
+      }
+      queryClient.invalidateQueries({ queryKey: ['snapshot-retention-policies'] })
+      queryClient.invalidateQueries({ queryKey: ['snapshots'] })
+      const deleted = report.vms.reduce((sum, vm) => sum + vm.deleted.length, 0)
+      if (report.error || report.vms.some((vm) => vm.error)) {
+        toast.error('Pruning finished with errors', { description: `${deleted} snapshot(s) deleted` })
+      } else {
+        toast.success('Pruning finished', { description: `${deleted} snapshot(s) deleted` })
+      }
+    },
+    onError: (error: Error) => {
+      toast.error(`Failed to run retention policy: ${error.message}`)
+    },
+  })
+
+  const openCreate = () => {
+    setForm(emptyRequest())
+    setTargetMode('vm')
+    setTagInput('')
+    setMatchAllTags(false)
+    setIsDialogOpen(true)
+  }
+
+  const setRule = (key: keyof RetentionRules, value: string) => {
+    const parsed = parseInt(value)
+    setForm({ ...form, rules: { ...form.rules, [key]: parsed > 0 ? parsed : null } })
+  }
+
+  const handleCreate = () => {
+    if (!form.name.trim()) {
+      toast.error('Please enter a policy name')
+      return
+    }
+    if (Object.values(form.rules).every((value) => value == null)) {
+      toast.error('Please set at least one retention rule')
+      return
+    }
+
+    if (targetMode === 'tags') {
+      const tags = tagInput.split(',').map((tag) => tag.trim()).filter((tag) => tag.length > 0)
+      if (tags.length === 0) {
+        toast.error('Please enter at least one tag')
+        return
+      }
+      // Tag policies resolve matching VMs across all connected hosts on every run
+      createMutation.mutate({ ...form, vmId: '', connectionId: null, tagSelector: { tags, matchAll: matchAllTags } })
+      return
+    }
+
+    if (!form.vmId) {
+      toast.error('Please select a VM')
+      return
+    }
+    const vm = vms?.find((v) => v.id === form.vmId)
+    createMutation.mutate({ ...form, tagSelector: null, connectionId: vm?.connectionId ?? null })
+  }
+
+  const handleDelete = (policy: SnapshotRetentionPolicy) => {
+    if (confirm(`Delete retention policy "${policy.name}"? Existing snapshots are kept.`)) {
+      deleteMutation.mutate(policy.id)
+    }
+  }
+
+  const describeTarget = (policy: SnapshotRetentionPolicy) => {
+    if (policy.tagSelector) {
+      return `Tags (${policy.tagSelector.matchAll ? 'all' : 'any'}): ${policy.tagSelector.tags.join(', ')}`
+    }
+    return `VM: ${vms?.find((v) => v.id === policy.vmId)?.name ?? policy.vmId}`
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="flex justify-between items-center">
+        <div>
+          <h2 className="text-2xl font-bold">Snapshot Retention</h2>
+          <p className="text-muted-foreground">
+            Prune scheduled snapshots automatically; manually created snapshots are never touched
+          </p>
+        </div>
+        <Button onClick={openCreate}>
+          <Plus className="mr-2 h-4 w-4" />
+          Create Policy
+        </Button>
+      </div>
+
+      {isLoading ? (
+        <div className="text-muted-foreground">Loading retention policies...</div>
+      ) : !policies || policies.length === 0 ? (
+        <Card>
+          <CardContent className="py-8 text-center text-muted-foreground">
+            No retention policies. Scheduled snapshots are kept until deleted by hand.
+          </CardContent>
+        </Card>
+      ) : (
+        <div className="grid gap-4">
+          {policies.map((policy) => {
+            const report = previews[policy.id] ?? policy.lastReport
+            return (
+              <Card key={policy.id}>
+                <CardHeader>
+                  <div className="flex justify-between items-start">
+                    <div className="flex-1 min-w-0">
+                      <CardTitle className="text-lg flex items-center gap-2">
+                        <Scissors className="h-4 w-4" />
+                        {policy.name}
+                      </CardTitle>
+                      <CardDescription className="mt-1">
+                        {describeTarget(policy)} • {describeRules(policy.rules)}
+                      </CardDescription>
+                    </div>
+                    <Badge variant={policy.enabled ? 'default' : 'secondary'}>
+                      {policy.enabled ? 'Enabled' : 'Disabled'}
+                    </Badge>
+                  </div>
+                </CardHeader>
+                <CardContent>
+                  <div className="space-y-3">
+                    {report ? (
+                      <PruneReportSummary report={report} />
+                    ) : (
+                      <div className="text-sm text-muted-foreground">Not run yet; enabled policies run hourly</div>
+                    )}
+                    <div className="flex gap-2">
+                      <Button
+                        size="sm"
+                        variant="outline"
+                        onClick={() => runMutation.mutate({ id: policy.id, dryRun: true })}
+                        disabled={runMutation.isPending}
+                      >
+                        <Eye className="mr-1 h-3 w-3" />
+                        Preview
+                      </Button>
+                      <Button
+                        size="sm"
+                        variant="outline"
+                        onClick={() => runMutation.mutate({ id: policy.id, dryRun: false })}
+                        disabled={runMutation.isPending}
+                      >
+                        <Scissors className="mr-1 h-3 w-3" />
+                        Prune Now
+                      </Button>
+                      <Button
+                        size="sm"
+                        variant="outline"
+                        onClick={() => updateStatusMutation.mutate({ id: policy.id, enabled: !policy.enabled })}
+                      >
+                        {policy.enabled ? (
+                          <>
+                            <PauseCircle className="mr-1 h-3 w-3" />
+                            Disable
+                          </>
+                        ) : (
+                          <>
+                            <PlayCircle className="mr-1 h-3 w-3" />
+                            Enable
+                          </>
+                        )}
+                      </Button>
+                      <Button size="sm" variant="outline" onClick={() => handleDelete(policy)}>
+                        <Trash2 className="h-3 w-3" />
+                      </Button>
+                    </div>
+                  </div>
+                </CardContent>
+              </Card>
+            )
+          })}
+        </div>
+      )}
+
+      <Dialog open={isDialogOpen} onOpenChange={setIsDialogOpen}>
+        <DialogContent className="max-w-2xl">
+          <DialogHeader>
+            <DialogTitle>Create Retention Policy</DialogTitle>
+            <DialogDescription>
+              Applies to snapshots taken by scheduled operations. A snapshot is kept if any keep rule selects it;
+              the max age removes older ones regardless.
+            </DialogDescription>
+          </DialogHeader>
+          <div className="space-y-4">
+            <div className="grid grid-cols-2 gap-4">
+              <div>
+                <Label htmlFor="retention-name">Name</Label>
+                <Input
+                  id="retention-name"
+                  value={form.name}
+                  onChange={(e) => setForm({ ...form, name: e.target.value })}
+                  placeholder="e.g., Production snapshots"
+                />
+              </div>
+              <div>
+                <Label htmlFor="retention-target-mode">Target</Label>
+                <select
+                  id="retention-target-mode"
+                  value={targetMode}
+                  onChange={(e) => setTargetMode(e.target.value as 'vm' | 'tags')}
+                  className={selectClassName}
+                >
+                  <option value="vm">A single VM</option>
+                  <option value="tags">All VMs with tags</option>
+                </select>
+              </div>
+            </div>
+
+            {targetMode === 'vm' ? (
+              <div>
+                <Label htmlFor="retention-vm">VM</Label>
+                <select
+                  id="retention-vm"
+                  value={form.vmId}
+                  onChange={(e) => setForm({ ...form, vmId: e.target.value })}
+                  className={selectClassName}
+                >
+                  <option value="">Select a VM</option>
+                  {vms?.map((vm) => (
+                    <option key={vm.id} value={vm.id}>{vm.name}</option>
+                  ))}
+                </select>
+              </div>
+            ) : (
+              <div className="space-y-3">
+                <div>
+                  <Label htmlFor="retention-tags">Tags</Label>
+                  <Input
+                    id="retention-tags"
+                    value={tagInput}
+                    onChange={(e) => setTagInput(e.target.value)}
+                    placeholder="e.g. production, database"
+                  />
+                </div>
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="retention-match-all"
+                    checked={matchAllTags}
+                    onCheckedChange={(checked) => setMatchAllTags(checked === true)}
+                  />
+                  <Label htmlFor="retention-match-all">VM must have every tag</Label>
+                </div>
+                <p className="text-xs text-muted-foreground">
+                  VMs with a policy of their own are skipped by tag policies.
+                </p>
+              </div>
+            )}
+
+            <div className="grid grid-cols-4 gap-4">
+              {ruleFields.map((field) => (
+                <div key={field.key}>
+                  <Label htmlFor={`retention-${field.key}`}>{field.label}</Label>
+                  <Input
+                    id={`retention-${field.key}`}
+                    type="number"
+                    min="1"
+                    value={form.rules[field.key] ?? ''}
+                    onChange={(e) => setRule(field.key, e.target.value)}
+                    placeholder={field.placeholder}
+                  />
+                </div>
+              ))}
+            </div>
+          </div>
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setIsDialogOpen(false)}>
+              Cancel
+            </Button>
+            <Button onClick={handleCreate} disabled={createMutation.isPending}>
+              {createMutation.isPending ? 'Creating...' : 'Create Policy'}
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+    </div>
+  )
+}
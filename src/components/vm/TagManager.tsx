@@ -10,16 +10,17 @@ import { X, Plus, Tag } from 'lucide-react'
 
 interface TagManagerProps {
   vmId: string
+  connectionId?: string | null
   currentTags: string[]
 }
 
-export function TagManager({ vmId, currentTags }: TagManagerProps) {
+export function TagManager({ vmId, connectionId, currentTags }: TagManagerProps) {
   const queryClient = useQueryClient()
   const [newTag, setNewTag] = useState('')
   const [isAdding, setIsAdding] = useState(false)
 
   const addTagMutation = useMutation({
-    mutationFn: (tag: string) => api.addVmTags(vmId, [tag]),
+    mutationFn: (tag: string) => api.addVmTags(vmId, [tag], connectionId ?? undefined),
     onSuccess: (_data, tag) => {
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -33,7 +34,7 @@ export function TagManager({ vmId, currentTags }: TagManagerProps) {
   })
 
   const removeTagMutation = useMutation({
-    mutationFn: (tag: string) => api.removeVmTags(vmId, [tag]),
+    mutationFn: (tag: string) => api.removeVmTags(vmId, [tag], connectionId ?? undefined),
     onSuccess: (_data, tag) => {
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
       queryClient.invalidateQueries({ queryKey: ['vms'] })
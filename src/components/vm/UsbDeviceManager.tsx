@@ -51,7 +51,7 @@ export function UsbDeviceManager({ vm, trigger }: UsbDeviceManagerProps) {
   // Fetch USB devices attached to this VM
   const { data: vmDevices = [], isLoading: vmLoading, refetch: refetchVm } = useQuery<UsbDevice[]>({
     queryKey: ['vm-usb-devices', vm.id],
-    queryFn: () => api.getVmUsbDevices(vm.id),
+    queryFn: () => api.getVmUsbDevices(vm.id, vm.connectionId ?? undefined),
     enabled: open,
     refetchInterval: open ? 5000 : false,
   })
@@ -59,7 +59,7 @@ export function UsbDeviceManager({ vm, trigger }: UsbDeviceManagerProps) {
   // Attach USB device mutation
   const attachMutation = useMutation({
     mutationFn: ({ vendorId, productId }: { vendorId: string; productId: string }) =>
-      api.attachUsbDevice(vm.id, vendorId, productId),
+      api.attachUsbDevice(vm.id, vendorId, productId, vm.connectionId ?? undefined),
     onSuccess: (_, variables) => {
       queryClient.invalidateQueries({ queryKey: ['usb-devices'] })
       queryClient.invalidateQueries({ queryKey: ['vm-usb-devices', vm.id] })
@@ -77,7 +77,7 @@ export function UsbDeviceManager({ vm, trigger }: UsbDeviceManagerProps) {
   // Detach USB device mutation
   const detachMutation = useMutation({
     mutationFn: ({ vendorId, productId }: { vendorId: string; productId: string }) =>
-      api.detachUsbDevice(vm.id, vendorId, productId),
+      api.detachUsbDevice(vm.id, vendorId, productId, vm.connectionId ?? undefined),
     onSuccess: (_, variables) => {
       queryClient.invalidateQueries({ queryKey: ['usb-devices'] })
       queryClient.invalidateQueries({ queryKey: ['vm-usb-devices', vm.id] })
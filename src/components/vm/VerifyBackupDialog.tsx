@@ -0,0 +1,141 @@
+import { useState, useEffect } from 'react'
+import { useMutation, useQueryClient } from '@tanstack/react-query'
+import { api, waitForJob } from '@/lib/tauri'
+import { Button } from '@/components/ui/button'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { Checkbox } from '@/components/ui/checkbox'
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+  DialogTrigger,
+} from '@/components/ui/dialog'
+import { ShieldCheck, CheckCircle2, XCircle } from 'lucide-react'
+import { toast } from 'sonner'
+import type { BackupRun, BackupVerification } from '@/lib/types'
+
+interface VerifyBackupDialogProps {
+  run: BackupRun
+  trigger?: React.ReactNode
+}
+
+export function VerifyBackupDialog({ run, trigger }: VerifyBackupDialogProps) {
+  const [open, setOpen] = useState(false)
+  const [boot, setBoot] = useState(false)
+  const [bootTimeoutSecs, setBootTimeoutSecs] = useState(180)
+  const [result, setResult] = useState<BackupVerification | null>(null)
+  const queryClient = useQueryClient()
+
+  // Show the stored result until a new verification finishes
+  useEffect(() => {
+    if (open) {
+      setBoot(false)
+      setBootTimeoutSecs(180)
+      setResult(run.verification ?? null)
+    }
+  }, [open, run])
+
+  const verifyMutation = useMutation({
+    mutationFn: async () =>
+      waitForJob<BackupVerification>(await api.verifyBackup(run.configId, run.id, { boot, bootTimeoutSecs })),
+    onSuccess: (verification) => {
+      setResult(verification)
+      queryClient.invalidateQueries({ queryKey: ['backup-runs', run.configId] })
+      if (verification.status === 'passed') {
+        toast.success('Backup Verified', { description: `${verification.checks.length} check(s) passed` })
+      } else {
+        toast.error('Verification Failed', {
+          description: `${verification.checks.filter((check) => !check.ok).length} check(s) failed`,
+        })
+      }
+    },
+    onError: (error: Error) => {
+      toast.error('Verification Failed', { description: error.message })
+    },
+  })
+
+  return (
+    <Dialog open={open} onOpenChange={setOpen}>
+      <DialogTrigger asChild>
+        {trigger || (
+          <Button variant="ghost" size="sm">
+            <ShieldCheck className="mr-2 h-4 w-4" />
+            Verify
+          </Button>
+        )}
+      </DialogTrigger>
+      <DialogContent className="sm:max-w-[560px]">
+        <DialogHeader>
+          <DialogTitle>Verify Backup</DialogTitle>
+          <DialogDescription>
+            Re-hash the restore point of "{run.vmName}" from {new Date(run.startedAt * 1000).toLocaleString()} and the
+            points it builds on, and check its disk images with qemu-img.
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="grid gap-4 py-4">
+          <div className="flex items-center space-x-2">
+            <Checkbox
+              id="verify-boot-sandbox"
+              checked={boot}
+              onCheckedChange={(checked) => setBoot(checked === true)}
+              disabled={verifyMutation.isPending}
+            />
+            <Label htmlFor="verify-boot-sandbox">Boot a throwaway copy without network access</Label>
+          </div>
+          {boot && (
+            <div className="grid gap-2">
+              <Label htmlFor="verify-timeout">Boot Timeout (seconds)</Label>
+              <Input
+                id="verify-timeout"
+                type="number"
+                min="10"
+                value={bootTimeoutSecs}
+                onChange={(e) => setBootTimeoutSecs(parseInt(e.target.value) || 180)}
+                disabled={verifyMutation.isPending}
+              />
+              <p className="text-xs text-muted-foreground">
+                The sandbox uses the VM's memory and CPUs. It passes once its guest agent answers, or if it is still
+                running at the timeout, and is destroyed afterwards.
+              </p>
+            </div>
+          )}
+
+          {result && (
+            <div className="space-y-1 text-sm">
+              <div className="font-medium">
+                {result.status === 'passed' ? 'Passed' : 'Failed'} on {new Date(result.finishedAt * 1000).toLocaleString()}
+              </div>
+              {result.checks.map((check, index) => (
+                <div key={index} className="flex items-start gap-2">
+                  {check.ok ? (
+                    <CheckCircle2 className="h-4 w-4 mt-0.5 text-green-600 shrink-0" />
+                  ) : (
+                    <XCircle className="h-4 w-4 mt-0.5 text-destructive shrink-0" />
+                  )}
+                  <div className="min-w-0">
+                    <span className="font-medium">{check.name}</span>
+                    <span className="text-muted-foreground"> — {check.detail}</span>
+                  </div>
+                </div>
+              ))}
+            </div>
+          )}
+        </div>
+
+        <DialogFooter>
+          <Button variant="outline" onClick={() => setOpen(false)} disabled={verifyMutation.isPending}>
+            Close
+          </Button>
+          <Button onClick={() => verifyMutation.mutate()} disabled={verifyMutation.isPending}>
+            {verifyMutation.isPending ? 'Verifying...' : 'Verify'}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  )
+}
@@ -25,6 +25,7 @@ import {
 import { Play, Square, Pause, Trash2, Monitor, RotateCcw, Info, Copy, XCircle, Edit, Loader2 } from 'lucide-react'
 import type { VM } from '@/lib/types'
 import { api } from '@/lib/tauri'
+import { vmDetailsPath } from '@/lib/utils'
 import { useMutation, useQueryClient } from '@tanstack/react-query'
 import { toast } from 'sonner'
 import { useState } from 'react'
@@ -40,7 +41,7 @@ export function VmCard({ vm }: VmCardProps) {
   const [deleteDisks, setDeleteDisks] = useState(false)
 
   const startMutation = useMutation({
-    mutationFn: () => api.startVm(vm.id),
+    mutationFn: () => api.startVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`VM "${vm.name}" started successfully`)
@@ -51,7 +52,7 @@ export function VmCard({ vm }: VmCardProps) {
   })
 
   const stopMutation = useMutation({
-    mutationFn: () => api.stopVm(vm.id),
+    mutationFn: () => api.stopVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`VM "${vm.name}" stopped successfully`)
@@ -62,7 +63,7 @@ export function VmCard({ vm }: VmCardProps) {
   })
 
   const pauseMutation = useMutation({
-    mutationFn: () => api.pauseVm(vm.id),
+    mutationFn: () => api.pauseVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`VM "${vm.name}" paused successfully`)
@@ -73,7 +74,7 @@ export function VmCard({ vm }: VmCardProps) {
   })
 
   const resumeMutation = useMutation({
-    mutationFn: () => api.resumeVm(vm.id),
+    mutationFn: () => api.resumeVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`VM "${vm.name}" resumed successfully`)
@@ -84,7 +85,7 @@ export function VmCard({ vm }: VmCardProps) {
   })
 
   const deleteMutation = useMutation({
-    mutationFn: () => api.deleteVm(vm.id, deleteDisks, false),
+    mutationFn: () => api.deleteVm(vm.id, deleteDisks, false, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       const diskMsg = deleteDisks ? ' (including disks)' : ''
@@ -97,7 +98,7 @@ export function VmCard({ vm }: VmCardProps) {
   })
 
   const openConsoleMutation = useMutation({
-    mutationFn: () => api.openVncConsole(vm.id),
+    mutationFn: () => api.openVncConsole(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       toast.success('VNC console opened')
     },
@@ -107,7 +108,7 @@ export function VmCard({ vm }: VmCardProps) {
   })
 
   const rebootMutation = useMutation({
-    mutationFn: () => api.rebootVm(vm.id),
+    mutationFn: () => api.rebootVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`VM "${vm.name}" rebooted successfully`)
@@ -138,7 +139,7 @@ export function VmCard({ vm }: VmCardProps) {
       <ContextMenuTrigger asChild>
     <Card
       className="bg-[var(--panel-bg)] cursor-pointer transition-shadow hover:shadow-md"
-      onDoubleClick={() => navigate(`/vms/${vm.id}`)}
+      onDoubleClick={() => navigate(vmDetailsPath(vm))}
     >
       <CardHeader className="py-3 px-4">
         <CardTitle className="flex items-center justify-between text-desktop-lg font-semibold">
@@ -238,7 +239,7 @@ export function VmCard({ vm }: VmCardProps) {
               size="sm"
               variant="outline"
               className="w-full h-7 text-desktop-xs"
-              onClick={() => navigate(`/vms/${vm.id}`)}
+              onClick={() => navigate(vmDetailsPath(vm))}
             >
               <Info className="w-3.5 h-3.5 mr-1" />
               View Details & Snapshots
@@ -248,7 +249,7 @@ export function VmCard({ vm }: VmCardProps) {
           {/* Clone button - only show when VM is stopped */}
           {vm.state === 'stopped' && (
             <div className="pt-1.5 border-t border-[var(--panel-border)]">
-              <CloneVmDialog vmId={vm.id} vmName={vm.name} />
+              <CloneVmDialog vmId={vm.id} connectionId={vm.connectionId} vmName={vm.name} />
             </div>
           )}
 
@@ -336,7 +337,7 @@ export function VmCard({ vm }: VmCardProps) {
             <ContextMenuItem icon={<Square className="w-3.5 h-3.5" />} onClick={() => stopMutation.mutate()}>
               Stop
             </ContextMenuItem>
-            <ContextMenuItem icon={<XCircle className="w-3.5 h-3.5" />} onClick={() => api.forceStopVm(vm.id)}>
+            <ContextMenuItem icon={<XCircle className="w-3.5 h-3.5" />} onClick={() => api.forceStopVm(vm.id, vm.connectionId ?? undefined)}>
               Force Stop
             </ContextMenuItem>
             <ContextMenuItem icon={<RotateCcw className="w-3.5 h-3.5" />} onClick={() => rebootMutation.mutate()}>
@@ -363,7 +364,7 @@ export function VmCard({ vm }: VmCardProps) {
         </ContextMenuItem>
         <ContextMenuItem
           icon={<Edit className="w-3.5 h-3.5" />}
-          onClick={() => navigate(`/vms/${vm.id}`)}
+          onClick={() => navigate(vmDetailsPath(vm))}
           shortcut="Enter"
         >
           Open Details
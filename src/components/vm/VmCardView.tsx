@@ -37,14 +37,14 @@ function VmCard({ vm, isSelected, onToggleSelect, onDoubleClick, onOpenRename, o
   // Poll stats for running VMs
   const { data: vmStats } = useQuery({
     queryKey: ['vm-stats', vm.id],
-    queryFn: () => api.getVmStats(vm.id),
+    queryFn: () => api.getVmStats(vm.id, vm.connectionId ?? undefined),
     enabled: vm.state === 'running',
     refetchInterval: 2000,
   })
 
   // VM action mutations
   const startMutation = useMutation({
-    mutationFn: () => api.startVm(vm.id),
+    mutationFn: () => api.startVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} started`)
@@ -53,7 +53,7 @@ function VmCard({ vm, isSelected, onToggleSelect, onDoubleClick, onOpenRename, o
   })
 
   const stopMutation = useMutation({
-    mutationFn: () => api.forceStopVm(vm.id),
+    mutationFn: () => api.forceStopVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} stopped`)
@@ -62,7 +62,7 @@ function VmCard({ vm, isSelected, onToggleSelect, onDoubleClick, onOpenRename, o
   })
 
   const pauseMutation = useMutation({
-    mutationFn: () => api.pauseVm(vm.id),
+    mutationFn: () => api.pauseVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} paused`)
@@ -71,7 +71,7 @@ function VmCard({ vm, isSelected, onToggleSelect, onDoubleClick, onOpenRename, o
   })
 
   const openConsoleMutation = useMutation({
-    mutationFn: () => api.openConsoleWindow(vm.id, vm.name),
+    mutationFn: () => api.openConsoleWindow(vm.id, vm.name, vm.connectionId ?? undefined),
     onError: (error) => toast.error(`Failed to open console: ${error}`),
   })
 
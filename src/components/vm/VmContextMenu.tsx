@@ -51,7 +51,7 @@ export function VmContextMenu({ vm, children, onOpenDetails, onOpenRename, onOpe
 
   // Mutations
   const startMutation = useMutation({
-    mutationFn: () => api.startVm(vm.id),
+    mutationFn: () => api.startVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} started`)
@@ -60,7 +60,7 @@ export function VmContextMenu({ vm, children, onOpenDetails, onOpenRename, onOpe
   })
 
   const stopMutation = useMutation({
-    mutationFn: () => api.forceStopVm(vm.id),
+    mutationFn: () => api.forceStopVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} stopped`)
@@ -69,7 +69,7 @@ export function VmContextMenu({ vm, children, onOpenDetails, onOpenRename, onOpe
   })
 
   const pauseMutation = useMutation({
-    mutationFn: () => api.pauseVm(vm.id),
+    mutationFn: () => api.pauseVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} paused`)
@@ -78,7 +78,7 @@ export function VmContextMenu({ vm, children, onOpenDetails, onOpenRename, onOpe
   })
 
   const resumeMutation = useMutation({
-    mutationFn: () => api.resumeVm(vm.id),
+    mutationFn: () => api.resumeVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} resumed`)
@@ -87,7 +87,7 @@ export function VmContextMenu({ vm, children, onOpenDetails, onOpenRename, onOpe
   })
 
   const rebootMutation = useMutation({
-    mutationFn: () => api.rebootVm(vm.id),
+    mutationFn: () => api.rebootVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} rebooted`)
@@ -96,7 +96,7 @@ export function VmContextMenu({ vm, children, onOpenDetails, onOpenRename, onOpe
   })
 
   const hibernateMutation = useMutation({
-    mutationFn: () => api.hibernateVm(vm.id),
+    mutationFn: () => api.hibernateVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} hibernated - will resume on next start`)
@@ -105,7 +105,7 @@ export function VmContextMenu({ vm, children, onOpenDetails, onOpenRename, onOpe
   })
 
   const cloneMutation = useMutation({
-    mutationFn: () => api.cloneVm(vm.id, `${vm.name}-clone`),
+    mutationFn: () => api.cloneVm(vm.id, `${vm.name}-clone`, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} cloned`)
@@ -117,7 +117,7 @@ export function VmContextMenu({ vm, children, onOpenDetails, onOpenRename, onOpe
     mutationFn: () => api.createSnapshot(vm.id, {
       name: `snapshot-${Date.now()}`,
       description: 'Quick snapshot from context menu',
-    }),
+    }, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['snapshots', vm.id] })
       toast.success('Snapshot created')
@@ -127,7 +127,7 @@ export function VmContextMenu({ vm, children, onOpenDetails, onOpenRename, onOpe
 
   const handleOpenConsole = async () => {
     try {
-      await api.openConsoleWindow(vm.id, vm.name)
+      await api.openConsoleWindow(vm.id, vm.name, vm.connectionId ?? undefined)
     } catch (err) {
       toast.error(`Failed to open console: ${err}`)
     }
@@ -135,7 +135,7 @@ export function VmContextMenu({ vm, children, onOpenDetails, onOpenRename, onOpe
 
   const handleExportXml = async () => {
     try {
-      const xml = await api.exportVm(vm.id)
+      const xml = await api.exportVm(vm.id, vm.connectionId ?? undefined)
       // Copy to clipboard
       await navigator.clipboard.writeText(xml)
       toast.success('VM XML copied to clipboard')
@@ -19,7 +19,7 @@ export function VmInfoTooltip({ vm, children }: VmInfoTooltipProps) {
   // Fetch live stats for running VMs
   const { data: vmStats } = useQuery({
     queryKey: ['vm-stats', vm.id],
-    queryFn: () => api.getVmStats(vm.id),
+    queryFn: () => api.getVmStats(vm.id, vm.connectionId ?? undefined),
     enabled: vm.state === 'running',
     staleTime: 2000,
   })
@@ -48,15 +48,15 @@ function VmRow({ vm, isSelected, onToggleSelect, onDoubleClick, onOpenRename, on
   // Poll stats for running VMs
   const { data: vmStats } = useQuery({
     queryKey: ['vm-stats', vm.id],
-    queryFn: () => api.getVmStats(vm.id),
+    queryFn: () => api.getVmStats(vm.id, vm.connectionId ?? undefined),
     enabled: vm.state === 'running',
     refetchInterval: 2000,
   })
 
   // Check guest agent status for running VMs
   const { data: agentStatus } = useQuery({
-    queryKey: ['guestAgentStatus', vm.name],
-    queryFn: () => api.checkGuestAgentStatus(vm.name),
+    queryKey: ['guestAgentStatus', vm.connectionId, vm.name],
+    queryFn: () => api.checkGuestAgentStatus(vm.name, vm.connectionId ?? undefined),
     enabled: vm.state === 'running',
     refetchInterval: 10000, // Check every 10 seconds
     retry: false,
@@ -64,8 +64,8 @@ function VmRow({ vm, isSelected, onToggleSelect, onDoubleClick, onOpenRename, on
 
   // Get guest network info if agent is available
   const { data: guestNetworkInfo } = useQuery({
-    queryKey: ['guestNetworkInfo', vm.name],
-    queryFn: () => api.getGuestNetworkInfo(vm.name),
+    queryKey: ['guestNetworkInfo', vm.connectionId, vm.name],
+    queryFn: () => api.getGuestNetworkInfo(vm.name, vm.connectionId ?? undefined),
     enabled: agentStatus?.available === true,
     refetchInterval: 15000,
     retry: false,
@@ -73,8 +73,8 @@ function VmRow({ vm, isSelected, onToggleSelect, onDoubleClick, onOpenRename, on
 
   // Get guest system info for tooltip
   const { data: guestSystemInfo } = useQuery({
-    queryKey: ['guestSystemInfo', vm.name],
-    queryFn: () => api.getGuestSystemInfo(vm.name),
+    queryKey: ['guestSystemInfo', vm.connectionId, vm.name],
+    queryFn: () => api.getGuestSystemInfo(vm.name, vm.connectionId ?? undefined),
     enabled: agentStatus?.available === true,
     refetchInterval: 30000,
     retry: false,
@@ -82,7 +82,7 @@ function VmRow({ vm, isSelected, onToggleSelect, onDoubleClick, onOpenRename, on
 
   // VM action mutations
   const startMutation = useMutation({
-    mutationFn: () => api.startVm(vm.id),
+    mutationFn: () => api.startVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} started`)
@@ -91,7 +91,7 @@ function VmRow({ vm, isSelected, onToggleSelect, onDoubleClick, onOpenRename, on
   })
 
   const stopMutation = useMutation({
-    mutationFn: () => api.forceStopVm(vm.id),
+    mutationFn: () => api.forceStopVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} stopped`)
@@ -100,7 +100,7 @@ function VmRow({ vm, isSelected, onToggleSelect, onDoubleClick, onOpenRename, on
   })
 
   const pauseMutation = useMutation({
-    mutationFn: () => api.pauseVm(vm.id),
+    mutationFn: () => api.pauseVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} paused`)
@@ -109,7 +109,7 @@ function VmRow({ vm, isSelected, onToggleSelect, onDoubleClick, onOpenRename, on
   })
 
   const resumeMutation = useMutation({
-    mutationFn: () => api.resumeVm(vm.id),
+    mutationFn: () => api.resumeVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} resumed`)
@@ -118,7 +118,7 @@ function VmRow({ vm, isSelected, onToggleSelect, onDoubleClick, onOpenRename, on
   })
 
   const hibernateMutation = useMutation({
-    mutationFn: () => api.hibernateVm(vm.id),
+    mutationFn: () => api.hibernateVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       toast.success(`${vm.name} hibernated`)
@@ -127,7 +127,7 @@ function VmRow({ vm, isSelected, onToggleSelect, onDoubleClick, onOpenRename, on
   })
 
   const openConsoleMutation = useMutation({
-    mutationFn: () => api.openConsoleWindow(vm.id, vm.name),
+    mutationFn: () => api.openConsoleWindow(vm.id, vm.name, vm.connectionId ?? undefined),
     onError: (error) => toast.error(`Failed to open console: ${error}`),
   })
 
@@ -379,7 +379,7 @@ function VmRow({ vm, isSelected, onToggleSelect, onDoubleClick, onOpenRename, on
         )}
         <ContextMenuItem onClick={async () => {
           try {
-            await api.cloneVm(vm.id, `${vm.name}-clone`)
+            await api.cloneVm(vm.id, `${vm.name}-clone`, vm.connectionId ?? undefined)
             queryClient.invalidateQueries({ queryKey: ['vms'] })
             toast.success('VM cloned successfully')
           } catch (error) {
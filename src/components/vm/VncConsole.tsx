@@ -7,21 +7,22 @@ import { Monitor, RefreshCw, ExternalLink } from 'lucide-react'
 
 interface VncConsoleProps {
   vmId: string
+  connectionId?: string | null
   vmName: string
 }
 
-export function VncConsole({ vmId, vmName }: VncConsoleProps) {
+export function VncConsole({ vmId, connectionId, vmName }: VncConsoleProps) {
   // Get VNC connection info
   const { data: vncInfo, isLoading, error, refetch } = useQuery({
     queryKey: ['vnc-info', vmId],
-    queryFn: () => api.getVncInfo(vmId),
+    queryFn: () => api.getVncInfo(vmId, connectionId ?? undefined),
     retry: 2,
     refetchOnWindowFocus: false,
   })
 
   // Open external VNC viewer mutation
   const openVncMutation = useMutation({
-    mutationFn: () => api.openVncConsole(vmId),
+    mutationFn: () => api.openVncConsole(vmId, connectionId ?? undefined),
     onSuccess: () => {
       toast.success('Opening VNC viewer...')
     },
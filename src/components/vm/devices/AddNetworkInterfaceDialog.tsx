@@ -79,7 +79,7 @@ export function AddNetworkInterfaceDialog({ vm, children }: AddNetworkInterfaceD
   const attachMutation = useMutation({
     mutationFn: () => {
       if (interfaceType === 'network') {
-        return api.attachInterface(vm.id, source, model, macAddress || undefined)
+        return api.attachInterface(vm.id, source, model, macAddress || undefined, vm.connectionId ?? undefined)
       }
       return api.attachInterfaceAdvanced(vm.id, interfaceType, source, model, {
         macAddress: macAddress || undefined,
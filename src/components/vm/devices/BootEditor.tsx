@@ -78,7 +78,7 @@ export function BootEditor({ vm, compact }: BootEditorProps) {
 
   // Load autostart status on mount
   useEffect(() => {
-    api.getVmAutostart(vm.id)
+    api.getVmAutostart(vm.id, vm.connectionId ?? undefined)
       .then((enabled) => {
         setAutostart(enabled)
       })
@@ -93,7 +93,7 @@ export function BootEditor({ vm, compact }: BootEditorProps) {
   // Load kernel boot settings on mount
   useEffect(() => {
     setKernelBootLoading(true)
-    api.getKernelBootSettings(vm.id)
+    api.getKernelBootSettings(vm.id, vm.connectionId ?? undefined)
       .then((settings) => {
         setKernelBoot(settings)
         setOriginalKernelBoot(settings)
@@ -114,7 +114,7 @@ export function BootEditor({ vm, compact }: BootEditorProps) {
   // Handle autostart toggle
   const handleAutostartChange = async (enabled: boolean) => {
     try {
-      await api.setVmAutostart(vm.id, enabled)
+      await api.setVmAutostart(vm.id, enabled, vm.connectionId ?? undefined)
       setAutostart(enabled)
     } catch (error) {
       console.error('Failed to set autostart:', error)
@@ -203,7 +203,7 @@ export function BootEditor({ vm, compact }: BootEditorProps) {
     setBootOrderSuccess(false)
 
     try {
-      await api.updateVmBootOrder(vm.id, bootOrder)
+      await api.updateVmBootOrder(vm.id, bootOrder, vm.connectionId ?? undefined)
       setBootOrderSuccess(true)
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -28,14 +28,14 @@ export function CdromEditor({ vm, compact }: CdromEditorProps) {
 
   // Fetch storage pools
   const { data: storagePools = [], isLoading: poolsLoading } = useQuery({
-    queryKey: ['storage-pools'],
-    queryFn: () => api.getStoragePools(),
+    queryKey: ['storage-pools', vm.connectionId],
+    queryFn: () => api.getStoragePools(vm.connectionId ?? undefined),
   })
 
   // Fetch volumes for selected pool
   const { data: volumes = [], isLoading: volumesLoading } = useQuery({
-    queryKey: ['volumes', selectedPool],
-    queryFn: () => api.getVolumes(selectedPool),
+    queryKey: ['volumes', selectedPool, vm.connectionId],
+    queryFn: () => api.getVolumes(selectedPool, vm.connectionId ?? undefined),
     enabled: !!selectedPool,
   })
 
@@ -54,7 +54,7 @@ export function CdromEditor({ vm, compact }: CdromEditorProps) {
 
   // Mount ISO mutation
   const mountMutation = useMutation({
-    mutationFn: (isoPath: string) => api.mountIso(vm.id, isoPath),
+    mutationFn: (isoPath: string) => api.mountIso(vm.id, isoPath, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -69,7 +69,7 @@ export function CdromEditor({ vm, compact }: CdromEditorProps) {
 
   // Eject CDROM mutation
   const ejectMutation = useMutation({
-    mutationFn: () => api.ejectCdrom(vm.id),
+    mutationFn: () => api.ejectCdrom(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
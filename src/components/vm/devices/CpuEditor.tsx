@@ -20,7 +20,7 @@ function CpuModelSection({ vm }: { vm: VM }) {
   // Get current CPU model
   const { data: cpuConfig, refetch: refetchCpuModel } = useQuery({
     queryKey: ['cpuModel', vm.id],
-    queryFn: () => api.getCpuModel(vm.id),
+    queryFn: () => api.getCpuModel(vm.id, vm.connectionId ?? undefined),
   })
 
   // Get available CPU models
@@ -53,7 +53,7 @@ function CpuModelSection({ vm }: { vm: VM }) {
 
   // Apply mutation
   const applyMutation = useMutation({
-    mutationFn: () => api.setCpuModel(vm.id, mode, mode === 'custom' ? customModel : undefined),
+    mutationFn: () => api.setCpuModel(vm.id, mode, mode === 'custom' ? customModel : undefined, vm.connectionId ?? undefined),
     onSuccess: () => {
       toast.success('CPU model updated')
       refetchCpuModel()
@@ -163,7 +163,7 @@ function CpuPinningSection({ vm }: { vm: VM }) {
   // Get current pinning configuration
   const { data: pinnings = [], refetch: refetchPinning } = useQuery({
     queryKey: ['cpuPinning', vm.id],
-    queryFn: () => api.getCpuPinning(vm.id),
+    queryFn: () => api.getCpuPinning(vm.id, vm.connectionId ?? undefined),
   })
 
   const hostCpuCount = hostInfo?.cpuCount || 8
@@ -201,10 +201,10 @@ function CpuPinningSection({ vm }: { vm: VM }) {
     setIsPending(true)
     try {
       if (selectedCpus.length === 0) {
-        await api.clearCpuPin(vm.id, editingVcpu)
+        await api.clearCpuPin(vm.id, editingVcpu, vm.connectionId ?? undefined)
         toast.success(`CPU pinning cleared for vCPU ${editingVcpu}`)
       } else {
-        await api.setCpuPin(vm.id, editingVcpu, selectedCpus)
+        await api.setCpuPin(vm.id, editingVcpu, selectedCpus, vm.connectionId ?? undefined)
         toast.success(`vCPU ${editingVcpu} pinned to CPU(s) ${selectedCpus.join(', ')}`)
       }
       refetchPinning()
@@ -220,7 +220,7 @@ function CpuPinningSection({ vm }: { vm: VM }) {
   const handleClearPin = async (vcpu: number) => {
     setIsPending(true)
     try {
-      await api.clearCpuPin(vm.id, vcpu)
+      await api.clearCpuPin(vm.id, vcpu, vm.connectionId ?? undefined)
       toast.success(`CPU pinning cleared for vCPU ${vcpu}`)
       refetchPinning()
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -405,7 +405,7 @@ export function CpuEditor({ vm, compact }: CpuEditorProps) {
     setVcpusSuccess(false)
 
     try {
-      await api.setVmVcpus(vm.id, vcpus)
+      await api.setVmVcpus(vm.id, vcpus, vm.connectionId ?? undefined)
       setVcpusSuccess(true)
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -423,7 +423,7 @@ export function CpuEditor({ vm, compact }: CpuEditorProps) {
     setTopologySuccess(false)
 
     try {
-      await api.setVmCpuTopology(vm.id, sockets, cores, threads)
+      await api.setVmCpuTopology(vm.id, sockets, cores, threads, vm.connectionId ?? undefined)
       setTopologySuccess(true)
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
       queryClient.invalidateQueries({ queryKey: ['vms'] })
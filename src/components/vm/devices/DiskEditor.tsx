@@ -64,7 +64,7 @@ export function DiskEditor({ vm, diskIndex, compact }: DiskEditorProps) {
   }, [disk])
 
   const detachMutation = useMutation({
-    mutationFn: () => api.detachDisk(vm.id, disk?.device || ''),
+    mutationFn: () => api.detachDisk(vm.id, disk?.device || '', vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
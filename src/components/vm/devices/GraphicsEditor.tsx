@@ -38,7 +38,7 @@ export function GraphicsEditor({ vm, compact }: GraphicsEditorProps) {
   // Query USB redirection status
   const { data: usbRedir, refetch: refetchUsbRedir } = useQuery({
     queryKey: ['usbRedirection', vm.id],
-    queryFn: () => api.getUsbRedirection(vm.id),
+    queryFn: () => api.getUsbRedirection(vm.id, vm.connectionId ?? undefined),
     staleTime: 5000,
   })
 
@@ -51,7 +51,7 @@ export function GraphicsEditor({ vm, compact }: GraphicsEditorProps) {
 
   const handleOpenConsole = async () => {
     try {
-      await api.openConsoleWindow(vm.id, vm.name)
+      await api.openConsoleWindow(vm.id, vm.name, vm.connectionId ?? undefined)
     } catch (error) {
       toast.error(`Failed to open console: ${(error as Error).message}`)
     }
@@ -63,7 +63,7 @@ export function GraphicsEditor({ vm, compact }: GraphicsEditorProps) {
     setSuccess(false)
 
     try {
-      await api.attachGraphics(vm.id, graphicsType, listenAddress)
+      await api.attachGraphics(vm.id, graphicsType, listenAddress, undefined, vm.connectionId ?? undefined)
       setSuccess(true)
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -370,7 +370,7 @@ export function GraphicsEditor({ vm, compact }: GraphicsEditorProps) {
                       onClick={async () => {
                         setUsbRedirLoading(true)
                         try {
-                          await api.attachUsbRedirection(vm.id, usbRedirCount)
+                          await api.attachUsbRedirection(vm.id, usbRedirCount, vm.connectionId ?? undefined)
                           toast.success(`Added ${usbRedirCount} USB redirection channel(s)`)
                           refetchUsbRedir()
                         } catch (err) {
@@ -404,7 +404,7 @@ export function GraphicsEditor({ vm, compact }: GraphicsEditorProps) {
                       onClick={async () => {
                         setUsbRedirLoading(true)
                         try {
-                          await api.removeUsbRedirection(vm.id)
+                          await api.removeUsbRedirection(vm.id, vm.connectionId ?? undefined)
                           toast.success('USB redirection disabled')
                           refetchUsbRedir()
                         } catch (err) {
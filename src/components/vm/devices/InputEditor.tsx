@@ -42,12 +42,12 @@ export function InputEditor({ vm, compact }: InputEditorProps) {
   // Query evdev devices attached to this VM
   const { data: attachedEvdevDevices = [], isLoading: loadingAttached, refetch: refetchAttached } = useQuery({
     queryKey: ['vm-evdev-devices', vm.id],
-    queryFn: () => api.getVmEvdevDevices(vm.id),
+    queryFn: () => api.getVmEvdevDevices(vm.id, vm.connectionId ?? undefined),
   })
 
   // Attach evdev device mutation
   const attachEvdevMutation = useMutation({
-    mutationFn: (devicePath: string) => api.attachEvdev(vm.id, devicePath, grabAll),
+    mutationFn: (devicePath: string) => api.attachEvdev(vm.id, devicePath, grabAll, vm.connectionId ?? undefined),
     onSuccess: () => {
       toast.success('Evdev device attached')
       queryClient.invalidateQueries({ queryKey: ['vm-evdev-devices', vm.id] })
@@ -60,7 +60,7 @@ export function InputEditor({ vm, compact }: InputEditorProps) {
 
   // Detach evdev device mutation
   const detachEvdevMutation = useMutation({
-    mutationFn: (devicePath: string) => api.detachEvdev(vm.id, devicePath),
+    mutationFn: (devicePath: string) => api.detachEvdev(vm.id, devicePath, vm.connectionId ?? undefined),
     onSuccess: () => {
       toast.success('Evdev device detached')
       queryClient.invalidateQueries({ queryKey: ['vm-evdev-devices', vm.id] })
@@ -43,7 +43,7 @@ export function MemoryEditor({ vm, compact }: MemoryEditorProps) {
   // Query VM hugepages settings
   const { data: hugepagesSettings, refetch: refetchHugepages } = useQuery({
     queryKey: ['hugepagesSettings', vm.id],
-    queryFn: () => api.getHugepagesSettings(vm.id),
+    queryFn: () => api.getHugepagesSettings(vm.id, vm.connectionId ?? undefined),
     staleTime: 5000,
   })
 
@@ -69,7 +69,7 @@ export function MemoryEditor({ vm, compact }: MemoryEditorProps) {
     setSuccess(false)
 
     try {
-      await api.setVmMemory(vm.id, memoryMb)
+      await api.setVmMemory(vm.id, memoryMb, vm.connectionId ?? undefined)
       setSuccess(true)
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -358,7 +358,7 @@ export function MemoryEditor({ vm, compact }: MemoryEditorProps) {
                     setHugepagesError(null)
                     setHugepagesSuccess(false)
                     try {
-                      await api.setHugepages(vm.id, hugepagesEnabled, hugepageSize)
+                      await api.setHugepages(vm.id, hugepagesEnabled, hugepageSize, vm.connectionId ?? undefined)
                       setHugepagesSuccess(true)
                       refetchHugepages()
                       setTimeout(() => setHugepagesSuccess(false), 3000)
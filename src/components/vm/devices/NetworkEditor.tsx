@@ -66,13 +66,13 @@ export function NetworkEditor({ vm, nicIndex, compact }: NetworkEditorProps) {
   // Fetch link state for this interface
   const { data: linkUp = true, refetch: refetchLinkState } = useQuery({
     queryKey: ['interface-link-state', vm.id, nic?.mac],
-    queryFn: () => api.getInterfaceLinkState(vm.id, nic?.mac || ''),
+    queryFn: () => api.getInterfaceLinkState(vm.id, nic?.mac || '', vm.connectionId ?? undefined),
     enabled: !!nic?.mac,
   })
 
   // Link state mutation
   const linkStateMutation = useMutation({
-    mutationFn: (newLinkUp: boolean) => api.setInterfaceLinkState(vm.id, nic?.mac || '', newLinkUp),
+    mutationFn: (newLinkUp: boolean) => api.setInterfaceLinkState(vm.id, nic?.mac || '', newLinkUp, vm.connectionId ?? undefined),
     onSuccess: (_, newLinkUp) => {
       queryClient.invalidateQueries({ queryKey: ['interface-link-state', vm.id, nic?.mac] })
       refetchLinkState()
@@ -147,7 +147,7 @@ export function NetworkEditor({ vm, nicIndex, compact }: NetworkEditorProps) {
   }
 
   const detachMutation = useMutation({
-    mutationFn: () => api.detachInterface(vm.id, nic?.mac || ''),
+    mutationFn: () => api.detachInterface(vm.id, nic?.mac || '', vm.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -163,9 +163,9 @@ export function NetworkEditor({ vm, nicIndex, compact }: NetworkEditorProps) {
   const updateMutation = useMutation({
     mutationFn: async () => {
       // First detach the existing interface
-      await api.detachInterface(vm.id, nic?.mac || '')
+      await api.detachInterface(vm.id, nic?.mac || '', vm.connectionId ?? undefined)
       // Then attach a new one with new settings
-      await api.attachInterface(vm.id, selectedNetwork, selectedModel, nic?.mac)
+      await api.attachInterface(vm.id, selectedNetwork, selectedModel, nic?.mac, vm.connectionId ?? undefined)
     },
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -182,8 +182,8 @@ export function NetworkEditor({ vm, nicIndex, compact }: NetworkEditorProps) {
   const regenerateMacMutation = useMutation({
     mutationFn: async () => {
       const newMac = generateMacAddress()
-      await api.detachInterface(vm.id, nic?.mac || '')
-      await api.attachInterface(vm.id, selectedNetwork, selectedModel, newMac)
+      await api.detachInterface(vm.id, nic?.mac || '', vm.connectionId ?? undefined)
+      await api.attachInterface(vm.id, selectedNetwork, selectedModel, newMac, vm.connectionId ?? undefined)
     },
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -34,7 +34,7 @@ export function NumaEditor({ vm }: NumaEditorProps) {
   // Get VM NUMA configuration
   const { data: numaConfig, isLoading: loadingConfig, refetch: refetchConfig } = useQuery({
     queryKey: ['vmNumaConfig', vm.id],
-    queryFn: () => api.getVmNumaConfig(vm.id),
+    queryFn: () => api.getVmNumaConfig(vm.id, vm.connectionId ?? undefined),
   })
 
   // Local state
@@ -87,7 +87,7 @@ export function NumaEditor({ vm }: NumaEditorProps) {
         nodeset: selectedNodes.join(','),
         cells: [],
       }
-      await api.setVmNumaConfig(vm.id, config)
+      await api.setVmNumaConfig(vm.id, config, vm.connectionId ?? undefined)
       toast.success(`NUMA configuration applied: ${mode} mode on node(s) ${selectedNodes.join(', ')}`)
       refetchConfig()
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
@@ -102,7 +102,7 @@ export function NumaEditor({ vm }: NumaEditorProps) {
   const handleClearConfig = async () => {
     setIsPending(true)
     try {
-      await api.clearVmNumaConfig(vm.id)
+      await api.clearVmNumaConfig(vm.id, vm.connectionId ?? undefined)
       toast.success('NUMA configuration cleared')
       refetchConfig()
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
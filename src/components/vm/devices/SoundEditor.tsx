@@ -34,7 +34,7 @@ export function SoundEditor({ vm, compact }: SoundEditorProps) {
     setSuccess(false)
 
     try {
-      await api.attachSound(vm.id, soundModel)
+      await api.attachSound(vm.id, soundModel, vm.connectionId ?? undefined)
       setSuccess(true)
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -56,7 +56,7 @@ export function SoundEditor({ vm, compact }: SoundEditorProps) {
     setError(null)
 
     try {
-      await api.detachSound(vm.id)
+      await api.detachSound(vm.id, vm.connectionId ?? undefined)
       setSuccess(true)
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -43,7 +43,7 @@ export function VideoEditor({ vm, compact }: VideoEditorProps) {
     setSuccess(false)
 
     try {
-      await api.attachVideo(vm.id, videoModel, vram, heads, accel3d)
+      await api.attachVideo(vm.id, videoModel, vram, heads, accel3d, vm.connectionId ?? undefined)
       setSuccess(true)
       queryClient.invalidateQueries({ queryKey: ['vm', vm.id] })
       queryClient.invalidateQueries({ queryKey: ['vms'] })
@@ -0,0 +1,58 @@
+import { useEffect } from 'react'
+import { listen, UnlistenFn } from '@tauri-apps/api/event'
+import { useQueryClient } from '@tanstack/react-query'
+import { toast } from 'sonner'
+import type { AlertEvent, AlertInstance, AlertSeverity, RuleAlertEvent } from '@/lib/types'
+
+/**
+ * Hook to surface alerts raised by the backend alert evaluator and event rules
+ * Shows a toast per triggered alert and refreshes alert state and history
+ */
+export function useAlertEvents() {
+  const queryClient = useQueryClient()
+
+  useEffect(() => {
+    const unlisteners: UnlistenFn[] = []
+
+    const notify = (message: string, severity: AlertSeverity) => {
+      switch (severity) {
+        case 'critical':
+          toast.error(message, { duration: 10000 })
+          break
+        case 'warning':
+          toast.warning(message, { duration: 6000 })
+          break
+        default:
+          toast.info(message, { duration: 4000 })
+      }
+    }
+
+    const setupListeners = async () => {
+      unlisteners.push(await listen<AlertEvent>('alert-triggered', (event) => {
+        const { alertName, subject, currentValue, thresholdValue, thresholdType, severity } = event.payload
+
+        queryClient.invalidateQueries({ queryKey: ['alerts'] })
+
+        const unit = thresholdType === 'disk' || thresholdType === 'network' ? ' MB/s' : thresholdType === 'load' ? '' : '%'
+        notify(`${alertName} (${subject}): ${currentValue.toFixed(1)}${unit} (threshold ${thresholdValue}${unit})`, severity)
+      }))
+
+      unlisteners.push(await listen<RuleAlertEvent>('alert-rule-triggered', (event) => {
+        queryClient.invalidateQueries({ queryKey: ['alert-rules'] })
+        notify(`${event.payload.ruleName}: ${event.payload.message}`, event.payload.severity)
+      }))
+
+      unlisteners.push(await listen<AlertInstance>('alert-instance-changed', () => {
+        queryClient.invalidateQueries({ queryKey: ['active-alerts'] })
+        queryClient.invalidateQueries({ queryKey: ['alert-history'] })
+      }))
+    }
+
+    setupListeners()
+
+    // Cleanup
+    return () => {
+      unlisteners.forEach((unlisten) => unlisten())
+    }
+  }, [queryClient])
+}
@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core'
-import type { VM, HostInfo, ConnectionStatus, VmConfig, VncInfo, VmStats, MigrationInfo, Network, NetworkConfig, NetworkDetails, DhcpLease, NwFilter, NwFilterConfig, StoragePool, Volume, VolumeConfig, VolumeEncryptionInfo, StoragePoolConfig, OvfMetadata, OvaImportConfig, Snapshot, SnapshotConfig, VmMetrics, HistoricalMetrics, VmTemplate, CreateTemplateRequest, ScheduledOperation, CreateScheduleRequest, ResourceAlert, CreateAlertRequest, AlertEvent, BackupConfig, CreateBackupRequest, BatchOperationResult, OptimizationSuggestion, RetentionPolicy, GuestAgentStatus, GuestSystemInfo, GuestNetworkInfo, GuestDiskUsage, GuestCommandResult, GuestCpuStats, GuestDiskStats, GuestUser, GuestTimezone, GuestFullInfo, SavedConnection, ConnectionType, KernelBootSettings, CloneConfig } from './types'
+import { listen } from '@tauri-apps/api/event'
+import type { VM, HostInfo, ConnectionStatus, VmConfig, VncInfo, VmStats, MigrationInfo, Network, NetworkConfig, NetworkDetails, DhcpLease, NwFilter, NwFilterConfig, StoragePool, Volume, VolumeConfig, VolumeEncryptionInfo, StoragePoolConfig, OvfMetadata, OvaImportConfig, Snapshot, SnapshotConfig, SnapshotNode, SnapshotConfigDiff, VmMetrics, HistoricalMetrics, VmTemplate, CreateTemplateRequest, ScheduledOperation, CreateScheduleRequest, SnapshotRetentionPolicy, CreateRetentionPolicyRequest, PruneReport, ResourceAlert, CreateAlertRequest, AlertEvent, BackupConfig, CreateBackupRequest, BackupRun, BackupChain, RestoreBackupRequest, RestoreDiskRequest, VerifyOptions, BackupTarget, CreateBackupTargetRequest, TargetCheck, TargetUsage, CatalogImport, BatchOperationResult, OptimizationSuggestion, RetentionPolicy, GuestAgentStatus, GuestSystemInfo, GuestNetworkInfo, GuestDiskUsage, GuestCommandResult, GuestCpuStats, GuestDiskStats, GuestUser, GuestTimezone, GuestFullInfo, SavedConnection, ConnectionType, ConnectionCredentials, CredentialsInfo, ConnectionStateEvent, KernelBootSettings, CloneConfig, Job, ScheduleRun, NotificationChannel, NotificationChannelRequest, DeliveryRecord, EventAlertRule, CreateEventRuleRequest, AlertInstance, AlertHistoryQuery, HostSnapshot, HostMetrics, PoolMetrics } from './types'
 
 /**
  * Tauri API wrapper for KVM Manager
@@ -8,27 +9,28 @@ import type { VM, HostInfo, ConnectionStatus, VmConfig, VncInfo, VmStats, Migrat
 
 export const api = {
   // VM Operations
-  getVms: () => invoke<VM[]>('get_vms'),
-  getVm: (vmId: string) => invoke<VM>('get_vm', { vmId }),
-  startVm: (vmId: string) => invoke<void>('start_vm', { vmId }),
-  stopVm: (vmId: string) => invoke<void>('stop_vm', { vmId }),
-  forceStopVm: (vmId: string) => invoke<void>('force_stop_vm', { vmId }),
-  pauseVm: (vmId: string) => invoke<void>('pause_vm', { vmId }),
-  resumeVm: (vmId: string) => invoke<void>('resume_vm', { vmId }),
-  hibernateVm: (vmId: string) => invoke<void>('hibernate_vm', { vmId }),
-  hasManagedSave: (vmId: string) => invoke<boolean>('has_managed_save', { vmId }),
-  removeManagedSave: (vmId: string) => invoke<void>('remove_managed_save', { vmId }),
-  rebootVm: (vmId: string) => invoke<void>('reboot_vm', { vmId }),
-  deleteVm: (vmId: string, deleteDisks: boolean, deleteSnapshots: boolean) => invoke<void>('delete_vm', { vmId, deleteDisks, deleteSnapshots }),
-  cloneVm: (sourceVmId: string, newName: string) => invoke<string>('clone_vm', { sourceVmId, newName }),
-  cloneVmWithOptions: (sourceVmId: string, config: CloneConfig) => invoke<string>('clone_vm_with_options', { sourceVmId, config }),
+  getVms: (connectionId?: string) => invoke<VM[]>('get_vms', { connectionId }),
+  getVm: (vmId: string, connectionId?: string) => invoke<VM>('get_vm', { vmId, connectionId }),
+  startVm: (vmId: string, connectionId?: string) => invoke<void>('start_vm', { vmId, connectionId }),
+  stopVm: (vmId: string, connectionId?: string) => invoke<void>('stop_vm', { vmId, connectionId }),
+  forceStopVm: (vmId: string, connectionId?: string) => invoke<void>('force_stop_vm', { vmId, connectionId }),
+  pauseVm: (vmId: string, connectionId?: string) => invoke<void>('pause_vm', { vmId, connectionId }),
+  resumeVm: (vmId: string, connectionId?: string) => invoke<void>('resume_vm', { vmId, connectionId }),
+  hibernateVm: (vmId: string, connectionId?: string) => invoke<void>('hibernate_vm', { vmId, connectionId }),
+  hasManagedSave: (vmId: string, connectionId?: string) => invoke<boolean>('has_managed_save', { vmId, connectionId }),
+  removeManagedSave: (vmId: string, connectionId?: string) => invoke<void>('remove_managed_save', { vmId, connectionId }),
+  rebootVm: (vmId: string, connectionId?: string) => invoke<void>('reboot_vm', { vmId, connectionId }),
+  deleteVm: (vmId: string, deleteDisks: boolean, deleteSnapshots: boolean, connectionId?: string) => invoke<void>('delete_vm', { vmId, deleteDisks, deleteSnapshots, connectionId }),
+  cloneVm: (sourceVmId: string, newName: string, connectionId?: string) => invoke<string>('clone_vm', { sourceVmId, newName, connectionId }),
+  // Returns a job ID; the job result is the cloned VM's ID
+  cloneVmWithOptions: (sourceVmId: string, config: CloneConfig, connectionId?: string) => invoke<string>('clone_vm_with_options', { sourceVmId, config, connectionId }),
   createVm: (config: VmConfig) => invoke<string>('create_vm', { config }),
-  addVmTags: (vmId: string, tags: string[]) => invoke<void>('add_vm_tags', { vmId, tags }),
-  removeVmTags: (vmId: string, tags: string[]) => invoke<void>('remove_vm_tags', { vmId, tags }),
-  exportVm: (vmId: string) => invoke<string>('export_vm', { vmId }),
+  addVmTags: (vmId: string, tags: string[], connectionId?: string) => invoke<void>('add_vm_tags', { vmId, tags, connectionId }),
+  removeVmTags: (vmId: string, tags: string[], connectionId?: string) => invoke<void>('remove_vm_tags', { vmId, tags, connectionId }),
+  exportVm: (vmId: string, connectionId?: string) => invoke<string>('export_vm', { vmId, connectionId }),
   importVm: (xml: string) => invoke<string>('import_vm', { xml }),
-  attachDisk: (vmId: string, diskPath: string, deviceTarget: string, busType: string) => invoke<void>('attach_disk', { vmId, diskPath, deviceTarget, busType }),
-  detachDisk: (vmId: string, deviceTarget: string) => invoke<void>('detach_disk', { vmId, deviceTarget }),
+  attachDisk: (vmId: string, diskPath: string, deviceTarget: string, busType: string, connectionId?: string) => invoke<void>('attach_disk', { vmId, diskPath, deviceTarget, busType, connectionId }),
+  detachDisk: (vmId: string, deviceTarget: string, connectionId?: string) => invoke<void>('detach_disk', { vmId, deviceTarget, connectionId }),
   updateDiskSettings: (
     vmId: string,
     deviceTarget: string,
@@ -56,7 +58,7 @@ export const api = {
   }),
 
   // Direct Kernel Boot Operations
-  getKernelBootSettings: (vmId: string) => invoke<KernelBootSettings>('get_kernel_boot_settings', { vmId }),
+  getKernelBootSettings: (vmId: string, connectionId?: string) => invoke<KernelBootSettings>('get_kernel_boot_settings', { vmId, connectionId }),
   setKernelBootSettings: (
     vmId: string,
     settings: {
@@ -65,7 +67,8 @@ export const api = {
       initrdPath?: string
       kernelArgs?: string
       dtbPath?: string
-    }
+    },
+    connectionId?: string
   ) => invoke<void>('set_kernel_boot_settings', {
     vmId,
     enabled: settings.enabled,
@@ -73,27 +76,28 @@ export const api = {
     initrdPath: settings.initrdPath,
     kernelArgs: settings.kernelArgs,
     dtbPath: settings.dtbPath,
+    connectionId,
   }),
 
   // Hugepages Memory Operations
-  getHugepagesSettings: (vmId: string) => invoke<import('./types').HugepagesSettings>('get_hugepages_settings', { vmId }),
-  setHugepages: (vmId: string, enabled: boolean, size?: number) =>
-    invoke<void>('set_hugepages', { vmId, enabled, size }),
+  getHugepagesSettings: (vmId: string, connectionId?: string) => invoke<import('./types').HugepagesSettings>('get_hugepages_settings', { vmId, connectionId }),
+  setHugepages: (vmId: string, enabled: boolean, size?: number, connectionId?: string) =>
+    invoke<void>('set_hugepages', { vmId, enabled, size, connectionId }),
   getHostHugepageInfo: () => invoke<import('./types').HugepageInfo[]>('get_host_hugepage_info'),
 
   // USB Redirection Operations (for SPICE)
-  getUsbRedirection: (vmId: string) => invoke<import('./types').UsbRedirectionInfo>('get_usb_redirection', { vmId }),
-  attachUsbRedirection: (vmId: string, count: number) =>
-    invoke<void>('attach_usb_redirection', { vmId, count }),
-  removeUsbRedirection: (vmId: string) => invoke<void>('remove_usb_redirection', { vmId }),
+  getUsbRedirection: (vmId: string, connectionId?: string) => invoke<import('./types').UsbRedirectionInfo>('get_usb_redirection', { vmId, connectionId }),
+  attachUsbRedirection: (vmId: string, count: number, connectionId?: string) =>
+    invoke<void>('attach_usb_redirection', { vmId, count, connectionId }),
+  removeUsbRedirection: (vmId: string, connectionId?: string) => invoke<void>('remove_usb_redirection', { vmId, connectionId }),
 
   // Evdev Input Passthrough Operations
   listEvdevDevices: () => invoke<import('./types').EvdevDevice[]>('list_evdev_devices'),
-  attachEvdev: (vmId: string, devicePath: string, grabAll: boolean) =>
-    invoke<void>('attach_evdev', { vmId, devicePath, grabAll }),
-  getVmEvdevDevices: (vmId: string) => invoke<import('./types').EvdevDevice[]>('get_vm_evdev_devices', { vmId }),
-  detachEvdev: (vmId: string, devicePath: string) =>
-    invoke<void>('detach_evdev', { vmId, devicePath }),
+  attachEvdev: (vmId: string, devicePath: string, grabAll: boolean, connectionId?: string) =>
+    invoke<void>('attach_evdev', { vmId, devicePath, grabAll, connectionId }),
+  getVmEvdevDevices: (vmId: string, connectionId?: string) => invoke<import('./types').EvdevDevice[]>('get_vm_evdev_devices', { vmId, connectionId }),
+  detachEvdev: (vmId: string, devicePath: string, connectionId?: string) =>
+    invoke<void>('detach_evdev', { vmId, devicePath, connectionId }),
 
   // Network Bandwidth/QoS Operations
   updateInterfaceBandwidth: (
@@ -106,7 +110,8 @@ export const api = {
       outboundAverage?: number
       outboundPeak?: number
       outboundBurst?: number
-    }
+    },
+    connectionId?: string
   ) => invoke<void>('update_interface_bandwidth', {
     vmId,
     macAddress,
@@ -116,29 +121,31 @@ export const api = {
     outboundAverage: settings.outboundAverage,
     outboundPeak: settings.outboundPeak,
     outboundBurst: settings.outboundBurst,
+    connectionId,
   }),
 
   // Network Interface Link State Operations
-  setInterfaceLinkState: (vmId: string, macAddress: string, linkUp: boolean) =>
-    invoke<void>('set_interface_link_state', { vmId, macAddress, linkUp }),
-  getInterfaceLinkState: (vmId: string, macAddress: string) =>
-    invoke<boolean>('get_interface_link_state', { vmId, macAddress }),
+  setInterfaceLinkState: (vmId: string, macAddress: string, linkUp: boolean, connectionId?: string) =>
+    invoke<void>('set_interface_link_state', { vmId, macAddress, linkUp, connectionId }),
+  getInterfaceLinkState: (vmId: string, macAddress: string, connectionId?: string) =>
+    invoke<boolean>('get_interface_link_state', { vmId, macAddress, connectionId }),
 
   // Migration Operations
-  migrateVm: (vmId: string, destUri: string, live: boolean, unsafeMigration: boolean) =>
-    invoke<void>('migrate_vm', { vmId, destUri, live, unsafeMigration }),
-  getMigrationInfo: (vmId: string) => invoke<MigrationInfo>('get_migration_info', { vmId }),
-  checkMigrationCompatibility: (vmId: string) =>
-    invoke<[boolean, string[]]>('check_migration_compatibility', { vmId }),
+  // Returns a job ID; cancelling the job aborts the migration
+  migrateVm: (vmId: string, destUri: string, live: boolean, unsafeMigration: boolean, connectionId?: string) =>
+    invoke<string>('migrate_vm', { vmId, destUri, live, unsafeMigration, connectionId }),
+  getMigrationInfo: (vmId: string, connectionId?: string) => invoke<MigrationInfo>('get_migration_info', { vmId, connectionId }),
+  checkMigrationCompatibility: (vmId: string, connectionId?: string) =>
+    invoke<[boolean, string[]]>('check_migration_compatibility', { vmId, connectionId }),
   getMigrationTargets: () => invoke<[string, string][]>('get_migration_targets'),
 
   // Console Operations
-  getVncInfo: (vmId: string) => invoke<VncInfo>('get_vnc_info', { vmId }),
-  openVncConsole: (vmId: string) => invoke<void>('open_vnc_console', { vmId }),
+  getVncInfo: (vmId: string, connectionId?: string) => invoke<VncInfo>('get_vnc_info', { vmId, connectionId }),
+  openVncConsole: (vmId: string, connectionId?: string) => invoke<void>('open_vnc_console', { vmId, connectionId }),
   stopVncProxy: (vmId: string) => invoke<void>('stop_vnc_proxy', { vmId }),
 
   // Performance Operations
-  getVmStats: (vmId: string) => invoke<VmStats>('get_vm_stats', { vmId }),
+  getVmStats: (vmId: string, connectionId?: string) => invoke<VmStats>('get_vm_stats', { vmId, connectionId }),
 
   // System Operations
   getHostInfo: () => invoke<HostInfo>('get_host_info'),
@@ -147,6 +154,8 @@ export const api = {
   // Connection Management
   getSavedConnections: () => invoke<SavedConnection[]>('get_saved_connections'),
   getActiveConnection: () => invoke<SavedConnection | null>('get_active_connection'),
+  getConnectedConnections: () => invoke<SavedConnection[]>('get_connected_connections'),
+  getConnectionStates: () => invoke<ConnectionStateEvent[]>('get_connection_states'),
   connectTo: (connectionId: string) => invoke<void>('connect_to', { connectionId }),
   disconnectFrom: (connectionId: string) => invoke<void>('disconnect_from', { connectionId }),
   addConnection: (
@@ -191,21 +200,29 @@ export const api = {
     host?: string,
     username?: string,
     sshPort?: number,
-    tlsPort?: number
-  ) => invoke<string>('test_connection', { connectionType, host, username, sshPort, tlsPort }),
+    tlsPort?: number,
+    credentials?: ConnectionCredentials
+  ) => invoke<string>('test_connection', { connectionType, host, username, sshPort, tlsPort, credentials }),
+  setConnectionCredentials: (connectionId: string, credentials: ConnectionCredentials) =>
+    invoke<void>('set_connection_credentials', { connectionId, credentials }),
+  getConnectionCredentials: (connectionId: string) =>
+    invoke<CredentialsInfo | null>('get_connection_credentials', { connectionId }),
+  clearConnectionCredentials: (connectionId: string) =>
+    invoke<void>('clear_connection_credentials', { connectionId }),
+  getConnectionsNeedingCredentials: () => invoke<string[]>('get_connections_needing_credentials'),
 
   // Network Operations
-  getNetworks: () => invoke<Network[]>('get_networks'),
-  getNetwork: (networkName: string) => invoke<Network>('get_network', { networkName }),
-  createNetwork: (config: NetworkConfig) => invoke<string>('create_network', { config }),
-  deleteNetwork: (networkName: string) => invoke<void>('delete_network', { networkName }),
-  startNetwork: (networkName: string) => invoke<void>('start_network', { networkName }),
-  stopNetwork: (networkName: string) => invoke<void>('stop_network', { networkName }),
+  getNetworks: (connectionId?: string) => invoke<Network[]>('get_networks', { connectionId }),
+  getNetwork: (networkName: string, connectionId?: string) => invoke<Network>('get_network', { networkName, connectionId }),
+  createNetwork: (config: NetworkConfig, connectionId?: string) => invoke<string>('create_network', { config, connectionId }),
+  deleteNetwork: (networkName: string, connectionId?: string) => invoke<void>('delete_network', { networkName, connectionId }),
+  startNetwork: (networkName: string, connectionId?: string) => invoke<void>('start_network', { networkName, connectionId }),
+  stopNetwork: (networkName: string, connectionId?: string) => invoke<void>('stop_network', { networkName, connectionId }),
   addPortForward: (hostPort: number, guestIp: string, guestPort: number, protocol: string) => invoke<void>('add_port_forward', { hostPort, guestIp, guestPort, protocol }),
   removePortForward: (hostPort: number, guestIp: string, guestPort: number, protocol: string) => invoke<void>('remove_port_forward', { hostPort, guestIp, guestPort, protocol }),
-  setNetworkAutostart: (networkName: string, autostart: boolean) => invoke<void>('set_network_autostart', { networkName, autostart }),
-  getNetworkDetails: (networkName: string) => invoke<NetworkDetails>('get_network_details', { networkName }),
-  getDhcpLeases: (networkName: string) => invoke<DhcpLease[]>('get_dhcp_leases', { networkName }),
+  setNetworkAutostart: (networkName: string, autostart: boolean, connectionId?: string) => invoke<void>('set_network_autostart', { networkName, autostart, connectionId }),
+  getNetworkDetails: (networkName: string, connectionId?: string) => invoke<NetworkDetails>('get_network_details', { networkName, connectionId }),
+  getDhcpLeases: (networkName: string, connectionId?: string) => invoke<DhcpLease[]>('get_dhcp_leases', { networkName, connectionId }),
 
   // Network Filter Operations
   getNwfilters: () => invoke<NwFilter[]>('get_nwfilters'),
@@ -216,36 +233,44 @@ export const api = {
   deleteNwfilter: (name: string) => invoke<void>('delete_nwfilter', { name }),
 
   // Storage Operations
-  getStoragePools: () => invoke<StoragePool[]>('get_storage_pools'),
-  getVolumes: (poolId: string) => invoke<Volume[]>('get_volumes', { poolId }),
-  createVolume: (poolId: string, config: VolumeConfig) => invoke<string>('create_volume', { poolId, config }),
-  deleteVolume: (poolId: string, volumeName: string) => invoke<void>('delete_volume', { poolId, volumeName }),
-  createStoragePool: (config: StoragePoolConfig) => invoke<string>('create_storage_pool', { config }),
-  resizeVolume: (poolId: string, volumeName: string, newCapacityGb: number) => invoke<void>('resize_volume', { poolId, volumeName, newCapacityGb }),
-  uploadVolume: (poolId: string, volumeName: string, sourcePath: string, format?: string) =>
-    invoke<Volume>('upload_volume', { poolId, volumeName, sourcePath, format }),
-  downloadVolume: (poolId: string, volumeName: string, destPath: string) =>
-    invoke<number>('download_volume', { poolId, volumeName, destPath }),
-  getVolumePath: (poolId: string, volumeName: string) =>
-    invoke<string>('get_volume_path', { poolId, volumeName }),
-  getVolumeEncryptionInfo: (poolId: string, volumeName: string) =>
-    invoke<VolumeEncryptionInfo>('get_volume_encryption_info', { poolId, volumeName }),
+  getStoragePools: (connectionId?: string) => invoke<StoragePool[]>('get_storage_pools', { connectionId }),
+  getVolumes: (poolId: string, connectionId?: string) => invoke<Volume[]>('get_volumes', { poolId, connectionId }),
+  createVolume: (poolId: string, config: VolumeConfig, connectionId?: string) => invoke<string>('create_volume', { poolId, config, connectionId }),
+  deleteVolume: (poolId: string, volumeName: string, connectionId?: string) => invoke<void>('delete_volume', { poolId, volumeName, connectionId }),
+  createStoragePool: (config: StoragePoolConfig, connectionId?: string) => invoke<string>('create_storage_pool', { config, connectionId }),
+  resizeVolume: (poolId: string, volumeName: string, newCapacityGb: number, connectionId?: string) => invoke<void>('resize_volume', { poolId, volumeName, newCapacityGb, connectionId }),
+  // Upload/download return a job ID; the job result is the Volume / bytes written
+  uploadVolume: (poolId: string, volumeName: string, sourcePath: string, format?: string, connectionId?: string) =>
+    invoke<string>('upload_volume', { poolId, volumeName, sourcePath, format, connectionId }),
+  downloadVolume: (poolId: string, volumeName: string, destPath: string, connectionId?: string) =>
+    invoke<string>('download_volume', { poolId, volumeName, destPath, connectionId }),
+  getVolumePath: (poolId: string, volumeName: string, connectionId?: string) =>
+    invoke<string>('get_volume_path', { poolId, volumeName, connectionId }),
+  getVolumeEncryptionInfo: (poolId: string, volumeName: string, connectionId?: string) =>
+    invoke<VolumeEncryptionInfo>('get_volume_encryption_info', { poolId, volumeName, connectionId }),
 
   // OVA/OVF Import
   getOvaMetadata: (sourcePath: string) =>
     invoke<OvfMetadata>('get_ova_metadata', { sourcePath }),
+  // Returns a job ID; the job result is the imported disk path
   importOva: (config: OvaImportConfig) =>
     invoke<string>('import_ova', { config }),
 
   // Snapshot Operations
-  getSnapshots: (vmId: string) => invoke<Snapshot[]>('get_snapshots', { vmId }),
-  createSnapshot: (vmId: string, config: SnapshotConfig) => invoke<string>('create_snapshot', { vmId, config }),
-  deleteSnapshot: (vmId: string, snapshotName: string) => invoke<void>('delete_snapshot', { vmId, snapshotName }),
-  revertSnapshot: (vmId: string, snapshotName: string) => invoke<void>('revert_snapshot', { vmId, snapshotName }),
+  getSnapshots: (vmId: string, connectionId?: string) => invoke<Snapshot[]>('get_snapshots', { vmId, connectionId }),
+  getSnapshotTree: (vmId: string, connectionId?: string) => invoke<SnapshotNode[]>('get_snapshot_tree', { vmId, connectionId }),
+  diffSnapshotConfig: (vmId: string, snapshotName: string, connectionId?: string) =>
+    invoke<SnapshotConfigDiff>('diff_snapshot_config', { vmId, snapshotName, connectionId }),
+  createSnapshot: (vmId: string, config: SnapshotConfig, connectionId?: string) => invoke<string>('create_snapshot', { vmId, config, connectionId }),
+  deleteSnapshot: (vmId: string, snapshotName: string, connectionId?: string) => invoke<void>('delete_snapshot', { vmId, snapshotName, connectionId }),
+  revertSnapshot: (vmId: string, snapshotName: string, connectionId?: string) => invoke<void>('revert_snapshot', { vmId, snapshotName, connectionId }),
 
   // Metrics Operations
   storeVmMetrics: (metrics: VmMetrics) => invoke<void>('store_vm_metrics', { metrics }),
   getHistoricalMetrics: (vmId: string, startTime: number, endTime: number, maxPoints?: number) => invoke<HistoricalMetrics>('get_historical_metrics', { vmId, startTime, endTime, maxPoints }),
+  getHostMetrics: () => invoke<HostSnapshot[]>('get_host_metrics'),
+  getHostMetricsHistory: (connectionId: string, startTime: number, endTime: number, maxPoints?: number) => invoke<HostMetrics[]>('get_host_metrics_history', { connectionId, startTime, endTime, maxPoints }),
+  getPoolMetricsHistory: (connectionId: string, poolName: string, startTime: number, endTime: number) => invoke<PoolMetrics[]>('get_pool_metrics_history', { connectionId, poolName, startTime, endTime }),
   cleanupOldMetrics: (beforeTimestamp: number) => invoke<void>('cleanup_old_metrics', { beforeTimestamp }),
   getMetricsCount: (vmId?: string) => invoke<number>('get_metrics_count', { vmId }),
 
@@ -263,6 +288,17 @@ export const api = {
   updateScheduleStatus: (id: string, enabled: boolean) => invoke<ScheduledOperation>('update_schedule_status', { id, enabled }),
   deleteSchedule: (id: string) => invoke<void>('delete_schedule', { id }),
   getVmSchedules: (vmId: string) => invoke<ScheduledOperation[]>('get_vm_schedules', { vmId }),
+  getScheduleRuns: (id: string) => invoke<ScheduleRun[]>('get_schedule_runs', { id }),
+
+  // Snapshot Retention Operations
+  createSnapshotRetentionPolicy: (request: CreateRetentionPolicyRequest) =>
+    invoke<SnapshotRetentionPolicy>('create_snapshot_retention_policy', { request }),
+  listSnapshotRetentionPolicies: () => invoke<SnapshotRetentionPolicy[]>('list_snapshot_retention_policies'),
+  updateSnapshotRetentionPolicyStatus: (id: string, enabled: boolean) =>
+    invoke<SnapshotRetentionPolicy>('update_snapshot_retention_policy_status', { id, enabled }),
+  deleteSnapshotRetentionPolicy: (id: string) => invoke<void>('delete_snapshot_retention_policy', { id }),
+  runSnapshotRetentionPolicy: (id: string, dryRun: boolean) =>
+    invoke<PruneReport>('run_snapshot_retention_policy', { id, dryRun }),
 
   // Alert Operations
   createAlert: (request: CreateAlertRequest) => invoke<ResourceAlert>('create_alert', { request }),
@@ -272,6 +308,23 @@ export const api = {
   deleteAlert: (id: string) => invoke<void>('delete_alert', { id }),
   getVmAlerts: (vmId: string) => invoke<ResourceAlert[]>('get_vm_alerts', { vmId }),
   checkAlertThreshold: (alertId: string, currentValue: number) => invoke<AlertEvent | null>('check_alert_threshold', { alertId, currentValue }),
+  setAlertChannels: (id: string, channelIds: string[]) => invoke<ResourceAlert>('set_alert_channels', { id, channelIds }),
+  createAlertRule: (request: CreateEventRuleRequest) => invoke<EventAlertRule>('create_alert_rule', { request }),
+  listAlertRules: () => invoke<EventAlertRule[]>('list_alert_rules'),
+  updateAlertRuleStatus: (id: string, enabled: boolean) => invoke<EventAlertRule>('update_alert_rule_status', { id, enabled }),
+  deleteAlertRule: (id: string) => invoke<void>('delete_alert_rule', { id }),
+  listActiveAlerts: () => invoke<AlertInstance[]>('list_active_alerts'),
+  acknowledgeAlert: (id: string, by?: string) => invoke<AlertInstance>('acknowledge_alert', { id, by }),
+  resolveAlert: (id: string, by?: string) => invoke<AlertInstance>('resolve_alert', { id, by }),
+  queryAlertHistory: (query: AlertHistoryQuery) => invoke<AlertInstance[]>('query_alert_history', { query }),
+
+  // Notification Channel Operations
+  listNotificationChannels: () => invoke<NotificationChannel[]>('list_notification_channels'),
+  createNotificationChannel: (request: NotificationChannelRequest) => invoke<NotificationChannel>('create_notification_channel', { request }),
+  updateNotificationChannel: (id: string, request: NotificationChannelRequest) => invoke<NotificationChannel>('update_notification_channel', { id, request }),
+  deleteNotificationChannel: (id: string) => invoke<void>('delete_notification_channel', { id }),
+  testNotificationChannel: (id: string) => invoke<DeliveryRecord>('test_notification_channel', { id }),
+  getNotificationDeliveries: (channelId?: string) => invoke<DeliveryRecord[]>('get_notification_deliveries', { channelId: channelId ?? null }),
 
   // Backup Operations
   createBackupConfig: (request: CreateBackupRequest) => invoke<BackupConfig>('create_backup_config', { request }),
@@ -280,12 +333,29 @@ export const api = {
   updateBackupStatus: (id: string, enabled: boolean) => invoke<BackupConfig>('update_backup_status', { id, enabled }),
   deleteBackupConfig: (id: string) => invoke<void>('delete_backup_config', { id }),
   getVmBackupConfigs: (vmId: string) => invoke<BackupConfig[]>('get_vm_backup_configs', { vmId }),
-  recordBackup: (id: string) => invoke<BackupConfig>('record_backup', { id }),
+  runBackupNow: (id: string) => invoke<string>('run_backup_now', { id }),
+  listBackupRuns: (configId: string) => invoke<BackupRun[]>('list_backup_runs', { configId }),
+  listBackupChains: (configId: string) => invoke<BackupChain[]>('list_backup_chains', { configId }),
+  restoreBackup: (request: RestoreBackupRequest) => invoke<string>('restore_backup', { request }),
+  restoreBackupDisk: (request: RestoreDiskRequest) => invoke<string>('restore_backup_disk', { request }),
+  attachBackupDisk: (configId: string, runId: string, disk: string, vmId: string) =>
+    invoke<string>('attach_backup_disk', { configId, runId, disk, vmId }),
+  verifyBackup: (configId: string, runId: string, options: VerifyOptions) =>
+    invoke<string>('verify_backup', { configId, runId, options }),
+  syncBackupTarget: (configId: string) => invoke<string>('sync_backup_target', { configId }),
+
+  // Backup Targets
+  listBackupTargets: () => invoke<BackupTarget[]>('list_backup_targets'),
+  createBackupTarget: (request: CreateBackupTargetRequest) => invoke<BackupTarget>('create_backup_target', { request }),
+  deleteBackupTarget: (id: string) => invoke<void>('delete_backup_target', { id }),
+  checkBackupTarget: (id: string) => invoke<TargetCheck>('check_backup_target', { id }),
+  getBackupTargetUsage: (id: string) => invoke<TargetUsage>('get_backup_target_usage', { id }),
+  importBackupCatalog: (targetId: string) => invoke<string>('import_backup_catalog', { targetId }),
 
   // Batch Operations
-  batchStartVms: (vmIds: string[]) => invoke<BatchOperationResult[]>('batch_start_vms', { vmIds }),
-  batchStopVms: (vmIds: string[], force: boolean) => invoke<BatchOperationResult[]>('batch_stop_vms', { vmIds, force }),
-  batchRebootVms: (vmIds: string[]) => invoke<BatchOperationResult[]>('batch_reboot_vms', { vmIds }),
+  batchStartVms: (vmIds: string[], connectionId?: string) => invoke<BatchOperationResult[]>('batch_start_vms', { vmIds, connectionId }),
+  batchStopVms: (vmIds: string[], force: boolean, connectionId?: string) => invoke<BatchOperationResult[]>('batch_stop_vms', { vmIds, force, connectionId }),
+  batchRebootVms: (vmIds: string[], connectionId?: string) => invoke<BatchOperationResult[]>('batch_reboot_vms', { vmIds, connectionId }),
 
   // Optimization Operations
   analyzeVmPerformance: (vmId: string, vmName: string, timeRangeHours?: number) => invoke<OptimizationSuggestion[]>('analyze_vm_performance', { vmId, vmName, timeRangeHours }),
@@ -297,59 +367,63 @@ export const api = {
   executeRetentionCleanup: () => invoke<number>('execute_retention_cleanup'),
 
   // Guest Agent Operations
-  checkGuestAgentStatus: (vmName: string) => invoke<GuestAgentStatus>('check_guest_agent_status', { vmName }),
-  getGuestSystemInfo: (vmName: string) => invoke<GuestSystemInfo>('get_guest_system_info', { vmName }),
-  getGuestNetworkInfo: (vmName: string) => invoke<GuestNetworkInfo>('get_guest_network_info', { vmName }),
-  getGuestDiskUsage: (vmName: string) => invoke<GuestDiskUsage>('get_guest_disk_usage', { vmName }),
-  executeGuestCommand: (vmName: string, command: string, args: string[], timeoutSeconds?: number) => invoke<GuestCommandResult>('execute_guest_command', { vmName, command, args, timeoutSeconds }),
-  readGuestFile: (vmName: string, path: string) => invoke<string>('read_guest_file', { vmName, path }),
-  writeGuestFile: (vmName: string, path: string, content: string, createDirs?: boolean) => invoke<void>('write_guest_file', { vmName, path, content, createDirs }),
-  guestAgentShutdown: (vmName: string, force?: boolean) => invoke<void>('guest_agent_shutdown', { vmName, force }),
-  guestAgentReboot: (vmName: string, force?: boolean) => invoke<void>('guest_agent_reboot', { vmName, force }),
+  checkGuestAgentStatus: (vmName: string, connectionId?: string) => invoke<GuestAgentStatus>('check_guest_agent_status', { vmName, connectionId }),
+  getGuestSystemInfo: (vmName: string, connectionId?: string) => invoke<GuestSystemInfo>('get_guest_system_info', { vmName, connectionId }),
+  getGuestNetworkInfo: (vmName: string, connectionId?: string) => invoke<GuestNetworkInfo>('get_guest_network_info', { vmName, connectionId }),
+  getGuestDiskUsage: (vmName: string, connectionId?: string) => invoke<GuestDiskUsage>('get_guest_disk_usage', { vmName, connectionId }),
+  executeGuestCommand: (vmName: string, command: string, args: string[], timeoutSeconds?: number, connectionId?: string) => invoke<GuestCommandResult>('execute_guest_command', { vmName, command, args, timeoutSeconds, connectionId }),
+  readGuestFile: (vmName: string, path: string, connectionId?: string) => invoke<string>('read_guest_file', { vmName, path, connectionId }),
+  writeGuestFile: (vmName: string, path: string, content: string, createDirs?: boolean, connectionId?: string) => invoke<void>('write_guest_file', { vmName, path, content, createDirs, connectionId }),
+  guestAgentShutdown: (vmName: string, force?: boolean, connectionId?: string) => invoke<void>('guest_agent_shutdown', { vmName, force, connectionId }),
+  guestAgentReboot: (vmName: string, force?: boolean, connectionId?: string) => invoke<void>('guest_agent_reboot', { vmName, force, connectionId }),
   // Extended Guest Agent Operations
-  getGuestCpuStats: (vmName: string) => invoke<GuestCpuStats>('get_guest_cpu_stats', { vmName }),
-  getGuestDiskStats: (vmName: string) => invoke<GuestDiskStats>('get_guest_disk_stats', { vmName }),
-  getGuestUsers: (vmName: string) => invoke<GuestUser[]>('get_guest_users', { vmName }),
-  getGuestTimezone: (vmName: string) => invoke<GuestTimezone>('get_guest_timezone', { vmName }),
-  getGuestFullInfo: (vmName: string) => invoke<GuestFullInfo>('get_guest_full_info', { vmName }),
+  getGuestCpuStats: (vmName: string, connectionId?: string) => invoke<GuestCpuStats>('get_guest_cpu_stats', { vmName, connectionId }),
+  getGuestDiskStats: (vmName: string, connectionId?: string) => invoke<GuestDiskStats>('get_guest_disk_stats', { vmName, connectionId }),
+  getGuestUsers: (vmName: string, connectionId?: string) => invoke<GuestUser[]>('get_guest_users', { vmName, connectionId }),
+  getGuestTimezone: (vmName: string, connectionId?: string) => invoke<GuestTimezone>('get_guest_timezone', { vmName, connectionId }),
+  getGuestFullInfo: (vmName: string, connectionId?: string) => invoke<GuestFullInfo>('get_guest_full_info', { vmName, connectionId }),
+  guestFsFreeze: (vmName: string, mountpoints?: string[], connectionId?: string) =>
+    invoke<number>('guest_fs_freeze', { vmName, mountpoints: mountpoints ?? null, connectionId }),
+  guestFsThaw: (vmName: string, connectionId?: string) => invoke<number>('guest_fs_thaw', { vmName, connectionId }),
+  getGuestFsFreezeStatus: (vmName: string, connectionId?: string) => invoke<string>('get_guest_fs_freeze_status', { vmName, connectionId }),
 
   // CD-ROM/ISO Management
-  mountIso: (vmId: string, isoPath: string) => invoke<void>('mount_iso', { vmId, isoPath }),
-  mountGuestAgentIso: (vmId: string) => invoke<void>('mount_guest_agent_iso', { vmId }),
-  ejectCdrom: (vmId: string) => invoke<void>('eject_cdrom', { vmId }),
+  mountIso: (vmId: string, isoPath: string, connectionId?: string) => invoke<void>('mount_iso', { vmId, isoPath, connectionId }),
+  mountGuestAgentIso: (vmId: string, connectionId?: string) => invoke<void>('mount_guest_agent_iso', { vmId, connectionId }),
+  ejectCdrom: (vmId: string, connectionId?: string) => invoke<void>('eject_cdrom', { vmId, connectionId }),
 
   // Boot Order
-  updateVmBootOrder: (vmId: string, bootOrder: string[]) => invoke<void>('update_vm_boot_order', { vmId, bootOrder }),
+  updateVmBootOrder: (vmId: string, bootOrder: string[], connectionId?: string) => invoke<void>('update_vm_boot_order', { vmId, bootOrder, connectionId }),
 
   // VM Rename
-  renameVm: (vmId: string, newName: string) => invoke<void>('rename_vm', { vmId, newName }),
+  renameVm: (vmId: string, newName: string, connectionId?: string) => invoke<void>('rename_vm', { vmId, newName, connectionId }),
 
   // VM Autostart
-  getVmAutostart: (vmId: string) => invoke<boolean>('get_vm_autostart', { vmId }),
-  setVmAutostart: (vmId: string, enable: boolean) => invoke<void>('set_vm_autostart', { vmId, enable }),
+  getVmAutostart: (vmId: string, connectionId?: string) => invoke<boolean>('get_vm_autostart', { vmId, connectionId }),
+  setVmAutostart: (vmId: string, enable: boolean, connectionId?: string) => invoke<void>('set_vm_autostart', { vmId, enable, connectionId }),
 
   // VM CPU/Memory Configuration
-  setVmVcpus: (vmId: string, vcpus: number) => invoke<void>('set_vm_vcpus', { vmId, vcpus }),
-  setVmMemory: (vmId: string, memoryMb: number) => invoke<void>('set_vm_memory', { vmId, memoryMb }),
-  setVmCpuTopology: (vmId: string, sockets: number, cores: number, threads: number) =>
-    invoke<void>('set_vm_cpu_topology', { vmId, sockets, cores, threads }),
+  setVmVcpus: (vmId: string, vcpus: number, connectionId?: string) => invoke<void>('set_vm_vcpus', { vmId, vcpus, connectionId }),
+  setVmMemory: (vmId: string, memoryMb: number, connectionId?: string) => invoke<void>('set_vm_memory', { vmId, memoryMb, connectionId }),
+  setVmCpuTopology: (vmId: string, sockets: number, cores: number, threads: number, connectionId?: string) =>
+    invoke<void>('set_vm_cpu_topology', { vmId, sockets, cores, threads, connectionId }),
 
   // CPU Model Configuration
-  getCpuModel: (vmId: string) => invoke<import('./types').CpuModelConfig>('get_cpu_model', { vmId }),
-  setCpuModel: (vmId: string, mode: string, model?: string) =>
-    invoke<void>('set_cpu_model', { vmId, mode, model }),
+  getCpuModel: (vmId: string, connectionId?: string) => invoke<import('./types').CpuModelConfig>('get_cpu_model', { vmId, connectionId }),
+  setCpuModel: (vmId: string, mode: string, model?: string, connectionId?: string) =>
+    invoke<void>('set_cpu_model', { vmId, mode, model, connectionId }),
   getAvailableCpuModels: () => invoke<string[]>('get_available_cpu_models'),
 
   // CPU Pinning
-  getCpuPinning: (vmId: string) => invoke<[number, number[]][]>('get_cpu_pinning', { vmId }),
-  setCpuPin: (vmId: string, vcpu: number, hostCpus: number[]) =>
-    invoke<void>('set_cpu_pin', { vmId, vcpu, hostCpus }),
-  clearCpuPin: (vmId: string, vcpu: number) =>
-    invoke<void>('clear_cpu_pin', { vmId, vcpu }),
+  getCpuPinning: (vmId: string, connectionId?: string) => invoke<[number, number[]][]>('get_cpu_pinning', { vmId, connectionId }),
+  setCpuPin: (vmId: string, vcpu: number, hostCpus: number[], connectionId?: string) =>
+    invoke<void>('set_cpu_pin', { vmId, vcpu, hostCpus, connectionId }),
+  clearCpuPin: (vmId: string, vcpu: number, connectionId?: string) =>
+    invoke<void>('clear_cpu_pin', { vmId, vcpu, connectionId }),
 
   // VM Network Interfaces
-  attachInterface: (vmId: string, network: string, model: string, macAddress?: string) =>
-    invoke<string>('attach_interface', { vmId, network, model, macAddress }),
+  attachInterface: (vmId: string, network: string, model: string, macAddress?: string, connectionId?: string) =>
+    invoke<string>('attach_interface', { vmId, network, model, macAddress, connectionId }),
   attachInterfaceAdvanced: (
     vmId: string,
     interfaceType: import('./types').InterfaceType,
@@ -361,7 +435,8 @@ export const api = {
       vlanId?: number,
       portgroup?: string,
       mtu?: number,
-    }
+    },
+    connectionId?: string
   ) => invoke<string>('attach_interface_advanced', {
     vmId,
     interfaceType,
@@ -372,45 +447,46 @@ export const api = {
     vlanId: options?.vlanId,
     portgroup: options?.portgroup,
     mtu: options?.mtu,
+    connectionId,
   }),
   listHostInterfaces: () => invoke<import('./types').HostNetworkInterface[]>('list_host_interfaces'),
-  detachInterface: (vmId: string, macAddress: string) =>
-    invoke<void>('detach_interface', { vmId, macAddress }),
+  detachInterface: (vmId: string, macAddress: string, connectionId?: string) =>
+    invoke<void>('detach_interface', { vmId, macAddress, connectionId }),
 
   // VM Sound Devices
-  attachSound: (vmId: string, model: string) =>
-    invoke<void>('attach_sound', { vmId, model }),
-  detachSound: (vmId: string) =>
-    invoke<void>('detach_sound', { vmId }),
+  attachSound: (vmId: string, model: string, connectionId?: string) =>
+    invoke<void>('attach_sound', { vmId, model, connectionId }),
+  detachSound: (vmId: string, connectionId?: string) =>
+    invoke<void>('detach_sound', { vmId, connectionId }),
 
   // VM Input Devices
-  attachInput: (vmId: string, deviceType: string, bus: string) =>
-    invoke<void>('attach_input', { vmId, deviceType, bus }),
+  attachInput: (vmId: string, deviceType: string, bus: string, connectionId?: string) =>
+    invoke<void>('attach_input', { vmId, deviceType, bus, connectionId }),
 
   // VM RNG Device
-  attachRng: (vmId: string, backend: string = '/dev/urandom') =>
-    invoke<void>('attach_rng', { vmId, backend }),
+  attachRng: (vmId: string, backend: string = '/dev/urandom', connectionId?: string) =>
+    invoke<void>('attach_rng', { vmId, backend, connectionId }),
 
   // VM Watchdog Device
-  attachWatchdog: (vmId: string, model: string, action: string = 'reset') =>
-    invoke<void>('attach_watchdog', { vmId, model, action }),
+  attachWatchdog: (vmId: string, model: string, action: string = 'reset', connectionId?: string) =>
+    invoke<void>('attach_watchdog', { vmId, model, action, connectionId }),
 
   // VM Channel Device
-  attachChannel: (vmId: string, channelType: string) =>
-    invoke<void>('attach_channel', { vmId, channelType }),
+  attachChannel: (vmId: string, channelType: string, connectionId?: string) =>
+    invoke<void>('attach_channel', { vmId, channelType, connectionId }),
 
   // VM Filesystem Sharing
-  attachFilesystem: (vmId: string, sourcePath: string, targetMount: string, fsType: string, readonly: boolean = false) =>
-    invoke<void>('attach_filesystem', { vmId, sourcePath, targetMount, fsType, readonly }),
+  attachFilesystem: (vmId: string, sourcePath: string, targetMount: string, fsType: string, readonly: boolean = false, connectionId?: string) =>
+    invoke<void>('attach_filesystem', { vmId, sourcePath, targetMount, fsType, readonly, connectionId }),
 
   // PCI Passthrough
   listPciDevices: () => invoke<import('./types').PciDevice[]>('list_pci_devices'),
   checkIommuStatus: () => invoke<import('./types').IommuStatus>('check_iommu_status'),
   getIommuGroups: () => invoke<import('./types').IommuGroup[]>('get_iommu_groups'),
-  attachPciDevice: (vmId: string, pciAddress: string, managed: boolean = true) =>
-    invoke<void>('attach_pci_device', { vmId, pciAddress, managed }),
-  detachPciDevice: (vmId: string, pciAddress: string) =>
-    invoke<void>('detach_pci_device', { vmId, pciAddress }),
+  attachPciDevice: (vmId: string, pciAddress: string, managed: boolean = true, connectionId?: string) =>
+    invoke<void>('attach_pci_device', { vmId, pciAddress, managed, connectionId }),
+  detachPciDevice: (vmId: string, pciAddress: string, connectionId?: string) =>
+    invoke<void>('detach_pci_device', { vmId, pciAddress, connectionId }),
   getVfioStatus: (pciAddress: string) =>
     invoke<import('./types').VfioStatus>('get_vfio_status', { pciAddress }),
   bindToVfio: (pciAddress: string) =>
@@ -420,28 +496,28 @@ export const api = {
 
   // USB Passthrough
   listUsbDevices: () => invoke<import('./types').UsbDevice[]>('list_usb_devices'),
-  attachUsbDevice: (vmId: string, vendorId: string, productId: string) =>
-    invoke<void>('attach_usb_device', { vmId, vendorId, productId }),
-  detachUsbDevice: (vmId: string, vendorId: string, productId: string) =>
-    invoke<void>('detach_usb_device', { vmId, vendorId, productId }),
-  getVmUsbDevices: (vmId: string) => invoke<import('./types').UsbDevice[]>('get_vm_usb_devices', { vmId }),
+  attachUsbDevice: (vmId: string, vendorId: string, productId: string, connectionId?: string) =>
+    invoke<void>('attach_usb_device', { vmId, vendorId, productId, connectionId }),
+  detachUsbDevice: (vmId: string, vendorId: string, productId: string, connectionId?: string) =>
+    invoke<void>('detach_usb_device', { vmId, vendorId, productId, connectionId }),
+  getVmUsbDevices: (vmId: string, connectionId?: string) => invoke<import('./types').UsbDevice[]>('get_vm_usb_devices', { vmId, connectionId }),
 
   // Graphics Device
-  attachGraphics: (vmId: string, graphicsType: string, listenAddress?: string, port?: number) =>
-    invoke<void>('attach_graphics', { vmId, graphicsType, listenAddress, port }),
+  attachGraphics: (vmId: string, graphicsType: string, listenAddress?: string, port?: number, connectionId?: string) =>
+    invoke<void>('attach_graphics', { vmId, graphicsType, listenAddress, port, connectionId }),
 
   // Video Device
-  attachVideo: (vmId: string, model: string, vram?: number, heads?: number, acceleration3d?: boolean) =>
-    invoke<void>('attach_video', { vmId, model, vram, heads, acceleration3d }),
+  attachVideo: (vmId: string, model: string, vram?: number, heads?: number, acceleration3d?: boolean, connectionId?: string) =>
+    invoke<void>('attach_video', { vmId, model, vram, heads, acceleration3d, connectionId }),
 
   // MDEV (vGPU) Passthrough
   checkMdevStatus: () => invoke<import('./types').MdevStatus>('check_mdev_status'),
   listMdevTypes: () => invoke<import('./types').MdevType[]>('list_mdev_types'),
   listMdevDevices: () => invoke<import('./types').MdevDevice[]>('list_mdev_devices'),
-  attachMdev: (vmId: string, mdevUuid: string) =>
-    invoke<void>('attach_mdev', { vmId, mdevUuid }),
-  detachMdev: (vmId: string, mdevUuid: string) =>
-    invoke<void>('detach_mdev', { vmId, mdevUuid }),
+  attachMdev: (vmId: string, mdevUuid: string, connectionId?: string) =>
+    invoke<void>('attach_mdev', { vmId, mdevUuid, connectionId }),
+  detachMdev: (vmId: string, mdevUuid: string, connectionId?: string) =>
+    invoke<void>('detach_mdev', { vmId, mdevUuid, connectionId }),
   createMdev: (parentDevice: string, mdevType: string) =>
     invoke<string>('create_mdev', { parentDevice, mdevType }),
   removeMdev: (mdevUuid: string) =>
@@ -455,69 +531,126 @@ export const api = {
     invoke<void>('enable_sriov_vfs', { pfAddress, numVfs }),
   configureSriovVf: (config: import('./types').SriovVfConfig) =>
     invoke<void>('configure_sriov_vf', { config }),
-  attachSriovVf: (vmId: string, vfAddress: string) =>
-    invoke<void>('attach_sriov_vf', { vmId, vfAddress }),
-  detachSriovVf: (vmId: string, vfAddress: string) =>
-    invoke<void>('detach_sriov_vf', { vmId, vfAddress }),
+  attachSriovVf: (vmId: string, vfAddress: string, connectionId?: string) =>
+    invoke<void>('attach_sriov_vf', { vmId, vfAddress, connectionId }),
+  detachSriovVf: (vmId: string, vfAddress: string, connectionId?: string) =>
+    invoke<void>('detach_sriov_vf', { vmId, vfAddress, connectionId }),
 
   // Serial Port Device
-  attachSerial: (vmId: string, portType: string, targetPort: number = 0) =>
-    invoke<void>('attach_serial', { vmId, portType, targetPort }),
+  attachSerial: (vmId: string, portType: string, targetPort: number = 0, connectionId?: string) =>
+    invoke<void>('attach_serial', { vmId, portType, targetPort, connectionId }),
 
   // Console Device
-  attachConsole: (vmId: string, targetPort: number = 0, targetType: string = 'virtio') =>
-    invoke<void>('attach_console', { vmId, targetPort, targetType }),
+  attachConsole: (vmId: string, targetPort: number = 0, targetType: string = 'virtio', connectionId?: string) =>
+    invoke<void>('attach_console', { vmId, targetPort, targetType, connectionId }),
 
   // TPM Device
-  attachTpm: (vmId: string, model: string = 'tpm-crb', version: string = '2.0') =>
-    invoke<void>('attach_tpm', { vmId, model, version }),
+  attachTpm: (vmId: string, model: string = 'tpm-crb', version: string = '2.0', connectionId?: string) =>
+    invoke<void>('attach_tpm', { vmId, model, version, connectionId }),
 
   // USB Controller
-  attachUsbController: (vmId: string, model: string) =>
-    invoke<void>('attach_usb_controller', { vmId, model }),
+  attachUsbController: (vmId: string, model: string, connectionId?: string) =>
+    invoke<void>('attach_usb_controller', { vmId, model, connectionId }),
 
   // SCSI Controller
-  attachScsiController: (vmId: string, model: string) =>
-    invoke<void>('attach_scsi_controller', { vmId, model }),
+  attachScsiController: (vmId: string, model: string, connectionId?: string) =>
+    invoke<void>('attach_scsi_controller', { vmId, model, connectionId }),
 
   // Panic Notifier
-  attachPanicNotifier: (vmId: string, model: string) =>
-    invoke<void>('attach_panic_notifier', { vmId, model }),
+  attachPanicNotifier: (vmId: string, model: string, connectionId?: string) =>
+    invoke<void>('attach_panic_notifier', { vmId, model, connectionId }),
 
   // VirtIO VSOCK
-  attachVsock: (vmId: string, cid: number) =>
-    invoke<void>('attach_vsock', { vmId, cid }),
+  attachVsock: (vmId: string, cid: number, connectionId?: string) =>
+    invoke<void>('attach_vsock', { vmId, cid, connectionId }),
 
   // Parallel Port
-  attachParallel: (vmId: string, targetPort: number) =>
-    invoke<void>('attach_parallel', { vmId, targetPort }),
+  attachParallel: (vmId: string, targetPort: number, connectionId?: string) =>
+    invoke<void>('attach_parallel', { vmId, targetPort, connectionId }),
 
   // Smartcard Reader
-  attachSmartcard: (vmId: string, mode: string) =>
-    invoke<void>('attach_smartcard', { vmId, mode }),
+  attachSmartcard: (vmId: string, mode: string, connectionId?: string) =>
+    invoke<void>('attach_smartcard', { vmId, mode, connectionId }),
 
   // NUMA Configuration
   getHostNumaTopology: () =>
     invoke<import('./types').HostNumaNode[]>('get_host_numa_topology'),
-  getVmNumaConfig: (vmId: string) =>
-    invoke<import('./types').VmNumaConfig | null>('get_vm_numa_config', { vmId }),
-  setVmNumaConfig: (vmId: string, config: import('./types').VmNumaConfig) =>
-    invoke<void>('set_vm_numa_config', { vmId, config }),
-  clearVmNumaConfig: (vmId: string) =>
-    invoke<void>('clear_vm_numa_config', { vmId }),
+  getVmNumaConfig: (vmId: string, connectionId?: string) =>
+    invoke<import('./types').VmNumaConfig | null>('get_vm_numa_config', { vmId, connectionId }),
+  setVmNumaConfig: (vmId: string, config: import('./types').VmNumaConfig, connectionId?: string) =>
+    invoke<void>('set_vm_numa_config', { vmId, config, connectionId }),
+  clearVmNumaConfig: (vmId: string, connectionId?: string) =>
+    invoke<void>('clear_vm_numa_config', { vmId, connectionId }),
 
   // Window Management
-  openVmDetailsWindow: (vmId: string, vmName: string) => invoke<void>('open_vm_details_window', { vmId, vmName }),
-  openConsoleWindow: (vmId: string, vmName: string) => invoke<void>('open_console_window', { vmId, vmName }),
+  openVmDetailsWindow: (vmId: string, vmName: string, connectionId?: string) => invoke<void>('open_vm_details_window', { vmId, vmName, connectionId }),
+  openConsoleWindow: (vmId: string, vmName: string, connectionId?: string) => invoke<void>('open_console_window', { vmId, vmName, connectionId }),
   closeVmWindows: (vmId: string) => invoke<void>('close_vm_windows', { vmId }),
   getOpenWindows: () => invoke<string[]>('get_open_windows'),
   closeWindow: (windowLabel: string) => invoke<void>('close_window', { windowLabel }),
 
   // Serial Console
-  getSerialConsoleInfo: (vmId: string) => invoke<import('./types').SerialConsoleInfo>('get_serial_console_info', { vmId }),
-  openSerialConsole: (vmId: string) => invoke<import('./types').SerialConsoleInfo>('open_serial_console', { vmId }),
+  getSerialConsoleInfo: (vmId: string, connectionId?: string) => invoke<import('./types').SerialConsoleInfo>('get_serial_console_info', { vmId, connectionId }),
+  openSerialConsole: (vmId: string, connectionId?: string) => invoke<import('./types').SerialConsoleInfo>('open_serial_console', { vmId, connectionId }),
   closeSerialConsole: (vmId: string) => invoke<void>('close_serial_console', { vmId }),
   readSerialConsole: (vmId: string) => invoke<string>('read_serial_console', { vmId }),
   writeSerialConsole: (vmId: string, input: string) => invoke<void>('write_serial_console', { vmId, input }),
   isSerialConsoleConnected: (vmId: string) => invoke<boolean>('is_serial_console_connected', { vmId }),
+
+  // Background Jobs
+  listJobs: () => invoke<Job[]>('list_jobs'),
+  getJob: (jobId: string) => invoke<Job>('get_job', { jobId }),
+  cancelJob: (jobId: string) => invoke<void>('cancel_job', { jobId }),
+  clearFinishedJobs: () => invoke<void>('clear_finished_jobs'),
+}
+
+/**
+ * Wait for a background job to finish and resolve with its result.
+ * Rejects with the job error when it fails or is cancelled.
+ */
+export async function waitForJob<T>(jobId: string, onProgress?: (job: Job) => void): Promise<T> {
+  return new Promise<T>((resolve, reject) => {
+    let done = false
+    const settle = (job: Job) => {
+      if (done) return
+      onProgress?.(job)
+      if (job.status === 'running') return
+      done = true
+      unlisten.then((stop) => stop())
+      if (job.status === 'completed') {
+        resolve(job.result as T)
+      } else {
+        reject(new Error(job.error ?? `Job ${job.status}`))
+      }
+    }
+
+    const unlisten = listen<Job>('job-event', (event) => {
+      if (event.payload.id === jobId) settle(event.payload)
+    })
+    // The job may have finished before the listener was registered
+    unlisten.then(() => api.getJob(jobId)).then(settle, (error) => {
+      if (!done) {
+        done = true
+        unlisten.then((stop) => stop())
+        reject(error)
+      }
+    })
+  })
+}
+
+/**
+ * Run a batch VM operation once per host, since each batch call targets a
+ * single connection. Results from every host are merged in order.
+ */
+export async function batchPerConnection(
+  vms: VM[],
+  run: (vmIds: string[], connectionId?: string) => Promise<BatchOperationResult[]>
+): Promise<BatchOperationResult[]> {
+  const groups = new Map<string | undefined, string[]>()
+  for (const vm of vms) {
+    const key = vm.connectionId ?? undefined
+    groups.set(key, [...(groups.get(key) ?? []), vm.id])
+  }
+  const results = await Promise.all([...groups].map(([connectionId, vmIds]) => run(vmIds, connectionId)))
+  return results.flat()
 }
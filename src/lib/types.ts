@@ -79,6 +79,7 @@ export interface VM {
   cpuSockets: number
   cpuCores: number
   cpuThreads: number
+  connectionId?: string | null  // Saved connection the VM was listed from
   topology?: {
     sockets: number
     cores: number
@@ -135,6 +136,20 @@ export interface SavedConnection {
   path: string
 }
 
+export interface ConnectionCredentials {
+  sshKeyPath?: string
+  tlsPkiPath?: string  // Directory holding cacert.pem, clientcert.pem and clientkey.pem
+  saslUsername?: string
+  saslPassword?: string
+}
+
+export interface CredentialsInfo {
+  sshKeyPath: string | null
+  tlsPkiPath: string | null
+  saslUsername: string | null
+  hasSaslPassword: boolean
+}
+
 // Cloud-Init Configuration
 export interface CloudInitNetworkConfig {
   version: number
@@ -314,6 +329,7 @@ export interface Network {
   active: boolean
   autostart: boolean
   ipRange: string | null
+  connectionId?: string | null
 }
 
 export interface DhcpLease {
@@ -418,6 +434,7 @@ export interface StoragePool {
   availableBytes: number
   path: string
   autostart: boolean
+  connectionId?: string | null
 }
 
 export interface Volume {
@@ -500,6 +517,16 @@ export interface OvaImportConfig {
 // Snapshot types
 export type SnapshotState = 'disksnapshot' | 'running' | 'paused' | 'shutoff'
 
+// internal: kept inside the qcow2 images; external: each disk frozen under a new overlay file
+export type SnapshotLocation = 'internal' | 'external'
+
+export interface SnapshotDisk {
+  target: string
+  snapshot: 'internal' | 'external' | 'no'
+  overlay?: string // file created by an external snapshot; later writes go here
+  backing?: string // image holding the disk's state at the snapshot
+}
+
 export interface Snapshot {
   name: string
   description?: string
@@ -507,12 +534,58 @@ export interface Snapshot {
   state: SnapshotState
   parent?: string
   isCurrent: boolean
+  location: SnapshotLocation
+  disks: SnapshotDisk[]
+  memoryFile?: string
+}
+
+// Snapshot with its saved definition and the snapshots taken after it
+export interface SnapshotNode extends Snapshot {
+  domainXml: string
+  hasMemory: boolean
+  sizeBytes?: number // approximate host space; absent when it cannot be measured
+  children: SnapshotNode[] // oldest first
+}
+
+// added: only in the current definition; removed: only in the snapshot
+export type ConfigChangeKind = 'added' | 'removed' | 'modified'
+
+export interface ConfigChange {
+  section: string // top-level element, or 'devices'
+  item: string // e.g. 'disk vda', 'interface 52:54:00:12:34:56', 'memory'
+  kind: ConfigChangeKind
+  snapshot?: string // XML in the snapshot
+  current?: string // XML in the current definition
+}
+
+export interface SnapshotConfigDiff {
+  snapshotName: string
+  changes: ConfigChange[]
 }
 
 export interface SnapshotConfig {
   name: string
   description?: string
   includeMemory?: boolean
+  quiesce?: QuiesceOptions // disk-only snapshots of running VMs
+  location?: SnapshotLocation // default internal
+}
+
+// Program run inside the guest with guest-exec
+export interface GuestHook {
+  path: string
+  args?: string[]
+  timeoutSecs?: number // default 120
+}
+
+// Freeze guest filesystems through the guest agent for application-consistent images
+export interface QuiesceOptions {
+  enabled: boolean
+  mountpoints?: string[] // empty freezes every filesystem
+  timeoutSecs?: number // watchdog thaws the guest after this, default 60
+  required?: boolean // fail instead of falling back to crash-consistent
+  preFreezeHook?: GuestHook | null
+  postThawHook?: GuestHook | null
 }
 
 // Historical Metrics types
@@ -528,6 +601,49 @@ export interface VmMetrics {
   networkTxBytesPerSec: number
 }
 
+// Hypervisor metrics; swap, load, hugepages and KSM are only known for the local host
+export interface HostMetrics {
+  connectionId: string
+  timestamp: number
+  cpuUsage?: number | null
+  cpuCount: number
+  memoryTotalMb: number
+  memoryFreeMb: number
+  swapTotalMb?: number | null
+  swapFreeMb?: number | null
+  load1?: number | null
+  load5?: number | null
+  load15?: number | null
+  hugepagesTotal?: number | null
+  hugepagesFree?: number | null
+  ksmSharedMb?: number | null
+  vcpusAllocated: number
+  memoryAllocatedMb: number
+}
+
+export interface PoolMetrics {
+  connectionId: string
+  poolName: string
+  timestamp: number
+  capacityBytes: number
+  allocationBytes: number
+  availableBytes: number
+}
+
+export interface NetworkThroughput {
+  connectionId: string
+  networkName: string
+  bridge: string
+  rxBytesPerSec: number
+  txBytesPerSec: number
+}
+
+export interface HostSnapshot {
+  host: HostMetrics
+  pools: PoolMetrics[]
+  networks: NetworkThroughput[]
+}
+
 export interface MetricDataPoint {
   timestamp: number
   value: number
@@ -560,42 +676,166 @@ export interface CreateTemplateRequest {
 }
 
 // Scheduler types
-export type ScheduleFrequency = 'once' | 'daily' | 'weekly' | 'monthly'
-export type OperationType = 'start' | 'stop' | 'reboot' | 'snapshot'
+export type ScheduleFrequency = 'once' | 'daily' | 'weekly' | 'monthly' | 'cron'
+export type OperationType =
+  | 'start'
+  | 'stop'
+  | 'reboot'
+  | 'snapshot'
+  | 'hibernate'
+  | 'guestCommand'
+  | 'setResources'
+  | 'pruneSnapshots'
+  | 'migrate'
+  | 'backup'
+  | 'verifyBackup' // newest restore point of a backup config
+
+// Typed payload required by guestCommand, setResources, pruneSnapshots, migrate, backup and verifyBackup
+export type OperationParams =
+  | { type: 'guestCommand'; command: string; args: string[] }
+  | { type: 'setResources'; vcpus?: number | null; memoryMb?: number | null }
+  | { type: 'pruneSnapshots'; keepLast: number; namePrefix: string }
+  | { type: 'migrate'; targetConnectionId: string; live: boolean; unsafeMigration: boolean }
+  | { type: 'backup'; backupConfigId: string }
+  | { type: 'snapshot'; quiesce: QuiesceOptions; location?: SnapshotLocation }
+  | { type: 'verifyBackup'; backupConfigId: string; options: VerifyOptions }
+export type ScheduleRunStatus = 'success' | 'failed' | 'skipped'
+// Handling of runs missed while the app was closed
+export type MissedRunPolicy = 'skip' | 'runOnce' | 'runAll'
+
+// Tag selector resolved against the VM inventory whenever a schedule fires
+export interface TagSelector {
+  tags: string[]
+  matchAll: boolean // require every tag instead of any
+}
 
 export interface ScheduledOperation {
   id: string
   name: string
-  vmId: string
+  vmId: string // empty for tag schedules
+  connectionId?: string | null
+  tagSelector?: TagSelector | null
+  parallelism: number
+  staggerSecs: number
   operation: OperationType
+  params?: OperationParams | null
   frequency: ScheduleFrequency
   scheduledTime: string // Format: "HH:MM"
   dayOfWeek?: number // 0-6 for weekly (0=Sunday)
-  dayOfMonth?: number // 1-31 for monthly
+  dayOfMonth?: number // 1-28 for monthly
+  cronExpression?: string | null // 5-field cron pattern for 'cron' frequency
+  timezone?: string | null // IANA name; system timezone when unset
+  missedRunPolicy: MissedRunPolicy
   enabled: boolean
   lastRun?: number
+  lastStatus?: ScheduleRunStatus | null
   nextRun: number
   createdAt: number
 }
 
+export interface VmRunResult {
+  vmId: string
+  vmName: string
+  connectionId: string | null
+  status: ScheduleRunStatus
+  message: string
+}
+
+// Emitted as 'schedule-run' after every executed schedule
+export interface ScheduleRun {
+  id: string
+  scheduleId: string
+  scheduleName: string
+  vmId: string
+  operation: OperationType
+  status: ScheduleRunStatus
+  message: string
+  vmResults: VmRunResult[] // per-VM results of tag schedules
+  scheduledFor: number
+  startedAt: number
+  finishedAt: number
+}
+
 export interface CreateScheduleRequest {
   name: string
   vmId: string
+  connectionId?: string | null
+  tagSelector?: TagSelector | null
+  parallelism?: number
+  staggerSecs?: number
   operation: OperationType
+  params?: OperationParams | null
   frequency: ScheduleFrequency
   scheduledTime: string
   dayOfWeek?: number
   dayOfMonth?: number
+  cronExpression?: string
+  timezone?: string
+  missedRunPolicy?: MissedRunPolicy
+}
+
+// Snapshot retention. Policies only prune snapshots named with the scheduler's
+// 'scheduled-' prefix; a snapshot is kept if any keep rule selects it, and
+// maxAgeDays then removes everything older.
+export interface RetentionRules {
+  keepLast?: number | null
+  keepDailyDays?: number | null // newest snapshot per day
+  keepWeeklyWeeks?: number | null // newest snapshot per ISO week
+  maxAgeDays?: number | null
+}
+
+export interface VmPruneResult {
+  vmId: string
+  vmName: string
+  connectionId?: string | null
+  kept: number
+  deleted: string[] // would be deleted, for dry runs
+  error?: string | null
+}
+
+// Emitted as 'snapshot-prune' after every background run of a policy
+export interface PruneReport {
+  policyId: string
+  policyName: string
+  startedAt: number
+  finishedAt: number
+  dryRun: boolean
+  vms: VmPruneResult[]
+  error?: string | null
+}
+
+export interface SnapshotRetentionPolicy {
+  id: string
+  name: string
+  vmId: string // Empty for tag policies
+  tagSelector?: TagSelector | null
+  connectionId?: string | null
+  rules: RetentionRules
+  enabled: boolean
+  createdAt: number
+  lastReport?: PruneReport | null
+}
+
+export interface CreateRetentionPolicyRequest {
+  name: string
+  vmId: string
+  tagSelector?: TagSelector | null
+  connectionId?: string | null
+  rules: RetentionRules
 }
 
 // Alert types
-export type ThresholdType = 'cpu' | 'memory' | 'disk' | 'network'
+export type ThresholdType = 'cpu' | 'memory' | 'disk' | 'network' | 'swap' | 'load' | 'hugepages' | 'storage'
 export type AlertSeverity = 'info' | 'warning' | 'critical'
+export type AlertScope = 'vm' | 'host' | 'pool' | 'network'
 
 export interface ResourceAlert {
   id: string
   name: string
-  vmId: string
+  scope: AlertScope
+  vmId: string // Empty for host, pool and network alerts
+  connectionId?: string | null
+  target?: string | null // Pool or network name
   thresholdType: ThresholdType
   thresholdValue: number
   severity: AlertSeverity
@@ -604,21 +844,35 @@ export interface ResourceAlert {
   currentTriggerCount: number
   lastTriggered?: number
   createdAt: number
+  channelIds: string[]
+  clearThreshold?: number | null // Defaults to thresholdValue
+  cooldownSecs: number
+  renotifySecs: number // 0 = notify once
 }
 
 export interface CreateAlertRequest {
   name: string
+  scope?: AlertScope
   vmId: string
+  connectionId?: string | null
+  target?: string | null
   thresholdType: ThresholdType
   thresholdValue: number
   severity: AlertSeverity
   consecutiveChecks: number
+  channelIds?: string[]
+  clearThreshold?: number | null
+  cooldownSecs?: number
+  renotifySecs?: number
 }
 
 export interface AlertEvent {
   alertId: string
+  instanceId: string
   alertName: string
+  scope: AlertScope
   vmId: string
+  subject: string
   thresholdType: ThresholdType
   thresholdValue: number
   currentValue: number
@@ -626,13 +880,171 @@ export interface AlertEvent {
   timestamp: number
 }
 
+// Event-based alert rules
+export type EventRuleKind =
+  | 'vmCrashed'
+  | 'guestAgentUnresponsive'
+  | 'guestPanic'
+  | 'storagePoolUsage'
+  | 'networkInactive'
+  | 'connectionLost'
+  | 'backupFailed'
+  | 'backupVerifyFailed'
+  | 'scheduleFailed'
+  | 'snapshotPruneFailed'
+
+export interface EventAlertRule {
+  id: string
+  name: string
+  kind: EventRuleKind
+  severity: AlertSeverity
+  enabled: boolean
+  target?: string | null // VM ID, pool/network name, connection ID or schedule/backup ID; empty matches all
+  connectionId?: string | null
+  thresholdPercent?: number | null
+  clearThresholdPercent?: number | null
+  channelIds: string[]
+  cooldownSecs: number
+  renotifySecs: number
+  lastTriggered?: number | null
+  createdAt: number
+}
+
+export interface CreateEventRuleRequest {
+  name: string
+  kind: EventRuleKind
+  severity: AlertSeverity
+  target?: string | null
+  connectionId?: string | null
+  thresholdPercent?: number | null
+  clearThresholdPercent?: number | null
+  channelIds?: string[]
+  cooldownSecs?: number
+  renotifySecs?: number
+}
+
+export interface RuleAlertEvent {
+  ruleId: string
+  instanceId: string
+  ruleName: string
+  kind: EventRuleKind
+  severity: AlertSeverity
+  connectionId?: string | null
+  subjectId: string
+  subjectName: string
+  message: string
+  timestamp: number
+}
+
+// Alert instances and history
+export type AlertSource = 'resource' | 'rule'
+export type AlertState = 'firing' | 'acknowledged' | 'resolved'
+
+export interface AlertInstance {
+  id: string
+  source: AlertSource
+  sourceId: string
+  name: string
+  severity: AlertSeverity
+  vmId?: string | null
+  subjectId: string
+  subjectName: string
+  state: AlertState
+  message: string
+  triggerValue?: number | null
+  firedAt: number
+  lastNotifiedAt: number
+  notifyCount: number
+  acknowledgedAt?: number | null
+  acknowledgedBy?: string | null
+  resolvedAt?: number | null
+  resolvedBy?: string | null // null when the alert cleared on its own
+}
+
+export interface AlertHistoryQuery {
+  vmId?: string
+  sourceId?: string
+  state?: AlertState
+  startTime?: number
+  endTime?: number
+  limit?: number
+}
+
+// Notification channel types
+export type SmtpSecurity = 'none' | 'startTls' | 'tls'
+export type CommandPayload = 'env' | 'stdin'
+
+export type ChannelConfig =
+  | { type: 'webhook'; url: string; headers: Record<string, string>; hmacSecret?: string | null }
+  | {
+      type: 'smtp'
+      host: string
+      port: number
+      security: SmtpSecurity
+      username?: string | null
+      password?: string | null
+      from: string
+      to: string[]
+    }
+  | { type: 'command'; program: string; args: string[]; payload: CommandPayload; timeoutSecs: number }
+  | { type: 'desktop' }
+
+export type ChannelType = ChannelConfig['type']
+
+export interface RetryPolicy {
+  maxAttempts: number
+  backoffSecs: number
+  backoffMultiplier: number
+}
+
+export interface NotificationChannel {
+  id: string
+  name: string
+  enabled: boolean
+  config: ChannelConfig
+  severities: AlertSeverity[] // Always routed here, in addition to alerts that select the channel
+  retry: RetryPolicy
+  createdAt: number
+}
+
+export interface NotificationChannelRequest {
+  name: string
+  config: ChannelConfig
+  severities: AlertSeverity[]
+  retry: RetryPolicy
+  enabled: boolean
+}
+
+export type DeliveryStatus = 'delivered' | 'failed'
+
+export interface DeliveryRecord {
+  id: string
+  channelId: string
+  channelName: string
+  sourceId: string
+  title: string
+  severity: AlertSeverity
+  status: DeliveryStatus
+  attempts: number
+  error?: string | null
+  timestamp: number
+}
+
 // Backup types
+// full: every run copies all disks; incremental/differential: changed blocks since the previous run / the chain's full
+export type BackupMode = 'full' | 'incremental' | 'differential'
+
 export interface BackupConfig {
   id: string
   name: string
   vmId: string
   scheduleId: string
   retentionCount: number
+  targetDir: string
+  mode: BackupMode
+  chainLength: number // 0 = no limit
+  quiesce: QuiesceOptions
+  targetId?: string | null // backup target runs are copied to
   enabled: boolean
   lastBackup?: number
   backupCount: number
@@ -647,6 +1059,179 @@ export interface CreateBackupRequest {
   dayOfWeek?: number
   dayOfMonth?: number
   retentionCount: number
+  targetDir: string
+  mode: BackupMode
+  chainLength: number
+  quiesce?: QuiesceOptions
+  targetId?: string | null
+  connectionId?: string | null  // Connection the VM was listed from; only the local host is supported
+}
+
+export type BackupRunStatus = 'completed' | 'failed' | 'pruned'
+
+export interface BackupDisk {
+  target: string
+  source: string
+  sourceFormat?: string | null
+  file: string
+  backing?: string | null // parent set image this overlay is backed by
+  sizeBytes: number
+}
+
+export interface BackupRun {
+  id: string
+  configId: string
+  vmId: string
+  vmName: string
+  startedAt: number
+  finishedAt: number
+  durationSecs: number
+  status: BackupRunStatus
+  kind: BackupMode
+  chainMode: BackupMode
+  chainId: string
+  parentRunId?: string | null
+  checkpoint?: string | null
+  note?: string | null // why a full backup was taken instead of the configured mode
+  live: boolean
+  quiesced: boolean // guest filesystems were frozen when the job started
+  path: string
+  sizeBytes: number
+  disks: BackupDisk[]
+  skippedDisks: string[]
+  nvram?: string | null
+  tpm: boolean
+  error?: string | null
+  remote?: RemoteCopy | null // copy on the config's backup target
+  verification?: BackupVerification | null // latest verification
+}
+
+export interface BackupChain {
+  id: string
+  mode: BackupMode
+  points: BackupRun[] // oldest first; the first is self-contained
+  sizeBytes: number
+  checkpoint?: string | null
+}
+
+export interface VerifyOptions {
+  boot: boolean // boot a throwaway, network-less copy of the point
+  bootTimeoutSecs: number
+}
+
+export type VerifyStatus = 'passed' | 'failed'
+
+export interface VerifyCheck {
+  name: string
+  ok: boolean
+  detail: string
+}
+
+export interface BackupVerification {
+  startedAt: number
+  finishedAt: number
+  status: VerifyStatus
+  booted: boolean
+  checks: VerifyCheck[]
+}
+
+export type RestoreMode = 'newVm' | 'inPlace'
+
+export interface RestoreBackupRequest {
+  configId: string
+  runId: string
+  mode: RestoreMode
+  newName?: string | null // required for 'newVm'
+  pool: string
+  confirm?: boolean // required for 'inPlace'
+}
+
+export interface RestoreDiskRequest {
+  configId: string
+  runId: string
+  disk: string // target device in the backup
+  pool: string
+  replace?: boolean // swap the restored image into the VM (must be shut off)
+  confirm?: boolean
+}
+
+export interface RestoredDisk {
+  target: string
+  path: string
+}
+
+export interface RestoreResult {
+  vmId: string
+  vmName: string
+  disks: RestoredDisk[]
+}
+
+// Backup target types
+export type TargetConfig =
+  | { type: 'local'; path: string; requireMount: boolean } // NFS share or removable disk
+  | { type: 'sftp'; host: string; port: number; username: string; sshKeyPath?: string | null; path: string }
+  | {
+      type: 's3'
+      endpoint: string
+      region: string
+      bucket: string
+      prefix: string
+      accessKey: string
+      secretKey: string
+      pathStyle: boolean // required by MinIO
+    }
+
+export interface TargetCheck {
+  ok: boolean
+  checkedAt: number
+  latencyMs: number
+  error?: string | null
+}
+
+export interface BackupTarget {
+  id: string
+  name: string
+  config: TargetConfig
+  compression: boolean // zstd
+  encryptionPassphrase?: string | null // sets can't be restored without it
+  chunkSizeMb: number
+  lastCheck?: TargetCheck | null
+  createdAt: number
+}
+
+export interface CreateBackupTargetRequest {
+  name: string
+  config: TargetConfig
+  compression: boolean
+  encryptionPassphrase?: string | null
+  chunkSizeMb: number
+}
+
+export type RemoteStatus = 'pending' | 'uploaded' | 'failed'
+
+export interface RemoteCopy {
+  targetId: string
+  key: string
+  status: RemoteStatus
+  storedBytes: number // after compression and encryption
+  uploadedAt?: number | null
+  error?: string | null
+}
+
+export interface TargetUsage {
+  targetId: string
+  sets: number
+  rawBytes: number
+  storedBytes: number
+  pendingSets: number
+  availableBytes?: number | null
+}
+
+export interface CatalogImport {
+  sets: number
+  importedRuns: number
+  createdConfigs: number // recreated disabled, for sets whose config is not on this host
+  skippedSets: number
 }
 
 // Batch operations types
@@ -922,3 +1507,74 @@ export interface SerialConsoleInfo {
   ptyPath: string
   active: boolean
 }
+
+// Libvirt event stream types (emitted as 'vm-event', 'network-event', 'storage-pool-event')
+export type LifecycleEvent = 'defined' | 'undefined' | 'started' | 'suspended' | 'resumed' | 'stopped' | 'shutdown' | 'pmsuspended' | 'crashed' | 'unknown'
+export type ResourceLifecycleEvent = 'defined' | 'undefined' | 'started' | 'stopped' | 'created' | 'deleted' | 'unknown'
+
+export type VmEventKind =
+  | { type: 'lifecycle'; event: LifecycleEvent; detail: number }
+  | { type: 'reboot' }
+  | { type: 'deviceAdded'; deviceAlias: string }
+  | { type: 'deviceRemoved'; deviceAlias: string }
+  | { type: 'blockJob'; disk: string; jobType: 'pull' | 'copy' | 'commit' | 'activecommit' | 'backup' | 'unknown'; status: 'completed' | 'failed' | 'canceled' | 'ready' | 'unknown' }
+  | { type: 'agentLifecycle'; connected: boolean; reason: number }
+  | { type: 'watchdog'; action: number }
+
+export interface VmEvent {
+  connectionId: string
+  vmId: string
+  vmName: string
+  state: VmState
+  kind: VmEventKind
+  timestamp: number
+}
+
+export interface NetworkEvent {
+  connectionId: string
+  networkName: string
+  event: ResourceLifecycleEvent
+  active: boolean
+  timestamp: number
+}
+
+export interface StoragePoolEvent {
+  connectionId: string
+  poolId: string
+  poolName: string
+  event: ResourceLifecycleEvent
+  active: boolean
+  timestamp: number
+}
+
+// Emitted as 'connection-state-changed' by the connection supervisor
+export interface ConnectionStateEvent {
+  connectionId: string
+  state: 'connected' | 'disconnected' | 'reconnecting'
+  attempt: number
+  retryInSecs: number | null
+  error: string | null
+  timestamp: number
+}
+
+// Background jobs; progress and state changes are emitted as 'job-event'
+export type JobKind = 'cloneVm' | 'migrateVm' | 'uploadVolume' | 'downloadVolume' | 'importOva' | 'backupVm' | 'restoreBackup' | 'uploadBackup' | 'verifyBackup' | 'importBackupCatalog'
+
+export type JobStatus = 'running' | 'completed' | 'failed' | 'cancelled'
+
+export interface Job {
+  id: string
+  kind: JobKind
+  description: string
+  connectionId: string | null
+  target: string
+  status: JobStatus
+  step: string | null
+  percent: number
+  bytesProcessed: number
+  bytesTotal: number | null
+  result: unknown
+  error: string | null
+  startedAt: number
+  finishedAt: number | null
+}
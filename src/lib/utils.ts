@@ -4,3 +4,8 @@ import { twMerge } from "tailwind-merge"
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs))
 }
+
+/** Details route for a VM, pinned to the connection it lives on */
+export function vmDetailsPath(vm: { id: string; connectionId?: string | null }) {
+  return vm.connectionId ? `/vms/${vm.id}?connectionId=${encodeURIComponent(vm.connectionId)}` : `/vms/${vm.id}`
+}
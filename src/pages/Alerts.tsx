@@ -1,9 +1,15 @@
 import { AlertManager } from '@/components/vm/AlertManager'
+import { AlertHistoryPanel } from '@/components/alerts/AlertHistoryPanel'
+import { AlertRuleManager } from '@/components/alerts/AlertRuleManager'
+import { NotificationChannelManager } from '@/components/alerts/NotificationChannelManager'
 
 export function Alerts() {
   return (
-    <div className="container mx-auto p-6">
+    <div className="container mx-auto p-6 space-y-8">
+      <AlertHistoryPanel />
       <AlertManager />
+      <AlertRuleManager />
+      <NotificationChannelManager />
     </div>
   )
 }
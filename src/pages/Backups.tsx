@@ -1,9 +1,11 @@
 import { BackupManager } from '@/components/vm/BackupManager'
+import { BackupTargetManager } from '@/components/vm/BackupTargetManager'
 
 export default function Backups() {
   return (
-    <div className="container mx-auto p-6">
+    <div className="container mx-auto p-6 space-y-8">
       <BackupManager />
+      <BackupTargetManager />
     </div>
   )
 }
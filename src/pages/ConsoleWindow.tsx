@@ -1,4 +1,4 @@
-import { useParams } from 'react-router-dom'
+import { useParams, useSearchParams } from 'react-router-dom'
 import { useQuery } from '@tanstack/react-query'
 import { api } from '@/lib/tauri'
 import { useEffect, useRef, useState } from 'react'
@@ -23,6 +23,9 @@ type ConsoleType = 'graphical' | 'serial'
 
 export function ConsoleWindow() {
   const { vmId } = useParams<{ vmId: string }>()
+  // The route carries ?connectionId= so VMs on remote hosts resolve there
+  const [searchParams] = useSearchParams()
+  const connectionId = searchParams.get('connectionId') ?? undefined
   const vncViewerRef = useRef<VncViewerRef | null>(null)
   const spiceViewerRef = useRef<SpiceViewerRef | null>(null)
   const [isFullscreen, setIsFullscreen] = useState(false)
@@ -34,14 +37,14 @@ export function ConsoleWindow() {
   useWindowState()
 
   const { data: vm, isLoading: vmLoading } = useQuery({
-    queryKey: ['vm', vmId],
-    queryFn: () => api.getVm(vmId!),
+    queryKey: ['vm', vmId, connectionId],
+    queryFn: () => api.getVm(vmId!, connectionId),
     enabled: !!vmId,
   })
 
   const { data: vncInfo, isLoading: vncLoading } = useQuery({
     queryKey: ['vnc-info', vmId],
-    queryFn: () => api.getVncInfo(vmId!),
+    queryFn: () => api.getVncInfo(vmId!, connectionId),
     enabled: !!vmId,
     refetchInterval: false, // Don't auto-refresh - websockify proxy stays active
   })
@@ -286,7 +289,7 @@ export function ConsoleWindow() {
         ) : (
           // Serial Console
           <div className="h-full p-4">
-            <SerialConsole vmId={vmId!} vmName={vm.name} />
+            <SerialConsole vmId={vmId!} connectionId={connectionId} vmName={vm.name} />
           </div>
         )}
       </div>
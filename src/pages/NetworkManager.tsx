@@ -53,7 +53,7 @@ export function NetworkManager() {
   const [showDeleteDialog, setShowDeleteDialog] = useState(false)
   const [showDetailsDialog, setShowDetailsDialog] = useState(false)
   const [selectedNetwork, setSelectedNetwork] = useState<NetworkType | null>(null)
-  const [detailsNetwork, setDetailsNetwork] = useState<string | null>(null)
+  const [detailsNetwork, setDetailsNetwork] = useState<NetworkType | null>(null)
 
   const [networkName, setNetworkName] = useState('')
   const [bridgeName, setBridgeName] = useState('virbr')
@@ -73,14 +73,16 @@ export function NetworkManager() {
   // Query networks
   const { data: networks = [], isLoading, error } = useQuery({
     queryKey: ['networks'],
-    queryFn: api.getNetworks,
+    queryFn: () => api.getNetworks(),
     refetchInterval: 10000,
   })
 
   // Query network details when details dialog is open
   const { data: networkDetails, isLoading: detailsLoading, refetch: refetchDetails } = useQuery({
-    queryKey: ['networkDetails', detailsNetwork],
-    queryFn: () => detailsNetwork ? api.getNetworkDetails(detailsNetwork) : null,
+    queryKey: ['networkDetails', detailsNetwork?.name, detailsNetwork?.connectionId],
+    queryFn: () => detailsNetwork
+      ? api.getNetworkDetails(detailsNetwork.name, detailsNetwork.connectionId ?? undefined)
+      : null,
     enabled: !!detailsNetwork && showDetailsDialog,
     refetchInterval: 5000,
   })
@@ -101,7 +103,7 @@ export function NetworkManager() {
 
   // Delete network mutation
   const deleteMutation = useMutation({
-    mutationFn: (networkName: string) => api.deleteNetwork(networkName),
+    mutationFn: (network: NetworkType) => api.deleteNetwork(network.name, network.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['networks'] })
       toast.success('Network deleted successfully')
@@ -115,7 +117,7 @@ export function NetworkManager() {
 
   // Start network mutation
   const startMutation = useMutation({
-    mutationFn: (networkName: string) => api.startNetwork(networkName),
+    mutationFn: (network: NetworkType) => api.startNetwork(network.name, network.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['networks'] })
       toast.success('Network started successfully')
@@ -127,7 +129,7 @@ export function NetworkManager() {
 
   // Stop network mutation
   const stopMutation = useMutation({
-    mutationFn: (networkName: string) => api.stopNetwork(networkName),
+    mutationFn: (network: NetworkType) => api.stopNetwork(network.name, network.connectionId ?? undefined),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['networks'] })
       toast.success('Network stopped successfully')
@@ -139,11 +141,11 @@ export function NetworkManager() {
 
   // Set autostart mutation
   const autostartMutation = useMutation({
-    mutationFn: ({ networkName, autostart }: { networkName: string; autostart: boolean }) =>
-      api.setNetworkAutostart(networkName, autostart),
+    mutationFn: ({ network, autostart }: { network: NetworkType; autostart: boolean }) =>
+      api.setNetworkAutostart(network.name, autostart, network.connectionId ?? undefined),
     onSuccess: (_, variables) => {
       queryClient.invalidateQueries({ queryKey: ['networks'] })
-      queryClient.invalidateQueries({ queryKey: ['networkDetails', variables.networkName] })
+      queryClient.invalidateQueries({ queryKey: ['networkDetails', variables.network.name] })
       toast.success(`Autostart ${variables.autostart ? 'enabled' : 'disabled'}`)
     },
     onError: (error) => {
@@ -427,9 +429,9 @@ export function NetworkManager() {
               onDoubleClick={() => {
                 // Double-click to toggle network state
                 if (network.active) {
-                  stopMutation.mutate(network.name)
+                  stopMutation.mutate(network)
                 } else {
-                  startMutation.mutate(network.name)
+                  startMutation.mutate(network)
                 }
               }}
             >
@@ -472,7 +474,7 @@ export function NetworkManager() {
                       <Button
                         size="sm"
                         variant="outline"
-                        onClick={() => stopMutation.mutate(network.name)}
+                        onClick={() => stopMutation.mutate(network)}
                         disabled={stopMutation.isPending}
                       >
                         <Square className="mr-2 h-4 w-4" />
@@ -482,7 +484,7 @@ export function NetworkManager() {
                       <Button
                         size="sm"
                         variant="outline"
-                        onClick={() => startMutation.mutate(network.name)}
+                        onClick={() => startMutation.mutate(network)}
                         disabled={startMutation.isPending}
                       >
                         <Play className="mr-2 h-4 w-4" />
@@ -493,7 +495,7 @@ export function NetworkManager() {
                       size="sm"
                       variant="outline"
                       onClick={() => {
-                        setDetailsNetwork(network.name)
+                        setDetailsNetwork(network)
                         setShowDetailsDialog(true)
                       }}
                     >
@@ -533,7 +535,7 @@ export function NetworkManager() {
           <AlertDialogFooter>
             <AlertDialogCancel onClick={() => setSelectedNetwork(null)}>Cancel</AlertDialogCancel>
             <AlertDialogAction
-              onClick={() => selectedNetwork && deleteMutation.mutate(selectedNetwork.name)}
+              onClick={() => selectedNetwork && deleteMutation.mutate(selectedNetwork)}
               disabled={deleteMutation.isPending}
               className="bg-destructive text-destructive-foreground hover:bg-destructive/90"
             >
@@ -555,7 +557,7 @@ export function NetworkManager() {
           <DialogHeader>
             <DialogTitle className="flex items-center gap-2">
               <Network className="h-5 w-5" />
-              Network Details: {detailsNetwork}
+              Network Details: {detailsNetwork?.name}
             </DialogTitle>
             <DialogDescription>
               View network configuration and DHCP leases
@@ -630,7 +632,7 @@ export function NetworkManager() {
                   checked={networkDetails.autostart}
                   onCheckedChange={(checked) => {
                     if (detailsNetwork) {
-                      autostartMutation.mutate({ networkName: detailsNetwork, autostart: checked })
+                      autostartMutation.mutate({ network: detailsNetwork, autostart: checked })
                     }
                   }}
                   disabled={autostartMutation.isPending}
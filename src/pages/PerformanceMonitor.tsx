@@ -23,6 +23,7 @@ import {
   Collapsible,
   CollapsibleContent,
 } from "@/components/ui/collapsible"
+import { HostMetricsPanel } from '@/components/host/HostMetricsPanel'
 
 interface AggregatedMetric {
   timestamp: number
@@ -69,7 +70,7 @@ export function PerformanceMonitor() {
   // Fetch all VMs
   const { data: vms, isLoading: vmsLoading } = useQuery({
     queryKey: ['vms'],
-    queryFn: api.getVms,
+    queryFn: () => api.getVms(),
     refetchInterval: autoRefresh ? 5000 : false,
   })
 
@@ -87,7 +88,7 @@ export function PerformanceMonitor() {
       await Promise.all(
         runningVms.map(async (vm: VM) => {
           try {
-            stats[vm.id] = await api.getVmStats(vm.id)
+            stats[vm.id] = await api.getVmStats(vm.id, vm.connectionId ?? undefined)
           } catch {
             stats[vm.id] = null
           }
@@ -318,6 +319,9 @@ export function PerformanceMonitor() {
             </CollapsibleContent>
           </Collapsible>
 
+          {/* Hypervisor usage, overcommit and pool capacity */}
+          <HostMetricsPanel />
+
           {/* Status Summary - Single compact line */}
           {runningVms.length === 0 ? (
             <div className="text-sm text-muted-foreground py-2">
@@ -1,9 +1,11 @@
 import { ScheduleManager } from '@/components/vm/ScheduleManager'
+import { SnapshotRetentionManager } from '@/components/vm/SnapshotRetentionManager'
 
 export function Schedules() {
   return (
-    <div className="container mx-auto p-6">
+    <div className="container mx-auto p-6 space-y-8">
       <ScheduleManager />
+      <SnapshotRetentionManager />
     </div>
   )
 }
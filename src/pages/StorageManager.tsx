@@ -1,6 +1,6 @@
 import { useState } from 'react'
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
-import { api } from '@/lib/tauri'
+import { api, waitForJob } from '@/lib/tauri'
 import { toast } from 'sonner'
 import { Button } from '@/components/ui/button'
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
@@ -29,7 +29,7 @@ import {
 } from '@/components/ui/select'
 import { Badge } from '@/components/ui/badge'
 import { Database, HardDrive, Plus, FolderOpen, Trash2, Maximize2, Upload, Download, Lock, Eye, EyeOff, FileArchive } from 'lucide-react'
-import type { StoragePool, VolumeConfig } from '@/lib/types'
+import type { StoragePool, Volume, VolumeConfig } from '@/lib/types'
 
 export function StorageManager() {
   const queryClient = useQueryClient()
@@ -51,21 +51,24 @@ export function StorageManager() {
   // Query storage pools
   const { data: pools = [], isLoading: poolsLoading, error: poolsError } = useQuery({
     queryKey: ['storage-pools'],
-    queryFn: api.getStoragePools,
+    queryFn: () => api.getStoragePools(),
     refetchInterval: 10000,
   })
 
+  // Volume calls go to the connection the selected pool was listed from
+  const poolConnectionId = pools.find((p) => p.id === selectedPool)?.connectionId ?? undefined
+
   // Query volumes for selected pool
   const { data: volumes = [], isLoading: volumesLoading } = useQuery({
     queryKey: ['volumes', selectedPool],
-    queryFn: () => api.getVolumes(selectedPool!),
+    queryFn: () => api.getVolumes(selectedPool!, poolConnectionId),
     enabled: !!selectedPool,
     refetchInterval: 10000,
   })
 
   // Create volume mutation
   const createVolumeMutation = useMutation({
-    mutationFn: (config: VolumeConfig) => api.createVolume(selectedPool!, config),
+    mutationFn: (config: VolumeConfig) => api.createVolume(selectedPool!, config, poolConnectionId),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['volumes', selectedPool] })
       queryClient.invalidateQueries({ queryKey: ['storage-pools'] })
@@ -85,7 +88,7 @@ export function StorageManager() {
 
   // Delete volume mutation
   const deleteVolumeMutation = useMutation({
-    mutationFn: (volumeName: string) => api.deleteVolume(selectedPool!, volumeName),
+    mutationFn: (volumeName: string) => api.deleteVolume(selectedPool!, volumeName, poolConnectionId),
     onSuccess: (_data, volumeName) => {
       queryClient.invalidateQueries({ queryKey: ['volumes', selectedPool] })
       queryClient.invalidateQueries({ queryKey: ['storage-pools'] })
@@ -101,7 +104,7 @@ export function StorageManager() {
   // Resize volume mutation
   const resizeVolumeMutation = useMutation({
     mutationFn: ({ volumeName, newCapacityGb }: { volumeName: string; newCapacityGb: number }) =>
-      api.resizeVolume(selectedPool!, volumeName, newCapacityGb),
+      api.resizeVolume(selectedPool!, volumeName, newCapacityGb, poolConnectionId),
     onSuccess: (_data, { volumeName }) => {
       queryClient.invalidateQueries({ queryKey: ['volumes', selectedPool] })
       queryClient.invalidateQueries({ queryKey: ['storage-pools'] })
@@ -116,8 +119,8 @@ export function StorageManager() {
 
   // Upload volume mutation
   const uploadVolumeMutation = useMutation({
-    mutationFn: ({ volumeName, sourcePath, format }: { volumeName: string; sourcePath: string; format?: string }) =>
-      api.uploadVolume(selectedPool!, volumeName, sourcePath, format),
+    mutationFn: async ({ volumeName, sourcePath, format }: { volumeName: string; sourcePath: string; format?: string }) =>
+      waitForJob<Volume>(await api.uploadVolume(selectedPool!, volumeName, sourcePath, format, poolConnectionId)),
     onSuccess: (_data, { volumeName }) => {
       queryClient.invalidateQueries({ queryKey: ['volumes', selectedPool] })
       queryClient.invalidateQueries({ queryKey: ['storage-pools'] })
@@ -130,8 +133,8 @@ export function StorageManager() {
 
   // Download volume mutation
   const downloadVolumeMutation = useMutation({
-    mutationFn: ({ volumeName, destPath }: { volumeName: string; destPath: string }) =>
-      api.downloadVolume(selectedPool!, volumeName, destPath),
+    mutationFn: async ({ volumeName, destPath }: { volumeName: string; destPath: string }) =>
+      waitForJob<number>(await api.downloadVolume(selectedPool!, volumeName, destPath, poolConnectionId)),
     onSuccess: (bytes, { volumeName, destPath }) => {
       toast.success(`Volume "${volumeName}" downloaded successfully (${formatBytes(bytes)}) to ${destPath}`)
     },
@@ -1,4 +1,4 @@
-import { useParams, useNavigate } from 'react-router-dom'
+import { useParams, useSearchParams, useNavigate } from 'react-router-dom'
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
 import { useCallback, useMemo } from 'react'
 import { api } from '@/lib/tauri'
@@ -18,12 +18,15 @@ import { ArrowLeft, Cpu, HardDrive, Network, Settings, Download, Play, Square, P
 
 export function VmDetails() {
   const { vmId } = useParams<{ vmId: string }>()
+  // The route carries ?connectionId= so VMs on remote hosts resolve there
+  const [searchParams] = useSearchParams()
+  const connectionId = searchParams.get('connectionId') ?? undefined
   const navigate = useNavigate()
   const queryClient = useQueryClient()
 
   const { data: vm, isLoading, error } = useQuery({
-    queryKey: ['vm', vmId],
-    queryFn: () => api.getVm(vmId!),
+    queryKey: ['vm', vmId, connectionId],
+    queryFn: () => api.getVm(vmId!, connectionId),
     enabled: !!vmId,
     refetchInterval: 5000,
   })
@@ -31,14 +34,14 @@ export function VmDetails() {
   // Poll live stats for running VMs
   const { data: vmStats } = useQuery({
     queryKey: ['vm-stats', vmId],
-    queryFn: () => api.getVmStats(vmId!),
+    queryFn: () => api.getVmStats(vmId!, connectionId),
     enabled: !!vmId && vm?.state === 'running',
     refetchInterval: 2000, // Update every 2 seconds
   })
 
   // VM control mutations
   const startMutation = useMutation({
-    mutationFn: () => api.startVm(vmId!),
+    mutationFn: () => api.startVm(vmId!, connectionId),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
       toast.success('VM started successfully')
@@ -54,7 +57,7 @@ export function VmDetails() {
   })
 
   const stopMutation = useMutation({
-    mutationFn: () => api.forceStopVm(vmId!),
+    mutationFn: () => api.forceStopVm(vmId!, connectionId),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
       toast.success('VM stopped successfully')
@@ -63,7 +66,7 @@ export function VmDetails() {
   })
 
   const pauseMutation = useMutation({
-    mutationFn: () => api.pauseVm(vmId!),
+    mutationFn: () => api.pauseVm(vmId!, connectionId),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
       toast.success('VM paused successfully')
@@ -72,7 +75,7 @@ export function VmDetails() {
   })
 
   const resumeMutation = useMutation({
-    mutationFn: () => api.resumeVm(vmId!),
+    mutationFn: () => api.resumeVm(vmId!, connectionId),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
       toast.success('VM resumed successfully')
@@ -81,7 +84,7 @@ export function VmDetails() {
   })
 
   const rebootMutation = useMutation({
-    mutationFn: () => api.rebootVm(vmId!),
+    mutationFn: () => api.rebootVm(vmId!, connectionId),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
       toast.success('VM rebooting...')
@@ -90,7 +93,7 @@ export function VmDetails() {
   })
 
   const openVncMutation = useMutation({
-    mutationFn: () => api.openVncConsole(vmId!),
+    mutationFn: () => api.openVncConsole(vmId!, connectionId),
     onSuccess: () => {
       toast.success('Opening VNC console...')
     },
@@ -104,7 +107,7 @@ export function VmDetails() {
   const handleExport = useCallback(async () => {
     if (!vm) return
     try {
-      const xml = await api.exportVm(vm.id)
+      const xml = await api.exportVm(vm.id, connectionId)
       const blob = new Blob([xml], { type: 'application/xml' })
       const url = URL.createObjectURL(blob)
       const a = document.createElement('a')
@@ -449,29 +452,29 @@ export function VmDetails() {
           )}
 
           {/* Snapshot Manager */}
-          <SnapshotManager vmId={vm.id} vmName={vm.name} />
+          <SnapshotManager vmId={vm.id} connectionId={vm.connectionId} vmName={vm.name} />
 
           {/* Disk Manager */}
-          <DiskManager vmId={vm.id} vmName={vm.name} disks={vm.disks} />
+          <DiskManager vmId={vm.id} connectionId={vm.connectionId} vmName={vm.name} disks={vm.disks} />
 
           {/* Boot Order Editor - Show when VM is stopped */}
           {vm.state === 'stopped' && (
-            <BootOrderEditor vmId={vm.id} vmName={vm.name} currentBootOrder={[]} />
+            <BootOrderEditor vmId={vm.id} connectionId={vm.connectionId} vmName={vm.name} currentBootOrder={[]} />
           )}
 
           {/* Guest Agent Information - Show for running VMs */}
           {vm.state === 'running' && (
-            <GuestInfo vmId={vm.id} vmName={vm.name} />
+            <GuestInfo vmId={vm.id} vmName={vm.name} connectionId={vm.connectionId} />
           )}
 
           {/* VNC Console - Only show for running VMs */}
           {vm.state === 'running' && (
-            <VncConsole vmId={vm.id} vmName={vm.name} />
+            <VncConsole vmId={vm.id} connectionId={vm.connectionId} vmName={vm.name} />
           )}
 
           {/* Resource Monitoring - Only show for running VMs */}
           {vm.state === 'running' && (
-            <ResourceGraphs vmId={vm.id} vmName={vm.name} />
+            <ResourceGraphs vmId={vm.id} connectionId={vm.connectionId} vmName={vm.name} />
           )}
 
           {/* Optimization Suggestions */}
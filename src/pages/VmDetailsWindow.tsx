@@ -1,4 +1,4 @@
-import { useParams } from 'react-router-dom'
+import { useParams, useSearchParams } from 'react-router-dom'
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
 import { api } from '@/lib/tauri'
 import { useState, useEffect } from 'react'
@@ -70,6 +70,9 @@ import {
 
 export function VmDetailsWindow() {
   const { vmId } = useParams<{ vmId: string }>()
+  // The route carries ?connectionId= so VMs on remote hosts resolve there
+  const [searchParams] = useSearchParams()
+  const connectionId = searchParams.get('connectionId') ?? undefined
   const queryClient = useQueryClient()
   const [activeTab, setActiveTab] = useState('overview')
   const [showAddHardware, setShowAddHardware] = useState(false)
@@ -81,8 +84,8 @@ export function VmDetailsWindow() {
   useWindowState()
 
   const { data: vm, isLoading, error } = useQuery({
-    queryKey: ['vm', vmId],
-    queryFn: () => api.getVm(vmId!),
+    queryKey: ['vm', vmId, connectionId],
+    queryFn: () => api.getVm(vmId!, connectionId),
     enabled: !!vmId,
     refetchInterval: 2000,
   })
@@ -92,7 +95,7 @@ export function VmDetailsWindow() {
 
   // Mutations
   const startMutation = useMutation({
-    mutationFn: () => api.startVm(vmId!),
+    mutationFn: () => api.startVm(vmId!, connectionId),
     onSuccess: () => {
       toast.success('VM started')
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
@@ -101,7 +104,7 @@ export function VmDetailsWindow() {
   })
 
   const stopMutation = useMutation({
-    mutationFn: () => api.stopVm(vmId!),
+    mutationFn: () => api.stopVm(vmId!, connectionId),
     onSuccess: () => {
       toast.success('VM stopped')
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
@@ -110,7 +113,7 @@ export function VmDetailsWindow() {
   })
 
   const pauseMutation = useMutation({
-    mutationFn: () => api.pauseVm(vmId!),
+    mutationFn: () => api.pauseVm(vmId!, connectionId),
     onSuccess: () => {
       toast.success('VM paused')
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
@@ -119,7 +122,7 @@ export function VmDetailsWindow() {
   })
 
   const resumeMutation = useMutation({
-    mutationFn: () => api.resumeVm(vmId!),
+    mutationFn: () => api.resumeVm(vmId!, connectionId),
     onSuccess: () => {
       toast.success('VM resumed')
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
@@ -128,7 +131,7 @@ export function VmDetailsWindow() {
   })
 
   const rebootMutation = useMutation({
-    mutationFn: () => api.rebootVm(vmId!),
+    mutationFn: () => api.rebootVm(vmId!, connectionId),
     onSuccess: () => {
       toast.success('VM rebooted')
       queryClient.invalidateQueries({ queryKey: ['vm', vmId] })
@@ -139,7 +142,7 @@ export function VmDetailsWindow() {
   const handleOpenConsole = async () => {
     if (!vm) return
     try {
-      await api.openConsoleWindow(vm.id, vm.name)
+      await api.openConsoleWindow(vm.id, vm.name, connectionId)
     } catch (error) {
       toast.error(`Failed to open console: ${(error as Error).message}`)
     }
@@ -149,7 +152,7 @@ export function VmDetailsWindow() {
     if (!vm) return
     try {
       const newName = `${vm.name}-clone`
-      await api.cloneVm(vm.id, newName)
+      await api.cloneVm(vm.id, newName, connectionId)
       toast.success(`Cloned as ${newName}`)
     } catch (error) {
       toast.error(`Clone failed: ${(error as Error).message}`)
@@ -366,7 +369,7 @@ export function VmDetailsWindow() {
                     <span className="text-sm font-medium">Guest Agent</span>
                   </div>
                   <div className="p-4">
-                    <GuestInfo vmId={vm.id} vmName={vm.name} vmState={vm.state} osType={vm.osType} compact />
+                    <GuestInfo vmId={vm.id} vmName={vm.name} connectionId={vm.connectionId} vmState={vm.state} osType={vm.osType} compact />
                   </div>
                 </div>
               </div>
@@ -381,7 +384,7 @@ export function VmDetailsWindow() {
                 </div>
                 <div className="p-4">
                   {isRunning ? (
-                    <ResourceGraphs vmId={vm.id} vmName={vm.name} compact />
+                    <ResourceGraphs vmId={vm.id} connectionId={vm.connectionId} vmName={vm.name} compact />
                   ) : (
                     <div className="text-center py-4 text-muted-foreground text-sm">
                       Start the VM to view performance metrics
@@ -551,7 +554,7 @@ export function VmDetailsWindow() {
           {/* Snapshots Tab */}
           <TabsContent value="snapshots" className="m-0 h-full">
             <div className="p-4">
-              <SnapshotManager vmId={vm.id} vmName={vm.name} />
+              <SnapshotManager vmId={vm.id} connectionId={vm.connectionId} vmName={vm.name} />
             </div>
           </TabsContent>
         </div>
@@ -68,7 +68,7 @@ export function VmList() {
 
   const { data: vms, isLoading, error } = useQuery({
     queryKey: ['vms'],
-    queryFn: api.getVms,
+    queryFn: () => api.getVms(),
     refetchInterval: 5000, // Poll every 5 seconds
   })
 
@@ -76,7 +76,7 @@ export function VmList() {
 
   // Bulk operations mutations
   const startMutation = useMutation({
-    mutationFn: (vmId: string) => api.startVm(vmId),
+    mutationFn: (vm: VM) => api.startVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => queryClient.invalidateQueries({ queryKey: ['vms'] }),
     onError: (error) => {
       const errorMsg = String(error)
@@ -89,7 +89,7 @@ export function VmList() {
   })
 
   const stopMutation = useMutation({
-    mutationFn: (vmId: string) => api.forceStopVm(vmId),
+    mutationFn: (vm: VM) => api.forceStopVm(vm.id, vm.connectionId ?? undefined),
     onSuccess: () => queryClient.invalidateQueries({ queryKey: ['vms'] }),
   })
 
@@ -193,7 +193,7 @@ export function VmList() {
     if (!vm) return
 
     try {
-      await api.openVmDetailsWindow(vm.id, vm.name)
+      await api.openVmDetailsWindow(vm.id, vm.name, vm.connectionId ?? undefined)
     } catch (error) {
       toast.error(`Failed to open VM details: ${(error as Error).message}`)
     }
@@ -213,7 +213,7 @@ export function VmList() {
       if (!focusedVm) return
       try {
         const newName = `${focusedVm.name}-clone`
-        await api.cloneVm(focusedVm.id, newName)
+        await api.cloneVm(focusedVm.id, newName, focusedVm.connectionId ?? undefined)
         toast.success(`VM cloned as ${newName}`)
         queryClient.invalidateQueries({ queryKey: ['vms'] })
       } catch (error) {
@@ -227,18 +227,18 @@ export function VmList() {
     // Actions menu (context-aware - operate on focused VM)
     onStartVm: () => {
       if (focusedVm && focusedVm.state !== 'running') {
-        startMutation.mutate(focusedVm.id)
+        startMutation.mutate(focusedVm)
       }
     },
     onStopVm: () => {
       if (focusedVm && focusedVm.state === 'running') {
-        stopMutation.mutate(focusedVm.id)
+        stopMutation.mutate(focusedVm)
       }
     },
     onOpenConsole: async () => {
       if (!focusedVm) return
       try {
-        await api.openConsoleWindow(focusedVm.id, focusedVm.name)
+        await api.openConsoleWindow(focusedVm.id, focusedVm.name, focusedVm.connectionId ?? undefined)
       } catch (error) {
         toast.error(`Failed to open console: ${(error as Error).message}`)
       }
@@ -270,7 +270,7 @@ export function VmList() {
       ctrlKey: true,
       handler: () => {
         if (focusedVm && focusedVm.state === 'stopped') {
-          startMutation.mutate(focusedVm.id)
+          startMutation.mutate(focusedVm)
         }
       },
       description: 'Start focused VM'
@@ -280,7 +280,7 @@ export function VmList() {
       ctrlKey: true,
       handler: () => {
         if (focusedVm && focusedVm.state === 'running') {
-          stopMutation.mutate(focusedVm.id)
+          stopMutation.mutate(focusedVm)
         }
       },
       description: 'Stop focused VM'
@@ -607,6 +607,7 @@ export function VmList() {
       {migrateVm && (
         <MigrationDialog
           vmId={migrateVm.id}
+          connectionId={migrateVm.connectionId}
           vmName={migrateVm.name}
           isRunning={migrateVm.state === 'running'}
           open={!!migrateVm}